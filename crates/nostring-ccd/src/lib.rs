@@ -232,6 +232,50 @@ pub fn compute_tweak_path(
     Ok(tweaks)
 }
 
+/// Parse a human-readable non-hardened derivation path like `"0/5"` or `"m/0/5"`.
+///
+/// Whitespace around the path and its components is ignored. Hardened
+/// components (`0'`, `0h`, or indices >= 2^31) are rejected with
+/// `CcdError::HardenedIndex`, since CCD tweaks only exist for public derivation.
+pub fn parse_derivation_path(path: &str) -> Result<Vec<u32>, CcdError> {
+    let trimmed = path.trim();
+    // A leading `m` must be its own component: "m0/5" is not "m/0/5"
+    let body = match trimmed.strip_prefix('m') {
+        Some(rest) => rest.trim_start().strip_prefix('/').ok_or_else(|| {
+            CcdError::InvalidPath(format!("expected \"m/\" at the start of {:?}", path))
+        })?,
+        None => trimmed,
+    };
+
+    if body.trim().is_empty() {
+        return Err(CcdError::InvalidPath(format!("empty path: {:?}", path)));
+    }
+
+    body.split('/')
+        .map(|component| {
+            let component = component.trim();
+            if component.ends_with(['\'', 'h', 'H']) {
+                return Err(CcdError::HardenedIndex);
+            }
+            let index: u32 = component.parse().map_err(|_| {
+                CcdError::InvalidPath(format!("invalid component {:?} in {:?}", component, path))
+            })?;
+            if index >= 0x80000000 {
+                return Err(CcdError::HardenedIndex);
+            }
+            Ok(index)
+        })
+        .collect()
+}
+
+/// [`compute_tweak_path`] for a string path such as `"m/0/5"`.
+pub fn compute_tweak_path_str(
+    delegated: &DelegatedKey,
+    path: &str,
+) -> Result<Vec<TweakDisclosure>, CcdError> {
+    compute_tweak_path(delegated, &parse_derivation_path(path)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -601,4 +645,93 @@ mod tests {
         let result = compute_tweak_path(&delegated, &[]);
         assert!(matches!(result, Err(CcdError::InvalidPath(_))));
     }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        assert_eq!(parse_derivation_path("0/5").unwrap(), vec![0, 5]);
+        assert_eq!(parse_derivation_path("m/0/5").unwrap(), vec![0, 5]);
+        assert_eq!(parse_derivation_path("7").unwrap(), vec![7]);
+        assert_eq!(
+            parse_derivation_path("  m / 1 / 2 /3 ").unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            parse_derivation_path("2147483647").unwrap(),
+            vec![0x7fffffff]
+        );
+    }
+
+    #[test]
+    fn test_parse_derivation_path_rejects_hardened() {
+        for path in ["0'/5", "m/0h", "1/2H", "2147483648", "m/44'/0'/0'"] {
+            assert!(
+                matches!(parse_derivation_path(path), Err(CcdError::HardenedIndex)),
+                "{} should be rejected as hardened",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_derivation_path_rejects_invalid() {
+        for path in [
+            "", "m", "m/", "   ", "0//5", "0/x", "/0", "0/-1", "m0/5", "mm/0", "m5",
+        ] {
+            assert!(
+                matches!(parse_derivation_path(path), Err(CcdError::InvalidPath(_))),
+                "{:?} should be rejected as invalid",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_tweak_path_bip32_test_vector_1() {
+        // BIP-32 test vector 1 (seed 000102030405060708090a0b0c0d0e0f):
+        // starting from m/0H/1/2H, derive the non-hardened tail 2/1000000000.
+        use bitcoin::bip32::Xpub;
+        use std::str::FromStr;
+
+        let parent = Xpub::from_str(
+            "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5",
+        )
+        .unwrap();
+        let expected_m_0h_1_2h_2 = Xpub::from_str(
+            "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV",
+        )
+        .unwrap();
+        let expected_leaf = Xpub::from_str(
+            "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy",
+        )
+        .unwrap();
+
+        let delegated = DelegatedKey {
+            cosigner_pubkey: parent.public_key,
+            chain_code: ChainCode(parent.chain_code.to_bytes()),
+            label: "bip32-vector-1".into(),
+        };
+
+        let tweaks = compute_tweak_path_str(&delegated, "m/2/1000000000").unwrap();
+        assert_eq!(tweaks.len(), 2);
+        assert_eq!(tweaks[0].derived_pubkey, expected_m_0h_1_2h_2.public_key);
+        assert_eq!(tweaks[1].derived_pubkey, expected_leaf.public_key);
+        assert_eq!(tweaks[1].child_index, 1_000_000_000);
+
+        // And from m/0H, the single step /1 (m/0H/1)
+        let m_0h = Xpub::from_str(
+            "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+        )
+        .unwrap();
+        let m_0h_1 = Xpub::from_str(
+            "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+        )
+        .unwrap();
+        let delegated = DelegatedKey {
+            cosigner_pubkey: m_0h.public_key,
+            chain_code: ChainCode(m_0h.chain_code.to_bytes()),
+            label: "bip32-vector-1".into(),
+        };
+        let tweaks = compute_tweak_path_str(&delegated, " 1 ").unwrap();
+        assert_eq!(tweaks[0].derived_pubkey, m_0h_1.public_key);
+    }
 }
@@ -0,0 +1,223 @@
+//! Canonical JSON (RFC 8785, JCS) for everything that is signed or hashed.
+//!
+//! `serde_json::to_string` output depends on struct field order and on
+//! whether any dependency enables `preserve_order`, so it can't be the input
+//! to a signature. JCS fixes the bytes: object keys sorted by UTF-16 code
+//! units, no insignificant whitespace, minimal string escaping, and numbers
+//! in ECMAScript `Number.prototype.toString` form.
+
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Largest integer an IEEE 754 double represents exactly (2^53 - 1).
+/// Larger integers would be silently rounded by JCS, so they're rejected.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serialize `value` to RFC 8785 canonical JSON bytes.
+pub fn to_canonical_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    let mut out = String::new();
+    write_value(&value, &mut out)?;
+    Ok(out.into_bytes())
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<(), String> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&format_number(n)?),
+        // serde_json's escaping (short forms for \b \t \n \f \r, lowercase
+        // \u00xx for other controls, nothing else escaped) is what JCS wants
+        Value::String(s) => out.push_str(&Value::String(s.clone()).to_string()),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_value(item, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn format_number(n: &Number) -> Result<String, String> {
+    if let Some(u) = n.as_u64() {
+        if u > MAX_SAFE_INTEGER {
+            return Err(format!("integer {} is not exactly representable", u));
+        }
+        return Ok(u.to_string());
+    }
+    if let Some(i) = n.as_i64() {
+        if i.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(format!("integer {} is not exactly representable", i));
+        }
+        return Ok(i.to_string());
+    }
+    let f = n.as_f64().ok_or("number is not finite")?;
+    Ok(format_f64(f))
+}
+
+/// ECMAScript `Number.prototype.toString` for a finite double.
+fn format_f64(f: f64) -> String {
+    if f == 0.0 {
+        // Covers -0, which serializes as "0"
+        return "0".into();
+    }
+
+    // `{:e}` gives the shortest round-trip digits, e.g. "-1.2345e-7"
+    let sci = format!("{:e}", f);
+    let (mantissa, exponent) = sci
+        .split_once('e')
+        .expect("LowerExp always has an exponent");
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // Decimal point position relative to the digits: value = 0.digits × 10^n
+    let n = exponent.parse::<i32>().expect("exponent is an integer") + 1;
+
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let exp = n - 1;
+        let exp_sign = if exp < 0 { "-" } else { "+" };
+        if k == 1 {
+            format!("{}e{}{}", digits, exp_sign, exp.abs())
+        } else {
+            format!(
+                "{}.{}e{}{}",
+                &digits[..1],
+                &digits[1..],
+                exp_sign,
+                exp.abs()
+            )
+        }
+    };
+    format!("{}{}", sign, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn canonical(value: &Value) -> String {
+        String::from_utf8(to_canonical_json(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        let value = serde_json::json!({"b": 1, "a": {"d": [2, {"f": 3, "e": 4}], "c": "x"}});
+        assert_eq!(
+            canonical(&value),
+            r#"{"a":{"c":"x","d":[2,{"e":4,"f":3}]},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_field_and_insertion_order_independent() {
+        #[derive(Serialize)]
+        struct Forward {
+            alpha: u32,
+            beta: &'static str,
+            gamma: Vec<u8>,
+        }
+        #[derive(Serialize)]
+        struct Reversed {
+            gamma: Vec<u8>,
+            beta: &'static str,
+            alpha: u32,
+        }
+
+        let a = to_canonical_json(&Forward {
+            alpha: 1,
+            beta: "two",
+            gamma: vec![3],
+        })
+        .unwrap();
+        let b = to_canonical_json(&Reversed {
+            gamma: vec![3],
+            beta: "two",
+            alpha: 1,
+        })
+        .unwrap();
+        assert_eq!(a, b);
+
+        let mut forward = HashMap::new();
+        let mut reverse = BTreeMap::new();
+        for (i, key) in ["z", "m", "a", "q"].iter().enumerate() {
+            forward.insert(*key, i);
+        }
+        for (i, key) in ["z", "m", "a", "q"].iter().enumerate().rev() {
+            reverse.insert(*key, i);
+        }
+        assert_eq!(
+            to_canonical_json(&forward).unwrap(),
+            to_canonical_json(&reverse).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_keys_sorted_by_utf16() {
+        // U+1F600 sorts after U+E000 in UTF-8 byte order, before it in UTF-16
+        let value = serde_json::json!({"\u{1F600}": 1, "\u{E000}": 2});
+        assert_eq!(canonical(&value), "{\"\u{1F600}\":1,\"\u{E000}\":2}");
+    }
+
+    #[test]
+    fn test_number_normalization() {
+        // Both sides of each ECMAScript notation boundary
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (1e-7, "1e-7"),
+            (0.000001, "0.000001"),
+            (123.456, "123.456"),
+            (4.35, "4.35"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (9007199254740992.0, "9007199254740992"),
+            (333333333.3333333, "333333333.3333333"),
+        ];
+        for (f, expected) in cases {
+            assert_eq!(format_f64(f), expected, "{}", f);
+        }
+
+        assert_eq!(canonical(&serde_json::json!([1, -2, 0.5])), "[1,-2,0.5]");
+        assert!(to_canonical_json(&serde_json::json!(u64::MAX)).is_err());
+        assert!(to_canonical_json(&serde_json::json!(i64::MIN)).is_err());
+    }
+
+    #[test]
+    fn test_string_escaping() {
+        let value = serde_json::json!({"s": "a\"b\\c\n\u{1}/é"});
+        assert_eq!(canonical(&value), "{\"s\":\"a\\\"b\\\\c\\n\\u0001/é\"}");
+    }
+}
@@ -0,0 +1,82 @@
+//! Wall-clock abstraction
+//!
+//! Rate limits, cooldowns and delivery logs all compare against "now".
+//! Code that takes a [`Clock`] instead of calling `SystemTime::now()` can be
+//! driven by a [`MockClock`] in tests, so a 24-hour cooldown is checked by
+//! advancing the clock rather than by waiting.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current unix time
+pub trait Clock: Send + Sync {
+    /// Seconds since the unix epoch
+    fn now_secs(&self) -> u64;
+}
+
+/// The real system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_secs(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// Manually driven clock for tests
+///
+/// Clones share the same time, so a test can keep one handle and advance
+/// the clock it handed to the code under test.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock(Arc<AtomicU64>);
+
+impl MockClock {
+    /// Start at `secs` since the unix epoch
+    pub fn new(secs: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(secs)))
+    }
+
+    /// Jump to `secs`
+    pub fn set(&self, secs: u64) {
+        self.0.store(secs, Ordering::SeqCst);
+    }
+
+    /// Move forward by `secs`
+    pub fn advance(&self, secs: u64) {
+        self.0.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_secs(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock_is_recent() {
+        // 2024-01-01
+        assert!(SystemClock.now_secs() > 1_704_067_200);
+    }
+
+    #[test]
+    fn test_mock_clock_shared_between_clones() {
+        let clock = MockClock::new(1_000);
+        let handle = clock.clone();
+
+        handle.advance(86_400);
+        assert_eq!(clock.now_secs(), 87_400);
+
+        handle.set(5);
+        assert_eq!(clock.now_secs(), 5);
+    }
+}
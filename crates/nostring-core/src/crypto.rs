@@ -211,10 +211,67 @@ pub fn decrypt_seed(
     Ok(seed)
 }
 
+/// Encrypt arbitrary data with a password
+///
+/// Same scheme as [`encrypt_seed`]; returns `salt || nonce || ciphertext`.
+pub fn encrypt_bytes(plaintext: &[u8], password: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let key = derive_key(password, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&*key));
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| CryptoError::EncryptionFailed(e.to_string()))?;
+
+    let mut bytes = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    bytes.extend_from_slice(&salt);
+    bytes.extend_from_slice(&nonce);
+    bytes.extend_from_slice(&ciphertext);
+    Ok(bytes)
+}
+
+/// Decrypt data produced by [`encrypt_bytes`]
+///
+/// # Errors
+/// Returns error if password is wrong or the data is tampered
+pub fn decrypt_bytes(bytes: &[u8], password: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    // salt + nonce + 16 byte tag
+    if bytes.len() < SALT_LEN + NONCE_LEN + 16 {
+        return Err(CryptoError::InvalidFormat);
+    }
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&bytes[..SALT_LEN]);
+    let nonce = &bytes[SALT_LEN..SALT_LEN + NONCE_LEN];
+
+    let key = derive_key(password, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&*key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), &bytes[SALT_LEN + NONCE_LEN..])
+        .map(Zeroizing::new)
+        .map_err(|_| {
+            CryptoError::DecryptionFailed("Invalid password or corrupted data".to_string())
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_encrypt_bytes_roundtrip() {
+        let data = b"{\"descriptor\":\"wsh(...)\"}";
+
+        let encrypted = encrypt_bytes(data, "pw").unwrap();
+        assert_eq!(*decrypt_bytes(&encrypted, "pw").unwrap(), data.to_vec());
+        assert!(decrypt_bytes(&encrypted, "wrong").is_err());
+        assert!(matches!(
+            decrypt_bytes(&encrypted[..10], "pw"),
+            Err(CryptoError::InvalidFormat)
+        ));
+    }
+
     #[test]
     fn test_encrypt_decrypt_roundtrip() {
         let seed = [42u8; 64]; // Test seed
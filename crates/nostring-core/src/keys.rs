@@ -13,6 +13,20 @@ pub const NIP06_PATH: &str = "m/44'/1237'/0'/0/0";
 /// BIP-84 derivation path for Bitcoin keys (native segwit)
 pub const BIP84_PATH: &str = "m/84'/0'/0'";
 
+/// BIP-84 derivation path on testnet, signet and regtest (BIP-44 coin type 1)
+pub const BIP84_TESTNET_PATH: &str = "m/84'/1'/0'";
+
+/// Coin type used when deriving Bitcoin keys off mainnet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoinType {
+    /// BIP-44: coin type 1' on test networks, 0' on mainnet
+    #[default]
+    Standard,
+    /// Coin type 0' on every network, for wallets set up while NoString
+    /// derived test-network keys under the mainnet path
+    LegacyMainnet,
+}
+
 #[derive(Error, Debug)]
 pub enum KeyError {
     #[error("Derivation failed: {0}")]
@@ -51,27 +65,44 @@ pub fn derive_bitcoin_master(seed: &[u8; 64]) -> Result<Xpriv, KeyError> {
 /// Derive Bitcoin master key from seed using BIP-84 path for a specific network
 ///
 /// - Mainnet: m/84'/0'/0'
-/// - Testnet: m/84'/1'/0'
+/// - Testnet/Signet/Regtest: m/84'/1'/0'
 pub fn derive_bitcoin_master_for_network(
     seed: &[u8; 64],
     network: Network,
+) -> Result<Xpriv, KeyError> {
+    derive_bitcoin_master_with_coin_type(seed, network, CoinType::Standard)
+}
+
+/// Derive Bitcoin master key from seed, choosing the test-network coin type
+///
+/// Use [`CoinType::LegacyMainnet`] only to recover a test-network wallet
+/// created under `m/84'/0'/0'`.
+pub fn derive_bitcoin_master_with_coin_type(
+    seed: &[u8; 64],
+    network: Network,
+    coin_type: CoinType,
 ) -> Result<Xpriv, KeyError> {
     let master =
         Xpriv::new_master(network, seed).map_err(|e| KeyError::DerivationFailed(e.to_string()))?;
 
-    // BIP-84 path differs by network
-    let path_str = match network {
-        Network::Bitcoin => BIP84_PATH,
-        _ => "m/84'/1'/0'", // Testnet/Signet/Regtest use coin type 1
+    master
+        .derive_priv(
+            &bitcoin::secp256k1::Secp256k1::new(),
+            &bip84_path(network, coin_type)?,
+        )
+        .map_err(|e| KeyError::DerivationFailed(e.to_string()))
+}
+
+/// BIP-84 account path for `network`
+pub fn bip84_path(network: Network, coin_type: CoinType) -> Result<DerivationPath, KeyError> {
+    let path_str = match (network, coin_type) {
+        (Network::Bitcoin, _) | (_, CoinType::LegacyMainnet) => BIP84_PATH,
+        _ => BIP84_TESTNET_PATH,
     };
 
-    let path: DerivationPath = path_str
+    path_str
         .parse()
-        .map_err(|e: bitcoin::bip32::Error| KeyError::InvalidPath(e.to_string()))?;
-
-    master
-        .derive_priv(&bitcoin::secp256k1::Secp256k1::new(), &path)
-        .map_err(|e| KeyError::DerivationFailed(e.to_string()))
+        .map_err(|e: bitcoin::bip32::Error| KeyError::InvalidPath(e.to_string()))
 }
 
 /// Derive a specific Bitcoin address from the master key
@@ -286,4 +317,54 @@ mod tests {
         assert!(addr1.to_string().starts_with("bc1q"));
         assert!(addr2.to_string().starts_with("bc1q"));
     }
+
+    /// Test networks derive under coin type 1', mainnet stays at 0'
+    #[test]
+    fn test_bip84_path_by_network() {
+        let parse = |p: &str| p.parse::<DerivationPath>().unwrap();
+        let path = |network| bip84_path(network, CoinType::Standard).unwrap();
+
+        assert_eq!(path(Network::Bitcoin), parse("m/84'/0'/0'"));
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            assert_eq!(path(network), parse("m/84'/1'/0'"));
+            assert_eq!(
+                bip84_path(network, CoinType::LegacyMainnet).unwrap(),
+                parse("m/84'/0'/0'")
+            );
+        }
+    }
+
+    /// BIP-84 testnet vector; mainnet output unchanged by network awareness
+    #[test]
+    fn test_bip84_testnet_derivation() {
+        let mnemonic = parse_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        ).unwrap();
+        let seed = derive_seed(&mnemonic, "");
+
+        let mainnet = derive_bitcoin_master_for_network(&seed, Network::Bitcoin).unwrap();
+        assert_eq!(mainnet, derive_bitcoin_master(&seed).unwrap());
+        assert_eq!(
+            derive_bitcoin_address(&mainnet, false, 0, Network::Bitcoin)
+                .unwrap()
+                .to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+
+        // m/84'/1'/0'/0/0
+        let testnet = derive_bitcoin_master_for_network(&seed, Network::Testnet).unwrap();
+        assert_eq!(
+            derive_bitcoin_address(&testnet, false, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "tb1q6rz28mcfaxtmd6v789l9rrlrusdprr9pqcpvkl"
+        );
+
+        // Legacy mode reproduces the old mainnet-path keys on testnet
+        let legacy =
+            derive_bitcoin_master_with_coin_type(&seed, Network::Testnet, CoinType::LegacyMainnet)
+                .unwrap();
+        assert_eq!(legacy.private_key, mainnet.private_key);
+        assert_ne!(legacy.private_key, testnet.private_key);
+    }
 }
@@ -6,19 +6,25 @@
 //!
 //! From a single BIP-39 seed:
 //! - Nostr keys via NIP-06: m/44'/1237'/0'/0/0
-//! - Bitcoin keys via BIP-84: m/84'/0'/0'
+//! - Bitcoin keys via BIP-84: m/84'/0'/0' (m/84'/1'/0' on test networks)
 //!
 //! # Encrypted Storage
 //!
 //! Seeds are encrypted at rest using Argon2id + AES-256-GCM.
 
+pub mod canonical;
+pub mod clock;
 pub mod crypto;
 pub mod keys;
+pub mod manifest;
 pub mod memory;
 pub mod password;
+pub mod redact;
 pub mod seed;
 
-pub use crypto::{decrypt_seed, encrypt_seed, CryptoError, EncryptedSeed};
+pub use crypto::{
+    decrypt_bytes, decrypt_seed, encrypt_bytes, encrypt_seed, CryptoError, EncryptedSeed,
+};
 pub use keys::*;
 pub use seed::*;
 
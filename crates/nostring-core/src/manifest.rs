@@ -0,0 +1,290 @@
+//! Signed `.nostring` manifest files
+//!
+//! The file format shared by the app, which writes manifests, and the
+//! offline recovery tool, which reads them. A manifest is signed by the
+//! service key over its canonical JSON and the file can optionally be
+//! encrypted with a password (Argon2id + AES-256-GCM).
+//!
+//! File layout (JSON):
+//!
+//! ```text
+//! { "format": "nostring-manifest", "version": 1,
+//!   "manifest": { ... } }            // plain
+//! { "format": "nostring-manifest", "version": 1,
+//!   "encrypted": "<base64>" }        // password-protected
+//! ```
+//!
+//! Descriptor backups use the same signature scheme without the wrapper.
+
+use crate::canonical::to_canonical_json;
+use crate::crypto::{decrypt_bytes, encrypt_bytes, CryptoError};
+use base64::prelude::*;
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::secp256k1::{schnorr, Keypair, Message, Secp256k1, XOnlyPublicKey};
+use nostr_sdk::prelude::{FromBech32, Keys, PublicKey, ToBech32};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// `format` value identifying a manifest file
+pub const MANIFEST_FORMAT: &str = "nostring-manifest";
+
+/// Current manifest format version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Fields left out of the signed digest
+const UNSIGNED_FIELDS: [&str; 3] = ["signature", "signer_npub", "stale_warning"];
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("Not a manifest file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown file format '{0}'")]
+    UnknownFormat(String),
+    #[error("Manifest version {found} is newer than this software supports ({supported}). Please update.")]
+    NewerVersion { found: u32, supported: u32 },
+    #[error("Manifest file must contain exactly one of manifest/encrypted")]
+    Malformed,
+    #[error("This manifest is encrypted; a password is required")]
+    PasswordRequired,
+    #[error("Invalid encrypted payload: {0}")]
+    InvalidPayload(#[from] base64::DecodeError),
+    #[error("Manifest encryption failed: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("Cannot canonicalize signed contents: {0}")]
+    Canonical(String),
+    #[error("Not signed")]
+    Unsigned,
+    #[error("Invalid service key: {0}")]
+    InvalidKey(String),
+    #[error("Invalid signer npub: {0}")]
+    InvalidSigner(String),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("Signature does not match the signed contents — they may have been tampered with")]
+    SignatureMismatch,
+}
+
+/// On-disk wrapper: exactly one of `manifest` or `encrypted` is set
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub format: String,
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<Value>,
+    /// Base64 of the password-encrypted manifest JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<String>,
+}
+
+impl ManifestFile {
+    /// Wrap `manifest`, encrypting it when a password is given.
+    pub fn new<M: Serialize>(manifest: &M, password: Option<&str>) -> Result<Self, ManifestError> {
+        let (manifest, encrypted) = match password {
+            Some(password) => {
+                let json = serde_json::to_vec(manifest)?;
+                let ciphertext = encrypt_bytes(&json, password)?;
+                (None, Some(BASE64_STANDARD.encode(ciphertext)))
+            }
+            None => (Some(serde_json::to_value(manifest)?), None),
+        };
+        Ok(Self {
+            format: MANIFEST_FORMAT.to_string(),
+            version: MANIFEST_VERSION,
+            manifest,
+            encrypted,
+        })
+    }
+
+    /// Parse file bytes, checking the format and version.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, ManifestError> {
+        let file: Self = serde_json::from_slice(bytes)?;
+        if file.format != MANIFEST_FORMAT {
+            return Err(ManifestError::UnknownFormat(file.format));
+        }
+        if file.version > MANIFEST_VERSION {
+            return Err(ManifestError::NewerVersion {
+                found: file.version,
+                supported: MANIFEST_VERSION,
+            });
+        }
+        Ok(file)
+    }
+
+    /// The manifest JSON, decrypted with `password` if needed.
+    ///
+    /// The signature is not checked; see [`verify_signed`].
+    pub fn open(self, password: Option<&str>) -> Result<Value, ManifestError> {
+        match (self.manifest, self.encrypted) {
+            (Some(manifest), None) => Ok(manifest),
+            (None, Some(encrypted)) => {
+                let password = password.ok_or(ManifestError::PasswordRequired)?;
+                let ciphertext = BASE64_STANDARD.decode(encrypted)?;
+                let json = decrypt_bytes(&ciphertext, password)?;
+                Ok(serde_json::from_slice(&json)?)
+            }
+            _ => Err(ManifestError::Malformed),
+        }
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>, ManifestError> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// SHA-256 of the canonical JSON of `value` without the signature fields.
+pub fn signed_digest<T: Serialize>(value: &T) -> Result<[u8; 32], ManifestError> {
+    let mut value = serde_json::to_value(value)?;
+    if let Some(map) = value.as_object_mut() {
+        for field in UNSIGNED_FIELDS {
+            map.remove(field);
+        }
+    }
+    let canonical = to_canonical_json(&value).map_err(ManifestError::Canonical)?;
+    Ok(sha256::Hash::hash(&canonical).to_byte_array())
+}
+
+/// Schnorr-sign a digest with the service key (hex secret).
+///
+/// Returns `(signature_hex, signer_npub)`.
+pub fn sign_digest(
+    digest: [u8; 32],
+    service_secret: &str,
+) -> Result<(String, String), ManifestError> {
+    let keys = Keys::parse(service_secret).map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+    let secp = Secp256k1::new();
+    let keypair = Keypair::from_seckey_slice(&secp, keys.secret_key().as_secret_bytes())
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+
+    let sig = secp.sign_schnorr_no_aux_rand(&Message::from_digest(digest), &keypair);
+    let npub = keys
+        .public_key()
+        .to_bech32()
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+    Ok((hex::encode(sig.serialize()), npub))
+}
+
+/// Check a Schnorr signature over `digest` against a signer npub.
+pub fn verify_digest(digest: [u8; 32], sig_hex: &str, npub: &str) -> Result<(), ManifestError> {
+    let pubkey =
+        PublicKey::from_bech32(npub).map_err(|e| ManifestError::InvalidSigner(e.to_string()))?;
+    let xonly = XOnlyPublicKey::from_str(&pubkey.to_hex())
+        .map_err(|e| ManifestError::InvalidSigner(e.to_string()))?;
+    let sig_bytes =
+        hex::decode(sig_hex).map_err(|e| ManifestError::InvalidSignature(e.to_string()))?;
+    let sig = schnorr::Signature::from_slice(&sig_bytes)
+        .map_err(|e| ManifestError::InvalidSignature(e.to_string()))?;
+
+    Secp256k1::verification_only()
+        .verify_schnorr(&sig, &Message::from_digest(digest), &xonly)
+        .map_err(|_| ManifestError::SignatureMismatch)
+}
+
+/// Verify the `signature`/`signer_npub` fields of a signed JSON object.
+///
+/// Returns the signer npub, which callers should compare with the npub the
+/// owner shared out of band.
+pub fn verify_signed(value: &Value) -> Result<String, ManifestError> {
+    let field = |name| value.get(name).and_then(Value::as_str);
+    let (Some(sig_hex), Some(npub)) = (field("signature"), field("signer_npub")) else {
+        return Err(ManifestError::Unsigned);
+    };
+    verify_digest(signed_digest(value)?, sig_hex, npub)?;
+    Ok(npub.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_value(keys: &Keys) -> Value {
+        let mut value = serde_json::json!({
+            "descriptor": "wsh(pk(A))",
+            "heirs": [{"label": "Alice", "timelock_months": 6.0}],
+            "stale_warning": "not signed",
+        });
+        let (sig, npub) = sign_digest(
+            signed_digest(&value).unwrap(),
+            &keys.secret_key().to_secret_hex(),
+        )
+        .unwrap();
+        value["signature"] = sig.into();
+        value["signer_npub"] = npub.into();
+        value
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keys = Keys::generate();
+        let mut value = signed_value(&keys);
+        assert_eq!(
+            verify_signed(&value).unwrap(),
+            keys.public_key().to_bech32().unwrap()
+        );
+
+        // Unsigned fields can change; signed ones can't
+        value["stale_warning"] = "changed".into();
+        assert!(verify_signed(&value).is_ok());
+        value["descriptor"] = "wsh(pk(B))".into();
+        assert!(matches!(
+            verify_signed(&value),
+            Err(ManifestError::SignatureMismatch)
+        ));
+
+        // The signature doesn't verify under a different npub
+        let mut other = signed_value(&keys);
+        other["signer_npub"] = Keys::generate().public_key().to_bech32().unwrap().into();
+        assert!(verify_signed(&other).is_err());
+
+        other.as_object_mut().unwrap().remove("signature");
+        assert!(matches!(
+            verify_signed(&other),
+            Err(ManifestError::Unsigned)
+        ));
+    }
+
+    #[test]
+    fn test_manifest_file_roundtrip() {
+        let value = signed_value(&Keys::generate());
+
+        let plain = ManifestFile::new(&value, None).unwrap().to_vec().unwrap();
+        let opened = ManifestFile::from_slice(&plain)
+            .unwrap()
+            .open(None)
+            .unwrap();
+        assert_eq!(opened, value);
+
+        let encrypted = ManifestFile::new(&value, Some("hunter2"))
+            .unwrap()
+            .to_vec()
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&encrypted).contains("wsh("));
+        let file = || ManifestFile::from_slice(&encrypted).unwrap();
+        assert!(matches!(
+            file().open(None),
+            Err(ManifestError::PasswordRequired)
+        ));
+        assert!(file().open(Some("wrong")).is_err());
+        assert_eq!(file().open(Some("hunter2")).unwrap(), value);
+    }
+
+    #[test]
+    fn test_manifest_file_format_checks() {
+        let future = serde_json::json!({"format": MANIFEST_FORMAT, "version": MANIFEST_VERSION + 1, "manifest": {}});
+        assert!(matches!(
+            ManifestFile::from_slice(&serde_json::to_vec(&future).unwrap()),
+            Err(ManifestError::NewerVersion { .. })
+        ));
+
+        let other = serde_json::json!({"format": "something-else", "version": 1, "manifest": {}});
+        assert!(matches!(
+            ManifestFile::from_slice(&serde_json::to_vec(&other).unwrap()),
+            Err(ManifestError::UnknownFormat(_))
+        ));
+
+        let both = serde_json::json!({"format": MANIFEST_FORMAT, "version": 1, "manifest": {}, "encrypted": "AA=="});
+        let file = ManifestFile::from_slice(&serde_json::to_vec(&both).unwrap()).unwrap();
+        assert!(matches!(file.open(None), Err(ManifestError::Malformed)));
+    }
+}
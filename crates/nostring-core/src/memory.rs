@@ -17,8 +17,19 @@
 //! - Unix/macOS/Linux: Full support via libc
 //! - Windows: Core dump prevention via SetErrorMode (partial), no mlock yet
 //! - Other: No-ops with warnings
+//!
+//! On Linux, unprivileged processes may lock at most `RLIMIT_MEMLOCK` bytes
+//! (`ulimit -l`, often 64 KiB or 8 MiB). Beyond that `mlock` fails with
+//! `ENOMEM`/`EPERM` and buffers stay usable but swappable — check
+//! [`LockedBuffer::is_locked`] if that matters. Locking also can't stop
+//! hibernation images from capturing RAM.
+//!
+//! Short-lived secrets (passwords, raw key bytes, the service key) that
+//! pass through a command are held in a [`zeroize::Zeroizing`], which wipes them
+//! on every exit path.
 
 use std::sync::atomic::{AtomicBool, Ordering};
+use zeroize::Zeroize;
 
 /// Track whether core dumps have been disabled (call only once)
 static CORE_DUMPS_DISABLED: AtomicBool = AtomicBool::new(false);
@@ -126,7 +137,11 @@ pub unsafe fn munlock(ptr: *const u8, len: usize) -> bool {
 
 /// A wrapper that mlocks its contents on creation and munlocks + zeroizes on drop.
 ///
-/// Use this for seed material that must never hit swap.
+/// Use this for seed material that must never hit swap, including secrets
+/// reconstructed from shares: [`from_vec`](Self::from_vec) takes ownership
+/// of the bytes and locks their allocation in place, so the secret is
+/// never copied into unlocked memory. Locking is best-effort (see the
+/// module docs); the buffer works either way.
 ///
 /// # Example
 /// ```
@@ -134,30 +149,45 @@ pub unsafe fn munlock(ptr: *const u8, len: usize) -> bool {
 /// let mut buf = LockedBuffer::new(64);
 /// buf.as_mut_slice()[..5].copy_from_slice(b"hello");
 /// // Memory is locked, zeroized on drop, then unlocked
+///
+/// let secret = LockedBuffer::from_vec(vec![0x42; 32]);
+/// assert_eq!(secret.len(), 32);
 /// ```
 pub struct LockedBuffer {
     data: Vec<u8>,
-    locked: bool,
+    /// Bytes locked at construction (the allocation's capacity)
+    locked_len: usize,
 }
 
 impl LockedBuffer {
     /// Create a new zero-filled buffer and lock it in memory.
     pub fn new(len: usize) -> Self {
-        let data = vec![0u8; len];
-        let locked = if !data.is_empty() {
-            unsafe { mlock(data.as_ptr(), data.len()) }
-        } else {
-            true
-        };
-
-        if !locked {
+        Self::from_vec(vec![0u8; len])
+    }
+
+    /// Wrap `data`, locking its allocation in memory.
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        let capacity = data.capacity();
+        let locked = capacity > 0 && unsafe { mlock(data.as_ptr(), capacity) };
+
+        if capacity > 0 && !locked {
             eprintln!(
                 "[nostring] Warning: failed to mlock {} bytes — seed may be swappable",
-                len
+                capacity
             );
         }
 
-        Self { data, locked }
+        Self {
+            data,
+            locked_len: if locked { capacity } else { 0 },
+        }
+    }
+
+    /// Copy `bytes` into a new locked buffer.
+    ///
+    /// The caller remains responsible for zeroizing `bytes`.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self::from_vec(bytes.to_vec())
     }
 
     /// Get a reference to the buffer contents.
@@ -170,22 +200,49 @@ impl LockedBuffer {
         &mut self.data
     }
 
+    /// The contents as UTF-8 text, if they are valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.data).ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     /// Whether the memory is actually locked.
+    ///
+    /// Always `true` for an empty buffer, which has nothing to protect.
     pub fn is_locked(&self) -> bool {
-        self.locked
+        self.data.capacity() == 0 || self.locked_len > 0
+    }
+}
+
+impl From<String> for LockedBuffer {
+    fn from(s: String) -> Self {
+        Self::from_vec(s.into_bytes())
+    }
+}
+
+impl std::fmt::Debug for LockedBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LockedBuffer({} bytes, redacted)", self.data.len())
     }
 }
 
 impl Drop for LockedBuffer {
     fn drop(&mut self) {
-        // Zeroize before unlocking
-        use zeroize::Zeroize;
+        // Zeroize clears the whole capacity (and sets len to 0), so capture
+        // the pointer first and unlock afterwards
+        let ptr = self.data.as_ptr();
         self.data.zeroize();
 
-        // Unlock the memory
-        if self.locked && !self.data.is_empty() {
+        if self.locked_len > 0 {
             unsafe {
-                munlock(self.data.as_ptr(), self.data.len());
+                munlock(ptr, self.locked_len);
             }
         }
     }
@@ -247,6 +304,59 @@ mod windows {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use zeroize::Zeroizing;
+
+    /// Key material whose bytes are copied to `wiped` when zeroized, so a
+    /// test can inspect them after the owner is gone
+    struct Tracked {
+        bytes: Vec<u8>,
+        wiped: Rc<RefCell<Option<Vec<u8>>>>,
+    }
+
+    impl Zeroize for Tracked {
+        fn zeroize(&mut self) {
+            self.bytes.zeroize();
+            *self.wiped.borrow_mut() = Some(self.bytes.clone());
+        }
+    }
+
+    fn tracked(wiped: &Rc<RefCell<Option<Vec<u8>>>>) -> Zeroizing<Tracked> {
+        Zeroizing::new(Tracked {
+            bytes: vec![0x42; 32],
+            wiped: Rc::clone(wiped),
+        })
+    }
+
+    #[test]
+    fn test_secret_zeroized_on_drop() {
+        let wiped = Rc::new(RefCell::new(None));
+        {
+            let secret = tracked(&wiped);
+            assert_eq!(secret.bytes, vec![0x42; 32]);
+            assert!(wiped.borrow().is_none());
+        }
+        assert_eq!(wiped.borrow().as_deref(), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_secret_zeroized_on_error_path() {
+        fn sign_with(secret: Zeroizing<Tracked>, input_valid: bool) -> Result<usize, &'static str> {
+            if !input_valid {
+                return Err("invalid input");
+            }
+            Ok(secret.bytes.len())
+        }
+
+        let wiped = Rc::new(RefCell::new(None));
+        assert!(sign_with(tracked(&wiped), false).is_err());
+        assert_eq!(wiped.borrow().as_deref(), Some(&[][..]));
+
+        let wiped = Rc::new(RefCell::new(None));
+        assert_eq!(sign_with(tracked(&wiped), true), Ok(32));
+        assert_eq!(wiped.borrow().as_deref(), Some(&[][..]));
+    }
 
     #[test]
     fn test_disable_core_dumps_succeeds_on_unix() {
@@ -461,6 +571,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_locked_buffer_from_vec_locks_or_degrades() {
+        let buf = LockedBuffer::from_vec(b"nsec1secret".to_vec());
+        assert_eq!(buf.as_slice(), b"nsec1secret");
+        assert_eq!(buf.as_str(), Some("nsec1secret"));
+        assert_eq!(buf.len(), 11);
+
+        // Locking may be refused (RLIMIT_MEMLOCK, unsupported platform);
+        // the contents must be usable either way. A large buffer likely
+        // exceeds unprivileged mlock limits
+        let big = LockedBuffer::from_vec(vec![7u8; 16 * 1024 * 1024]);
+        assert!(big.as_slice().iter().all(|&b| b == 7));
+
+        let empty = LockedBuffer::from_vec(Vec::new());
+        assert!(empty.is_empty());
+        assert!(empty.is_locked());
+    }
+
+    #[test]
+    fn test_locked_buffer_debug_is_redacted() {
+        let buf = LockedBuffer::from(String::from("hunter2"));
+        assert_eq!(format!("{:?}", buf), "LockedBuffer(7 bytes, redacted)");
+    }
+
+    #[test]
+    fn test_locked_buffer_from_vec_zeroizes_on_drop() {
+        let buf = LockedBuffer::from_vec(vec![0xFF; 64]);
+        let ptr = buf.as_slice().as_ptr();
+        let len = buf.len();
+
+        drop(buf);
+
+        // Same caveat as test_locked_buffer_zeroizes_on_drop: reading freed
+        // memory is UB, but it's the only way to observe zeroization
+        unsafe {
+            let zeroed_count = (0..len)
+                .filter(|&i| std::ptr::read_volatile(ptr.add(i)) == 0)
+                .count();
+            assert!(
+                zeroed_count >= len - 16,
+                "after drop: at least {} of {} bytes should be zeroed, got {}",
+                len - 16,
+                len,
+                zeroed_count
+            );
+        }
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_mlock_unaligned_address() {
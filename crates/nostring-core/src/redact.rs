@@ -0,0 +1,153 @@
+//! Redaction of sensitive values in logs and error messages
+//!
+//! Descriptors reveal xpubs (and with them the full wallet history), and
+//! email addresses identify the owner and heirs. Anything that may end up
+//! in a log line or a user-visible error should pass through these helpers.
+//!
+//! For local troubleshooting, debug builds honour `NOSTRING_NO_REDACT=1`,
+//! which returns every value unchanged. Release builds always redact.
+
+/// Environment variable that disables redaction (debug builds only)
+pub const NO_REDACT_ENV: &str = "NOSTRING_NO_REDACT";
+
+/// Extended key prefixes (BIP-32, SLIP-132)
+const EXTENDED_KEY_PREFIXES: &[&str] = &[
+    "xpub", "ypub", "zpub", "Ypub", "Zpub", "tpub", "upub", "vpub", "Upub", "Vpub", "xprv", "yprv",
+    "zprv", "tprv", "uprv", "vprv",
+];
+
+/// Minimum length of a base58 extended key (they are always 111 chars)
+const EXTENDED_KEY_MIN_LEN: usize = 100;
+
+/// Placeholder for masked content
+const MASK: &str = "…";
+
+/// Whether redaction is active for this process.
+pub fn redaction_enabled() -> bool {
+    enabled_for(std::env::var(NO_REDACT_ENV).ok().as_deref())
+}
+
+fn enabled_for(no_redact: Option<&str>) -> bool {
+    if !cfg!(debug_assertions) {
+        return true;
+    }
+    !matches!(no_redact, Some("1") | Some("true"))
+}
+
+/// Mask a single extended key, keeping only its version prefix.
+///
+/// `xpub661MyMwAqRbc…` becomes `xpub…`. Values that don't look like an
+/// extended key are returned unchanged.
+pub fn redact_xpub(key: &str) -> String {
+    if !redaction_enabled() {
+        return key.to_string();
+    }
+    mask_xpub(key).unwrap_or_else(|| key.to_string())
+}
+
+/// Mask every key in a descriptor (or any text containing one).
+///
+/// Extended keys and raw hex public keys are replaced by a short
+/// placeholder; key origins (`[fingerprint/path]`), script structure and
+/// timelocks are left intact so the output stays useful for debugging.
+pub fn redact_descriptor(descriptor: &str) -> String {
+    if !redaction_enabled() {
+        return descriptor.to_string();
+    }
+
+    let mut out = String::with_capacity(descriptor.len());
+    let mut word = String::new();
+    for c in descriptor.chars() {
+        if c.is_ascii_alphanumeric() {
+            word.push(c);
+        } else {
+            flush_word(&mut out, &mut word);
+            out.push(c);
+        }
+    }
+    flush_word(&mut out, &mut word);
+    out
+}
+
+/// Mask the local part of an email address, keeping the first character
+/// and the domain: `alice@example.com` becomes `a***@example.com`.
+pub fn redact_email(addr: &str) -> String {
+    if !redaction_enabled() {
+        return addr.to_string();
+    }
+    match addr.split_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
+fn flush_word(out: &mut String, word: &mut String) {
+    if let Some(masked) = mask_xpub(word) {
+        out.push_str(&masked);
+    } else if is_hex_pubkey(word) {
+        out.push_str(&word[..4]);
+        out.push_str(MASK);
+    } else {
+        out.push_str(word);
+    }
+    word.clear();
+}
+
+fn mask_xpub(key: &str) -> Option<String> {
+    let prefix = EXTENDED_KEY_PREFIXES
+        .iter()
+        .find(|p| key.starts_with(**p))?;
+    (key.len() >= EXTENDED_KEY_MIN_LEN).then(|| format!("{}{}", prefix, MASK))
+}
+
+/// Compressed (66 hex) or x-only (64 hex) public key
+fn is_hex_pubkey(word: &str) -> bool {
+    matches!(word.len(), 64 | 66) && word.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP-32 test vector 1, chain m
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    #[test]
+    fn test_redact_xpub() {
+        assert_eq!(redact_xpub(XPUB), "xpub…");
+        assert_eq!(redact_xpub("not-a-key"), "not-a-key");
+        // Short strings with an xpub prefix aren't keys
+        assert_eq!(redact_xpub("xpubkey"), "xpubkey");
+    }
+
+    #[test]
+    fn test_redact_descriptor_keeps_fingerprint() {
+        let desc = format!(
+            "wsh(or_d(pk([d34db33f/84'/0'/0']{}/0/*),and_v(v:pk(02{}),older(26280))))",
+            XPUB,
+            "ab".repeat(32)
+        );
+        let redacted = redact_descriptor(&desc);
+
+        assert!(!redacted.contains(XPUB));
+        assert!(!redacted.contains(&"ab".repeat(32)));
+        assert!(redacted.contains("[d34db33f/84'/0'/0']xpub…/0/*"));
+        assert!(redacted.contains("older(26280)"));
+    }
+
+    #[test]
+    fn test_redact_email() {
+        assert_eq!(redact_email("alice@example.com"), "a***@example.com");
+        assert_eq!(redact_email("garbage"), "***");
+    }
+
+    #[test]
+    fn test_env_override_only_in_debug() {
+        assert!(enabled_for(None));
+        assert!(enabled_for(Some("0")));
+        assert_eq!(enabled_for(Some("1")), !cfg!(debug_assertions));
+    }
+}
@@ -6,7 +6,8 @@
 //!
 //! BIP-39 defines a standard for mnemonic phrases:
 //! - Word counts: 12, 15, 18, 21, or 24 words
-//! - Language: English (we only support English for now)
+//! - Language: any of the ten BIP-39 wordlists ([`Language`]); parsing
+//!   detects the language from the words themselves
 //! - Checksum: Last bits of SHA256 hash of entropy
 //! - Seed derivation: PBKDF2-HMAC-SHA512 with 2048 iterations
 //!
@@ -15,11 +16,25 @@
 //! - Mnemonics should never be logged or stored in plaintext
 //! - Always use the encrypted storage functions for persistence
 //! - Passphrases (optional 25th word) add an extra layer of security
+//!
+//! # SeedQR
+//!
+//! Mnemonics can be exported as [SeedQR](https://github.com/SeedSigner/seedsigner/blob/dev/docs/seed_qr/README.md)
+//! payloads for transfer to SeedSigner-compatible devices:
+//! - **Standard**: each word's BIP-39 index as 4 decimal digits (48 or 96 digits)
+//! - **Compact**: the raw entropy bytes (16 or 32 bytes, QR byte mode)
+//!
+//! For moving a seed between NoString installs, [`encrypt_seed_qr`] wraps
+//! [`encrypt_seed`] so the QR payload is never plaintext.
 
-use bip39::{Language, Mnemonic};
+use crate::crypto::{decrypt_seed, encrypt_seed, EncryptedSeed};
+use bip39::Mnemonic;
+use bitcoin::hex::{DisplayHex, FromHex};
 use thiserror::Error;
 use zeroize::Zeroizing;
 
+pub use bip39::Language;
+
 /// Supported word counts for BIP-39 mnemonics
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WordCount {
@@ -59,35 +74,52 @@ pub enum SeedError {
     EncryptionFailed(String),
     #[error("Decryption failed: {0}")]
     DecryptionFailed(String),
+    #[error("Invalid SeedQR: {0}")]
+    InvalidSeedQr(String),
+    #[error("Unknown mnemonic language: {0}")]
+    UnknownLanguage(String),
 }
 
+/// Prefix for password-encrypted seed QR payloads.
+///
+/// Uppercase so the whole payload fits QR alphanumeric mode.
+pub const ENCRYPTED_SEEDQR_PREFIX: &str = "NOSTRING:ESEED:";
+
 /// Generate a new BIP-39 mnemonic with the specified word count.
 ///
 /// # Arguments
 /// * `word_count` - Number of words (12, 15, 18, 21, or 24)
+/// * `language` - Wordlist to draw the words from
 ///
 /// # Returns
 /// A valid BIP-39 mnemonic phrase
 ///
 /// # Example
 /// ```
-/// use nostring_core::seed::{generate_mnemonic, WordCount};
-/// let mnemonic = generate_mnemonic(WordCount::Words24).unwrap();
+/// use nostring_core::seed::{generate_mnemonic, Language, WordCount};
+/// let mnemonic = generate_mnemonic(WordCount::Words24, Language::English).unwrap();
 /// assert_eq!(mnemonic.word_count(), 24);
 /// ```
-pub fn generate_mnemonic(word_count: WordCount) -> Result<Mnemonic, SeedError> {
-    Mnemonic::generate_in(Language::English, word_count.into())
+pub fn generate_mnemonic(word_count: WordCount, language: Language) -> Result<Mnemonic, SeedError> {
+    Mnemonic::generate_in(language, word_count.into())
         .map_err(|e: bip39::Error| SeedError::InvalidMnemonic(e.to_string()))
 }
 
-/// Generate a new BIP-39 mnemonic with 24 words (256-bit entropy).
+/// Generate a new English BIP-39 mnemonic with 24 words (256-bit entropy).
 ///
 /// This is the recommended word count for maximum security.
 pub fn generate_mnemonic_24() -> Result<Mnemonic, SeedError> {
-    generate_mnemonic(WordCount::Words24)
+    generate_mnemonic(WordCount::Words24, Language::English)
 }
 
-/// Parse and validate a mnemonic from a space-separated word string.
+/// Parse and validate a mnemonic from a space-separated word string,
+/// detecting its language.
+///
+/// The first word usually settles the language. A few wordlists share
+/// words (e.g. English and French both have "abandon"), so later words
+/// narrow it down; if the whole phrase fits several lists, the one whose
+/// checksum verifies wins, English first. Use [`Mnemonic::language`] on
+/// the result to see which list matched.
 ///
 /// # Arguments
 /// * `words` - Space-separated mnemonic words
@@ -97,11 +129,46 @@ pub fn generate_mnemonic_24() -> Result<Mnemonic, SeedError> {
 ///
 /// # Errors
 /// - Invalid word count
-/// - Invalid words (not in BIP-39 wordlist)
+/// - Invalid words (not in any BIP-39 wordlist)
 /// - Invalid checksum
 pub fn parse_mnemonic(words: &str) -> Result<Mnemonic, SeedError> {
-    Mnemonic::parse_in(Language::English, words)
-        .map_err(|e| SeedError::InvalidMnemonic(e.to_string()))
+    match Mnemonic::parse(words) {
+        Err(bip39::Error::AmbiguousLanguages(candidates)) => {
+            let mut languages: Vec<Language> = candidates.iter().collect();
+            languages.sort_by_key(|l| *l != Language::English);
+            languages
+                .into_iter()
+                .find_map(|language| Mnemonic::parse_in(language, words).ok())
+                .ok_or_else(|| {
+                    SeedError::InvalidMnemonic(
+                        bip39::Error::AmbiguousLanguages(candidates).to_string(),
+                    )
+                })
+        }
+        result => result.map_err(|e| SeedError::InvalidMnemonic(e.to_string())),
+    }
+}
+
+/// Parse and validate a mnemonic in a specific language.
+///
+/// Unlike [`parse_mnemonic`], words from any other wordlist are rejected.
+pub fn parse_mnemonic_in(words: &str, language: Language) -> Result<Mnemonic, SeedError> {
+    Mnemonic::parse_in(language, words).map_err(|e| SeedError::InvalidMnemonic(e.to_string()))
+}
+
+/// Look up a BIP-39 language by name, case-insensitively
+/// (e.g. "english", "Spanish", "simplifiedchinese").
+pub fn language_from_name(name: &str) -> Result<Language, SeedError> {
+    let wanted: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    Language::all()
+        .iter()
+        .copied()
+        .find(|l| l.to_string().to_lowercase() == wanted)
+        .ok_or_else(|| SeedError::UnknownLanguage(name.to_string()))
 }
 
 /// Derive a 64-byte seed from a mnemonic.
@@ -130,6 +197,129 @@ pub fn is_valid_mnemonic(words: &str) -> bool {
     parse_mnemonic(words).is_ok()
 }
 
+/// Encode a mnemonic as a Standard SeedQR digit string.
+///
+/// Each word becomes its zero-padded 4-digit BIP-39 index, so a 12-word
+/// mnemonic yields 48 digits and a 24-word mnemonic 96.
+///
+/// SeedQR is defined over the English wordlist: the digits of a
+/// non-English mnemonic decode back to *English* words, which derive a
+/// different seed.
+pub fn mnemonic_to_seedqr(mnemonic: &Mnemonic) -> String {
+    use std::fmt::Write;
+
+    // Write straight into the output so no per-word temporaries linger
+    let mut digits = String::with_capacity(mnemonic.word_count() * 4);
+    for index in mnemonic.word_indices() {
+        let _ = write!(digits, "{:04}", index);
+    }
+    digits
+}
+
+/// Decode a Standard SeedQR digit string back into a mnemonic.
+///
+/// # Errors
+/// - Non-digit characters or a length that isn't a multiple of 4
+/// - A word index outside the 2048-word list
+/// - Invalid word count or checksum
+pub fn seedqr_to_mnemonic(digits: &str) -> Result<Mnemonic, SeedError> {
+    let digits = digits.trim();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(SeedError::InvalidSeedQr(
+            "expected a string of decimal digits".into(),
+        ));
+    }
+    if digits.len() % 4 != 0 {
+        return Err(SeedError::InvalidSeedQr(format!(
+            "length {} is not a multiple of 4",
+            digits.len()
+        )));
+    }
+
+    let word_list = Language::English.word_list();
+    let mut words = Zeroizing::new(String::with_capacity(digits.len() * 2));
+    for chunk in digits.as_bytes().chunks(4) {
+        // Chunk is ASCII digits, so this can't fail
+        let index: usize = std::str::from_utf8(chunk)
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .ok_or_else(|| SeedError::InvalidSeedQr("invalid digit group".into()))?;
+        let word = word_list.get(index).ok_or_else(|| {
+            SeedError::InvalidSeedQr(format!("word index {} out of range", index))
+        })?;
+        if !words.is_empty() {
+            words.push(' ');
+        }
+        words.push_str(word);
+    }
+
+    parse_mnemonic_in(&words, Language::English)
+}
+
+/// Encode a mnemonic as a Compact SeedQR payload (raw entropy bytes).
+///
+/// 12 words → 16 bytes, 24 words → 32 bytes. The returned buffer is zeroized
+/// on drop.
+pub fn mnemonic_to_compact_seedqr(mnemonic: &Mnemonic) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(mnemonic.to_entropy())
+}
+
+/// Decode a Compact SeedQR payload (raw entropy bytes) into a mnemonic.
+pub fn compact_seedqr_to_mnemonic(entropy: &[u8]) -> Result<Mnemonic, SeedError> {
+    match entropy.len() {
+        16 | 32 => Mnemonic::from_entropy_in(Language::English, entropy)
+            .map_err(|e| SeedError::InvalidSeedQr(e.to_string())),
+        n => Err(SeedError::InvalidSeedQr(format!(
+            "compact SeedQR must be 16 or 32 bytes, got {}",
+            n
+        ))),
+    }
+}
+
+/// Encrypt a 64-byte seed with a password and encode it as a QR payload.
+///
+/// Wraps [`encrypt_seed`] (Argon2id + AES-256-GCM); the result is
+/// [`ENCRYPTED_SEEDQR_PREFIX`] followed by the uppercase hex of the
+/// encrypted blob.
+pub fn encrypt_seed_qr(seed: &[u8; 64], password: &str) -> Result<String, SeedError> {
+    let encrypted =
+        encrypt_seed(seed, password).map_err(|e| SeedError::EncryptionFailed(e.to_string()))?;
+    Ok(encrypted_seed_to_qr(&encrypted))
+}
+
+/// Encode an already-encrypted seed as a QR payload.
+pub fn encrypted_seed_to_qr(encrypted: &EncryptedSeed) -> String {
+    format!(
+        "{}{}",
+        ENCRYPTED_SEEDQR_PREFIX,
+        encrypted.to_bytes().to_upper_hex_string()
+    )
+}
+
+/// Parse an encrypted seed QR payload without decrypting it.
+pub fn encrypted_seed_from_qr(payload: &str) -> Result<EncryptedSeed, SeedError> {
+    let hex = payload
+        .trim()
+        .strip_prefix(ENCRYPTED_SEEDQR_PREFIX)
+        .ok_or_else(|| {
+            SeedError::InvalidSeedQr(format!("missing {} prefix", ENCRYPTED_SEEDQR_PREFIX))
+        })?;
+    let bytes = Vec::<u8>::from_hex(hex)
+        .map_err(|e| SeedError::InvalidSeedQr(format!("invalid hex: {}", e)))?;
+    EncryptedSeed::from_bytes(&bytes).map_err(|e| SeedError::InvalidSeedQr(e.to_string()))
+}
+
+/// Decode and decrypt an encrypted seed QR payload.
+pub fn decrypt_seed_qr(payload: &str, password: &str) -> Result<Zeroizing<[u8; 64]>, SeedError> {
+    let encrypted = encrypted_seed_from_qr(payload)?;
+    decrypt_seed(&encrypted, password).map_err(|e| SeedError::DecryptionFailed(e.to_string()))
+}
+
+/// Check whether a QR payload is a NoString encrypted seed (vs. a SeedQR).
+pub fn is_encrypted_seed_qr(payload: &str) -> bool {
+    payload.trim().starts_with(ENCRYPTED_SEEDQR_PREFIX)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,7 +332,7 @@ mod tests {
 
     #[test]
     fn test_generate_mnemonic_12() {
-        let mnemonic = generate_mnemonic(WordCount::Words12).unwrap();
+        let mnemonic = generate_mnemonic(WordCount::Words12, Language::English).unwrap();
         assert_eq!(mnemonic.word_count(), 12);
     }
 
@@ -227,6 +417,137 @@ mod tests {
 
         assert_eq!(seed.as_slice(), expected_seed.as_slice());
     }
+
+    #[test]
+    fn test_seedqr_standard_vector() {
+        let mnemonic = parse_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        ).unwrap();
+
+        // "abandon" = 0, "about" = 3
+        let digits = mnemonic_to_seedqr(&mnemonic);
+        assert_eq!(digits, format!("{}0003", "0000".repeat(11)));
+
+        let zoo = parse_mnemonic("zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong").unwrap();
+        assert!(mnemonic_to_seedqr(&zoo).starts_with("2047"));
+    }
+
+    #[test]
+    fn test_seedqr_standard_roundtrip() {
+        for wc in [WordCount::Words12, WordCount::Words24] {
+            let mnemonic = generate_mnemonic(wc, Language::English).unwrap();
+            let digits = mnemonic_to_seedqr(&mnemonic);
+            assert_eq!(digits.len(), usize::from(wc) * 4);
+
+            let decoded = seedqr_to_mnemonic(&digits).unwrap();
+            assert_eq!(decoded, mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_seedqr_compact_roundtrip() {
+        for (wc, len) in [(WordCount::Words12, 16), (WordCount::Words24, 32)] {
+            let mnemonic = generate_mnemonic(wc, Language::English).unwrap();
+            let compact = mnemonic_to_compact_seedqr(&mnemonic);
+            assert_eq!(compact.len(), len);
+
+            let decoded = compact_seedqr_to_mnemonic(&compact).unwrap();
+            assert_eq!(decoded, mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_seedqr_rejects_malformed() {
+        assert!(matches!(
+            seedqr_to_mnemonic("0000abcd"),
+            Err(SeedError::InvalidSeedQr(_))
+        ));
+        assert!(matches!(
+            seedqr_to_mnemonic("00000"),
+            Err(SeedError::InvalidSeedQr(_))
+        ));
+        // Index 2048 is past the end of the word list
+        assert!(matches!(
+            seedqr_to_mnemonic(&"2048".repeat(12)),
+            Err(SeedError::InvalidSeedQr(_))
+        ));
+        // Bad checksum (all "abandon")
+        assert!(seedqr_to_mnemonic(&"0000".repeat(12)).is_err());
+        // Wrong compact length
+        assert!(compact_seedqr_to_mnemonic(&[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_seed_qr_roundtrip() {
+        let mnemonic = generate_mnemonic_24().unwrap();
+        let seed = derive_seed(&mnemonic, "");
+
+        let payload = encrypt_seed_qr(&seed, "correct horse").unwrap();
+        assert!(is_encrypted_seed_qr(&payload));
+        assert!(!is_encrypted_seed_qr(&mnemonic_to_seedqr(&mnemonic)));
+
+        let decrypted = decrypt_seed_qr(&payload, "correct horse").unwrap();
+        assert_eq!(decrypted.as_slice(), seed.as_slice());
+
+        assert!(matches!(
+            decrypt_seed_qr(&payload, "wrong password"),
+            Err(SeedError::DecryptionFailed(_))
+        ));
+        assert!(matches!(
+            decrypt_seed_qr("NOT-A-SEED", "correct horse"),
+            Err(SeedError::InvalidSeedQr(_))
+        ));
+    }
+
+    #[test]
+    fn test_non_english_roundtrip() {
+        for language in [Language::Spanish, Language::Japanese, Language::French] {
+            let mnemonic = generate_mnemonic(WordCount::Words12, language).unwrap();
+            assert_eq!(mnemonic.language(), language);
+
+            let phrase = mnemonic.to_string();
+            let parsed = parse_mnemonic_in(&phrase, language).unwrap();
+            assert_eq!(parsed, mnemonic);
+            assert_eq!(*derive_seed(&parsed, ""), *derive_seed(&mnemonic, ""));
+        }
+    }
+
+    #[test]
+    fn test_parse_detects_language() {
+        for language in [Language::Spanish, Language::Japanese, Language::Italian] {
+            let mnemonic = generate_mnemonic(WordCount::Words24, language).unwrap();
+            let phrase = mnemonic.to_string();
+
+            let detected = parse_mnemonic(&phrase).unwrap();
+            assert_eq!(detected.language(), language);
+            assert_eq!(detected, mnemonic);
+
+            // Pinning the wrong language rejects it
+            assert!(parse_mnemonic_in(&phrase, Language::English).is_err());
+        }
+
+        // English phrases still parse as English, even where the first word
+        // ("abandon") is also French
+        let english = parse_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        assert_eq!(english.language(), Language::English);
+    }
+
+    #[test]
+    fn test_language_from_name() {
+        assert_eq!(language_from_name("spanish").unwrap(), Language::Spanish);
+        assert_eq!(language_from_name("Japanese").unwrap(), Language::Japanese);
+        assert_eq!(
+            language_from_name("Simplified Chinese").unwrap(),
+            Language::SimplifiedChinese
+        );
+        assert!(matches!(
+            language_from_name("klingon"),
+            Err(SeedError::UnknownLanguage(_))
+        ));
+    }
 }
 
 // Encrypted storage implemented in crypto.rs (Argon2id + AES-256-GCM)
@@ -0,0 +1,136 @@
+//! Non-blocking wrapper around [`ElectrumClient`]
+//!
+//! The underlying Electrum client does blocking socket I/O. This wrapper
+//! moves each call onto tokio's blocking thread pool so async callers (the
+//! notification service, the server daemon, Tauri commands) don't stall
+//! their executor and can poll several policies concurrently.
+//!
+//! Requires the `async` feature.
+
+use crate::{BroadcastOutcome, ElectrumClient, Error, ScriptHistoryItem, ServerFeatures, Utxo};
+use bitcoin::{Network, ScriptBuf, Transaction, Txid};
+use std::sync::Arc;
+
+/// Async Electrum client backed by a shared blocking [`ElectrumClient`]
+///
+/// Cheap to clone; clones share the same connection.
+#[derive(Clone)]
+pub struct AsyncElectrumClient {
+    inner: Arc<ElectrumClient>,
+}
+
+impl AsyncElectrumClient {
+    /// Connect to an Electrum server
+    ///
+    /// Connection setup itself is blocking, so it also runs on the blocking pool.
+    pub async fn new(url: &str, network: Network) -> Result<Self, Error> {
+        let url = url.to_string();
+        let client = run_blocking(move || ElectrumClient::new(&url, network)).await?;
+        Ok(Self::from_client(client))
+    }
+
+    /// Wrap an already-connected blocking client
+    pub fn from_client(client: ElectrumClient) -> Self {
+        Self {
+            inner: Arc::new(client),
+        }
+    }
+
+    /// Get the network this client is configured for
+    pub fn network(&self) -> Network {
+        self.inner.network()
+    }
+
+    /// The server's software, protocol range, pruning and genesis hash
+    pub async fn server_features(&self) -> Result<ServerFeatures, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.server_features()).await
+    }
+
+    /// Get current blockchain tip height
+    pub async fn get_height(&self) -> Result<u32, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.get_height()).await
+    }
+
+    /// Get UTXOs for a script
+    pub async fn get_utxos_for_script(&self, script: ScriptBuf) -> Result<Vec<Utxo>, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.get_utxos_for_script(&script)).await
+    }
+
+    /// Get a script's transaction history, cached while its status is unchanged
+    pub async fn get_script_history_cached(
+        &self,
+        script: ScriptBuf,
+    ) -> Result<Vec<ScriptHistoryItem>, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.get_script_history_cached(&script)).await
+    }
+
+    /// Forget cached script histories
+    pub fn clear_history_cache(&self) {
+        self.inner.clear_history_cache();
+    }
+
+    /// Get a transaction by txid
+    pub async fn get_transaction(&self, txid: Txid) -> Result<Transaction, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.get_transaction(&txid)).await
+    }
+
+    /// Get several transactions in one batched request
+    pub async fn get_transactions(
+        &self,
+        txids: Vec<Txid>,
+    ) -> Result<Vec<(Txid, Transaction)>, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.get_transactions(&txids)).await
+    }
+
+    /// Broadcast a signed transaction, returning its txid
+    pub async fn broadcast(&self, tx: Transaction) -> Result<Txid, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.broadcast(&tx)).await
+    }
+
+    /// Broadcast a signed transaction, reporting whether the server already knew it
+    pub async fn broadcast_idempotent(&self, tx: Transaction) -> Result<BroadcastOutcome, Error> {
+        let client = Arc::clone(&self.inner);
+        run_blocking(move || client.broadcast_idempotent(&tx)).await
+    }
+}
+
+/// Run a blocking Electrum call on tokio's blocking pool
+async fn run_blocking<T, F>(f: F) -> Result<T, Error>
+where
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Task(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default_server;
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "requires network access"]
+    async fn test_concurrent_get_height() {
+        let client = AsyncElectrumClient::new(default_server(Network::Bitcoin), Network::Bitcoin)
+            .await
+            .unwrap();
+
+        let other = client.clone();
+        let (a, b) = tokio::join!(client.get_height(), other.get_height());
+        let (a, b) = (a.unwrap(), b.unwrap());
+        println!("Concurrent heights: {} / {}", a, b);
+
+        assert!(a > 0 && b > 0);
+        // Both calls hit the same tip, give or take a block found in between
+        assert!(a.abs_diff(b) <= 1);
+    }
+}
@@ -0,0 +1,307 @@
+//! In-memory script history cache
+//!
+//! Electrum summarises a script's history as a status hash
+//! (`blockchain.scripthash.subscribe`). While the status is unchanged the
+//! history is too, so [`ElectrumClient::get_script_history_cached`] keeps
+//! the last history per script and only re-fetches when the status moves.
+//!
+//! [`ElectrumClient::get_script_history_cached`]: crate::ElectrumClient::get_script_history_cached
+
+use crate::{Error, ScriptHistoryItem};
+use bitcoin::{Script, ScriptBuf};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Scripts whose history is kept before the least recently used is dropped
+pub const DEFAULT_HISTORY_CACHE_SIZE: usize = 256;
+
+/// LRU cache of script histories, keyed by script and tagged with the
+/// status hash they were fetched at
+///
+/// `S` is the server's status type; `None` means the script has no history.
+#[derive(Debug)]
+pub(crate) struct HistoryCache<S> {
+    capacity: usize,
+    /// Last known status of every subscribed script. Subscriptions live as
+    /// long as the connection, so this outlives evicted or cleared entries.
+    statuses: HashMap<ScriptBuf, Option<S>>,
+    /// Cached histories, least recently used first
+    entries: VecDeque<(ScriptBuf, Option<S>, Vec<ScriptHistoryItem>)>,
+}
+
+impl<S: Clone + PartialEq> HistoryCache<S> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            statuses: HashMap::new(),
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Whether `script` is subscribed, i.e. has a known status
+    pub(crate) fn is_subscribed(&self, script: &Script) -> bool {
+        self.statuses.contains_key(script)
+    }
+
+    /// Record the latest status of `script`
+    pub(crate) fn set_status(&mut self, script: &Script, status: Option<S>) {
+        self.statuses.insert(script.to_owned(), status);
+    }
+
+    /// Last known status of `script` (`None` if unknown or no history)
+    pub(crate) fn status(&self, script: &Script) -> Option<S> {
+        self.statuses.get(script).cloned().flatten()
+    }
+
+    /// Cached history of `script`, if it was fetched at the current status.
+    ///
+    /// A stale entry is dropped.
+    pub(crate) fn get(&mut self, script: &Script) -> Option<Vec<ScriptHistoryItem>> {
+        let status = self.status(script);
+        let i = self.entries.iter().position(|(s, _, _)| s == script)?;
+        let entry = self.entries.remove(i).expect("position is in range");
+        if entry.1 != status {
+            log::debug!("Script status changed; refreshing cached history");
+            return None;
+        }
+        let history = entry.2.clone();
+        self.entries.push_back(entry);
+        Some(history)
+    }
+
+    /// Cache `history` of `script`, fetched at `status`
+    pub(crate) fn insert(
+        &mut self,
+        script: &Script,
+        status: Option<S>,
+        history: Vec<ScriptHistoryItem>,
+    ) {
+        self.entries.retain(|(s, _, _)| s != script);
+        self.entries.push_back((script.to_owned(), status, history));
+        if self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Drop every cached history (statuses are kept)
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Forget subscriptions too, after the connection they lived on is gone
+    pub(crate) fn reset(&mut self) {
+        self.statuses.clear();
+        self.entries.clear();
+    }
+}
+
+/// Server calls behind [`cached_history`]
+pub(crate) trait HistorySource {
+    type Status: Clone + PartialEq;
+
+    /// Subscribe to `script`, returning its current status
+    fn subscribe(&self, script: &Script) -> Result<Option<Self::Status>, Error>;
+
+    /// Status notifications received for a subscribed `script`, oldest first
+    fn notifications(&self, script: &Script) -> Result<Vec<Self::Status>, Error>;
+
+    /// Full history of `script`
+    fn history(&self, script: &Script) -> Result<Vec<ScriptHistoryItem>, Error>;
+}
+
+/// `script`'s history from `cache`, or from `source` if its status moved.
+///
+/// The first call subscribes to the script; later calls only read pending
+/// notifications. When reading them fails the subscriptions were lost with
+/// the connection, so they are forgotten and the script is subscribed
+/// again. The cache lock is never held across a call to `source`.
+pub(crate) fn cached_history<C: HistorySource>(
+    cache: &Mutex<HistoryCache<C::Status>>,
+    source: &C,
+    script: &Script,
+) -> Result<Vec<ScriptHistoryItem>, Error> {
+    let subscribed = cache.lock().unwrap().is_subscribed(script);
+    let notified = if subscribed {
+        match source.notifications(script) {
+            Ok(statuses) => Some(statuses),
+            Err(e) => {
+                log::debug!("Script subscriptions lost ({}); resubscribing", e);
+                cache.lock().unwrap().reset();
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let subscribed_status = match notified {
+        Some(_) => None,
+        None => Some(source.subscribe(script)?),
+    };
+
+    let status = {
+        let mut cache = cache.lock().unwrap();
+        for status in notified.into_iter().flatten() {
+            cache.set_status(script, Some(status));
+        }
+        if let Some(status) = subscribed_status {
+            cache.set_status(script, status);
+        }
+        if let Some(history) = cache.get(script) {
+            return Ok(history);
+        }
+        cache.status(script)
+    };
+
+    let history = source.history(script)?;
+    cache
+        .lock()
+        .unwrap()
+        .insert(script, status, history.clone());
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+    use bitcoin::Txid;
+    use std::cell::{Cell, RefCell};
+
+    fn script(n: u8) -> ScriptBuf {
+        ScriptBuf::from_bytes(vec![0x00, 0x14, n])
+    }
+
+    fn history(n: u8) -> Vec<ScriptHistoryItem> {
+        vec![ScriptHistoryItem {
+            txid: Txid::from_byte_array([n; 32]),
+            height: 800_000 + n as u32,
+        }]
+    }
+
+    /// Server double: one status shared by every script, plus counters
+    #[derive(Default)]
+    struct StubServer {
+        status: Cell<u8>,
+        pending: RefCell<Vec<[u8; 32]>>,
+        connection_lost: Cell<bool>,
+        fail_history: Cell<bool>,
+        subscribes: Cell<usize>,
+        fetches: Cell<usize>,
+    }
+
+    impl StubServer {
+        /// Move every script to status `n`, notifying subscribers
+        fn set_status(&self, n: u8) {
+            self.status.set(n);
+            self.pending.borrow_mut().push([n; 32]);
+        }
+    }
+
+    impl HistorySource for StubServer {
+        type Status = [u8; 32];
+
+        fn subscribe(&self, _: &Script) -> Result<Option<[u8; 32]>, Error> {
+            self.subscribes.set(self.subscribes.get() + 1);
+            self.connection_lost.set(false);
+            self.pending.borrow_mut().clear();
+            Ok(Some([self.status.get(); 32]))
+        }
+
+        fn notifications(&self, _: &Script) -> Result<Vec<[u8; 32]>, Error> {
+            if self.connection_lost.get() {
+                return Err(Error::Connection("connection reset".into()));
+            }
+            Ok(self.pending.borrow_mut().drain(..).collect())
+        }
+
+        fn history(&self, _: &Script) -> Result<Vec<ScriptHistoryItem>, Error> {
+            if self.fail_history.get() {
+                return Err(Error::Timeout("history".into()));
+            }
+            self.fetches.set(self.fetches.get() + 1);
+            Ok(history(self.status.get()))
+        }
+    }
+
+    #[test]
+    fn test_unchanged_status_served_from_cache() {
+        let cache = Mutex::new(HistoryCache::new(8));
+        let server = StubServer::default();
+        server.set_status(1);
+
+        let fetched = cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(fetched[0].height, 800_001);
+        assert_eq!(server.fetches.get(), 1);
+
+        // Same status: no history round trip
+        let cached = cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(cached[0].txid, history(1)[0].txid);
+        assert_eq!(server.fetches.get(), 1);
+
+        // A status notification invalidates the entry
+        server.set_status(2);
+        let refreshed = cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(refreshed[0].height, 800_002);
+        assert_eq!(server.fetches.get(), 2);
+
+        // Manual clear forces a refresh; the subscription is kept
+        cache.lock().unwrap().clear();
+        cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(server.fetches.get(), 3);
+        assert_eq!(server.subscribes.get(), 1);
+    }
+
+    #[test]
+    fn test_least_recently_used_evicted() {
+        let cache = Mutex::new(HistoryCache::new(2));
+        let server = StubServer::default();
+        let get = |n| {
+            cached_history(&cache, &server, &script(n)).unwrap();
+        };
+
+        get(1);
+        get(2);
+        get(1); // 1 is now the most recently used
+        get(3); // evicts 2
+        assert_eq!(server.fetches.get(), 3);
+
+        get(1);
+        assert_eq!(server.fetches.get(), 3);
+        get(2);
+        assert_eq!(server.fetches.get(), 4);
+    }
+
+    #[test]
+    fn test_fetch_errors_not_cached() {
+        let cache = Mutex::new(HistoryCache::new(2));
+        let server = StubServer::default();
+        server.fail_history.set(true);
+        assert!(cached_history(&cache, &server, &script(1)).is_err());
+
+        server.fail_history.set(false);
+        let fetched = cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(server.fetches.get(), 1);
+    }
+
+    #[test]
+    fn test_lost_subscriptions_resubscribe() {
+        let cache = Mutex::new(HistoryCache::new(2));
+        let server = StubServer::default();
+        cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(server.subscribes.get(), 1);
+
+        // The client reconnected and the status moved while nobody was
+        // subscribed: the cached copy must not be trusted
+        server.connection_lost.set(true);
+        server.status.set(5);
+        let fetched = cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(fetched[0].height, 800_005);
+        assert_eq!(server.subscribes.get(), 2);
+        assert_eq!(server.fetches.get(), 2);
+
+        // Subscribed again: served from cache
+        cached_history(&cache, &server, &script(1)).unwrap();
+        assert_eq!(server.fetches.get(), 2);
+    }
+}
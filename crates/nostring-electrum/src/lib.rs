@@ -21,11 +21,30 @@
 //! let height = client.get_height()?;
 //! println!("Current block height: {}", height);
 //! ```
+//!
+//! With the `async` feature, [`AsyncElectrumClient`] offers the same calls as
+//! futures for use from async services.
 
-use bitcoin::{Address, Amount, Network, OutPoint, Script, ScriptBuf, Transaction, Txid};
-use electrum_client::{ElectrumApi, Error as ElectrumError};
+use bitcoin::constants::genesis_block;
+use bitcoin::{
+    Address, Amount, BlockHash, Network, OutPoint, Script, ScriptBuf, Transaction, Txid,
+};
+use electrum_client::{ElectrumApi, Error as ElectrumError, ScriptStatus};
+use history_cache::HistoryCache;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 use thiserror::Error;
 
+mod history_cache;
+pub use history_cache::DEFAULT_HISTORY_CACHE_SIZE;
+
+#[cfg(feature = "async")]
+pub mod async_client;
+#[cfg(feature = "async")]
+pub use async_client::AsyncElectrumClient;
+
 // Re-export the raw client for direct usage
 pub use electrum_client::Client as RawClient;
 
@@ -36,7 +55,10 @@ pub enum Error {
     Connection(String),
 
     #[error("Electrum protocol error: {0}")]
-    Protocol(#[from] ElectrumError),
+    Protocol(ElectrumError),
+
+    #[error("Electrum server timed out: {0}")]
+    Timeout(String),
 
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
@@ -49,8 +71,94 @@ pub enum Error {
 
     #[error("No UTXOs found for address")]
     NoUtxos,
+
+    #[error("Background task failed: {0}")]
+    Task(String),
+
+    #[error("Invalid custom signet parameters: {0}")]
+    InvalidSignet(String),
+}
+
+impl From<ElectrumError> for Error {
+    fn from(e: ElectrumError) -> Self {
+        if is_timeout(&e) {
+            Error::Timeout(e.to_string())
+        } else {
+            Error::Protocol(e)
+        }
+    }
 }
 
+/// Whether an Electrum error is an I/O timeout rather than a protocol failure
+fn is_timeout(e: &ElectrumError) -> bool {
+    match e {
+        ElectrumError::IOError(io) => matches!(
+            io.kind(),
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+        ),
+        ElectrumError::AllAttemptsErrored(errors) => {
+            !errors.is_empty() && errors.iter().all(is_timeout)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the server rejected a header request because the height is past
+/// its tip ("height N out of range"), as opposed to any other failure
+fn is_height_out_of_range(e: &ElectrumError) -> bool {
+    match e {
+        ElectrumError::Protocol(value) => value.to_string().contains("out of range"),
+        _ => false,
+    }
+}
+
+/// Server rejections meaning the transaction was already accepted earlier
+const ALREADY_BROADCAST_ERRORS: &[&str] = &[
+    "txn-already-known",
+    "txn-already-in-mempool",
+    "already in block chain",
+    "already in blockchain",
+    "already in the mempool",
+];
+
+/// Outcome of a successful broadcast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastOutcome {
+    /// The server accepted the transaction
+    Accepted(Txid),
+    /// The server already had the transaction (e.g. a retried check-in)
+    AlreadyKnown(Txid),
+}
+
+impl BroadcastOutcome {
+    /// The broadcast transaction's txid
+    pub fn txid(&self) -> Txid {
+        match self {
+            BroadcastOutcome::Accepted(txid) | BroadcastOutcome::AlreadyKnown(txid) => *txid,
+        }
+    }
+}
+
+/// Map a broadcast rejection to success if it means "already accepted"
+///
+/// Also applies to rejections from other broadcast paths (e.g. an HTTP
+/// endpoint relaying bitcoind's error text).
+pub fn broadcast_error_outcome(txid: Txid, msg: &str) -> Result<BroadcastOutcome, Error> {
+    let lower = msg.to_lowercase();
+    if ALREADY_BROADCAST_ERRORS.iter().any(|s| lower.contains(s)) {
+        log::info!("Transaction {} already known to server: {}", txid, msg);
+        Ok(BroadcastOutcome::AlreadyKnown(txid))
+    } else {
+        Err(Error::BroadcastFailed(msg.to_string()))
+    }
+}
+
+/// Default time allowed to establish a connection to the server
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time allowed for a single request/response round trip
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// A transaction in a script's history
 #[derive(Debug, Clone)]
 pub struct ScriptHistoryItem {
@@ -73,10 +181,116 @@ pub struct Utxo {
     pub script_pubkey: ScriptBuf,
 }
 
+/// An Electrum protocol version (`major.minor[.patch]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ProtocolVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl std::str::FromStr for ProtocolVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::Connection(format!("invalid protocol version {:?}", s));
+        let parts: Vec<u32> = s
+            .trim()
+            .split('.')
+            .map(|part| part.parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        match parts[..] {
+            [major, minor] => Ok(Self::new(major, minor, 0)),
+            [major, minor, patch] => Ok(Self::new(major, minor, patch)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.patch == 0 {
+            write!(f, "{}.{}", self.major, self.minor)
+        } else {
+            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        }
+    }
+}
+
+/// What an Electrum server reports about itself (`server.features`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerFeatures {
+    /// Server software and version, as `server.version` reports it
+    /// (e.g. "ElectrumX 1.16.0")
+    pub server_version: String,
+    /// Oldest protocol version the server speaks
+    pub protocol_min: ProtocolVersion,
+    /// Newest protocol version the server speaks
+    pub protocol_max: ProtocolVersion,
+    /// Genesis block of the chain the server follows
+    pub genesis_hash: BlockHash,
+    /// History pruning limit, if the server prunes
+    pub pruning: Option<i64>,
+    /// Script hash function (always "sha256" today)
+    pub hash_function: Option<String>,
+}
+
+impl ServerFeatures {
+    /// Whether the server speaks protocol `version`
+    pub fn supports_protocol(&self, version: ProtocolVersion) -> bool {
+        (self.protocol_min..=self.protocol_max).contains(&version)
+    }
+
+    /// Whether the server keeps full script history
+    ///
+    /// A pruning server may no longer know the transaction that spent an
+    /// old inheritance UTXO, so spend detection can come up empty.
+    pub fn has_full_history(&self) -> bool {
+        self.pruning.is_none()
+    }
+
+    fn from_response(res: electrum_client::ServerFeaturesRes) -> Result<Self, Error> {
+        use bitcoin::hex::DisplayHex;
+        use std::str::FromStr;
+
+        // The hash arrives as its hex string's bytes, i.e. in display order
+        let genesis_hash = BlockHash::from_str(&res.genesis_hash.to_lower_hex_string())
+            .map_err(|e| Error::Connection(format!("invalid genesis hash: {}", e)))?;
+        Ok(Self {
+            server_version: res.server_version,
+            protocol_min: res.protocol_min.parse()?,
+            protocol_max: res.protocol_max.parse()?,
+            genesis_hash,
+            pruning: res.pruning,
+            hash_function: res.hash_function,
+        })
+    }
+}
+
 /// Electrum client for Bitcoin network operations
 pub struct ElectrumClient {
     client: electrum_client::Client,
     network: Network,
+    /// Genesis block hash the server must report
+    expected_genesis: BlockHash,
+    /// Outcome of the genesis-hash check, filled in on first use
+    network_check: OnceLock<Result<(), String>>,
+    /// Script histories keyed by their subscribed status hash
+    history_cache: Mutex<HistoryCache<ScriptStatus>>,
+    /// `server.features`, fetched on first use
+    features: OnceLock<ServerFeatures>,
+    /// Set once the server rejects a batch request; later calls go one by one
+    batch_rejected: AtomicBool,
 }
 
 impl ElectrumClient {
@@ -86,18 +300,129 @@ impl ElectrumClient {
     /// * `url` - Electrum server URL (e.g., "ssl://blockstream.info:700")
     /// * `network` - Bitcoin network (Mainnet, Testnet, Signet, Regtest)
     ///
+    /// Uses [`DEFAULT_CONNECT_TIMEOUT`] (10s) and [`DEFAULT_REQUEST_TIMEOUT`] (30s).
+    ///
     /// # Security
     /// Always use SSL URLs in production. Plaintext connections can be MITM'd.
     pub fn new(url: &str, network: Network) -> Result<Self, Error> {
+        Self::with_timeouts(
+            url,
+            network,
+            DEFAULT_CONNECT_TIMEOUT,
+            DEFAULT_REQUEST_TIMEOUT,
+        )
+    }
+
+    /// Create a new Electrum client with explicit timeouts
+    ///
+    /// `connect_timeout` bounds reaching the server; `timeout` bounds each
+    /// request afterwards (socket read/write). Either expiring surfaces as
+    /// [`Error::Timeout`] so callers can back off or fail over to another
+    /// server instead of hanging.
+    pub fn with_timeouts(
+        url: &str,
+        network: Network,
+        connect_timeout: Duration,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
         // Warn if not using SSL
         if !url.starts_with("ssl://") && !url.contains("tls") {
             log::warn!("Connecting to Electrum without SSL - insecure for mainnet!");
         }
 
-        let client = electrum_client::Client::new(url)
-            .map_err(|e: ElectrumError| Error::Connection(e.to_string()))?;
+        check_reachable(url, connect_timeout)?;
+
+        // electrum-client takes whole seconds (1-255)
+        let timeout_secs = timeout.as_secs().clamp(1, u8::MAX as u64) as u8;
+        let config = electrum_client::ConfigBuilder::new()
+            .timeout(Some(timeout_secs))
+            .build();
+
+        let client = electrum_client::Client::from_config(url, config).map_err(|e| {
+            match Error::from(e) {
+                Error::Protocol(e) => Error::Connection(e.to_string()),
+                other => other,
+            }
+        })?;
+
+        Ok(Self {
+            client,
+            network,
+            expected_genesis: genesis_block(network).block_hash(),
+            network_check: OnceLock::new(),
+            history_cache: Mutex::new(HistoryCache::new(DEFAULT_HISTORY_CACHE_SIZE)),
+            features: OnceLock::new(),
+            batch_rejected: AtomicBool::new(false),
+        })
+    }
+
+    /// Expect `genesis` instead of the network's standard genesis block
+    /// (e.g. for a custom signet).
+    pub fn with_expected_genesis(mut self, genesis: BlockHash) -> Self {
+        self.expected_genesis = genesis;
+        self.network_check = OnceLock::new();
+        self
+    }
+
+    /// Verify the server is serving `network` by comparing its genesis block
+    /// hash against the expected one.
+    ///
+    /// Runs lazily on the first network call and caches the outcome, so a
+    /// mismatched server fails every subsequent call with `Error::Connection`.
+    /// Transport errors while fetching the header are not cached.
+    ///
+    /// The genesis the server claims in `server.features` must match too;
+    /// servers that don't answer `server.features` are judged on the header
+    /// alone.
+    pub fn verify_network(&self) -> Result<(), Error> {
+        if let Some(result) = self.network_check.get() {
+            return result.clone().map_err(Error::Connection);
+        }
+
+        let genesis = self.client.block_header(0)?;
+        let mut result = check_genesis(self.network, self.expected_genesis, genesis.block_hash());
+        if result.is_ok() {
+            match self.fetch_server_features() {
+                Ok(features) => {
+                    result =
+                        check_genesis(self.network, self.expected_genesis, features.genesis_hash);
+                    if !features.has_full_history() {
+                        log::warn!(
+                            "Electrum server {} prunes history; old spends may not be found",
+                            features.server_version
+                        );
+                    }
+                }
+                Err(e) => log::debug!("server.features unavailable: {}", e),
+            }
+        }
+        let _ = self.network_check.set(result.clone());
+        result.map_err(Error::Connection)
+    }
+
+    /// The server's software, protocol range, pruning and genesis hash
+    /// (`server.features`)
+    ///
+    /// Fetched once per connection and cached.
+    pub fn server_features(&self) -> Result<ServerFeatures, Error> {
+        self.verify_network()?;
+        self.fetch_server_features()
+    }
+
+    fn fetch_server_features(&self) -> Result<ServerFeatures, Error> {
+        if let Some(features) = self.features.get() {
+            return Ok(features.clone());
+        }
+        let features = ServerFeatures::from_response(self.client.server_features()?)?;
+        Ok(self.features.get_or_init(|| features).clone())
+    }
 
-        Ok(Self { client, network })
+    /// Whether to send batch requests to this server
+    ///
+    /// Batching isn't advertised in `server.features`, so it's assumed until
+    /// the server rejects a batch.
+    pub fn supports_batching(&self) -> bool {
+        !self.batch_rejected.load(Ordering::Relaxed)
     }
 
     /// Get current blockchain tip height
@@ -106,22 +431,85 @@ impl ElectrumClient {
     /// the current tip directly. This is network-agnostic and works on
     /// mainnet, testnet, signet, and regtest without assumptions about
     /// block height ranges.
+    ///
+    /// Only a height that can't be right for the header it came with (see
+    /// [`plausible_tip_height`]) falls back to searching for the highest
+    /// header the server has. Request errors are returned as they are.
     pub fn get_height(&self) -> Result<u32, Error> {
+        self.verify_network()?;
         let notification = self.client.block_headers_subscribe()?;
-        Ok(notification.height as u32)
+
+        match plausible_tip_height(notification.height, &notification.header) {
+            Some(height) => Ok(height),
+            None => {
+                log::warn!(
+                    "Subscribed height {} is implausible for its header; searching for tip",
+                    notification.height
+                );
+                self.search_height()
+            }
+        }
+    }
+
+    /// Find the tip height by searching for the highest header the server has.
+    ///
+    /// Probes upward exponentially to bracket the tip, then bisects, so no
+    /// chain-height assumptions are baked in.
+    ///
+    /// Only an out-of-range rejection counts as a missing header; timeouts
+    /// and other errors are returned instead of being read as the tip.
+    fn search_height(&self) -> Result<u32, Error> {
+        let has_header = |h: u32| match self.client.block_header(h as usize) {
+            Ok(_) => Ok(true),
+            Err(e) if is_height_out_of_range(&e) => Ok(false),
+            Err(e) => Err(Error::from(e)),
+        };
+
+        // Bracket: `low` exists, `high` does not
+        let mut low = 0u32;
+        let mut high = 1u32;
+        while has_header(high)? {
+            low = high;
+            high = high
+                .checked_mul(2)
+                .ok_or_else(|| Error::Connection("server reports unbounded chain".into()))?;
+        }
+
+        bisect_height(low, high, has_header)
     }
 
     /// Get the tip header via subscription (height may be unreliable)
     pub fn get_tip_header(&self) -> Result<bitcoin::block::Header, Error> {
+        self.verify_network()?;
         let notification = self.client.block_headers_subscribe()?;
         Ok(notification.header)
     }
 
+    /// Get the timestamp of the block at `height`.
+    pub fn get_block_time(&self, height: u32) -> Result<u32, Error> {
+        self.verify_network()?;
+        Ok(self.client.block_header(height as usize)?.time)
+    }
+
+    /// Get the timestamps of the last `count` block headers, oldest first.
+    ///
+    /// Used to calibrate timelock expiry estimates against the actual recent
+    /// block interval instead of assuming 10 minutes.
+    pub fn get_recent_block_times(&self, count: u32) -> Result<Vec<u32>, Error> {
+        let tip = self.get_height()?;
+        let start = tip.saturating_sub(count.saturating_sub(1));
+        let res = self
+            .client
+            .block_headers(start as usize, (tip - start + 1) as usize)?;
+        Ok(res.headers.iter().map(|h| h.time).collect())
+    }
+
     /// Get UTXOs for a script (typically from a descriptor address)
     ///
     /// # Arguments
     /// * `script` - The script pubkey to search for
     pub fn get_utxos_for_script(&self, script: &Script) -> Result<Vec<Utxo>, Error> {
+        self.verify_network()?;
         let unspent = self.client.script_list_unspent(script)?;
 
         let utxos: Vec<Utxo> = unspent
@@ -145,6 +533,7 @@ impl ElectrumClient {
     /// Returns all transactions that have interacted with this script,
     /// including both funding and spending transactions.
     pub fn get_script_history(&self, script: &Script) -> Result<Vec<ScriptHistoryItem>, Error> {
+        self.verify_network()?;
         let history = self.client.script_get_history(script)?;
         Ok(history
             .into_iter()
@@ -155,6 +544,46 @@ impl ElectrumClient {
             .collect())
     }
 
+    /// Get transaction history for a script, reusing the last fetched copy
+    /// while the script's status hash is unchanged
+    ///
+    /// The first call subscribes to the script
+    /// (`blockchain.scripthash.subscribe`); later calls only read pending
+    /// status notifications, so a stable address costs a ping instead of a
+    /// full history round trip. A status change invalidates the entry.
+    ///
+    /// electrum-client reconnects on its own after a failed call, and the
+    /// new connection carries none of our subscriptions; when reading
+    /// notifications fails the subscriptions are forgotten and the script
+    /// is subscribed again.
+    pub fn get_script_history_cached(
+        &self,
+        script: &Script,
+    ) -> Result<Vec<ScriptHistoryItem>, Error> {
+        self.verify_network()?;
+        history_cache::cached_history(&self.history_cache, self, script)
+    }
+
+    /// Forget cached script histories so the next
+    /// [`get_script_history_cached`](Self::get_script_history_cached) call
+    /// re-fetches (manual refresh, tests)
+    pub fn clear_history_cache(&self) {
+        self.history_cache.lock().unwrap().clear();
+    }
+
+    /// Confirmation height of a transaction found in `script`'s history.
+    ///
+    /// Returns `Some(0)` for a mempool transaction and `None` if the script
+    /// has no record of `txid`.
+    pub fn get_tx_height(&self, txid: &Txid, script: &Script) -> Result<Option<u32>, Error> {
+        self.verify_network()?;
+        let history = self.client.script_get_history(script)?;
+        Ok(history
+            .into_iter()
+            .find(|h| h.tx_hash == *txid)
+            .map(|h| h.height.max(0) as u32))
+    }
+
     /// Get UTXOs for an address
     pub fn get_utxos(&self, address: &Address) -> Result<Vec<Utxo>, Error> {
         // Note: Address type in bitcoin 0.32 uses NetworkKind, not Network directly
@@ -164,23 +593,85 @@ impl ElectrumClient {
 
     /// Get a transaction by txid
     pub fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
+        self.verify_network()?;
         self.client
             .transaction_get(txid)
             .map_err(|_| Error::TxNotFound(*txid))
     }
 
+    /// Get several transactions in one batched request
+    ///
+    /// Results keep the order of `txids`. Transactions the server can't
+    /// return (or returns with the wrong txid) are skipped rather than
+    /// failing the whole call; if the batch itself is rejected, each txid
+    /// is retried individually, and later calls skip batching altogether.
+    /// Timeouts are still reported as errors.
+    pub fn get_transactions(&self, txids: &[Txid]) -> Result<Vec<(Txid, Transaction)>, Error> {
+        self.verify_network()?;
+        if txids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if !self.supports_batching() {
+            return Ok(self.get_transactions_individually(txids));
+        }
+
+        match self.client.batch_transaction_get(txids.iter()) {
+            Ok(txs) => Ok(txids
+                .iter()
+                .copied()
+                .zip(txs)
+                .filter(|(txid, tx)| tx.compute_txid() == *txid)
+                .collect()),
+            Err(e) if is_timeout(&e) => Err(Error::Timeout(e.to_string())),
+            Err(e) => {
+                log::debug!(
+                    "Batch fetch of {} transactions failed ({}); fetching individually",
+                    txids.len(),
+                    e
+                );
+                self.batch_rejected.store(true, Ordering::Relaxed);
+                Ok(self.get_transactions_individually(txids))
+            }
+        }
+    }
+
+    fn get_transactions_individually(&self, txids: &[Txid]) -> Vec<(Txid, Transaction)> {
+        txids
+            .iter()
+            .filter_map(|txid| {
+                self.client
+                    .transaction_get(txid)
+                    .ok()
+                    .filter(|tx| tx.compute_txid() == *txid)
+                    .map(|tx| (*txid, tx))
+            })
+            .collect()
+    }
+
     /// Broadcast a signed transaction
     ///
+    /// A transaction the server already has (in its mempool or in a block)
+    /// counts as success, so retrying a broadcast is safe.
+    ///
     /// # Returns
     /// The txid of the broadcast transaction
     pub fn broadcast(&self, tx: &Transaction) -> Result<Txid, Error> {
-        self.client
-            .transaction_broadcast(tx)
-            .map_err(|e: ElectrumError| Error::BroadcastFailed(e.to_string()))
+        self.broadcast_idempotent(tx).map(|outcome| outcome.txid())
+    }
+
+    /// Broadcast a signed transaction, reporting whether the server
+    /// already knew it.
+    pub fn broadcast_idempotent(&self, tx: &Transaction) -> Result<BroadcastOutcome, Error> {
+        self.verify_network()?;
+        match self.client.transaction_broadcast(tx) {
+            Ok(txid) => Ok(BroadcastOutcome::Accepted(txid)),
+            Err(e) => broadcast_error_outcome(tx.compute_txid(), &e.to_string()),
+        }
     }
 
     /// Get the balance for a script
     pub fn get_balance(&self, script: &Script) -> Result<Amount, Error> {
+        self.verify_network()?;
         let balance = self.client.script_get_balance(script)?;
         // Note: unconfirmed can be negative (pending spends), so handle carefully
         let total = balance.confirmed as i64 + balance.unconfirmed;
@@ -198,6 +689,8 @@ impl ElectrumClient {
     /// This works by fetching the transaction, then checking its presence
     /// in the script history (which includes block height for confirmed txs).
     pub fn is_confirmed(&self, txid: &Txid) -> Result<bool, Error> {
+        self.verify_network()?;
+
         // Get the transaction to find its outputs
         let tx = match self.client.transaction_get(txid) {
             Ok(t) => t,
@@ -229,6 +722,8 @@ impl ElectrumClient {
     /// - Ceiling: 500 sat/vB (protects against malicious server)
     /// - Fallback: 10.0 sat/vB if estimation fails
     pub fn estimate_fee_rate(&self, target_blocks: usize) -> Result<f64, Error> {
+        self.verify_network()?;
+
         let btc_per_kb = match self.client.estimate_fee(target_blocks) {
             Ok(rate) if rate > 0.0 => rate,
             _ => {
@@ -265,6 +760,8 @@ impl ElectrumClient {
     }
 
     pub fn get_confirmation_height(&self, txid: &Txid) -> Result<Option<u32>, Error> {
+        self.verify_network()?;
+
         let tx = match self.client.transaction_get(txid) {
             Ok(t) => t,
             Err(_) => return Ok(None),
@@ -285,27 +782,270 @@ impl ElectrumClient {
     }
 }
 
-/// Default Electrum servers for each network
+impl history_cache::HistorySource for ElectrumClient {
+    type Status = ScriptStatus;
+
+    fn subscribe(&self, script: &Script) -> Result<Option<ScriptStatus>, Error> {
+        Ok(self.client.script_subscribe(script)?)
+    }
+
+    fn notifications(&self, script: &Script) -> Result<Vec<ScriptStatus>, Error> {
+        // Notifications are only read off the socket alongside a response
+        self.client.ping()?;
+        let mut statuses = Vec::new();
+        while let Some(status) = self.client.script_pop(script)? {
+            statuses.push(status);
+        }
+        Ok(statuses)
+    }
+
+    fn history(&self, script: &Script) -> Result<Vec<ScriptHistoryItem>, Error> {
+        self.get_script_history(script)
+    }
+}
+
+/// `height` as a tip height, or `None` when it can't belong to `header`:
+/// beyond what a block height can be, or at 0 for a header that isn't a
+/// genesis block (and the other way round).
+fn plausible_tip_height(height: usize, header: &bitcoin::block::Header) -> Option<u32> {
+    use bitcoin::hashes::Hash;
+
+    let height = u32::try_from(height).ok()?;
+    let is_genesis = header.prev_blockhash == BlockHash::all_zeros();
+    ((height == 0) == is_genesis).then_some(height)
+}
+
+/// Highest height in `[low, high)` for which `exists` holds, given that it
+/// holds at `low`, fails at `high`, and is monotone in between.
+fn bisect_height(
+    mut low: u32,
+    mut high: u32,
+    exists: impl Fn(u32) -> Result<bool, Error>,
+) -> Result<u32, Error> {
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if exists(mid)? {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+/// Check the server accepts a TCP connection within `connect_timeout`.
+///
+/// electrum-client applies one timeout to both connecting and requests, so
+/// the connect deadline is enforced separately here.
+fn check_reachable(url: &str, connect_timeout: Duration) -> Result<(), Error> {
+    let host_port = url.split("://").nth(1).unwrap_or(url);
+    let addrs: Vec<_> = host_port
+        .to_socket_addrs()
+        .map_err(|e| Error::Connection(format!("cannot resolve {}: {}", host_port, e)))?
+        .collect();
+
+    let mut last_err = None;
+    for addr in &addrs {
+        match TcpStream::connect_timeout(addr, connect_timeout) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    match last_err {
+        Some(e) if e.kind() == std::io::ErrorKind::TimedOut => Err(Error::Timeout(format!(
+            "connecting to {} took longer than {:?}",
+            host_port, connect_timeout
+        ))),
+        Some(e) => Err(Error::Connection(format!("{}: {}", host_port, e))),
+        None => Err(Error::Connection(format!("no addresses for {}", host_port))),
+    }
+}
+
+/// Compare a server's genesis block hash against the one expected for `network`
+fn check_genesis(
+    network: Network,
+    expected: BlockHash,
+    server_genesis: BlockHash,
+) -> Result<(), String> {
+    if server_genesis == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "server is not on {} (genesis {} != expected {})",
+            network, server_genesis, expected
+        ))
+    }
+}
+
+/// Parameters of a custom (private) signet.
+///
+/// Addresses are the same on every signet, so only the server check differs.
+/// The challenge identifies the signet, but Electrum serves no block
+/// signatures to check it against: it is validated as a script when parsed,
+/// and servers are checked by genesis. Signets that don't share the default
+/// signet genesis block override it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomSignet {
+    /// The block-signing challenge script
+    pub challenge: ScriptBuf,
+    /// Genesis block hash, when it differs from the default signet's
+    pub genesis: Option<BlockHash>,
+}
+
+impl CustomSignet {
+    /// Parse a hex challenge script and optional genesis block hash.
+    pub fn from_hex(challenge_hex: &str, genesis: Option<&str>) -> Result<Self, Error> {
+        use std::str::FromStr;
+
+        let challenge = ScriptBuf::from_hex(challenge_hex.trim())
+            .map_err(|e| Error::InvalidSignet(format!("challenge is not hex: {}", e)))?;
+        if challenge.is_empty() {
+            return Err(Error::InvalidSignet("challenge is empty".into()));
+        }
+        if let Some(Err(e)) = challenge.instructions().find(Result::is_err) {
+            return Err(Error::InvalidSignet(format!(
+                "challenge is not a valid script: {}",
+                e
+            )));
+        }
+        let genesis = genesis
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(|g| {
+                BlockHash::from_str(g)
+                    .map_err(|e| Error::InvalidSignet(format!("invalid genesis hash: {}", e)))
+            })
+            .transpose()?;
+        Ok(Self { challenge, genesis })
+    }
+
+    /// Genesis block hash Electrum servers for this signet must report
+    pub fn genesis_hash(&self) -> BlockHash {
+        self.genesis
+            .unwrap_or_else(|| genesis_block(Network::Signet).block_hash())
+    }
+}
+
+/// Well-known public Electrum servers for each network, preferred first
 ///
 /// Note: Blockstream uses non-standard ports:
 /// - Mainnet SSL: 700
 /// - Testnet SSL: 993 (or 143 TCP)
 /// - Liquid: 995 (or 195 TCP)
-pub fn default_server(network: Network) -> &'static str {
+///
+/// Regtest lists the usual local ports of Electrum Personal Server /
+/// Fulcrum (50001) and electrs (60401).
+pub fn default_servers(network: Network) -> Vec<&'static str> {
     match network {
-        // Blockstream mainnet on port 700 (SSL) or 110 (TCP)
-        Network::Bitcoin => "ssl://blockstream.info:700",
-        // Alternative: "tcp://electrum.blockstream.info:50001"
-        Network::Testnet => "ssl://blockstream.info:993",
-        Network::Signet => "ssl://mempool.space:60602",
-        Network::Regtest => "tcp://127.0.0.1:50001",
-        _ => "ssl://blockstream.info:700",
+        Network::Testnet => vec![
+            "ssl://blockstream.info:993",
+            "ssl://electrum.blockstream.info:60002",
+            "ssl://testnet.aranguren.org:51002",
+        ],
+        Network::Signet => vec![
+            "ssl://mempool.space:60602",
+            "ssl://signet-electrumx.wakiyamap.dev:50002",
+        ],
+        Network::Regtest => vec!["tcp://127.0.0.1:50001", "tcp://127.0.0.1:60401"],
+        // Mainnet, and the fallback for any future network variant
+        _ => vec![
+            "ssl://blockstream.info:700",
+            "ssl://electrum.emzy.de:50002",
+            "ssl://electrum.bitaroo.net:50002",
+            "ssl://fortress.qtornado.com:443",
+        ],
+    }
+}
+
+/// The preferred default Electrum server for a network
+pub fn default_server(network: Network) -> &'static str {
+    default_servers(network)[0]
+}
+
+/// Electrum servers used in rotation.
+///
+/// Each [`connect`](Self::connect) starts one server further along the
+/// list than the last and fails over to the rest, so no single server is
+/// a point of failure and load is spread across all of them.
+#[derive(Debug)]
+pub struct ServerPool {
+    servers: Vec<String>,
+    next: AtomicUsize,
+}
+
+impl ServerPool {
+    pub fn new(servers: Vec<String>) -> Self {
+        Self {
+            servers,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pool of every default server for `network`
+    pub fn for_network(network: Network) -> Self {
+        Self::new(
+            default_servers(network)
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    pub fn servers(&self) -> &[String] {
+        &self.servers
+    }
+
+    /// Servers in the order the next connection attempt will try them
+    fn rotation(&self) -> Vec<&str> {
+        if self.servers.is_empty() {
+            return Vec::new();
+        }
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.servers.len();
+        self.servers[start..]
+            .iter()
+            .chain(&self.servers[..start])
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Connect to the next server in rotation, failing over to the others.
+    ///
+    /// Returns the last server's error if none could be reached.
+    pub fn connect(&self, network: Network) -> Result<ElectrumClient, Error> {
+        self.connect_expecting(network, None)
+    }
+
+    /// Like [`connect`](Self::connect), but servers must report `genesis`
+    /// (when given) instead of the network's standard genesis block.
+    pub fn connect_expecting(
+        &self,
+        network: Network,
+        genesis: Option<BlockHash>,
+    ) -> Result<ElectrumClient, Error> {
+        let mut last_err = Error::Connection("No Electrum servers configured".into());
+        for url in self.rotation() {
+            match ElectrumClient::new(url, network) {
+                Ok(client) => {
+                    return Ok(match genesis {
+                        Some(genesis) => client.with_expected_genesis(genesis),
+                        None => client,
+                    })
+                }
+                Err(e) => {
+                    log::warn!("Electrum server {} unavailable: {}", url, e);
+                    last_err = e;
+                }
+            }
+        }
+        Err(last_err)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::hashes::Hash;
 
     #[test]
     fn test_fee_conversion_math() {
@@ -348,11 +1088,284 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_already_broadcast_errors_are_success() {
+        let txid = Txid::from_byte_array([7; 32]);
+
+        for msg in [
+            r#"Electrum server error: {"code":-27,"message":"Transaction already in block chain"}"#,
+            r#"Electrum server error: {"code":-26,"message":"txn-already-known"}"#,
+            "the transaction was rejected by network rules.\n\ntxn-already-in-mempool",
+        ] {
+            assert_eq!(
+                broadcast_error_outcome(txid, msg).unwrap(),
+                BroadcastOutcome::AlreadyKnown(txid),
+                "{}",
+                msg
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_broadcast_errors_still_fail() {
+        let txid = Txid::from_byte_array([7; 32]);
+
+        for msg in [
+            "bad-txns-inputs-missingorspent",
+            "min relay fee not met",
+            "non-mandatory-script-verify-flag (Locktime requirement not satisfied)",
+        ] {
+            assert!(matches!(
+                broadcast_error_outcome(txid, msg),
+                Err(Error::BroadcastFailed(_))
+            ));
+        }
+        assert_eq!(BroadcastOutcome::Accepted(txid).txid(), txid);
+    }
+
+    #[test]
+    fn test_check_genesis() {
+        let mainnet = genesis_block(Network::Bitcoin).block_hash();
+        let testnet = genesis_block(Network::Testnet).block_hash();
+
+        assert!(check_genesis(Network::Bitcoin, mainnet, mainnet).is_ok());
+        assert!(check_genesis(Network::Testnet, testnet, testnet).is_ok());
+
+        let err = check_genesis(Network::Testnet, testnet, mainnet).unwrap_err();
+        assert!(err.contains("testnet"), "{}", err);
+    }
+
+    #[test]
+    fn test_custom_signet_genesis() {
+        // The 1-of-1 challenge of the default signet
+        let challenge = "512103ad5e0edad18cb1f0fc0d28a3d4f1f3e445640337489abb10404f2d1e086be430210359ef5021964fe22d6f8e05b2463c9540ce96883fe3b278760f048f5189f2e6c452ae";
+        let default_signet = genesis_block(Network::Signet).block_hash();
+        let custom_genesis = BlockHash::from_byte_array([0x42; 32]);
+
+        let shared = CustomSignet::from_hex(challenge, None).unwrap();
+        assert_eq!(shared.genesis_hash(), default_signet);
+
+        let custom = CustomSignet::from_hex(challenge, Some(&custom_genesis.to_string())).unwrap();
+        assert_eq!(custom.genesis_hash(), custom_genesis);
+        assert!(check_genesis(Network::Signet, custom.genesis_hash(), custom_genesis).is_ok());
+        let err =
+            check_genesis(Network::Signet, custom.genesis_hash(), default_signet).unwrap_err();
+        assert!(err.contains("signet"), "{}", err);
+
+        assert!(CustomSignet::from_hex("zz", None).is_err());
+        assert!(CustomSignet::from_hex("", None).is_err());
+        // PUSHDATA1 announcing more bytes than follow
+        let err = CustomSignet::from_hex("4c05ab", None).unwrap_err();
+        assert!(err.to_string().contains("not a valid script"), "{}", err);
+        // A trivial OP_TRUE challenge is valid (anyone can mine)
+        assert!(CustomSignet::from_hex("51", None).is_ok());
+        assert!(CustomSignet::from_hex(challenge, Some("not-a-hash")).is_err());
+    }
+
+    #[test]
+    #[ignore = "requires network access"]
+    fn test_network_mismatch_rejected() {
+        // Mainnet server, testnet client: every call must fail the genesis check
+        let client =
+            ElectrumClient::new(default_server(Network::Bitcoin), Network::Testnet).unwrap();
+
+        match client.get_height() {
+            Err(Error::Connection(msg)) => println!("✓ Rejected: {}", msg),
+            other => panic!("Expected Error::Connection, got {:?}", other),
+        }
+        // The cached result keeps rejecting without another round trip
+        assert!(matches!(client.get_height(), Err(Error::Connection(_))));
+    }
+
+    #[test]
+    #[ignore = "requires a network that silently drops packets to TEST-NET-1"]
+    fn test_unreachable_host_times_out() {
+        // 192.0.2.0/24 is reserved for documentation and never answers
+        let start = std::time::Instant::now();
+        let result = ElectrumClient::with_timeouts(
+            "tcp://192.0.2.1:50001",
+            Network::Bitcoin,
+            Duration::from_secs(2),
+            DEFAULT_REQUEST_TIMEOUT,
+        );
+
+        assert!(
+            matches!(result, Err(Error::Timeout(_))),
+            "expected Timeout, got {:?}",
+            result.err()
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_protocol_version_parse() {
+        let v: ProtocolVersion = "1.4".parse().unwrap();
+        assert_eq!(v, ProtocolVersion::new(1, 4, 0));
+        assert_eq!(v.to_string(), "1.4");
+
+        let v: ProtocolVersion = "1.4.2".parse().unwrap();
+        assert!(v > ProtocolVersion::new(1, 4, 0));
+        assert_eq!(v.to_string(), "1.4.2");
+
+        for bad in ["", "1", "1.x", "1.4.2.1", "v1.4"] {
+            assert!(bad.parse::<ProtocolVersion>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_server_features_protocol_range() {
+        let features = ServerFeatures {
+            server_version: "ElectrumX 1.16.0".into(),
+            protocol_min: ProtocolVersion::new(1, 4, 0),
+            protocol_max: ProtocolVersion::new(1, 4, 2),
+            genesis_hash: genesis_block(Network::Bitcoin).block_hash(),
+            pruning: None,
+            hash_function: Some("sha256".into()),
+        };
+        assert!(features.supports_protocol(ProtocolVersion::new(1, 4, 0)));
+        assert!(features.supports_protocol(ProtocolVersion::new(1, 4, 1)));
+        assert!(!features.supports_protocol(ProtocolVersion::new(1, 2, 0)));
+        assert!(!features.supports_protocol(ProtocolVersion::new(1, 5, 0)));
+        assert!(features.has_full_history());
+    }
+
+    #[test]
+    #[ignore = "requires network access"]
+    fn test_server_features_mainnet() {
+        let client = ElectrumClient::new(default_server(Network::Bitcoin), Network::Bitcoin)
+            .expect("connect");
+
+        let features = client.server_features().unwrap();
+        assert!(features.protocol_min <= features.protocol_max);
+        assert!(features.protocol_max >= ProtocolVersion::new(1, 4, 0));
+        assert_eq!(
+            features.genesis_hash,
+            genesis_block(Network::Bitcoin).block_hash()
+        );
+        assert!(client.supports_batching());
+    }
+
+    #[test]
+    fn test_bisect_height() {
+        let tip = 881_234;
+        assert_eq!(bisect_height(0, 1 << 20, |h| Ok(h <= tip)).unwrap(), tip);
+        assert_eq!(bisect_height(0, 1, |h| Ok(h == 0)).unwrap(), 0);
+        assert_eq!(bisect_height(4, 8, |h| Ok(h <= 7)).unwrap(), 7);
+
+        // A failed probe aborts the search instead of lowering the tip
+        let flaky = |h: u32| {
+            if h == 1 << 19 {
+                Err(Error::Timeout("timed out".into()))
+            } else {
+                Ok(h <= tip)
+            }
+        };
+        assert!(matches!(
+            bisect_height(0, 1 << 20, flaky),
+            Err(Error::Timeout(_))
+        ));
+    }
+
+    #[test]
+    fn test_plausible_tip_height() {
+        let genesis = genesis_block(Network::Bitcoin).header;
+        let mut tip = genesis;
+        tip.prev_blockhash = genesis.block_hash();
+
+        assert_eq!(plausible_tip_height(881_234, &tip), Some(881_234));
+        assert_eq!(plausible_tip_height(0, &genesis), Some(0));
+
+        assert_eq!(plausible_tip_height(0, &tip), None);
+        assert_eq!(plausible_tip_height(5, &genesis), None);
+        assert_eq!(plausible_tip_height(u32::MAX as usize + 1, &tip), None);
+    }
+
+    #[test]
+    fn test_height_out_of_range_classified() {
+        let out_of_range = ElectrumError::Protocol("height 900000 out of range".into());
+        assert!(is_height_out_of_range(&out_of_range));
+
+        let other = ElectrumError::Protocol("excessive resource usage".into());
+        assert!(!is_height_out_of_range(&other));
+        let io = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        assert!(!is_height_out_of_range(&ElectrumError::IOError(io)));
+    }
+
+    #[test]
+    #[ignore = "requires network access"]
+    fn test_subscribed_height_matches_search() {
+        let client = ElectrumClient::new(default_server(Network::Bitcoin), Network::Bitcoin)
+            .expect("connect");
+
+        let subscribed = client.get_height().unwrap();
+        let searched = client.search_height().unwrap();
+
+        // A block may land between the two calls
+        assert!(
+            searched.abs_diff(subscribed) <= 2,
+            "subscribed {} vs searched {}",
+            subscribed,
+            searched
+        );
+    }
+
+    #[test]
+    fn test_io_timeout_classified() {
+        let io = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        assert!(matches!(
+            Error::from(ElectrumError::IOError(io)),
+            Error::Timeout(_)
+        ));
+
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        assert!(matches!(
+            Error::from(ElectrumError::IOError(io)),
+            Error::Protocol(_)
+        ));
+    }
+
     #[test]
     fn test_default_servers() {
         assert!(default_server(Network::Bitcoin).contains("blockstream"));
         assert!(default_server(Network::Bitcoin).contains("700"));
         assert!(default_server(Network::Testnet).contains("993"));
+
+        for network in [
+            Network::Bitcoin,
+            Network::Testnet,
+            Network::Signet,
+            Network::Regtest,
+        ] {
+            let servers = default_servers(network);
+            assert!(servers.len() >= 2, "{:?} has {:?}", network, servers);
+            assert_eq!(servers[0], default_server(network));
+        }
+        assert!(default_servers(Network::Bitcoin)
+            .iter()
+            .any(|s| s.contains("blockstream")));
+    }
+
+    #[test]
+    fn test_server_pool_round_robin() {
+        let pool = ServerPool::new(vec!["a".into(), "b".into(), "c".into()]);
+        assert_eq!(pool.rotation(), vec!["a", "b", "c"]);
+        assert_eq!(pool.rotation(), vec!["b", "c", "a"]);
+        assert_eq!(pool.rotation(), vec!["c", "a", "b"]);
+        assert_eq!(pool.rotation(), vec!["a", "b", "c"]);
+
+        assert!(ServerPool::new(Vec::new()).rotation().is_empty());
+        assert!(matches!(
+            ServerPool::new(Vec::new()).connect(Network::Regtest),
+            Err(Error::Connection(_))
+        ));
+    }
+
+    #[test]
+    fn test_server_pool_fails_over() {
+        // Nothing listens on port 1: every server fails, the error surfaces
+        let pool = ServerPool::new(vec!["tcp://127.0.0.1:1".into(), "tcp://127.0.0.1:1".into()]);
+        assert!(pool.connect(Network::Regtest).is_err());
+        assert_eq!(pool.servers().len(), 2);
     }
 
     // Integration tests require network access
@@ -375,8 +1388,8 @@ mod tests {
         let height = client.get_height().unwrap();
         println!("Current mainnet height: {}", height);
         assert!(
-            height > 930000 && height < 960000,
-            "Height {} is unexpected (expected 930k-960k)",
+            height > 930000,
+            "Height {} is unexpected (expected > 930k)",
             height
         );
         println!("✓ Block height valid");
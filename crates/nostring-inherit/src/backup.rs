@@ -5,6 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::policy::{PolicyError, Timelock};
 use crate::taproot::{InheritError, InheritableVault};
 
 /// Serializable vault descriptor backup.
@@ -24,8 +25,13 @@ pub struct VaultBackup {
     pub chain_code: String,
     /// BIP-32 derivation index for this vault
     pub address_index: u32,
-    /// Timelock in blocks
+    /// Timelock in blocks, for display. Only approximate for a time-based
+    /// lock: the vault is rebuilt from `timelock`.
     pub timelock_blocks: u16,
+    /// Exact `older()` value of the heir timelock, block- or time-based.
+    /// Absent in backups written before time-based locks (all block-based).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timelock: Option<u32>,
     /// Threshold required for multi-heir claim (e.g., 2 of 3).
     /// For single heir, this is 1. For n-of-n, equals heirs.len().
     pub threshold: usize,
@@ -53,8 +59,11 @@ pub struct RecoveryLeaf {
     pub script_hex: String,
     /// Taproot control block for this leaf (hex)
     pub control_block_hex: String,
-    /// CSV timelock value for this spending path
+    /// CSV timelock of this spending path in blocks, for display
     pub timelock_blocks: u16,
+    /// Exact `older()` value of this spending path's timelock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timelock: Option<u32>,
     /// Tapscript leaf version (0xc0)
     pub leaf_version: u8,
 }
@@ -89,6 +98,7 @@ pub fn extract_recovery_leaves(vault: &InheritableVault) -> Vec<RecoveryLeaf> {
                 script_hex: hex::encode(script.as_bytes()),
                 control_block_hex: hex::encode(cb.serialize()),
                 timelock_blocks: timelock.blocks(),
+                timelock: Some(u32::from(*timelock)),
                 leaf_version: LeafVersion::TapScript.to_consensus(),
             })
         })
@@ -96,6 +106,15 @@ pub fn extract_recovery_leaves(vault: &InheritableVault) -> Vec<RecoveryLeaf> {
 }
 
 impl VaultBackup {
+    /// The heir timelock: exact from `timelock`, or from `timelock_blocks`
+    /// for backups that predate it.
+    pub fn timelock(&self) -> Result<Timelock, PolicyError> {
+        match self.timelock {
+            Some(value) => Timelock::from_older(value),
+            None => Timelock::from_blocks(self.timelock_blocks),
+        }
+    }
+
     /// Reconstruct an InheritableVault from the backup data and verify the address matches.
     ///
     /// This proves the backup is valid — the vault_address in the backup must match
@@ -173,7 +192,8 @@ impl VaultBackup {
             crate::policy::PathInfo::Multi(threshold, descs)
         };
 
-        let timelock = crate::policy::Timelock::from_blocks(self.timelock_blocks)
+        let timelock = self
+            .timelock()
             .map_err(|e| InheritError::Backup(format!("invalid timelock: {}", e)))?;
 
         // Parse network
@@ -228,6 +248,7 @@ mod tests {
             chain_code: "ab".repeat(32),
             address_index: 0,
             timelock_blocks: 26280,
+            timelock: None,
             threshold: 1,
             heirs: vec![HeirBackupEntry {
                 label: "Alice".into(),
@@ -353,6 +374,43 @@ mod tests {
         assert_eq!(reconstructed.address.to_string(), vault.address.to_string());
     }
 
+    #[test]
+    fn test_reconstruct_time_based() {
+        use crate::test_utils::make_test_vault_with;
+
+        let timelock = Timelock::from_seconds(180 * 86_400).unwrap();
+        let vault = make_test_vault_with(timelock);
+        let (_, cosigner_pk) = crate::test_utils::test_keypair(2);
+        let (_, heir_pk) = crate::test_utils::test_keypair(3);
+        let heir_xpub = bitcoin::bip32::Xpub {
+            network: bitcoin::NetworkKind::Test,
+            depth: 0,
+            parent_fingerprint: Default::default(),
+            child_number: bitcoin::bip32::ChildNumber::from_normal_idx(0).unwrap(),
+            public_key: heir_pk,
+            chain_code: bitcoin::bip32::ChainCode::from([0u8; 32]),
+        };
+
+        let mut backup = sample_backup();
+        backup.network = "testnet".into();
+        backup.owner_pubkey = vault.owner_pubkey.to_string();
+        backup.cosigner_pubkey = cosigner_pk.to_string();
+        backup.chain_code = hex::encode(crate::test_utils::test_chain_code().0);
+        backup.heirs[0].xpub = heir_xpub.to_string();
+        backup.vault_address = vault.address.to_string();
+        backup.timelock_blocks = timelock.blocks();
+        backup.timelock = Some(u32::from(timelock));
+
+        let json = serde_json::to_string(&backup).unwrap();
+        let restored: VaultBackup = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.timelock().unwrap(), timelock);
+        assert_eq!(restored.reconstruct().unwrap().address, vault.address);
+
+        // The block approximation alone rebuilds a different script
+        backup.timelock = None;
+        assert!(backup.reconstruct().is_err());
+    }
+
     #[test]
     fn test_reconstruct_invalid_owner_pubkey() {
         let mut backup = sample_backup();
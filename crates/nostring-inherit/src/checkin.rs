@@ -10,6 +10,11 @@
 //! 3. Owner creates a new UTXO with the same policy
 //! 4. The heir's timelock resets (since CSV is relative to UTXO age)
 //!
+//! The new UTXO normally lands on the address being spent. A
+//! [`CheckinDestination`] can instead rotate to the descriptor's next
+//! address, or sweep to an external address (which ends the protection
+//! unless it belongs to the same policy).
+//!
 //! # Optimization: Batching
 //!
 //! In practice, any transaction that spends the inheritance UTXO resets the
@@ -19,10 +24,13 @@
 use bitcoin::absolute::LockTime;
 use bitcoin::bip32::{ChildNumber, DerivationPath, Fingerprint};
 use bitcoin::psbt::Psbt;
+use bitcoin::relative;
 use bitcoin::secp256k1;
 use bitcoin::transaction::Version;
-use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
+use bitcoin::{Address, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
+use chrono::{DateTime, Duration, Utc};
 use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::psbt::PsbtInputExt;
 use miniscript::{Descriptor, ForEachKey};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -30,6 +38,8 @@ use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
+use crate::policy::{multisig_owner_branch, DescriptorKind, Timelock, TimelockUnit};
+
 #[derive(Error, Debug)]
 pub enum CheckinError {
     #[error("No UTXO to check in")]
@@ -41,10 +51,48 @@ pub enum CheckinError {
     #[error("PSBT creation failed: {0}")]
     PsbtError(String),
 
+    #[error("Fee rate {fee_rate} sat/vB is below the {min} sat/vB relay minimum")]
+    FeeTooLow { fee_rate: u64, min: u64 },
+
+    #[error("Check-in output of {0} is below the dust limit")]
+    DustOutput(Amount),
+
+    #[error("Derivation index {0} has no next index to rotate to")]
+    NoNextIndex(u32),
+
     #[error("Policy error: {0}")]
     PolicyError(#[from] crate::policy::PolicyError),
 }
 
+/// Relative-timelock pre-flight failures
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TimelockError {
+    #[error("Timelock not yet satisfied: {blocks_remaining} more block(s) needed")]
+    NotYetSpendable { blocks_remaining: u32 },
+
+    #[error("Input {input} locks {sequence_blocks} blocks but the script requires {required}")]
+    SequenceTooShort {
+        input: usize,
+        sequence_blocks: u16,
+        required: u32,
+    },
+
+    #[error("PSBT input {0} is missing its witness UTXO")]
+    MissingWitnessUtxo(usize),
+
+    #[error("{0}")]
+    Lookup(String),
+}
+
+/// A confirmed block, by height and header timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainPoint {
+    /// Block height
+    pub height: u32,
+    /// Block timestamp (unix seconds)
+    pub time: u64,
+}
+
 /// Status of the inheritance timelock
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimelockStatus {
@@ -79,6 +127,42 @@ impl TimelockStatus {
         }
     }
 
+    /// Calculate status of a time-based timelock from block timestamps
+    ///
+    /// Consensus measures these against median-time-past, which trails the
+    /// header timestamps by about an hour at both ends. Block counts are
+    /// estimated at ~10 minutes per block.
+    pub fn calculate_time_based(
+        current: ChainPoint,
+        utxo: ChainPoint,
+        timelock_seconds: u32,
+    ) -> Self {
+        let unlock_time = utxo.time + timelock_seconds as u64;
+        let seconds_remaining = unlock_time as i64 - current.time as i64;
+        let blocks_remaining = if seconds_remaining > 0 {
+            (seconds_remaining + 599) / 600
+        } else {
+            seconds_remaining / 600
+        };
+
+        Self {
+            current_height: current.height,
+            utxo_height: utxo.height,
+            unlock_height: (current.height as i64 + blocks_remaining.max(0)) as u32,
+            blocks_remaining: blocks_remaining as i32,
+            seconds_remaining,
+            expired: seconds_remaining <= 0,
+        }
+    }
+
+    /// Status of `timelock`, in whichever unit it counts
+    pub fn for_timelock(timelock: &Timelock, current: ChainPoint, utxo: ChainPoint) -> Self {
+        match timelock.unit() {
+            TimelockUnit::Blocks => Self::calculate(current.height, utxo.height, timelock.value()),
+            TimelockUnit::Time => Self::calculate_time_based(current, utxo, timelock.seconds()),
+        }
+    }
+
     /// Human-readable time remaining
     pub fn time_remaining_display(&self) -> String {
         if self.expired {
@@ -117,6 +201,247 @@ impl TimelockStatus {
     }
 }
 
+/// Largest height-based relative lock (BIP-68) among a PSBT's inputs.
+///
+/// Returns the input index and lock in blocks, or `None` when no input
+/// carries one (e.g. an owner check-in, which doesn't need the CSV path).
+pub fn relative_lock_blocks(psbt: &Psbt) -> Option<(usize, u16)> {
+    psbt.unsigned_tx
+        .input
+        .iter()
+        .enumerate()
+        .filter_map(|(i, txin)| match txin.sequence.to_relative_lock_time()? {
+            relative::LockTime::Blocks(height) if height.value() > 0 => Some((i, height.value())),
+            _ => None,
+        })
+        .max_by_key(|(_, blocks)| *blocks)
+}
+
+/// Pre-flight check that a PSBT's relative timelock is met at `current_height`.
+///
+/// A CSV-locked input can be mined in the first block at least
+/// `timelock_blocks` after `utxo_confirm_height`; mempools check against
+/// the next block, so the spend is broadcastable once
+/// `current_height + 1 >= utxo_confirm_height + timelock_blocks`. An
+/// unconfirmed UTXO (`utxo_confirm_height == 0`) hasn't started its lock.
+///
+/// PSBTs without a height-based relative lock pass unchanged.
+pub fn check_timelock_satisfied(
+    psbt: &Psbt,
+    current_height: u32,
+    utxo_confirm_height: u32,
+    timelock_blocks: u32,
+) -> Result<(), TimelockError> {
+    if relative_lock_blocks(psbt).is_none() {
+        return Ok(());
+    }
+
+    for (input, txin) in psbt.unsigned_tx.input.iter().enumerate() {
+        if let Some(relative::LockTime::Blocks(height)) = txin.sequence.to_relative_lock_time() {
+            if height.value() > 0 && (height.value() as u32) < timelock_blocks {
+                return Err(TimelockError::SequenceTooShort {
+                    input,
+                    sequence_blocks: height.value(),
+                    required: timelock_blocks,
+                });
+            }
+        }
+    }
+
+    if utxo_confirm_height == 0 {
+        return Err(TimelockError::NotYetSpendable {
+            blocks_remaining: timelock_blocks,
+        });
+    }
+
+    let unlock_height = utxo_confirm_height.saturating_add(timelock_blocks);
+    let next_block = current_height.saturating_add(1);
+    if next_block < unlock_height {
+        return Err(TimelockError::NotYetSpendable {
+            blocks_remaining: unlock_height - next_block,
+        });
+    }
+    Ok(())
+}
+
+/// Refuse to broadcast a CSV-locked (heir path) spend before it matures.
+///
+/// `current_height` fetches the chain tip and `input_height` the
+/// confirmation height of an input's transaction in its script's history
+/// (`None` or `Some(0)` while unconfirmed); both only run for a PSBT with a
+/// relative lock. The most recently confirmed input decides, and any
+/// unconfirmed one blocks. Without a known policy (`timelock_blocks` of
+/// `None`) the PSBT's own sequence lock is the requirement.
+pub fn check_psbt_timelock(
+    psbt: &Psbt,
+    timelock_blocks: Option<u32>,
+    current_height: impl FnOnce() -> Result<u32, String>,
+    mut input_height: impl FnMut(&bitcoin::Txid, &bitcoin::Script) -> Result<Option<u32>, String>,
+) -> Result<(), TimelockError> {
+    let Some((_, lock)) = relative_lock_blocks(psbt) else {
+        return Ok(());
+    };
+    let current_height = current_height().map_err(TimelockError::Lookup)?;
+
+    let mut heights = Vec::with_capacity(psbt.inputs.len());
+    for (i, (txin, input)) in psbt.unsigned_tx.input.iter().zip(&psbt.inputs).enumerate() {
+        let utxo = input
+            .witness_utxo
+            .as_ref()
+            .ok_or(TimelockError::MissingWitnessUtxo(i))?;
+        let height = input_height(&txin.previous_output.txid, &utxo.script_pubkey)
+            .map_err(TimelockError::Lookup)?;
+        heights.push(height.unwrap_or(0));
+    }
+    let utxo_height = if heights.contains(&0) {
+        0
+    } else {
+        heights.into_iter().max().unwrap_or(0)
+    };
+
+    check_timelock_satisfied(
+        psbt,
+        current_height,
+        utxo_height,
+        timelock_blocks.unwrap_or(lock as u32),
+    )
+}
+
+/// Nominal Bitcoin block interval in seconds
+pub const TARGET_BLOCK_SECS: f64 = 600.0;
+
+/// Average block interval in seconds from consecutive header timestamps.
+///
+/// `recent_block_times` are header timestamps ordered oldest to newest.
+/// Returns `None` if fewer than two are given or they don't advance.
+pub fn average_block_interval(recent_block_times: &[u32]) -> Option<f64> {
+    let (first, last) = (recent_block_times.first()?, recent_block_times.last()?);
+    let intervals = recent_block_times.len().checked_sub(1).filter(|n| *n > 0)?;
+    if last <= first {
+        return None;
+    }
+    Some((last - first) as f64 / intervals as f64)
+}
+
+/// Estimate the calendar date at which `expiry_height` is reached.
+///
+/// Calibrates the block interval from `recent_block_times` (header timestamps,
+/// oldest first) and projects forward from the newest header's time. Falls
+/// back to 600-second blocks from now when no usable timestamps are given.
+/// An `expiry_height` at or below `current_height` yields a date in the past.
+pub fn estimate_expiry_date(
+    current_height: u32,
+    expiry_height: u32,
+    recent_block_times: &[u32],
+) -> DateTime<Utc> {
+    let blocks_remaining = expiry_height as i64 - current_height as i64;
+
+    let (base, interval) = match (
+        average_block_interval(recent_block_times),
+        recent_block_times.last(),
+    ) {
+        (Some(interval), Some(&tip_time)) => (
+            DateTime::from_timestamp(tip_time as i64, 0).unwrap_or_else(Utc::now),
+            interval,
+        ),
+        _ => (Utc::now(), TARGET_BLOCK_SECS),
+    };
+
+    let offset_secs = (blocks_remaining as f64 * interval).round() as i64;
+    base + Duration::seconds(offset_secs)
+}
+
+/// Approximate number of blocks from now until `target`, at 600 seconds per
+/// block. Negative if `target` is in the past.
+pub fn blocks_remaining_from_date(target: DateTime<Utc>) -> i64 {
+    let secs = (target - Utc::now()).num_seconds();
+    (secs as f64 / TARGET_BLOCK_SECS).round() as i64
+}
+
+/// Blocks in a day at the nominal 10-minute interval
+pub const BLOCKS_PER_DAY: u32 = 144;
+
+/// Blocks in a year at the nominal 10-minute interval
+pub const BLOCKS_PER_YEAR: u32 = 365 * BLOCKS_PER_DAY;
+
+/// How far a stack of pre-signed check-ins carries the timelock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckinRunway {
+    /// Blocks from now until the timelock expires once every check-in in
+    /// the stack has been broadcast
+    pub runway_blocks: u64,
+    /// Check-ins needed to keep the timelock alive for the whole horizon
+    pub checkins_needed: u32,
+    /// Whether the stack holds fewer than `checkins_needed`
+    pub needs_more: bool,
+}
+
+/// Estimate how long `active` pre-signed check-ins keep the heirs locked out.
+///
+/// Check-ins are auto-broadcast once `blocks_remaining` drops to
+/// `threshold_blocks`, and each one resets the lock to `timelock_blocks`.
+/// So the first fires in `blocks_remaining - threshold_blocks` blocks,
+/// every later one `timelock_blocks - threshold_blocks` after the previous,
+/// and the lock runs out `timelock_blocks` after the last.
+///
+/// A threshold at or above the timelock would fire every check-in straight
+/// after the previous one; it is treated as a one-block cycle.
+pub fn checkin_runway(
+    blocks_remaining: i64,
+    timelock_blocks: u32,
+    threshold_blocks: u32,
+    active: u32,
+    horizon_blocks: u32,
+) -> CheckinRunway {
+    let remaining = blocks_remaining.max(0) as u64;
+    let timelock = timelock_blocks as u64;
+    let first = remaining.saturating_sub(threshold_blocks as u64);
+    let cycle = timelock.saturating_sub(threshold_blocks as u64).max(1);
+    let horizon = horizon_blocks as u64;
+
+    let runway = |count: u64| match count {
+        0 => remaining,
+        n => first + (n - 1) * cycle + timelock,
+    };
+
+    let checkins_needed = if remaining >= horizon {
+        0
+    } else if first + timelock >= horizon {
+        1
+    } else {
+        1 + (horizon - first - timelock).div_ceil(cycle)
+    };
+    let checkins_needed = u32::try_from(checkins_needed).unwrap_or(u32::MAX);
+
+    CheckinRunway {
+        runway_blocks: runway(active as u64),
+        checkins_needed,
+        needs_more: active < checkins_needed,
+    }
+}
+
+/// Sequential check-ins to pre-sign so the stack covers `horizon_blocks`.
+///
+/// Plans from a freshly reset lock (the state right after a check-in
+/// confirms), with each check-in auto-broadcast at `threshold_blocks`
+/// remaining. A horizon inside one timelock still gets a single check-in,
+/// so there's always one ready to extend it.
+pub fn recommended_chain_length(
+    horizon_blocks: u32,
+    timelock_blocks: u32,
+    threshold_blocks: u32,
+) -> u32 {
+    checkin_runway(
+        timelock_blocks as i64,
+        timelock_blocks,
+        threshold_blocks,
+        0,
+        horizon_blocks,
+    )
+    .checkins_needed
+    .max(1)
+}
+
 /// Urgency level for check-in
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CheckinUrgency {
@@ -196,10 +521,75 @@ impl InheritanceUtxo {
     }
 }
 
+/// Consolidation above this share of the swept value is flagged as wasteful
+pub const DEFAULT_MAX_CONSOLIDATION_FEE_PERCENT: f64 = 5.0;
+
+/// Cost of a check-in transaction, before it is built
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidationEstimate {
+    /// UTXOs spent
+    pub input_count: usize,
+    /// Sum of all spent UTXOs
+    pub total_value: Amount,
+    /// Estimated fee at the builder's fee rate
+    pub fee: Amount,
+    /// Value of the single recreated inheritance output
+    pub output_value: Amount,
+}
+
+impl ConsolidationEstimate {
+    /// Fee as a percentage of the total value spent
+    pub fn fee_percent(&self) -> f64 {
+        if self.total_value == Amount::ZERO {
+            return 100.0;
+        }
+        self.fee.to_sat() as f64 * 100.0 / self.total_value.to_sat() as f64
+    }
+
+    /// Whether the fee eats more than `max_fee_percent` of the total
+    pub fn exceeds(&self, max_fee_percent: f64) -> bool {
+        self.fee_percent() > max_fee_percent
+    }
+}
+
+/// Addresses past the spent index searched when checking whether an
+/// external destination is still covered by the inheritance descriptor
+pub const DESTINATION_POLICY_GAP: u32 = 20;
+
+/// Fee rates above this (sat/vB) are almost certainly a typo; see
+/// [`CheckinTxBuilder::fee_rate_warning`]
+pub const FEE_RATE_WARN_CEILING: u64 = 1000;
+
+/// Lowest fee rate (sat/vB) a check-in may pay on `network`.
+///
+/// Nodes don't relay transactions below 1 sat/vB by default; regtest is
+/// left permissive for testing.
+pub fn min_fee_rate(network: bitcoin::Network) -> u64 {
+    match network {
+        bitcoin::Network::Regtest => 0,
+        _ => 1,
+    }
+}
+
+/// Where a check-in sends the recreated output
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CheckinDestination {
+    /// Recreate the UTXO at the address being spent
+    #[default]
+    SameAddress,
+    /// Rotate to the descriptor's next receive address (index + 1)
+    NextIndex,
+    /// Sweep to any address, e.g. cold storage
+    External(Address),
+}
+
 /// Builder for check-in transactions
 pub struct CheckinTxBuilder {
     /// The UTXO to spend
     utxo: InheritanceUtxo,
+    /// Further UTXOs swept into the check-in output, each with the
+    /// derivation index of the address holding it
+    consolidate: Vec<(InheritanceUtxo, u32)>,
     /// The descriptor for this UTXO
     descriptor: Descriptor<DescriptorPublicKey>,
     /// Fee rate in sat/vbyte
@@ -208,6 +598,10 @@ pub struct CheckinTxBuilder {
     derivation_index: u32,
     /// Optional additional outputs (e.g., if sending funds elsewhere)
     extra_outputs: Vec<TxOut>,
+    /// Where the check-in output goes
+    destination: CheckinDestination,
+    /// Network the transaction is for; sets the fee and dust floors
+    network: bitcoin::Network,
 }
 
 impl CheckinTxBuilder {
@@ -227,33 +621,209 @@ impl CheckinTxBuilder {
             descriptor,
             fee_rate,
             derivation_index,
+            consolidate: Vec::new(),
             extra_outputs: Vec::new(),
+            destination: CheckinDestination::SameAddress,
+            network: bitcoin::Network::Bitcoin,
         }
     }
 
+    /// Apply `network`'s fee-rate and dust floors (mainnet's by default)
+    pub fn with_network(mut self, network: bitcoin::Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Warning when the fee rate is above [`FEE_RATE_WARN_CEILING`].
+    ///
+    /// Not an error: a high fee may be intended when blocks are full, but
+    /// on an inheritance UTXO a misplaced digit is expensive.
+    pub fn fee_rate_warning(&self) -> Option<String> {
+        (self.fee_rate > FEE_RATE_WARN_CEILING).then(|| {
+            format!(
+                "Fee rate of {} sat/vB is unusually high (above {} sat/vB). \
+                 Double-check it before signing.",
+                self.fee_rate, FEE_RATE_WARN_CEILING
+            )
+        })
+    }
+
+    /// Send the check-in output to `destination` instead of the spent address
+    pub fn with_destination(mut self, destination: CheckinDestination) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Also spend `utxos` (at the same address and derivation index), so
+    /// every tracked UTXO ends up in the single recreated output
+    pub fn with_consolidation(self, utxos: Vec<InheritanceUtxo>) -> Self {
+        let index = self.derivation_index;
+        self.with_consolidation_at(index, utxos)
+    }
+
+    /// Also spend `utxos` held at the receive address of `derivation_index`,
+    /// e.g. funds left at an earlier address by rotating check-ins
+    pub fn with_consolidation_at(
+        mut self,
+        derivation_index: u32,
+        utxos: Vec<InheritanceUtxo>,
+    ) -> Self {
+        self.consolidate
+            .extend(utxos.into_iter().map(|utxo| (utxo, derivation_index)));
+        self
+    }
+
+    /// Every UTXO this check-in spends with its derivation index, primary
+    /// first
+    fn inputs(&self) -> impl Iterator<Item = (&InheritanceUtxo, u32)> {
+        std::iter::once((&self.utxo, self.derivation_index))
+            .chain(self.consolidate.iter().map(|(utxo, index)| (utxo, *index)))
+    }
+
+    /// Fee and output value of the transaction this builder would produce
+    pub fn estimate(&self) -> Result<ConsolidationEstimate, CheckinError> {
+        let fee = self.estimate_fee(self.kind()?);
+        let total_value: Amount = self.inputs().map(|(u, _)| u.value()).sum();
+        let extra_output_total: Amount = self.extra_outputs.iter().map(|o| o.value).sum();
+        Ok(ConsolidationEstimate {
+            input_count: 1 + self.consolidate.len(),
+            total_value,
+            fee,
+            output_value: total_value
+                .checked_sub(fee)
+                .and_then(|v| v.checked_sub(extra_output_total))
+                .unwrap_or(Amount::ZERO),
+        })
+    }
+
     /// Add an extra output (for payments during check-in)
     pub fn with_output(mut self, output: TxOut) -> Self {
         self.extra_outputs.push(output);
         self
     }
 
+    /// Receive-path (`<0;1>` → 0) single descriptor
+    fn receive_descriptor(&self) -> Result<Descriptor<DescriptorPublicKey>, CheckinError> {
+        self.descriptor
+            .clone()
+            .into_single_descriptors()
+            .map_err(|e| CheckinError::PsbtError(format!("descriptor split failed: {}", e)))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| CheckinError::PsbtError("empty descriptor list".to_string()))
+    }
+
+    /// Script of the receive address at `index`
+    fn script_at(&self, index: u32) -> Result<ScriptBuf, CheckinError> {
+        let derived = self
+            .receive_descriptor()?
+            .at_derivation_index(index)
+            .map_err(|e| CheckinError::PsbtError(format!("descriptor derivation failed: {}", e)))?;
+        Ok(derived.script_pubkey())
+    }
+
+    /// P2WSH witness script of the spent address
+    fn witness_script(&self) -> Result<ScriptBuf, CheckinError> {
+        self.witness_script_at(self.derivation_index)
+    }
+
+    /// P2WSH witness script of the receive address at `index`
+    fn witness_script_at(&self, index: u32) -> Result<ScriptBuf, CheckinError> {
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let derived = self
+            .receive_descriptor()?
+            .derived_descriptor(&secp, index)
+            .map_err(|e| CheckinError::PsbtError(format!("descriptor derivation failed: {}", e)))?;
+        derived.explicit_script().map_err(|e| {
+            CheckinError::PsbtError(format!("witness script extraction failed: {}", e))
+        })
+    }
+
+    /// Script the check-in output pays to
+    pub fn destination_script(&self) -> Result<ScriptBuf, CheckinError> {
+        match &self.destination {
+            CheckinDestination::SameAddress => Ok(self.utxo.script_pubkey()),
+            CheckinDestination::NextIndex => {
+                let next = self
+                    .derivation_index
+                    .checked_add(1)
+                    .ok_or(CheckinError::NoNextIndex(self.derivation_index))?;
+                self.script_at(next)
+            }
+            CheckinDestination::External(address) => Ok(address.script_pubkey()),
+        }
+    }
+
+    /// Warning when the destination leaves the inheritance policy.
+    ///
+    /// An external address is fine if it is one of the descriptor's own
+    /// receive addresses (up to [`DESTINATION_POLICY_GAP`] past the spent
+    /// index); anything else means heirs lose their claim on the funds.
+    pub fn destination_warning(&self) -> Option<String> {
+        let CheckinDestination::External(address) = &self.destination else {
+            return None;
+        };
+        let target = address.script_pubkey();
+        let covered = (0..=self.derivation_index.saturating_add(DESTINATION_POLICY_GAP))
+            .any(|i| self.script_at(i).is_ok_and(|spk| spk == target));
+        (!covered).then(|| {
+            format!(
+                "{} is not controlled by the inheritance policy. \
+                 Funds sent there are no longer claimable by heirs.",
+                address
+            )
+        })
+    }
+
+    /// Detect the descriptor's script type, rejecting unsupported kinds
+    fn kind(&self) -> Result<DescriptorKind, CheckinError> {
+        Ok(DescriptorKind::of(&self.descriptor)?)
+    }
+
     /// Calculate the fee for this transaction
-    fn estimate_fee(&self) -> Amount {
-        // Estimate vbytes based on P2WSH spend
-        // Input: ~138 vbytes for P2WSH multisig
-        // Output: ~43 vbytes for P2WSH
-        let input_vbytes = 138u64;
+    fn estimate_fee(&self, kind: DescriptorKind) -> Amount {
+        // Input: ~138 vbytes for a P2WSH script-path spend, ~58 vbytes for a
+        // Taproot key-path spend (single 64-byte Schnorr signature)
+        // Output: ~43 vbytes for either P2WSH or P2TR
+        let input_vbytes = match kind {
+            DescriptorKind::Wsh => self.multisig_owner_input_vbytes().unwrap_or(138),
+            DescriptorKind::Tr => 58u64,
+        };
+        let input_count = 1 + self.consolidate.len() as u64;
         let output_vbytes = 43u64 * (1 + self.extra_outputs.len() as u64);
         let overhead = 11u64; // version, locktime, counts
 
-        let total_vbytes = input_vbytes + output_vbytes + overhead;
+        let total_vbytes = input_vbytes * input_count + output_vbytes + overhead;
         Amount::from_sat(total_vbytes * self.fee_rate)
     }
 
+    /// Input vbytes of a P2WSH owner-path spend by a k-of-n owner: the
+    /// 41-byte input plus a witness holding the CHECKMULTISIG dummy, `k`
+    /// signatures, the branch selector when the owner sits under `or_i`,
+    /// and the witness script. `None` for a single-key owner.
+    fn multisig_owner_input_vbytes(&self) -> Option<u64> {
+        let witness_script = self.witness_script().ok()?;
+        let branch = multisig_owner_branch(&witness_script)?;
+        let k = branch.threshold as u64;
+        let selector = if branch.selector.is_some() { 2 } else { 0 };
+        // item count + dummy + k × (length + 72-byte DER sig + sighash)
+        // + selector + script
+        let witness = 1 + 1 + k * 74 + selector + 3 + witness_script.len() as u64;
+        Some(41 + witness.div_ceil(4))
+    }
+
     /// Build an unsigned transaction for the check-in
     pub fn build_unsigned_tx(&self) -> Result<Transaction, CheckinError> {
-        let fee = self.estimate_fee();
-        let utxo_value = self.utxo.value();
+        let min = min_fee_rate(self.network);
+        if self.fee_rate < min {
+            return Err(CheckinError::FeeTooLow {
+                fee_rate: self.fee_rate,
+                min,
+            });
+        }
+
+        let fee = self.estimate_fee(self.kind()?);
+        let utxo_value: Amount = self.inputs().map(|(u, _)| u.value()).sum();
 
         // Calculate change
         let extra_output_total: Amount = self.extra_outputs.iter().map(|o| o.value).sum();
@@ -265,22 +835,30 @@ impl CheckinTxBuilder {
                 available: utxo_value,
             })?;
 
+        let script_pubkey = self.destination_script()?;
+        if self.network != bitcoin::Network::Regtest && change < script_pubkey.minimal_non_dust() {
+            return Err(CheckinError::DustOutput(change));
+        }
+
         // Build transaction
         let mut outputs = self.extra_outputs.clone();
         outputs.push(TxOut {
             value: change,
-            script_pubkey: self.utxo.script_pubkey(), // Same address for check-in
+            script_pubkey,
         });
 
         let tx = Transaction {
             version: Version::TWO,
             lock_time: LockTime::ZERO,
-            input: vec![TxIn {
-                previous_output: self.utxo.outpoint(),
-                script_sig: ScriptBuf::new(), // Empty for SegWit
-                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
-                witness: Witness::default(),
-            }],
+            input: self
+                .inputs()
+                .map(|(utxo, _)| TxIn {
+                    previous_output: utxo.outpoint(),
+                    script_sig: ScriptBuf::new(), // Empty for SegWit
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
             output: outputs,
         };
 
@@ -298,7 +876,12 @@ impl CheckinTxBuilder {
     /// The PSBT can be exported to SeedSigner or other hardware wallets for signing.
     /// Populates BIP-174 `witness_utxo` and `witness_script` fields so hardware
     /// wallets can validate input amounts (prevents fee-manipulation attacks).
+    ///
+    /// For `tr()` descriptors the owner signs via the key path, so the input
+    /// instead carries the BIP-371 internal key, script leaves and Taproot
+    /// key origins.
     pub fn build_psbt(&self) -> Result<Psbt, CheckinError> {
+        let kind = self.kind()?;
         let tx = self.build_unsigned_tx()?;
 
         let mut psbt =
@@ -307,10 +890,12 @@ impl CheckinTxBuilder {
         // Populate witness_utxo: the TxOut being spent (amount + scriptPubKey).
         // Without this, hardware wallets cannot verify the input amount and
         // are vulnerable to fee-manipulation attacks (BIP-174 §input.witness_utxo).
-        psbt.inputs[0].witness_utxo = Some(TxOut {
-            value: self.utxo.value(),
-            script_pubkey: self.utxo.script_pubkey(),
-        });
+        for (input, (utxo, _)) in psbt.inputs.iter_mut().zip(self.inputs()) {
+            input.witness_utxo = Some(TxOut {
+                value: utxo.value(),
+                script_pubkey: utxo.script_pubkey(),
+            });
+        }
 
         // Populate witness_script: the redeemScript for P2WSH inputs.
         // For P2WSH, the scriptPubKey is OP_0 <32-byte-hash>, and the
@@ -324,94 +909,28 @@ impl CheckinTxBuilder {
 
         // For multi-path descriptors (<0;1>/*), split into single-path
         // descriptors and use the receive path (index 0).
-        let single_descs = self
-            .descriptor
-            .clone()
-            .into_single_descriptors()
-            .map_err(|e| CheckinError::PsbtError(format!("descriptor split failed: {}", e)))?;
-        let receive_desc = single_descs
-            .into_iter()
-            .next()
-            .ok_or_else(|| CheckinError::PsbtError("empty descriptor list".to_string()))?;
-
-        let derived = receive_desc
-            .derived_descriptor(&secp, self.derivation_index)
-            .map_err(|e| CheckinError::PsbtError(format!("descriptor derivation failed: {}", e)))?;
-
-        let witness_script = derived.explicit_script().map_err(|e| {
-            CheckinError::PsbtError(format!("witness script extraction failed: {}", e))
-        })?;
-
-        psbt.inputs[0].witness_script = Some(witness_script);
-
-        // Populate BIP-32 derivation paths (BIP-174 PSBT_IN_BIP32_DERIVATION).
-        // This tells hardware wallets which HD key path to use for signing.
-        // For each key in the descriptor, we map the derived public key to
-        // its (master_fingerprint, full_derivation_path).
-        let mut bip32_derivation: BTreeMap<secp256k1::PublicKey, (Fingerprint, DerivationPath)> =
-            BTreeMap::new();
-
-        receive_desc.for_each_key(|key| {
-            if let DescriptorPublicKey::XPub(ref xkey) = key {
-                if let Some((fingerprint, base_path)) = &xkey.origin {
-                    // Derive the child pubkey at our derivation index
-                    if let Ok(child_xpub) = xkey.xkey.derive_pub(
-                        &secp,
-                        &[ChildNumber::Normal {
-                            index: self.derivation_index,
-                        }],
-                    ) {
-                        let pubkey = child_xpub.public_key;
-
-                        // Full path = origin path + xpub derivation path + child index
-                        // e.g., [fingerprint/84'/0'/0']xpub/0/* at index 5 →
-                        //        m/84'/0'/0'/0/5
-                        let mut full_path: Vec<ChildNumber> = base_path.as_ref().to_vec();
-                        for step in xkey.derivation_path.as_ref() {
-                            full_path.push(*step);
-                        }
-                        full_path.push(ChildNumber::Normal {
-                            index: self.derivation_index,
-                        });
-
-                        bip32_derivation
-                            .insert(pubkey, (*fingerprint, DerivationPath::from(full_path)));
-                    }
-                }
-            } else if let DescriptorPublicKey::MultiXPub(ref xkey) = key {
-                if let Some((fingerprint, base_path)) = &xkey.origin {
-                    // For multi-path xpubs (<0;1>/*), use path index 0 (receive)
-                    if let Some(first_path) = xkey.derivation_paths.paths().first() {
-                        if let Ok(child_xpub) = xkey.xkey.derive_pub(&secp, first_path) {
-                            if let Ok(final_xpub) = child_xpub.derive_pub(
-                                &secp,
-                                &[ChildNumber::Normal {
-                                    index: self.derivation_index,
-                                }],
-                            ) {
-                                let pubkey = final_xpub.public_key;
-
-                                let mut full_path: Vec<ChildNumber> = base_path.as_ref().to_vec();
-                                for step in first_path.as_ref() {
-                                    full_path.push(*step);
-                                }
-                                full_path.push(ChildNumber::Normal {
-                                    index: self.derivation_index,
-                                });
-
-                                bip32_derivation.insert(
-                                    pubkey,
-                                    (*fingerprint, DerivationPath::from(full_path)),
-                                );
-                            }
-                        }
-                    }
-                }
+        let receive_desc = self.receive_descriptor()?;
+
+        if kind == DescriptorKind::Tr {
+            for (input, (_, index)) in psbt.inputs.iter_mut().zip(self.inputs()) {
+                let definite = receive_desc.at_derivation_index(index).map_err(|e| {
+                    CheckinError::PsbtError(format!("descriptor derivation failed: {}", e))
+                })?;
+                input
+                    .update_with_descriptor_unchecked(&definite)
+                    .map_err(|e| {
+                        CheckinError::PsbtError(format!("taproot input population failed: {}", e))
+                    })?;
             }
-            true // continue iterating
-        });
+            return Ok(psbt);
+        }
 
-        psbt.inputs[0].bip32_derivation = bip32_derivation;
+        // A k-of-n owner signs with any k of its keys; every owner key gets
+        // a derivation entry below so each co-owner's device can sign.
+        for (input, (_, index)) in psbt.inputs.iter_mut().zip(self.inputs()) {
+            input.witness_script = Some(self.witness_script_at(index)?);
+            input.bip32_derivation = bip32_derivation_at(&secp, &receive_desc, index);
+        }
 
         Ok(psbt)
     }
@@ -430,12 +949,185 @@ impl CheckinTxBuilder {
     }
 }
 
+/// BIP-32 derivation paths (BIP-174 PSBT_IN_BIP32_DERIVATION) of the keys
+/// in `receive_desc` at child `index`, telling hardware wallets which HD
+/// key path to sign with: each derived public key maps to its
+/// (master_fingerprint, full_derivation_path).
+fn bip32_derivation_at(
+    secp: &secp256k1::Secp256k1<secp256k1::VerifyOnly>,
+    receive_desc: &Descriptor<DescriptorPublicKey>,
+    index: u32,
+) -> BTreeMap<secp256k1::PublicKey, (Fingerprint, DerivationPath)> {
+    let mut bip32_derivation = BTreeMap::new();
+
+    receive_desc.for_each_key(|key| {
+        if let DescriptorPublicKey::XPub(ref xkey) = key {
+            if let Some((fingerprint, base_path)) = &xkey.origin {
+                // Derive the child pubkey at our derivation index
+                if let Ok(child_xpub) = xkey.xkey.derive_pub(secp, &[ChildNumber::Normal { index }])
+                {
+                    let pubkey = child_xpub.public_key;
+
+                    // Full path = origin path + xpub derivation path + child index
+                    // e.g., [fingerprint/84'/0'/0']xpub/0/* at index 5 →
+                    //        m/84'/0'/0'/0/5
+                    let mut full_path: Vec<ChildNumber> = base_path.as_ref().to_vec();
+                    for step in xkey.derivation_path.as_ref() {
+                        full_path.push(*step);
+                    }
+                    full_path.push(ChildNumber::Normal { index });
+
+                    bip32_derivation
+                        .insert(pubkey, (*fingerprint, DerivationPath::from(full_path)));
+                }
+            }
+        } else if let DescriptorPublicKey::MultiXPub(ref xkey) = key {
+            if let Some((fingerprint, base_path)) = &xkey.origin {
+                // For multi-path xpubs (<0;1>/*), use path index 0 (receive)
+                if let Some(first_path) = xkey.derivation_paths.paths().first() {
+                    if let Ok(child_xpub) = xkey.xkey.derive_pub(secp, first_path) {
+                        if let Ok(final_xpub) =
+                            child_xpub.derive_pub(secp, &[ChildNumber::Normal { index }])
+                        {
+                            let pubkey = final_xpub.public_key;
+
+                            let mut full_path: Vec<ChildNumber> = base_path.as_ref().to_vec();
+                            for step in first_path.as_ref() {
+                                full_path.push(*step);
+                            }
+                            full_path.push(ChildNumber::Normal { index });
+
+                            bip32_derivation
+                                .insert(pubkey, (*fingerprint, DerivationPath::from(full_path)));
+                        }
+                    }
+                }
+            }
+        }
+        true // continue iterating
+    });
+
+    bip32_derivation
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::test_xpub_str;
     use bitcoin::hashes::Hash;
     use bitcoin::Txid;
 
+    /// Single-input PSBT spending with the given nSequence
+    fn psbt_with_sequence(sequence: Sequence) -> Psbt {
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), 0),
+                script_sig: ScriptBuf::new(),
+                sequence,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(10_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        Psbt::from_unsigned_tx(tx).unwrap()
+    }
+
+    #[test]
+    fn test_timelock_satisfied() {
+        let psbt = psbt_with_sequence(Sequence::from_height(26_280));
+        assert_eq!(relative_lock_blocks(&psbt), Some((0, 26_280)));
+        assert!(check_timelock_satisfied(&psbt, 830_000, 800_000, 26_280).is_ok());
+    }
+
+    #[test]
+    fn test_timelock_exactly_satisfied() {
+        // The next block (826,280) is the first the CSV input may be mined in
+        let psbt = psbt_with_sequence(Sequence::from_height(26_280));
+        assert!(check_timelock_satisfied(&psbt, 826_279, 800_000, 26_280).is_ok());
+        assert_eq!(
+            check_timelock_satisfied(&psbt, 826_278, 800_000, 26_280),
+            Err(TimelockError::NotYetSpendable {
+                blocks_remaining: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_timelock_not_yet_satisfied() {
+        let psbt = psbt_with_sequence(Sequence::from_height(26_280));
+        assert_eq!(
+            check_timelock_satisfied(&psbt, 810_000, 800_000, 26_280),
+            Err(TimelockError::NotYetSpendable {
+                blocks_remaining: 16_279
+            })
+        );
+
+        // Unconfirmed UTXO: the lock hasn't started
+        assert_eq!(
+            check_timelock_satisfied(&psbt, 810_000, 0, 26_280),
+            Err(TimelockError::NotYetSpendable {
+                blocks_remaining: 26_280
+            })
+        );
+    }
+
+    #[test]
+    fn test_timelock_check_ignores_unlocked_spends() {
+        // Owner check-ins don't use the CSV path
+        let psbt = psbt_with_sequence(Sequence::ENABLE_RBF_NO_LOCKTIME);
+        assert_eq!(relative_lock_blocks(&psbt), None);
+        assert!(check_timelock_satisfied(&psbt, 800_001, 800_000, 26_280).is_ok());
+    }
+
+    #[test]
+    fn test_timelock_sequence_too_short() {
+        let psbt = psbt_with_sequence(Sequence::from_height(144));
+        assert_eq!(
+            check_timelock_satisfied(&psbt, 900_000, 800_000, 26_280),
+            Err(TimelockError::SequenceTooShort {
+                input: 0,
+                sequence_blocks: 144,
+                required: 26_280
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_psbt_timelock_uses_input_heights() {
+        let mut psbt = psbt_with_sequence(Sequence::from_height(26_280));
+
+        // Owner spends never touch the chain
+        let owner = psbt_with_sequence(Sequence::ENABLE_RBF_NO_LOCKTIME);
+        assert!(
+            check_psbt_timelock(&owner, None, || unreachable!(), |_, _| unreachable!()).is_ok()
+        );
+
+        assert_eq!(
+            check_psbt_timelock(&psbt, None, || Ok(810_000), |_, _| Ok(Some(800_000))),
+            Err(TimelockError::MissingWitnessUtxo(0))
+        );
+
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(20_000),
+            script_pubkey: ScriptBuf::new(),
+        });
+        assert!(check_psbt_timelock(&psbt, None, || Ok(830_000), |_, _| Ok(Some(800_000))).is_ok());
+        assert_eq!(
+            check_psbt_timelock(&psbt, Some(26_280), || Ok(830_000), |_, _| Ok(None)),
+            Err(TimelockError::NotYetSpendable {
+                blocks_remaining: 26_280
+            })
+        );
+        assert_eq!(
+            check_psbt_timelock(&psbt, None, || Err("offline".into()), |_, _| Ok(None)),
+            Err(TimelockError::Lookup("offline".into()))
+        );
+    }
+
     #[test]
     fn test_timelock_status_calculation() {
         // UTXO at height 800,000, current height 810,000
@@ -459,6 +1151,36 @@ mod tests {
         assert_eq!(status.urgency(), CheckinUrgency::Expired);
     }
 
+    #[test]
+    fn test_time_based_timelock_status() {
+        // 4,096 × 512 s ≈ 24.3 days, confirmed at t = 1,700,000,000
+        let timelock = Timelock::from_512_second_intervals(4_096).unwrap();
+        let utxo = ChainPoint {
+            height: 800_000,
+            time: 1_700_000_000,
+        };
+
+        // Ten days later, but only 100 blocks mined: time decides
+        let current = ChainPoint {
+            height: 800_100,
+            time: 1_700_000_000 + 10 * 86_400,
+        };
+        let status = TimelockStatus::for_timelock(&timelock, current, utxo);
+        assert_eq!(status.seconds_remaining, 4_096 * 512 - 10 * 86_400);
+        assert_eq!(status.blocks_remaining, 2_056);
+        assert_eq!(status.unlock_height, 802_156);
+        assert!(!status.expired);
+
+        // Far fewer blocks than a block count would need, yet expired
+        let current = ChainPoint {
+            height: 801_000,
+            time: 1_700_000_000 + 4_096 * 512,
+        };
+        let status = TimelockStatus::for_timelock(&timelock, current, utxo);
+        assert!(status.expired);
+        assert_eq!(status.urgency(), CheckinUrgency::Expired);
+    }
+
     #[test]
     fn test_urgency_levels() {
         // Test different urgency levels based on blocks remaining
@@ -483,6 +1205,97 @@ mod tests {
         assert!(display.contains("months"));
     }
 
+    #[test]
+    fn test_average_block_interval() {
+        assert_eq!(average_block_interval(&[]), None);
+        assert_eq!(average_block_interval(&[1_700_000_000]), None);
+        assert_eq!(
+            average_block_interval(&[1_700_000_600, 1_700_000_000]),
+            None
+        );
+
+        let times = [1_700_000_000, 1_700_000_540, 1_700_001_080, 1_700_001_620];
+        assert_eq!(average_block_interval(&times), Some(540.0));
+    }
+
+    #[test]
+    fn test_calibrated_expiry_differs_from_naive() {
+        // Blocks have been arriving every ~9 minutes, not 10
+        let tip = 1_700_000_000u32;
+        let times: Vec<u32> = (0..11).map(|i| tip - (10 - i) * 540).collect();
+
+        let calibrated = estimate_expiry_date(800_000, 801_000, &times);
+        let naive_from_tip = DateTime::from_timestamp(tip as i64 + 1_000 * 600, 0).unwrap();
+
+        assert_eq!(calibrated.timestamp(), tip as i64 + 1_000 * 540);
+        assert_ne!(calibrated, naive_from_tip);
+        assert!(calibrated < naive_from_tip);
+    }
+
+    #[test]
+    fn test_expiry_date_fallback_and_round_trip() {
+        // No headers: 600s blocks from now
+        let estimate = estimate_expiry_date(800_000, 800_144, &[]);
+        let blocks = blocks_remaining_from_date(estimate);
+        assert!((143..=145).contains(&blocks), "got {}", blocks);
+
+        // Already expired → date in the past
+        let past = estimate_expiry_date(800_144, 800_000, &[]);
+        assert!(blocks_remaining_from_date(past) < 0);
+    }
+
+    #[test]
+    fn test_checkin_runway_sufficient_stack() {
+        // 6-month lock, auto-broadcast at 30 days, 100 days left
+        let runway = checkin_runway(14_400, 26_280, 4_320, 3, BLOCKS_PER_YEAR);
+
+        // Fires at day 70, then every ~152 days; lock ends 6 months after the last
+        assert_eq!(runway.runway_blocks, 10_080 + 2 * 21_960 + 26_280);
+        assert_eq!(runway.checkins_needed, 2);
+        assert!(!runway.needs_more);
+        assert!(runway.runway_blocks >= BLOCKS_PER_YEAR as u64);
+    }
+
+    #[test]
+    fn test_checkin_runway_insufficient_stack() {
+        let runway = checkin_runway(14_400, 26_280, 4_320, 1, BLOCKS_PER_YEAR);
+        assert_eq!(runway.runway_blocks, 10_080 + 26_280);
+        assert!(runway.needs_more);
+
+        // An empty stack only has what's left on the current lock
+        let empty = checkin_runway(14_400, 26_280, 4_320, 0, BLOCKS_PER_YEAR);
+        assert_eq!(empty.runway_blocks, 14_400);
+        assert!(empty.needs_more);
+
+        // A horizon inside the current lock needs nothing
+        let short = checkin_runway(14_400, 26_280, 4_320, 0, 1_000);
+        assert_eq!(short.checkins_needed, 0);
+        assert!(!short.needs_more);
+    }
+
+    #[test]
+    fn test_recommended_chain_length() {
+        // 6-month lock at 30 days: lock ends day ~182, then every ~152 days
+        assert_eq!(recommended_chain_length(BLOCKS_PER_YEAR, 26_280, 4_320), 2);
+        assert_eq!(
+            recommended_chain_length(2 * BLOCKS_PER_YEAR, 26_280, 4_320),
+            4
+        );
+
+        // 1-year lock: one check-in carries well past a year
+        assert_eq!(recommended_chain_length(BLOCKS_PER_YEAR, 52_560, 4_320), 1);
+
+        // 30-day lock, broadcast with a day left: one per 29 days after
+        // the first 30
+        assert_eq!(
+            recommended_chain_length(90 * BLOCKS_PER_DAY, 30 * BLOCKS_PER_DAY, BLOCKS_PER_DAY),
+            3
+        );
+
+        // A horizon inside the current lock still keeps one ready
+        assert_eq!(recommended_chain_length(1_000, 26_280, 4_320), 1);
+    }
+
     #[test]
     fn test_inheritance_utxo() {
         let outpoint = OutPoint {
@@ -530,6 +1343,117 @@ mod tests {
         derived.script_pubkey()
     }
 
+    #[test]
+    fn test_consolidating_checkin_psbt() {
+        use crate::policy::{InheritancePolicy, Timelock};
+        use bitcoin::bip32::Xpub;
+
+        let test_xpub = Xpub::from_str(test_xpub_str()).unwrap();
+        let owner_key =
+            DescriptorPublicKey::from_str(&format!("[00000001/84'/0'/0']{}/<0;1>/*", test_xpub))
+                .unwrap();
+        let heir_key =
+            DescriptorPublicKey::from_str(&format!("[00000002/84'/0'/1']{}/<0;1>/*", test_xpub))
+                .unwrap();
+        let descriptor = InheritancePolicy::simple(owner_key, heir_key, Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        let spk = derive_script_pubkey(&descriptor, 0);
+
+        let utxos: Vec<InheritanceUtxo> = [50_000u64, 20_000, 15_000, 15_000]
+            .iter()
+            .enumerate()
+            .map(|(vout, sats)| {
+                let outpoint = OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: vout as u32,
+                };
+                InheritanceUtxo::new(outpoint, Amount::from_sat(*sats), 800_000, spk.clone())
+            })
+            .collect();
+
+        let builder = CheckinTxBuilder::new(utxos[0].clone(), descriptor, 10, 0)
+            .with_consolidation(utxos[1..].to_vec());
+        let estimate = builder.estimate().unwrap();
+        assert_eq!(estimate.input_count, 4);
+        assert_eq!(estimate.total_value, Amount::from_sat(100_000));
+        // 4 × 138 input + 43 output + 11 overhead vbytes at 10 sat/vB
+        assert_eq!(estimate.fee, Amount::from_sat(6_060));
+        assert_eq!(estimate.output_value, Amount::from_sat(93_940));
+        assert!(estimate.exceeds(DEFAULT_MAX_CONSOLIDATION_FEE_PERCENT));
+        assert!(!estimate.exceeds(10.0));
+
+        let psbt = builder.build_psbt().unwrap();
+        let tx = &psbt.unsigned_tx;
+        assert_eq!(tx.input.len(), 4);
+        assert_eq!(tx.output.len(), 1);
+        assert_eq!(tx.output[0].value, estimate.output_value);
+        assert_eq!(tx.output[0].script_pubkey, spk);
+
+        // Every input carries what a hardware wallet needs to sign it
+        let in_total: Amount = psbt
+            .inputs
+            .iter()
+            .map(|i| i.witness_utxo.as_ref().unwrap().value)
+            .sum();
+        assert_eq!(in_total, Amount::from_sat(100_000));
+        assert!(psbt.inputs.iter().all(|i| i.witness_script.is_some()));
+        assert!(psbt.inputs.iter().all(|i| !i.bip32_derivation.is_empty()));
+    }
+
+    #[test]
+    fn test_consolidating_across_rotated_indexes() {
+        use crate::policy::{InheritancePolicy, Timelock};
+        use bitcoin::bip32::Xpub;
+
+        let test_xpub = Xpub::from_str(test_xpub_str()).unwrap();
+        let owner_key =
+            DescriptorPublicKey::from_str(&format!("[00000001/84'/0'/0']{}/<0;1>/*", test_xpub))
+                .unwrap();
+        let heir_key =
+            DescriptorPublicKey::from_str(&format!("[00000002/84'/0'/1']{}/<0;1>/*", test_xpub))
+                .unwrap();
+        let descriptor = InheritancePolicy::simple(owner_key, heir_key, Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        let utxo_at = |index: u32, vout: u32| {
+            let outpoint = OutPoint {
+                txid: Txid::all_zeros(),
+                vout,
+            };
+            let spk = derive_script_pubkey(&descriptor, index);
+            InheritanceUtxo::new(outpoint, Amount::from_sat(50_000), 800_000, spk)
+        };
+
+        // Current funds at index 2, older ones left at 0
+        let builder = CheckinTxBuilder::new(utxo_at(2, 0), descriptor.clone(), 10, 2)
+            .with_destination(CheckinDestination::NextIndex)
+            .with_consolidation_at(0, vec![utxo_at(0, 1)]);
+        let psbt = builder.build_psbt().unwrap();
+        assert_eq!(psbt.unsigned_tx.input.len(), 2);
+        assert_eq!(
+            psbt.unsigned_tx.output[0].script_pubkey,
+            derive_script_pubkey(&descriptor, 3)
+        );
+
+        // Each input is signable at its own index
+        for (input, index) in psbt.inputs.iter().zip([2u32, 0]) {
+            let witness_script = input.witness_script.as_ref().unwrap();
+            assert_eq!(
+                witness_script.to_p2wsh(),
+                derive_script_pubkey(&descriptor, index)
+            );
+            assert!(!input.bip32_derivation.is_empty());
+            let child = ChildNumber::Normal { index };
+            let paths = input.bip32_derivation.values();
+            assert!(paths
+                .map(|(_, path)| path.as_ref().last())
+                .all(|last| last == Some(&child)));
+        }
+    }
+
     #[test]
     fn test_checkin_psbt_generation() {
         use crate::policy::{InheritancePolicy, Timelock};
@@ -786,4 +1710,274 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_multisig_owner_checkin_psbt() {
+        use crate::policy::{InheritancePolicy, OwnerSpec, Timelock};
+        use crate::test_utils::{test_co_owner_keys, test_heir_key};
+
+        let owners = test_co_owner_keys();
+        let heir = test_heir_key();
+
+        let build = |owner: OwnerSpec| {
+            let descriptor = InheritancePolicy::simple(owner, heir.clone(), Timelock::six_months())
+                .unwrap()
+                .to_wsh_descriptor()
+                .unwrap();
+            let spk = derive_script_pubkey(&descriptor, 0);
+            let utxo = InheritanceUtxo::new(
+                OutPoint::new(Txid::all_zeros(), 0),
+                Amount::from_sat(100_000),
+                800_000,
+                spk,
+            );
+            CheckinTxBuilder::new(utxo, descriptor, 10, 0)
+        };
+
+        let multi = build(OwnerSpec::thresh(2, owners.clone()).unwrap());
+        let psbt = multi.build_psbt().unwrap();
+
+        // Every co-owner plus the heir can find their key
+        let fingerprints: Vec<_> = psbt.inputs[0]
+            .bip32_derivation
+            .values()
+            .map(|(fp, _)| fp.to_bytes())
+            .collect();
+        assert_eq!(fingerprints.len(), 4);
+        for fp in [[0, 0, 0, 0x11], [0, 0, 0, 0x12], [0, 0, 0, 0x13]] {
+            assert!(fingerprints.contains(&fp), "missing owner {:?}", fp);
+        }
+
+        // Two signatures and a longer script cost more than one signature
+        let single = build(OwnerSpec::Single(owners[0].clone()));
+        assert!(multi.estimate_fee(DescriptorKind::Wsh) > single.estimate_fee(DescriptorKind::Wsh));
+        assert_eq!(
+            psbt.unsigned_tx.output[0].value,
+            Amount::from_sat(100_000) - multi.estimate_fee(DescriptorKind::Wsh)
+        );
+    }
+
+    #[test]
+    fn test_taproot_checkin_psbt() {
+        use crate::policy::{InheritancePolicy, Timelock};
+        use bitcoin::bip32::Xpub;
+        use std::str::FromStr;
+
+        let owner_xpub = Xpub::from_str(test_xpub_str()).unwrap();
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let heir_xpub = owner_xpub
+            .derive_pub(&secp, &[bitcoin::bip32::ChildNumber::Normal { index: 1 }])
+            .unwrap();
+        let owner_key =
+            DescriptorPublicKey::from_str(&format!("[00000001/86'/0'/0']{}/<0;1>/*", owner_xpub))
+                .unwrap();
+        let heir_key =
+            DescriptorPublicKey::from_str(&format!("[00000002/86'/0'/1']{}/<0;1>/*", heir_xpub))
+                .unwrap();
+
+        let policy =
+            InheritancePolicy::simple(owner_key, heir_key, Timelock::six_months()).unwrap();
+        let descriptor = policy.to_tr_descriptor().unwrap();
+        let spk = derive_script_pubkey(&descriptor, 3);
+        assert!(spk.is_p2tr());
+
+        let outpoint = OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        };
+        let utxo = InheritanceUtxo::new(outpoint, Amount::from_sat(100_000), 800_000, spk.clone());
+        let builder = CheckinTxBuilder::new(utxo, descriptor, 10, 3);
+        let psbt = builder.build_psbt().expect("PSBT creation should succeed");
+        let input = &psbt.inputs[0];
+
+        // Key-path fee: (58 + 43 + 11) vB at 10 sat/vB
+        assert_eq!(psbt.unsigned_tx.output[0].value, Amount::from_sat(98_880));
+        assert_eq!(psbt.unsigned_tx.output[0].script_pubkey, spk);
+
+        assert!(input.witness_script.is_none());
+        assert!(input.tap_internal_key.is_some());
+        assert!(input.tap_merkle_root.is_some());
+        assert_eq!(input.tap_scripts.len(), 1);
+
+        // Owner origin has no leaf hashes (key path), heir origin has one
+        let mut leaf_counts: Vec<(u32, usize)> = input
+            .tap_key_origins
+            .values()
+            .map(|(leaves, (fp, _))| (u32::from_be_bytes(fp.to_bytes()), leaves.len()))
+            .collect();
+        leaf_counts.sort();
+        assert_eq!(leaf_counts, vec![(1, 0), (2, 1)]);
+    }
+
+    fn simple_builder(fee_rate: u64, value_sat: u64) -> CheckinTxBuilder {
+        use crate::policy::{InheritancePolicy, Timelock};
+        use bitcoin::bip32::Xpub;
+
+        let test_xpub = Xpub::from_str(test_xpub_str()).unwrap();
+        let key = |origin: &str| {
+            DescriptorPublicKey::from_str(&format!("[{}]{}/<0;1>/*", origin, test_xpub)).unwrap()
+        };
+        let descriptor = InheritancePolicy::simple(
+            key("00000001/84'/0'/0'"),
+            key("00000002/84'/0'/1'"),
+            Timelock::six_months(),
+        )
+        .unwrap()
+        .to_wsh_descriptor()
+        .unwrap();
+        let spk = derive_script_pubkey(&descriptor, 0);
+        let outpoint = OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        };
+        let utxo = InheritanceUtxo::new(outpoint, Amount::from_sat(value_sat), 800_000, spk);
+        CheckinTxBuilder::new(utxo, descriptor, fee_rate, 0)
+    }
+
+    #[test]
+    fn test_fee_rate_floor() {
+        // At the mainnet floor
+        assert!(simple_builder(1, 100_000).build_psbt().is_ok());
+
+        // Below it
+        assert!(matches!(
+            simple_builder(0, 100_000).build_psbt(),
+            Err(CheckinError::FeeTooLow {
+                fee_rate: 0,
+                min: 1
+            })
+        ));
+        assert!(matches!(
+            simple_builder(0, 100_000)
+                .with_network(bitcoin::Network::Testnet)
+                .build_psbt(),
+            Err(CheckinError::FeeTooLow { .. })
+        ));
+
+        // Regtest is permissive
+        assert!(simple_builder(0, 100_000)
+            .with_network(bitcoin::Network::Regtest)
+            .build_psbt()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_fee_rate_ceiling_warns() {
+        assert!(simple_builder(FEE_RATE_WARN_CEILING, 1_000_000)
+            .fee_rate_warning()
+            .is_none());
+
+        let fat_finger = simple_builder(FEE_RATE_WARN_CEILING + 1, 1_000_000);
+        assert!(fat_finger
+            .fee_rate_warning()
+            .unwrap()
+            .contains("1001 sat/vB"));
+        // A warning, not an error
+        assert!(fat_finger.build_psbt().is_ok());
+    }
+
+    #[test]
+    fn test_dust_output_rejected() {
+        // 192 vB at 1 sat/vB leaves 100 sats: under the P2WSH dust limit
+        assert!(matches!(
+            simple_builder(1, 292).build_psbt(),
+            Err(CheckinError::DustOutput(_))
+        ));
+        assert!(simple_builder(1, 292)
+            .with_network(bitcoin::Network::Regtest)
+            .build_psbt()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_descriptor_rejected() {
+        let descriptor: Descriptor<DescriptorPublicKey> =
+            format!("pkh({}/0/*)", test_xpub_str()).parse().unwrap();
+        let spk = derive_script_pubkey(&descriptor, 0);
+        let outpoint = OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        };
+        let utxo = InheritanceUtxo::new(outpoint, Amount::from_sat(100_000), 800_000, spk);
+        let builder = CheckinTxBuilder::new(utxo, descriptor, 10, 0);
+
+        assert!(matches!(
+            builder.build_psbt(),
+            Err(CheckinError::PolicyError(
+                crate::policy::PolicyError::UnsupportedDescriptor(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_checkin_destinations() {
+        use crate::policy::{InheritancePolicy, Timelock};
+        use bitcoin::bip32::Xpub;
+
+        let test_xpub = Xpub::from_str(test_xpub_str()).unwrap();
+        let owner_key =
+            DescriptorPublicKey::from_str(&format!("[00000001/84'/0'/0']{}/<0;1>/*", test_xpub))
+                .unwrap();
+        let heir_key =
+            DescriptorPublicKey::from_str(&format!("[00000002/84'/0'/1']{}/<0;1>/*", test_xpub))
+                .unwrap();
+        let descriptor = InheritancePolicy::simple(owner_key, heir_key, Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        let spk = derive_script_pubkey(&descriptor, 2);
+        let outpoint = OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        };
+        let utxo = InheritanceUtxo::new(outpoint, Amount::from_sat(100_000), 800_000, spk.clone());
+        let builder = |destination| {
+            CheckinTxBuilder::new(utxo.clone(), descriptor.clone(), 10, 2)
+                .with_destination(destination)
+        };
+        let output_script = |b: &CheckinTxBuilder| {
+            b.build_psbt().unwrap().unsigned_tx.output[0]
+                .script_pubkey
+                .clone()
+        };
+
+        // Default: same address
+        let same = builder(CheckinDestination::SameAddress);
+        assert_eq!(output_script(&same), spk);
+        assert!(same.destination_warning().is_none());
+
+        // Rotation: index + 1 of the same descriptor
+        let next = builder(CheckinDestination::NextIndex);
+        assert_eq!(output_script(&next), derive_script_pubkey(&descriptor, 3));
+        assert!(next.destination_warning().is_none());
+
+        // No index past the last one
+        let last = CheckinTxBuilder::new(utxo.clone(), descriptor.clone(), 10, u32::MAX)
+            .with_destination(CheckinDestination::NextIndex);
+        assert!(matches!(
+            last.destination_script(),
+            Err(CheckinError::NoNextIndex(u32::MAX))
+        ));
+
+        // External cold storage: outside the policy, so warned about
+        let cold = bitcoin::Address::from_str("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+            .unwrap()
+            .assume_checked();
+        let external = builder(CheckinDestination::External(cold.clone()));
+        assert_eq!(output_script(&external), cold.script_pubkey());
+        assert!(external.destination_warning().unwrap().contains("heirs"));
+
+        // External address that is another index of the same policy: no warning
+        let policy_address = bitcoin::Address::from_script(
+            &derive_script_pubkey(&descriptor, 7),
+            bitcoin::Network::Bitcoin,
+        )
+        .unwrap();
+        let rotated = builder(CheckinDestination::External(policy_address));
+        assert_eq!(
+            output_script(&rotated),
+            derive_script_pubkey(&descriptor, 7)
+        );
+        assert!(rotated.destination_warning().is_none());
+    }
 }
@@ -0,0 +1,282 @@
+//! Finalizing externally signed PSBTs
+//!
+//! Air-gapped signers (hardware wallets, Sparrow in offline mode) often
+//! return a PSBT carrying only partial signatures, leaving the witness for
+//! the coordinator to build. [`finalize_psbt`] does that for inheritance
+//! inputs: it locates each input's address in the descriptor, fills in the
+//! script fields and lets miniscript's satisfier pick the spending path the
+//! signatures (and the input's sequence) allow.
+
+use crate::policy::{DescriptorKind, PolicyError};
+use bitcoin::bip32::ChildNumber;
+use bitcoin::psbt::Psbt;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::{ScriptBuf, Transaction};
+use miniscript::descriptor::{DefiniteDescriptorKey, DescriptorPublicKey};
+use miniscript::psbt::PsbtExt;
+use miniscript::Descriptor;
+use thiserror::Error;
+
+/// Receive/change indices searched for an input's address when its key
+/// origins don't point at one
+pub const FINALIZE_SCAN_LIMIT: u32 = 1000;
+
+#[derive(Error, Debug)]
+pub enum PsbtError {
+    #[error("Input {0} has no witness or previous transaction")]
+    MissingUtxo(usize),
+
+    #[error("Input {0} doesn't spend an address of this descriptor")]
+    ForeignInput(usize),
+
+    #[error("Cannot finalize input {index}: {reason}")]
+    Finalize { index: usize, reason: String },
+
+    #[error("Finalized transaction is invalid: {0}")]
+    Extract(String),
+
+    #[error("Policy error: {0}")]
+    Policy(#[from] PolicyError),
+}
+
+/// Finalize a signed inheritance PSBT and extract its transaction.
+///
+/// Inputs that already carry a final witness are kept as they are. Every
+/// other input must spend an address of `descriptor` (either branch of a
+/// multipath descriptor, up to [`FINALIZE_SCAN_LIMIT`]); its witness is
+/// built from the partial signatures. The extracted transaction is checked
+/// by miniscript's interpreter, so a missing or wrong signature, or a
+/// sequence too short for the heir timelock, is an error rather than a
+/// transaction the network would reject.
+pub fn finalize_psbt(
+    mut psbt: Psbt,
+    descriptor: &Descriptor<DescriptorPublicKey>,
+) -> Result<Transaction, PsbtError> {
+    DescriptorKind::of(descriptor)?;
+    let branches = descriptor
+        .clone()
+        .into_single_descriptors()
+        .map_err(PolicyError::from)?;
+    let secp = Secp256k1::verification_only();
+
+    for index in 0..psbt.inputs.len() {
+        let input = &psbt.inputs[index];
+        if input.final_script_witness.is_some() || input.final_script_sig.is_some() {
+            continue;
+        }
+
+        let script_pubkey = spent_script(&psbt, index).ok_or(PsbtError::MissingUtxo(index))?;
+        let definite = find_definite(&branches, &psbt, index, &script_pubkey)
+            .ok_or(PsbtError::ForeignInput(index))?;
+
+        psbt.update_input_with_descriptor(index, &definite)
+            .map_err(|e| PsbtError::Finalize {
+                index,
+                reason: e.to_string(),
+            })?;
+        psbt.finalize_inp_mut(&secp, index)
+            .map_err(|e| PsbtError::Finalize {
+                index,
+                reason: e.to_string(),
+            })?;
+    }
+
+    psbt.extract(&secp)
+        .map_err(|e| PsbtError::Extract(e.to_string()))
+}
+
+/// Script of the output input `index` spends
+fn spent_script(psbt: &Psbt, index: usize) -> Option<ScriptBuf> {
+    let input = &psbt.inputs[index];
+    if let Some(utxo) = &input.witness_utxo {
+        return Some(utxo.script_pubkey.clone());
+    }
+    let vout = psbt.unsigned_tx.input[index].previous_output.vout as usize;
+    input
+        .non_witness_utxo
+        .as_ref()
+        .and_then(|tx| tx.output.get(vout))
+        .map(|out| out.script_pubkey.clone())
+}
+
+/// The derived descriptor paying to `script_pubkey`.
+///
+/// Tries the indices named by the input's key origins first (signers
+/// usually get them from the PSBT NoString built), then scans.
+fn find_definite(
+    branches: &[Descriptor<DescriptorPublicKey>],
+    psbt: &Psbt,
+    index: usize,
+    script_pubkey: &ScriptBuf,
+) -> Option<Descriptor<DefiniteDescriptorKey>> {
+    let input = &psbt.inputs[index];
+    let origin_paths = input
+        .bip32_derivation
+        .values()
+        .map(|(_, path)| path)
+        .chain(input.tap_key_origins.values().map(|(_, (_, path))| path));
+    let mut hinted: Vec<u32> = origin_paths
+        .filter_map(|path| match path.into_iter().last() {
+            Some(ChildNumber::Normal { index }) => Some(*index),
+            _ => None,
+        })
+        .collect();
+    hinted.sort_unstable();
+    hinted.dedup();
+
+    hinted
+        .into_iter()
+        .chain(0..FINALIZE_SCAN_LIMIT)
+        .flat_map(|i| branches.iter().map(move |branch| (branch, i)))
+        .filter_map(|(branch, i)| branch.at_derivation_index(i).ok())
+        .find(|definite| &definite.script_pubkey() == script_pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heir::{build_recovery_psbt, RecoveryInput};
+    use crate::policy::{InheritancePolicy, Timelock};
+    use bitcoin::bip32::{DerivationPath, Xpriv, Xpub};
+    use bitcoin::hashes::Hash;
+    use bitcoin::secp256k1::{All, Message};
+    use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+    use bitcoin::{Address, Amount, Network, OutPoint};
+    use std::str::FromStr;
+
+    fn master(seed: u8) -> Xpriv {
+        Xpriv::new_master(Network::Regtest, &[seed; 32]).unwrap()
+    }
+
+    fn descriptor_key(secp: &Secp256k1<All>, seed: u8) -> DescriptorPublicKey {
+        let master = master(seed);
+        let path = DerivationPath::from_str("m/84'/1'/0'").unwrap();
+        let xpub = Xpub::from_priv(secp, &master.derive_priv(secp, &path).unwrap());
+        DescriptorPublicKey::from_str(&format!(
+            "[{}/84'/1'/0']{}/<0;1>/*",
+            master.fingerprint(secp),
+            xpub
+        ))
+        .unwrap()
+    }
+
+    /// Add `seed`'s partial signature to every input, as an offline signer would
+    fn sign_inputs(secp: &Secp256k1<All>, psbt: &mut Psbt, seed: u8) {
+        let fingerprint = master(seed).fingerprint(secp);
+        let mut cache = SighashCache::new(psbt.unsigned_tx.clone());
+        for (index, input) in psbt.inputs.iter_mut().enumerate() {
+            let (pubkey, (_, path)) = input
+                .bip32_derivation
+                .iter()
+                .find(|(_, (fp, _))| *fp == fingerprint)
+                .map(|(pk, origin)| (*pk, origin.clone()))
+                .unwrap();
+            let key = master(seed).derive_priv(secp, &path).unwrap().private_key;
+            assert_eq!(key.public_key(secp), pubkey);
+
+            let sighash = cache
+                .p2wsh_signature_hash(
+                    index,
+                    input.witness_script.as_ref().unwrap(),
+                    input.witness_utxo.as_ref().unwrap().value,
+                    EcdsaSighashType::All,
+                )
+                .unwrap();
+            let sig = secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &key);
+            input.partial_sigs.insert(
+                bitcoin::PublicKey::new(pubkey),
+                bitcoin::ecdsa::Signature::sighash_all(sig),
+            );
+        }
+    }
+
+    fn recovery_fixture(
+        secp: &Secp256k1<All>,
+    ) -> (Descriptor<DescriptorPublicKey>, Psbt, Timelock) {
+        let timelock = Timelock::from_blocks(144).unwrap();
+        let descriptor =
+            InheritancePolicy::simple(descriptor_key(secp, 1), descriptor_key(secp, 2), timelock)
+                .unwrap()
+                .to_wsh_descriptor()
+                .unwrap();
+        let destination = Address::p2wpkh(
+            &bitcoin::CompressedPublicKey(master(9).private_key.public_key(secp)),
+            Network::Regtest,
+        );
+        let inputs = [
+            RecoveryInput {
+                outpoint: OutPoint::new(bitcoin::Txid::all_zeros(), 0),
+                value: Amount::from_sat(60_000),
+                derivation_index: 0,
+            },
+            RecoveryInput {
+                outpoint: OutPoint::new(bitcoin::Txid::all_zeros(), 1),
+                value: Amount::from_sat(40_000),
+                derivation_index: 7,
+            },
+        ];
+        let psbt = build_recovery_psbt(&descriptor, &inputs, timelock, &destination, 2).unwrap();
+        (descriptor, psbt, timelock)
+    }
+
+    #[test]
+    fn test_finalize_heir_signed_recovery_psbt() {
+        let secp = Secp256k1::new();
+        let (descriptor, mut psbt, timelock) = recovery_fixture(&secp);
+        let spent: Vec<_> = psbt
+            .inputs
+            .iter()
+            .map(|i| i.witness_utxo.clone().unwrap())
+            .collect();
+
+        // Signed only, as an air-gapped heir wallet returns it
+        sign_inputs(&secp, &mut psbt, 2);
+        assert!(psbt.inputs.iter().all(|i| i.final_script_witness.is_none()));
+
+        let tx = finalize_psbt(psbt, &descriptor).unwrap();
+        assert_eq!(tx.input.len(), 2);
+        for txin in &tx.input {
+            assert_eq!(txin.sequence, timelock.to_sequence());
+            assert!(!txin.witness.is_empty());
+        }
+
+        // Bitcoin Core's interpreter agrees
+        let tx_bytes = bitcoin::consensus::serialize(&tx);
+        for (index, utxo) in spent.iter().enumerate() {
+            bitcoinconsensus::verify(
+                utxo.script_pubkey.as_bytes(),
+                utxo.value.to_sat(),
+                &tx_bytes,
+                None,
+                index,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_finalize_rejects_unsigned_and_foreign_inputs() {
+        let secp = Secp256k1::new();
+        let (descriptor, psbt, _) = recovery_fixture(&secp);
+
+        // No signatures at all
+        assert!(matches!(
+            finalize_psbt(psbt.clone(), &descriptor),
+            Err(PsbtError::Finalize { index: 0, .. })
+        ));
+
+        // A descriptor with other keys doesn't control the inputs
+        let other = InheritancePolicy::simple(
+            descriptor_key(&secp, 3),
+            descriptor_key(&secp, 4),
+            Timelock::from_blocks(144).unwrap(),
+        )
+        .unwrap()
+        .to_wsh_descriptor()
+        .unwrap();
+        assert!(matches!(
+            finalize_psbt(psbt, &other),
+            Err(PsbtError::ForeignInput(0))
+        ));
+    }
+}
@@ -6,8 +6,9 @@
 //!
 //! # How It Works
 //!
-//! CSV timelocks are relative to UTXO confirmation height. Every key-path
-//! spend resets the clock. The heartbeat module evaluates how much of the
+//! CSV timelocks are relative to UTXO confirmation: its height, or for
+//! time-based (512-second) locks its block timestamp. Every key-path spend
+//! resets the clock. The heartbeat module evaluates how much of the
 //! timelock has elapsed and recommends action:
 //!
 //! ```text
@@ -17,7 +18,8 @@
 //!
 //! Thresholds are configurable.
 
-use crate::checkin::{CheckinUrgency, TimelockStatus};
+use crate::checkin::{ChainPoint, CheckinUrgency, TimelockStatus};
+use crate::policy::TimelockUnit;
 use crate::taproot::InheritableVault;
 use serde::{Deserialize, Serialize};
 
@@ -116,26 +118,36 @@ pub enum HeartbeatError {
 ///
 /// # Arguments
 /// * `vault` — the inheritable vault
-/// * `utxo_height` — block height when the vault UTXO was confirmed
-/// * `current_height` — current blockchain tip height
+/// * `utxo` — block where the vault UTXO was confirmed
+/// * `current` — current blockchain tip
 /// * `config` — heartbeat thresholds
 ///
+/// Block timestamps only matter for a time-based timelock.
+///
 /// # Returns
 /// `HeartbeatStatus` with the recommended action.
 pub fn evaluate_heartbeat(
     vault: &InheritableVault,
-    utxo_height: u32,
-    current_height: u32,
+    utxo: ChainPoint,
+    current: ChainPoint,
     config: &HeartbeatConfig,
 ) -> HeartbeatStatus {
-    let timelock_blocks = vault.timelock.blocks();
-    let timelock_status = TimelockStatus::calculate(current_height, utxo_height, timelock_blocks);
-
-    let blocks_elapsed = current_height.saturating_sub(utxo_height);
-    let elapsed_fraction = if timelock_blocks == 0 {
+    let timelock_status = TimelockStatus::for_timelock(&vault.timelock, current, utxo);
+
+    let (elapsed, total) = match vault.timelock.unit() {
+        TimelockUnit::Blocks => (
+            current.height.saturating_sub(utxo.height) as f64,
+            vault.timelock.value() as f64,
+        ),
+        TimelockUnit::Time => (
+            current.time.saturating_sub(utxo.time) as f64,
+            vault.timelock.seconds() as f64,
+        ),
+    };
+    let elapsed_fraction = if total == 0.0 {
         1.0 // Degenerate case: zero timelock is always expired
     } else {
-        blocks_elapsed as f64 / timelock_blocks as f64
+        elapsed / total
     };
 
     let action = if timelock_status.expired {
@@ -162,27 +174,27 @@ pub fn evaluate_heartbeat(
 /// If any recovery path is expired, that's the one that matters.
 pub fn evaluate_cascade_heartbeat(
     vault: &InheritableVault,
-    utxo_height: u32,
-    current_height: u32,
+    utxo: ChainPoint,
+    current: ChainPoint,
     config: &HeartbeatConfig,
 ) -> HeartbeatStatus {
     // For cascade vaults, the earliest timelock is the most urgent.
     // InheritableVault stores recovery_scripts sorted by timelock (left-leaning tree).
     // The vault's `timelock` field is the primary (earliest) timelock.
-    evaluate_heartbeat(vault, utxo_height, current_height, config)
+    evaluate_heartbeat(vault, utxo, current, config)
 }
 
 /// Batch evaluate heartbeat for multiple vaults.
 ///
 /// Returns statuses sorted by urgency (most urgent first).
 pub fn evaluate_batch(
-    vaults: &[(InheritableVault, u32)], // (vault, utxo_height)
-    current_height: u32,
+    vaults: &[(InheritableVault, ChainPoint)], // (vault, utxo confirmation)
+    current: ChainPoint,
     config: &HeartbeatConfig,
 ) -> Vec<HeartbeatStatus> {
     let mut statuses: Vec<HeartbeatStatus> = vaults
         .iter()
-        .map(|(vault, utxo_height)| evaluate_heartbeat(vault, *utxo_height, current_height, config))
+        .map(|(vault, utxo)| evaluate_heartbeat(vault, *utxo, current, config))
         .collect();
 
     // Sort: Expired first, then CheckinRequired, then CheckinRecommended, then Healthy
@@ -210,17 +222,25 @@ pub fn evaluate_batch(
 mod tests {
     use super::*;
     use crate::policy::PathInfo;
-    use crate::test_utils::{make_test_vault, test_chain_code, test_keypair};
+    use crate::test_utils::{make_test_vault, make_test_vault_with, test_chain_code, test_keypair};
     use bitcoin::Network;
     use miniscript::descriptor::DescriptorPublicKey;
     use nostring_ccd::register_cosigner_with_chain_code;
     use std::str::FromStr;
 
+    /// Block at `height`, mined on a steady 10-minute schedule
+    fn at(height: u32) -> ChainPoint {
+        ChainPoint {
+            height,
+            time: height as u64 * 600,
+        }
+    }
+
     #[test]
     fn test_healthy_status() {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default();
-        let status = evaluate_heartbeat(&vault, 100, 200, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(200), &config);
 
         assert_eq!(status.action, HeartbeatAction::Healthy);
         assert!((status.elapsed_fraction - 0.1).abs() < 0.001);
@@ -232,7 +252,7 @@ mod tests {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default(); // threshold at 0.5
                                                  // 600 of 1000 blocks elapsed = 0.6
-        let status = evaluate_heartbeat(&vault, 100, 700, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(700), &config);
 
         assert_eq!(status.action, HeartbeatAction::CheckinRecommended);
         assert!((status.elapsed_fraction - 0.6).abs() < 0.001);
@@ -243,7 +263,7 @@ mod tests {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default(); // critical at 0.9
                                                  // 950 of 1000 blocks elapsed = 0.95
-        let status = evaluate_heartbeat(&vault, 100, 1050, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(1050), &config);
 
         assert_eq!(status.action, HeartbeatAction::CheckinRequired);
         assert!((status.elapsed_fraction - 0.95).abs() < 0.001);
@@ -254,7 +274,7 @@ mod tests {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default();
         // 1100 of 1000 blocks elapsed = 1.1 (past expiry)
-        let status = evaluate_heartbeat(&vault, 100, 1200, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(1200), &config);
 
         assert_eq!(status.action, HeartbeatAction::Expired);
         assert!(status.timelock_status.expired);
@@ -264,7 +284,7 @@ mod tests {
     fn test_zero_blocks_elapsed() {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default();
-        let status = evaluate_heartbeat(&vault, 100, 100, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(100), &config);
 
         assert_eq!(status.action, HeartbeatAction::Healthy);
         assert!((status.elapsed_fraction).abs() < 0.001);
@@ -275,7 +295,7 @@ mod tests {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default(); // threshold at 0.5
                                                  // Exactly 500 of 1000 = 0.5
-        let status = evaluate_heartbeat(&vault, 100, 600, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(600), &config);
 
         assert_eq!(status.action, HeartbeatAction::CheckinRecommended);
     }
@@ -285,7 +305,7 @@ mod tests {
         let vault = make_test_vault(1000);
         let config = HeartbeatConfig::default(); // critical at 0.9
                                                  // Exactly 900 of 1000 = 0.9
-        let status = evaluate_heartbeat(&vault, 100, 1000, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(1000), &config);
 
         assert_eq!(status.action, HeartbeatAction::CheckinRequired);
     }
@@ -300,14 +320,52 @@ mod tests {
         };
 
         // 350 of 1000 = 0.35 (past 0.3 threshold)
-        let status = evaluate_heartbeat(&vault, 100, 450, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(450), &config);
         assert_eq!(status.action, HeartbeatAction::CheckinRecommended);
 
         // 750 of 1000 = 0.75 (past 0.7 critical)
-        let status = evaluate_heartbeat(&vault, 100, 850, &config);
+        let status = evaluate_heartbeat(&vault, at(100), at(850), &config);
         assert_eq!(status.action, HeartbeatAction::CheckinRequired);
     }
 
+    #[test]
+    fn test_time_based_timelock_uses_timestamps() {
+        use crate::policy::Timelock;
+
+        // 1,000 × 512 s ≈ 5.9 days
+        let vault = make_test_vault_with(Timelock::from_512_second_intervals(1_000).unwrap());
+        let config = HeartbeatConfig::default();
+        let utxo = ChainPoint {
+            height: 100,
+            time: 1_700_000_000,
+        };
+
+        // 60% of the time elapsed while only 10 blocks were mined
+        let current = ChainPoint {
+            height: 110,
+            time: utxo.time + 307_200,
+        };
+        let status = evaluate_heartbeat(&vault, utxo, current, &config);
+        assert_eq!(status.action, HeartbeatAction::CheckinRecommended);
+        assert!((status.elapsed_fraction - 0.6).abs() < 0.001);
+
+        // Many blocks but little time: still healthy
+        let current = ChainPoint {
+            height: 10_000,
+            time: utxo.time + 51_200,
+        };
+        let status = evaluate_heartbeat(&vault, utxo, current, &config);
+        assert_eq!(status.action, HeartbeatAction::Healthy);
+
+        let current = ChainPoint {
+            height: 120,
+            time: utxo.time + 512_000,
+        };
+        let status = evaluate_heartbeat(&vault, utxo, current, &config);
+        assert_eq!(status.action, HeartbeatAction::Expired);
+        assert!(status.timelock_status.expired);
+    }
+
     #[test]
     fn test_config_validation() {
         let bad1 = HeartbeatConfig {
@@ -360,13 +418,13 @@ mod tests {
         let config = HeartbeatConfig::default();
 
         let vaults = vec![
-            (vault1, 500), // healthy (current_height - 500 = 100 blocks elapsed)
-            (vault2, 100), // critical (500 blocks elapsed = 0.5, recommended)
-            (vault3, 0),   // expired (600 blocks elapsed > 1000? No, 600/1000=0.6 recommended)
+            (vault1, at(500)), // healthy (current_height - 500 = 100 blocks elapsed)
+            (vault2, at(100)), // critical (500 blocks elapsed = 0.5, recommended)
+            (vault3, at(0)),   // expired (600 blocks elapsed > 1000? No, 600/1000=0.6 recommended)
         ];
 
         // current_height = 600
-        let statuses = evaluate_batch(&vaults, 600, &config);
+        let statuses = evaluate_batch(&vaults, at(600), &config);
         assert_eq!(statuses.len(), 3);
 
         // vault at height 500: 100/1000 = 0.1 → Healthy
@@ -420,12 +478,12 @@ mod tests {
         let config = HeartbeatConfig::default();
 
         // At 50% of 3-month timelock (6,570 blocks): should be CheckinRecommended
-        let status = evaluate_heartbeat(&vault, 800_000, 806_570, &config);
+        let status = evaluate_heartbeat(&vault, at(800_000), at(806_570), &config);
         assert_eq!(status.action, HeartbeatAction::CheckinRecommended);
 
         // At 50% of 6-month timelock but only 50% of 3-month: same answer
         // because we evaluate against the earliest timelock
-        let status2 = evaluate_cascade_heartbeat(&vault, 800_000, 806_570, &config);
+        let status2 = evaluate_cascade_heartbeat(&vault, at(800_000), at(806_570), &config);
         assert_eq!(status2.action, HeartbeatAction::CheckinRecommended);
     }
 
@@ -436,19 +494,19 @@ mod tests {
         let config = HeartbeatConfig::default();
 
         // Just created
-        let status = evaluate_heartbeat(&vault, 800_000, 800_000, &config);
+        let status = evaluate_heartbeat(&vault, at(800_000), at(800_000), &config);
         assert_eq!(status.action, HeartbeatAction::Healthy);
 
         // 3 months in (halfway)
-        let status = evaluate_heartbeat(&vault, 800_000, 813_140, &config);
+        let status = evaluate_heartbeat(&vault, at(800_000), at(813_140), &config);
         assert_eq!(status.action, HeartbeatAction::CheckinRecommended);
 
         // 5.5 months in (~95%)
-        let status = evaluate_heartbeat(&vault, 800_000, 824_966, &config);
+        let status = evaluate_heartbeat(&vault, at(800_000), at(824_966), &config);
         assert_eq!(status.action, HeartbeatAction::CheckinRequired);
 
         // 6+ months (expired)
-        let status = evaluate_heartbeat(&vault, 800_000, 826_281, &config);
+        let status = evaluate_heartbeat(&vault, at(800_000), at(826_281), &config);
         assert_eq!(status.action, HeartbeatAction::Expired);
     }
 }
@@ -22,11 +22,15 @@
 
 pub mod backup;
 pub mod checkin;
+pub mod finalize;
 pub mod heartbeat;
 pub mod heir;
 pub mod policy;
 pub mod taproot;
 pub mod taproot_checkin;
 
-#[cfg(test)]
-pub(crate) mod test_utils;
+pub use finalize::{finalize_psbt, PsbtError};
+pub use policy::{parse_and_validate_descriptor, verify_address, verify_address_at};
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
@@ -14,11 +14,14 @@
 //! This creates a Bitcoin script where:
 //! - The owner can spend at any time with their key
 //! - The heir can only spend after TIMELOCK blocks have passed
+//!
+//! Timelocks may instead count 512-second intervals (BIP-68 time-based
+//! locks); see [`TimelockUnit`].
 
-use bitcoin::Sequence;
-use miniscript::descriptor::DescriptorPublicKey;
-use miniscript::policy::Concrete;
-use miniscript::{Descriptor, Miniscript, Segwitv0};
+use bitcoin::{Address, Network, Script, Sequence};
+use miniscript::descriptor::{checksum, DescriptorPublicKey, TapTree};
+use miniscript::policy::{Concrete, Liftable, Semantic};
+use miniscript::{Descriptor, ForEachKey, Miniscript, MiniscriptKey, Segwitv0};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fmt;
@@ -47,11 +50,96 @@ pub enum PolicyError {
 
     #[error("Policy compilation failed: {0}")]
     Compilation(String),
+
+    #[error("Unsupported descriptor type: {0} (expected wsh() or tr())")]
+    UnsupportedDescriptor(String),
+
+    #[error("Key derivation failed: {0}")]
+    Derivation(String),
+
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+
+    #[error("Cannot explain policy: {0}")]
+    Unexplainable(String),
+
+    #[error("Descriptor checksum mismatch: expected #{expected}, found #{found}")]
+    ChecksumMismatch { expected: String, found: String },
+
+    #[error("Invalid character {0:?} in descriptor")]
+    InvalidDescriptorChar(char),
+
+    #[error("Descriptor key {key} is not a {network} key")]
+    WrongNetwork { key: String, network: Network },
 }
 
-/// Timelock duration in blocks (~10 min each)
+/// Output script type of an inheritance descriptor.
+///
+/// Everything that spends or watches an inheritance UTXO dispatches on this,
+/// since P2WSH and Taproot differ in witness layout, PSBT fields and fees.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Timelock(u16);
+pub enum DescriptorKind {
+    /// `wsh(...)`: owner and heirs share a single witness script
+    Wsh,
+    /// `tr(...)`: owner is the internal key, heirs are script leaves
+    Tr,
+}
+
+impl DescriptorKind {
+    /// Detect the kind of a descriptor, rejecting anything NoString can't spend.
+    pub fn of<Pk: MiniscriptKey>(descriptor: &Descriptor<Pk>) -> Result<Self, PolicyError> {
+        match descriptor {
+            Descriptor::Wsh(_) => Ok(Self::Wsh),
+            Descriptor::Tr(_) => Ok(Self::Tr),
+            other => Err(PolicyError::UnsupportedDescriptor(format!(
+                "{:?}",
+                other.desc_type()
+            ))),
+        }
+    }
+}
+
+/// BIP-68 flag bit marking a relative timelock as time-based
+pub const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+
+/// Seconds in one unit of a time-based relative timelock
+pub const SECONDS_PER_TIME_UNIT: u32 = 512;
+
+/// What a relative timelock counts (BIP-68)
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockUnit {
+    /// Blocks (~10 min each)
+    #[default]
+    Blocks,
+    /// 512-second intervals
+    Time,
+}
+
+impl TimelockUnit {
+    /// Unit of an `older(n)` value, from its BIP-68 type flag
+    pub fn of_older(value: u32) -> Self {
+        if value & SEQUENCE_LOCKTIME_TYPE_FLAG != 0 {
+            Self::Time
+        } else {
+            Self::Blocks
+        }
+    }
+}
+
+/// Relative timelock: a count of blocks (~10 min each) or of 512-second
+/// intervals
+///
+/// Serializes as its `older()` value, so block-based timelocks keep their
+/// plain block count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "u32", try_from = "u32")]
+pub struct Timelock {
+    value: u16,
+    unit: TimelockUnit,
+}
 
 impl Timelock {
     /// Create a timelock from a number of blocks
@@ -59,17 +147,58 @@ impl Timelock {
         if blocks == 0 {
             return Err(PolicyError::InvalidTimelock(blocks as u32));
         }
-        Ok(Self(blocks))
+        Ok(Self {
+            value: blocks,
+            unit: TimelockUnit::Blocks,
+        })
+    }
+
+    /// Create a time-based timelock from a number of 512-second intervals
+    pub fn from_512_second_intervals(intervals: u16) -> Result<Self, PolicyError> {
+        if intervals == 0 {
+            return Err(PolicyError::InvalidTimelock(intervals as u32));
+        }
+        Ok(Self {
+            value: intervals,
+            unit: TimelockUnit::Time,
+        })
+    }
+
+    /// Time-based timelock of at least `seconds`, rounded up to whole
+    /// 512-second intervals
+    pub fn from_seconds(seconds: u32) -> Result<Self, PolicyError> {
+        let intervals = seconds.div_ceil(SECONDS_PER_TIME_UNIT);
+        if intervals > u16::MAX as u32 {
+            return Err(PolicyError::InvalidTimelock(intervals));
+        }
+        Self::from_512_second_intervals(intervals as u16)
+    }
+
+    /// Decode an `older(n)` value, using its type flag for the unit
+    pub fn from_older(value: u32) -> Result<Self, PolicyError> {
+        match Sequence::from_consensus(value).to_relative_lock_time() {
+            Some(bitcoin::relative::LockTime::Blocks(height)) => Self::from_blocks(height.value()),
+            Some(bitcoin::relative::LockTime::Time(time)) => {
+                Self::from_512_second_intervals(time.value())
+            }
+            None => Err(PolicyError::InvalidTimelock(value)),
+        }
     }
 
     /// 6 months (~26,280 blocks)
     pub fn six_months() -> Self {
-        Self(26_280)
+        Self {
+            value: 26_280,
+            unit: TimelockUnit::Blocks,
+        }
     }
 
     /// 1 year (~52,560 blocks)
     pub fn one_year() -> Self {
-        Self(52_560)
+        Self {
+            value: 52_560,
+            unit: TimelockUnit::Blocks,
+        }
     }
 
     /// Custom duration in days
@@ -81,14 +210,138 @@ impl Timelock {
         Self::from_blocks(blocks as u16)
     }
 
+    /// The unit `value` counts
+    pub fn unit(&self) -> TimelockUnit {
+        self.unit
+    }
+
+    /// The raw count: blocks or 512-second intervals, per `unit`
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
     /// Get the block count
+    ///
+    /// For a time-based timelock, the equivalent at ~10 minutes per block.
     pub fn blocks(&self) -> u16 {
-        self.0
+        match self.unit {
+            TimelockUnit::Blocks => self.value,
+            TimelockUnit::Time => (self.seconds() / 600) as u16,
+        }
+    }
+
+    /// Duration in seconds
+    ///
+    /// Exact for a time-based timelock; ~600 seconds per block otherwise.
+    pub fn seconds(&self) -> u32 {
+        match self.unit {
+            TimelockUnit::Blocks => self.value as u32 * 600,
+            TimelockUnit::Time => self.value as u32 * SECONDS_PER_TIME_UNIT,
+        }
     }
 
     /// Convert to Bitcoin sequence value for CSV
     pub fn to_sequence(&self) -> Sequence {
-        Sequence::from_height(self.0)
+        match self.unit {
+            TimelockUnit::Blocks => Sequence::from_height(self.value),
+            TimelockUnit::Time => Sequence::from_512_second_intervals(self.value),
+        }
+    }
+
+    /// The `older()` argument for this timelock
+    pub fn to_rel_lock_time(&self) -> miniscript::RelLockTime {
+        match self.unit {
+            TimelockUnit::Blocks => miniscript::RelLockTime::from_height(self.value),
+            TimelockUnit::Time => miniscript::RelLockTime::from_512_second_intervals(self.value),
+        }
+    }
+}
+
+impl From<Timelock> for u32 {
+    fn from(timelock: Timelock) -> u32 {
+        timelock.to_sequence().to_consensus_u32()
+    }
+}
+
+impl TryFrom<u32> for Timelock {
+    type Error = PolicyError;
+
+    fn try_from(value: u32) -> Result<Self, PolicyError> {
+        Self::from_older(value)
+    }
+}
+
+/// Every relative timelock in `descriptor`, earliest first
+///
+/// The unit of each is detected from its `older()` type flag.
+pub fn descriptor_timelocks<Pk: MiniscriptKey>(
+    descriptor: &Descriptor<Pk>,
+) -> Result<Vec<Timelock>, PolicyError> {
+    let mut timelocks = Vec::new();
+    collect_timelocks(&descriptor.lift()?, &mut timelocks)?;
+    timelocks.sort();
+    timelocks.dedup();
+    Ok(timelocks)
+}
+
+fn collect_timelocks<Pk: MiniscriptKey>(
+    policy: &Semantic<Pk>,
+    out: &mut Vec<Timelock>,
+) -> Result<(), PolicyError> {
+    match policy {
+        Semantic::Older(older) => out.push(Timelock::from_older(older.to_consensus_u32())?),
+        Semantic::Thresh(thresh) => {
+            for sub in thresh.iter() {
+                collect_timelocks(sub, out)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Keys that can only sign behind a timelock, i.e. the heirs'
+///
+/// Every spending path with an `older()` condition contributes its keys,
+/// in descriptor order without duplicates. The owner's paths (and a
+/// Taproot internal key) are left out.
+pub fn heir_keys(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+) -> Result<Vec<DescriptorPublicKey>, PolicyError> {
+    let policy = descriptor.lift()?;
+    let mut branches = Vec::new();
+    collect_or_branches(&policy, &mut branches);
+
+    let mut keys: Vec<DescriptorPublicKey> = Vec::new();
+    for branch in branches {
+        let mut conditions = Vec::new();
+        collect_and_conditions(branch, &mut conditions);
+        if !conditions.iter().any(|c| matches!(c, Semantic::Older(_))) {
+            continue;
+        }
+        let mut branch_keys = Vec::new();
+        collect_keys(branch, &mut branch_keys);
+        for key in branch_keys {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+    Ok(keys)
+}
+
+fn collect_keys<'a>(
+    policy: &'a Semantic<DescriptorPublicKey>,
+    out: &mut Vec<&'a DescriptorPublicKey>,
+) {
+    match policy {
+        Semantic::Key(key) => out.push(key),
+        Semantic::Thresh(thresh) => {
+            for sub in thresh.iter() {
+                collect_keys(sub, out);
+            }
+        }
+        _ => {}
     }
 }
 
@@ -145,6 +398,32 @@ impl PathInfo {
     }
 }
 
+/// Who controls the primary (owner) path
+///
+/// A couple or a business can be the "owner" with `Thresh`, producing
+/// `or(thresh(k, owners...), and(heirs, older(N)))`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnerSpec {
+    /// One owner key
+    Single(DescriptorPublicKey),
+    /// Any `k` of the owner keys, e.g. a 2-of-3
+    Thresh(usize, Vec<DescriptorPublicKey>),
+}
+
+impl OwnerSpec {
+    /// A `k`-of-n owner
+    pub fn thresh(k: usize, keys: Vec<DescriptorPublicKey>) -> Result<Self, PolicyError> {
+        PathInfo::multi(k, keys.clone())?;
+        Ok(Self::Thresh(k, keys))
+    }
+}
+
+impl From<DescriptorPublicKey> for OwnerSpec {
+    fn from(key: DescriptorPublicKey) -> Self {
+        OwnerSpec::Single(key)
+    }
+}
+
 /// A NoString inheritance policy
 ///
 /// Defines who can spend (owner) and who inherits (recovery paths with timelocks).
@@ -184,15 +463,15 @@ impl InheritancePolicy {
         Ok(Self { primary, recovery })
     }
 
-    /// Create a simple single-owner, single-heir policy
+    /// Create a simple single-heir policy
+    ///
+    /// `owner` is a key or an [`OwnerSpec`] (e.g. a 2-of-3 owner).
     pub fn simple(
-        owner: DescriptorPublicKey,
+        owner: impl Into<OwnerSpec>,
         heir: DescriptorPublicKey,
         timelock: Timelock,
     ) -> Result<Self, PolicyError> {
-        let mut recovery = BTreeMap::new();
-        recovery.insert(timelock, PathInfo::Single(heir));
-        Self::new(PathInfo::Single(owner), recovery)
+        Self::cascade(owner, vec![(timelock, PathInfo::Single(heir))])
     }
 
     /// Create a cascade inheritance policy with multiple heirs at different timelocks
@@ -210,7 +489,7 @@ impl InheritancePolicy {
     /// )?;
     /// ```
     pub fn cascade(
-        owner: DescriptorPublicKey,
+        owner: impl Into<OwnerSpec>,
         heirs: Vec<(Timelock, PathInfo)>,
     ) -> Result<Self, PolicyError> {
         if heirs.is_empty() {
@@ -220,12 +499,17 @@ impl InheritancePolicy {
         let mut recovery = BTreeMap::new();
         for (timelock, path_info) in heirs {
             if recovery.contains_key(&timelock) {
-                return Err(PolicyError::InvalidTimelock(timelock.blocks() as u32));
+                return Err(PolicyError::InvalidTimelock(u32::from(timelock)));
             }
             recovery.insert(timelock, path_info);
         }
 
-        Self::new(PathInfo::Single(owner), recovery)
+        match owner.into() {
+            OwnerSpec::Single(key) => Self::new(PathInfo::Single(key), recovery),
+            OwnerSpec::Thresh(k, keys) => {
+                Self::multisig_owner(k, keys, recovery.into_iter().collect())
+            }
+        }
     }
 
     /// Create a multi-sig owner policy with cascade heirs
@@ -276,7 +560,11 @@ impl InheritancePolicy {
     pub fn to_concrete_policy(&self) -> Concrete<DescriptorPublicKey> {
         // Primary path (owner)
         let primary = Arc::new(self.primary.to_policy());
+        Concrete::Or(vec![(1, primary), (1, self.recovery_policy())])
+    }
 
+    /// All recovery paths combined: or(recovery1, or(recovery2, ...))
+    fn recovery_policy(&self) -> Arc<Concrete<DescriptorPublicKey>> {
         // Recovery paths as and(keys, older(timelock))
         let mut recovery_policies: Vec<Arc<Concrete<DescriptorPublicKey>>> = self
             .recovery
@@ -284,24 +572,16 @@ impl InheritancePolicy {
             .map(|(timelock, path_info)| {
                 Arc::new(Concrete::And(vec![
                     Arc::new(path_info.to_policy()),
-                    Arc::new(Concrete::Older(miniscript::RelLockTime::from_height(
-                        timelock.blocks(),
-                    ))),
+                    Arc::new(Concrete::Older(timelock.to_rel_lock_time())),
                 ]))
             })
             .collect();
 
-        // Combine primary with all recovery paths using Or
-        if recovery_policies.len() == 1 {
-            Concrete::Or(vec![(1, primary), (1, recovery_policies.remove(0))])
-        } else {
-            // Multiple recovery paths: or(primary, or(recovery1, or(recovery2, ...)))
-            let mut combined_recovery = recovery_policies.pop().unwrap();
-            while let Some(path) = recovery_policies.pop() {
-                combined_recovery = Arc::new(Concrete::Or(vec![(1, path), (1, combined_recovery)]));
-            }
-            Concrete::Or(vec![(1, primary), (1, combined_recovery)])
+        let mut combined_recovery = recovery_policies.pop().unwrap();
+        while let Some(path) = recovery_policies.pop() {
+            combined_recovery = Arc::new(Concrete::Or(vec![(1, path), (1, combined_recovery)]));
         }
+        combined_recovery
     }
 
     /// Compile to a P2WSH descriptor
@@ -313,6 +593,31 @@ impl InheritancePolicy {
         Ok(Descriptor::new_wsh(ms)?)
     }
 
+    /// Compile to a Taproot descriptor
+    ///
+    /// The owner key becomes the internal key, so a check-in is a plain
+    /// key-path spend that looks like any other single-sig Taproot payment.
+    /// Each recovery path becomes its own script leaf. Multisig owners are
+    /// rejected here; use a MuSig2 aggregate via [`crate::taproot`] instead.
+    pub fn to_tr_descriptor(&self) -> Result<Descriptor<DescriptorPublicKey>, PolicyError> {
+        let internal_key = match &self.primary {
+            PathInfo::Single(key) => key.clone(),
+            PathInfo::Multi(..) => {
+                return Err(PolicyError::Compilation(
+                    "tr() descriptors require a single owner key".to_string(),
+                ))
+            }
+        };
+
+        let tree = self
+            .compile_recovery_tapscripts()?
+            .into_iter()
+            .map(|(_, ms)| TapTree::Leaf(Arc::new(ms)))
+            .reduce(TapTree::combine);
+
+        Ok(Descriptor::new_tr(internal_key, tree)?)
+    }
+
     /// Compile only the recovery paths to Tapscript leaves.
     ///
     /// For use with Taproot outputs where the primary (owner) path is the
@@ -332,9 +637,7 @@ impl InheritancePolicy {
             // Build policy: and(heir_keys, older(timelock))
             let recovery_policy = Concrete::And(vec![
                 Arc::new(path_info.to_policy()),
-                Arc::new(Concrete::Older(miniscript::RelLockTime::from_height(
-                    timelock.blocks(),
-                ))),
+                Arc::new(Concrete::Older(timelock.to_rel_lock_time())),
             ]);
 
             let ms: Miniscript<DescriptorPublicKey, miniscript::Tap> = recovery_policy
@@ -350,20 +653,26 @@ impl InheritancePolicy {
 
 impl fmt::Display for Timelock {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let days = self.0 / 144;
+        let days = self.seconds() / 86_400;
+        let count = match self.unit {
+            TimelockUnit::Blocks => format!("{} blocks", self.value),
+            TimelockUnit::Time => format!("{} × 512 s", self.value),
+        };
         if days >= 365 {
-            write!(f, "~{:.1} years ({} blocks)", days as f32 / 365.0, self.0)
+            write!(f, "~{:.1} years ({})", days as f32 / 365.0, count)
         } else if days >= 30 {
-            write!(f, "~{:.1} months ({} blocks)", days as f32 / 30.0, self.0)
+            write!(f, "~{:.1} months ({})", days as f32 / 30.0, count)
         } else {
-            write!(f, "~{} days ({} blocks)", days, self.0)
+            write!(f, "~{} days ({})", days, count)
         }
     }
 }
 
+/// Ordered by (approximate) duration, so cascades mixing units stay in
+/// the order their paths open
 impl Ord for Timelock {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.0.cmp(&other.0)
+        (self.seconds(), self.unit, self.value).cmp(&(other.seconds(), other.unit, other.value))
     }
 }
 
@@ -386,6 +695,617 @@ impl InheritancePolicy {
     }
 }
 
+/// Non-witness weight of a one-input, one-output claim: version, locktime,
+/// in/out counts and segwit marker (42 WU), the input (41 bytes) and a
+/// P2WSH/P2TR output (43 bytes).
+const CLAIM_TX_BASE_WEIGHT: usize = 42 + 41 * 4 + 43 * 4;
+
+/// Estimate the vsize of a one-input, one-output heir claim.
+///
+/// Uses the descriptor's worst-case satisfaction weight. For an inheritance
+/// policy that is the recovery branch: it carries the heir signatures plus
+/// the owner-path dissatisfaction (P2WSH) or the script and control block
+/// (Taproot), both heavier than the owner's single-signature spend. With a
+/// cascade, the heaviest recovery path is reported.
+pub fn estimate_claim_vsize<Pk: MiniscriptKey>(
+    descriptor: &Descriptor<Pk>,
+) -> Result<usize, PolicyError> {
+    let satisfaction = descriptor.max_weight_to_satisfy()?.to_wu() as usize;
+    Ok((CLAIM_TX_BASE_WEIGHT + satisfaction).div_ceil(4))
+}
+
+/// Where a k-of-n owner's `multi` sits in a compiled P2WSH witness script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultisigOwnerBranch {
+    /// Signatures the owner path needs
+    pub threshold: usize,
+    /// For `or_i`, whether the owner branch is taken with a `1` selector
+    /// (first branch) or an empty one (second). `None` for `or_d`, where
+    /// the heir path dissatisfies the `multi` with `k + 1` empty items.
+    pub selector: Option<bool>,
+}
+
+/// The multisig owner branch of a P2WSH witness script, as compiled for an
+/// [`OwnerSpec::Thresh`] owner (see [`InheritancePolicy::multisig_owner`]).
+///
+/// The compiler chooses between `or_d(multi(..), ..)` and `or_i` with the
+/// `multi` on either side; all three are recognised. `None` for a
+/// single-key owner or any other script.
+pub fn multisig_owner_branch(witness_script: &Script) -> Option<MultisigOwnerBranch> {
+    use miniscript::Terminal;
+
+    let ms = Miniscript::<bitcoin::PublicKey, Segwitv0>::parse(witness_script).ok()?;
+    let multi_k = |ms: &Miniscript<bitcoin::PublicKey, Segwitv0>| match &ms.node {
+        Terminal::Multi(thresh) => Some(thresh.k()),
+        _ => None,
+    };
+    let (threshold, selector) = match &ms.node {
+        Terminal::OrD(owner, _) => (multi_k(owner)?, None),
+        Terminal::OrI(first, second) => match multi_k(first) {
+            Some(k) => (k, Some(true)),
+            None => (multi_k(second)?, Some(false)),
+        },
+        _ => return None,
+    };
+    Some(MultisigOwnerBranch {
+        threshold,
+        selector,
+    })
+}
+
+/// Largest P2WSH witness script relay policy accepts (bytes)
+pub const MAX_STANDARD_P2WSH_SCRIPT_SIZE: usize = 3600;
+
+/// Most witness stack items (excluding the witness script) a standard
+/// P2WSH spend may carry
+pub const MAX_STANDARD_P2WSH_STACK_ITEMS: usize = 100;
+
+/// Consensus limit on non-push opcodes in a P2WSH witness script
+pub const MAX_OPS_PER_SCRIPT: usize = 201;
+
+/// Consensus limit on stack items when executing a tapscript
+pub const MAX_STACK_SIZE: usize = 1000;
+
+/// Heaviest transaction relay policy accepts (weight units)
+pub const MAX_STANDARD_TX_WEIGHT: usize = 400_000;
+
+/// Resource usage of a descriptor's spending paths against relay limits.
+///
+/// Produced by [`check_standardness`]. A descriptor that fails these limits
+/// can still be valid by consensus, but its claim transaction won't relay
+/// through default nodes — the heirs would need a miner to include it
+/// directly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardnessReport {
+    pub kind: DescriptorKind,
+    /// Worst-case satisfaction weight of an input (weight units)
+    pub max_satisfaction_weight: usize,
+    /// Witness script size (P2WSH) or largest leaf script (Taproot), in bytes
+    pub max_script_size: usize,
+    /// Most witness stack items any spending path needs, excluding the
+    /// script itself and the control block
+    pub max_witness_items: usize,
+    /// Counted non-push opcodes (P2WSH only; tapscript has no op limit)
+    pub max_ops: Option<usize>,
+    /// Opcodes relay policy rejects or reserves for upgrades
+    pub non_standard_opcodes: Vec<String>,
+    /// Human-readable description of every exceeded limit
+    pub warnings: Vec<String>,
+}
+
+impl StandardnessReport {
+    /// Whether every spending path stays within relay policy
+    pub fn is_standard(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Check a descriptor's spending paths against relay policy limits.
+///
+/// Computes the worst-case satisfaction weight, script size, witness item
+/// count and (for P2WSH) opcode count, and flags any limit a claim would
+/// exceed. Miniscript only emits standard opcodes, so the opcode scan is a
+/// guard for descriptors built outside NoString. Multipath descriptors are
+/// checked on their receive branch; every branch shares the same scripts.
+pub fn check_standardness(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+) -> Result<StandardnessReport, PolicyError> {
+    use bitcoin::opcodes::ClassifyContext;
+    use miniscript::descriptor::WshInner;
+
+    let kind = DescriptorKind::of(descriptor)?;
+    let max_satisfaction_weight = descriptor.max_weight_to_satisfy()?.to_wu() as usize;
+    let derived = descriptor
+        .clone()
+        .into_single_descriptors()?
+        .remove(0)
+        .at_derivation_index(0)
+        .map_err(|e| PolicyError::Derivation(e.to_string()))?;
+
+    let mut report = StandardnessReport {
+        kind,
+        max_satisfaction_weight,
+        max_script_size: 0,
+        max_witness_items: 0,
+        max_ops: None,
+        non_standard_opcodes: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    match &derived {
+        Descriptor::Wsh(wsh) => {
+            let script = derived.explicit_script()?;
+            report.max_script_size = script.len();
+            report.max_ops = Some(count_ops(&script));
+            report.max_witness_items = match wsh.as_inner() {
+                // k signatures plus the CHECKMULTISIG dummy
+                WshInner::SortedMulti(multi) => multi.k() + 1,
+                WshInner::Ms(ms) => ms.max_satisfaction_witness_elements()? - 1,
+            };
+            report.non_standard_opcodes = non_standard_opcodes(&script, ClassifyContext::Legacy);
+
+            if report.max_script_size > MAX_STANDARD_P2WSH_SCRIPT_SIZE {
+                report.warnings.push(format!(
+                    "Witness script is {} bytes; relay policy allows at most {}",
+                    report.max_script_size, MAX_STANDARD_P2WSH_SCRIPT_SIZE
+                ));
+            }
+            if report.max_witness_items > MAX_STANDARD_P2WSH_STACK_ITEMS {
+                report.warnings.push(format!(
+                    "A spending path needs {} witness items; relay policy allows at most {}",
+                    report.max_witness_items, MAX_STANDARD_P2WSH_STACK_ITEMS
+                ));
+            }
+            if let Some(ops) = report.max_ops.filter(|&ops| ops > MAX_OPS_PER_SCRIPT) {
+                report.warnings.push(format!(
+                    "Witness script has {} opcodes; consensus allows at most {}",
+                    ops, MAX_OPS_PER_SCRIPT
+                ));
+            }
+        }
+        Descriptor::Tr(tr) => {
+            for (_, ms) in tr.iter_scripts() {
+                let script = ms.encode();
+                report.max_script_size = report.max_script_size.max(script.len());
+                report.max_witness_items = report
+                    .max_witness_items
+                    .max(ms.max_satisfaction_witness_elements()? - 1);
+                for op in non_standard_opcodes(&script, ClassifyContext::TapScript) {
+                    if !report.non_standard_opcodes.contains(&op) {
+                        report.non_standard_opcodes.push(op);
+                    }
+                }
+            }
+            if report.max_witness_items > MAX_STACK_SIZE {
+                report.warnings.push(format!(
+                    "A script path needs {} witness items; consensus allows at most {}",
+                    report.max_witness_items, MAX_STACK_SIZE
+                ));
+            }
+        }
+        _ => unreachable!("DescriptorKind::of accepts only wsh() and tr()"),
+    }
+
+    if !report.non_standard_opcodes.is_empty() {
+        report.warnings.push(format!(
+            "Script uses non-standard opcodes: {}",
+            report.non_standard_opcodes.join(", ")
+        ));
+    }
+    if CLAIM_TX_BASE_WEIGHT + max_satisfaction_weight > MAX_STANDARD_TX_WEIGHT {
+        report.warnings.push(format!(
+            "A claim would weigh over {} WU, the largest transaction relay policy accepts",
+            MAX_STANDARD_TX_WEIGHT
+        ));
+    }
+    Ok(report)
+}
+
+/// Non-push opcodes in `script`, counted the way consensus does: each
+/// CHECKMULTISIG(VERIFY) also counts its public keys.
+fn count_ops(script: &Script) -> usize {
+    use bitcoin::opcodes::all::{OP_CHECKMULTISIG, OP_CHECKMULTISIGVERIFY, OP_PUSHNUM_16};
+    use bitcoin::opcodes::{Class, ClassifyContext};
+    use bitcoin::script::Instruction;
+
+    let mut ops = 0;
+    let mut last_push_num = None;
+    for instruction in script.instructions().flatten() {
+        let Instruction::Op(op) = instruction else {
+            last_push_num = None;
+            continue;
+        };
+        if op == OP_CHECKMULTISIG || op == OP_CHECKMULTISIGVERIFY {
+            // Without a preceding key count, consensus assumes the maximum
+            ops += last_push_num.unwrap_or(20);
+        }
+        if op.to_u8() > OP_PUSHNUM_16.to_u8() {
+            ops += 1;
+        }
+        last_push_num = match op.classify(ClassifyContext::Legacy) {
+            Class::PushNum(n) if n > 0 => Some(n as usize),
+            _ => None,
+        };
+    }
+    ops
+}
+
+/// Distinct opcodes in `script` that relay policy rejects: illegal and
+/// `OP_RETURN`-like opcodes, OP_SUCCESSx in tapscript, and the NOPs
+/// reserved for soft forks (CLTV and CSV excepted).
+fn non_standard_opcodes(
+    script: &Script,
+    context: bitcoin::opcodes::ClassifyContext,
+) -> Vec<String> {
+    use bitcoin::opcodes::all::{OP_CLTV, OP_CSV, OP_NOP};
+    use bitcoin::opcodes::Class;
+    use bitcoin::script::Instruction;
+
+    let mut found = Vec::new();
+    for instruction in script.instructions().flatten() {
+        let Instruction::Op(op) = instruction else {
+            continue;
+        };
+        let non_standard = match op.classify(context) {
+            Class::IllegalOp | Class::ReturnOp | Class::SuccessOp => true,
+            Class::NoOp => op != OP_NOP && op != OP_CLTV && op != OP_CSV,
+            _ => false,
+        };
+        let name = op.to_string();
+        if non_standard && !found.contains(&name) {
+            found.push(name);
+        }
+    }
+    found
+}
+
+/// Check that `address` is the index-0 address of `descriptor` on `network`.
+///
+/// Lets an heir confirm a backup's address independently of whoever sent
+/// it: a tampered descriptor derives to a different address. Multipath
+/// descriptors (`<0;1>/*`) use their receive branch. An address for a
+/// different network never matches.
+pub fn verify_address(
+    descriptor: &str,
+    address: &str,
+    network: Network,
+) -> Result<bool, PolicyError> {
+    verify_address_at(descriptor, address, 0, network)
+}
+
+/// [`verify_address`] at receive index `index`, for funds a rotating
+/// check-in moved past index 0.
+pub fn verify_address_at(
+    descriptor: &str,
+    address: &str,
+    index: u32,
+    network: Network,
+) -> Result<bool, PolicyError> {
+    let descriptor: Descriptor<DescriptorPublicKey> = descriptor.parse()?;
+    DescriptorKind::of(&descriptor)?;
+
+    let receive = descriptor
+        .into_single_descriptors()?
+        .into_iter()
+        .next()
+        .ok_or_else(|| PolicyError::Derivation("descriptor has no paths".into()))?;
+    let derived = receive
+        .at_derivation_index(index)
+        .map_err(|e| PolicyError::Derivation(e.to_string()))?
+        .address(network)?;
+
+    let provided: Address<bitcoin::address::NetworkUnchecked> = address
+        .trim()
+        .parse()
+        .map_err(|e: bitcoin::address::ParseError| PolicyError::InvalidAddress(e.to_string()))?;
+    Ok(provided.is_valid_for_network(network) && provided.assume_checked() == derived)
+}
+
+/// Parse a stored or imported descriptor and check it is usable on `network`.
+///
+/// Checks, in order: the `#checksum` (if present), that it parses as a
+/// `wsh()` or `tr()` descriptor, that every extended key belongs to
+/// `network` (mainnet vs. test networks), and that each of its paths
+/// derives a script and address at index 0. A descriptor that passes can be
+/// handed to the check-in and claim builders without further checks.
+pub fn parse_and_validate_descriptor(
+    descriptor: &str,
+    network: Network,
+) -> Result<Descriptor<DescriptorPublicKey>, PolicyError> {
+    let descriptor = descriptor.trim();
+    validate_checksum(descriptor)?;
+    let parsed: Descriptor<DescriptorPublicKey> = descriptor.parse()?;
+    DescriptorKind::of(&parsed)?;
+
+    let expected = bitcoin::NetworkKind::from(network);
+    let mut foreign = None;
+    parsed.for_each_key(|key| {
+        let kind = match key {
+            DescriptorPublicKey::Single(_) => return true,
+            DescriptorPublicKey::XPub(xkey) => xkey.xkey.network,
+            DescriptorPublicKey::MultiXPub(xkey) => xkey.xkey.network,
+        };
+        if kind != expected {
+            foreign = Some(key.to_string());
+        }
+        foreign.is_none()
+    });
+    if let Some(key) = foreign {
+        return Err(PolicyError::WrongNetwork { key, network });
+    }
+
+    for single in parsed.clone().into_single_descriptors()? {
+        single
+            .at_derivation_index(0)
+            .map_err(|e| PolicyError::Derivation(e.to_string()))?
+            .address(network)?;
+    }
+    Ok(parsed)
+}
+
+/// BIP-380 checksum of a descriptor body (without `#...`)
+fn descriptor_checksum(body: &str) -> Result<String, PolicyError> {
+    let mut engine = checksum::Engine::new();
+    if engine.input(body).is_err() {
+        // Name the first character outside the checksum's input alphabet
+        let bad = body
+            .chars()
+            .find(|c| {
+                checksum::Engine::new()
+                    .input(c.encode_utf8(&mut [0; 4]))
+                    .is_err()
+            })
+            .unwrap_or_default();
+        return Err(PolicyError::InvalidDescriptorChar(bad));
+    }
+    Ok(engine.checksum())
+}
+
+/// Append the BIP-380 checksum to a descriptor (`desc#checksum`).
+///
+/// A descriptor that already carries a checksum is verified rather than
+/// re-stamped, so a corrupted one isn't laundered into a valid-looking one.
+pub fn with_checksum(descriptor: &str) -> Result<String, PolicyError> {
+    let descriptor = descriptor.trim();
+    if descriptor.contains('#') {
+        validate_checksum(descriptor)?;
+        return Ok(descriptor.to_string());
+    }
+    Ok(format!(
+        "{}#{}",
+        descriptor,
+        descriptor_checksum(descriptor)?
+    ))
+}
+
+/// Verify a descriptor's `#checksum`, if it has one.
+///
+/// Descriptors without a checksum pass; one that doesn't match the
+/// descriptor body is rejected with [`PolicyError::ChecksumMismatch`].
+pub fn validate_checksum(descriptor: &str) -> Result<(), PolicyError> {
+    let Some((body, found)) = descriptor.trim().split_once('#') else {
+        return Ok(());
+    };
+    let expected = descriptor_checksum(body)?;
+    if found != expected {
+        return Err(PolicyError::ChecksumMismatch {
+            expected,
+            found: found.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Who a spending path belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathRole {
+    /// Can spend at any time
+    Owner,
+    /// Can spend once a timelock has passed
+    Heir,
+}
+
+/// One way to spend an inheritance output, in plain terms
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendingPathExplanation {
+    pub role: PathRole,
+    /// Signatures required
+    pub threshold: usize,
+    /// Master fingerprints of the keys that can sign (hex)
+    pub key_fingerprints: Vec<String>,
+    /// Relative timelock in blocks; `None` if spendable immediately.
+    /// Approximate (~600 seconds per block) for a time-based lock.
+    pub timelock_blocks: Option<u32>,
+    /// The exact timelock, block- or time-based
+    pub timelock: Option<Timelock>,
+    /// Timelock at ~144 blocks per day
+    pub timelock_days: Option<f64>,
+    /// Timelock at ~4,380 blocks per month
+    pub timelock_months: Option<f64>,
+    /// One-sentence description of this path
+    pub description: String,
+}
+
+/// Plain-English structure of an inheritance descriptor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyExplanation {
+    pub kind: DescriptorKind,
+    /// Owner paths first, then heir paths by ascending timelock
+    pub paths: Vec<SpendingPathExplanation>,
+    /// The path descriptions joined into a paragraph
+    pub summary: String,
+}
+
+/// Explain who can spend under `descriptor`, and when.
+///
+/// Lifts the descriptor to its semantic policy and splits it into spending
+/// paths: paths without a timelock belong to the owner, paths behind
+/// `older(N)` to heirs. Lets owners check a policy matches their intent
+/// without reading miniscript.
+pub fn explain(descriptor: &str) -> Result<PolicyExplanation, PolicyError> {
+    let descriptor: Descriptor<DescriptorPublicKey> = descriptor.trim().parse()?;
+    let kind = DescriptorKind::of(&descriptor)?;
+    let policy = descriptor.lift()?;
+
+    let mut branches = Vec::new();
+    collect_or_branches(&policy, &mut branches);
+    let mut paths = branches
+        .into_iter()
+        .map(explain_path)
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort_by_key(|p| (p.role == PathRole::Heir, p.timelock));
+
+    let summary = paths
+        .iter()
+        .map(|p| p.description.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok(PolicyExplanation {
+        kind,
+        paths,
+        summary,
+    })
+}
+
+/// Flatten nested 1-of-n thresholds (`or`s) into alternative spending paths
+fn collect_or_branches<'a>(
+    policy: &'a Semantic<DescriptorPublicKey>,
+    out: &mut Vec<&'a Semantic<DescriptorPublicKey>>,
+) {
+    match policy {
+        Semantic::Thresh(thresh) if thresh.k() == 1 && thresh.n() > 1 => {
+            for branch in thresh.iter() {
+                collect_or_branches(branch, out);
+            }
+        }
+        other => out.push(other),
+    }
+}
+
+/// Flatten nested n-of-n thresholds (`and`s) into their conditions
+fn collect_and_conditions<'a>(
+    policy: &'a Semantic<DescriptorPublicKey>,
+    out: &mut Vec<&'a Semantic<DescriptorPublicKey>>,
+) {
+    match policy {
+        Semantic::Thresh(thresh) if thresh.k() == thresh.n() && thresh.n() > 1 => {
+            for condition in thresh.iter() {
+                collect_and_conditions(condition, out);
+            }
+        }
+        other => out.push(other),
+    }
+}
+
+fn explain_path(
+    branch: &Semantic<DescriptorPublicKey>,
+) -> Result<SpendingPathExplanation, PolicyError> {
+    let mut conditions = Vec::new();
+    collect_and_conditions(branch, &mut conditions);
+
+    let mut keys: Vec<&DescriptorPublicKey> = Vec::new();
+    let mut threshold = 0;
+    let mut timelock: Option<Timelock> = None;
+    let mut single_keys = 0;
+    let mut key_groups = 0;
+    for condition in conditions {
+        match condition {
+            Semantic::Key(key) => {
+                keys.push(key);
+                threshold += 1;
+                single_keys += 1;
+            }
+            Semantic::Older(older) => {
+                let lock = Timelock::from_older(older.to_consensus_u32())?;
+                timelock = Some(timelock.map_or(lock, |t| t.max(lock)));
+            }
+            Semantic::Thresh(thresh)
+                if thresh
+                    .iter()
+                    .all(|c| matches!(c.as_ref(), Semantic::Key(_))) =>
+            {
+                key_groups += 1;
+                threshold += thresh.k();
+                keys.extend(thresh.iter().filter_map(|c| match c.as_ref() {
+                    Semantic::Key(key) => Some(key),
+                    _ => None,
+                }));
+            }
+            other => {
+                return Err(PolicyError::Unexplainable(format!(
+                    "unsupported condition {}",
+                    other
+                )))
+            }
+        }
+    }
+    if keys.is_empty() {
+        return Err(PolicyError::Unexplainable(
+            "a spending path requires no signature".into(),
+        ));
+    }
+    // A k-of-n group mixed with other keys has no single "k of n" reading
+    if key_groups > 1 || (key_groups == 1 && single_keys > 0) {
+        return Err(PolicyError::Unexplainable(
+            "nested key thresholds are not supported".into(),
+        ));
+    }
+
+    let key_fingerprints: Vec<String> = keys
+        .iter()
+        .map(|key| key.master_fingerprint().to_string())
+        .collect();
+    let signers = if keys.len() == 1 {
+        format!("Key {}", key_fingerprints[0])
+    } else if threshold == keys.len() {
+        format!("All {} keys ({})", keys.len(), key_fingerprints.join(", "))
+    } else {
+        format!(
+            "Any {} of {} keys ({})",
+            threshold,
+            keys.len(),
+            key_fingerprints.join(", ")
+        )
+    };
+
+    // ~600 seconds per block: 144 blocks a day, 4,380 a month
+    let days = timelock.map(|t| t.seconds() as f64 / 86_400.0);
+    let months = timelock.map(|t| t.seconds() as f64 / 2_628_000.0);
+    let (role, description) = match timelock {
+        None => (
+            PathRole::Owner,
+            format!("{} can spend at any time.", signers),
+        ),
+        Some(lock) => {
+            let count = match lock.unit() {
+                TimelockUnit::Blocks => format!("{} blocks", lock.value()),
+                TimelockUnit::Time => format!("{} × 512 seconds", lock.value()),
+            };
+            (
+                PathRole::Heir,
+                format!(
+                    "{} can spend once the coins have gone {} (~{:.0} days, ~{:.1} months) without moving.",
+                    signers,
+                    count,
+                    days.unwrap_or_default(),
+                    months.unwrap_or_default()
+                ),
+            )
+        }
+    };
+
+    Ok(SpendingPathExplanation {
+        role,
+        threshold,
+        key_fingerprints,
+        timelock_blocks: timelock.map(|t| t.blocks() as u32),
+        timelock,
+        timelock_days: days,
+        timelock_months: months,
+        description,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,6 +1346,101 @@ mod tests {
         assert!(Timelock::from_blocks(0).is_err());
     }
 
+    #[test]
+    fn test_timelock_units() {
+        let blocks = Timelock::from_blocks(4_320).unwrap();
+        assert_eq!(blocks.unit(), TimelockUnit::Blocks);
+        assert_eq!(blocks.to_sequence(), Sequence::from_height(4_320));
+        assert_eq!(u32::from(blocks), 4_320);
+
+        // 30 days in 512-second intervals
+        let time = Timelock::from_seconds(30 * 86_400).unwrap();
+        assert_eq!(time.unit(), TimelockUnit::Time);
+        assert_eq!(time.value(), 5_063);
+        assert!(time.seconds() >= 30 * 86_400);
+        assert_eq!(time.blocks(), 4_320);
+        assert_eq!(
+            time.to_sequence(),
+            Sequence::from_512_second_intervals(5_063)
+        );
+        assert_eq!(u32::from(time), SEQUENCE_LOCKTIME_TYPE_FLAG | 5_063);
+        assert!(format!("{}", time).contains("5063 × 512 s"));
+
+        assert!(Timelock::from_512_second_intervals(0).is_err());
+        assert!(Timelock::from_seconds(u32::MAX).is_err());
+
+        // Ordered by duration across units
+        assert!(Timelock::from_blocks(5_000).unwrap() > time);
+        assert!(Timelock::from_blocks(4_000).unwrap() < time);
+    }
+
+    #[test]
+    fn test_timelock_unit_flag_bit() {
+        assert_eq!(TimelockUnit::of_older(26_280), TimelockUnit::Blocks);
+        assert_eq!(
+            TimelockUnit::of_older(SEQUENCE_LOCKTIME_TYPE_FLAG | 100),
+            TimelockUnit::Time
+        );
+
+        let time = Timelock::from_older(SEQUENCE_LOCKTIME_TYPE_FLAG | 100).unwrap();
+        assert_eq!(time, Timelock::from_512_second_intervals(100).unwrap());
+        assert_eq!(
+            Timelock::from_older(26_280).unwrap(),
+            Timelock::six_months()
+        );
+        // Disable flag set: not a relative timelock
+        assert!(Timelock::from_older(1 << 31).is_err());
+    }
+
+    #[test]
+    fn test_timelock_serde_keeps_block_format() {
+        assert_eq!(
+            serde_json::to_string(&Timelock::six_months()).unwrap(),
+            "26280"
+        );
+        let old: Timelock = serde_json::from_str("26280").unwrap();
+        assert_eq!(old, Timelock::six_months());
+
+        let time = Timelock::from_512_second_intervals(100).unwrap();
+        let json = serde_json::to_string(&time).unwrap();
+        assert_eq!(serde_json::from_str::<Timelock>(&json).unwrap(), time);
+    }
+
+    #[test]
+    fn test_descriptor_timelock_unit_detected() {
+        let blocks_desc =
+            InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+                .unwrap()
+                .to_wsh_descriptor()
+                .unwrap();
+        assert_eq!(
+            descriptor_timelocks(&blocks_desc).unwrap(),
+            vec![Timelock::six_months()]
+        );
+
+        let time = Timelock::from_seconds(180 * 86_400).unwrap();
+        let time_desc = InheritancePolicy::simple(owner_key(), heir_key(), time)
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+
+        // Round-trip through the string form, as a stored descriptor would
+        let parsed: Descriptor<DescriptorPublicKey> = time_desc.to_string().parse().unwrap();
+        assert!(parsed
+            .to_string()
+            .contains(&format!("older({})", u32::from(time))));
+        let timelocks = descriptor_timelocks(&parsed).unwrap();
+        assert_eq!(timelocks, vec![time]);
+        assert_eq!(timelocks[0].unit(), TimelockUnit::Time);
+
+        // Taproot leaves too
+        let tr = InheritancePolicy::simple(owner_key(), heir_key(), time)
+            .unwrap()
+            .to_tr_descriptor()
+            .unwrap();
+        assert_eq!(descriptor_timelocks(&tr).unwrap(), vec![time]);
+    }
+
     #[test]
     fn test_timelock_display() {
         let tl = Timelock::six_months();
@@ -706,4 +1721,607 @@ mod tests {
             _ => panic!("Expected multi-sig primary path"),
         }
     }
+
+    #[test]
+    fn test_simple_policy_compiles_to_tr() {
+        let policy =
+            InheritancePolicy::simple(owner_key(), heir_key_2(), Timelock::six_months()).unwrap();
+        let desc = policy.to_tr_descriptor().unwrap();
+
+        assert_eq!(DescriptorKind::of(&desc).unwrap(), DescriptorKind::Tr);
+        let desc_str = desc.to_string();
+        assert!(
+            desc_str.starts_with("tr([00000001/84'/0'/0']"),
+            "{}",
+            desc_str
+        );
+        assert!(desc_str.contains("older(26280)"), "{}", desc_str);
+    }
+
+    #[test]
+    fn test_cascade_compiles_to_tr_with_leaf_per_heir() {
+        let policy = InheritancePolicy::cascade(
+            owner_key(),
+            vec![
+                (Timelock::six_months(), PathInfo::Single(heir_key_2())),
+                (Timelock::one_year(), PathInfo::Single(heir_key_3())),
+            ],
+        )
+        .unwrap();
+        let desc = policy.to_tr_descriptor().unwrap();
+
+        match &desc {
+            Descriptor::Tr(tr) => assert_eq!(tr.iter_scripts().count(), 2),
+            _ => panic!("Expected tr() descriptor"),
+        }
+    }
+
+    #[test]
+    fn test_multisig_owner_rejected_for_tr() {
+        let policy = InheritancePolicy::multisig_owner(
+            2,
+            vec![owner_key(), heir_key()],
+            vec![(Timelock::six_months(), PathInfo::Single(heir_key_2()))],
+        )
+        .unwrap();
+
+        assert!(matches!(
+            policy.to_tr_descriptor(),
+            Err(PolicyError::Compilation(_))
+        ));
+    }
+
+    /// Index-0 witness script of a P2WSH descriptor
+    fn witness_script_of(desc: Descriptor<DescriptorPublicKey>) -> bitcoin::ScriptBuf {
+        desc.into_single_descriptors()
+            .unwrap()
+            .remove(0)
+            .at_derivation_index(0)
+            .unwrap()
+            .explicit_script()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_two_of_three_owner_compiles_to_wsh() {
+        use crate::test_utils::test_co_owner_keys;
+
+        let owner = OwnerSpec::thresh(2, test_co_owner_keys()).unwrap();
+        let policy = InheritancePolicy::simple(owner, heir_key(), Timelock::six_months()).unwrap();
+        let desc = policy.to_wsh_descriptor().unwrap();
+
+        // Same descriptor as a vault built with `multisig_owner`
+        let existing = InheritancePolicy::multisig_owner(
+            2,
+            test_co_owner_keys(),
+            vec![(Timelock::six_months(), PathInfo::Single(heir_key()))],
+        )
+        .unwrap();
+        assert_eq!(desc, existing.to_wsh_descriptor().unwrap());
+
+        let desc_str = desc.to_string();
+        assert!(desc_str.contains("multi(2,"), "{}", desc_str);
+        assert!(desc_str.contains("older(26280)"), "{}", desc_str);
+
+        let derived = desc
+            .clone()
+            .into_single_descriptors()
+            .unwrap()
+            .remove(0)
+            .at_derivation_index(0)
+            .unwrap();
+        assert!(derived.address(Network::Bitcoin).is_ok());
+        let branch = multisig_owner_branch(&witness_script_of(desc)).unwrap();
+        assert_eq!(branch.threshold, 2);
+    }
+
+    #[test]
+    fn test_existing_descriptors_compile_unchanged() {
+        use crate::test_utils::test_co_owner_keys;
+
+        // Every owner kind is left to the policy compiler, so descriptors
+        // of vaults created before k-of-n owners existed don't move
+        let policies = [
+            InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months()).unwrap(),
+            InheritancePolicy::cascade(
+                owner_key(),
+                vec![
+                    (Timelock::six_months(), PathInfo::Single(heir_key_2())),
+                    (Timelock::one_year(), PathInfo::Single(heir_key_3())),
+                ],
+            )
+            .unwrap(),
+            InheritancePolicy::multisig_owner(
+                2,
+                test_co_owner_keys(),
+                vec![(Timelock::six_months(), PathInfo::Single(heir_key()))],
+            )
+            .unwrap(),
+        ];
+        for policy in policies {
+            let compiled: Miniscript<DescriptorPublicKey, Segwitv0> =
+                policy.to_concrete_policy().compile().unwrap();
+            assert_eq!(
+                policy.to_wsh_descriptor().unwrap(),
+                Descriptor::new_wsh(compiled).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_multisig_owner_branch_layouts() {
+        use crate::test_utils::{test_co_owner_keys, test_heir_key};
+
+        let keys = test_co_owner_keys();
+        let owners = format!("multi(2,{},{},{})", keys[0], keys[1], keys[2]);
+        let heir = format!("and_v(v:pkh({}),older(26280))", test_heir_key());
+
+        for (ms, selector) in [
+            (format!("or_d({},{})", owners, heir), None),
+            (format!("or_i({},{})", owners, heir), Some(true)),
+            (format!("or_i({},{})", heir, owners), Some(false)),
+        ] {
+            let desc =
+                Descriptor::<DescriptorPublicKey>::from_str(&format!("wsh({})", ms)).unwrap();
+            assert_eq!(
+                multisig_owner_branch(&witness_script_of(desc)),
+                Some(MultisigOwnerBranch {
+                    threshold: 2,
+                    selector
+                }),
+                "{}",
+                ms
+            );
+        }
+    }
+
+    #[test]
+    fn test_owner_threshold_absent_for_single_owner() {
+        use crate::test_utils::test_co_owner_keys;
+
+        let desc = InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        assert_eq!(multisig_owner_branch(&witness_script_of(desc)), None);
+
+        // Invalid thresholds are rejected up front
+        assert!(OwnerSpec::thresh(4, test_co_owner_keys()).is_err());
+        assert!(OwnerSpec::thresh(0, test_co_owner_keys()).is_err());
+    }
+
+    #[test]
+    fn test_descriptor_kind_detection() {
+        let policy =
+            InheritancePolicy::simple(owner_key(), heir_key_2(), Timelock::six_months()).unwrap();
+        let wsh = policy.to_wsh_descriptor().unwrap();
+        assert_eq!(DescriptorKind::of(&wsh).unwrap(), DescriptorKind::Wsh);
+
+        let pkh: Descriptor<DescriptorPublicKey> = format!("pkh({})", owner_key()).parse().unwrap();
+        assert!(matches!(
+            DescriptorKind::of(&pkh),
+            Err(PolicyError::UnsupportedDescriptor(_))
+        ));
+    }
+
+    #[test]
+    fn test_claim_vsize_grows_with_threshold() {
+        let single = InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+
+        let heirs = PathInfo::multi(
+            3,
+            vec![
+                heir_key(),
+                heir_key_2(),
+                heir_key_3(),
+                spouse_key(),
+                executor_key(),
+            ],
+        )
+        .unwrap();
+        let three_of_five = InheritancePolicy::simple_with_multisig_heir(
+            owner_key(),
+            heirs,
+            Timelock::six_months(),
+        )
+        .unwrap()
+        .to_wsh_descriptor()
+        .unwrap();
+
+        let single_vsize = estimate_claim_vsize(&single).unwrap();
+        let multi_vsize = estimate_claim_vsize(&three_of_five).unwrap();
+
+        // One signature plus a small script: roughly 150-200 vB
+        assert!((120..250).contains(&single_vsize), "{}", single_vsize);
+        // Two more signatures (~36 vB each) and four more keys in the script
+        assert!(
+            multi_vsize > single_vsize + 2 * 36,
+            "{} vs {}",
+            multi_vsize,
+            single_vsize
+        );
+    }
+
+    /// A wsh descriptor chaining four 20-of-20 multisigs and a final
+    /// `last`-of-`last` one, so a satisfaction needs 84 + `last` + 1 items
+    fn multisig_chain_descriptor(last: u8) -> Descriptor<DescriptorPublicKey> {
+        let secp = bitcoin::secp256k1::Secp256k1::new();
+        let mut next_key = 0u8;
+        let mut multi = |n: u8| {
+            let keys: Vec<String> = (0..n)
+                .map(|_| {
+                    next_key += 1;
+                    let secret =
+                        bitcoin::secp256k1::SecretKey::from_slice(&[next_key; 32]).unwrap();
+                    bitcoin::secp256k1::PublicKey::from_secret_key(&secp, &secret).to_string()
+                })
+                .collect();
+            format!("multi({},{})", n, keys.join(","))
+        };
+
+        let mut ms = multi(last);
+        for _ in 0..4 {
+            ms = format!("and_v(v:{},{})", multi(20), ms);
+        }
+        let ms = Miniscript::<DescriptorPublicKey, Segwitv0>::from_str_insane(&ms).unwrap();
+        Descriptor::new_wsh(ms).unwrap()
+    }
+
+    #[test]
+    fn test_standardness_of_inheritance_policies() {
+        let policy =
+            InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months()).unwrap();
+
+        for descriptor in [
+            policy.to_wsh_descriptor().unwrap(),
+            policy.to_tr_descriptor().unwrap(),
+        ] {
+            let report = check_standardness(&descriptor).unwrap();
+            assert!(report.is_standard(), "{:?}", report.warnings);
+            assert!(report.non_standard_opcodes.is_empty());
+            assert!(report.max_witness_items >= 1);
+            assert!(report.max_satisfaction_weight > 0);
+        }
+
+        let wsh = check_standardness(&policy.to_wsh_descriptor().unwrap()).unwrap();
+        assert_eq!(wsh.kind, DescriptorKind::Wsh);
+        // Two signature checks, the CSV and a little branching
+        assert!((3..20).contains(&wsh.max_ops.unwrap()), "{:?}", wsh.max_ops);
+        assert_eq!(
+            check_standardness(&policy.to_tr_descriptor().unwrap())
+                .unwrap()
+                .max_ops,
+            None
+        );
+    }
+
+    #[test]
+    fn test_standardness_flags_too_many_witness_items() {
+        // Exactly at the limit: 4 × (20 + 1) + (15 + 1) = 100 items
+        let boundary = check_standardness(&multisig_chain_descriptor(15)).unwrap();
+        assert_eq!(boundary.max_witness_items, MAX_STANDARD_P2WSH_STACK_ITEMS);
+        assert!(boundary.max_script_size <= MAX_STANDARD_P2WSH_SCRIPT_SIZE);
+        assert!(boundary.is_standard(), "{:?}", boundary.warnings);
+
+        // One signature more: 101 items won't relay
+        let oversized = check_standardness(&multisig_chain_descriptor(16)).unwrap();
+        assert_eq!(
+            oversized.max_witness_items,
+            MAX_STANDARD_P2WSH_STACK_ITEMS + 1
+        );
+        assert!(!oversized.is_standard());
+        assert_eq!(oversized.warnings.len(), 1);
+        assert!(oversized.warnings[0].contains("101 witness items"));
+        assert!(oversized.max_satisfaction_weight > boundary.max_satisfaction_weight);
+    }
+
+    #[test]
+    fn test_verify_address_matches_descriptor() {
+        use miniscript::descriptor::DefiniteDescriptorKey;
+
+        let descriptor = InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        let receive = descriptor
+            .clone()
+            .into_single_descriptors()
+            .unwrap()
+            .remove(0);
+        let derived: Descriptor<DefiniteDescriptorKey> = receive.at_derivation_index(0).unwrap();
+        let address = derived.address(Network::Bitcoin).unwrap().to_string();
+
+        let descriptor = descriptor.to_string();
+        assert!(verify_address(&descriptor, &address, Network::Bitcoin).unwrap());
+        // Case-insensitive like any bech32 address
+        assert!(verify_address(&descriptor, &address.to_uppercase(), Network::Bitcoin).unwrap());
+        // Right script, wrong network
+        assert!(!verify_address(&descriptor, &address, Network::Testnet).unwrap());
+
+        // A later receive index only matches when asked for
+        let rotated = receive
+            .at_derivation_index(3)
+            .unwrap()
+            .address(Network::Bitcoin)
+            .unwrap()
+            .to_string();
+        assert!(!verify_address(&descriptor, &rotated, Network::Bitcoin).unwrap());
+        assert!(verify_address_at(&descriptor, &rotated, 3, Network::Bitcoin).unwrap());
+        assert!(!verify_address_at(&descriptor, &address, 3, Network::Bitcoin).unwrap());
+    }
+
+    #[test]
+    fn test_verify_address_detects_tampered_descriptor() {
+        let genuine = InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        let genuine_address = genuine
+            .into_single_descriptors()
+            .unwrap()
+            .remove(0)
+            .at_derivation_index(0)
+            .unwrap()
+            .address(Network::Bitcoin)
+            .unwrap()
+            .to_string();
+
+        // Same keys, shorter timelock: a different script and address
+        let tampered =
+            InheritancePolicy::simple(owner_key(), heir_key(), Timelock::days(1).unwrap())
+                .unwrap()
+                .to_wsh_descriptor()
+                .unwrap()
+                .to_string();
+        assert!(!verify_address(&tampered, &genuine_address, Network::Bitcoin).unwrap());
+
+        assert!(matches!(
+            verify_address(&tampered, "not-an-address", Network::Bitcoin),
+            Err(PolicyError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_explain_owner_and_threshold_heirs() {
+        // or(pk(owner), and(thresh(2, heirs...), older(26280)))
+        let heirs = PathInfo::multi(2, vec![heir_key(), heir_key_2(), heir_key_3()]).unwrap();
+        let descriptor = InheritancePolicy::simple_with_multisig_heir(
+            owner_key(),
+            heirs,
+            Timelock::six_months(),
+        )
+        .unwrap()
+        .to_wsh_descriptor()
+        .unwrap()
+        .to_string();
+
+        let explanation = explain(&descriptor).unwrap();
+        assert_eq!(explanation.kind, DescriptorKind::Wsh);
+        assert_eq!(explanation.paths.len(), 2);
+
+        let owner = &explanation.paths[0];
+        assert_eq!(owner.role, PathRole::Owner);
+        assert_eq!(owner.threshold, 1);
+        assert_eq!(owner.key_fingerprints, vec!["00000001"]);
+        assert_eq!(owner.timelock_blocks, None);
+        assert!(
+            owner.description.contains("at any time"),
+            "{}",
+            owner.description
+        );
+
+        let heir = &explanation.paths[1];
+        assert_eq!(heir.role, PathRole::Heir);
+        assert_eq!(heir.threshold, 2);
+        let mut fingerprints = heir.key_fingerprints.clone();
+        fingerprints.sort();
+        assert_eq!(fingerprints, vec!["00000002", "00000003", "00000004"]);
+        assert_eq!(heir.timelock_blocks, Some(26_280));
+        assert_eq!(heir.timelock_days, Some(182.5));
+        assert_eq!(heir.timelock_months, Some(6.0));
+        assert!(
+            heir.description.starts_with("Any 2 of 3 keys"),
+            "{}",
+            heir.description
+        );
+
+        assert!(explanation.summary.starts_with(&owner.description));
+    }
+
+    #[test]
+    fn test_explain_cascade_ordered_by_timelock() {
+        let policy = InheritancePolicy::cascade(
+            owner_key(),
+            vec![
+                (Timelock::one_year(), PathInfo::Single(heir_key_3())),
+                (Timelock::six_months(), PathInfo::Single(spouse_key())),
+            ],
+        )
+        .unwrap();
+
+        for descriptor in [
+            policy.to_wsh_descriptor().unwrap(),
+            policy.to_tr_descriptor().unwrap(),
+        ] {
+            let explanation = explain(&descriptor.to_string()).unwrap();
+            let summary: Vec<_> = explanation
+                .paths
+                .iter()
+                .map(|p| (p.role, p.key_fingerprints[0].as_str(), p.timelock_blocks))
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    (PathRole::Owner, "00000001", None),
+                    (PathRole::Heir, "00000005", Some(26_280)),
+                    (PathRole::Heir, "00000004", Some(52_560)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_heir_keys() {
+        let policy = InheritancePolicy::cascade(
+            owner_key(),
+            vec![
+                (Timelock::one_year(), PathInfo::Single(heir_key_3())),
+                (Timelock::six_months(), PathInfo::Single(spouse_key())),
+            ],
+        )
+        .unwrap();
+
+        for descriptor in [
+            policy.to_wsh_descriptor().unwrap(),
+            policy.to_tr_descriptor().unwrap(),
+        ] {
+            let mut heirs = heir_keys(&descriptor).unwrap();
+            heirs.sort_by_key(|key| key.to_string());
+            let mut expected = vec![heir_key_3(), spouse_key()];
+            expected.sort_by_key(|key| key.to_string());
+            assert_eq!(heirs, expected);
+        }
+    }
+
+    #[test]
+    fn test_explain_time_based_heir() {
+        let timelock = Timelock::from_seconds(90 * 86_400).unwrap();
+        let descriptor = InheritancePolicy::simple(owner_key(), heir_key(), timelock)
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap()
+            .to_string();
+
+        let explanation = explain(&descriptor).unwrap();
+        let heir = &explanation.paths[1];
+        assert_eq!(heir.role, PathRole::Heir);
+        assert_eq!(heir.timelock, Some(timelock));
+        assert_eq!(heir.timelock_blocks, Some(timelock.blocks() as u32));
+        assert_eq!(heir.timelock_days.unwrap().round(), 90.0);
+        assert!(
+            heir.description.contains("15188 × 512 seconds"),
+            "{}",
+            heir.description
+        );
+    }
+
+    #[test]
+    fn test_explain_rejects_unsupported_descriptors() {
+        let pkh = format!("pkh({})", owner_key());
+        assert!(matches!(
+            explain(&pkh),
+            Err(PolicyError::UnsupportedDescriptor(_))
+        ));
+        assert!(explain("not a descriptor").is_err());
+    }
+
+    #[test]
+    fn test_with_checksum_appends_bip380_checksum() {
+        // Vectors from BIP-380
+        assert_eq!(
+            with_checksum("raw(deadbeef)").unwrap(),
+            "raw(deadbeef)#89f8spxm"
+        );
+        assert_eq!(
+            with_checksum("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)").unwrap(),
+            "addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)#02wpgw69"
+        );
+
+        // Agrees with the checksum miniscript prints
+        let descriptor = InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap()
+            .to_string();
+        let (body, _) = descriptor.split_once('#').unwrap();
+        assert_eq!(with_checksum(body).unwrap(), descriptor);
+        assert_eq!(with_checksum(&descriptor).unwrap(), descriptor);
+    }
+
+    fn checksummed_policy_descriptor() -> String {
+        let descriptor = InheritancePolicy::simple(owner_key(), heir_key(), Timelock::six_months())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap();
+        with_checksum(&descriptor.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_validate_descriptor_accepts_valid() {
+        let descriptor = checksummed_policy_descriptor();
+        let parsed = parse_and_validate_descriptor(&descriptor, Network::Bitcoin).unwrap();
+        assert_eq!(DescriptorKind::of(&parsed).unwrap(), DescriptorKind::Wsh);
+        // Surrounding whitespace from a paste is tolerated
+        assert!(
+            parse_and_validate_descriptor(&format!(" {}\n", descriptor), Network::Bitcoin).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_and_validate_descriptor_rejects_bad_checksum() {
+        let descriptor = checksummed_policy_descriptor();
+        let (body, checksum) = descriptor.split_once('#').unwrap();
+        let flipped = if checksum.starts_with('q') { 'p' } else { 'q' };
+        let corrupted = format!("{}#{}{}", body, flipped, &checksum[1..]);
+        assert!(matches!(
+            parse_and_validate_descriptor(&corrupted, Network::Bitcoin),
+            Err(PolicyError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_and_validate_descriptor_rejects_wrong_network() {
+        // Mainnet xpubs on a test network
+        let descriptor = checksummed_policy_descriptor();
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            assert!(matches!(
+                parse_and_validate_descriptor(&descriptor, network),
+                Err(PolicyError::WrongNetwork { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_and_validate_descriptor_rejects_non_derivable() {
+        // A hardened wildcard can't be derived from an xpub
+        let xpub = test_xpub();
+        let descriptor = format!(
+            "wsh(or_d(pk([00000001/84'/0'/0']{}/0/*'),and_v(v:pk([00000002/84'/0'/1']{}/0/*),older(26280))))",
+            xpub, xpub
+        );
+        assert!(matches!(
+            parse_and_validate_descriptor(&descriptor, Network::Bitcoin),
+            Err(PolicyError::Derivation(_))
+        ));
+
+        // And a descriptor NoString can't spend is rejected outright
+        let wpkh = format!("wpkh([00000001/84'/0'/0']{}/0/*)", xpub);
+        assert!(matches!(
+            parse_and_validate_descriptor(&wpkh, Network::Bitcoin),
+            Err(PolicyError::UnsupportedDescriptor(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_checksum() {
+        assert!(validate_checksum("raw(deadbeef)#89f8spxm").is_ok());
+        // No checksum to check
+        assert!(validate_checksum("raw(deadbeef)").is_ok());
+
+        // Corrupted body or checksum
+        for corrupted in ["raw(deadbeee)#89f8spxm", "raw(deadbeef)#89f8spxn"] {
+            assert!(matches!(
+                validate_checksum(corrupted),
+                Err(PolicyError::ChecksumMismatch { .. })
+            ));
+            assert!(with_checksum(corrupted).is_err());
+        }
+        assert!(matches!(
+            with_checksum("raw(deadbeef\u{e9})"),
+            Err(PolicyError::InvalidDescriptorChar('\u{e9}'))
+        ));
+    }
 }
@@ -331,7 +331,7 @@ pub fn create_cascade_vault(
     let primary_timelock = recovery_scripts
         .iter()
         .map(|(tl, _)| *tl)
-        .min_by_key(|tl| tl.blocks())
+        .min()
         .expect("non-empty recovery_paths");
 
     let address = Address::p2tr(
@@ -405,7 +405,7 @@ pub fn build_heir_claim_psbt(
         .map(|(outpoint, _)| TxIn {
             previous_output: *outpoint,
             script_sig: ScriptBuf::new(),
-            sequence: Sequence::from_height(timelock.blocks()),
+            sequence: timelock.to_sequence(),
             witness: bitcoin::Witness::new(),
         })
         .collect();
@@ -615,9 +615,7 @@ fn compile_recovery_to_tapscript(
     let heir_policy = heirs.to_policy();
     let recovery_policy = Concrete::And(vec![
         Arc::new(heir_policy),
-        Arc::new(Concrete::Older(miniscript::RelLockTime::from_height(
-            timelock.blocks(),
-        ))),
+        Arc::new(Concrete::Older(timelock.to_rel_lock_time())),
     ]);
 
     let ms: Miniscript<DescriptorPublicKey, Tap> = recovery_policy
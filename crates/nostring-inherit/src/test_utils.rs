@@ -1,7 +1,8 @@
 //! Shared test utilities for nostring-inherit tests.
 //!
 //! Provides deterministic keypair generation, chain codes, and vault construction
-//! helpers used across multiple test modules.
+//! helpers used across multiple test modules. Other crates' tests get them
+//! through the `test-utils` feature.
 
 use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
 use bitcoin::Network;
@@ -37,8 +38,39 @@ pub fn test_xpub_str() -> &'static str {
     "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
 }
 
+/// Three co-owner keys of [`test_xpub_str`] for a k-of-n owner, with
+/// fingerprints `00000011`..`00000013` and distinct derivation paths.
+pub fn test_co_owner_keys() -> Vec<DescriptorPublicKey> {
+    (1..=3)
+        .map(|i| {
+            DescriptorPublicKey::from_str(&format!(
+                "[0000001{}/48'/0'/0'/2']{}/{}/<0;1>/*",
+                i,
+                test_xpub_str(),
+                i
+            ))
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Heir key of [`test_xpub_str`] with fingerprint `00000002`.
+pub fn test_heir_key() -> DescriptorPublicKey {
+    DescriptorPublicKey::from_str(&format!(
+        "[00000002/84'/0'/1']{}/0/<0;1>/*",
+        test_xpub_str()
+    ))
+    .unwrap()
+}
+
 /// Create a test vault with a single heir and the given timelock.
 pub fn make_test_vault(timelock_blocks: u16) -> InheritableVault {
+    make_test_vault_with(Timelock::from_blocks(timelock_blocks).unwrap())
+}
+
+/// Create a test vault with a single heir and any (block- or time-based)
+/// timelock.
+pub fn make_test_vault_with(timelock: Timelock) -> InheritableVault {
     let (_owner_sk, owner_pk) = test_keypair(1);
     let (_cosigner_sk, cosigner_pk) = test_keypair(2);
     let (_heir_sk, heir_pk) = test_keypair(3);
@@ -52,7 +84,7 @@ pub fn make_test_vault(timelock_blocks: u16) -> InheritableVault {
         &delegated,
         0,
         PathInfo::Single(heir_desc),
-        Timelock::from_blocks(timelock_blocks).unwrap(),
+        timelock,
         0,
         Network::Testnet,
     )
@@ -8,40 +8,53 @@ use mdk_storage_traits::MdkStorageProvider;
 use nostr::event::builder::EventBuilder;
 use nostr::{Event, EventId, Kind, PublicKey, RelayUrl, UnsignedEvent};
 
-use crate::{GroupId, MessagingClient, MessagingError};
+use crate::{Ciphersuite, GroupId, MessagingClient, MessagingError};
 
 /// Information about an MLS group.
+///
+/// Carries everything a group list needs to render, so callers don't have
+/// to query members and messages per group.
 #[derive(Clone, Debug)]
 pub struct GroupInfo {
     pub mls_group_id: GroupId,
     pub nostr_group_id: [u8; 32],
     pub name: String,
     pub description: String,
-}
-
-impl From<MdkGroup> for GroupInfo {
-    fn from(g: MdkGroup) -> Self {
-        Self {
-            mls_group_id: g.mls_group_id,
-            nostr_group_id: g.nostr_group_id,
-            name: g.name,
-            description: g.description,
-        }
-    }
+    pub ciphersuite: Ciphersuite,
+    /// Current MLS epoch (advances with every commit)
+    pub epoch: u64,
+    pub member_count: usize,
+    pub admin_pubkeys: Vec<PublicKey>,
+    /// Timestamp of the newest stored message, if any
+    pub last_message_at: Option<nostr::Timestamp>,
+    /// Messages from others since the group was last marked read
+    pub unread_count: usize,
 }
 
 /// A decrypted message from a group.
 #[derive(Clone, Debug)]
 pub struct Message {
+    pub id: EventId,
     pub sender: PublicKey,
     pub content: String,
     pub kind: Kind,
     pub created_at: nostr::Timestamp,
 }
 
+impl Message {
+    /// Where this message sits in the group's history, for paging.
+    pub fn cursor(&self) -> MessageCursor {
+        MessageCursor {
+            created_at: self.created_at,
+            id: self.id,
+        }
+    }
+}
+
 impl From<MdkMessage> for Message {
     fn from(m: MdkMessage) -> Self {
         Self {
+            id: m.id,
             sender: m.pubkey,
             content: m.content,
             kind: m.kind,
@@ -50,6 +63,17 @@ impl From<MdkMessage> for Message {
     }
 }
 
+/// Position in a group's history for
+/// [`MessagingClient::get_messages_paged`].
+///
+/// Ordered by `created_at`, then event id, so messages sent in the same
+/// second still page without gaps or repeats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MessageCursor {
+    pub created_at: nostr::Timestamp,
+    pub id: EventId,
+}
+
 /// Result of creating a group.
 pub struct GroupCreateResult {
     pub group: GroupInfo,
@@ -63,9 +87,29 @@ pub struct MessageSendResult {
     pub event: Event,
 }
 
+/// Processed event ids remembered before the oldest are forgotten.
+///
+/// Past this, MDK's own processed-message records still catch a duplicate;
+/// the cache only saves the decrypt attempt for the common relay echo.
+pub const SEEN_EVENTS_CAPACITY: usize = 10_000;
+
+/// Outcome of [`MessagingClient::process_messages`].
+#[derive(Debug, Default)]
+pub struct BatchProcessResult {
+    /// Events processed for the first time
+    pub processed: usize,
+    /// Events skipped because they were already processed
+    pub duplicates: usize,
+    /// Events that failed, with why; retry these after catching up
+    pub failed: Vec<(EventId, MessagingError)>,
+}
+
 // All group operations are generic over storage backend.
 impl<S: MdkStorageProvider> MessagingClient<S> {
     /// Create a new MLS group and invite members.
+    ///
+    /// Every key package must have been issued for this client's
+    /// ciphersuite; otherwise `UnsupportedCiphersuite` names the member.
     pub fn create_group(
         &self,
         name: &str,
@@ -74,6 +118,8 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
         member_pubkeys: Vec<PublicKey>,
         member_key_package_events: Vec<Event>,
     ) -> Result<GroupCreateResult, MessagingError> {
+        self.check_key_package_suites(&member_key_package_events)?;
+
         let image_hash: [u8; 32] = generate_random_bytes(32).try_into().unwrap();
         let image_key: [u8; 32] = generate_random_bytes(32).try_into().unwrap();
         let image_nonce: [u8; 12] = generate_random_bytes(12).try_into().unwrap();
@@ -96,11 +142,63 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
                 .create_group(&self.keys.public_key(), member_key_package_events, config)?;
 
         Ok(GroupCreateResult {
-            group: GroupInfo::from(result.group),
+            group: self.group_info(result.group)?,
             welcome_rumors: result.welcome_rumors,
         })
     }
 
+    /// Summarize an MDK group, filling in members and last activity.
+    pub(crate) fn group_info(&self, g: MdkGroup) -> Result<GroupInfo, MessagingError> {
+        let last_read = self.read.last_read_at(&g.mls_group_id);
+        self.group_info_with(g, last_read)
+    }
+
+    /// Summarize an MDK group whose read marker is already loaded.
+    ///
+    /// Members and ciphersuite come from one load of the MLS group, and
+    /// history is read only back to `last_read`.
+    pub(crate) fn group_info_with(
+        &self,
+        g: MdkGroup,
+        last_read: Option<nostr::Timestamp>,
+    ) -> Result<GroupInfo, MessagingError> {
+        let mls_group = self
+            .mdk
+            .load_mls_group(&g.mls_group_id)?
+            .ok_or_else(|| MessagingError::GroupNotFound(hex::encode(g.mls_group_id.as_slice())))?;
+        let member_count = mls_group.members().count();
+        // The suite the group was created with, not this client's default
+        let ciphersuite = mls_group.ciphersuite();
+        let (last_message_at, unread_count) = self.recent_activity(&g.mls_group_id, last_read)?;
+
+        Ok(GroupInfo {
+            mls_group_id: g.mls_group_id,
+            nostr_group_id: g.nostr_group_id,
+            name: g.name,
+            description: g.description,
+            ciphersuite,
+            epoch: g.epoch,
+            member_count,
+            admin_pubkeys: g.admin_pubkeys.into_iter().collect(),
+            last_message_at,
+            unread_count,
+        })
+    }
+
+    /// Reject key packages issued for a ciphersuite other than ours.
+    fn check_key_package_suites(&self, events: &[Event]) -> Result<(), MessagingError> {
+        for event in events {
+            let key_package = self.mdk.parse_key_package(event)?;
+            if key_package.ciphersuite() != self.ciphersuite() {
+                return Err(MessagingError::UnsupportedCiphersuite {
+                    member: event.pubkey.to_hex(),
+                    ciphersuite: self.ciphersuite(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Process a welcome rumor received via gift-wrap.
     pub fn process_welcome(
         &self,
@@ -108,7 +206,8 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
         welcome_rumor: &UnsignedEvent,
     ) -> Result<(), MessagingError> {
         self.mdk
-            .process_welcome(gift_wrap_event_id, welcome_rumor)?;
+            .process_welcome(gift_wrap_event_id, welcome_rumor)
+            .map_err(MessagingError::from_welcome_error)?;
         Ok(())
     }
 
@@ -123,14 +222,16 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
             .first()
             .ok_or_else(|| MessagingError::GroupNotFound("no pending welcomes".into()))?;
 
-        self.mdk.accept_welcome(welcome)?;
+        self.mdk
+            .accept_welcome(welcome)
+            .map_err(MessagingError::from_welcome_error)?;
 
         let groups = self.mdk.get_groups()?;
         let group = groups
             .first()
             .ok_or_else(|| MessagingError::GroupNotFound("group not found after accept".into()))?;
 
-        Ok(GroupInfo::from(group.clone()))
+        self.group_info(group.clone())
     }
 
     /// Send a text message to a group.
@@ -145,9 +246,71 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
     }
 
     /// Process a received MLS message event from relays.
+    ///
+    /// Idempotent: the same event arriving from several relays is only
+    /// processed once, and repeats return `Ok`. Fails with `EpochMismatch`
+    /// or `CannotDecrypt` when our group state is behind the sender's.
     pub fn process_message(&self, event: &Event) -> Result<(), MessagingError> {
-        self.mdk.process_message(event)?;
-        Ok(())
+        self.process_message_once(event).map(|_| ())
+    }
+
+    /// Process a batch of relay events, skipping duplicates.
+    ///
+    /// Events are handled oldest first, so commits fetched together with
+    /// the messages that follow them apply in order. One failure doesn't
+    /// stop the rest of the batch.
+    pub fn process_messages(&self, mut events: Vec<Event>) -> BatchProcessResult {
+        events.sort_by_key(|e| e.created_at);
+
+        let mut result = BatchProcessResult::default();
+        for event in &events {
+            match self.process_message_once(event) {
+                Ok(true) => result.processed += 1,
+                Ok(false) => result.duplicates += 1,
+                Err(e) => result.failed.push((event.id, e)),
+            }
+        }
+        result
+    }
+
+    /// Process `event` unless already seen; `Ok(false)` for a duplicate.
+    fn process_message_once(&self, event: &Event) -> Result<bool, MessagingError> {
+        if self.seen.contains(&event.id) {
+            return Ok(false);
+        }
+
+        let fresh = match self
+            .mdk
+            .process_message(event)
+            .map_err(MessagingError::from)
+        {
+            Ok(_) => true,
+            // Processed before the cache knew of it (e.g. since evicted)
+            Err(MessagingError::MessageAlreadyProcessed) => false,
+            Err(MessagingError::EpochMismatch { found, .. }) => {
+                return Err(MessagingError::EpochMismatch {
+                    expected: self.local_epoch(event),
+                    found,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+        self.seen.insert(event.id);
+        Ok(fresh)
+    }
+
+    /// Our epoch of the group `event` was sent to (named by its `h` tag)
+    fn local_epoch(&self, event: &Event) -> Option<u64> {
+        let nostr_group_id = event.tags.iter().find_map(|tag| match tag.as_slice() {
+            [kind, id, ..] if kind == "h" => Some(id.as_str()),
+            _ => None,
+        })?;
+        self.mdk
+            .get_groups()
+            .ok()?
+            .into_iter()
+            .find(|g| hex::encode(g.nostr_group_id) == nostr_group_id)
+            .map(|g| g.epoch)
     }
 
     /// Merge a pending commit (after adding/removing members).
@@ -220,6 +383,245 @@ mod tests {
         assert_eq!(members.len(), 2);
     }
 
+    /// Alice and Bob in a fresh group; Bob has joined via the welcome.
+    async fn joined_pair() -> (crate::InMemoryClient, crate::InMemoryClient, GroupId) {
+        let alice = create_test_client();
+        let bob = create_test_client();
+        let relay = RelayUrl::parse("ws://localhost:8080").unwrap();
+
+        let (bob_kp_encoded, bob_tags) = bob.create_key_package(vec![relay.clone()]).unwrap();
+        let bob_kp_event = EventBuilder::new(Kind::MlsKeyPackage, bob_kp_encoded)
+            .tags(bob_tags)
+            .build(bob.public_key())
+            .sign(bob.keys())
+            .await
+            .unwrap();
+
+        let result = alice
+            .create_group(
+                "sync-test",
+                "",
+                vec![relay],
+                vec![bob.public_key()],
+                vec![bob_kp_event],
+            )
+            .unwrap();
+
+        bob.process_welcome(&EventId::all_zeros(), &result.welcome_rumors[0])
+            .unwrap();
+        bob.accept_first_welcome().unwrap();
+
+        (alice, bob, result.group.mls_group_id)
+    }
+
+    #[tokio::test]
+    async fn test_out_of_sync_message_reports_behind() {
+        let (alice, bob, group_id) = joined_pair().await;
+
+        // Alice advances the epoch; Bob never sees the commit
+        alice.mdk().self_update(&group_id).unwrap();
+        alice.merge_pending_commit(&group_id).unwrap();
+
+        let msg = alice.send_message(&group_id, "from the future").unwrap();
+        let err = bob.process_message(&msg.event).unwrap_err();
+        match err {
+            // Bob is still at the epoch he joined at
+            MessagingError::EpochMismatch { expected, .. } => {
+                let bob_epoch = bob.mdk().get_groups().unwrap()[0].epoch;
+                assert_eq!(expected, Some(bob_epoch));
+            }
+            MessagingError::CannotDecrypt => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_message_ignored() {
+        let (alice, bob, group_id) = joined_pair().await;
+
+        let msg = alice.send_message(&group_id, "once").unwrap();
+        bob.process_message(&msg.event).unwrap();
+
+        // A relay echo of the same event is silently ignored
+        bob.process_message(&msg.event).unwrap();
+        assert_eq!(bob.get_messages(&group_id).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_messages_dedups_batch() {
+        let (alice, bob, group_id) = joined_pair().await;
+
+        let first = alice.send_message(&group_id, "first").unwrap().event;
+        let second = alice.send_message(&group_id, "second").unwrap().event;
+        bob.process_message(&first).unwrap();
+
+        // The same events as delivered by three relays
+        let batch = vec![second.clone(), first.clone(), second.clone(), first, second];
+        let result = bob.process_messages(batch);
+        assert_eq!(result.processed, 1);
+        assert_eq!(result.duplicates, 4);
+        assert!(result.failed.is_empty());
+        assert_eq!(bob.get_messages(&group_id).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_welcome_is_stale() {
+        let alice = create_test_client();
+        let bob = create_test_client();
+        let relay = RelayUrl::parse("ws://localhost:8080").unwrap();
+
+        let (bob_kp_encoded, bob_tags) = bob.create_key_package(vec![relay.clone()]).unwrap();
+        let bob_kp_event = EventBuilder::new(Kind::MlsKeyPackage, bob_kp_encoded)
+            .tags(bob_tags)
+            .build(bob.public_key())
+            .sign(bob.keys())
+            .await
+            .unwrap();
+
+        let result = alice
+            .create_group(
+                "stale",
+                "",
+                vec![relay],
+                vec![bob.public_key()],
+                vec![bob_kp_event],
+            )
+            .unwrap();
+
+        bob.process_welcome(&EventId::all_zeros(), &result.welcome_rumors[0])
+            .unwrap();
+        bob.accept_first_welcome().unwrap();
+
+        // The key package was consumed by the first join
+        let replay = bob
+            .process_welcome(&EventId::all_zeros(), &result.welcome_rumors[0])
+            .and_then(|_| bob.accept_first_welcome().map(|_| ()));
+        assert!(
+            matches!(
+                replay,
+                Err(MessagingError::StaleWelcome) | Err(MessagingError::GroupNotFound(_))
+            ),
+            "unexpected result: {:?}",
+            replay
+        );
+    }
+
+    async fn key_package_event(client: &crate::InMemoryClient, relay: &RelayUrl) -> Event {
+        let (encoded, tags) = client.create_key_package(vec![relay.clone()]).unwrap();
+        EventBuilder::new(Kind::MlsKeyPackage, encoded)
+            .tags(tags)
+            .build(client.public_key())
+            .sign(client.keys())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_groups_report_their_ciphersuite() {
+        let relay = RelayUrl::parse("ws://localhost:8080").unwrap();
+        let chacha = Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519;
+
+        for suite in [crate::DEFAULT_CIPHERSUITE, chacha] {
+            let alice = create_test_client().with_ciphersuite(suite);
+            let bob = create_test_client().with_ciphersuite(suite);
+            let bob_kp_event = key_package_event(&bob, &relay).await;
+
+            let result = alice
+                .create_group(
+                    "suite",
+                    "",
+                    vec![relay.clone()],
+                    vec![bob.public_key()],
+                    vec![bob_kp_event],
+                )
+                .unwrap();
+            assert_eq!(result.group.ciphersuite, suite);
+            assert_eq!(alice.get_groups().unwrap()[0].ciphersuite, suite);
+
+            bob.process_welcome(&EventId::all_zeros(), &result.welcome_rumors[0])
+                .unwrap();
+            assert_eq!(bob.accept_first_welcome().unwrap().ciphersuite, suite);
+
+            // The group keeps its suite when the client's default changes
+            let other = if suite == chacha {
+                crate::DEFAULT_CIPHERSUITE
+            } else {
+                chacha
+            };
+            let alice = alice.with_ciphersuite(other);
+            assert_eq!(alice.get_groups().unwrap()[0].ciphersuite, suite);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_group_rejects_mismatched_ciphersuite() {
+        let relay = RelayUrl::parse("ws://localhost:8080").unwrap();
+        let alice = create_test_client();
+        let bob = create_test_client()
+            .with_ciphersuite(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519);
+        let bob_kp_event = key_package_event(&bob, &relay).await;
+
+        let err = alice
+            .create_group(
+                "mismatch",
+                "",
+                vec![relay],
+                vec![bob.public_key()],
+                vec![bob_kp_event],
+            )
+            .err()
+            .unwrap();
+        match err {
+            MessagingError::UnsupportedCiphersuite {
+                member,
+                ciphersuite,
+            } => {
+                assert_eq!(member, bob.public_key().to_hex());
+                assert_eq!(ciphersuite, crate::DEFAULT_CIPHERSUITE);
+            }
+            other => panic!("expected UnsupportedCiphersuite, got {:?}", other),
+        }
+        assert!(alice.get_groups().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fresh_group_details() {
+        let alice = create_test_client();
+        let relay = RelayUrl::parse("ws://localhost:8080").unwrap();
+
+        let solo = alice
+            .create_group("solo", "", vec![relay], vec![], vec![])
+            .unwrap()
+            .group;
+        assert_eq!(solo.epoch, 0);
+        assert_eq!(solo.member_count, 1);
+        assert_eq!(solo.admin_pubkeys, vec![alice.public_key()]);
+        assert!(solo.last_message_at.is_none());
+        assert_eq!(solo.unread_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_group_details_track_members_and_activity() {
+        let (alice, bob, group_id) = joined_pair().await;
+
+        let before = alice.get_groups().unwrap();
+        assert_eq!(before.len(), 1);
+        assert_eq!(before[0].member_count, 2);
+        assert!(before[0].admin_pubkeys.contains(&alice.public_key()));
+        assert!(before[0].last_message_at.is_none());
+
+        let msg = alice.send_message(&group_id, "hi").unwrap();
+        bob.process_message(&msg.event).unwrap();
+
+        let bob_groups = bob.get_groups().unwrap();
+        assert_eq!(bob_groups[0].member_count, 2);
+        assert_eq!(bob_groups[0].epoch, before[0].epoch);
+        assert_eq!(
+            bob_groups[0].last_message_at,
+            Some(bob.get_messages(&group_id).unwrap()[0].created_at)
+        );
+    }
+
     #[test]
     fn test_empty_groups() {
         let client = create_test_client();
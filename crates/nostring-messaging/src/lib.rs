@@ -11,10 +11,17 @@
 //! `MessagingClient<S>` is generic over storage:
 //! - `InMemoryClient` — ephemeral, for testing and short-lived sessions
 //! - `PersistentClient` — encrypted SQLite, for production use
+//!
+//! # Ciphersuites
+//!
+//! Clients use [`DEFAULT_CIPHERSUITE`] unless built with
+//! [`MessagingClient::with_ciphersuite`]. Key packages are issued for the
+//! client's suite, so every group it creates or joins uses that suite.
 
 use mdk_core::prelude::*;
 use mdk_memory_storage::MdkMemoryStorage;
 use mdk_sqlite_storage::MdkSqliteStorage;
+use mdk_storage_traits::groups::Pagination;
 use mdk_storage_traits::MdkStorageProvider;
 use nostr::Keys;
 use thiserror::Error;
@@ -22,11 +29,18 @@ use thiserror::Error;
 pub mod ccd;
 pub mod groups;
 pub mod persistent;
+mod read_state;
 pub mod relay;
+mod seen_events;
 
 // Re-export key types for consumers
+pub use mdk_core::prelude::Ciphersuite;
 pub use mdk_core::GroupId;
 
+/// Ciphersuite used unless another is chosen (X25519, AES-128-GCM, Ed25519).
+pub const DEFAULT_CIPHERSUITE: Ciphersuite =
+    Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+
 #[derive(Error, Debug)]
 pub enum MessagingError {
     #[error("MLS error: {0}")]
@@ -37,18 +51,108 @@ pub enum MessagingError {
     Processing(String),
     #[error("Storage initialization failed: {0}")]
     StorageInit(String),
+    /// The message belongs to a different epoch than our local group state.
+    /// Usually we're behind: fetch and process the missing commits.
+    #[error("Epoch mismatch (expected {expected:?}, found {found:?}) — fetch missing commits")]
+    EpochMismatch {
+        /// Our epoch of the group, if the group is known
+        expected: Option<u64>,
+        /// The message's epoch, if MDK reported it
+        found: Option<u64>,
+    },
+    /// The welcome refers to a key package or group state we no longer have.
+    #[error("Welcome is stale — request a fresh invite")]
+    StaleWelcome,
+    /// The event was already processed; safe to ignore.
+    #[error("Message already processed")]
+    MessageAlreadyProcessed,
+    /// The message could not be decrypted with any known secret.
+    #[error("Cannot decrypt message")]
+    CannotDecrypt,
+    /// A member's key package was issued for a different ciphersuite.
+    #[error("Member {member} does not support ciphersuite {ciphersuite:?}")]
+    UnsupportedCiphersuite {
+        member: String,
+        ciphersuite: Ciphersuite,
+    },
+    /// Read markers couldn't be loaded or saved.
+    #[error("Read state error: {0}")]
+    ReadState(String),
+    /// The seen-event cache couldn't be opened.
+    #[error("Seen events error: {0}")]
+    SeenEvents(String),
 }
 
 impl From<mdk_core::Error> for MessagingError {
     fn from(e: mdk_core::Error) -> Self {
-        MessagingError::Mls(e.to_string())
+        use mdk_core::Error;
+        match e {
+            Error::ProcessMessageWrongEpoch { .. } => MessagingError::EpochMismatch {
+                expected: None,
+                found: first_number(&e.to_string()),
+            },
+            // Our own message echoed back by a relay; it's already stored
+            Error::CannotDecryptOwnMessage { .. } => MessagingError::MessageAlreadyProcessed,
+            Error::NIP44 { .. } => MessagingError::CannotDecrypt,
+            e => MessagingError::Mls(e.to_string()),
+        }
+    }
+}
+
+impl MessagingError {
+    /// Convert an error raised while handling a welcome.
+    ///
+    /// Any epoch or key-package failure there means the invite was built
+    /// against state we can't join from, so it's reported as `StaleWelcome`.
+    pub(crate) fn from_welcome_error(e: mdk_core::Error) -> Self {
+        use mdk_core::Error;
+        match e {
+            Error::ProcessMessageWrongEpoch { .. } | Error::KeyPackage { .. } => {
+                MessagingError::StaleWelcome
+            }
+            e => match MessagingError::from(e) {
+                MessagingError::CannotDecrypt => MessagingError::StaleWelcome,
+                other => other,
+            },
+        }
     }
+
+    /// Whether fetching more commits from relays may resolve this error.
+    pub fn is_behind(&self) -> bool {
+        matches!(self, MessagingError::EpochMismatch { .. })
+    }
+}
+
+/// First integer in `text`; MDK only reports the message's epoch in the
+/// wrong-epoch error's text.
+fn first_number(text: &str) -> Option<u64> {
+    let digits: String = text
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// Messages read from storage per query by
+/// [`MessagingClient::get_messages_paged`]
+const MESSAGE_QUERY_BATCH: usize = 50;
+
+/// Sort newest first by cursor and keep `limit` messages.
+fn finish_page(mut page: Vec<groups::Message>, limit: usize) -> Vec<groups::Message> {
+    page.sort_by_key(|m| std::cmp::Reverse(m.cursor()));
+    page.truncate(limit);
+    page
 }
 
 /// Generic messaging client over any MDK storage backend.
 pub struct MessagingClient<S: MdkStorageProvider> {
     keys: Keys,
     mdk: MDK<S>,
+    /// Recently processed event ids, so relay echoes are skipped
+    seen: seen_events::SeenEvents,
+    /// When the user last read each group (client-side only)
+    read: read_state::ReadMarkers,
 }
 
 /// In-memory messaging client (ephemeral, for testing).
@@ -60,6 +164,21 @@ pub type PersistentClient = MessagingClient<MdkSqliteStorage>;
 // === Shared methods for all storage backends ===
 
 impl<S: MdkStorageProvider> MessagingClient<S> {
+    /// Use `ciphersuite` instead of [`DEFAULT_CIPHERSUITE`].
+    ///
+    /// Chain this onto a constructor, before any key package or group is
+    /// created: existing key packages stay bound to the suite they were
+    /// issued for.
+    pub fn with_ciphersuite(mut self, ciphersuite: Ciphersuite) -> Self {
+        self.mdk.ciphersuite = ciphersuite;
+        self
+    }
+
+    /// The ciphersuite used for this client's key packages and groups.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.mdk.ciphersuite
+    }
+
     /// Get the Nostr public key for this client.
     pub fn public_key(&self) -> nostr::PublicKey {
         self.keys.public_key()
@@ -83,11 +202,16 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
 
     /// Get all groups this client is a member of.
     pub fn get_groups(&self) -> Result<Vec<groups::GroupInfo>, MessagingError> {
-        let mdk_groups = self.mdk.get_groups()?;
-        Ok(mdk_groups
+        // Every read marker in one query rather than one per group
+        let markers = self.read.all()?;
+        self.mdk
+            .get_groups()?
             .into_iter()
-            .map(groups::GroupInfo::from)
-            .collect())
+            .map(|g| {
+                let last_read = markers.get(g.mls_group_id.as_slice()).copied();
+                self.group_info_with(g, last_read)
+            })
+            .collect()
     }
 
     /// Get members of a group.
@@ -105,6 +229,127 @@ impl<S: MdkStorageProvider> MessagingClient<S> {
         Ok(msgs.into_iter().map(groups::Message::from).collect())
     }
 
+    /// Get one page of a group's history, newest first.
+    ///
+    /// Returns at most `limit` messages strictly before the `before` cursor
+    /// (or the newest messages when `before` is `None`). Pass the
+    /// [`cursor`](groups::Message::cursor) of the last message in a page as
+    /// `before` to load the next one.
+    ///
+    /// Storage is read newest first a page at a time and only until the
+    /// page is full, so old history is never loaded for recent pages.
+    pub fn get_messages_paged(
+        &self,
+        group_id: &GroupId,
+        before: Option<groups::MessageCursor>,
+        limit: usize,
+    ) -> Result<Vec<groups::Message>, MessagingError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let batch_size = limit.max(MESSAGE_QUERY_BATCH);
+        let mut page: Vec<groups::Message> = Vec::new();
+        let mut offset = 0;
+        loop {
+            let batch = self
+                .mdk
+                .get_messages(
+                    group_id,
+                    Some(Pagination::new(Some(batch_size), Some(offset))),
+                )
+                .map_err(|e| MessagingError::Processing(e.to_string()))?;
+            let fetched = batch.len();
+            offset += fetched;
+
+            for msg in batch.into_iter().map(groups::Message::from) {
+                // Storage orders by time only; keep reading through the
+                // last second of a full page so ties can be ordered by id
+                if page.len() >= limit
+                    && page
+                        .last()
+                        .is_some_and(|last| msg.created_at < last.created_at)
+                {
+                    return Ok(finish_page(page, limit));
+                }
+                if before.is_none_or(|cursor| msg.cursor() < cursor) {
+                    page.push(msg);
+                }
+            }
+            if fetched < batch_size {
+                return Ok(finish_page(page, limit));
+            }
+        }
+    }
+
+    /// Number of messages stored for a group.
+    pub fn get_message_count(&self, group_id: &GroupId) -> Result<usize, MessagingError> {
+        Ok(self.get_messages(group_id)?.len())
+    }
+
+    /// When the user last read `group_id`, if ever.
+    pub fn last_read_at(&self, group_id: &GroupId) -> Option<nostr::Timestamp> {
+        self.read.last_read_at(group_id)
+    }
+
+    /// Mark `group_id` read up to and including `timestamp`.
+    ///
+    /// Pass the `created_at` of the newest message the user has seen. The
+    /// marker never moves backwards. This is local state: other members
+    /// get no read receipt.
+    pub fn mark_read(
+        &self,
+        group_id: &GroupId,
+        timestamp: nostr::Timestamp,
+    ) -> Result<(), MessagingError> {
+        self.read.mark(group_id, timestamp)
+    }
+
+    /// Number of messages from other members newer than the read marker
+    /// (all of them if the group was never marked read).
+    pub fn unread_count(&self, group_id: &GroupId) -> Result<usize, MessagingError> {
+        let last_read = self.read.last_read_at(group_id);
+        Ok(self.recent_activity(group_id, last_read)?.1)
+    }
+
+    /// Newest message time and unread count for `group_id`.
+    ///
+    /// Storage is read newest first and only back to `last_read`, so
+    /// history the user has already read is never loaded.
+    pub(crate) fn recent_activity(
+        &self,
+        group_id: &GroupId,
+        last_read: Option<nostr::Timestamp>,
+    ) -> Result<(Option<nostr::Timestamp>, usize), MessagingError> {
+        let me = self.keys.public_key();
+        let mut last_message_at = None;
+        let mut unread = 0;
+        let mut offset = 0;
+        loop {
+            let batch = self
+                .mdk
+                .get_messages(
+                    group_id,
+                    Some(Pagination::new(Some(MESSAGE_QUERY_BATCH), Some(offset))),
+                )
+                .map_err(|e| MessagingError::Processing(e.to_string()))?;
+            let fetched = batch.len();
+            offset += fetched;
+
+            for msg in batch.into_iter().map(groups::Message::from) {
+                last_message_at = last_message_at.max(Some(msg.created_at));
+                if last_read.is_some_and(|at| msg.created_at <= at) {
+                    return Ok((last_message_at, unread));
+                }
+                if msg.sender != me {
+                    unread += 1;
+                }
+            }
+            if fetched < MESSAGE_QUERY_BATCH {
+                return Ok((last_message_at, unread));
+            }
+        }
+    }
+
     /// Get the underlying MDK instance (for advanced operations).
     pub fn mdk(&self) -> &MDK<S> {
         &self.mdk
@@ -119,26 +364,183 @@ impl MessagingClient<MdkMemoryStorage> {
         Self {
             keys,
             mdk: MDK::new(MdkMemoryStorage::default()),
+            seen: seen_events::SeenEvents::in_memory(),
+            read: read_state::ReadMarkers::in_memory(),
         }
     }
 }
 
+// === Storage key resolution ===
+
+/// Environment variable holding a hex-encoded 32-byte database key, used
+/// when the platform keyring is unavailable.
+pub const DB_KEY_ENV_VAR: &str = "NOSTRING_DB_KEY";
+
+/// Where the persistent store's encryption key came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKeySource {
+    /// The platform keyring (the normal case)
+    Keyring,
+    /// The [`DB_KEY_ENV_VAR`] environment variable
+    Environment,
+    /// Derived from a passphrase the user was prompted for
+    Prompt,
+}
+
+/// Length of the Argon2 salt for passphrase-derived keys
+const PASSPHRASE_SALT_LEN: usize = 16;
+
+/// Try each key source in order: keyring, `env_key`, then `prompt`.
+///
+/// `keyring` opens the store through the platform keyring; `open_with_key`
+/// opens it with an explicit key. `salt` is only asked for once a
+/// passphrase was given. Every failure is collected so the final
+/// `StorageInit` error says why each source was skipped.
+pub(crate) fn resolve_storage<T>(
+    keyring: impl FnOnce() -> Result<T, String>,
+    env_key: Option<String>,
+    prompt: Option<impl FnOnce() -> Option<String>>,
+    salt: impl FnOnce() -> Result<[u8; PASSPHRASE_SALT_LEN], String>,
+    open_with_key: impl Fn([u8; 32]) -> Result<T, String>,
+) -> Result<(T, StorageKeySource), MessagingError> {
+    let mut failures = Vec::new();
+
+    match keyring() {
+        Ok(storage) => return Ok((storage, StorageKeySource::Keyring)),
+        Err(e) => failures.push(format!("keyring: {}", e)),
+    }
+
+    match env_key.as_deref().map(parse_hex_key) {
+        Some(Ok(key)) => match open_with_key(key) {
+            Ok(storage) => return Ok((storage, StorageKeySource::Environment)),
+            Err(e) => failures.push(format!("{}: {}", DB_KEY_ENV_VAR, e)),
+        },
+        Some(Err(e)) => failures.push(format!("{}: {}", DB_KEY_ENV_VAR, e)),
+        None => failures.push(format!("{}: not set", DB_KEY_ENV_VAR)),
+    }
+
+    match prompt.and_then(|prompt| prompt()) {
+        Some(passphrase) => {
+            let key = salt().and_then(|salt| derive_passphrase_key(&passphrase, &salt));
+            match key.and_then(&open_with_key) {
+                Ok(storage) => return Ok((storage, StorageKeySource::Prompt)),
+                Err(e) => failures.push(format!("passphrase: {}", e)),
+            }
+        }
+        None => failures.push("passphrase: none given".to_string()),
+    }
+
+    Err(MessagingError::StorageInit(format!(
+        "no usable database key ({})",
+        failures.join("; ")
+    )))
+}
+
+/// Parse a hex-encoded 32-byte key.
+fn parse_hex_key(hex_key: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(hex_key.trim()).map_err(|e| format!("invalid hex: {}", e))?;
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| format!("expected 32 bytes, got {}", b.len()))
+}
+
+/// Derive a database key from a passphrase and salt with Argon2id.
+fn derive_passphrase_key(
+    passphrase: &str,
+    salt: &[u8; PASSPHRASE_SALT_LEN],
+) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+/// Load the passphrase salt kept next to the database at `db_path`.
+///
+/// The first call writes a fresh random salt to `<db_path>.salt`; later
+/// calls read it back, so the same passphrase reopens the same store.
+pub(crate) fn passphrase_salt(
+    db_path: &std::path::Path,
+) -> Result<[u8; PASSPHRASE_SALT_LEN], String> {
+    use rand::RngCore;
+
+    let mut salt_path = db_path.as_os_str().to_owned();
+    salt_path.push(".salt");
+    let salt_path = std::path::PathBuf::from(salt_path);
+
+    match std::fs::read(&salt_path) {
+        Ok(bytes) => bytes.try_into().map_err(|b: Vec<u8>| {
+            format!(
+                "{}: expected {} bytes, got {}",
+                salt_path.display(),
+                PASSPHRASE_SALT_LEN,
+                b.len()
+            )
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut salt = [0u8; PASSPHRASE_SALT_LEN];
+            rand::rngs::OsRng.fill_bytes(&mut salt);
+            std::fs::write(&salt_path, salt)
+                .map_err(|e| format!("{}: {}", salt_path.display(), e))?;
+            Ok(salt)
+        }
+        Err(e) => Err(format!("{}: {}", salt_path.display(), e)),
+    }
+}
+
 // === Persistent constructors ===
 
 impl MessagingClient<MdkSqliteStorage> {
     /// Open or create a persistent messaging store with platform keyring.
+    ///
+    /// Falls back to [`DB_KEY_ENV_VAR`] when the keyring is unavailable
+    /// (headless Linux, containers).
     pub fn open<P: AsRef<std::path::Path>>(
         keys: Keys,
         db_path: P,
         service_id: &str,
         db_key_id: &str,
     ) -> Result<Self, MessagingError> {
-        let storage = MdkSqliteStorage::new(db_path, service_id, db_key_id)
-            .map_err(|e| MessagingError::StorageInit(e.to_string()))?;
-        Ok(Self {
+        Self::open_with_fallback(
             keys,
-            mdk: MDK::new(storage),
-        })
+            db_path,
+            service_id,
+            db_key_id,
+            None::<fn() -> Option<String>>,
+        )
+        .map(|(client, _)| client)
+    }
+
+    /// Open a persistent store, falling back when the keyring is unavailable.
+    ///
+    /// Tries the platform keyring, then [`DB_KEY_ENV_VAR`], then a key
+    /// derived from the passphrase `prompt` returns and a random salt stored
+    /// beside the database. Reports which source was used so callers can
+    /// tell users their key isn't in the keyring.
+    pub fn open_with_fallback<P: AsRef<std::path::Path>>(
+        keys: Keys,
+        db_path: P,
+        service_id: &str,
+        db_key_id: &str,
+        prompt: Option<impl FnOnce() -> Option<String>>,
+    ) -> Result<(Self, StorageKeySource), MessagingError> {
+        let db_path = db_path.as_ref();
+        let (storage, source) = resolve_storage(
+            || MdkSqliteStorage::new(db_path, service_id, db_key_id).map_err(|e| e.to_string()),
+            std::env::var(DB_KEY_ENV_VAR).ok(),
+            prompt,
+            || passphrase_salt(db_path),
+            |key| {
+                let config = mdk_sqlite_storage::EncryptionConfig::new(key);
+                MdkSqliteStorage::new_with_key(db_path, config).map_err(|e| e.to_string())
+            },
+        )?;
+        let key = read_state::state_key(&keys);
+        Ok((
+            Self::with_client_state(keys, storage, db_path, Some(&key)),
+            source,
+        ))
     }
 
     /// Open with an explicit encryption key (for environments without keyring).
@@ -148,12 +550,15 @@ impl MessagingClient<MdkSqliteStorage> {
         encryption_key: [u8; 32],
     ) -> Result<Self, MessagingError> {
         let config = mdk_sqlite_storage::EncryptionConfig::new(encryption_key);
-        let storage = MdkSqliteStorage::new_with_key(db_path, config)
+        let storage = MdkSqliteStorage::new_with_key(db_path.as_ref(), config)
             .map_err(|e| MessagingError::StorageInit(e.to_string()))?;
-        Ok(Self {
+        let key = read_state::state_key(&keys);
+        Ok(Self::with_client_state(
             keys,
-            mdk: MDK::new(storage),
-        })
+            storage,
+            db_path.as_ref(),
+            Some(&key),
+        ))
     }
 
     /// Open without encryption (for testing only).
@@ -162,11 +567,225 @@ impl MessagingClient<MdkSqliteStorage> {
         keys: Keys,
         db_path: P,
     ) -> Result<Self, MessagingError> {
-        let storage = MdkSqliteStorage::new_unencrypted(db_path)
+        let storage = MdkSqliteStorage::new_unencrypted(db_path.as_ref())
             .map_err(|e| MessagingError::StorageInit(e.to_string()))?;
-        Ok(Self {
+        Ok(Self::with_client_state(
+            keys,
+            storage,
+            db_path.as_ref(),
+            None,
+        ))
+    }
+
+    /// Wrap an opened store, loading the client state kept beside it.
+    ///
+    /// Read markers and seen event ids are conveniences: if their database
+    /// can't be opened the client still uses the store, with in-memory
+    /// state for this session.
+    fn with_client_state(
+        keys: Keys,
+        storage: MdkSqliteStorage,
+        db_path: &std::path::Path,
+        key: Option<&[u8; 32]>,
+    ) -> Self {
+        let state = seen_events::SeenEvents::open(db_path, key)
+            .and_then(|seen| read_state::ReadMarkers::open(db_path, key).map(|read| (seen, read)));
+        let (seen, read) = state.unwrap_or_else(|e| {
+            log::warn!(
+                "Client state beside {} unavailable, keeping it in memory: {}",
+                db_path.display(),
+                e
+            );
+            (
+                seen_events::SeenEvents::in_memory(),
+                read_state::ReadMarkers::in_memory(),
+            )
+        });
+        Self {
             keys,
             mdk: MDK::new(storage),
-        })
+            seen,
+            read,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdk_storage_traits::test_utils::crypto_utils::generate_random_bytes;
+    use nostr::event::builder::EventBuilder;
+    use nostr::{EventId, Kind, RelayUrl, Timestamp};
+
+    #[test]
+    fn test_mls_error_kinds() {
+        assert!(matches!(
+            MessagingError::from(mdk_core::Error::GroupNotFound),
+            MessagingError::Mls(_)
+        ));
+        assert!(matches!(
+            MessagingError::from_welcome_error(mdk_core::Error::KeyPackage(
+                "no matching key package".into()
+            )),
+            MessagingError::StaleWelcome
+        ));
+        assert!(MessagingError::EpochMismatch {
+            expected: Some(3),
+            found: None
+        }
+        .is_behind());
+        assert_eq!(first_number("wrong epoch: 7"), Some(7));
+        assert_eq!(first_number("wrong epoch"), None);
+        assert!(!MessagingError::CannotDecrypt.is_behind());
+    }
+
+    /// Alice and Bob in a fresh group; Bob has joined via the welcome.
+    async fn paired_group(name: &str) -> (InMemoryClient, InMemoryClient, GroupId) {
+        let alice = InMemoryClient::new(Keys::generate());
+        let bob = InMemoryClient::new(Keys::generate());
+        let relay = RelayUrl::parse("ws://localhost:8080").unwrap();
+
+        let (bob_kp, bob_tags) = bob.create_key_package(vec![relay.clone()]).unwrap();
+        let bob_kp_event = EventBuilder::new(Kind::MlsKeyPackage, bob_kp)
+            .tags(bob_tags)
+            .build(bob.public_key())
+            .sign(bob.keys())
+            .await
+            .unwrap();
+
+        let image_hash: [u8; 32] = generate_random_bytes(32).try_into().unwrap();
+        let image_key: [u8; 32] = generate_random_bytes(32).try_into().unwrap();
+        let image_nonce: [u8; 12] = generate_random_bytes(12).try_into().unwrap();
+        let config = NostrGroupConfigData::new(
+            name.into(),
+            "".into(),
+            Some(image_hash),
+            Some(image_key),
+            Some(image_nonce),
+            vec![relay],
+            vec![alice.public_key(), bob.public_key()],
+        );
+        let created = alice
+            .mdk()
+            .create_group(&alice.public_key(), vec![bob_kp_event], config)
+            .unwrap();
+        let group_id = created.group.mls_group_id;
+
+        bob.mdk()
+            .process_welcome(&EventId::all_zeros(), &created.welcome_rumors[0])
+            .unwrap();
+        bob.accept_first_welcome().unwrap();
+
+        (alice, bob, group_id)
+    }
+
+    /// Alice sends `content` to the group with a fixed `created_at`; Bob
+    /// processes it.
+    fn send_at(
+        alice: &InMemoryClient,
+        bob: &InMemoryClient,
+        group_id: &GroupId,
+        content: String,
+        created_at: u64,
+    ) {
+        let rumor = EventBuilder::new(Kind::Custom(9), content)
+            .custom_created_at(Timestamp::from(created_at))
+            .build(alice.public_key());
+        let event = alice.mdk().create_message(group_id, rumor).unwrap();
+        bob.process_message(&event).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_paged() {
+        let (alice, bob, group_id) = paired_group("paging").await;
+
+        // 50 messages one second apart so page boundaries are unambiguous
+        let base = 1_700_000_000u64;
+        for i in 0..50u64 {
+            send_at(&alice, &bob, &group_id, format!("msg {}", i), base + i);
+        }
+
+        assert_eq!(bob.get_message_count(&group_id).unwrap(), 50);
+
+        // First page: the 20 newest, newest first
+        let page1 = bob.get_messages_paged(&group_id, None, 20).unwrap();
+        assert_eq!(page1.len(), 20);
+        assert_eq!(page1[0].content, "msg 49");
+        assert_eq!(page1[19].content, "msg 30");
+
+        // Walk the rest using the oldest message of each page as the cursor
+        let page2 = bob
+            .get_messages_paged(&group_id, Some(page1[19].cursor()), 20)
+            .unwrap();
+        assert_eq!(page2.len(), 20);
+        assert_eq!(page2[0].content, "msg 29");
+        assert_eq!(page2[19].content, "msg 10");
+
+        let page3 = bob
+            .get_messages_paged(&group_id, Some(page2[19].cursor()), 20)
+            .unwrap();
+        assert_eq!(page3.len(), 10);
+        assert_eq!(page3[0].content, "msg 9");
+        assert_eq!(page3[9].content, "msg 0");
+
+        let past_end = bob
+            .get_messages_paged(&group_id, Some(page3[9].cursor()), 20)
+            .unwrap();
+        assert!(past_end.is_empty());
+
+        assert!(bob
+            .get_messages_paged(&group_id, None, 0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_paged_same_second() {
+        let (alice, bob, group_id) = paired_group("same-second").await;
+        for i in 0..5 {
+            send_at(&alice, &bob, &group_id, format!("msg {}", i), 1_700_000_000);
+        }
+
+        // A time-only cursor would drop the rest of the second
+        let mut seen = Vec::new();
+        let mut before = None;
+        loop {
+            let page = bob.get_messages_paged(&group_id, before, 2).unwrap();
+            let Some(last) = page.last() else { break };
+            before = Some(last.cursor());
+            seen.extend(page.iter().map(|m| m.content.clone()));
+        }
+        seen.sort();
+        assert_eq!(seen, ["msg 0", "msg 1", "msg 2", "msg 3", "msg 4"]);
+    }
+
+    #[tokio::test]
+    async fn test_unread_count_after_mark_read() {
+        let (alice, bob, group_id) = paired_group("unread").await;
+
+        let base = 1_700_000_000u64;
+        for i in 0..3u64 {
+            send_at(&alice, &bob, &group_id, format!("msg {}", i), base + i);
+        }
+        assert_eq!(bob.last_read_at(&group_id), None);
+        assert_eq!(bob.unread_count(&group_id).unwrap(), 3);
+
+        // Read up to the second message
+        let second = bob
+            .get_messages(&group_id)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.content == "msg 1")
+            .unwrap();
+        bob.mark_read(&group_id, second.created_at).unwrap();
+        assert_eq!(bob.unread_count(&group_id).unwrap(), 1);
+        assert_eq!(bob.get_groups().unwrap()[0].unread_count, 1);
+
+        // An older mark doesn't un-read anything
+        bob.mark_read(&group_id, Timestamp::from(base)).unwrap();
+        assert_eq!(bob.last_read_at(&group_id), Some(second.created_at));
+
+        // Our own messages never count as unread
+        assert_eq!(alice.unread_count(&group_id).unwrap(), 0);
     }
 }
@@ -1,7 +1,9 @@
 //! Persistent storage tests.
 //!
-//! The `PersistentClient` type alias and constructors (`open`, `open_with_key`,
-//! `open_unencrypted`) live in `lib.rs` on `MessagingClient<MdkSqliteStorage>`.
+//! The `PersistentClient` type alias and constructors (`open`,
+//! `open_with_fallback`, `open_with_key`, `open_unencrypted`) live in
+//! `lib.rs` on `MessagingClient<MdkSqliteStorage>`, along with the storage
+//! key fallback chain.
 //! This module contains tests for persistent storage behavior.
 
 #[cfg(test)]
@@ -144,6 +146,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encrypted_read_markers_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("encrypted.db");
+        let keys = Keys::generate();
+        let enc_key = [0x42u8; 32];
+        let group = crate::GroupId::from_slice(&[7u8; 32]);
+
+        {
+            let client = PersistentClient::open_with_key(keys.clone(), &db_path, enc_key).unwrap();
+            client
+                .mark_read(&group, nostr::Timestamp::from(200))
+                .unwrap();
+        }
+
+        let client = PersistentClient::open_with_key(keys.clone(), &db_path, enc_key).unwrap();
+        assert_eq!(
+            client.last_read_at(&group),
+            Some(nostr::Timestamp::from(200))
+        );
+        drop(client);
+
+        // Client state the keys can't open doesn't cost us the store
+        let stranger = Keys::generate();
+        let client = PersistentClient::open_with_key(stranger, &db_path, enc_key).unwrap();
+        assert!(client.get_groups().unwrap().is_empty());
+        assert_eq!(client.last_read_at(&group), None);
+        client
+            .mark_read(&group, nostr::Timestamp::from(300))
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_persistent_encrypted_group_survives_reopen() {
         use mdk_core::prelude::*;
@@ -214,4 +248,138 @@ mod tests {
             assert_eq!(msgs[0].content, "encrypted hello");
         }
     }
+
+    // === Storage key fallback chain ===
+
+    use crate::{passphrase_salt, resolve_storage, MessagingError, StorageKeySource};
+
+    /// A keyring that is never available, as on headless Linux
+    fn broken_keyring() -> Result<[u8; 32], String> {
+        Err("no secret service".to_string())
+    }
+
+    /// A fixed passphrase salt
+    fn salt() -> Result<[u8; 16], String> {
+        Ok([9u8; 16])
+    }
+
+    /// "Opens" storage by handing back the key it was given
+    fn echo_key(key: [u8; 32]) -> Result<[u8; 32], String> {
+        Ok(key)
+    }
+
+    #[test]
+    fn test_keyring_preferred_when_available() {
+        let (key, source) = resolve_storage(
+            || Ok([7u8; 32]),
+            Some("11".repeat(32)),
+            Some(|| Some("passphrase".to_string())),
+            salt,
+            echo_key,
+        )
+        .unwrap();
+        assert_eq!(source, StorageKeySource::Keyring);
+        assert_eq!(key, [7u8; 32]);
+    }
+
+    #[test]
+    fn test_env_key_used_when_keyring_fails() {
+        let mut prompted = false;
+        let (key, source) = resolve_storage(
+            broken_keyring,
+            Some("11".repeat(32)),
+            Some(|| {
+                prompted = true;
+                Some("passphrase".to_string())
+            }),
+            salt,
+            echo_key,
+        )
+        .unwrap();
+        assert_eq!(source, StorageKeySource::Environment);
+        assert_eq!(key, [0x11u8; 32]);
+        assert!(!prompted, "prompt must not be shown when the env key works");
+    }
+
+    #[test]
+    fn test_prompt_used_when_env_key_missing_or_invalid() {
+        for env_key in [None, Some("not-hex".to_string()), Some("11".repeat(16))] {
+            let (key, source) = resolve_storage(
+                broken_keyring,
+                env_key,
+                Some(|| Some("correct horse".to_string())),
+                salt,
+                echo_key,
+            )
+            .unwrap();
+            assert_eq!(source, StorageKeySource::Prompt);
+            assert_ne!(key, [0u8; 32]);
+        }
+
+        // Same passphrase and salt → same key
+        let derive = |salt: [u8; 16]| {
+            resolve_storage(
+                broken_keyring,
+                None,
+                Some(|| Some("correct horse".to_string())),
+                || Ok(salt),
+                echo_key,
+            )
+            .unwrap()
+            .0
+        };
+        assert_eq!(derive([1u8; 16]), derive([1u8; 16]));
+        assert_ne!(derive([1u8; 16]), derive([2u8; 16]));
+    }
+
+    #[test]
+    fn test_all_sources_failing_is_storage_init_error() {
+        let result = resolve_storage(
+            broken_keyring,
+            None,
+            None::<fn() -> Option<String>>,
+            salt,
+            echo_key,
+        );
+        match result {
+            Err(MessagingError::StorageInit(msg)) => {
+                assert!(msg.contains("keyring"));
+                assert!(msg.contains("NOSTRING_DB_KEY"));
+                assert!(msg.contains("passphrase"));
+            }
+            other => panic!("expected StorageInit, got {:?}", other.map(|(_, s)| s)),
+        }
+    }
+
+    #[test]
+    fn test_passphrase_salt_is_random_and_stored() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("messages.db");
+        let other = dir.path().join("other.db");
+
+        let salt = passphrase_salt(&db).unwrap();
+        assert!(dir.path().join("messages.db.salt").exists());
+        assert_eq!(
+            passphrase_salt(&db).unwrap(),
+            salt,
+            "reopen reuses the salt"
+        );
+        assert_ne!(passphrase_salt(&other).unwrap(), salt);
+
+        std::fs::write(dir.path().join("messages.db.salt"), [0u8; 3]).unwrap();
+        assert!(passphrase_salt(&db).is_err());
+    }
+
+    #[test]
+    fn test_salt_not_touched_without_passphrase() {
+        let (_, source) = resolve_storage(
+            broken_keyring,
+            Some("11".repeat(32)),
+            None::<fn() -> Option<String>>,
+            || -> Result<[u8; 16], String> { panic!("salt read without a passphrase") },
+            echo_key,
+        )
+        .unwrap();
+        assert_eq!(source, StorageKeySource::Environment);
+    }
 }
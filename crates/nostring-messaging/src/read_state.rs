@@ -0,0 +1,208 @@
+//! Per-group read markers, for unread badges.
+//!
+//! Client-side state only: nothing here touches MLS or is shared with the
+//! other members. In-memory clients keep their markers in an in-memory
+//! SQLite database; persistent clients keep them in an encrypted state
+//! database beside the message store (see [`open_state`]), so no group ids
+//! sit in plaintext next to it and MDK's own file is left alone.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use nostr::hashes::{hmac, sha256, Hash, HashEngine};
+use nostr::{Keys, Timestamp};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{GroupId, MessagingError};
+
+pub(crate) fn read_state_error(e: rusqlite::Error) -> MessagingError {
+    MessagingError::ReadState(e.to_string())
+}
+
+/// Domain separator for [`state_key`]
+const STATE_KEY_CONTEXT: &[u8] = b"nostring-messaging/client-state/v1";
+
+/// SQLCipher key for the client-state database of `keys`' store.
+///
+/// Derived from the Nostr secret key the client is opened with, which is
+/// in hand whichever source unlocked MDK's store, so the keyring is never
+/// read a second time.
+pub(crate) fn state_key(keys: &Keys) -> [u8; 32] {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(STATE_KEY_CONTEXT);
+    engine.input(&keys.secret_key().to_secret_bytes());
+    hmac::Hmac::<sha256::Hash>::from_engine(engine).to_byte_array()
+}
+
+/// Path of the client-state database for the message store at `db_path`.
+pub(crate) fn state_path(db_path: &Path) -> std::path::PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".state");
+    path.into()
+}
+
+/// Connect to the client-state database beside the message store at
+/// `db_path`.
+///
+/// `key` is its SQLCipher key (see [`state_key`]); `None` leaves it
+/// unencrypted, for tests only. A wrong key fails here rather than on the
+/// first query.
+pub(crate) fn open_state(db_path: &Path, key: Option<&[u8; 32]>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(state_path(db_path))?;
+    if let Some(key) = key {
+        conn.execute_batch(&format!("PRAGMA key = \"x'{}'\";", hex::encode(key)))?;
+    }
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+    Ok(conn)
+}
+
+/// When the user last read each group
+#[derive(Debug)]
+pub(crate) struct ReadMarkers {
+    conn: Mutex<Connection>,
+}
+
+impl ReadMarkers {
+    /// Markers kept in memory only.
+    pub(crate) fn in_memory() -> Self {
+        let conn = Connection::open_in_memory().expect("in-memory SQLite");
+        Self::init(conn).expect("in-memory read markers")
+    }
+
+    /// Open the markers table of the client state beside `db_path`.
+    ///
+    /// `key` is the state database's SQLCipher key; `None` for tests.
+    pub(crate) fn open(db_path: &Path, key: Option<&[u8; 32]>) -> Result<Self, MessagingError> {
+        Self::init(open_state(db_path, key).map_err(read_state_error)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, MessagingError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS nostring_read_markers (
+                group_id BLOB PRIMARY KEY,
+                last_read_at INTEGER NOT NULL
+            );",
+        )
+        .map_err(read_state_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// When `group_id` was last marked read, if ever.
+    pub(crate) fn last_read_at(&self, group_id: &GroupId) -> Option<Timestamp> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT last_read_at FROM nostring_read_markers WHERE group_id = ?1",
+            params![group_id.as_slice()],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .ok()
+        .flatten()
+        .map(|secs| Timestamp::from(secs as u64))
+    }
+
+    /// Every group's marker, keyed by MLS group id, in one query.
+    pub(crate) fn all(&self) -> Result<HashMap<Vec<u8>, Timestamp>, MessagingError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT group_id, last_read_at FROM nostring_read_markers")
+            .map_err(read_state_error)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, Vec<u8>>(0)?,
+                    Timestamp::from(row.get::<_, i64>(1)? as u64),
+                ))
+            })
+            .map_err(read_state_error)?;
+        rows.collect::<Result<_, _>>().map_err(read_state_error)
+    }
+
+    /// Mark `group_id` read up to `timestamp`.
+    ///
+    /// Markers only move forward, so a stale mark (say, from a view that
+    /// rendered before newer messages arrived) never un-reads anything.
+    pub(crate) fn mark(
+        &self,
+        group_id: &GroupId,
+        timestamp: Timestamp,
+    ) -> Result<(), MessagingError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO nostring_read_markers (group_id, last_read_at) VALUES (?1, ?2)
+             ON CONFLICT(group_id) DO UPDATE
+             SET last_read_at = MAX(last_read_at, excluded.last_read_at)",
+            params![group_id.as_slice(), timestamp.as_u64() as i64],
+        )
+        .map_err(read_state_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers_survive_reopen_and_only_advance() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("messages.db");
+        let group = GroupId::from_slice(&[7u8; 32]);
+        let other = GroupId::from_slice(&[9u8; 32]);
+
+        {
+            let markers = ReadMarkers::open(&db_path, None).unwrap();
+            assert_eq!(markers.last_read_at(&group), None);
+            markers.mark(&group, Timestamp::from(200)).unwrap();
+            markers.mark(&group, Timestamp::from(100)).unwrap();
+            markers.mark(&other, Timestamp::from(50)).unwrap();
+        }
+
+        let markers = ReadMarkers::open(&db_path, None).unwrap();
+        assert_eq!(markers.last_read_at(&group), Some(Timestamp::from(200)));
+        assert_eq!(markers.last_read_at(&other), Some(Timestamp::from(50)));
+
+        let all = markers.all().unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[group.as_slice()], Timestamp::from(200));
+        assert_eq!(all[other.as_slice()], Timestamp::from(50));
+
+        // Nothing is written into MDK's own file
+        assert!(!db_path.exists());
+        assert!(state_path(&db_path).exists());
+    }
+
+    #[test]
+    fn test_encrypted_markers_need_the_same_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("messages.db");
+        let keys = Keys::generate();
+        let key = state_key(&keys);
+        let group = GroupId::from_slice(&[7u8; 32]);
+
+        {
+            let markers = ReadMarkers::open(&db_path, Some(&key)).unwrap();
+            markers.mark(&group, Timestamp::from(200)).unwrap();
+        }
+
+        // Not readable as plain SQLite
+        assert!(ReadMarkers::open(&db_path, None).is_err());
+        // Nor under another identity's key
+        let other = state_key(&Keys::generate());
+        assert!(ReadMarkers::open(&db_path, Some(&other)).is_err());
+
+        let markers = ReadMarkers::open(&db_path, Some(&state_key(&keys))).unwrap();
+        assert_eq!(markers.last_read_at(&group), Some(Timestamp::from(200)));
+    }
+
+    #[test]
+    fn test_in_memory_markers() {
+        let markers = ReadMarkers::in_memory();
+        let group = GroupId::from_slice(&[7u8; 32]);
+        assert_eq!(markers.last_read_at(&group), None);
+        markers.mark(&group, Timestamp::from(10)).unwrap();
+        assert_eq!(markers.last_read_at(&group), Some(Timestamp::from(10)));
+    }
+}
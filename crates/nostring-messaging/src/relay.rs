@@ -176,12 +176,11 @@ impl RelayMessagingClient {
                 .await
                 .map_err(|e| MessagingError::Processing(format!("sync failed: {e}")))?;
 
-            for event in events {
-                if event.pubkey == self.inner.public_key() {
-                    continue;
-                }
-                let _ = self.inner.process_message(&event);
-            }
+            let theirs: Vec<Event> = events
+                .into_iter()
+                .filter(|event| event.pubkey != self.inner.public_key())
+                .collect();
+            self.inner.process_messages(theirs);
 
             let messages = self.inner.get_messages(&group.mls_group_id)?;
             if !messages.is_empty() {
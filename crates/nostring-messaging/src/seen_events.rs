@@ -0,0 +1,135 @@
+//! Recently processed Nostr event ids, so relay echoes are skipped.
+//!
+//! Kept next to the read markers: in an in-memory SQLite database for
+//! in-memory clients, and in the encrypted client-state database beside
+//! the message store for persistent ones, so the cache survives a restart. It is only a cache:
+//! MDK's own processed-message records still catch anything it misses.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use nostr::EventId;
+use rusqlite::{params, Connection};
+
+use crate::groups::SEEN_EVENTS_CAPACITY;
+use crate::read_state::open_state;
+use crate::MessagingError;
+
+fn seen_events_error(e: rusqlite::Error) -> MessagingError {
+    MessagingError::SeenEvents(e.to_string())
+}
+
+/// Bounded set of processed event ids, oldest evicted first
+#[derive(Debug)]
+pub(crate) struct SeenEvents {
+    conn: Mutex<Connection>,
+}
+
+impl SeenEvents {
+    /// Ids kept in memory only.
+    pub(crate) fn in_memory() -> Self {
+        let conn = Connection::open_in_memory().expect("in-memory SQLite");
+        Self::init(conn).expect("in-memory seen events")
+    }
+
+    /// Open the seen-events table of the client state beside `db_path`.
+    ///
+    /// `key` is the state database's SQLCipher key; `None` for tests.
+    pub(crate) fn open(db_path: &Path, key: Option<&[u8; 32]>) -> Result<Self, MessagingError> {
+        Self::init(open_state(db_path, key).map_err(seen_events_error)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, MessagingError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS nostring_seen_events (
+                seq INTEGER PRIMARY KEY,
+                event_id BLOB NOT NULL UNIQUE
+            );",
+        )
+        .map_err(seen_events_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Whether `id` was processed recently. A failed lookup counts as
+    /// unseen and leaves the duplicate to MDK.
+    pub(crate) fn contains(&self, id: &EventId) -> bool {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM nostring_seen_events WHERE event_id = ?1)",
+            params![id.as_bytes().as_slice()],
+            |row| row.get(0),
+        )
+        .unwrap_or(false)
+    }
+
+    /// Remember `id`, forgetting the oldest past [`SEEN_EVENTS_CAPACITY`].
+    ///
+    /// Best effort: a write that fails only costs a decrypt attempt later.
+    pub(crate) fn insert(&self, id: EventId) {
+        let conn = self.conn.lock().unwrap();
+        let _ = conn
+            .execute(
+                "INSERT OR IGNORE INTO nostring_seen_events (event_id) VALUES (?1)",
+                params![id.as_bytes().as_slice()],
+            )
+            .and_then(|_| {
+                conn.execute(
+                    "DELETE FROM nostring_seen_events
+                     WHERE seq <= (SELECT MAX(seq) FROM nostring_seen_events) - ?1",
+                    params![SEEN_EVENTS_CAPACITY as i64],
+                )
+            });
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM nostring_seen_events", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .unwrap() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(i: u32) -> EventId {
+        let mut bytes = [0u8; 32];
+        bytes[..4].copy_from_slice(&i.to_be_bytes());
+        EventId::from_byte_array(bytes)
+    }
+
+    #[test]
+    fn test_seen_events_bounded() {
+        let seen = SeenEvents::in_memory();
+        for i in 0..=SEEN_EVENTS_CAPACITY as u32 {
+            seen.insert(id(i));
+        }
+        seen.insert(id(1));
+
+        assert_eq!(seen.len(), SEEN_EVENTS_CAPACITY);
+        assert!(!seen.contains(&id(0)));
+        assert!(seen.contains(&id(1)));
+        assert!(seen.contains(&id(SEEN_EVENTS_CAPACITY as u32)));
+    }
+
+    #[test]
+    fn test_seen_events_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("messages.db");
+
+        {
+            let seen = SeenEvents::open(&db_path, None).unwrap();
+            assert!(!seen.contains(&id(7)));
+            seen.insert(id(7));
+        }
+
+        let seen = SeenEvents::open(&db_path, None).unwrap();
+        assert!(seen.contains(&id(7)));
+        assert!(!seen.contains(&id(8)));
+    }
+}
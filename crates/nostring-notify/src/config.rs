@@ -1,7 +1,23 @@
 //! Notification configuration
 
 use crate::templates::NotificationLevel;
+use crate::NotifyError;
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// Blocks remaining at which heirs get the descriptor (~1 day)
+pub const DEFAULT_CRITICAL_THRESHOLD_BLOCKS: u32 = 144;
+
+/// Cooldown between heir deliveries whose content can't be compared
+pub const DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS: u64 = 86_400;
+
+fn default_critical_threshold_blocks() -> u32 {
+    DEFAULT_CRITICAL_THRESHOLD_BLOCKS
+}
+
+fn default_heir_delivery_cooldown_secs() -> u64 {
+    DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS
+}
 
 /// Main notification configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +28,13 @@ pub struct NotifyConfig {
     pub email: Option<EmailConfig>,
     /// Nostr DM configuration (optional)
     pub nostr: Option<NostrConfig>,
+    /// Escalate to heirs once this many blocks or fewer remain
+    #[serde(default = "default_critical_threshold_blocks")]
+    pub critical_threshold_blocks: u32,
+    /// Minimum time between deliveries to the same heir when the last
+    /// delivery predates content hashing
+    #[serde(default = "default_heir_delivery_cooldown_secs")]
+    pub heir_delivery_cooldown_secs: u64,
 }
 
 impl Default for NotifyConfig {
@@ -25,7 +48,42 @@ impl Default for NotifyConfig {
             ],
             email: None,
             nostr: None,
+            critical_threshold_blocks: DEFAULT_CRITICAL_THRESHOLD_BLOCKS,
+            heir_delivery_cooldown_secs: DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// Check the thresholds escalate monotonically.
+    ///
+    /// Fewer days remaining must never mean a less urgent level, and no two
+    /// thresholds may share a day count.
+    pub fn validate(&self) -> Result<(), NotifyError> {
+        let mut thresholds: Vec<&Threshold> = self.thresholds.iter().collect();
+        thresholds.sort_by(|a, b| b.days.cmp(&a.days));
+        for pair in thresholds.windows(2) {
+            let (earlier, later) = (pair[0], pair[1]);
+            if earlier.days == later.days {
+                return Err(NotifyError::Config(format!(
+                    "Two notification thresholds at {} days",
+                    later.days
+                )));
+            }
+            if later.level < earlier.level {
+                return Err(NotifyError::Config(format!(
+                    "{:?} at {} days comes after {:?} at {} days; thresholds must escalate",
+                    later.level, later.days, earlier.level, earlier.days
+                )));
+            }
         }
+        Ok(())
+    }
+
+    /// Whether `blocks_remaining` is close enough to expiry to deliver the
+    /// descriptor to heirs
+    pub fn escalates_to_heirs(&self, blocks_remaining: i64) -> bool {
+        blocks_remaining <= self.critical_threshold_blocks as i64
     }
 }
 
@@ -57,6 +115,45 @@ impl Threshold {
     }
 }
 
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsMode {
+    /// Connect in plaintext, then upgrade with STARTTLS (usually port 587)
+    #[default]
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    ImplicitTls,
+    /// No TLS at all — for local test servers like MailHog.
+    /// **Never use in production!**
+    #[serde(alias = "plaintext")]
+    None,
+}
+
+impl TlsMode {
+    /// The port conventionally used with this mode
+    pub fn default_port(self) -> u16 {
+        match self {
+            TlsMode::StartTls => 587,
+            TlsMode::ImplicitTls => 465,
+            TlsMode::None => 25,
+        }
+    }
+}
+
+impl std::str::FromStr for TlsMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "starttls" | "start_tls" => Ok(TlsMode::StartTls),
+            "implicit_tls" | "implicit" | "tls" | "ssl" => Ok(TlsMode::ImplicitTls),
+            "none" | "plaintext" => Ok(TlsMode::None),
+            other => Err(format!("Unknown SMTP TLS mode: {}", other)),
+        }
+    }
+}
+
 /// Email (SMTP) configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailConfig {
@@ -64,7 +161,7 @@ pub struct EmailConfig {
     pub enabled: bool,
     /// SMTP server hostname
     pub smtp_host: String,
-    /// SMTP port (typically 587 for TLS)
+    /// SMTP port (587 for STARTTLS, 465 for implicit TLS)
     pub smtp_port: u16,
     /// SMTP username
     pub smtp_user: String,
@@ -74,10 +171,18 @@ pub struct EmailConfig {
     pub from_address: String,
     /// Recipient email address
     pub to_address: String,
-    /// Use plaintext SMTP (no TLS) — for local test servers like MailHog.
-    /// **Never use in production!**
+    /// Additional visible recipients for owner reminders (e.g. a trusted friend)
+    #[serde(default)]
+    pub cc: Vec<String>,
+    /// Additional hidden recipients for owner reminders
+    #[serde(default)]
+    pub bcc: Vec<String>,
+    /// How the SMTP connection is secured
+    #[serde(default)]
+    pub tls_mode: TlsMode,
+    /// Send plain text only, without the HTML alternative part
     #[serde(default)]
-    pub plaintext: bool,
+    pub plaintext_only: bool,
 }
 
 impl EmailConfig {
@@ -97,9 +202,32 @@ impl EmailConfig {
             smtp_password: smtp_password.into(),
             from_address: from_address.into(),
             to_address: to_address.into(),
-            plaintext: false,
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            tls_mode: TlsMode::StartTls,
+            plaintext_only: false,
         }
     }
+
+    /// Use a different TLS mode, switching to that mode's standard port
+    pub fn with_tls_mode(mut self, tls_mode: TlsMode) -> Self {
+        self.tls_mode = tls_mode;
+        self.smtp_port = tls_mode.default_port();
+        self
+    }
+
+    /// Send plain-text emails only (no HTML part)
+    pub fn with_plaintext_only(mut self, plaintext_only: bool) -> Self {
+        self.plaintext_only = plaintext_only;
+        self
+    }
+
+    /// Copy owner reminders to extra recipients
+    pub fn with_cc(mut self, cc: Vec<String>, bcc: Vec<String>) -> Self {
+        self.cc = cc;
+        self.bcc = bcc;
+        self
+    }
 }
 
 /// Nostr DM configuration
@@ -138,6 +266,12 @@ impl NostrConfig {
     }
 }
 
+impl Drop for NostrConfig {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +282,56 @@ mod tests {
         assert_eq!(config.thresholds.len(), 4);
         assert!(config.email.is_none());
         assert!(config.nostr.is_none());
+        assert!(config.validate().is_ok());
+
+        // Older stored configs without the escalation fields get the defaults
+        let json = r#"{"thresholds":[],"email":null,"nostr":null}"#;
+        let config: NotifyConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.critical_threshold_blocks, 144);
+        assert_eq!(config.heir_delivery_cooldown_secs, 86_400);
+    }
+
+    #[test]
+    fn test_critical_threshold_blocks_moves_escalation() {
+        let mut config = NotifyConfig::default();
+        assert!(config.escalates_to_heirs(144));
+        assert!(!config.escalates_to_heirs(145));
+
+        config.critical_threshold_blocks = 432;
+        assert!(config.escalates_to_heirs(300));
+        assert!(!config.escalates_to_heirs(433));
+
+        config.critical_threshold_blocks = 0;
+        assert!(!config.escalates_to_heirs(1));
+        assert!(config.escalates_to_heirs(-5));
+    }
+
+    #[test]
+    fn test_non_monotonic_thresholds_rejected() {
+        // Order in the list doesn't matter, only days vs. level
+        let config = NotifyConfig {
+            thresholds: vec![Threshold::days(1), Threshold::days(30), Threshold::days(7)],
+            ..NotifyConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let inverted = NotifyConfig {
+            thresholds: vec![
+                Threshold::custom(30, NotificationLevel::Urgent),
+                Threshold::custom(7, NotificationLevel::Warning),
+            ],
+            ..NotifyConfig::default()
+        };
+        assert!(matches!(inverted.validate(), Err(NotifyError::Config(_))));
+
+        let duplicate = NotifyConfig {
+            thresholds: vec![
+                Threshold::custom(7, NotificationLevel::Reminder),
+                Threshold::custom(7, NotificationLevel::Warning),
+            ],
+            ..NotifyConfig::default()
+        };
+        assert!(matches!(duplicate.validate(), Err(NotifyError::Config(_))));
     }
 
     #[test]
@@ -174,6 +358,27 @@ mod tests {
         );
         assert!(config.enabled);
         assert_eq!(config.smtp_port, 587);
+        assert_eq!(config.tls_mode, TlsMode::StartTls);
+
+        let implicit = config.with_tls_mode(TlsMode::ImplicitTls);
+        assert_eq!(implicit.smtp_port, 465);
+    }
+
+    #[test]
+    fn test_tls_mode_parsing() {
+        assert_eq!("starttls".parse::<TlsMode>().unwrap(), TlsMode::StartTls);
+        assert_eq!(
+            "implicit_tls".parse::<TlsMode>().unwrap(),
+            TlsMode::ImplicitTls
+        );
+        assert_eq!("None".parse::<TlsMode>().unwrap(), TlsMode::None);
+        assert!("carrier-pigeon".parse::<TlsMode>().is_err());
+
+        // Missing field in stored config defaults to STARTTLS
+        let json = r#"{"enabled":true,"smtp_host":"h","smtp_port":587,"smtp_user":"u",
+            "smtp_password":"p","from_address":"a@b.c","to_address":"d@e.f"}"#;
+        let config: EmailConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.tls_mode, TlsMode::StartTls);
     }
 
     #[test]
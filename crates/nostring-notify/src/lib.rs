@@ -20,6 +20,7 @@
 //!     ],
 //!     email: Some(EmailConfig { ... }),
 //!     nostr: Some(NostrConfig { ... }),
+//!     ..NotifyConfig::default()
 //! };
 //!
 //! let service = NotificationService::new(config);
@@ -32,7 +33,10 @@ pub mod nostr_relay;
 pub mod smtp;
 pub mod templates;
 
-pub use config::{EmailConfig, NostrConfig, NotifyConfig, Threshold};
+pub use config::{
+    EmailConfig, NostrConfig, NotifyConfig, Threshold, TlsMode, DEFAULT_CRITICAL_THRESHOLD_BLOCKS,
+    DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS,
+};
 pub use templates::NotificationLevel;
 
 use thiserror::Error;
@@ -51,6 +55,19 @@ pub enum NotifyError {
 
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("Unsupported encryption version: {0}")]
+    UnsupportedEncryptionVersion(u8),
+
+    #[error(
+        "Only {healthy} relay(s) reachable, {required} required (unreachable: {})",
+        .failed.join(", ")
+    )]
+    InsufficientRelays {
+        healthy: usize,
+        required: usize,
+        failed: Vec<String>,
+    },
 }
 
 /// Notification service for check-in reminders
@@ -97,12 +114,20 @@ impl NotificationService {
         let message =
             templates::generate_message(level, days_remaining, blocks_remaining, current_height);
 
-        // Send via configured channels
+        self.send(&message).await?;
+        Ok(Some(level))
+    }
+
+    /// Send a message to the owner via every enabled channel
+    ///
+    /// Succeeds if at least one channel delivered the message.
+    pub async fn send(&self, message: &templates::NotificationMessage) -> Result<(), NotifyError> {
+        let level = message.level;
         let mut sent_any = false;
 
         if let Some(ref email_config) = self.config.email {
             if email_config.enabled {
-                match smtp::send_email(email_config, &message).await {
+                match smtp::send_email(email_config, message).await {
                     Ok(_) => {
                         log::info!("Email notification sent for level {:?}", level);
                         sent_any = true;
@@ -116,7 +141,7 @@ impl NotificationService {
 
         if let Some(ref nostr_config) = self.config.nostr {
             if nostr_config.enabled {
-                match nostr_dm::send_dm(nostr_config, &message).await {
+                match nostr_dm::send_dm(nostr_config, message).await {
                     Ok(event_id) => {
                         log::info!("Nostr DM sent for level {:?} (event: {})", level, event_id);
                         sent_any = true;
@@ -129,7 +154,7 @@ impl NotificationService {
         }
 
         if sent_any {
-            Ok(Some(level))
+            Ok(())
         } else {
             Err(NotifyError::Config(
                 "No notification channels enabled or all failed".into(),
@@ -188,6 +213,7 @@ mod tests {
             ],
             email: None,
             nostr: None,
+            ..NotifyConfig::default()
         };
 
         // 45 days remaining - no notification
@@ -124,6 +124,7 @@ pub async fn deliver_vault_backup(
     let notification = NotificationMessage {
         subject: "Vault Backup".to_string(),
         body: message,
+        html_body: None,
         level: crate::NotificationLevel::Critical,
     };
 
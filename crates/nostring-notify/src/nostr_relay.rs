@@ -3,7 +3,8 @@
 //! Publishes encrypted locked shares to multiple Nostr relays as redundant
 //! backup beyond the descriptor file. Each share is encrypted to the heir's
 //! npub using NIP-44 (with NIP-04 fallback), so only the intended heir can
-//! decrypt it.
+//! decrypt it. Events carry an `encryption` tag naming the scheme and NIP-44
+//! version, and [`decrypt_share`] dispatches on the payload's version byte.
 //!
 //! # Security Model
 //!
@@ -11,11 +12,57 @@
 //! this is defense-in-depth. Even if an attacker scrapes all relay data,
 //! they get encrypted blobs they can't decrypt (without the heir's nsec)
 //! and even decrypted shares alone can't reconstruct the secret.
+//!
+//! Standard share events are authored by the service key, which tells
+//! observers that the owner has an inheritance set up and who the heirs
+//! are. [`PrivacyMode::GiftWrap`] instead seals each share in a NIP-59 gift
+//! wrap signed by a throwaway key; only the heir can see who sent it.
+//!
+//! # Relay Discovery
+//!
+//! The service key advertises the relays it published to in a NIP-65
+//! relay list (kind 10002), mirrored to [`DEFAULT_RELAYS`]. Heirs call
+//! [`discover_relays`] with the service npub to search those relays,
+//! so a custom relay choice doesn't strand the shares. The list is signed
+//! by the service key, so it is only published in
+//! [`PrivacyMode::Standard`]. Results can be kept in a
+//! [`RelayDiscoveryCache`] so repeated fetches skip the lookup.
+//!
+//! # Key Migration
+//!
+//! When the service key is rotated, the old key signs a migration event
+//! ([`KEY_MIGRATION_KIND`]) naming the new key. Heirs holding only the old
+//! npub call [`fetch_key_migrations`] to follow the chain to the current
+//! key.
 
 use crate::NotifyError;
 use nostr_sdk::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Tag name recording which encryption scheme a share event uses.
+const ENCRYPTION_TAG: &str = "encryption";
+
+/// Kind of the (never published) rumor carrying a share inside a gift wrap
+pub const SHARE_RUMOR_KIND: u16 = 4559;
+
+/// Kind of the event announcing that the service key moved to a new key
+pub const KEY_MIGRATION_KIND: u16 = 4560;
+
+/// Most migrations [`fetch_key_migrations`] follows before giving up
+pub const MAX_KEY_MIGRATIONS: usize = 16;
+
+/// How share events are published
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyMode {
+    /// NIP-44 encrypted kind-4 events authored by the service key
+    #[default]
+    Standard,
+    /// NIP-59 gift wraps; the service key only appears inside the seal
+    GiftWrap,
+}
 
 /// Default relays for publishing shares
 pub const DEFAULT_RELAYS: &[&str] = &[
@@ -24,6 +71,39 @@ pub const DEFAULT_RELAYS: &[&str] = &[
     "wss://nos.lol",
 ];
 
+/// How long a relay gets to accept a connection during a health probe
+pub const RELAY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Minimum number of reachable relays before locked shares are published.
+///
+/// Publishing to a single relay leaves the backup one outage away from
+/// being lost, so refuse rather than report a fragile success.
+pub const DEFAULT_MIN_HEALTHY_RELAYS: usize = 2;
+
+/// Outcome of probing a single relay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayHealth {
+    /// Relay URL
+    pub url: String,
+    /// Whether the relay accepted a connection within the probe timeout
+    pub reachable: bool,
+    /// Connection round-trip in milliseconds (if reachable)
+    pub latency_ms: Option<u64>,
+    /// Why the probe failed (if unreachable)
+    pub error: Option<String>,
+}
+
+impl RelayHealth {
+    fn unreachable(url: &str, error: impl std::fmt::Display) -> Self {
+        Self {
+            url: url.to_string(),
+            reachable: false,
+            latency_ms: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
 /// Result of publishing shares to relays
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayPublishResult {
@@ -45,6 +125,22 @@ pub struct HeirPublishResult {
     pub shares_published: usize,
     pub event_ids: Vec<String>,
     pub error: Option<String>,
+    /// Where each share was accepted or refused, in share order
+    #[serde(default)]
+    pub publications: Vec<SharePublication>,
+}
+
+/// How one share fared on the relays it was sent to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharePublication {
+    /// Index of the share in the split
+    pub index: usize,
+    /// Event carrying the share; `None` if it was never sent
+    pub event_id: Option<String>,
+    /// Relays that accepted the event
+    pub accepted: Vec<String>,
+    /// Relays that refused it or couldn't be reached, with the reason
+    pub failed: Vec<(String, String)>,
 }
 
 /// A locked share to be published for a specific heir
@@ -89,6 +185,53 @@ pub async fn publish_shares_to_relays(
     shares: &[String],
     split_id: &str,
     relays: &[String],
+) -> Result<HeirPublishResult, NotifyError> {
+    publish_heir_shares(
+        sender_secret,
+        heir_npub,
+        heir_label,
+        shares,
+        split_id,
+        relays,
+        PrivacyMode::Standard,
+    )
+    .await
+}
+
+/// Gift-wrap each share to an heir's npub and publish to relays.
+///
+/// Same arguments as [`publish_shares_to_relays`], but every share goes out
+/// as a NIP-59 gift wrap: the outer event is signed by a random key and
+/// carries only the heir's `p` tag, so relays can't link it to the service
+/// key or group it by split. Read back with [`fetch_giftwrapped_shares`].
+pub async fn publish_share_giftwrapped(
+    sender_secret: &str,
+    heir_npub: &str,
+    heir_label: &str,
+    shares: &[String],
+    split_id: &str,
+    relays: &[String],
+) -> Result<HeirPublishResult, NotifyError> {
+    publish_heir_shares(
+        sender_secret,
+        heir_npub,
+        heir_label,
+        shares,
+        split_id,
+        relays,
+        PrivacyMode::GiftWrap,
+    )
+    .await
+}
+
+async fn publish_heir_shares(
+    sender_secret: &str,
+    heir_npub: &str,
+    heir_label: &str,
+    shares: &[String],
+    split_id: &str,
+    relays: &[String],
+    privacy_mode: PrivacyMode,
 ) -> Result<HeirPublishResult, NotifyError> {
     let recipient = parse_pubkey(heir_npub)
         .map_err(|e| NotifyError::NostrFailed(format!("Invalid heir npub: {}", e)))?;
@@ -108,6 +251,7 @@ pub async fn publish_shares_to_relays(
     tokio::time::sleep(Duration::from_secs(2)).await;
 
     let mut event_ids = Vec::new();
+    let mut publications = Vec::with_capacity(shares.len());
 
     for (i, share) in shares.iter().enumerate() {
         let payload = SharePayload {
@@ -117,33 +261,43 @@ pub async fn publish_shares_to_relays(
             split_id: split_id.to_string(),
         };
 
-        let payload_json = serde_json::to_string(&payload).map_err(|e| {
-            NotifyError::NostrFailed(format!("Failed to serialize share payload: {}", e))
-        })?;
-
-        // Try NIP-44 first, fall back to NIP-04
-        let (encrypted, kind) = encrypt_for_heir(&keys, &recipient, &payload_json)?;
-
-        let event = EventBuilder::new(kind, &encrypted)
-            .tag(Tag::public_key(recipient))
-            .tag(Tag::custom(
-                TagKind::Custom("split".into()),
-                vec![split_id.to_string()],
-            ))
-            .sign_with_keys(&keys)
-            .map_err(|e| NotifyError::NostrFailed(format!("Failed to build event: {}", e)))?;
+        let event = match privacy_mode {
+            PrivacyMode::Standard => share_event(&keys, &recipient, &payload)?,
+            PrivacyMode::GiftWrap => gift_wrap_share(&keys, &recipient, &payload).await?,
+        };
 
         match client.send_event(&event).await {
             Ok(output) => {
                 let eid = output.id().to_hex();
-                log::info!(
-                    "Published share {}/{} for heir {} (event: {})",
-                    i + 1,
-                    shares.len(),
-                    heir_label,
-                    eid
-                );
-                event_ids.push(eid);
+                let publication = share_publication(i, Some(eid.clone()), relays, |relay| {
+                    if output.success.contains(relay) {
+                        Ok(())
+                    } else {
+                        Err(output
+                            .failed
+                            .get(relay)
+                            .cloned()
+                            .unwrap_or_else(|| "relay not connected".to_string()))
+                    }
+                });
+                if publication.accepted.is_empty() {
+                    log::error!(
+                        "No relay accepted share {}/{} for heir {}",
+                        i + 1,
+                        shares.len(),
+                        heir_label
+                    );
+                } else {
+                    log::info!(
+                        "Published share {}/{} for heir {} (event: {})",
+                        i + 1,
+                        shares.len(),
+                        heir_label,
+                        eid
+                    );
+                    event_ids.push(eid);
+                }
+                publications.push(publication);
             }
             Err(e) => {
                 log::error!(
@@ -153,6 +307,7 @@ pub async fn publish_shares_to_relays(
                     heir_label,
                     e
                 );
+                publications.push(share_publication(i, None, relays, |_| Err(e.to_string())));
             }
         }
     }
@@ -171,39 +326,223 @@ pub async fn publish_shares_to_relays(
         } else {
             None
         },
+        publications,
     })
 }
 
+/// Sort `relays` into those that accepted share `index` and those that
+/// didn't, as `outcome` reports for each relay's parsed URL.
+fn share_publication(
+    index: usize,
+    event_id: Option<String>,
+    relays: &[String],
+    outcome: impl Fn(&RelayUrl) -> Result<(), String>,
+) -> SharePublication {
+    let mut publication = SharePublication {
+        index,
+        event_id,
+        accepted: Vec::new(),
+        failed: Vec::new(),
+    };
+    for relay in relays {
+        let result = RelayUrl::parse(relay)
+            .map_err(|e| format!("invalid relay URL: {}", e))
+            .and_then(|url| outcome(&url));
+        match result {
+            Ok(()) => publication.accepted.push(relay.clone()),
+            Err(reason) => publication.failed.push((relay.clone(), reason)),
+        }
+    }
+    publication
+}
+
+/// NIP-44 encrypted share event authored by the service key.
+fn share_event(
+    keys: &Keys,
+    recipient: &PublicKey,
+    payload: &SharePayload,
+) -> Result<Event, NotifyError> {
+    let payload_json = serde_json::to_string(payload).map_err(|e| {
+        NotifyError::NostrFailed(format!("Failed to serialize share payload: {}", e))
+    })?;
+
+    // Try NIP-44 first, fall back to NIP-04
+    let (encrypted, kind) = encrypt_for_heir(keys, recipient, &payload_json)?;
+
+    EventBuilder::new(kind, &encrypted)
+        .tag(Tag::public_key(*recipient))
+        .tag(split_tag(&payload.split_id))
+        .tag(encryption_tag(&encrypted))
+        .sign_with_keys(keys)
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to build event: {}", e)))
+}
+
+/// Seal a share in a NIP-59 gift wrap addressed to `recipient`.
+///
+/// The rumor (authored by the service key) carries the payload and split
+/// tag; both are only visible after unwrapping.
+async fn gift_wrap_share(
+    keys: &Keys,
+    recipient: &PublicKey,
+    payload: &SharePayload,
+) -> Result<Event, NotifyError> {
+    let payload_json = serde_json::to_string(payload).map_err(|e| {
+        NotifyError::NostrFailed(format!("Failed to serialize share payload: {}", e))
+    })?;
+
+    let rumor = EventBuilder::new(Kind::Custom(SHARE_RUMOR_KIND), payload_json)
+        .tag(split_tag(&payload.split_id))
+        .build(keys.public_key());
+    EventBuilder::gift_wrap(keys, recipient, rumor, [])
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to gift-wrap share: {}", e)))
+}
+
+/// Open a gift-wrapped share, checking it was sealed by `sender_pk`.
+async fn unwrap_share(
+    keys: &Keys,
+    sender_pk: &PublicKey,
+    event: &Event,
+) -> Result<SharePayload, NotifyError> {
+    let unwrapped = UnwrappedGift::from_gift_wrap(keys, event)
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to unwrap: {}", e)))?;
+    if unwrapped.sender != *sender_pk {
+        return Err(NotifyError::NostrFailed(
+            "Gift wrap was not sealed by the expected service key".into(),
+        ));
+    }
+    if unwrapped.rumor.kind != Kind::Custom(SHARE_RUMOR_KIND) {
+        return Err(NotifyError::NostrFailed(
+            "Gift wrap does not contain a share".into(),
+        ));
+    }
+    serde_json::from_str(&unwrapped.rumor.content)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid share payload: {}", e)))
+}
+
+fn split_tag(split_id: &str) -> Tag {
+    Tag::custom(TagKind::Custom("split".into()), vec![split_id.to_string()])
+}
+
+/// Probe relays by opening a connection to each and timing the handshake.
+///
+/// Relays are probed concurrently; each gets [`RELAY_PROBE_TIMEOUT`].
+/// Results are returned in the same order as `relays`.
+pub async fn probe_relays(relays: &[String]) -> Vec<RelayHealth> {
+    let handles: Vec<_> = relays
+        .iter()
+        .cloned()
+        .map(|url| tokio::spawn(async move { probe_relay(&url).await }))
+        .collect();
+
+    let mut health = Vec::with_capacity(relays.len());
+    for (url, handle) in relays.iter().zip(handles) {
+        health.push(
+            handle
+                .await
+                .unwrap_or_else(|e| RelayHealth::unreachable(url, e)),
+        );
+    }
+    health
+}
+
+async fn probe_relay(url: &str) -> RelayHealth {
+    let client = Client::default();
+    if let Err(e) = client.add_relay(url).await {
+        return RelayHealth::unreachable(url, e);
+    }
+    let relay = match client.relay(url).await {
+        Ok(relay) => relay,
+        Err(e) => return RelayHealth::unreachable(url, e),
+    };
+
+    let started = Instant::now();
+    let result = relay.try_connect(RELAY_PROBE_TIMEOUT).await;
+    let elapsed = started.elapsed();
+    client.disconnect().await;
+
+    match result {
+        Ok(()) => RelayHealth {
+            url: url.to_string(),
+            reachable: true,
+            latency_ms: Some(elapsed.as_millis() as u64),
+            error: None,
+        },
+        Err(e) => RelayHealth::unreachable(url, e),
+    }
+}
+
+/// Check that at least `min_healthy` relays are reachable.
+///
+/// Returns the reachable relay URLs, or [`NotifyError::InsufficientRelays`]
+/// naming the ones that failed.
+pub fn require_healthy(
+    health: &[RelayHealth],
+    min_healthy: usize,
+) -> Result<Vec<String>, NotifyError> {
+    let (healthy, failed): (Vec<_>, Vec<_>) = health.iter().partition(|h| h.reachable);
+
+    if healthy.is_empty() || healthy.len() < min_healthy {
+        return Err(NotifyError::InsufficientRelays {
+            healthy: healthy.len(),
+            required: min_healthy.max(1),
+            failed: failed.into_iter().map(|h| h.url.clone()).collect(),
+        });
+    }
+    Ok(healthy.into_iter().map(|h| h.url.clone()).collect())
+}
+
 /// Publish locked shares to multiple relays for all heirs.
 ///
+/// Relays are probed first and nothing is published unless at least
+/// `min_healthy` of them are reachable. Unreachable relays are skipped
+/// and reported in [`RelayPublishResult::failed_relays`].
+///
 /// # Arguments
 /// * `sender_secret` - Service key secret (hex)
 /// * `heirs` - List of (npub, label) pairs
 /// * `locked_shares` - The locked share strings
 /// * `split_id` - Unique identifier for this split
 /// * `relays` - Optional relay list (defaults to DEFAULT_RELAYS)
+/// * `min_healthy` - Minimum reachable relays (see [`DEFAULT_MIN_HEALTHY_RELAYS`])
+/// * `privacy_mode` - Plain NIP-44 events or NIP-59 gift wraps
 pub async fn publish_all_shares(
     sender_secret: &str,
     heirs: &[(String, String)], // (npub, label)
     locked_shares: &[String],
     split_id: &str,
     relays: Option<&[String]>,
+    min_healthy: usize,
+    privacy_mode: PrivacyMode,
 ) -> Result<RelayPublishResult, NotifyError> {
-    let relay_list: Vec<String> = relays
+    let candidates: Vec<String> = relays
         .map(|r| r.to_vec())
         .unwrap_or_else(|| DEFAULT_RELAYS.iter().map(|s| s.to_string()).collect());
 
+    let health = probe_relays(&candidates).await;
+    let relay_list = require_healthy(&health, min_healthy)?;
+    let unreachable: Vec<String> = health
+        .into_iter()
+        .filter(|h| !h.reachable)
+        .map(|h| h.url)
+        .collect();
+    for url in &unreachable {
+        log::warn!("Skipping unreachable relay {}", url);
+    }
+
     let mut heir_results = Vec::new();
     let mut total_published = 0;
 
     for (npub, label) in heirs {
-        match publish_shares_to_relays(
+        match publish_heir_shares(
             sender_secret,
             npub,
             label,
             locked_shares,
             split_id,
             &relay_list,
+            privacy_mode,
         )
         .await
         {
@@ -218,18 +557,20 @@ pub async fn publish_all_shares(
                     shares_published: 0,
                     event_ids: Vec::new(),
                     error: Some(format!("{}", e)),
+                    publications: Vec::new(),
                 });
             }
         }
     }
 
-    // Determine relay status (simplified — we published to all, consider successful
-    // if at least one event was accepted)
-    let (successful_relays, failed_relays) = if total_published > 0 {
-        (relay_list.clone(), Vec::new())
+    // Determine relay status (simplified — we published to every reachable
+    // relay, consider them successful if at least one event was accepted)
+    let (successful_relays, mut failed_relays) = if total_published > 0 {
+        (relay_list, Vec::new())
     } else {
-        (Vec::new(), relay_list.clone())
+        (Vec::new(), relay_list)
     };
+    failed_relays.extend(unreachable);
 
     Ok(RelayPublishResult {
         shares_published: total_published,
@@ -239,6 +580,345 @@ pub async fn publish_all_shares(
     })
 }
 
+/// Publish a NIP-65 relay list (kind 10002) naming `relays` for the key.
+///
+/// The list goes to `relays` and to [`DEFAULT_RELAYS`], where
+/// [`discover_relays`] looks first. Relay lists are replaceable, so the
+/// newest one wins. Returns the event id.
+pub async fn publish_relay_list(
+    sender_secret: &str,
+    relays: &[String],
+) -> Result<String, NotifyError> {
+    let keys = Keys::parse(sender_secret)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid secret key: {}", e)))?;
+    let event = relay_list_event(&keys, relays)?;
+
+    let client = Client::new(keys);
+    for relay in relays
+        .iter()
+        .map(String::as_str)
+        .chain(DEFAULT_RELAYS.iter().copied())
+    {
+        if let Err(e) = client.add_relay(relay).await {
+            log::warn!("Failed to add relay {}: {}", relay, e);
+        }
+    }
+    client.connect().await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let sent = client
+        .send_event(&event)
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to publish relay list: {}", e)));
+    client.disconnect().await;
+
+    let eid = sent?.id().to_hex();
+    log::info!(
+        "Published NIP-65 relay list ({} relays, event: {})",
+        relays.len(),
+        eid
+    );
+    Ok(eid)
+}
+
+/// How long [`discover_relays`] waits for relay lists
+pub const RELAY_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Find the relays a key publishes to from its NIP-65 relay list.
+///
+/// Queries `relays` (defaults to [`DEFAULT_RELAYS`]) for the newest
+/// kind-10002 event by `npub` and returns its write relays. An empty list
+/// means none was found; callers should fall back to the relays they
+/// queried.
+pub async fn discover_relays(
+    npub: &str,
+    relays: Option<&[String]>,
+) -> Result<Vec<String>, NotifyError> {
+    let author =
+        parse_pubkey(npub).map_err(|e| NotifyError::NostrFailed(format!("Invalid npub: {}", e)))?;
+
+    let relay_list: Vec<String> = relays
+        .map(|r| r.to_vec())
+        .unwrap_or_else(|| DEFAULT_RELAYS.iter().map(|s| s.to_string()).collect());
+
+    let client = Client::default();
+    for relay in &relay_list {
+        if let Err(e) = client.add_relay(relay).await {
+            log::warn!("Failed to add relay {}: {}", relay, e);
+        }
+    }
+    client.connect().await;
+
+    let filter = Filter::new().kind(Kind::RelayList).author(author).limit(5);
+    let events = client
+        .fetch_events(filter, RELAY_DISCOVERY_TIMEOUT)
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to fetch relay list: {}", e)));
+    client.disconnect().await;
+
+    Ok(events?
+        .iter()
+        .max_by_key(|e| e.created_at)
+        .map(relays_from_relay_list)
+        .unwrap_or_default())
+}
+
+/// How long [`RelayDiscoveryCache`] keeps a discovered relay list
+pub const RELAY_DISCOVERY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Relay lists found by [`discover_relays`], by npub.
+///
+/// Entries expire after the cache's TTL so a republished list is picked up
+/// eventually. Empty results are cached too: a key without a relay list
+/// would otherwise pay the discovery timeout on every fetch.
+#[derive(Debug)]
+pub struct RelayDiscoveryCache {
+    ttl: Duration,
+    entries: std::sync::Mutex<HashMap<String, (Instant, Vec<String>)>>,
+}
+
+impl Default for RelayDiscoveryCache {
+    fn default() -> Self {
+        Self::new(RELAY_DISCOVERY_TTL)
+    }
+}
+
+impl RelayDiscoveryCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached relays for `npub`, if still fresh.
+    pub fn get(&self, npub: &str) -> Option<Vec<String>> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(npub)
+            .filter(|(found_at, _)| found_at.elapsed() < self.ttl)
+            .map(|(_, relays)| relays.clone())
+    }
+
+    pub fn insert(&self, npub: &str, relays: Vec<String>) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(npub.to_string(), (Instant::now(), relays));
+    }
+
+    /// [`discover_relays`] through the cache. Failed lookups aren't cached.
+    pub async fn discover(
+        &self,
+        npub: &str,
+        relays: Option<&[String]>,
+    ) -> Result<Vec<String>, NotifyError> {
+        if let Some(found) = self.get(npub) {
+            return Ok(found);
+        }
+        let found = discover_relays(npub, relays).await?;
+        self.insert(npub, found.clone());
+        Ok(found)
+    }
+}
+
+/// NIP-65 relay list event: one `["r", url]` tag per relay.
+fn relay_list_event(keys: &Keys, relays: &[String]) -> Result<Event, NotifyError> {
+    let tags = relays
+        .iter()
+        .map(|url| Tag::custom(TagKind::Custom("r".into()), vec![url.clone()]));
+    EventBuilder::new(Kind::RelayList, "")
+        .tags(tags)
+        .sign_with_keys(keys)
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to build relay list: {}", e)))
+}
+
+/// Write relays named by a NIP-65 relay list, in order and deduplicated.
+///
+/// Relays marked `read` are skipped: the owner doesn't publish there.
+fn relays_from_relay_list(event: &Event) -> Vec<String> {
+    let mut relays: Vec<String> = Vec::new();
+    for tag in event.tags.iter().map(|t| t.as_slice()) {
+        let (Some("r"), Some(url)) = (tag.first().map(String::as_str), tag.get(1)) else {
+            continue;
+        };
+        let read_only = tag.get(2).is_some_and(|marker| marker == "read");
+        let is_relay = url.starts_with("wss://") || url.starts_with("ws://");
+        if is_relay && !read_only && !relays.contains(url) {
+            relays.push(url.clone());
+        }
+    }
+    relays
+}
+
+/// Migration event, signed by `old_keys`, announcing `new_pubkey`.
+///
+/// The new key goes in a `p` tag; the content repeats it as an npub for
+/// clients that only show text.
+pub fn key_migration_event(old_keys: &Keys, new_pubkey: &PublicKey) -> Result<Event, NotifyError> {
+    let npub = new_pubkey
+        .to_bech32()
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid new key: {}", e)))?;
+    EventBuilder::new(
+        Kind::Custom(KEY_MIGRATION_KIND),
+        format!("NoString service key moved to {}", npub),
+    )
+    .tag(Tag::public_key(*new_pubkey))
+    .sign_with_keys(old_keys)
+    .map_err(|e| NotifyError::NostrFailed(format!("Failed to build migration event: {}", e)))
+}
+
+/// The key a migration event announces, after checking its kind and
+/// signature.
+pub fn parse_key_migration(event: &Event) -> Result<PublicKey, NotifyError> {
+    if event.kind != Kind::Custom(KEY_MIGRATION_KIND) {
+        return Err(NotifyError::NostrFailed(format!(
+            "Not a key migration event (kind {})",
+            event.kind.as_u16()
+        )));
+    }
+    event
+        .verify()
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid migration signature: {}", e)))?;
+    event
+        .tags
+        .public_keys()
+        .next()
+        .copied()
+        .ok_or_else(|| NotifyError::NostrFailed("Migration event names no new key".into()))
+}
+
+/// Follow migrations from `start` through `events`.
+///
+/// Returns every key after `start`, oldest first; empty if `start` never
+/// migrated. Invalid events are ignored. A key that announced two
+/// different successors (say, the owner and whoever stole the key both
+/// rotated) is an error: the heir has to confirm the right one with the
+/// owner's family or executor.
+pub fn follow_key_migrations(
+    start: &PublicKey,
+    events: &[Event],
+) -> Result<Vec<PublicKey>, NotifyError> {
+    let mut chain: Vec<PublicKey> = Vec::new();
+    let mut current = *start;
+    loop {
+        let mut successors: Vec<PublicKey> = events
+            .iter()
+            .filter(|e| e.pubkey == current)
+            .filter_map(|e| parse_key_migration(e).ok())
+            .collect();
+        successors.sort();
+        successors.dedup();
+        let next = match successors.as_slice() {
+            [] => return Ok(chain),
+            [next] => *next,
+            _ => {
+                return Err(NotifyError::NostrFailed(format!(
+                    "Key {} announced {} different successors",
+                    current.to_hex(),
+                    successors.len()
+                )))
+            }
+        };
+        // A loop back to a key already seen ends the chain
+        if next == *start || chain.contains(&next) || chain.len() >= MAX_KEY_MIGRATIONS {
+            return Ok(chain);
+        }
+        chain.push(next);
+        current = next;
+    }
+}
+
+/// Publish a migration event to `relays` and [`DEFAULT_RELAYS`].
+///
+/// Returns the event id once at least one relay accepted it.
+pub async fn publish_key_migration(
+    event: &Event,
+    relays: &[String],
+) -> Result<String, NotifyError> {
+    let client = Client::default();
+    for relay in relays
+        .iter()
+        .map(String::as_str)
+        .chain(DEFAULT_RELAYS.iter().copied())
+    {
+        if let Err(e) = client.add_relay(relay).await {
+            log::warn!("Failed to add relay {}: {}", relay, e);
+        }
+    }
+    client.connect().await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let sent = client
+        .send_event(event)
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to publish migration: {}", e)));
+    client.disconnect().await;
+
+    let output = sent?;
+    if output.success.is_empty() {
+        return Err(NotifyError::NostrFailed(
+            "No relay accepted the migration event".into(),
+        ));
+    }
+    let eid = output.id().to_hex();
+    log::info!("Published service key migration (event: {})", eid);
+    Ok(eid)
+}
+
+/// Fetch the migration chain starting at `npub` from [`DEFAULT_RELAYS`].
+///
+/// Returns the keys `npub` migrated to, oldest first, as npubs; the last
+/// one is the current service key. Empty if `npub` never migrated.
+pub async fn fetch_key_migrations(npub: &str) -> Result<Vec<String>, NotifyError> {
+    let start =
+        parse_pubkey(npub).map_err(|e| NotifyError::NostrFailed(format!("Invalid npub: {}", e)))?;
+
+    let client = Client::default();
+    for relay in DEFAULT_RELAYS {
+        if let Err(e) = client.add_relay(*relay).await {
+            log::warn!("Failed to add relay {}: {}", relay, e);
+        }
+    }
+    client.connect().await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    // Fetch hop by hop: each key's migrations are only known once the
+    // previous hop has been read
+    let mut events: Vec<Event> = Vec::new();
+    let mut chain = Vec::new();
+    let result = loop {
+        let author = chain.last().copied().unwrap_or(start);
+        let filter = Filter::new()
+            .kind(Kind::Custom(KEY_MIGRATION_KIND))
+            .author(author);
+        match client.fetch_events(filter, Duration::from_secs(10)).await {
+            Ok(found) => events.extend(found),
+            Err(e) => {
+                break Err(NotifyError::NostrFailed(format!(
+                    "Failed to fetch migrations: {}",
+                    e
+                )))
+            }
+        }
+        let next = match follow_key_migrations(&start, &events) {
+            Ok(next) => next,
+            Err(e) => break Err(e),
+        };
+        if next.len() == chain.len() {
+            break Ok(next);
+        }
+        chain = next;
+    };
+    client.disconnect().await;
+
+    result?
+        .iter()
+        .map(|pk| {
+            pk.to_bech32()
+                .map_err(|e| NotifyError::NostrFailed(e.to_string()))
+        })
+        .collect()
+}
+
 /// Fetch locked shares from relays for a specific heir.
 ///
 /// The heir provides their nsec to decrypt the shares that were
@@ -290,12 +970,44 @@ pub async fn fetch_shares_from_relays(
         .await
         .map_err(|e| NotifyError::NostrFailed(format!("Failed to fetch events: {}", e)))?;
 
-    let mut shares = Vec::new();
     let events_found = events.len();
+    let mut shares = decrypt_share_events(&heir_keys, &sender_pk, events.iter(), split_id);
 
-    for event in events.iter() {
+    // Sort by index and deduplicate
+    shares.sort_by_key(|s| s.index);
+    shares.dedup_by_key(|s| (s.split_id.clone(), s.index));
+
+    client.disconnect().await;
+
+    Ok(RelayFetchResult {
+        shares,
+        responding_relays: relay_list,
+        events_found,
+    })
+}
+
+/// Decrypt share events sent by `sender_pk`, keeping those in `split_id`
+/// (if given). Events that don't decrypt to a share are skipped.
+fn decrypt_share_events<'a>(
+    heir_keys: &Keys,
+    sender_pk: &PublicKey,
+    events: impl Iterator<Item = &'a Event>,
+    split_id: Option<&str>,
+) -> Vec<SharePayload> {
+    let mut shares = Vec::new();
+    for event in events {
         // Try to decrypt
-        let decrypted = decrypt_event(&heir_keys, &sender_pk, event);
+        let decrypted = match decrypt_event(heir_keys, sender_pk, event) {
+            Err(NotifyError::UnsupportedEncryptionVersion(v)) => {
+                log::warn!(
+                    "Skipping share event {}: NIP-44 version {} is not supported by this build",
+                    event.id,
+                    v
+                );
+                continue;
+            }
+            other => other,
+        };
 
         if let Ok(content) = decrypted {
             // Try to parse as SharePayload
@@ -310,20 +1022,290 @@ pub async fn fetch_shares_from_relays(
             }
         }
     }
+    shares
+}
+
+/// Relays queried at once by [`fetch_shares_progressively`]
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 2;
+
+/// How long one relay gets to answer during [`fetch_shares_progressively`]
+pub const RELAY_FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Fetch locked shares one relay at a time, for heirs on slow connections.
+///
+/// Unlike [`fetch_shares_from_relays`], at most
+/// [`DEFAULT_FETCH_CONCURRENCY`] relays are queried at once, each capped
+/// at [`RELAY_FETCH_TIMEOUT`], so a slow relay can't hold up the rest.
+/// `on_share` is called for each new share as it arrives. With
+/// `min_shares_needed`, fetching stops as soon as one split has that many
+/// distinct shares; relays still in flight are abandoned.
+///
+/// `responding_relays` lists the relays that answered before the fetch
+/// finished.
+pub async fn fetch_shares_progressively(
+    heir_nsec: &str,
+    sender_npub: &str,
+    relays: Option<&[String]>,
+    split_id: Option<&str>,
+    min_shares_needed: Option<usize>,
+    on_share: impl FnMut(&SharePayload),
+) -> Result<RelayFetchResult, NotifyError> {
+    let heir_keys = Keys::parse(heir_nsec)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid heir nsec: {}", e)))?;
+
+    let sender_pk = parse_pubkey(sender_npub)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid sender npub: {}", e)))?;
+
+    let relay_list: Vec<String> = relays
+        .map(|r| r.to_vec())
+        .unwrap_or_else(|| DEFAULT_RELAYS.iter().map(|s| s.to_string()).collect());
+
+    let split_id = split_id.map(str::to_string);
+    let query = move |relay: String| {
+        let heir_keys = heir_keys.clone();
+        let split_id = split_id.clone();
+        async move {
+            let client = Client::new(heir_keys.clone());
+            client
+                .add_relay(&relay)
+                .await
+                .map_err(|e| NotifyError::NostrFailed(format!("Failed to add relay: {}", e)))?;
+            client.connect().await;
+
+            let filter = Filter::new()
+                .kinds(vec![Kind::EncryptedDirectMessage, Kind::Custom(1059)])
+                .author(sender_pk)
+                .pubkey(heir_keys.public_key())
+                .limit(100);
+            let events = client.fetch_events(filter, RELAY_FETCH_TIMEOUT).await;
+            client.disconnect().await;
+
+            let events = events
+                .map_err(|e| NotifyError::NostrFailed(format!("Failed to fetch events: {}", e)))?;
+            let shares =
+                decrypt_share_events(&heir_keys, &sender_pk, events.iter(), split_id.as_deref());
+            Ok((events.len(), shares))
+        }
+    };
+
+    Ok(collect_shares_progressively(
+        relay_list,
+        DEFAULT_FETCH_CONCURRENCY,
+        RELAY_FETCH_TIMEOUT,
+        min_shares_needed,
+        query,
+        on_share,
+    )
+    .await)
+}
+
+/// Run `query` over `relays` with bounded concurrency, gathering shares
+/// until some split reaches `min_shares_needed`.
+///
+/// `query` returns the number of events a relay held and the shares
+/// decrypted from them. A relay that errors or exceeds `relay_timeout` is
+/// skipped.
+async fn collect_shares_progressively<F, Fut>(
+    relays: Vec<String>,
+    concurrency: usize,
+    relay_timeout: Duration,
+    min_shares_needed: Option<usize>,
+    query: F,
+    mut on_share: impl FnMut(&SharePayload),
+) -> RelayFetchResult
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<(usize, Vec<SharePayload>), NotifyError>>
+        + Send
+        + 'static,
+{
+    let mut pending = relays.into_iter();
+    let mut in_flight = tokio::task::JoinSet::new();
+    let mut shares: Vec<SharePayload> = Vec::new();
+    let mut responding_relays = Vec::new();
+    let mut events_found = 0;
+
+    let mut spawn_next = |in_flight: &mut tokio::task::JoinSet<_>| {
+        if let Some(relay) = pending.next() {
+            let fut = query(relay.clone());
+            in_flight.spawn(async move { (relay, tokio::time::timeout(relay_timeout, fut).await) });
+        }
+    };
+    for _ in 0..concurrency.max(1) {
+        spawn_next(&mut in_flight);
+    }
+
+    while let Some(joined) = in_flight.join_next().await {
+        match joined {
+            Ok((relay, Ok(Ok((events, found))))) => {
+                responding_relays.push(relay);
+                events_found += events;
+                for share in found {
+                    let seen = shares
+                        .iter()
+                        .any(|s| s.split_id == share.split_id && s.index == share.index);
+                    if !seen {
+                        on_share(&share);
+                        shares.push(share);
+                    }
+                }
+            }
+            Ok((relay, Ok(Err(e)))) => log::warn!("Relay {} fetch failed: {}", relay, e),
+            Ok((relay, Err(_))) => log::warn!("Relay {} timed out", relay),
+            Err(e) => log::warn!("Relay fetch task failed: {}", e),
+        }
+
+        let enough = min_shares_needed.is_some_and(|needed| {
+            shares
+                .iter()
+                .any(|s| shares.iter().filter(|o| o.split_id == s.split_id).count() >= needed)
+        });
+        if enough {
+            log::info!("Collected enough shares; abandoning remaining relays");
+            break;
+        }
+        spawn_next(&mut in_flight);
+    }
+    // Dropping the set aborts relays still in flight
+    drop(in_flight);
 
-    // Sort by index and deduplicate
     shares.sort_by_key(|s| s.index);
-    shares.dedup_by_key(|s| (s.split_id.clone(), s.index));
+    RelayFetchResult {
+        shares,
+        responding_relays,
+        events_found,
+    }
+}
+
+/// Fetch gift-wrapped shares from relays for a specific heir.
+///
+/// Counterpart of [`publish_share_giftwrapped`]. Gift wraps are signed by
+/// random keys, so every wrap addressed to the heir is fetched and only
+/// those sealed by `sender_npub` are kept.
+pub async fn fetch_giftwrapped_shares(
+    heir_nsec: &str,
+    sender_npub: &str,
+    relays: Option<&[String]>,
+    split_id: Option<&str>,
+) -> Result<RelayFetchResult, NotifyError> {
+    let heir_keys = Keys::parse(heir_nsec)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid heir nsec: {}", e)))?;
+
+    let sender_pk = parse_pubkey(sender_npub)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid sender npub: {}", e)))?;
+
+    let relay_list: Vec<String> = relays
+        .map(|r| r.to_vec())
+        .unwrap_or_else(|| DEFAULT_RELAYS.iter().map(|s| s.to_string()).collect());
+
+    let client = Client::new(heir_keys.clone());
+    for relay in &relay_list {
+        if let Err(e) = client.add_relay(relay).await {
+            log::warn!("Failed to add relay {}: {}", relay, e);
+        }
+    }
+    client.connect().await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
 
+    let filter = Filter::new()
+        .kind(Kind::GiftWrap)
+        .pubkey(heir_keys.public_key())
+        .limit(500);
+    let events = client
+        .fetch_events(filter, Duration::from_secs(10))
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to fetch events: {}", e)));
     client.disconnect().await;
+    let events = events?;
+
+    let mut shares = Vec::new();
+    for event in events.iter() {
+        // Other gift wraps (DMs, other senders) are expected; skip quietly
+        let Ok(payload) = unwrap_share(&heir_keys, &sender_pk, event).await else {
+            continue;
+        };
+        if split_id.is_some_and(|sid| payload.split_id != sid) {
+            continue;
+        }
+        shares.push(payload);
+    }
+
+    shares.sort_by_key(|s| s.index);
+    shares.dedup_by_key(|s| (s.split_id.clone(), s.index));
 
     Ok(RelayFetchResult {
         shares,
         responding_relays: relay_list,
-        events_found,
+        events_found: events.len(),
     })
 }
 
+/// Count the share events a sender published for `split_id`, per recipient.
+///
+/// Unlike [`fetch_shares_from_relays`] this needs no heir key: it only
+/// checks that the encrypted events are still retrievable, which lets the
+/// owner confirm heirs will find their shares without decrypting them.
+/// Gift-wrapped shares can't be counted this way: nothing outside the seal
+/// names the sender.
+pub async fn count_published_shares(
+    sender_npub: &str,
+    split_id: &str,
+    relays: Option<&[String]>,
+) -> Result<HashMap<PublicKey, usize>, NotifyError> {
+    let sender_pk = parse_pubkey(sender_npub)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid sender npub: {}", e)))?;
+
+    let relay_list: Vec<String> = relays
+        .map(|r| r.to_vec())
+        .unwrap_or_else(|| DEFAULT_RELAYS.iter().map(|s| s.to_string()).collect());
+
+    let client = Client::default();
+    for relay in &relay_list {
+        if let Err(e) = client.add_relay(relay).await {
+            log::warn!("Failed to add relay {}: {}", relay, e);
+        }
+    }
+    client.connect().await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let filter = Filter::new()
+        .kind(Kind::EncryptedDirectMessage)
+        .author(sender_pk)
+        .limit(500);
+    let events = client
+        .fetch_events(filter, Duration::from_secs(10))
+        .await
+        .map_err(|e| NotifyError::NostrFailed(format!("Failed to fetch events: {}", e)));
+    client.disconnect().await;
+
+    Ok(count_split_events(events?.iter(), split_id))
+}
+
+/// Events tagged `["split", split_id]`, counted per `p` tag recipient.
+fn count_split_events<'a>(
+    events: impl Iterator<Item = &'a Event>,
+    split_id: &str,
+) -> HashMap<PublicKey, usize> {
+    let mut counts = HashMap::new();
+    for event in events {
+        let tags: Vec<&[String]> = event.tags.iter().map(|t| t.as_slice()).collect();
+        if !tags
+            .iter()
+            .any(|t| t.len() >= 2 && t[0] == "split" && t[1] == split_id)
+        {
+            continue;
+        }
+        for tag in &tags {
+            if tag.len() >= 2 && tag[0] == "p" {
+                if let Ok(pk) = PublicKey::from_hex(&tag[1]) {
+                    *counts.entry(pk).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
 /// Encrypt content for an heir using NIP-44 (with NIP-04 fallback).
 ///
 /// Returns (encrypted_content, event_kind).
@@ -351,18 +1333,67 @@ fn encrypt_for_heir(
     }
 }
 
-/// Try to decrypt an event, attempting NIP-44 first then NIP-04.
+/// Try to decrypt a share event.
 fn decrypt_event(keys: &Keys, sender_pk: &PublicKey, event: &Event) -> Result<String, NotifyError> {
-    let content = &event.content;
+    decrypt_share(keys, sender_pk, &event.content)
+}
 
-    // Try NIP-44 first
-    if let Ok(decrypted) = nip44::decrypt(keys.secret_key(), sender_pk, content) {
-        return Ok(decrypted);
+/// Decrypt an encrypted share payload, dispatching on its encryption version.
+///
+/// NIP-04 payloads (`<ciphertext>?iv=<iv>`) are decrypted as legacy DMs.
+/// Everything else is treated as NIP-44: the leading version byte is checked
+/// explicitly so that payloads from a newer NIP-44 revision fail with
+/// [`NotifyError::UnsupportedEncryptionVersion`] instead of a generic
+/// decryption error. Heirs running older recovery tools can then tell
+/// "upgrade needed" apart from "wrong key".
+pub fn decrypt_share(
+    keys: &Keys,
+    sender_pk: &PublicKey,
+    payload: &str,
+) -> Result<String, NotifyError> {
+    if is_nip04_payload(payload) {
+        return nip04::decrypt(keys.secret_key(), sender_pk, payload)
+            .map_err(|e| NotifyError::NostrFailed(format!("NIP-04 decryption failed: {}", e)));
+    }
+
+    match nip44_payload_version(payload)? {
+        2 => nip44::decrypt(keys.secret_key(), sender_pk, payload)
+            .map_err(|e| NotifyError::NostrFailed(format!("NIP-44 decryption failed: {}", e))),
+        v => Err(NotifyError::UnsupportedEncryptionVersion(v)),
     }
+}
+
+/// Read the version byte from a NIP-44 payload without decrypting it.
+pub fn nip44_payload_version(payload: &str) -> Result<u8, NotifyError> {
+    use base64::prelude::*;
+
+    let bytes = BASE64_STANDARD
+        .decode(payload)
+        .map_err(|e| NotifyError::NostrFailed(format!("Invalid NIP-44 payload: {}", e)))?;
 
-    // Fall back to NIP-04
-    nip04::decrypt(keys.secret_key(), sender_pk, content)
-        .map_err(|e| NotifyError::NostrFailed(format!("Decryption failed: {}", e)))
+    bytes
+        .first()
+        .copied()
+        .ok_or_else(|| NotifyError::NostrFailed("Empty NIP-44 payload".into()))
+}
+
+/// NIP-04 ciphertexts carry their IV as a `?iv=` suffix; NIP-44 is plain base64.
+fn is_nip04_payload(payload: &str) -> bool {
+    payload.contains("?iv=")
+}
+
+/// Build the tag recording which encryption scheme produced `payload`,
+/// e.g. `["encryption", "nip44", "2"]` or `["encryption", "nip04"]`.
+fn encryption_tag(payload: &str) -> Tag {
+    let values = if is_nip04_payload(payload) {
+        vec!["nip04".to_string()]
+    } else {
+        match nip44_payload_version(payload) {
+            Ok(v) => vec!["nip44".to_string(), v.to_string()],
+            Err(_) => vec!["nip44".to_string()],
+        }
+    };
+    Tag::custom(TagKind::Custom(ENCRYPTION_TAG.into()), values)
 }
 
 /// Parse a public key from npub or hex format
@@ -477,6 +1508,75 @@ mod tests {
         assert_eq!(decrypted, "test message");
     }
 
+    #[tokio::test]
+    async fn test_gift_wrap_share_roundtrip() {
+        let sender = Keys::generate();
+        let heir = Keys::generate();
+        let payload = SharePayload {
+            share: "ms12nsecaxxxxxxxxxxxxxxxxxxxxxxxxxxxx".into(),
+            index: 1,
+            total: 3,
+            split_id: "abc123".into(),
+        };
+
+        let wrap = gift_wrap_share(&sender, &heir.public_key(), &payload)
+            .await
+            .unwrap();
+
+        // Nothing on the outer event points at the service key or split
+        assert_eq!(wrap.kind, Kind::GiftWrap);
+        assert_ne!(wrap.pubkey, sender.public_key());
+        assert!(!wrap.content.contains("abc123"));
+        assert!(wrap
+            .tags
+            .iter()
+            .all(|t| t.as_slice().first().map(String::as_str) == Some("p")));
+
+        let opened = unwrap_share(&heir, &sender.public_key(), &wrap)
+            .await
+            .unwrap();
+        assert_eq!(opened.share, payload.share);
+        assert_eq!(opened.index, 1);
+        assert_eq!(opened.split_id, "abc123");
+
+        // Sealed by someone else, or opened by the wrong heir: rejected
+        let impostor = Keys::generate();
+        assert!(unwrap_share(&heir, &impostor.public_key(), &wrap)
+            .await
+            .is_err());
+        assert!(unwrap_share(&impostor, &sender.public_key(), &wrap)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_count_split_events() {
+        let sender = Keys::generate();
+        let alice = Keys::generate().public_key();
+        let bob = Keys::generate().public_key();
+        let event = |to: PublicKey, split: &str| {
+            EventBuilder::new(Kind::EncryptedDirectMessage, "ciphertext")
+                .tag(Tag::public_key(to))
+                .tag(Tag::custom(
+                    TagKind::Custom("split".into()),
+                    vec![split.to_string()],
+                ))
+                .sign_with_keys(&sender)
+                .unwrap()
+        };
+        let events = vec![
+            event(alice, "s1"),
+            event(alice, "s1"),
+            event(bob, "s1"),
+            event(bob, "old-split"),
+        ];
+
+        let counts = count_split_events(events.iter(), "s1");
+        assert_eq!(counts.get(&alice), Some(&2));
+        assert_eq!(counts.get(&bob), Some(&1));
+        assert!(count_split_events(events.iter(), "missing").is_empty());
+    }
+
     #[test]
     fn test_decrypt_event_tries_both_nips() {
         let sender = Keys::generate();
@@ -516,6 +1616,81 @@ mod tests {
         assert_eq!(decrypted, "nip44 message");
     }
 
+    #[test]
+    fn test_decrypt_share_known_v2_vector() {
+        // NIP-44 v2 test vector (sec1 = 1, sec2 = 2, plaintext "a")
+        let sec1 =
+            SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+        let sec2 =
+            SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000002")
+                .unwrap();
+        let payload = "AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb";
+
+        assert_eq!(nip44_payload_version(payload).unwrap(), 2);
+
+        let recipient = Keys::new(sec2);
+        let sender_pk = Keys::new(sec1).public_key();
+        let decrypted = decrypt_share(&recipient, &sender_pk, payload).unwrap();
+        assert_eq!(decrypted, "a");
+    }
+
+    #[test]
+    fn test_decrypt_share_rejects_unknown_version() {
+        use base64::prelude::*;
+
+        let sender = Keys::generate();
+        let recipient = Keys::generate();
+
+        let encrypted = nip44::encrypt(
+            sender.secret_key(),
+            &recipient.public_key(),
+            "future share",
+            nip44::Version::V2,
+        )
+        .unwrap();
+
+        // Pretend a future NIP-44 revision produced this payload
+        let mut bytes = BASE64_STANDARD.decode(&encrypted).unwrap();
+        bytes[0] = 0x03;
+        let bogus = BASE64_STANDARD.encode(&bytes);
+
+        let err = decrypt_share(&recipient, &sender.public_key(), &bogus).unwrap_err();
+        assert!(
+            matches!(err, NotifyError::UnsupportedEncryptionVersion(3)),
+            "expected UnsupportedEncryptionVersion(3), got {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_decrypt_share_handles_nip04() {
+        let sender = Keys::generate();
+        let recipient = Keys::generate();
+
+        let encrypted =
+            nip04::encrypt(sender.secret_key(), &recipient.public_key(), "legacy").unwrap();
+
+        let decrypted = decrypt_share(&recipient, &sender.public_key(), &encrypted).unwrap();
+        assert_eq!(decrypted, "legacy");
+    }
+
+    #[test]
+    fn test_encryption_tag_records_version() {
+        let sender = Keys::generate();
+        let recipient = Keys::generate();
+
+        let (encrypted, _kind) =
+            encrypt_for_heir(&sender, &recipient.public_key(), "tagged").unwrap();
+        let tag = encryption_tag(&encrypted);
+        assert_eq!(tag.as_slice(), &["encryption", "nip44", "2"]);
+
+        let legacy =
+            nip04::encrypt(sender.secret_key(), &recipient.public_key(), "tagged").unwrap();
+        let tag = encryption_tag(&legacy);
+        assert_eq!(tag.as_slice(), &["encryption", "nip04"]);
+    }
+
     #[test]
     fn test_generate_split_id() {
         let id1 = generate_split_id();
@@ -591,6 +1766,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_share_publication_splits_relays() {
+        let relays = vec![
+            "wss://nos.lol".to_string(),
+            "wss://relay.damus.io".to_string(),
+            "not a url".to_string(),
+        ];
+        let accepting = RelayUrl::parse("wss://nos.lol").unwrap();
+        let publication = share_publication(2, Some("abc".into()), &relays, |url| {
+            if *url == accepting {
+                Ok(())
+            } else {
+                Err("rate limited".into())
+            }
+        });
+
+        assert_eq!(publication.index, 2);
+        assert_eq!(publication.accepted, vec!["wss://nos.lol".to_string()]);
+        assert_eq!(publication.failed.len(), 2);
+        assert_eq!(
+            publication.failed[0],
+            (
+                "wss://relay.damus.io".to_string(),
+                "rate limited".to_string()
+            )
+        );
+        assert!(publication.failed[1].1.starts_with("invalid relay URL"));
+    }
+
     #[test]
     fn test_relay_publish_result_serialization() {
         let result = RelayPublishResult {
@@ -602,6 +1806,7 @@ mod tests {
                     shares_published: 3,
                     event_ids: vec!["abc".to_string(), "def".to_string(), "ghi".to_string()],
                     error: None,
+                    publications: Vec::new(),
                 },
                 HeirPublishResult {
                     heir_npub: "npub1test2".to_string(),
@@ -609,6 +1814,7 @@ mod tests {
                     shares_published: 3,
                     event_ids: vec!["jkl".to_string()],
                     error: None,
+                    publications: Vec::new(),
                 },
             ],
             successful_relays: vec!["wss://relay.damus.io".to_string()],
@@ -640,9 +1846,285 @@ mod tests {
         assert_eq!(decoded.shares[0].share, "ms12test");
     }
 
+    #[test]
+    fn test_relay_list_roundtrip() {
+        let keys = Keys::generate();
+        let relays = vec![
+            "wss://relay.example.com".to_string(),
+            "wss://nos.lol".to_string(),
+        ];
+
+        let event = relay_list_event(&keys, &relays).unwrap();
+        assert_eq!(event.kind, Kind::RelayList);
+        assert_eq!(event.kind.as_u16(), 10002);
+        assert_eq!(relays_from_relay_list(&event), relays);
+    }
+
+    #[test]
+    fn test_relay_list_parsing_skips_read_only() {
+        let json = serde_json::json!({
+            "id": "0000000000000000000000000000000000000000000000000000000000000000",
+            "pubkey": Keys::generate().public_key().to_hex(),
+            "created_at": 1_700_000_000,
+            "kind": 10002,
+            "tags": [
+                ["r", "wss://write.example.com", "write"],
+                ["r", "wss://read.example.com", "read"],
+                ["r", "wss://both.example.com"],
+                ["r", "wss://both.example.com"],
+                ["r", "https://not-a-relay.example.com"],
+                ["p", "deadbeef"]
+            ],
+            "content": "",
+            "sig": "0".repeat(128)
+        });
+        let event: Event = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            relays_from_relay_list(&event),
+            vec!["wss://write.example.com", "wss://both.example.com"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relay_discovery_cache() {
+        let npub = Keys::generate().public_key().to_bech32().unwrap();
+        let relays = vec!["wss://custom.example.com".to_string()];
+
+        let cache = RelayDiscoveryCache::default();
+        assert_eq!(cache.get(&npub), None);
+        cache.insert(&npub, relays.clone());
+        assert_eq!(cache.get(&npub), Some(relays.clone()));
+        // A cached answer is returned without querying any relay
+        assert_eq!(cache.discover(&npub, Some(&[])).await.unwrap(), relays);
+
+        // An empty result is cached as well
+        let other = Keys::generate().public_key().to_bech32().unwrap();
+        cache.insert(&other, Vec::new());
+        assert_eq!(cache.get(&other), Some(Vec::new()));
+
+        let expired = RelayDiscoveryCache::new(Duration::ZERO);
+        expired.insert(&npub, relays);
+        assert_eq!(expired.get(&npub), None);
+    }
+
+    #[test]
+    fn test_key_migration_event_signed_by_old_key() {
+        let old = Keys::generate();
+        let new = Keys::generate();
+
+        let event = key_migration_event(&old, &new.public_key()).unwrap();
+        assert_eq!(event.pubkey, old.public_key());
+        assert_eq!(event.kind.as_u16(), KEY_MIGRATION_KIND);
+        assert_eq!(parse_key_migration(&event).unwrap(), new.public_key());
+
+        // The relay list kind is not a migration
+        let list = relay_list_event(&old, &[]).unwrap();
+        assert!(parse_key_migration(&list).is_err());
+    }
+
+    #[test]
+    fn test_follow_key_migrations() {
+        let keys: Vec<Keys> = (0..3).map(|_| Keys::generate()).collect();
+        let hop = |from: usize, to: usize| {
+            key_migration_event(&keys[from], &keys[to].public_key()).unwrap()
+        };
+
+        // Events arrive in any order
+        let events = vec![hop(1, 2), hop(0, 1)];
+        assert_eq!(
+            follow_key_migrations(&keys[0].public_key(), &events).unwrap(),
+            vec![keys[1].public_key(), keys[2].public_key()]
+        );
+        assert!(follow_key_migrations(&keys[2].public_key(), &events)
+            .unwrap()
+            .is_empty());
+
+        // A cycle back to the start ends the chain
+        let cycle = vec![hop(0, 1), hop(1, 0)];
+        assert_eq!(
+            follow_key_migrations(&keys[0].public_key(), &cycle).unwrap(),
+            vec![keys[1].public_key()]
+        );
+
+        // Two successors for one key can't be resolved automatically
+        let forked = vec![hop(0, 1), hop(0, 2)];
+        assert!(follow_key_migrations(&keys[0].public_key(), &forked).is_err());
+    }
+
+    fn mock_share(index: usize) -> SharePayload {
+        SharePayload {
+            share: format!("ms12test{}", index),
+            index,
+            total: 3,
+            split_id: "split".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progressive_fetch_stops_at_threshold() {
+        let relays: Vec<String> = ["wss://a", "wss://b", "wss://c", "wss://d"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let queried = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let log = queried.clone();
+        let query = move |relay: String| {
+            log.lock().unwrap().push(relay.clone());
+            async move {
+                // Each relay holds a different share; "a" and "b" together
+                // reach the threshold
+                let index = relay.as_bytes()[6] - b'a';
+                Ok((1, vec![mock_share(index as usize)]))
+            }
+        };
+
+        let mut arrived = Vec::new();
+        let result = collect_shares_progressively(
+            relays,
+            1,
+            Duration::from_secs(5),
+            Some(2),
+            query,
+            |share| arrived.push(share.index),
+        )
+        .await;
+
+        assert_eq!(arrived, vec![0, 1]);
+        assert_eq!(result.shares.len(), 2);
+        assert_eq!(result.responding_relays, vec!["wss://a", "wss://b"]);
+        // The remaining relays were never asked
+        assert_eq!(*queried.lock().unwrap(), vec!["wss://a", "wss://b"]);
+    }
+
+    #[tokio::test]
+    async fn test_progressive_fetch_not_blocked_by_slow_relay() {
+        let relays: Vec<String> = ["wss://slow", "wss://fast1", "wss://fast2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let query = |relay: String| async move {
+            match relay.as_str() {
+                "wss://slow" => {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    Ok((3, vec![mock_share(0), mock_share(1), mock_share(2)]))
+                }
+                "wss://fast1" => Ok((1, vec![mock_share(0)])),
+                _ => Ok((2, vec![mock_share(0), mock_share(2)])),
+            }
+        };
+
+        let started = Instant::now();
+        let result = collect_shares_progressively(
+            relays,
+            2,
+            Duration::from_secs(20),
+            Some(2),
+            query,
+            |_| {},
+        )
+        .await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let indices: Vec<usize> = result.shares.iter().map(|s| s.index).collect();
+        assert_eq!(indices, vec![0, 2]);
+        assert!(!result.responding_relays.contains(&"wss://slow".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_progressive_fetch_skips_timed_out_relays() {
+        let relays = vec!["wss://stuck".to_string(), "wss://ok".to_string()];
+        let query = |relay: String| async move {
+            if relay == "wss://stuck" {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok((1, vec![mock_share(1)]))
+        };
+
+        let result =
+            collect_shares_progressively(relays, 1, Duration::from_millis(50), None, query, |_| {})
+                .await;
+
+        assert_eq!(result.responding_relays, vec!["wss://ok"]);
+        assert_eq!(result.shares.len(), 1);
+    }
+
     #[test]
     fn test_default_relays() {
         assert_eq!(DEFAULT_RELAYS.len(), 3);
         assert!(DEFAULT_RELAYS.iter().all(|r| r.starts_with("wss://")));
     }
+
+    fn healthy(url: &str) -> RelayHealth {
+        RelayHealth {
+            url: url.to_string(),
+            reachable: true,
+            latency_ms: Some(42),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_require_healthy() {
+        let health = vec![
+            healthy("wss://a"),
+            RelayHealth::unreachable("wss://b", "timeout"),
+            healthy("wss://c"),
+        ];
+
+        assert_eq!(
+            require_healthy(&health, 2).unwrap(),
+            vec!["wss://a".to_string(), "wss://c".to_string()]
+        );
+
+        match require_healthy(&health, 3) {
+            Err(NotifyError::InsufficientRelays {
+                healthy,
+                required,
+                failed,
+            }) => {
+                assert_eq!(healthy, 2);
+                assert_eq!(required, 3);
+                assert_eq!(failed, vec!["wss://b".to_string()]);
+            }
+            other => panic!("expected InsufficientRelays, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_require_healthy_rejects_zero_even_when_min_is_zero() {
+        let health = vec![RelayHealth::unreachable("wss://a", "refused")];
+        let err = require_healthy(&health, 0).unwrap_err();
+        assert!(err.to_string().contains("wss://a"));
+    }
+
+    #[tokio::test]
+    async fn test_publish_fails_cleanly_with_no_healthy_relays() {
+        // Nothing listens on port 1, so every probe is refused
+        let relays = vec!["ws://127.0.0.1:1".to_string()];
+        let heirs = vec![(Keys::generate().public_key().to_hex(), "Alice".to_string())];
+        let sender = Keys::generate();
+
+        let result = publish_all_shares(
+            &sender.secret_key().to_secret_hex(),
+            &heirs,
+            &["ms12share".to_string()],
+            "split",
+            Some(&relays),
+            1,
+            PrivacyMode::Standard,
+        )
+        .await;
+
+        match result {
+            Err(NotifyError::InsufficientRelays {
+                healthy, failed, ..
+            }) => {
+                assert_eq!(healthy, 0);
+                assert_eq!(failed, relays);
+            }
+            other => panic!("expected InsufficientRelays, got {:?}", other),
+        }
+    }
 }
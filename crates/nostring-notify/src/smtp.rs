@@ -1,28 +1,39 @@
 //! SMTP email sending
 
-use crate::config::EmailConfig;
+use crate::config::{EmailConfig, TlsMode};
 use crate::templates::NotificationMessage;
 use crate::NotifyError;
+use lettre::message::MultiPart;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use nostring_core::redact::redact_email;
 
 /// Send an email notification (async — safe for tokio runtimes)
 pub async fn send_email(
     config: &EmailConfig,
     notification: &NotificationMessage,
 ) -> Result<(), NotifyError> {
-    let email = build_message(&config.from_address, &config.to_address, notification)?;
+    let email = build_message(
+        &config.from_address,
+        &config.to_address,
+        &config.cc,
+        &config.bcc,
+        notification,
+        config.plaintext_only,
+    )?;
 
     let mailer = build_async_transport(config)?;
 
     mailer
         .send(email)
         .await
-        .map_err(|e| NotifyError::EmailFailed(format!("SMTP send failed: {}", e)))?;
+        .map_err(|e| send_failed(config, e))?;
 
     log::info!(
-        "Email notification sent to {} (level: {:?})",
-        config.to_address,
+        "Email notification sent to {} (+{} cc, +{} bcc, level: {:?})",
+        redact_email(&config.to_address),
+        config.cc.len(),
+        config.bcc.len(),
         notification.level
     );
 
@@ -31,48 +42,119 @@ pub async fn send_email(
 
 /// Send an email notification to an arbitrary recipient (async).
 ///
-/// Unlike `send_email`, this overrides the `to_address` with a custom recipient.
-/// Used for heir descriptor delivery.
+/// Unlike `send_email`, this overrides the `to_address` with a custom recipient
+/// and never adds the configured CC/BCC — used for heir descriptor delivery,
+/// where one heir's address must not leak to another.
 pub async fn send_email_to_recipient(
     config: &EmailConfig,
     recipient_email: &str,
     notification: &NotificationMessage,
 ) -> Result<(), NotifyError> {
-    let email = build_message(&config.from_address, recipient_email, notification)?;
+    let email = build_message(
+        &config.from_address,
+        recipient_email,
+        &[],
+        &[],
+        notification,
+        config.plaintext_only,
+    )?;
 
     let mailer = build_async_transport(config)?;
 
     mailer
         .send(email)
         .await
-        .map_err(|e| NotifyError::EmailFailed(format!("SMTP send failed: {}", e)))?;
+        .map_err(|e| send_failed(config, e))?;
 
     log::info!(
         "Email notification sent to {} (level: {:?})",
-        recipient_email,
+        redact_email(recipient_email),
         notification.level
     );
 
     Ok(())
 }
 
+/// Basic sanity check for a recipient address: an `@` with a dotted domain.
+fn validate_address(addr: &str) -> Result<(), NotifyError> {
+    match addr.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && domain.contains('.') => Ok(()),
+        _ => Err(NotifyError::EmailFailed(format!(
+            "Invalid recipient address: {}",
+            redact_email(addr)
+        ))),
+    }
+}
+
 /// Build a `lettre::Message` from addresses and notification content.
+///
+/// Notifications with an HTML body become multipart/alternative with the
+/// plain text as the fallback part, unless `plaintext_only` is set.
 fn build_message(
     from: &str,
     to: &str,
+    cc: &[String],
+    bcc: &[String],
     notification: &NotificationMessage,
+    plaintext_only: bool,
 ) -> Result<Message, NotifyError> {
-    Message::builder()
+    let mut builder = Message::builder()
         .from(
             from.parse()
                 .map_err(|e| NotifyError::EmailFailed(format!("Invalid from address: {}", e)))?,
         )
         .to(to
             .parse()
-            .map_err(|e| NotifyError::EmailFailed(format!("Invalid to address: {}", e)))?)
-        .subject(&notification.subject)
-        .body(notification.body.clone())
-        .map_err(|e| NotifyError::EmailFailed(format!("Failed to build email: {}", e)))
+            .map_err(|e| NotifyError::EmailFailed(format!("Invalid to address: {}", e)))?);
+
+    for addr in cc {
+        validate_address(addr)?;
+        builder = builder.cc(addr
+            .parse()
+            .map_err(|e| NotifyError::EmailFailed(format!("Invalid cc address: {}", e)))?);
+    }
+    for addr in bcc {
+        validate_address(addr)?;
+        builder =
+            builder
+                .bcc(addr.parse().map_err(|e| {
+                    NotifyError::EmailFailed(format!("Invalid bcc address: {}", e))
+                })?);
+    }
+
+    let builder = builder.subject(&notification.subject);
+    match (&notification.html_body, plaintext_only) {
+        (Some(html), false) => builder.multipart(MultiPart::alternative_plain_html(
+            notification.body.clone(),
+            html.clone(),
+        )),
+        _ => builder.body(notification.body.clone()),
+    }
+    .map_err(|e| NotifyError::EmailFailed(format!("Failed to build email: {}", e)))
+}
+
+/// Wrap a lettre send error, scrubbing anything the server echoed back
+/// that matches our credentials or the recipient addresses.
+fn send_failed(config: &EmailConfig, e: lettre::transport::smtp::Error) -> NotifyError {
+    let mut message = format!("SMTP send failed: {}", e);
+    if !config.smtp_password.is_empty() {
+        message = message.replace(&config.smtp_password, "***");
+    }
+    NotifyError::EmailFailed(scrub_addresses(&message))
+}
+
+/// Redact every `local@domain` token in free text.
+fn scrub_addresses(text: &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            if word.contains('@') {
+                redact_email(word.trim_matches(|c: char| "<>,;:()\"'".contains(c)))
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Build an async SMTP transport from config.
@@ -81,23 +163,20 @@ fn build_async_transport(
 ) -> Result<AsyncSmtpTransport<Tokio1Executor>, NotifyError> {
     let creds = Credentials::new(config.smtp_user.clone(), config.smtp_password.clone());
 
-    if config.plaintext {
+    let builder = match config.tls_mode {
         // Plaintext SMTP — for local test servers (MailHog, etc.)
-        Ok(
-            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
-                .credentials(creds)
-                .port(config.smtp_port)
-                .build(),
-        )
-    } else {
-        Ok(
-            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
-                .map_err(|e| NotifyError::EmailFailed(format!("SMTP relay error: {}", e)))?
-                .credentials(creds)
-                .port(config.smtp_port)
-                .build(),
-        )
-    }
+        TlsMode::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+        // Plaintext connect, then STARTTLS upgrade
+        TlsMode::StartTls => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+                .map_err(|e| NotifyError::EmailFailed(format!("SMTP STARTTLS error: {}", e)))?
+        }
+        // TLS wrapper from the first byte
+        TlsMode::ImplicitTls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
+            .map_err(|e| NotifyError::EmailFailed(format!("SMTP relay error: {}", e)))?,
+    };
+
+    Ok(builder.credentials(creds).port(config.smtp_port).build())
 }
 
 #[cfg(test)]
@@ -110,11 +189,114 @@ mod tests {
         // Test that we can build a valid email message
         let notification = generate_message(NotificationLevel::Reminder, 25.0, 3600, 934000);
 
-        let email = build_message("noreply@nostring.dev", "test@example.com", &notification);
+        let email = build_message(
+            "noreply@nostring.dev",
+            "test@example.com",
+            &[],
+            &[],
+            &notification,
+            false,
+        );
 
         assert!(email.is_ok());
     }
 
+    #[test]
+    fn test_email_cc_and_bcc() {
+        let notification = generate_message(NotificationLevel::Reminder, 25.0, 3600, 934000);
+        let cc = vec![
+            "friend@example.com".to_string(),
+            "lawyer@example.org".to_string(),
+        ];
+        let bcc = vec!["archive@example.net".to_string()];
+
+        let email = build_message(
+            "noreply@nostring.dev",
+            "owner@example.com",
+            &cc,
+            &bcc,
+            &notification,
+            false,
+        )
+        .unwrap();
+
+        let raw = String::from_utf8(email.formatted()).unwrap();
+        let cc_line = raw
+            .lines()
+            .find(|l| l.starts_with("Cc:"))
+            .expect("Cc header present");
+        assert!(cc_line.contains("friend@example.com"), "{}", cc_line);
+        assert!(cc_line.contains("lawyer@example.org"), "{}", cc_line);
+
+        // BCC recipients receive the message but aren't in its headers
+        let envelope: Vec<String> = email
+            .envelope()
+            .to()
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert!(envelope.contains(&"archive@example.net".to_string()));
+        assert!(!raw.contains("archive@example.net"));
+    }
+
+    #[test]
+    fn test_invalid_cc_rejected() {
+        let notification = generate_message(NotificationLevel::Reminder, 25.0, 3600, 934000);
+        for bad in ["no-at-sign.example.com", "user@localhost", "@example.com"] {
+            let result = build_message(
+                "noreply@nostring.dev",
+                "owner@example.com",
+                &[bad.to_string()],
+                &[],
+                &notification,
+                false,
+            );
+            assert!(result.is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_email_multipart_alternative() {
+        let notification = generate_message(NotificationLevel::Warning, 10.0, 1440, 934000);
+
+        let email = build_message(
+            "noreply@nostring.dev",
+            "owner@example.com",
+            &[],
+            &[],
+            &notification,
+            false,
+        )
+        .unwrap();
+        let raw = String::from_utf8(email.formatted()).unwrap();
+        assert!(raw.contains("multipart/alternative"), "{}", raw);
+        let plain_at = raw.find("text/plain").expect("plain text part");
+        let html_at = raw.find("text/html").expect("HTML part");
+        // Clients show the last part they support, so plain text comes first
+        assert!(plain_at < html_at);
+
+        let email = build_message(
+            "noreply@nostring.dev",
+            "owner@example.com",
+            &[],
+            &[],
+            &notification,
+            true,
+        )
+        .unwrap();
+        let raw = String::from_utf8(email.formatted()).unwrap();
+        assert!(!raw.contains("multipart"));
+        assert!(!raw.contains("text/html"));
+    }
+
     // Note: Actual SMTP tests require a real server
     // Use: cargo test --package nostring-notify -- --ignored
+
+    #[test]
+    fn test_scrub_addresses() {
+        let scrubbed = scrub_addresses("550 5.1.1 <heir@example.com>: mailbox unavailable");
+        assert!(!scrubbed.contains("heir@example.com"));
+        assert!(scrubbed.contains("h***@example.com"));
+        assert!(scrubbed.contains("mailbox unavailable"));
+    }
 }
@@ -22,6 +22,8 @@ pub struct NotificationMessage {
     pub subject: String,
     /// Message body (plain text)
     pub body: String,
+    /// Styled HTML version of `body` for email, if the template has one
+    pub html_body: Option<String>,
     /// Urgency level
     pub level: NotificationLevel,
 }
@@ -41,6 +43,21 @@ pub fn generate_message(
         format!("{:.0} days", days_remaining)
     };
 
+    let (headline, accent) = match level {
+        NotificationLevel::Reminder => ("Check-in reminder", "#2f6f4f"),
+        NotificationLevel::Warning => ("Check-in required soon", "#b7791f"),
+        NotificationLevel::Urgent => ("Check-in required immediately", "#c05621"),
+        NotificationLevel::Critical => ("Timelock expired or expiring now", "#c53030"),
+    };
+    let html_body = render_html(
+        headline,
+        accent,
+        &days_str,
+        blocks_remaining,
+        current_height,
+        level,
+    );
+
     let (subject, body) = match level {
         NotificationLevel::Reminder => (
             format!("NoString: Check-in reminder ({} remaining)", days_str),
@@ -132,10 +149,112 @@ NoString"#,
     NotificationMessage {
         subject,
         body,
+        html_body: Some(html_body),
         level,
     }
 }
 
+/// Render the HTML email body for a timelock notification, with the time
+/// and blocks remaining as the most prominent element.
+fn render_html(
+    headline: &str,
+    accent: &str,
+    days_str: &str,
+    blocks_remaining: i64,
+    current_height: u32,
+    level: NotificationLevel,
+) -> String {
+    let (remaining, action) = match level {
+        NotificationLevel::Critical => (
+            "Expired or expiring now".to_string(),
+            "Your heirs can now claim your Bitcoin. If you are still in control, \
+             open NoString immediately, complete a check-in transaction and \
+             monitor for heir claims.",
+        ),
+        NotificationLevel::Urgent => (
+            format!("{} remaining", days_str),
+            "If you do not check in before expiry, your heirs can claim. \
+             Please complete a check-in transaction immediately.",
+        ),
+        NotificationLevel::Warning => (
+            format!("{} remaining", days_str),
+            "If you do not check in before the timelock expires, your designated \
+             heirs will be able to claim your Bitcoin. Please check in soon.",
+        ),
+        NotificationLevel::Reminder => (
+            format!("{} remaining", days_str),
+            "To reset your timelock and prove you're still in control of your \
+             Bitcoin, open NoString and complete a check-in transaction.",
+        ),
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<body style="margin:0;padding:24px;background:#f4f4f5;font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;color:#1f2933;">
+<table role="presentation" width="100%" cellpadding="0" cellspacing="0" style="max-width:560px;margin:0 auto;background:#ffffff;border-radius:8px;border-top:6px solid {accent};">
+<tr><td style="padding:24px;">
+<h1 style="margin:0 0 16px;font-size:20px;color:{accent};">{headline}</h1>
+<p style="margin:0;font-size:32px;font-weight:bold;">{remaining}</p>
+<p style="margin:4px 0 24px;font-size:16px;">{blocks} blocks remaining &middot; current height {height}</p>
+<p style="margin:0 0 24px;font-size:15px;line-height:1.5;">{action}</p>
+<p style="margin:0;font-size:13px;color:#6b7280;">Stay sovereign,<br>NoString</p>
+</td></tr>
+</table>
+</body>
+</html>"#,
+        accent = accent,
+        headline = html_escape(headline),
+        remaining = html_escape(&remaining),
+        blocks = blocks_remaining,
+        height = current_height,
+        action = html_escape(action),
+    )
+}
+
+/// Escape text for inclusion in HTML element content.
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Generate an inactivity reminder, independent of timelock proximity.
+///
+/// Sent when the owner hasn't checked in for a while even though the
+/// timelock may still be far from expiry (e.g. long timelocks or late funding).
+pub fn generate_inactivity_message(days_since_checkin: u64) -> NotificationMessage {
+    NotificationMessage {
+        subject: format!("NoString: No check-in for {} days", days_since_checkin),
+        body: format!(
+            r#"Hello,
+
+It has been {} days since your last NoString check-in.
+
+Your inheritance timelock may not be close to expiring yet, but regular
+check-ins keep your heirs' timelock from running down and confirm you
+still have access to your keys.
+
+Please open NoString and complete a check-in when convenient.
+
+Stay sovereign,
+NoString"#,
+            days_since_checkin
+        ),
+        html_body: None,
+        level: NotificationLevel::Reminder,
+    }
+}
+
 /// Generate a descriptor delivery message for an heir.
 ///
 /// This is sent when the timelock is critical — it contains the full
@@ -174,9 +293,11 @@ If you have questions, refer to the NoString documentation.
 This message was sent automatically by the NoString inheritance system."#,
     );
 
+    // Plain text only: heirs copy the backup out verbatim
     NotificationMessage {
         subject,
         body,
+        html_body: None,
         level: NotificationLevel::Critical,
     }
 }
@@ -207,6 +328,26 @@ mod tests {
         assert!(msg.body.contains("EXPIRED"));
     }
 
+    #[test]
+    fn test_generate_html_body() {
+        let msg = generate_message(NotificationLevel::Warning, 10.0, 1440, 934000);
+        let html = msg.html_body.expect("timelock notifications have HTML");
+        assert!(html.contains("10 days remaining"));
+        assert!(html.contains("1440 blocks remaining"));
+        assert!(html.contains("934000"));
+
+        let msg = generate_message(NotificationLevel::Critical, -1.0, -144, 934000);
+        assert!(msg.html_body.unwrap().contains("Expired or expiring now"));
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(
+            html_escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
     #[test]
     fn test_level_ordering() {
         assert!(NotificationLevel::Critical > NotificationLevel::Urgent);
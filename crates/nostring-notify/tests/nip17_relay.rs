@@ -24,6 +24,7 @@ async fn test_nip17_dm_roundtrip() {
     let notification = NotificationMessage {
         subject: "Test Notification".into(),
         body: "Your vault timelock is approaching.".into(),
+        html_body: None,
         level: NotificationLevel::Warning,
     };
 
@@ -0,0 +1,392 @@
+//! Command-line parsing.
+//!
+//! Kept dependency-free (no clap), like `nostring-server`: the first bare
+//! word selects a subcommand, the second names the backup file, and flags
+//! are matched by hand.
+
+use anyhow::{Context, Result};
+use bitcoin::{Amount, OutPoint};
+use nostring_inherit::heir::RecoveryInput;
+use nostring_inherit::policy::Timelock;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Environment variable holding the manifest password, so it stays out of
+/// shell history
+pub const PASSWORD_ENV_VAR: &str = "NOSTRING_MANIFEST_PASSWORD";
+
+/// What the binary was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Check the descriptor against the rest of the backup (offline
+    /// unless `owner_npub` is given)
+    Verify {
+        /// The service npub the owner gave out; the signer must be it or a
+        /// key it migrated to
+        owner_npub: Option<String>,
+    },
+    /// Reconstruct the owner's nsec from shares (offline)
+    Nsec { shares: Vec<String> },
+    /// Build the unsigned recovery PSBT (offline)
+    Psbt {
+        destination: String,
+        utxos: Vec<RecoveryInput>,
+        fee_rate: u64,
+        /// Heir timelock to claim with; the backup's when not given
+        timelock: Option<Timelock>,
+    },
+    /// List the inheritance UTXOs via Electrum
+    Balance { electrum_url: String },
+    /// Broadcast a signed PSBT via Electrum
+    Broadcast { psbt: String, electrum_url: String },
+    /// Print usage
+    Help,
+    /// Print version
+    Version,
+}
+
+/// Parsed command-line arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+    /// Manifest or descriptor backup file; `None` only for help/version
+    pub backup: Option<PathBuf>,
+    pub password: Option<String>,
+    /// Recover from a backup that carries no signature
+    pub allow_unsigned: bool,
+    pub command: Command,
+}
+
+/// Parse arguments (excluding the program name).
+pub fn parse_args(args: &[String]) -> Result<CliArgs> {
+    let mut words: Vec<&str> = Vec::new();
+    let mut password = None;
+    let mut allow_unsigned = false;
+    let mut shares = Vec::new();
+    let mut utxos = Vec::new();
+    let mut destination = None;
+    let mut fee_rate = None;
+    let mut timelock = None;
+    let mut electrum_url = None;
+    let mut owner_npub = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        let mut value = || -> Result<String> {
+            i += 1;
+            match args.get(i) {
+                Some(v) if !v.starts_with("--") => Ok(v.clone()),
+                _ => anyhow::bail!("{} requires a value", flag),
+            }
+        };
+        match flag {
+            "--help" | "-h" | "help" => return Ok(bare(Command::Help)),
+            "--version" | "-V" => return Ok(bare(Command::Version)),
+            "--password" => password = Some(value()?),
+            "--allow-unsigned" => allow_unsigned = true,
+            "--share" => shares.push(value()?),
+            "--utxo" => utxos.push(parse_utxo(&value()?)?),
+            "--to" => destination = Some(value()?),
+            "--fee-rate" => {
+                let rate = value()?;
+                fee_rate = Some(
+                    rate.parse::<u64>()
+                        .ok()
+                        .filter(|r| *r > 0)
+                        .with_context(|| format!("Invalid --fee-rate '{}' (sat/vB)", rate))?,
+                )
+            }
+            "--timelock" => timelock = Some(parse_timelock(&value()?)?),
+            "--electrum" => electrum_url = Some(value()?),
+            "--owner-npub" => owner_npub = Some(value()?),
+            other if other.starts_with('-') => {
+                anyhow::bail!("Unknown option: {} (see --help)", other)
+            }
+            word => words.push(word),
+        }
+        i += 1;
+    }
+
+    let (subcommand, rest) = words
+        .split_first()
+        .context("No command given (see --help)")?;
+    let positional = |n: usize, what: &str| -> Result<String> {
+        rest.get(n)
+            .map(|s| s.to_string())
+            .with_context(|| format!("{} requires {}", subcommand, what))
+    };
+    let backup = PathBuf::from(positional(0, "a manifest or backup file")?);
+    let expected_words = if *subcommand == "broadcast" { 2 } else { 1 };
+    if rest.len() > expected_words {
+        anyhow::bail!("Unexpected argument: {}", rest[expected_words]);
+    }
+    let electrum = |url: Option<String>| {
+        url.with_context(|| format!("{} requires --electrum <URL>", subcommand))
+    };
+
+    let command = match *subcommand {
+        "verify" => Command::Verify { owner_npub },
+        "nsec" => {
+            if shares.is_empty() {
+                anyhow::bail!("nsec requires at least one --share");
+            }
+            Command::Nsec { shares }
+        }
+        "psbt" => {
+            if utxos.is_empty() {
+                anyhow::bail!("psbt requires at least one --utxo (see `balance`)");
+            }
+            Command::Psbt {
+                destination: destination.context("psbt requires --to <ADDRESS>")?,
+                utxos,
+                fee_rate: fee_rate.context("psbt requires --fee-rate <SAT/VB>")?,
+                timelock,
+            }
+        }
+        "balance" => Command::Balance {
+            electrum_url: electrum(electrum_url)?,
+        },
+        "broadcast" => Command::Broadcast {
+            psbt: positional(1, "a signed PSBT (base64 or file path)")?,
+            electrum_url: electrum(electrum_url)?,
+        },
+        other => anyhow::bail!("Unknown command: {} (see --help)", other),
+    };
+
+    Ok(CliArgs {
+        backup: Some(backup),
+        password: password.or_else(|| std::env::var(PASSWORD_ENV_VAR).ok()),
+        allow_unsigned,
+        command,
+    })
+}
+
+fn bare(command: Command) -> CliArgs {
+    CliArgs {
+        backup: None,
+        password: None,
+        allow_unsigned: false,
+        command,
+    }
+}
+
+/// Parse a `--timelock`: `BLOCKS`, or a time-based lock as `<DAYS>d` or
+/// `<SECONDS>s` (rounded up to whole 512-second intervals).
+pub fn parse_timelock(s: &str) -> Result<Timelock> {
+    let invalid = || {
+        format!(
+            "Invalid --timelock '{}' (expected BLOCKS, <DAYS>d or <SECONDS>s)",
+            s
+        )
+    };
+    let timelock = if let Some(days) = s.strip_suffix('d') {
+        let days: u32 = days.parse().with_context(invalid)?;
+        Timelock::from_seconds(days.checked_mul(86_400).with_context(invalid)?)
+    } else if let Some(seconds) = s.strip_suffix('s') {
+        Timelock::from_seconds(seconds.parse().with_context(invalid)?)
+    } else {
+        Timelock::from_blocks(s.parse().with_context(invalid)?)
+    };
+    timelock.with_context(invalid)
+}
+
+/// Parse `TXID:VOUT:SATS[:INDEX]`, the form `balance` prints.
+pub fn parse_utxo(s: &str) -> Result<RecoveryInput> {
+    let invalid = || format!("Invalid --utxo '{}' (expected TXID:VOUT:SATS[:INDEX])", s);
+    let parts: Vec<&str> = s.split(':').collect();
+    let (outpoint, sats, index) = match parts.as_slice() {
+        [txid, vout, sats] => (format!("{}:{}", txid, vout), *sats, "0"),
+        [txid, vout, sats, index] => (format!("{}:{}", txid, vout), *sats, *index),
+        _ => anyhow::bail!(invalid()),
+    };
+    Ok(RecoveryInput {
+        outpoint: OutPoint::from_str(&outpoint).with_context(invalid)?,
+        value: Amount::from_sat(sats.parse().with_context(invalid)?),
+        derivation_index: index.parse().with_context(invalid)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        assert_eq!(parse(&["-h"]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["verify", "--help"]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["-V"]).unwrap().command, Command::Version);
+    }
+
+    #[test]
+    fn test_offline_commands() {
+        let cli = parse(&["verify", "family.nostring"]).unwrap();
+        assert_eq!(cli.command, Command::Verify { owner_npub: None });
+        assert_eq!(cli.backup, Some(PathBuf::from("family.nostring")));
+        assert!(!cli.allow_unsigned);
+        let cli = parse(&["verify", "family.nostring", "--allow-unsigned"]).unwrap();
+        assert!(cli.allow_unsigned);
+        let cli = parse(&["verify", "family.nostring", "--owner-npub", "npub1abc"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Verify {
+                owner_npub: Some("npub1abc".into())
+            }
+        );
+
+        let cli = parse(&[
+            "nsec",
+            "backup.json",
+            "--share",
+            "ms12testaxxx",
+            "--share",
+            "ms12testcyyy",
+            "--password",
+            "hunter2",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Nsec {
+                shares: vec!["ms12testaxxx".into(), "ms12testcyyy".into()]
+            }
+        );
+        assert_eq!(cli.password.as_deref(), Some("hunter2"));
+
+        let utxo = format!("{}:1:50000:7", TXID);
+        let cli = parse(&[
+            "psbt",
+            "family.nostring",
+            "--utxo",
+            &utxo,
+            "--to",
+            "bc1qexample",
+            "--fee-rate",
+            "4",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Psbt {
+                destination,
+                utxos,
+                fee_rate,
+                timelock,
+            } => {
+                assert_eq!(destination, "bc1qexample");
+                assert_eq!(fee_rate, 4);
+                assert_eq!(timelock, None);
+                assert_eq!(utxos[0].outpoint.vout, 1);
+                assert_eq!(utxos[0].value, Amount::from_sat(50_000));
+                assert_eq!(utxos[0].derivation_index, 7);
+            }
+            other => panic!("expected psbt, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_network_commands_need_electrum() {
+        assert!(parse(&["balance", "family.nostring"]).is_err());
+        let cli = parse(&["balance", "family.nostring", "--electrum", "ssl://e:50002"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Balance {
+                electrum_url: "ssl://e:50002".into()
+            }
+        );
+
+        let cli = parse(&[
+            "broadcast",
+            "family.nostring",
+            "signed.psbt",
+            "--electrum",
+            "tcp://e:50001",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Broadcast {
+                psbt: "signed.psbt".into(),
+                electrum_url: "tcp://e:50001".into()
+            }
+        );
+        assert!(parse(&["broadcast", "family.nostring", "--electrum", "tcp://e:1"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["verify"]).is_err());
+        assert!(parse(&["verify", "a", "b"]).is_err());
+        assert!(parse(&["frobnicate", "a"]).is_err());
+        assert!(parse(&["nsec", "a"]).is_err());
+        assert!(parse(&["verify", "a", "--bogus"]).is_err());
+        assert!(parse(&["verify", "a", "--password"]).is_err());
+
+        // psbt needs inputs, a destination and a positive fee rate
+        let utxo = format!("{}:0:1000", TXID);
+        assert!(parse(&["psbt", "a", "--to", "x", "--fee-rate", "2"]).is_err());
+        assert!(parse(&["psbt", "a", "--utxo", &utxo, "--fee-rate", "2"]).is_err());
+        assert!(parse(&["psbt", "a", "--utxo", &utxo, "--to", "x"]).is_err());
+        assert!(parse(&["psbt", "a", "--utxo", &utxo, "--to", "x", "--fee-rate", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_timelock() {
+        assert_eq!(
+            parse_timelock("26280").unwrap(),
+            Timelock::from_blocks(26_280).unwrap()
+        );
+        assert_eq!(
+            parse_timelock("180d").unwrap(),
+            Timelock::from_seconds(180 * 86_400).unwrap()
+        );
+        // Seconds round up to the next 512-second interval
+        assert_eq!(
+            parse_timelock("1000s").unwrap(),
+            Timelock::from_512_second_intervals(2).unwrap()
+        );
+
+        assert!(parse_timelock("0").is_err());
+        assert!(parse_timelock("70000").is_err());
+        assert!(parse_timelock("1000d").is_err());
+        assert!(parse_timelock("6m").is_err());
+        assert!(parse_timelock("d").is_err());
+
+        let utxo = format!("{}:0:1000", TXID);
+        let cli = parse(&[
+            "psbt",
+            "a",
+            "--utxo",
+            &utxo,
+            "--to",
+            "x",
+            "--fee-rate",
+            "2",
+            "--timelock",
+            "90d",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Psbt { timelock: Some(t), .. } if t == Timelock::from_seconds(90 * 86_400).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_parse_utxo() {
+        let utxo = parse_utxo(&format!("{}:2:1500", TXID)).unwrap();
+        assert_eq!(utxo.outpoint.to_string(), format!("{}:2", TXID));
+        assert_eq!(utxo.derivation_index, 0);
+
+        assert!(parse_utxo(TXID).is_err());
+        assert!(parse_utxo(&format!("{}:x:1500", TXID)).is_err());
+        assert!(parse_utxo(&format!("{}:0:lots", TXID)).is_err());
+        assert!(parse_utxo("nothex:0:1500").is_err());
+    }
+}
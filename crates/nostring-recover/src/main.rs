@@ -0,0 +1,265 @@
+//! NoString Recover — standalone heir recovery tool
+//!
+//! Everything an heir needs to claim an inheritance, without the desktop
+//! app or the owner's server. Verifying the backup, rebuilding the nsec and
+//! building the recovery PSBT run entirely offline, so the tool can live on
+//! an air-gapped machine; only `balance` and `broadcast` talk to Electrum,
+//! and `verify --owner-npub` asks Nostr relays for service key rotations.
+//!
+//! # Usage
+//!
+//! ```bash
+//! nostring-recover verify family.nostring
+//! nostring-recover verify family.nostring --owner-npub npub1...
+//! nostring-recover nsec family.nostring --share ms12nost...
+//! nostring-recover psbt family.nostring --utxo TXID:VOUT:SATS:INDEX --to bc1q... --fee-rate 4
+//! nostring-recover balance family.nostring --electrum ssl://electrum.blockstream.info:50002
+//! nostring-recover broadcast family.nostring signed.psbt --electrum ssl://...
+//! ```
+
+mod cli;
+mod recovery;
+
+use anyhow::{Context, Result};
+use bitcoin::psbt::Psbt;
+use cli::Command;
+use nostring_core::redact::redact_descriptor;
+use nostring_electrum::ElectrumClient;
+use recovery::VerifiedBackup;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Receive indices `balance` scans for funds past the backup's address
+const BALANCE_SCAN_INDICES: u32 = 20;
+
+fn main() -> Result<()> {
+    // Security hardening: disable core dumps to prevent the nsec leaking to disk
+    nostring_core::memory::disable_core_dumps();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli::CliArgs {
+        backup,
+        password,
+        allow_unsigned,
+        command,
+    } = cli::parse_args(&args)?;
+
+    let backup_path = match command {
+        Command::Help => {
+            print_help();
+            return Ok(());
+        }
+        Command::Version => {
+            println!("nostring-recover {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        _ => backup.context("No manifest or backup file given")?,
+    };
+
+    let backup = recovery::load_backup(&backup_path, password.as_deref())?;
+    let verified =
+        recovery::verify_backup(backup, allow_unsigned).context("Backup failed verification")?;
+    if verified.signer_npub.is_none() {
+        eprintln!(
+            "⚠️  WARNING: this backup is not signed. Nothing shows it is the one the owner \
+             made — check the descriptor and addresses with them or their executor before \
+             moving funds."
+        );
+    }
+
+    match command {
+        Command::Verify { owner_npub } => {
+            print_verified(&verified);
+            if let Some(owner_npub) = owner_npub {
+                check_signer(&verified, &owner_npub)?;
+            }
+        }
+        Command::Nsec { shares } => {
+            let recovered = recovery::reconstruct_nsec(&verified.backup, &shares)?;
+            println!("npub: {}", recovered.npub);
+            println!("nsec: {}", recovered.nsec);
+        }
+        Command::Psbt {
+            destination,
+            utxos,
+            fee_rate,
+            timelock,
+        } => {
+            let psbt =
+                recovery::recovery_psbt(&verified, &utxos, &destination, fee_rate, timelock)?;
+            println!("{}", psbt);
+        }
+        Command::Balance { electrum_url } => print_balance(&verified, &electrum_url)?,
+        Command::Broadcast { psbt, electrum_url } => broadcast(&verified, &psbt, &electrum_url)?,
+        Command::Help | Command::Version => unreachable!("handled above"),
+    }
+    Ok(())
+}
+
+fn print_verified(verified: &VerifiedBackup) {
+    let backup = &verified.backup;
+    println!("✅ Backup verified.");
+    match &verified.signer_npub {
+        Some(npub) => {
+            println!("  Signed by:   {}", npub);
+            println!("               (compare with the npub the owner gave you)");
+        }
+        None => println!("  Signed by:   nobody (unsigned backup)"),
+    }
+    println!("  Network:     {}", verified.network);
+    println!("  Descriptor:  {}", redact_descriptor(&backup.descriptor));
+    println!("  Timelock:    {}", verified.timelock);
+    if let Some(address) = &backup.address {
+        match backup.address_index {
+            Some(index) => println!("  Address:     {} (receive index {})", address, index),
+            None => println!("  Address:     {}", address),
+        }
+    }
+    for heir in &backup.heirs {
+        println!("  Heir:        {}", heir.label);
+    }
+    if let Some(shares) = &backup.locked_shares {
+        println!("  Locked shares: {}", shares.len());
+    }
+}
+
+/// Check the backup's signer against the npub the owner gave out,
+/// following the service key's migrations on relays if it was rotated.
+fn check_signer(verified: &VerifiedBackup, owner_npub: &str) -> Result<()> {
+    let signer = verified
+        .signer_npub
+        .as_deref()
+        .context("The backup is unsigned, so there is no signer to check")?;
+    let rotations = match recovery::rotations_to_signer(owner_npub, signer, &[])? {
+        Some(0) => 0,
+        _ => {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            let migrations = runtime
+                .block_on(nostring_notify::nostr_relay::fetch_key_migrations(
+                    owner_npub,
+                ))
+                .context("Failed to fetch service key migrations")?;
+            recovery::rotations_to_signer(owner_npub, signer, &migrations)?.with_context(|| {
+                format!(
+                    "The backup is signed by {}, which is neither {} nor a key it \
+                     migrated to",
+                    signer, owner_npub
+                )
+            })?
+        }
+    };
+    match rotations {
+        0 => println!("  Signer:      the owner's npub"),
+        n => println!("  Signer:      the owner's key after {} rotation(s)", n),
+    }
+    Ok(())
+}
+
+fn connect(verified: &VerifiedBackup, electrum_url: &str) -> Result<ElectrumClient> {
+    ElectrumClient::new(electrum_url, verified.network)
+        .with_context(|| format!("Failed to connect to Electrum at {}", electrum_url))
+}
+
+/// Print the inheritance UTXOs as `--utxo` arguments for `psbt`.
+fn print_balance(verified: &VerifiedBackup, electrum_url: &str) -> Result<()> {
+    let client = connect(verified, electrum_url)?;
+    let receive = verified
+        .descriptor
+        .clone()
+        .into_single_descriptors()
+        .context("Invalid descriptor")?
+        .remove(0);
+
+    let scan_end = verified
+        .backup
+        .address_index
+        .unwrap_or(0)
+        .saturating_add(BALANCE_SCAN_INDICES);
+    let mut total = bitcoin::Amount::ZERO;
+    for index in 0..scan_end {
+        let script = receive
+            .at_derivation_index(index)
+            .context("Invalid derivation index")?
+            .script_pubkey();
+        let utxos = client
+            .get_utxos_for_script(&script)
+            .context("Failed to fetch UTXOs")?;
+        for utxo in utxos {
+            total += utxo.value;
+            println!("--utxo {}:{}:{}", utxo.outpoint, utxo.value.to_sat(), index);
+        }
+    }
+    println!("Total: {} sats", total.to_sat());
+    Ok(())
+}
+
+/// Broadcast a signed PSBT given as base64 or a file path.
+fn broadcast(verified: &VerifiedBackup, psbt_arg: &str, electrum_url: &str) -> Result<()> {
+    let encoded = if Path::new(psbt_arg).is_file() {
+        std::fs::read_to_string(psbt_arg)
+            .with_context(|| format!("Failed to read PSBT file {}", psbt_arg))?
+    } else {
+        psbt_arg.to_string()
+    };
+
+    let psbt = Psbt::from_str(encoded.trim()).context("Invalid PSBT (expected base64)")?;
+    // Signers that stop at partial signatures leave the witness to us
+    let tx = nostring_inherit::finalize_psbt(psbt, &verified.descriptor)
+        .context("PSBT is not fully signed")?;
+
+    let client = connect(verified, electrum_url)?;
+    let txid = client.broadcast(&tx).context("Broadcast failed")?;
+    println!("{}", txid);
+    Ok(())
+}
+
+fn print_help() {
+    println!(
+        r#"NoString Recover — offline heir recovery tool
+
+USAGE:
+    nostring-recover <COMMAND> <BACKUP> [OPTIONS]
+
+BACKUP is a .nostring manifest (plain or encrypted) or a descriptor
+backup JSON file.
+
+COMMANDS (offline):
+    verify                Check the descriptor against the rest of the backup
+                          (with --owner-npub, also checks the signer via relays)
+    nsec                  Reconstruct the owner's nsec from shares
+    psbt                  Build the unsigned recovery PSBT (printed as base64)
+
+COMMANDS (network, via Electrum):
+    balance               List the inheritance UTXOs as --utxo arguments
+    broadcast <PSBT>      Broadcast a signed PSBT (base64 string or file path)
+
+OPTIONS:
+    --password <PW>       Password for an encrypted manifest
+    --allow-unsigned      Accept a backup with no signature (prints a warning)
+    --share <SHARE>       A codex32 share (repeat for each share you hold)
+    --utxo <T:V:S[:I]>    Input to sweep: TXID:VOUT:SATS[:INDEX] (repeatable)
+    --to <ADDRESS>        Destination address for the recovered funds
+    --fee-rate <SAT/VB>   Fee rate for the recovery transaction
+    --timelock <LOCK>     Heir timelock to claim with (default: the backup's):
+                          BLOCKS, or time-based as <DAYS>d or <SECONDS>s
+    --electrum <URL>      Electrum server (balance/broadcast only)
+    --owner-npub <NPUB>   Service npub the owner gave you (verify only)
+    -h, --help            Show this help message
+    -V, --version         Show version
+
+ENVIRONMENT VARIABLES:
+    NOSTRING_MANIFEST_PASSWORD  Manifest password (keeps it out of shell history)
+
+EXAMPLES:
+    # On a connected machine: find the UTXOs
+    nostring-recover balance family.nostring --electrum ssl://electrum.blockstream.info:50002
+
+    # On the air-gapped machine: build the PSBT, then sign it with your wallet
+    nostring-recover psbt family.nostring --utxo <TXID>:0:250000:0 --to bc1q... --fee-rate 4
+
+    # Back online: broadcast the signed PSBT
+    nostring-recover broadcast family.nostring signed.psbt --electrum ssl://electrum.blockstream.info:50002"#
+    );
+}
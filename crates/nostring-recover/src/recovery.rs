@@ -0,0 +1,574 @@
+//! Offline recovery: load the backup, verify its descriptor, rebuild the
+//! nsec and the recovery PSBT.
+//!
+//! Nothing in this module touches the network.
+
+use anyhow::{Context, Result};
+use bitcoin::psbt::Psbt;
+use bitcoin::{Address, Network};
+use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::Descriptor;
+use nostring_core::manifest::{self, ManifestFile};
+use nostring_core::memory::LockedBuffer;
+use nostring_inherit::heir::{build_recovery_psbt, RecoveryInput};
+use nostring_inherit::policy::{
+    descriptor_timelocks, heir_keys, parse_and_validate_descriptor, verify_address_at, Timelock,
+};
+use nostring_shamir::codex32::{self, Codex32Share};
+use serde::Deserialize;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The fields of a manifest or descriptor backup recovery needs.
+///
+/// Both formats share these field names, so either file deserializes
+/// straight into it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Backup {
+    pub descriptor: String,
+    pub network: String,
+    /// Heir timelock as the app recorded it. Only a consistency check: the
+    /// timelock recovery uses comes from the descriptor itself.
+    #[serde(default)]
+    pub timelock_blocks: Option<u16>,
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Receive index `address` is derived at; 0 when absent
+    #[serde(default)]
+    pub address_index: Option<u32>,
+    #[serde(default)]
+    pub heirs: Vec<BackupHeir>,
+    #[serde(default)]
+    pub nsec_owner_npub: Option<String>,
+    #[serde(default)]
+    pub locked_shares: Option<Vec<String>>,
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub signer_npub: Option<String>,
+    /// Digest the signature covers, taken from the file as read (it
+    /// includes fields this struct doesn't keep)
+    #[serde(skip)]
+    pub signed_digest: Option<[u8; 32]>,
+}
+
+/// An heir as listed in the backup
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BackupHeir {
+    pub label: String,
+    pub xpub: String,
+}
+
+/// Load a `.nostring` manifest (plain or encrypted) or a descriptor
+/// backup JSON file.
+pub fn load_backup(path: &Path, password: Option<&str>) -> Result<Backup> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_backup(&bytes, password)
+}
+
+/// Parse manifest or descriptor backup bytes, decrypting if needed.
+///
+/// The signature is checked later, by [`verify_backup`].
+pub fn parse_backup(bytes: &[u8], password: Option<&str>) -> Result<Backup> {
+    let mut value: serde_json::Value =
+        serde_json::from_slice(bytes).context("Not a manifest or backup (invalid JSON)")?;
+    if value.get("format").is_some() {
+        let file = ManifestFile::from_slice(bytes)?;
+        if file.encrypted.is_some() && password.is_none() {
+            anyhow::bail!(
+                "This manifest is encrypted; pass --password or set NOSTRING_MANIFEST_PASSWORD"
+            );
+        }
+        value = file.open(password)?;
+    }
+
+    let mut backup: Backup =
+        serde_json::from_value(value.clone()).context("Not a manifest or descriptor backup")?;
+    backup.signed_digest = Some(manifest::signed_digest(&value)?);
+    Ok(backup)
+}
+
+/// A backup whose signature and descriptor checked out
+#[derive(Debug)]
+pub struct VerifiedBackup {
+    pub backup: Backup,
+    /// Service key that signed the backup; `None` for an unsigned backup
+    /// accepted with `allow_unsigned`
+    pub signer_npub: Option<String>,
+    pub network: Network,
+    pub descriptor: Descriptor<DescriptorPublicKey>,
+    pub timelock: Timelock,
+}
+
+/// Check the backup is signed, then that the descriptor is valid for the
+/// backup's network, derives the recorded address, has a timelocked heir
+/// path matching the recorded timelock and contains every listed heir's
+/// key in an heir path.
+///
+/// The signer npub only proves the file is unchanged since it was signed;
+/// the heir should compare it with the npub the owner shared. With
+/// `allow_unsigned`, a backup without a signature is checked all the same
+/// but nothing vouches for it; a signature that is present must still be
+/// valid.
+pub fn verify_backup(backup: Backup, allow_unsigned: bool) -> Result<VerifiedBackup> {
+    let signer_npub = match (&backup.signature, &backup.signer_npub, backup.signed_digest) {
+        (Some(sig_hex), Some(npub), Some(digest)) => {
+            manifest::verify_digest(digest, sig_hex, npub)
+                .context("Backup signature is invalid")?;
+            Some(npub.clone())
+        }
+        _ if allow_unsigned => None,
+        _ => anyhow::bail!(
+            "Backup is not signed — it can't be checked for tampering \
+             (pass --allow-unsigned to recover from it anyway)"
+        ),
+    };
+
+    let network = Network::from_str(&backup.network)
+        .with_context(|| format!("Unknown network '{}'", backup.network))?;
+    let descriptor = parse_and_validate_descriptor(&backup.descriptor, network)
+        .context("Descriptor is not valid")?;
+
+    if let Some(address) = &backup.address {
+        let index = backup.address_index.unwrap_or(0);
+        if !verify_address_at(&backup.descriptor, address, index, network)? {
+            anyhow::bail!(
+                "Descriptor does not derive the recorded address {} — the backup may have been altered",
+                address
+            );
+        }
+    }
+
+    let timelock = heir_timelock(&descriptor, backup.timelock_blocks)?;
+
+    let heir_keys: Vec<String> = heir_keys(&descriptor)?
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    for heir in &backup.heirs {
+        if !heir_keys.iter().any(|k| k.contains(&heir.xpub)) {
+            anyhow::bail!(
+                "Heir '{}' is listed but their key is not in the descriptor's heir paths",
+                heir.label
+            );
+        }
+    }
+
+    Ok(VerifiedBackup {
+        backup,
+        signer_npub,
+        network,
+        descriptor,
+        timelock,
+    })
+}
+
+/// The descriptor's heir timelock: the one matching the backup's recorded
+/// `timelock_blocks` or, when the backup doesn't record one, the shortest.
+///
+/// Time-based locks match on the approximate block count the app records
+/// for them.
+fn heir_timelock(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    recorded_blocks: Option<u16>,
+) -> Result<Timelock> {
+    let timelocks = descriptor_timelocks(descriptor)?;
+    let timelock = match recorded_blocks {
+        Some(blocks) => timelocks.iter().find(|t| t.blocks() == blocks),
+        None => timelocks.first(),
+    };
+    match (timelock, recorded_blocks) {
+        (Some(timelock), _) => Ok(*timelock),
+        (None, Some(blocks)) => anyhow::bail!(
+            "Backup records an heir timelock of {} blocks, but the descriptor's heir paths \
+             are locked for {} — the backup may have been altered",
+            blocks,
+            timelocks
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (None, None) => anyhow::bail!("Descriptor has no timelocked heir path"),
+    }
+}
+
+/// The owner's Nostr identity rebuilt from shares
+#[derive(Debug)]
+pub struct RecoveredNsec {
+    pub nsec: String,
+    pub npub: String,
+}
+
+/// Combine the backup's locked shares with the heir-held `shares` and
+/// decode the result as a Nostr secret key.
+///
+/// A share given twice counts once. When the backup records the owner's
+/// npub, the recovered key must match it.
+pub fn reconstruct_nsec(backup: &Backup, shares: &[String]) -> Result<RecoveredNsec> {
+    use nostr::{Keys, SecretKey, ToBech32};
+
+    let mut parsed: Vec<Codex32Share> = Vec::new();
+    let locked = backup.locked_shares.iter().flatten();
+    for (i, share) in locked.chain(shares).enumerate() {
+        let share = codex32::parse_share(share.trim())
+            .with_context(|| format!("Invalid share #{}", i + 1))?;
+        if !parsed.iter().any(|s| s.index == share.index) {
+            parsed.push(share);
+        }
+    }
+
+    let mut identifiers: Vec<&str> = parsed.iter().map(|s| s.identifier.as_str()).collect();
+    identifiers.sort_unstable();
+    identifiers.dedup();
+    if identifiers.len() > 1 {
+        anyhow::bail!(
+            "Shares come from different splits (identifiers: {})",
+            identifiers.join(", ")
+        );
+    }
+
+    let secret: LockedBuffer = codex32::combine_shares_secure(&parsed)
+        .context("Could not reconstruct: more shares are needed")?;
+    let secret_key = SecretKey::from_slice(secret.as_slice())
+        .context("Shares reconstructed, but the result is not a Nostr key")?;
+    let keys = Keys::new(secret_key);
+    let npub = keys.public_key().to_bech32()?;
+    if let Some(expected) = &backup.nsec_owner_npub {
+        if *expected != npub {
+            anyhow::bail!(
+                "Recovered key {} does not match the owner's npub {} in the backup",
+                npub,
+                expected
+            );
+        }
+    }
+
+    Ok(RecoveredNsec {
+        nsec: keys.secret_key().to_bech32()?,
+        npub,
+    })
+}
+
+/// How many service key rotations separate `owner_npub` from `signer`.
+///
+/// `migrations` are the keys `owner_npub` moved to, oldest first, as
+/// fetched from relays. `Some(0)` means the backup is signed by the key the
+/// owner gave out; `None` means `signer` is not in the chain at all.
+pub fn rotations_to_signer(
+    owner_npub: &str,
+    signer: &str,
+    migrations: &[String],
+) -> Result<Option<usize>> {
+    use nostr::PublicKey;
+
+    let parse = |npub: &str| {
+        PublicKey::parse(npub.trim()).with_context(|| format!("Invalid npub '{}'", npub))
+    };
+    let signer = parse(signer)?;
+    let mut chain = vec![parse(owner_npub)?];
+    for npub in migrations {
+        chain.push(parse(npub)?);
+    }
+    Ok(chain.iter().position(|pk| *pk == signer))
+}
+
+/// Build the unsigned PSBT sweeping `utxos` to `destination`.
+pub fn recovery_psbt(
+    verified: &VerifiedBackup,
+    utxos: &[RecoveryInput],
+    destination: &str,
+    fee_rate: u64,
+    timelock: Option<Timelock>,
+) -> Result<Psbt> {
+    let destination = Address::from_str(destination.trim())
+        .context("Invalid destination address")?
+        .require_network(verified.network)
+        .context("Destination address is for a different network")?;
+    let timelock = timelock.unwrap_or(verified.timelock);
+    Ok(build_recovery_psbt(
+        &verified.descriptor,
+        utxos,
+        timelock,
+        &destination,
+        fee_rate,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::bip32::{DerivationPath, Xpriv, Xpub};
+    use bitcoin::hashes::Hash;
+    use bitcoin::secp256k1::Secp256k1;
+    use bitcoin::{Amount, OutPoint};
+    use nostring_inherit::policy::InheritancePolicy;
+    use nostring_shamir::codex32::Codex32Config;
+
+    const TIMELOCK_BLOCKS: u16 = 4_320;
+
+    /// (descriptor key, bare xpub) of a fixed test seed
+    fn account_key(seed: u8) -> (DescriptorPublicKey, String) {
+        let secp = Secp256k1::new();
+        let master = Xpriv::new_master(Network::Testnet, &[seed; 32]).unwrap();
+        let path = DerivationPath::from_str("m/84'/1'/0'").unwrap();
+        let xpub = Xpub::from_priv(&secp, &master.derive_priv(&secp, &path).unwrap());
+        let key = DescriptorPublicKey::from_str(&format!(
+            "[{}/84'/1'/0']{}/<0;1>/*",
+            master.fingerprint(&secp),
+            xpub
+        ))
+        .unwrap();
+        (key, xpub.to_string())
+    }
+
+    fn test_descriptor() -> Descriptor<DescriptorPublicKey> {
+        let (owner, _) = account_key(1);
+        let (heir, _) = account_key(2);
+        InheritancePolicy::simple(owner, heir, Timelock::from_blocks(TIMELOCK_BLOCKS).unwrap())
+            .unwrap()
+            .to_wsh_descriptor()
+            .unwrap()
+    }
+
+    fn test_address(index: u32) -> String {
+        test_descriptor()
+            .into_single_descriptors()
+            .unwrap()
+            .remove(0)
+            .at_derivation_index(index)
+            .unwrap()
+            .address(Network::Testnet)
+            .unwrap()
+            .to_string()
+    }
+
+    /// Backup JSON as the app writes it, with `edit` applied before signing
+    fn signed_body(
+        keys: &nostr::Keys,
+        edit: impl FnOnce(&mut serde_json::Value),
+    ) -> serde_json::Value {
+        let (_, heir_xpub) = account_key(2);
+        let descriptor = test_descriptor();
+        let address = test_address(0);
+        let mut body = serde_json::json!({
+            "descriptor": descriptor.to_string(),
+            "network": "testnet",
+            "timelock_blocks": TIMELOCK_BLOCKS,
+            "address": address,
+            "heirs": [{"label": "Spouse", "xpub": heir_xpub, "timelock_months": 1.0}],
+            "nsec_owner_npub": null,
+            "locked_shares": null,
+            "relays": ["wss://nos.lol"],
+        });
+        edit(&mut body);
+
+        let digest = manifest::signed_digest(&body).unwrap();
+        let (signature, npub) =
+            manifest::sign_digest(digest, &keys.secret_key().to_secret_hex()).unwrap();
+        body["signature"] = signature.into();
+        body["signer_npub"] = npub.into();
+        body
+    }
+
+    fn parse(body: &serde_json::Value) -> Backup {
+        parse_backup(&serde_json::to_vec(body).unwrap(), None).unwrap()
+    }
+
+    fn test_backup() -> Backup {
+        parse(&signed_body(&nostr::Keys::generate(), |_| {}))
+    }
+
+    #[test]
+    fn test_reconstruct_nsec_from_locked_and_heir_shares() {
+        use nostr::{Keys, ToBech32};
+
+        let owner = Keys::generate();
+        let config = Codex32Config::new(3, "nost", 5).unwrap();
+        let shares: Vec<String> =
+            codex32::generate_shares(owner.secret_key().as_secret_bytes(), 32, &config)
+                .unwrap()
+                .iter()
+                .map(|s| s.encoded.clone())
+                .collect();
+
+        // Two shares locked in the backup, one held by the heir
+        let mut backup = test_backup();
+        backup.locked_shares = Some(shares[..2].to_vec());
+        backup.nsec_owner_npub = Some(owner.public_key().to_bech32().unwrap());
+
+        let recovered = reconstruct_nsec(&backup, &shares[3..4]).unwrap();
+        assert_eq!(recovered.nsec, owner.secret_key().to_bech32().unwrap());
+        assert_eq!(recovered.npub, owner.public_key().to_bech32().unwrap());
+
+        // A locked share handed in again doesn't count twice
+        assert!(reconstruct_nsec(&backup, &shares[..1]).is_err());
+
+        // A different owner npub in the backup is refused
+        backup.nsec_owner_npub = Some(Keys::generate().public_key().to_bech32().unwrap());
+        assert!(reconstruct_nsec(&backup, &shares[3..4]).is_err());
+    }
+
+    #[test]
+    fn test_verify_backup() {
+        use nostr::ToBech32;
+
+        let keys = nostr::Keys::generate();
+        let verified = verify_backup(parse(&signed_body(&keys, |_| {})), false).unwrap();
+        assert_eq!(verified.network, Network::Testnet);
+        assert_eq!(verified.timelock.blocks(), TIMELOCK_BLOCKS);
+        assert_eq!(
+            verified.signer_npub,
+            Some(keys.public_key().to_bech32().unwrap())
+        );
+
+        // A swapped address, an unknown timelock or a stranger heir fail
+        // even when the owner's service key signed them
+        let wrong_address = signed_body(&keys, |body| {
+            body["address"] = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".into();
+        });
+        assert!(verify_backup(parse(&wrong_address), false).is_err());
+
+        // After a rotating check-in the address is at a later index
+        let rotated = signed_body(&keys, |body| {
+            body["address"] = test_address(2).into();
+            body["address_index"] = 2.into();
+        });
+        assert!(verify_backup(parse(&rotated), false).is_ok());
+        let unindexed = signed_body(&keys, |body| body["address"] = test_address(2).into());
+        assert!(verify_backup(parse(&unindexed), false).is_err());
+
+        let wrong_timelock = signed_body(&keys, |body| body["timelock_blocks"] = 10.into());
+        assert!(verify_backup(parse(&wrong_timelock), false).is_err());
+
+        // Without a recorded timelock, the descriptor's own is used
+        let unrecorded = signed_body(&keys, |body| {
+            body.as_object_mut().unwrap().remove("timelock_blocks");
+        });
+        let verified = verify_backup(parse(&unrecorded), false).unwrap();
+        assert_eq!(verified.timelock.blocks(), TIMELOCK_BLOCKS);
+
+        let stranger = signed_body(&keys, |body| {
+            body["heirs"][0]["xpub"] = account_key(9).1.into();
+        });
+        assert!(verify_backup(parse(&stranger), false).is_err());
+
+        // The owner's key isn't an heir key
+        let owner_as_heir = signed_body(&keys, |body| {
+            body["heirs"][0]["xpub"] = account_key(1).1.into();
+        });
+        assert!(verify_backup(parse(&owner_as_heir), false).is_err());
+    }
+
+    #[test]
+    fn test_verify_backup_signature() {
+        let body = signed_body(&nostr::Keys::generate(), |_| {});
+
+        // Any signed field changed after signing, including ones `Backup`
+        // doesn't keep, breaks the signature
+        let mut tampered = body.clone();
+        tampered["relays"] = serde_json::json!(["wss://evil.example"]);
+        assert!(verify_backup(parse(&tampered), false).is_err());
+
+        let mut heir_swapped = body.clone();
+        heir_swapped["heirs"][0]["label"] = "Mallory".into();
+        assert!(verify_backup(parse(&heir_swapped), false).is_err());
+
+        let mut unsigned = body.clone();
+        unsigned.as_object_mut().unwrap().remove("signature");
+        assert!(verify_backup(parse(&unsigned), false).is_err());
+
+        // Accepted when asked for, but with no signer to show
+        let verified = verify_backup(parse(&unsigned), true).unwrap();
+        assert_eq!(verified.signer_npub, None);
+
+        // A signature that is there still has to hold
+        assert!(verify_backup(parse(&tampered), true).is_err());
+
+        // The unsigned stale warning may change
+        let mut warned = body;
+        warned["stale_warning"] = "Backup is older than the current policy".into();
+        assert!(verify_backup(parse(&warned), false).is_ok());
+    }
+
+    #[test]
+    fn test_rotations_to_signer() {
+        use nostr::{Keys, ToBech32};
+
+        let npub = |keys: &Keys| keys.public_key().to_bech32().unwrap();
+        let (old, mid, new) = (Keys::generate(), Keys::generate(), Keys::generate());
+        let migrations = vec![npub(&mid), npub(&new)];
+
+        assert_eq!(
+            rotations_to_signer(&npub(&old), &npub(&old), &[]).unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            rotations_to_signer(&npub(&old), &npub(&new), &migrations).unwrap(),
+            Some(2)
+        );
+        // Hex and bech32 name the same key
+        assert_eq!(
+            rotations_to_signer(&old.public_key().to_hex(), &npub(&mid), &migrations).unwrap(),
+            Some(1)
+        );
+        let stranger = Keys::generate();
+        assert_eq!(
+            rotations_to_signer(&npub(&old), &npub(&stranger), &migrations).unwrap(),
+            None
+        );
+        assert!(rotations_to_signer("npub1bogus", &npub(&old), &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_backup_formats() {
+        let body = signed_body(&nostr::Keys::generate(), |_| {});
+        let backup = parse(&body);
+        assert_eq!(backup.heirs[0].label, "Spouse");
+
+        // Manifest, plain and encrypted
+        let plain = ManifestFile::new(&body, None).unwrap().to_vec().unwrap();
+        assert_eq!(parse_backup(&plain, None).unwrap(), backup);
+
+        let encrypted = ManifestFile::new(&body, Some("correct horse"))
+            .unwrap()
+            .to_vec()
+            .unwrap();
+        assert!(parse_backup(&encrypted, None).is_err());
+        assert!(parse_backup(&encrypted, Some("wrong")).is_err());
+        let decrypted = parse_backup(&encrypted, Some("correct horse")).unwrap();
+        assert_eq!(decrypted, backup);
+        assert!(verify_backup(decrypted, false).is_ok());
+
+        let future = serde_json::json!({
+            "format": manifest::MANIFEST_FORMAT, "version": 99, "manifest": body,
+        });
+        assert!(parse_backup(&serde_json::to_vec(&future).unwrap(), None).is_err());
+    }
+
+    #[test]
+    fn test_recovery_psbt_offline() {
+        let verified = verify_backup(test_backup(), false).unwrap();
+        let utxo = RecoveryInput {
+            outpoint: OutPoint::new(bitcoin::Txid::all_zeros(), 0),
+            value: Amount::from_sat(250_000),
+            derivation_index: 0,
+        };
+        let destination = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+
+        let psbt = recovery_psbt(&verified, &[utxo], destination, 3, None).unwrap();
+        assert_eq!(
+            psbt.unsigned_tx.input[0].sequence,
+            verified.timelock.to_sequence()
+        );
+
+        // Mainnet destinations are refused for a testnet backup
+        let mainnet = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert!(recovery_psbt(&verified, &[utxo], mainnet, 3, None).is_err());
+
+        // A timelock the descriptor doesn't have is refused, time-based too
+        let other = Timelock::from_seconds(30 * 86_400).unwrap();
+        assert!(recovery_psbt(&verified, &[utxo], destination, 3, Some(other)).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Command-line parsing and one-off operator subcommands.
+//!
+//! Kept dependency-free (no clap): flags are matched by hand and the first
+//! bare word selects a subcommand.
+
+use crate::config::ServerConfig;
+use anyhow::{Context, Result};
+use bitcoin::psbt::Psbt;
+use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::Descriptor;
+use nostring_electrum::ElectrumClient;
+use nostring_inherit::checkin::check_psbt_timelock;
+use nostring_watch::WatchState;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Default config location inside the Docker image
+pub const DEFAULT_CONFIG_PATH: &str = "/config/nostring-server.toml";
+
+/// What the binary was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Run the daemon loop (default)
+    Run,
+    /// Run a single check cycle and exit
+    Check,
+    /// Validate config and exit
+    Validate,
+    /// Print tracked UTXOs and balances
+    Utxos,
+    /// Print the inheritance address
+    Address,
+    /// Broadcast a signed PSBT (base64, or a path to a file containing it)
+    Broadcast { psbt: String },
+    /// Print usage
+    Help,
+    /// Print version
+    Version,
+}
+
+/// Parsed command-line arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+    pub config_path: PathBuf,
+    pub command: Command,
+}
+
+/// Parse arguments (excluding the program name).
+pub fn parse_args(args: &[String]) -> Result<CliArgs> {
+    let mut config_path = PathBuf::from(DEFAULT_CONFIG_PATH);
+    let mut command: Option<Command> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--config" | "-c" => {
+                i += 1;
+                match args.get(i) {
+                    Some(path) => config_path = PathBuf::from(path),
+                    None => anyhow::bail!("--config requires a path argument"),
+                }
+            }
+            "--check" | "--once" | "check" => set_command(&mut command, Command::Check)?,
+            "--validate" | "validate" => set_command(&mut command, Command::Validate)?,
+            "--help" | "-h" | "help" => return Ok(with(config_path, Command::Help)),
+            "--version" | "-V" => return Ok(with(config_path, Command::Version)),
+            "utxos" => set_command(&mut command, Command::Utxos)?,
+            "address" => set_command(&mut command, Command::Address)?,
+            "broadcast" => {
+                i += 1;
+                match args.get(i) {
+                    Some(psbt) if !psbt.starts_with('-') => {
+                        set_command(&mut command, Command::Broadcast { psbt: psbt.clone() })?
+                    }
+                    _ => anyhow::bail!("broadcast requires a PSBT (base64 or file path)"),
+                }
+            }
+            other => anyhow::bail!("Unknown argument: {} (see --help)", other),
+        }
+        i += 1;
+    }
+
+    Ok(with(config_path, command.unwrap_or(Command::Run)))
+}
+
+fn set_command(command: &mut Option<Command>, cmd: Command) -> Result<()> {
+    if let Some(existing) = command {
+        anyhow::bail!("Conflicting commands: {:?} and {:?}", existing, cmd);
+    }
+    *command = Some(cmd);
+    Ok(())
+}
+
+fn with(config_path: PathBuf, command: Command) -> CliArgs {
+    CliArgs {
+        config_path,
+        command,
+    }
+}
+
+/// `nostring-server address` — derive the inheritance address.
+///
+/// Uses the receive index the last check cycle saw the funds on, so a
+/// rotating check-in moves it forward; 0 before the first check.
+pub fn print_address(config: &ServerConfig) -> Result<()> {
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(&config.policy.descriptor)
+        .context("policy.descriptor is not a valid descriptor")?;
+    let state_path = config.server.data_dir.join("watch_state.json");
+    let index = WatchState::load(&state_path)
+        .with_context(|| format!("Failed to read {}", state_path.display()))?
+        .policies
+        .get(&config.policy.label)
+        .map_or(0, |policy| policy.address_index);
+    let address = descriptor
+        .at_derivation_index(index)
+        .with_context(|| format!("Failed to derive descriptor at index {}", index))?
+        .address(config.network()?)
+        .context("Descriptor has no address form")?;
+    println!("{}", address);
+    Ok(())
+}
+
+/// `nostring-server utxos` — list UTXOs recorded by the last check cycle.
+pub fn print_utxos(config: &ServerConfig) -> Result<()> {
+    let state_path = config.server.data_dir.join("watch_state.json");
+    let state = WatchState::load(&state_path)
+        .with_context(|| format!("Failed to read {}", state_path.display()))?;
+
+    if state.policies.is_empty() {
+        anyhow::bail!(
+            "No tracked policies in {} — run `nostring-server --check` first",
+            state_path.display()
+        );
+    }
+
+    let mut ids: Vec<&String> = state.policies.keys().collect();
+    ids.sort();
+    let mut grand_total = bitcoin::Amount::ZERO;
+
+    for id in ids {
+        let policy = &state.policies[id];
+        let total: bitcoin::Amount = policy.utxos.iter().map(|u| u.value).sum();
+        grand_total += total;
+
+        println!("Policy '{}': {} UTXO(s), {}", id, policy.utxos.len(), total);
+        for utxo in &policy.utxos {
+            println!(
+                "  {}  {:>20}  height {}",
+                utxo.outpoint, utxo.value, utxo.height
+            );
+        }
+    }
+
+    println!("Total: {}", grand_total);
+    if let Some(height) = state.last_height {
+        println!("As of block {}", height);
+    }
+    Ok(())
+}
+
+/// `nostring-server broadcast <psbt>` — extract and broadcast a finalized PSBT.
+///
+/// Heir claims are refused until the policy's relative timelock has matured,
+/// rather than letting the mempool reject them.
+pub fn broadcast_psbt(config: &ServerConfig, psbt_arg: &str) -> Result<()> {
+    let encoded = if Path::new(psbt_arg).is_file() {
+        std::fs::read_to_string(psbt_arg)
+            .with_context(|| format!("Failed to read PSBT file {}", psbt_arg))?
+    } else {
+        psbt_arg.to_string()
+    };
+
+    let psbt = Psbt::from_str(encoded.trim()).context("Invalid PSBT (expected base64)")?;
+    let tx = psbt
+        .clone()
+        .extract_tx()
+        .context("PSBT is not fully signed and finalized")?;
+
+    let client = ElectrumClient::new(&config.bitcoin.electrum_url, config.network()?)
+        .with_context(|| {
+            format!(
+                "Failed to connect to Electrum at {}",
+                config.bitcoin.electrum_url
+            )
+        })?;
+    check_psbt_timelock(
+        &psbt,
+        Some(config.policy.timelock_blocks),
+        || {
+            client
+                .get_height()
+                .map_err(|e| format!("Failed to get block height: {}", e))
+        },
+        |txid, script| {
+            client
+                .get_tx_height(txid, script)
+                .map_err(|e| format!("Failed to look up input confirmation: {}", e))
+        },
+    )?;
+
+    let txid = client.broadcast(&tx).context("Broadcast failed")?;
+    println!("{}", txid);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_default_is_run() {
+        let cli = parse(&[]).unwrap();
+        assert_eq!(cli.command, Command::Run);
+        assert_eq!(cli.config_path, PathBuf::from(DEFAULT_CONFIG_PATH));
+    }
+
+    #[test]
+    fn test_legacy_flags() {
+        assert_eq!(parse(&["--check"]).unwrap().command, Command::Check);
+        assert_eq!(parse(&["--once"]).unwrap().command, Command::Check);
+        assert_eq!(parse(&["--validate"]).unwrap().command, Command::Validate);
+        assert_eq!(parse(&["-h"]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["-V"]).unwrap().command, Command::Version);
+    }
+
+    #[test]
+    fn test_utxos_subcommand() {
+        let cli = parse(&["--config", "/tmp/n.toml", "utxos"]).unwrap();
+        assert_eq!(cli.command, Command::Utxos);
+        assert_eq!(cli.config_path, PathBuf::from("/tmp/n.toml"));
+    }
+
+    #[test]
+    fn test_address_subcommand() {
+        let cli = parse(&["address", "-c", "x.toml"]).unwrap();
+        assert_eq!(cli.command, Command::Address);
+        assert_eq!(cli.config_path, PathBuf::from("x.toml"));
+    }
+
+    #[test]
+    fn test_broadcast_subcommand() {
+        let cli = parse(&["broadcast", "cHNidP8BAH0CAAAA"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Broadcast {
+                psbt: "cHNidP8BAH0CAAAA".into()
+            }
+        );
+
+        // Missing or flag-looking argument
+        assert!(parse(&["broadcast"]).is_err());
+        assert!(parse(&["broadcast", "--config", "x.toml"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+        assert!(parse(&["utxos", "address"]).is_err());
+        assert!(parse(&["--check", "utxos"]).is_err());
+    }
+}
@@ -2,9 +2,112 @@
 //!
 //! Priority: environment variables > config file > defaults.
 
-use anyhow::{Context, Result};
+use nostring_inherit::policy::PolicyError;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Longest relative timelock BIP-68 can express, in blocks
+const MAX_TIMELOCK_BLOCKS: u32 = 0xffff;
+
+/// Why a configuration can't be loaded or used.
+///
+/// Each message names the TOML key to fix rather than passing on the raw
+/// deserializer output.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Not valid TOML, or a value of the wrong type
+    #[error("{location}: {message}")]
+    Parse { location: String, message: String },
+
+    /// A value that parsed but can't be used
+    #[error("{key} {reason}")]
+    Invalid { key: String, reason: String },
+}
+
+impl ConfigError {
+    fn invalid(key: &str, reason: impl Into<String>) -> Self {
+        ConfigError::Invalid {
+            key: key.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Describe a TOML error by the key it's about and where it is.
+    fn parse(contents: &str, err: &toml::de::Error) -> Self {
+        let message = err.message().trim().to_string();
+        let span = err.span();
+        let mut key = span.as_ref().and_then(|span| key_at(contents, span.start));
+
+        // A missing field is reported on the table that lacks it. `policy`
+        // is the only required table, so it's the one missing from the root.
+        if let Some(field) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.strip_suffix('`'))
+        {
+            key = match key {
+                Some(table) if field != "policy" => Some(format!("{}.{}", table, field)),
+                _ => Some(field.to_string()),
+            };
+        }
+
+        let position = span.map(|span| {
+            let before = &contents[..span.start.min(contents.len())];
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+            format!("line {}, column {}", line, column)
+        });
+        let location = match (key, position) {
+            (Some(key), Some(position)) => format!("{} ({})", key, position),
+            (Some(key), None) => key,
+            (None, Some(position)) => position,
+            (None, None) => "config".to_string(),
+        };
+        ConfigError::Parse { location, message }
+    }
+}
+
+/// Dotted path of the TOML key at byte `offset`: the enclosing table
+/// header, plus the `key =` on that line when the offset is in its value.
+fn key_at(contents: &str, offset: usize) -> Option<String> {
+    let offset = offset.min(contents.len());
+    let line_start = contents[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = contents[offset..]
+        .find('\n')
+        .map_or(contents.len(), |i| offset + i);
+
+    let header = |line: &str| {
+        let line = line.trim();
+        line.starts_with('[').then(|| {
+            line.trim_start_matches('[')
+                .split(']')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        })
+    };
+    if let Some(table) = header(&contents[line_start..line_end]) {
+        return Some(table);
+    }
+
+    let table = contents[..line_start].lines().rev().find_map(header);
+    let key = contents[line_start..offset]
+        .split_once('=')
+        .map(|(key, _)| key.trim().trim_matches('"').to_string())
+        .filter(|key| !key.is_empty());
+    match (table, key) {
+        (Some(table), Some(key)) => Some(format!("{}.{}", table, key)),
+        (table, key) => key.or(table),
+    }
+}
 
 /// Top-level server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +139,12 @@ pub struct ServerSection {
     #[serde(default = "default_check_interval")]
     pub check_interval_secs: u64,
 
+    /// Randomize each wait between checks by up to ± this many seconds, so
+    /// servers sharing an interval don't hit public Electrum servers at
+    /// the same moment (default: 0, off)
+    #[serde(default)]
+    pub poll_jitter_secs: u64,
+
     /// Log level (error, warn, info, debug, trace)
     #[serde(default = "default_log_level")]
     pub log_level: String,
@@ -46,6 +155,7 @@ impl Default for ServerSection {
         Self {
             data_dir: default_data_dir(),
             check_interval_secs: default_check_interval(),
+            poll_jitter_secs: 0,
             log_level: default_log_level(),
         }
     }
@@ -84,6 +194,49 @@ pub struct PolicySection {
     /// Human-readable label for this policy
     #[serde(default = "default_policy_label")]
     pub label: String,
+
+    /// Owner npub for this policy's reminders, instead of
+    /// `notifications.nostr.owner_npub`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_npub: Option<String>,
+
+    /// Owner email for this policy's reminders, instead of
+    /// `notifications.email.owner_email`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_email: Option<String>,
+
+    /// Addresses CC'd on this policy's email reminders. Without it, the
+    /// policy only gets `notifications.email.cc` if it also uses the global
+    /// owner email.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cc: Option<Vec<String>>,
+
+    /// Addresses BCC'd on this policy's email reminders; falls back like
+    /// `cc`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bcc: Option<Vec<String>>,
+
+    /// Heirs who get this policy's descriptor, instead of
+    /// `notifications.heirs`. An empty list means no heirs, not the global
+    /// ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heirs: Option<Vec<HeirContact>>,
+}
+
+/// Who gets notified about one policy: its own recipients where set, the
+/// global `[notifications]` ones otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recipients<'a> {
+    /// Owner npub for Nostr reminders (needs `notifications.nostr`)
+    pub owner_npub: Option<&'a str>,
+    /// Owner address for email reminders (needs `notifications.email`)
+    pub owner_email: Option<&'a str>,
+    /// Addresses CC'd on email reminders
+    pub cc: &'a [String],
+    /// Addresses BCC'd on email reminders
+    pub bcc: &'a [String],
+    /// Heirs to deliver the descriptor to
+    pub heirs: &'a [HeirContact],
 }
 
 /// Notification channel configuration
@@ -102,6 +255,23 @@ pub struct NotificationSection {
     /// Heir contacts for descriptor delivery
     #[serde(default)]
     pub heirs: Vec<HeirContact>,
+
+    /// Remind the owner (weekly) once this many days pass without a
+    /// check-in, regardless of timelock proximity. 0 disables.
+    #[serde(default = "default_reminder_interval_days")]
+    pub reminder_interval_days: u32,
+
+    /// Deliver descriptors to heirs once this many blocks or fewer remain
+    #[serde(default = "default_critical_threshold_blocks")]
+    pub critical_threshold_blocks: u32,
+
+    /// URL to POST a JSON payload to whenever a watched UTXO is spent
+    #[serde(default)]
+    pub spend_webhook_url: Option<String>,
+
+    /// Shared secret for signing webhook bodies (HMAC-SHA256)
+    #[serde(default)]
+    pub spend_webhook_secret: Option<String>,
 }
 
 impl Default for NotificationSection {
@@ -111,6 +281,10 @@ impl Default for NotificationSection {
             email: None,
             threshold_days: default_thresholds(),
             heirs: Vec::new(),
+            reminder_interval_days: default_reminder_interval_days(),
+            critical_threshold_blocks: default_critical_threshold_blocks(),
+            spend_webhook_url: None,
+            spend_webhook_secret: None,
         }
     }
 }
@@ -135,9 +309,14 @@ pub struct EmailNotifySection {
     /// SMTP server hostname
     pub smtp_host: String,
 
-    /// SMTP port (default: 587)
-    #[serde(default = "default_smtp_port")]
-    pub smtp_port: u16,
+    /// SMTP port (default: the usual port for `tls_mode`, see
+    /// [`EmailNotifySection::port`])
+    #[serde(default)]
+    pub smtp_port: Option<u16>,
+
+    /// Connection security: "start_tls" (default), "implicit_tls" or "none"
+    #[serde(default)]
+    pub tls_mode: nostring_notify::TlsMode,
 
     /// SMTP username
     pub smtp_user: String,
@@ -150,10 +329,33 @@ pub struct EmailNotifySection {
 
     /// Owner's email for check-in reminders
     pub owner_email: String,
+
+    /// Extra recipients CC'd on owner reminders sent to `owner_email`.
+    /// Policies with their own owner email only use their own `cc`; heir
+    /// delivery never uses it.
+    #[serde(default)]
+    pub cc: Vec<String>,
+
+    /// Extra recipients BCC'd on owner reminders; scoped like `cc`
+    #[serde(default)]
+    pub bcc: Vec<String>,
+
+    /// Send plain-text emails only, without the HTML alternative
+    #[serde(default)]
+    pub plaintext_only: bool,
+}
+
+impl EmailNotifySection {
+    /// The configured SMTP port, else the one conventional for `tls_mode`
+    /// (587 for STARTTLS, 465 for implicit TLS).
+    pub fn port(&self) -> u16 {
+        self.smtp_port
+            .unwrap_or_else(|| self.tls_mode.default_port())
+    }
 }
 
 /// Heir contact information for descriptor delivery
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HeirContact {
     /// Human-readable label
     pub label: String,
@@ -197,6 +399,14 @@ fn default_thresholds() -> Vec<u32> {
     vec![30, 7, 1, 0]
 }
 
+fn default_reminder_interval_days() -> u32 {
+    90
+}
+
+fn default_critical_threshold_blocks() -> u32 {
+    nostring_notify::DEFAULT_CRITICAL_THRESHOLD_BLOCKS
+}
+
 fn default_relays() -> Vec<String> {
     vec![
         "wss://relay.damus.io".into(),
@@ -205,22 +415,25 @@ fn default_relays() -> Vec<String> {
     ]
 }
 
-fn default_smtp_port() -> u16 {
-    587
-}
-
 // ============================================================================
 // Loading & environment override
 // ============================================================================
 
 impl ServerConfig {
     /// Load configuration from a TOML file.
-    pub fn from_file(path: &Path) -> Result<Self> {
-        let contents = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-        let config: ServerConfig =
-            toml::from_str(&contents).with_context(|| "Failed to parse TOML config")?;
-        Ok(config)
+    ///
+    /// Syntax and type errors name the offending key and its position.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml(&contents)
+    }
+
+    /// Parse configuration from TOML text.
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        toml::from_str(contents).map_err(|e| ConfigError::parse(contents, &e))
     }
 
     /// Apply environment variable overrides.
@@ -228,6 +441,7 @@ impl ServerConfig {
     /// Supported env vars:
     /// - `NOSTRING_DATA_DIR`
     /// - `NOSTRING_CHECK_INTERVAL`
+    /// - `NOSTRING_POLL_JITTER`
     /// - `NOSTRING_LOG_LEVEL`
     /// - `NOSTRING_NETWORK`
     /// - `NOSTRING_ELECTRUM_URL`
@@ -244,6 +458,11 @@ impl ServerConfig {
                 self.server.check_interval_secs = secs;
             }
         }
+        if let Ok(v) = std::env::var("NOSTRING_POLL_JITTER") {
+            if let Ok(secs) = v.parse::<u64>() {
+                self.server.poll_jitter_secs = secs;
+            }
+        }
         if let Ok(v) = std::env::var("NOSTRING_LOG_LEVEL") {
             self.server.log_level = v;
         }
@@ -274,63 +493,245 @@ impl ServerConfig {
     }
 
     /// Parse the bitcoin network string to a `bitcoin::Network`.
-    pub fn network(&self) -> bitcoin::Network {
+    pub fn network(&self) -> Result<bitcoin::Network, ConfigError> {
         match self.bitcoin.network.as_str() {
-            "testnet" | "testnet3" => bitcoin::Network::Testnet,
-            "signet" => bitcoin::Network::Signet,
-            "regtest" => bitcoin::Network::Regtest,
-            _ => bitcoin::Network::Bitcoin,
+            "bitcoin" => Ok(bitcoin::Network::Bitcoin),
+            "testnet" | "testnet3" => Ok(bitcoin::Network::Testnet),
+            "signet" => Ok(bitcoin::Network::Signet),
+            "regtest" => Ok(bitcoin::Network::Regtest),
+            other => Err(ConfigError::invalid(
+                "bitcoin.network",
+                format!(
+                    "must be one of bitcoin, testnet, testnet3, signet, regtest (got \"{}\")",
+                    other
+                ),
+            )),
         }
     }
 
-    /// Validate that the configuration is usable.
-    pub fn validate(&self) -> Result<()> {
-        // Descriptor must not be empty
-        anyhow::ensure!(
-            !self.policy.descriptor.is_empty(),
-            "policy.descriptor must not be empty"
-        );
+    /// The recipients for `policy`, falling back to the global
+    /// notification settings for anything the policy doesn't set.
+    ///
+    /// Sending still goes through the global Nostr service key and SMTP
+    /// server; only who receives changes. The global cc/bcc go with the
+    /// global owner email, so they never see a policy whose reminders go
+    /// to its own owner.
+    pub fn recipients<'a>(&'a self, policy: &'a PolicySection) -> Recipients<'a> {
+        let notifications = &self.notifications;
+        let global_email = notifications
+            .email
+            .as_ref()
+            .filter(|_| policy.owner_email.is_none());
+        Recipients {
+            owner_npub: policy
+                .owner_npub
+                .as_deref()
+                .or_else(|| notifications.nostr.as_ref().map(|n| n.owner_npub.as_str())),
+            owner_email: policy
+                .owner_email
+                .as_deref()
+                .or_else(|| notifications.email.as_ref().map(|e| e.owner_email.as_str())),
+            cc: policy
+                .cc
+                .as_deref()
+                .or_else(|| global_email.map(|e| e.cc.as_slice()))
+                .unwrap_or_default(),
+            bcc: policy
+                .bcc
+                .as_deref()
+                .or_else(|| global_email.map(|e| e.bcc.as_slice()))
+                .unwrap_or_default(),
+            heirs: policy.heirs.as_deref().unwrap_or(&notifications.heirs),
+        }
+    }
 
-        // Timelock must be positive
-        anyhow::ensure!(
-            self.policy.timelock_blocks > 0,
-            "policy.timelock_blocks must be > 0"
-        );
+    /// Check a policy's recipient overrides can be delivered to and that,
+    /// with notifications configured, the policy has an owner recipient of
+    /// its own or inherited from `[notifications]`.
+    fn validate_recipients(&self, policy: &PolicySection) -> Result<(), ConfigError> {
+        let label = &policy.label;
+        if let Some(ref npub) = policy.owner_npub {
+            if npub.is_empty() {
+                return Err(ConfigError::invalid(
+                    "policy.owner_npub",
+                    format!("must not be empty (policy '{}')", label),
+                ));
+            }
+            if self.notifications.nostr.is_none() {
+                return Err(ConfigError::invalid(
+                    "policy.owner_npub",
+                    format!(
+                        "needs [notifications.nostr] for the service key (policy '{}')",
+                        label
+                    ),
+                ));
+            }
+        }
+        if let Some(ref email) = policy.owner_email {
+            if email.is_empty() {
+                return Err(ConfigError::invalid(
+                    "policy.owner_email",
+                    format!("must not be empty (policy '{}')", label),
+                ));
+            }
+            if self.notifications.email.is_none() {
+                return Err(ConfigError::invalid(
+                    "policy.owner_email",
+                    format!(
+                        "needs [notifications.email] for the SMTP server (policy '{}')",
+                        label
+                    ),
+                ));
+            }
+        }
+        for (key, list) in [("policy.cc", &policy.cc), ("policy.bcc", &policy.bcc)] {
+            if list.is_some() && self.notifications.email.is_none() {
+                return Err(ConfigError::invalid(
+                    key,
+                    format!(
+                        "needs [notifications.email] for the SMTP server (policy '{}')",
+                        label
+                    ),
+                ));
+            }
+        }
+
+        let notifications_configured =
+            self.notifications.nostr.is_some() || self.notifications.email.is_some();
+        let recipients = self.recipients(policy);
+        if notifications_configured
+            && recipients.owner_npub.is_none()
+            && recipients.owner_email.is_none()
+        {
+            return Err(ConfigError::invalid(
+                "policy",
+                format!(
+                    "'{}' has no owner recipient of its own or from [notifications]",
+                    label
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Validate that the configuration is usable.
+    ///
+    /// Errors name the TOML key at fault and what it needs to be.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let ensure = |ok: bool, key: &str, reason: String| {
+            if ok {
+                Ok(())
+            } else {
+                Err(ConfigError::invalid(key, reason))
+            }
+        };
+
+        let network = self.network()?;
+
+        // Descriptor must be present and usable on the configured network
+        let descriptor = &self.policy.descriptor;
+        ensure(
+            !descriptor.trim().is_empty(),
+            "policy.descriptor",
+            "must not be empty".into(),
+        )?;
+        nostring_inherit::policy::parse_and_validate_descriptor(descriptor, network).map_err(
+            |e| {
+                let reason = match e {
+                    PolicyError::ChecksumMismatch { expected, found } => format!(
+                        "has checksum #{} but its contents checksum to #{}; fix or drop the checksum",
+                        found, expected
+                    ),
+                    PolicyError::WrongNetwork { key, network } => format!(
+                        "has key {} that isn't for bitcoin.network ({})",
+                        key, network
+                    ),
+                    e => format!("failed to parse: {}", e),
+                };
+                ConfigError::invalid("policy.descriptor", reason)
+            },
+        )?;
+
+        // Timelock must fit a BIP-68 relative lock
+        let timelock = self.policy.timelock_blocks;
+        ensure(
+            timelock >= 1,
+            "policy.timelock_blocks",
+            format!("must be ≥ 1 (got {})", timelock),
+        )?;
+        ensure(
+            timelock <= MAX_TIMELOCK_BLOCKS,
+            "policy.timelock_blocks",
+            format!("must be ≤ {} (got {})", MAX_TIMELOCK_BLOCKS, timelock),
+        )?;
+
+        let url = &self.bitcoin.electrum_url;
+        ensure(
+            url.starts_with("ssl://") || url.starts_with("tcp://"),
+            "bitcoin.electrum_url",
+            format!("must start with ssl:// or tcp:// (got \"{}\")", url),
+        )?;
 
         // Check interval must be at least 60 seconds
-        anyhow::ensure!(
+        ensure(
             self.server.check_interval_secs >= 60,
-            "server.check_interval_secs must be >= 60"
-        );
+            "server.check_interval_secs",
+            format!("must be ≥ 60 (got {})", self.server.check_interval_secs),
+        )?;
+
+        // Jitter can't make the wait zero or negative
+        ensure(
+            self.server.poll_jitter_secs < self.server.check_interval_secs,
+            "server.poll_jitter_secs",
+            format!(
+                "must be less than server.check_interval_secs ({})",
+                self.server.check_interval_secs
+            ),
+        )?;
 
         // If Nostr notifications configured, need service key and owner npub
         if let Some(ref nostr) = self.notifications.nostr {
-            anyhow::ensure!(
+            ensure(
                 !nostr.service_key.is_empty(),
-                "notifications.nostr.service_key must not be empty"
-            );
-            anyhow::ensure!(
+                "notifications.nostr.service_key",
+                "must not be empty".into(),
+            )?;
+            ensure(
                 !nostr.owner_npub.is_empty(),
-                "notifications.nostr.owner_npub must not be empty"
-            );
+                "notifications.nostr.owner_npub",
+                "must not be empty".into(),
+            )?;
         }
 
         // If email notifications configured, need core SMTP fields
         if let Some(ref email) = self.notifications.email {
-            anyhow::ensure!(
+            ensure(
                 !email.smtp_host.is_empty(),
-                "notifications.email.smtp_host must not be empty"
-            );
-            anyhow::ensure!(
+                "notifications.email.smtp_host",
+                "must not be empty".into(),
+            )?;
+            ensure(
                 !email.from_address.is_empty(),
-                "notifications.email.from_address must not be empty"
-            );
-            anyhow::ensure!(
+                "notifications.email.from_address",
+                "must not be empty".into(),
+            )?;
+            ensure(
                 !email.owner_email.is_empty(),
-                "notifications.email.owner_email must not be empty"
-            );
+                "notifications.email.owner_email",
+                "must not be empty".into(),
+            )?;
         }
 
+        if let Some(ref url) = self.notifications.spend_webhook_url {
+            ensure(
+                url.starts_with("https://") || url.starts_with("http://"),
+                "notifications.spend_webhook_url",
+                format!("must be an http(s) URL (got \"{}\")", url),
+            )?;
+        }
+
+        self.validate_recipients(&self.policy)?;
+
         Ok(())
     }
 }
@@ -345,19 +746,30 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
-    fn minimal_toml() -> &'static str {
-        r#"
+    /// An owner-or-heir-after-26280-blocks policy (BIP-32 test vector keys)
+    const DESCRIPTOR: &str = "wsh(or_d(pk([00000001/84'/0'/0']xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*),and_v(v:pk([00000002/84'/0'/1']xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB/0/*),older(26280))))";
+
+    /// The same policy with the keys re-encoded for test networks
+    const TESTNET_DESCRIPTOR: &str = "wsh(or_d(pk([00000001/84'/1'/0']tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp/0/*),and_v(v:pk([00000002/84'/1'/1']tpubD6NzVbkrYhZ4XJDrzRvuxHEyQaPd1mwwdDofEJwekX18tAdsqeKfxss79AJzg1431FybXg5rfpTrJF4iAhyR7RubberdzEQXiRmXGADH2eA/0/*),older(26280))))";
+
+    fn minimal_toml() -> String {
+        format!(
+            r#"
 [policy]
-descriptor = "wsh(or_d(pk([00000001/84'/0'/0']xpub6.../0/*),and_v(v:pk([00000002/84'/0'/1']xpub6.../0/*),older(26280))))"
+descriptor = "{}"
 timelock_blocks = 26280
-"#
+"#,
+            DESCRIPTOR
+        )
     }
 
-    fn full_toml() -> &'static str {
-        r#"
+    fn full_toml() -> String {
+        format!(
+            r#"
 [server]
 data_dir = "/custom/data"
 check_interval_secs = 3600
+poll_jitter_secs = 300
 log_level = "debug"
 
 [bitcoin]
@@ -365,12 +777,16 @@ network = "testnet"
 electrum_url = "ssl://blockstream.info:993"
 
 [policy]
-descriptor = "wsh(or_d(pk(xpub1),and_v(v:pk(xpub2),older(26280))))"
+descriptor = "{}"
 timelock_blocks = 26280
 label = "family-inheritance"
 
 [notifications]
 threshold_days = [30, 14, 7, 3, 1, 0]
+reminder_interval_days = 60
+critical_threshold_blocks = 288
+spend_webhook_url = "https://hooks.example.com/nostring"
+spend_webhook_secret = "hunter2"
 
 [notifications.nostr]
 service_key = "nsec1testkey"
@@ -393,7 +809,9 @@ email = "spouse@example.com"
 [[notifications.heirs]]
 label = "Child"
 npub = "npub1child"
-"#
+"#,
+            TESTNET_DESCRIPTOR
+        )
     }
 
     #[test]
@@ -404,6 +822,7 @@ npub = "npub1child"
         let config = ServerConfig::from_file(file.path()).unwrap();
         assert_eq!(config.policy.timelock_blocks, 26280);
         assert_eq!(config.server.check_interval_secs, 21600); // default
+        assert_eq!(config.server.poll_jitter_secs, 0); // default: off
         assert_eq!(config.bitcoin.network, "bitcoin"); // default
         assert!(config.notifications.nostr.is_none());
         assert!(config.notifications.email.is_none());
@@ -418,6 +837,7 @@ npub = "npub1child"
 
         assert_eq!(config.server.data_dir, PathBuf::from("/custom/data"));
         assert_eq!(config.server.check_interval_secs, 3600);
+        assert_eq!(config.server.poll_jitter_secs, 300);
         assert_eq!(config.server.log_level, "debug");
         assert_eq!(config.bitcoin.network, "testnet");
         assert_eq!(config.policy.label, "family-inheritance");
@@ -430,12 +850,55 @@ npub = "npub1child"
         let email = config.notifications.email.as_ref().unwrap();
         assert_eq!(email.smtp_host, "smtp.example.com");
         assert_eq!(email.owner_email, "owner@example.com");
+        assert_eq!(email.tls_mode, nostring_notify::TlsMode::StartTls); // default
+        assert_eq!(email.port(), 587);
 
+        assert_eq!(config.notifications.reminder_interval_days, 60);
+        assert_eq!(config.notifications.critical_threshold_blocks, 288);
+        assert_eq!(
+            config.notifications.spend_webhook_url.as_deref(),
+            Some("https://hooks.example.com/nostring")
+        );
+        assert_eq!(
+            config.notifications.spend_webhook_secret.as_deref(),
+            Some("hunter2")
+        );
         assert_eq!(config.notifications.heirs.len(), 2);
         assert_eq!(config.notifications.heirs[0].label, "Spouse");
         assert_eq!(config.notifications.heirs[1].email, None);
     }
 
+    #[test]
+    fn test_email_port_follows_tls_mode() {
+        let email = |extra: &str| {
+            let toml = format!(
+                "{}\n[notifications.email]\nsmtp_host = \"smtp.example.com\"\n\
+                 smtp_user = \"u\"\nsmtp_password = \"p\"\n\
+                 from_address = \"a@example.com\"\nowner_email = \"o@example.com\"\n{}",
+                minimal_toml(),
+                extra
+            );
+            ServerConfig::from_toml(&toml)
+                .unwrap()
+                .notifications
+                .email
+                .unwrap()
+        };
+
+        let implicit = email("tls_mode = \"implicit_tls\"\n");
+        assert_eq!(implicit.tls_mode, nostring_notify::TlsMode::ImplicitTls);
+        assert_eq!(implicit.port(), 465);
+
+        // An explicit port wins over the mode's default
+        let custom = email("tls_mode = \"implicit_tls\"\nsmtp_port = 2465\n");
+        assert_eq!(custom.port(), 2465);
+
+        // The spelling `TlsMode::from_str` accepts works here too
+        let plaintext = email("tls_mode = \"plaintext\"\nsmtp_port = 1025\n");
+        assert_eq!(plaintext.tls_mode, nostring_notify::TlsMode::None);
+        assert_eq!(plaintext.port(), 1025);
+    }
+
     #[test]
     fn test_env_overrides() {
         let mut file = NamedTempFile::new().unwrap();
@@ -465,7 +928,34 @@ npub = "npub1child"
         let mut file = NamedTempFile::new().unwrap();
         write!(file, "{}", minimal_toml()).unwrap();
         let config = ServerConfig::from_file(file.path()).unwrap();
-        assert_eq!(config.network(), bitcoin::Network::Bitcoin);
+        assert_eq!(config.network().unwrap(), bitcoin::Network::Bitcoin);
+
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", full_toml()).unwrap();
+        let config = ServerConfig::from_file(file.path()).unwrap();
+        assert_eq!(config.network().unwrap(), bitcoin::Network::Testnet);
+    }
+
+    #[test]
+    fn test_validation_full_config() {
+        let config = ServerConfig::from_toml(&full_toml()).unwrap();
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validation_descriptor_network_mismatch() {
+        let mut config = ServerConfig::from_toml(&minimal_toml()).unwrap();
+        config.bitcoin.network = "testnet".into();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.starts_with("policy.descriptor has key ["), "{}", err);
+        assert!(
+            err.contains("isn't for bitcoin.network (testnet)"),
+            "{}",
+            err
+        );
+
+        config.policy.descriptor = TESTNET_DESCRIPTOR.into();
+        assert!(config.validate().is_ok());
     }
 
     #[test]
@@ -487,38 +977,149 @@ timelock_blocks = 26280
         write!(file, "{}", toml).unwrap();
 
         let config = ServerConfig::from_file(file.path()).unwrap();
-        assert!(config.validate().is_err());
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(err, "policy.descriptor must not be empty");
     }
 
     #[test]
-    fn test_validation_zero_timelock() {
+    fn test_validation_bad_descriptor_checksum() {
         let toml = r#"
 [policy]
-descriptor = "wsh(pk(xpub...))"
-timelock_blocks = 0
+descriptor = "raw(deadbeef)#89f8spxn"
+timelock_blocks = 26280
 "#;
         let mut file = NamedTempFile::new().unwrap();
         write!(file, "{}", toml).unwrap();
 
         let config = ServerConfig::from_file(file.path()).unwrap();
-        assert!(config.validate().is_err());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("checksum"), "{}", err);
+    }
+
+    #[test]
+    fn test_validation_zero_timelock() {
+        let toml = minimal_toml().replace("timelock_blocks = 26280", "timelock_blocks = 0");
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", toml).unwrap();
+
+        let config = ServerConfig::from_file(file.path()).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(err, "policy.timelock_blocks must be ≥ 1 (got 0)");
     }
 
     #[test]
     fn test_validation_check_interval_too_low() {
-        let toml = r#"
-[server]
-check_interval_secs = 30
+        let toml = format!("[server]\ncheck_interval_secs = 30\n{}", minimal_toml());
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", toml).unwrap();
 
-[policy]
-descriptor = "wsh(pk(xpub...))"
-timelock_blocks = 26280
-"#;
+        let config = ServerConfig::from_file(file.path()).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.starts_with("server.check_interval_secs"), "{}", err);
+    }
+
+    #[test]
+    fn test_validation_jitter_not_below_interval() {
+        let toml = format!(
+            "[server]\ncheck_interval_secs = 600\npoll_jitter_secs = 600\n{}",
+            minimal_toml()
+        );
         let mut file = NamedTempFile::new().unwrap();
         write!(file, "{}", toml).unwrap();
 
         let config = ServerConfig::from_file(file.path()).unwrap();
-        assert!(config.validate().is_err());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.starts_with("server.poll_jitter_secs"), "{}", err);
+    }
+
+    #[test]
+    fn test_validation_targets_offending_key() {
+        let base: ServerConfig = ServerConfig::from_toml(&minimal_toml()).unwrap();
+        let error_for = |edit: fn(&mut ServerConfig)| {
+            let mut config = base.clone();
+            edit(&mut config);
+            config.validate().unwrap_err().to_string()
+        };
+
+        let err = error_for(|c| c.policy.descriptor = "wsh(pk(xpub1))".into());
+        assert!(
+            err.starts_with("policy.descriptor failed to parse: "),
+            "{}",
+            err
+        );
+
+        let err = error_for(|c| c.policy.timelock_blocks = 70_000);
+        assert_eq!(err, "policy.timelock_blocks must be ≤ 65535 (got 70000)");
+
+        let err = error_for(|c| c.bitcoin.electrum_url = "https://electrum.example".into());
+        assert_eq!(
+            err,
+            "bitcoin.electrum_url must start with ssl:// or tcp:// (got \"https://electrum.example\")"
+        );
+
+        let err = error_for(|c| c.bitcoin.network = "mainnet".into());
+        assert!(
+            err.starts_with("bitcoin.network must be one of bitcoin"),
+            "{}",
+            err
+        );
+
+        let err = error_for(|c| c.notifications.spend_webhook_url = Some("hooks.example".into()));
+        assert!(
+            err.starts_with("notifications.spend_webhook_url must be an http(s) URL"),
+            "{}",
+            err
+        );
+
+        // Regtest's plain-TCP Electrum is fine
+        let mut regtest = base.clone();
+        regtest.bitcoin.network = "regtest".into();
+        regtest.policy.descriptor = TESTNET_DESCRIPTOR.into();
+        regtest.bitcoin.electrum_url = "tcp://127.0.0.1:50001".into();
+        assert!(regtest.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_key() {
+        // Wrong type: the key and where it is
+        let toml = minimal_toml().replace("timelock_blocks = 26280", "timelock_blocks = \"soon\"");
+        let err = ServerConfig::from_toml(&toml).unwrap_err().to_string();
+        assert!(
+            err.starts_with("policy.timelock_blocks (line 4, column 19): invalid type"),
+            "{}",
+            err
+        );
+
+        // Missing field: the table it belongs in
+        let toml = minimal_toml().replace("timelock_blocks = 26280\n", "");
+        let err = ServerConfig::from_toml(&toml).unwrap_err().to_string();
+        assert!(err.starts_with("policy.timelock_blocks"), "{}", err);
+        assert!(err.contains("missing field"), "{}", err);
+
+        // No policy at all
+        let err = ServerConfig::from_toml("[server]\nlog_level = \"info\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("policy"), "{}", err);
+        assert!(!err.starts_with("server.policy"), "{}", err);
+
+        // Wrong type in a nested table
+        let toml = format!(
+            "{}\n[notifications.email]\nsmtp_host = \"smtp.example.com\"\nsmtp_port = 99999\n",
+            minimal_toml()
+        );
+        let err = ServerConfig::from_toml(&toml).unwrap_err().to_string();
+        assert!(
+            err.starts_with("notifications.email.smtp_port (line"),
+            "{}",
+            err
+        );
+
+        // Unreadable file: the path
+        let err = ServerConfig::from_file(Path::new("/nonexistent/nostring.toml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/nonexistent/nostring.toml"), "{}", err);
     }
 
     #[test]
@@ -527,6 +1128,112 @@ timelock_blocks = 26280
         write!(file, "{}", minimal_toml()).unwrap();
         let config = ServerConfig::from_file(file.path()).unwrap();
         assert_eq!(config.notifications.threshold_days, vec![30, 7, 1, 0]);
+        assert_eq!(config.notifications.reminder_interval_days, 90);
+        assert_eq!(config.notifications.critical_threshold_blocks, 144);
+    }
+
+    #[test]
+    fn test_policy_recipients_fall_back_to_global() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", full_toml()).unwrap();
+        let config = ServerConfig::from_file(file.path()).unwrap();
+
+        let recipients = config.recipients(&config.policy);
+        assert_eq!(recipients.owner_npub, Some("npub1testowner"));
+        assert_eq!(recipients.owner_email, Some("owner@example.com"));
+        assert_eq!(recipients.heirs, &config.notifications.heirs[..]);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_policy_recipients_override_global() {
+        let toml = full_toml().replace(
+            "label = \"family-inheritance\"",
+            r#"label = "client-b"
+owner_npub = "npub1clientb"
+
+[[policy.heirs]]
+label = "Client B's daughter"
+email = "daughter@example.org""#,
+        );
+        let config: ServerConfig = toml::from_str(&toml).unwrap();
+        assert!(config.validate().is_ok());
+
+        // Overridden fields come from the policy, the rest stay global
+        let recipients = config.recipients(&config.policy);
+        assert_eq!(recipients.owner_npub, Some("npub1clientb"));
+        assert_eq!(recipients.owner_email, Some("owner@example.com"));
+        assert_eq!(recipients.heirs.len(), 1);
+        assert_eq!(recipients.heirs[0].label, "Client B's daughter");
+
+        // Global cc/bcc follow the global owner email only
+        let mut copied = config.clone();
+        let email = copied.notifications.email.as_mut().unwrap();
+        email.cc = vec!["custodian@example.com".into()];
+        email.bcc = vec!["archive@example.com".into()];
+        let recipients = copied.recipients(&copied.policy);
+        assert_eq!(recipients.cc, ["custodian@example.com"]);
+        assert_eq!(recipients.bcc, ["archive@example.com"]);
+
+        copied.policy.owner_email = Some("clientb@example.org".into());
+        let recipients = copied.recipients(&copied.policy);
+        assert!(recipients.cc.is_empty());
+        assert!(recipients.bcc.is_empty());
+
+        copied.policy.cc = Some(vec!["clientb-lawyer@example.org".into()]);
+        let recipients = copied.recipients(&copied.policy);
+        assert_eq!(recipients.cc, ["clientb-lawyer@example.org"]);
+        assert!(recipients.bcc.is_empty());
+
+        // An explicitly empty heir list doesn't inherit the global heirs
+        let mut no_heirs = config.clone();
+        no_heirs.policy.heirs = Some(Vec::new());
+        assert!(no_heirs.recipients(&no_heirs.policy).heirs.is_empty());
+
+        let reparsed: ServerConfig =
+            toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(reparsed.policy.owner_npub.as_deref(), Some("npub1clientb"));
+    }
+
+    #[test]
+    fn test_validation_policy_recipients() {
+        // Own recipients only, no global owner: fine
+        let toml = minimal_toml()
+            + r#"label = "client-a"
+owner_email = "a@example.org"
+
+[notifications.email]
+smtp_host = "smtp.example.com"
+smtp_user = "user"
+smtp_password = "secret"
+from_address = "nostring@example.com"
+owner_email = "custodian@example.com"
+"#;
+        let config: ServerConfig = toml::from_str(&toml).unwrap();
+        assert!(config.validate().is_ok());
+
+        // An npub override with no Nostr service key to send from
+        let mut npub_only = config.clone();
+        npub_only.policy.owner_npub = Some("npub1clienta".into());
+        let err = npub_only.validate().unwrap_err().to_string();
+        assert!(err.contains("client-a"), "{}", err);
+
+        let mut empty = config.clone();
+        empty.policy.owner_email = Some(String::new());
+        assert!(empty.validate().is_err());
+
+        // An email override with no SMTP server
+        let mut no_smtp = config.clone();
+        no_smtp.notifications.email = None;
+        assert!(no_smtp.validate().is_err());
+
+        // Neither can a cc list be sent without one
+        let mut cc_only = config;
+        cc_only.policy.owner_email = None;
+        cc_only.policy.cc = Some(vec!["lawyer@example.org".into()]);
+        cc_only.notifications.email = None;
+        let err = cc_only.validate().unwrap_err().to_string();
+        assert!(err.contains("policy.cc"), "{}", err);
     }
 
     #[test]
@@ -1,10 +1,14 @@
 //! The daemon loop — periodically polls the blockchain and sends notifications.
 
-use crate::config::ServerConfig;
+use crate::config::{PolicySection, ServerConfig};
+use crate::reminder::{self, ReminderState};
+use crate::webhook;
 use anyhow::{Context, Result};
+use nostring_core::clock::{Clock, SystemClock};
 use nostring_electrum::ElectrumClient;
 use nostring_notify::{EmailConfig, NostrConfig, NotificationService, NotifyConfig, Threshold};
 use nostring_watch::{WatchConfig, WatchEvent, WatchService};
+use rand::Rng;
 use std::time::Duration;
 
 /// Run the daemon loop. Blocks forever (until shutdown signal).
@@ -19,8 +23,8 @@ pub async fn run(config: ServerConfig) -> Result<()> {
     );
     log::info!("  Data dir:   {}", config.server.data_dir.display());
     log::info!(
-        "  Descriptor: {}…",
-        &config.policy.descriptor[..config.policy.descriptor.len().min(60)]
+        "  Descriptor: {}",
+        nostring_core::redact::redact_descriptor(&config.policy.descriptor)
     );
 
     // Ensure data directory exists
@@ -31,17 +35,17 @@ pub async fn run(config: ServerConfig) -> Result<()> {
         )
     })?;
 
-    let interval = Duration::from_secs(config.server.check_interval_secs);
-
     // Run first check immediately, then loop
     let mut first = true;
     loop {
         if !first {
-            log::info!(
-                "Sleeping {} seconds until next check…",
-                config.server.check_interval_secs
+            let delay = next_poll_delay(
+                config.server.check_interval_secs,
+                config.server.poll_jitter_secs,
+                &mut rand::thread_rng(),
             );
-            tokio::time::sleep(interval).await;
+            log::info!("Sleeping {} seconds until next check…", delay.as_secs());
+            tokio::time::sleep(delay).await;
         }
         first = false;
 
@@ -52,12 +56,23 @@ pub async fn run(config: ServerConfig) -> Result<()> {
     }
 }
 
+/// Time to wait before the next check: `interval_secs`, moved by a uniform
+/// random offset in `[-jitter_secs, +jitter_secs]`.
+pub fn next_poll_delay(interval_secs: u64, jitter_secs: u64, rng: &mut impl Rng) -> Duration {
+    if jitter_secs == 0 {
+        return Duration::from_secs(interval_secs);
+    }
+    let low = interval_secs.saturating_sub(jitter_secs);
+    let high = interval_secs.saturating_add(jitter_secs);
+    Duration::from_secs(rng.gen_range(low..=high))
+}
+
 /// Execute a single check cycle: poll blockchain, evaluate events, send notifications.
 pub async fn run_check_cycle(config: &ServerConfig) -> Result<()> {
     log::info!("Starting check cycle…");
 
     // Connect to Electrum
-    let network = config.network();
+    let network = config.network()?;
     let client = ElectrumClient::new(&config.bitcoin.electrum_url, network).with_context(|| {
         format!(
             "Failed to connect to Electrum at {}",
@@ -72,6 +87,8 @@ pub async fn run_check_cycle(config: &ServerConfig) -> Result<()> {
         poll_interval_secs: config.server.check_interval_secs,
         min_poll_interval_secs: 0, // Server manages its own interval via tokio::sleep
         warning_threshold_blocks: largest_threshold_blocks(&config.notifications.threshold_days),
+        min_confirmations: 1,
+        ..WatchConfig::default()
     };
 
     let mut watch =
@@ -114,18 +131,36 @@ pub async fn run_check_cycle(config: &ServerConfig) -> Result<()> {
                     height
                 );
             }
+            WatchEvent::UtxoPendingFunding {
+                policy_id,
+                outpoint,
+                value,
+                confirmations,
+                required,
+            } => {
+                log::info!(
+                    "[{}] UTXO pending: {} ({} sats), {}/{} confirmations",
+                    policy_id,
+                    outpoint,
+                    value,
+                    confirmations,
+                    required
+                );
+            }
             WatchEvent::UtxoSpent {
                 policy_id,
                 outpoint,
                 spending_txid,
                 spend_type,
+                confidence,
             } => {
                 log::warn!(
-                    "[{}] UTXO spent: {} by {} (type: {:?})",
+                    "[{}] UTXO spent: {} by {} (type: {:?}, confidence {:.2})",
                     policy_id,
                     outpoint,
                     spending_txid,
-                    spend_type
+                    spend_type,
+                    confidence
                 );
             }
             WatchEvent::TimelockWarning {
@@ -147,6 +182,11 @@ pub async fn run_check_cycle(config: &ServerConfig) -> Result<()> {
         }
     }
 
+    if let Some(ref url) = config.notifications.spend_webhook_url {
+        let secret = config.notifications.spend_webhook_secret.as_deref();
+        webhook::post_spend_events(url, secret, &events).await;
+    }
+
     // If no timelock warning event, compute blocks_remaining from state
     if blocks_remaining.is_none() {
         if let Some(policy) = watch.get_policy(&config.policy.label) {
@@ -163,6 +203,57 @@ pub async fn run_check_cycle(config: &ServerConfig) -> Result<()> {
         log::info!("No active UTXOs — nothing to notify about.");
     }
 
+    // Inactivity reminder, independent of timelock proximity. A check-in
+    // recreates the inheritance UTXO, so the newest tracked UTXO marks it.
+    let last_checkin = watch
+        .get_policy(&config.policy.label)
+        .and_then(|p| p.utxos.iter().map(|u| u.first_seen).max());
+    // A reminder that fails (e.g. unreadable reminder state) is logged
+    // rather than failing the rest of the check cycle
+    if let Err(e) = send_inactivity_reminder(config, last_checkin, &SystemClock).await {
+        log::error!("Failed to send inactivity reminder: {:#}", e);
+    }
+
+    Ok(())
+}
+
+/// Remind the owner if they haven't checked in for `reminder_interval_days`.
+async fn send_inactivity_reminder(
+    config: &ServerConfig,
+    last_checkin: Option<u64>,
+    clock: &dyn Clock,
+) -> Result<()> {
+    let state_path = config.server.data_dir.join("reminder_state.json");
+    let mut state = ReminderState::load(&state_path)?;
+
+    let now = clock.now_secs();
+
+    let Some(days) = reminder::reminder_due(
+        last_checkin,
+        state.last_reminder,
+        now,
+        config.notifications.reminder_interval_days,
+    ) else {
+        return Ok(());
+    };
+
+    log::info!(
+        "No check-in for {} days — sending inactivity reminder…",
+        days
+    );
+    let message = nostring_notify::templates::generate_inactivity_message(days);
+    match notification_service(config, &config.policy)
+        .send(&message)
+        .await
+    {
+        Ok(()) => {
+            state.last_reminder = Some(now);
+            state.save(&state_path)?;
+            log::info!("✉️  Inactivity reminder sent.");
+        }
+        Err(e) => log::error!("Inactivity reminder error: {}", e),
+    }
+
     Ok(())
 }
 
@@ -180,10 +271,51 @@ async fn send_notifications(
         days_remaining
     );
 
-    // Build notify config
+    let notify_config = notify_config(config, &config.policy);
+    let service = NotificationService::new(notify_config.clone());
+
+    // Owner notifications
+    match service
+        .check_and_notify(blocks_remaining, current_height)
+        .await
+    {
+        Ok(Some(level)) => {
+            log::info!("✉️  Owner notification sent: {:?}", level);
+        }
+        Ok(None) => {
+            log::info!("No owner notification needed — timelock healthy.");
+        }
+        Err(e) => {
+            log::error!("Owner notification error: {}", e);
+        }
+    }
+
+    // Heir descriptor delivery — only when critical
+    if notify_config.escalates_to_heirs(blocks_remaining) {
+        log::warn!(
+            "🔴 CRITICAL: Timelock ≤{} blocks — delivering descriptors to heirs…",
+            notify_config.critical_threshold_blocks
+        );
+        deliver_to_heirs(config, &config.policy).await;
+    }
+
+    Ok(())
+}
+
+/// Build the owner notification service for `policy` from the configured
+/// channels and the policy's recipients.
+fn notification_service(config: &ServerConfig, policy: &PolicySection) -> NotificationService {
+    NotificationService::new(notify_config(config, policy))
+}
+
+/// Notification settings for `policy`: channels, recipients, thresholds
+/// and when to escalate to heirs.
+fn notify_config(config: &ServerConfig, policy: &PolicySection) -> NotifyConfig {
+    let recipients = config.recipients(policy);
+
     let nostr_config = config.notifications.nostr.as_ref().map(|n| NostrConfig {
         enabled: true,
-        recipient_pubkey: n.owner_npub.clone(),
+        recipient_pubkey: recipients.owner_npub.unwrap_or(&n.owner_npub).to_string(),
         relays: n.relays.clone(),
         secret_key: Some(n.service_key.clone()),
     });
@@ -191,12 +323,15 @@ async fn send_notifications(
     let email_config = config.notifications.email.as_ref().map(|e| EmailConfig {
         enabled: true,
         smtp_host: e.smtp_host.clone(),
-        smtp_port: e.smtp_port,
+        smtp_port: e.port(),
         smtp_user: e.smtp_user.clone(),
         smtp_password: e.smtp_password.clone(),
         from_address: e.from_address.clone(),
-        to_address: e.owner_email.clone(),
-        plaintext: false,
+        to_address: recipients.owner_email.unwrap_or(&e.owner_email).to_string(),
+        cc: recipients.cc.to_vec(),
+        bcc: recipients.bcc.to_vec(),
+        tls_mode: e.tls_mode,
+        plaintext_only: e.plaintext_only,
     });
 
     // Build thresholds from config
@@ -207,41 +342,17 @@ async fn send_notifications(
         .map(|&d| Threshold::days(d))
         .collect();
 
-    let notify_config = NotifyConfig {
+    NotifyConfig {
         thresholds,
-        email: email_config.clone(),
+        email: email_config,
         nostr: nostr_config,
-    };
-
-    let service = NotificationService::new(notify_config);
-
-    // Owner notifications
-    match service
-        .check_and_notify(blocks_remaining, current_height)
-        .await
-    {
-        Ok(Some(level)) => {
-            log::info!("✉️  Owner notification sent: {:?}", level);
-        }
-        Ok(None) => {
-            log::info!("No owner notification needed — timelock healthy.");
-        }
-        Err(e) => {
-            log::error!("Owner notification error: {}", e);
-        }
-    }
-
-    // Heir descriptor delivery — only when critical (≤1 day / ≤144 blocks)
-    if blocks_remaining <= 144 {
-        log::warn!("🔴 CRITICAL: Timelock ≤144 blocks — delivering descriptors to heirs…");
-        deliver_to_heirs(config).await;
+        critical_threshold_blocks: config.notifications.critical_threshold_blocks,
+        ..NotifyConfig::default()
     }
-
-    Ok(())
 }
 
-/// Deliver the descriptor backup to configured heirs.
-async fn deliver_to_heirs(config: &ServerConfig) {
+/// Deliver `policy`'s descriptor backup to its heirs.
+async fn deliver_to_heirs(config: &ServerConfig, policy: &PolicySection) {
     let service_key = match config.notifications.nostr.as_ref() {
         Some(n) => &n.service_key,
         None => {
@@ -259,14 +370,14 @@ async fn deliver_to_heirs(config: &ServerConfig) {
 
     // Build a simple descriptor backup JSON
     let backup = serde_json::json!({
-        "descriptor": config.policy.descriptor,
+        "descriptor": policy.descriptor,
         "network": config.bitcoin.network,
-        "timelock_blocks": config.policy.timelock_blocks,
-        "label": config.policy.label,
+        "timelock_blocks": policy.timelock_blocks,
+        "label": policy.label,
     });
     let backup_json = serde_json::to_string_pretty(&backup).unwrap_or_default();
 
-    for heir in &config.notifications.heirs {
+    for heir in config.recipients(policy).heirs {
         let msg =
             nostring_notify::templates::generate_heir_delivery_message(&heir.label, &backup_json);
 
@@ -289,12 +400,15 @@ async fn deliver_to_heirs(config: &ServerConfig) {
             let smtp_config = nostring_notify::EmailConfig {
                 enabled: true,
                 smtp_host: email_config.smtp_host.clone(),
-                smtp_port: email_config.smtp_port,
+                smtp_port: email_config.port(),
                 smtp_user: email_config.smtp_user.clone(),
                 smtp_password: email_config.smtp_password.clone(),
                 from_address: email_config.from_address.clone(),
                 to_address: email_addr.clone(),
-                plaintext: false,
+                cc: Vec::new(),
+                bcc: Vec::new(),
+                tls_mode: email_config.tls_mode,
+                plaintext_only: email_config.plaintext_only,
             };
             match nostring_notify::smtp::send_email_to_recipient(&smtp_config, email_addr, &msg)
                 .await
@@ -311,3 +425,45 @@ fn largest_threshold_blocks(threshold_days: &[u32]) -> i64 {
     let max_days = threshold_days.iter().copied().max().unwrap_or(30);
     (max_days as i64) * 144
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_poll_delay_within_jitter() {
+        let mut rng = rand::thread_rng();
+        let mut delays = std::collections::HashSet::new();
+        for _ in 0..1000 {
+            let delay = next_poll_delay(600, 60, &mut rng).as_secs();
+            assert!((540..=660).contains(&delay), "{} out of range", delay);
+            delays.insert(delay);
+        }
+        // Actually spread out, not stuck on one value
+        assert!(delays.len() > 10);
+    }
+
+    #[test]
+    fn test_next_poll_delay_without_jitter() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert_eq!(next_poll_delay(600, 0, &mut rng), Duration::from_secs(600));
+        }
+    }
+    #[test]
+    fn test_heir_escalation_follows_configured_threshold() {
+        let toml = r#"
+[policy]
+descriptor = "wsh(or_d(pk([00000001/84'/0'/0']xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*),and_v(v:pk([00000002/84'/0'/1']xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB/0/*),older(26280))))"
+timelock_blocks = 26280
+
+[notifications]
+critical_threshold_blocks = 288
+"#;
+        let config = ServerConfig::from_toml(toml).unwrap();
+        let notify = notify_config(&config, &config.policy);
+        assert!(notify.escalates_to_heirs(288));
+        assert!(notify.escalates_to_heirs(200));
+        assert!(!notify.escalates_to_heirs(289));
+    }
+}
@@ -9,13 +9,20 @@
 //! nostring-server --config /path/to/nostring-server.toml
 //! nostring-server --check   # Run one check cycle and exit
 //! nostring-server --validate # Validate config and exit
+//! nostring-server utxos      # Print tracked UTXOs and balances
+//! nostring-server address    # Print the inheritance address
+//! nostring-server broadcast <psbt>  # Broadcast a signed PSBT
 //! ```
 
+mod cli;
 mod config;
 mod daemon;
+mod reminder;
+mod webhook;
 
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use cli::Command;
+use nostring_core::redact::redact_descriptor;
 
 fn main() -> Result<()> {
     // Security hardening: disable core dumps to prevent seed material leaking to disk
@@ -28,42 +35,22 @@ fn main() -> Result<()> {
         .ok();
 
     // Parse CLI args (minimal — no clap dependency needed)
-    let args: Vec<String> = std::env::args().collect();
-
-    let mut config_path = PathBuf::from("/config/nostring-server.toml");
-    let mut one_shot = false;
-    let mut validate_only = false;
-
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--config" | "-c" => {
-                i += 1;
-                if i < args.len() {
-                    config_path = PathBuf::from(&args[i]);
-                } else {
-                    anyhow::bail!("--config requires a path argument");
-                }
-            }
-            "--check" | "--once" => {
-                one_shot = true;
-            }
-            "--validate" => {
-                validate_only = true;
-            }
-            "--help" | "-h" => {
-                print_help();
-                return Ok(());
-            }
-            "--version" | "-V" => {
-                println!("nostring-server {}", env!("CARGO_PKG_VERSION"));
-                return Ok(());
-            }
-            other => {
-                anyhow::bail!("Unknown argument: {}", other);
-            }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli::CliArgs {
+        config_path,
+        command,
+    } = cli::parse_args(&args)?;
+
+    match command {
+        Command::Help => {
+            print_help();
+            return Ok(());
         }
-        i += 1;
+        Command::Version => {
+            println!("nostring-server {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        _ => {}
     }
 
     // Load config
@@ -82,13 +69,20 @@ fn main() -> Result<()> {
     std::env::set_var("RUST_LOG", &server_config.server.log_level);
     env_logger::init();
 
-    if validate_only {
+    match &command {
+        Command::Utxos => return cli::print_utxos(&server_config),
+        Command::Address => return cli::print_address(&server_config),
+        Command::Broadcast { psbt } => return cli::broadcast_psbt(&server_config, psbt),
+        _ => {}
+    }
+
+    if command == Command::Validate {
         println!("✅ Configuration is valid.");
         println!("  Network:       {}", server_config.bitcoin.network);
         println!("  Electrum:      {}", server_config.bitcoin.electrum_url);
         println!(
-            "  Descriptor:    {}…",
-            &server_config.policy.descriptor[..server_config.policy.descriptor.len().min(60)]
+            "  Descriptor:    {}",
+            redact_descriptor(&server_config.policy.descriptor)
         );
         println!(
             "  Timelock:      {} blocks",
@@ -116,7 +110,7 @@ fn main() -> Result<()> {
     // Build tokio runtime
     let rt = tokio::runtime::Runtime::new().context("Failed to create Tokio runtime")?;
 
-    if one_shot {
+    if command == Command::Check {
         log::info!("Running single check cycle…");
         rt.block_on(daemon::run_check_cycle(&server_config))?;
         log::info!("Done.");
@@ -146,7 +140,15 @@ fn print_help() {
         r#"NoString Server — headless inheritance monitoring daemon
 
 USAGE:
-    nostring-server [OPTIONS]
+    nostring-server [OPTIONS] [COMMAND]
+
+COMMANDS:
+    (none)                Run the monitoring daemon
+    check                 Same as --check
+    validate              Same as --validate
+    utxos                 Print tracked UTXOs and balances (from the last check)
+    address               Print the inheritance address (index 0)
+    broadcast <PSBT>      Broadcast a signed PSBT (base64 string or file path)
 
 OPTIONS:
     -c, --config <PATH>   Config file path (default: /config/nostring-server.toml)
@@ -158,6 +160,7 @@ OPTIONS:
 ENVIRONMENT VARIABLES (override config file):
     NOSTRING_DATA_DIR         Data directory path
     NOSTRING_CHECK_INTERVAL   Check interval in seconds
+    NOSTRING_POLL_JITTER      Random ± seconds added to each check interval
     NOSTRING_LOG_LEVEL        Log level (error/warn/info/debug/trace)
     NOSTRING_NETWORK          Bitcoin network (bitcoin/testnet/signet/regtest)
     NOSTRING_ELECTRUM_URL     Electrum server URL
@@ -165,6 +168,7 @@ ENVIRONMENT VARIABLES (override config file):
     NOSTRING_TIMELOCK_BLOCKS  Timelock in blocks
     NOSTRING_SERVICE_KEY      Nostr service key (nsec or hex)
     NOSTRING_OWNER_NPUB       Owner's Nostr public key
+    NOSTRING_NO_REDACT        Show full descriptors/emails in logs (debug builds only)
 
 EXAMPLES:
     # Run as daemon with config file
@@ -175,6 +179,13 @@ EXAMPLES:
 
     # Validate configuration
     nostring-server --config config.toml --validate
+
+    # Where should funds go? What's tracked?
+    nostring-server --config config.toml address
+    nostring-server --config config.toml utxos
+
+    # Broadcast a PSBT signed elsewhere
+    nostring-server --config config.toml broadcast signed.psbt
 "#
     );
 }
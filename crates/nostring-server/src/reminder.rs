@@ -0,0 +1,142 @@
+//! Inactivity reminders — nudge the owner when check-ins lapse.
+//!
+//! Independent of timelock proximity: if the owner hasn't checked in for
+//! `reminder_interval_days`, remind them once a week until they do. The time
+//! of the last reminder is persisted so restarts don't cause duplicates.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// How often to repeat the reminder while the owner stays inactive
+pub const REMINDER_CADENCE_DAYS: u64 = 7;
+
+/// Persisted reminder bookkeeping
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReminderState {
+    /// When the last inactivity reminder was sent (unix timestamp)
+    pub last_reminder: Option<u64>,
+}
+
+impl ReminderState {
+    /// Load from file, or start empty if it doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&contents).context("Failed to parse reminder state")
+    }
+
+    /// Save to file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Decide whether an inactivity reminder is due at `now`.
+///
+/// Returns the whole days since the last check-in if so. Nothing is due when
+/// reminders are disabled (`interval_days == 0`), no check-in is known yet,
+/// or a reminder went out less than a week ago (and after the last check-in).
+pub fn reminder_due(
+    last_checkin: Option<u64>,
+    last_reminder: Option<u64>,
+    now: u64,
+    interval_days: u32,
+) -> Option<u64> {
+    if interval_days == 0 {
+        return None;
+    }
+    let last_checkin = last_checkin?;
+    let inactive_secs = now.saturating_sub(last_checkin);
+    if inactive_secs < interval_days as u64 * SECS_PER_DAY {
+        return None;
+    }
+
+    match last_reminder {
+        // Reminded during this stretch of inactivity, within the cadence
+        Some(t)
+            if t >= last_checkin
+                && now.saturating_sub(t) < REMINDER_CADENCE_DAYS * SECS_PER_DAY =>
+        {
+            None
+        }
+        _ => Some(inactive_secs / SECS_PER_DAY),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const DAY: u64 = SECS_PER_DAY;
+    const T0: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_no_reminder_before_interval() {
+        assert_eq!(reminder_due(Some(T0), None, T0 + 29 * DAY, 30), None);
+        assert_eq!(reminder_due(Some(T0), None, T0 + 30 * DAY, 30), Some(30));
+    }
+
+    #[test]
+    fn test_disabled_or_unfunded() {
+        assert_eq!(reminder_due(Some(T0), None, T0 + 365 * DAY, 0), None);
+        assert_eq!(reminder_due(None, None, T0, 30), None);
+    }
+
+    #[test]
+    fn test_weekly_cadence_over_simulated_clock() {
+        // Check twice a day for 60 days; owner last checked in at T0
+        let mut last_reminder = None;
+        let mut sent = Vec::new();
+
+        for half_day in 0..120 {
+            let now = T0 + half_day * DAY / 2;
+            if let Some(days) = reminder_due(Some(T0), last_reminder, now, 30) {
+                sent.push(days);
+                last_reminder = Some(now);
+            }
+        }
+
+        // Day 30, then every 7 days: 37, 44, 51, 58
+        assert_eq!(sent, vec![30, 37, 44, 51, 58]);
+    }
+
+    #[test]
+    fn test_checkin_resets_reminders() {
+        // Reminded on day 30, owner checks in on day 32
+        let reminded = T0 + 30 * DAY;
+        let checkin = T0 + 32 * DAY;
+
+        // Not due again until 30 days after the new check-in
+        assert_eq!(
+            reminder_due(Some(checkin), Some(reminded), checkin + 10 * DAY, 30),
+            None
+        );
+        assert_eq!(
+            reminder_due(Some(checkin), Some(reminded), checkin + 30 * DAY, 30),
+            Some(30)
+        );
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("reminder_state.json");
+
+        assert!(ReminderState::load(&path).unwrap().last_reminder.is_none());
+
+        let state = ReminderState {
+            last_reminder: Some(T0),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(ReminderState::load(&path).unwrap().last_reminder, Some(T0));
+    }
+}
@@ -0,0 +1,263 @@
+//! Spend-event webhook — POSTs a JSON payload whenever a watched UTXO is spent.
+//!
+//! An heir claim showing up on-chain is the one event a custodian wants to
+//! hear about immediately, so every `UtxoSpent` is forwarded as soon as the
+//! check cycle sees it. When a secret is configured the body is signed with
+//! HMAC-SHA256 and the hex digest sent in [`SIGNATURE_HEADER`] as
+//! `sha256=<hex>`, so receivers can reject forged requests.
+
+use anyhow::{Context, Result};
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use bitcoin::hex::DisplayHex;
+use nostring_watch::{SpendType, WatchEvent};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Header carrying the body signature
+pub const SIGNATURE_HEADER: &str = "X-NoString-Signature";
+
+/// Time allowed for the receiver to answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON body sent for each spend
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendWebhookPayload {
+    pub policy_id: String,
+    pub outpoint: String,
+    pub spending_txid: String,
+    pub spend_type: SpendType,
+    pub confidence: f64,
+}
+
+impl SpendWebhookPayload {
+    /// Build the payload for a spend event; other events yield `None`.
+    pub fn from_event(event: &WatchEvent) -> Option<Self> {
+        match event {
+            WatchEvent::UtxoSpent {
+                policy_id,
+                outpoint,
+                spending_txid,
+                spend_type,
+                confidence,
+            } => Some(Self {
+                policy_id: policy_id.clone(),
+                outpoint: outpoint.to_string(),
+                spending_txid: spending_txid.to_string(),
+                spend_type: *spend_type,
+                confidence: *confidence,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// HMAC-SHA256 of `body` keyed with `secret`, as lowercase hex.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(body);
+    hmac::Hmac::<sha256::Hash>::from_engine(engine)
+        .to_byte_array()
+        .to_lower_hex_string()
+}
+
+/// POST every spend event in `events` to `url`.
+///
+/// Failures are logged rather than returned: a broken webhook must not stop
+/// the check cycle from sending the regular notifications. Returns how many
+/// payloads were delivered.
+pub async fn post_spend_events(url: &str, secret: Option<&str>, events: &[WatchEvent]) -> usize {
+    let payloads: Vec<SpendWebhookPayload> = events
+        .iter()
+        .filter_map(SpendWebhookPayload::from_event)
+        .collect();
+    if payloads.is_empty() {
+        return 0;
+    }
+
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::error!("Spend webhook disabled: {}", e);
+            return 0;
+        }
+    };
+
+    let mut delivered = 0;
+    for payload in &payloads {
+        match post_payload(&client, url, secret, payload).await {
+            Ok(()) => {
+                delivered += 1;
+                log::info!(
+                    "Spend webhook sent for {} ({:?})",
+                    payload.outpoint,
+                    payload.spend_type
+                );
+            }
+            Err(e) => log::error!("Spend webhook for {} failed: {:#}", payload.outpoint, e),
+        }
+    }
+    delivered
+}
+
+async fn post_payload(
+    client: &reqwest::Client,
+    url: &str,
+    secret: Option<&str>,
+    payload: &SpendWebhookPayload,
+) -> Result<()> {
+    let body = serde_json::to_vec(payload).context("Failed to serialize payload")?;
+
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    if let Some(secret) = secret {
+        request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &body)));
+    }
+
+    let response = request.body(body).send().await.context("Request failed")?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Receiver answered {}",
+        response.status()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{Amount, OutPoint, Txid};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP receiver: records each request and answers 200.
+    async fn spawn_receiver() -> (String, Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let count = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let (c, r) = (Arc::clone(&count), Arc::clone(&requests));
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let request = read_request(&mut socket).await;
+                c.fetch_add(1, Ordering::SeqCst);
+                r.lock().unwrap().push(request);
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                    .await;
+            }
+        });
+
+        (url, count, requests)
+    }
+
+    /// Read headers plus `content-length` bytes of body.
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|l| {
+                        let (name, value) = l.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if buf.len() >= end + 4 + length {
+                    return text;
+                }
+            }
+        }
+        String::from_utf8_lossy(&buf).to_string()
+    }
+
+    fn spend_event() -> WatchEvent {
+        WatchEvent::UtxoSpent {
+            policy_id: "inheritance".into(),
+            outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            spending_txid: Txid::from_byte_array([2; 32]),
+            spend_type: SpendType::HeirClaim,
+            confidence: 0.95,
+        }
+    }
+
+    #[test]
+    fn test_payload_only_for_spends() {
+        let payload = SpendWebhookPayload::from_event(&spend_event()).unwrap();
+        assert_eq!(payload.spend_type, SpendType::HeirClaim);
+        assert_eq!(payload.confidence, 0.95);
+        assert!(payload.outpoint.ends_with(":0"));
+
+        let appeared = WatchEvent::UtxoAppeared {
+            policy_id: "inheritance".into(),
+            outpoint: OutPoint::null(),
+            value: Amount::from_sat(1000),
+            height: 1,
+        };
+        assert!(SpendWebhookPayload::from_event(&appeared).is_none());
+    }
+
+    #[test]
+    fn test_sign_rfc4231_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spend_event_posts_exactly_once() {
+        let (url, count, requests) = spawn_receiver().await;
+        let events = vec![
+            WatchEvent::UtxoAppeared {
+                policy_id: "inheritance".into(),
+                outpoint: OutPoint::null(),
+                value: Amount::from_sat(1000),
+                height: 1,
+            },
+            spend_event(),
+            WatchEvent::PollError {
+                message: "ignored".into(),
+            },
+        ];
+
+        let delivered = post_spend_events(&url, Some("secret"), &events).await;
+        assert_eq!(delivered, 1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /hook"));
+        assert!(request.contains("\"spend_type\":\"HeirClaim\""));
+
+        let body = request.split("\r\n\r\n").nth(1).unwrap();
+        let expected = format!("sha256={}", sign("secret", body.as_bytes()));
+        assert!(request.to_lowercase().contains(&format!(
+            "{}: {}",
+            SIGNATURE_HEADER.to_lowercase(),
+            expected
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_no_spends_no_request() {
+        let (url, count, _) = spawn_receiver().await;
+        assert_eq!(post_spend_events(&url, None, &[]).await, 0);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+    }
+}
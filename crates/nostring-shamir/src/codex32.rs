@@ -19,6 +19,8 @@
 //! Example: `ms12namea320zyxwvutsrqpnmlkjhgfedcaxrpp870hkkqrm` (2-of-N, identifier "name", share 'a')
 
 use crate::ShamirError;
+use bip39::{Language, Mnemonic};
+use nostring_core::memory::LockedBuffer;
 use serde::{Deserialize, Serialize};
 
 /// Bech32 character set (same as BIP-173)
@@ -60,6 +62,13 @@ pub struct Codex32Share {
     pub encoded: String,
 }
 
+impl Codex32Share {
+    /// Length in bytes of the secret this share reconstructs to
+    pub fn secret_len(&self) -> usize {
+        self.payload.len()
+    }
+}
+
 /// Configuration for Codex32 generation
 #[derive(Debug, Clone)]
 pub struct Codex32Config {
@@ -110,6 +119,28 @@ impl Codex32Config {
     }
 }
 
+/// Derive a 4-character share identifier from an owner's npub.
+///
+/// The first 20 bits of `SHA256(npub)` mapped onto the bech32 alphabet, so
+/// each owner's shares are self-identifying and splits for different owners
+/// can be told apart during recovery. Stable for a given npub; case and
+/// surrounding whitespace are ignored.
+pub fn identifier_from_npub(npub: &str) -> String {
+    use bitcoin::hashes::{sha256, Hash};
+
+    let normalized = npub.trim().to_lowercase();
+    let hash = sha256::Hash::hash(normalized.as_bytes());
+    let bytes = hash.as_byte_array();
+    let bits = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+    (0..4)
+        .map(|i| {
+            let v = (bits >> (27 - 5 * i)) & 0x1f;
+            CHARSET.as_bytes()[v as usize] as char
+        })
+        .collect()
+}
+
 /// Compute the BCH polymod checksum
 fn ms32_polymod(values: &[u8]) -> u128 {
     let mut residue: u128 = 0x23181b3;
@@ -241,7 +272,16 @@ pub fn ms32_recover(shares: &[Codex32Share]) -> Result<Codex32Share, ShamirError
         return Err(ShamirError::InsufficientShares);
     }
 
-    let threshold = shares[0].threshold as usize;
+    // Never interpolate across different splits
+    let first = &shares[0];
+    if shares
+        .iter()
+        .any(|s| s.threshold != first.threshold || s.identifier != first.identifier)
+    {
+        return Err(ShamirError::VerificationFailed);
+    }
+
+    let threshold = first.threshold as usize;
     if shares.len() < threshold {
         return Err(ShamirError::InsufficientShares);
     }
@@ -260,20 +300,35 @@ pub fn ms32_recover(shares: &[Codex32Share]) -> Result<Codex32Share, ShamirError
     parse_share(&encoded)
 }
 
+/// Secret lengths accepted by [`generate_shares`]: 128-bit and 256-bit
+pub const SUPPORTED_SECRET_LENS: [usize; 2] = [16, 32];
+
 /// Generate Codex32 shares from a master seed
 ///
 /// # Arguments
-/// * `seed` - The BIP-32 master seed (16-64 bytes)
+/// * `seed` - The secret to split; must be exactly `secret_len` bytes
+/// * `secret_len` - Secret length in bytes: 16 (e.g. 128-bit BIP-39 entropy) or 32
 /// * `config` - Configuration for share generation
 ///
 /// # Returns
-/// Vector of Codex32 shares
+/// Vector of Codex32 shares. Recombining them yields exactly `secret_len` bytes.
 pub fn generate_shares(
     seed: &[u8],
+    secret_len: usize,
     config: &Codex32Config,
 ) -> Result<Vec<Codex32Share>, ShamirError> {
-    if seed.len() < 16 || seed.len() > 64 {
-        return Err(ShamirError::InvalidShare("Seed must be 16-64 bytes".into()));
+    if !SUPPORTED_SECRET_LENS.contains(&secret_len) {
+        return Err(ShamirError::InvalidShare(format!(
+            "Unsupported secret length {} (must be 16 or 32 bytes)",
+            secret_len
+        )));
+    }
+    if seed.len() != secret_len {
+        return Err(ShamirError::InvalidShare(format!(
+            "Secret is {} bytes, expected {}",
+            seed.len(),
+            secret_len
+        )));
     }
 
     // First, create the secret share (index 's')
@@ -565,16 +620,108 @@ pub fn parse_share(encoded: &str) -> Result<Codex32Share, ShamirError> {
     })
 }
 
+/// Progress towards recovering a secret from a partial set of shares
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryProgress {
+    /// Shares required to recover (from the first share's header)
+    pub threshold: u8,
+    /// Distinct shares collected so far
+    pub have: usize,
+    /// Further shares needed to reach the threshold
+    pub missing: usize,
+    /// Whether every share carries the same threshold and identifier
+    pub same_split: bool,
+}
+
+/// Report how close a set of shares is to recovering the secret.
+///
+/// Duplicate shares (same index) are counted once. An unshared secret
+/// (threshold 0, index 's') is complete on its own. Shares from different
+/// splits are reported via `same_split` rather than being mixed; combining
+/// them fails with [`ShamirError::VerificationFailed`].
+pub fn recovery_progress(shares: &[Codex32Share]) -> Result<RecoveryProgress, ShamirError> {
+    let first = shares.first().ok_or(ShamirError::InsufficientShares)?;
+
+    let same_split = shares
+        .iter()
+        .all(|s| s.threshold == first.threshold && s.identifier == first.identifier);
+
+    let mut indices: Vec<char> = shares.iter().map(|s| s.index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    let have = indices.len();
+
+    let needed = if first.threshold == 0 {
+        1
+    } else {
+        first.threshold as usize
+    };
+
+    Ok(RecoveryProgress {
+        threshold: first.threshold,
+        have,
+        missing: needed.saturating_sub(have),
+        same_split,
+    })
+}
+
 /// Combine Codex32 shares to recover the master seed
+///
+/// The returned secret has the length it was split with (16 or 32 bytes for
+/// shares from [`generate_shares`]); see [`Codex32Share::secret_len`].
 pub fn combine_shares(shares: &[Codex32Share]) -> Result<Vec<u8>, ShamirError> {
     let secret = ms32_recover(shares)?;
     Ok(secret.payload)
 }
 
+/// Like [`combine_shares`], but hands the secret back in a [`LockedBuffer`]
+/// (memory-locked, zeroized on drop). Prefer this in recovery flows.
+pub fn combine_shares_secure(shares: &[Codex32Share]) -> Result<LockedBuffer, ShamirError> {
+    combine_shares(shares).map(LockedBuffer::from_vec)
+}
+
+/// Combine Codex32 shares that split BIP-39 entropy back into a mnemonic
+/// in `language`.
+///
+/// The entropy is language-independent, so heirs can restore the phrase in
+/// their own wordlist. Note that BIP-39 seeds are derived from the *words*:
+/// wallets expecting the original phrase must be given the same language.
+pub fn combine_shares_to_mnemonic(
+    shares: &[Codex32Share],
+    language: Language,
+) -> Result<Mnemonic, ShamirError> {
+    let entropy = combine_shares_secure(shares)?;
+    Mnemonic::from_entropy_in(language, entropy.as_slice())
+        .map_err(|e| ShamirError::InvalidEntropy(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_identifier_from_npub() {
+        let alice = "npub1sg6plzptd64u62a878hep2kev88swjh3tw00gjsfl8f237lmu63q0uf63m";
+        let bob = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+
+        let id = identifier_from_npub(alice);
+        assert_eq!(id.len(), 4);
+        assert!(id.chars().all(|c| CHARSET.contains(c)));
+
+        // Stable, and insensitive to case/whitespace
+        assert_eq!(identifier_from_npub(alice), id);
+        assert_eq!(
+            identifier_from_npub(&format!("  {}\n", alice.to_uppercase())),
+            id
+        );
+
+        // Distinct owners get distinct identifiers
+        assert_ne!(identifier_from_npub(bob), id);
+
+        // Usable as a Codex32 identifier
+        assert!(Codex32Config::new(2, &id, 3).is_ok());
+    }
+
     #[test]
     fn test_config_validation() {
         assert!(Codex32Config::new(2, "test", 3).is_ok());
@@ -693,7 +840,7 @@ mod tests {
         let seed = vec![0x42u8; 16]; // 128-bit seed
         let config = Codex32Config::new(2, "cash", 3).unwrap();
 
-        let shares = generate_shares(&seed, &config).unwrap();
+        let shares = generate_shares(&seed, 16, &config).unwrap();
         assert_eq!(shares.len(), 3);
 
         // All shares should have valid checksums
@@ -706,4 +853,117 @@ mod tests {
         let recovered = combine_shares(&shares[0..2]).unwrap();
         assert_eq!(recovered, seed);
     }
+
+    #[test]
+    fn test_recovery_progress() {
+        let seed = vec![0x42u8; 16];
+        let config = Codex32Config::new(3, "cash", 5).unwrap();
+        let shares = generate_shares(&seed, 16, &config).unwrap();
+
+        let progress = recovery_progress(&shares[..1]).unwrap();
+        assert_eq!(progress.threshold, 3);
+        assert_eq!(progress.have, 1);
+        assert_eq!(progress.missing, 2);
+        assert!(progress.same_split);
+
+        // A duplicated share doesn't count twice
+        let dup = vec![shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        let progress = recovery_progress(&dup).unwrap();
+        assert_eq!(progress.have, 2);
+        assert_eq!(progress.missing, 1);
+
+        let progress = recovery_progress(&shares[..4]).unwrap();
+        assert_eq!(progress.have, 4);
+        assert_eq!(progress.missing, 0);
+
+        assert!(matches!(
+            recovery_progress(&[]),
+            Err(ShamirError::InsufficientShares)
+        ));
+    }
+
+    #[test]
+    fn test_mixed_splits_rejected() {
+        let seed = vec![0x42u8; 16];
+        let a = generate_shares(&seed, 16, &Codex32Config::new(2, "cash", 3).unwrap()).unwrap();
+        let b = generate_shares(&seed, 16, &Codex32Config::new(2, "test", 3).unwrap()).unwrap();
+        let mixed = vec![a[0].clone(), b[1].clone()];
+
+        let progress = recovery_progress(&mixed).unwrap();
+        assert!(!progress.same_split);
+
+        assert!(matches!(
+            combine_shares(&mixed),
+            Err(ShamirError::VerificationFailed)
+        ));
+    }
+
+    #[test]
+    fn test_generate_and_recover_32_byte_secret() {
+        let secret: Vec<u8> = (0u8..32).collect();
+        let config = Codex32Config::new(3, "nsec", 5).unwrap();
+
+        let shares = generate_shares(&secret, 32, &config).unwrap();
+        assert!(shares.iter().all(|s| s.secret_len() == 32));
+
+        let recovered = combine_shares(&shares[2..5]).unwrap();
+        assert_eq!(recovered.len(), 32);
+        assert_eq!(recovered, secret);
+    }
+
+    #[test]
+    fn test_combine_shares_secure() {
+        let secret: Vec<u8> = (0u8..32).collect();
+        let config = Codex32Config::new(2, "nsec", 3).unwrap();
+        let shares = generate_shares(&secret, 32, &config).unwrap();
+
+        let recovered = combine_shares_secure(&shares[0..2]).unwrap();
+        assert_eq!(recovered.as_slice(), secret.as_slice());
+        assert!(combine_shares_secure(&shares[0..1]).is_err());
+    }
+
+    #[test]
+    fn test_combine_shares_to_mnemonic_in_language() {
+        let entropy = [0x42u8; 16];
+        let config = Codex32Config::new(2, "seed", 3).unwrap();
+        let shares = generate_shares(&entropy, 16, &config).unwrap();
+
+        let english = combine_shares_to_mnemonic(&shares[0..2], Language::English).unwrap();
+        let spanish = combine_shares_to_mnemonic(&shares[1..3], Language::Spanish).unwrap();
+        assert_eq!(english.language(), Language::English);
+        assert_eq!(spanish.language(), Language::Spanish);
+        assert_eq!(english.to_entropy(), entropy);
+        assert_eq!(spanish.to_entropy(), entropy);
+        assert_ne!(english.to_string(), spanish.to_string());
+    }
+
+    #[test]
+    fn test_generate_16_byte_secret_len() {
+        let secret = vec![0x42u8; 16];
+        let config = Codex32Config::new(2, "cash", 3).unwrap();
+
+        let shares = generate_shares(&secret, 16, &config).unwrap();
+        assert!(shares.iter().all(|s| s.secret_len() == 16));
+        assert_eq!(combine_shares(&shares[1..3]).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_unsupported_secret_len_rejected() {
+        let config = Codex32Config::new(2, "cash", 3).unwrap();
+
+        // Lengths other than 16 or 32
+        for len in [15, 24, 64] {
+            let secret = vec![0x42u8; len];
+            assert!(matches!(
+                generate_shares(&secret, len, &config),
+                Err(ShamirError::InvalidShare(_))
+            ));
+        }
+
+        // Secret doesn't match the declared length
+        assert!(matches!(
+            generate_shares(&[0x42u8; 32], 16, &config),
+            Err(ShamirError::InvalidShare(_))
+        ));
+    }
 }
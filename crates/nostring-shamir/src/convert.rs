@@ -0,0 +1,103 @@
+//! Convert share sets between SLIP-39 and Codex32
+//!
+//! Reconstructs the secret from one scheme's shares and re-splits it in the
+//! other, so an existing SLIP-39 backup can join the Codex32 inheritance
+//! flow (and back) without the secret leaving the process. The recovered
+//! secret only ever lives in a [`LockedBuffer`], zeroized on drop.
+//!
+//! The old shares stay valid: converting creates a second, independent
+//! share set for the same secret. Destroy the old one once the new shares
+//! are distributed.
+
+use crate::codex32::{self, Codex32Config, Codex32Share, SUPPORTED_SECRET_LENS};
+use crate::slip39::{self, Slip39Config, Slip39Share};
+use crate::ShamirError;
+use nostring_core::memory::LockedBuffer;
+
+/// Re-split the secret behind `slip39_shares` as Codex32 shares.
+///
+/// The SLIP-39 shares must meet their thresholds. Codex32 only supports
+/// 128- and 256-bit secrets, so SLIP-39 secrets of other lengths are
+/// rejected.
+pub fn slip39_to_codex32(
+    slip39_shares: &[Slip39Share],
+    new_config: &Codex32Config,
+) -> Result<Vec<Codex32Share>, ShamirError> {
+    let secret = LockedBuffer::from_vec(slip39::combine_shares(slip39_shares)?);
+    if !SUPPORTED_SECRET_LENS.contains(&secret.len()) {
+        return Err(ShamirError::InvalidShare(format!(
+            "{}-bit SLIP-39 secret can't be converted: Codex32 supports 128 or 256 bits",
+            secret.len() * 8
+        )));
+    }
+    codex32::generate_shares(secret.as_slice(), secret.len(), new_config)
+}
+
+/// Re-split the secret behind `codex32_shares` as SLIP-39 shares, one
+/// `Vec` per group of `new_config`.
+pub fn codex32_to_slip39(
+    codex32_shares: &[Codex32Share],
+    new_config: &Slip39Config,
+) -> Result<Vec<Vec<Slip39Share>>, ShamirError> {
+    let secret = codex32::combine_shares_secure(codex32_shares)?;
+    slip39::generate_shares(secret.as_slice(), new_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET_128: [u8; 16] = [
+        0x0c, 0x94, 0x90, 0xbc, 0xc3, 0x9b, 0x4e, 0x52, 0x7d, 0xa4, 0x05, 0x11, 0x72, 0xf0, 0x8c,
+        0x1e,
+    ];
+
+    #[test]
+    fn test_slip39_to_codex32_roundtrip() {
+        let groups = slip39::generate_shares(&SECRET_128, &Slip39Config::two_of_three()).unwrap();
+        let config = Codex32Config::three_of_five("test").unwrap();
+
+        let codex32_shares = slip39_to_codex32(&groups[0][1..], &config).unwrap();
+        assert_eq!(codex32_shares.len(), 5);
+        assert!(codex32_shares.iter().all(|s| s.threshold == 3));
+
+        let recovered = codex32::combine_shares(&codex32_shares[2..]).unwrap();
+        assert_eq!(recovered, SECRET_128);
+    }
+
+    #[test]
+    fn test_codex32_to_slip39_roundtrip() {
+        let config = Codex32Config::two_of_three("test").unwrap();
+        let codex32_shares = codex32::generate_shares(&SECRET_128, 16, &config).unwrap();
+
+        let groups =
+            codex32_to_slip39(&codex32_shares[..2], &Slip39Config::three_of_five()).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 5);
+
+        let recovered = slip39::combine_shares(&groups[0][..3]).unwrap();
+        assert_eq!(recovered, SECRET_128);
+
+        // And back again
+        let back = slip39_to_codex32(&groups[0][1..4], &config).unwrap();
+        assert_eq!(codex32::combine_shares(&back[1..]).unwrap(), SECRET_128);
+    }
+
+    #[test]
+    fn test_below_threshold_fails() {
+        let groups = slip39::generate_shares(&SECRET_128, &Slip39Config::two_of_three()).unwrap();
+        let config = Codex32Config::two_of_three("test").unwrap();
+        assert!(slip39_to_codex32(&groups[0][..1], &config).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_secret_length_rejected() {
+        // 160-bit secrets are valid SLIP-39 but not Codex32
+        let groups = slip39::generate_shares(&[0x42u8; 20], &Slip39Config::two_of_three()).unwrap();
+        let config = Codex32Config::two_of_three("test").unwrap();
+        assert!(matches!(
+            slip39_to_codex32(&groups[0][..2], &config),
+            Err(ShamirError::InvalidShare(_))
+        ));
+    }
+}
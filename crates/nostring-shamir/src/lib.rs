@@ -15,6 +15,10 @@
 //! - Bech32-encoded shares for error detection
 //! - **Reconstructs to BIP-39 compatible seed**
 //!
+//! Existing share sets can be moved between the two with [`convert`].
+//! Callers that don't care which scheme is in use can go through the
+//! [`SecretSharing`] trait and pick a backend with [`Scheme`].
+//!
 //! # Example: Split a BIP-39 entropy
 //!
 //! ```
@@ -38,14 +42,17 @@
 //! ```
 
 pub mod codex32;
+pub mod convert;
 pub mod gf256;
 pub mod rs1024;
+pub mod scheme;
 pub mod shamir;
 pub mod shares;
 pub mod slip39;
 pub mod wordlist;
 
 // Re-exports
+pub use scheme::{Scheme, SecretSharing, ShareString};
 pub use shamir::{reconstruct_secret, split_secret, Share};
 pub use slip39::{combine_shares, generate_shares, Slip39Config, Slip39Share};
 
@@ -63,6 +70,8 @@ pub enum ShamirError {
     VerificationFailed,
     #[error("Invalid share format: {0}")]
     InvalidShare(String),
+    #[error("Recovered secret is not valid BIP-39 entropy: {0}")]
+    InvalidEntropy(String),
     #[error("Division by zero in GF(256): {0}")]
     DivisionByZero(&'static str),
 }
@@ -0,0 +1,260 @@
+//! Scheme-agnostic secret sharing
+//!
+//! [`SecretSharing`] splits a secret into encoded share strings and
+//! combines them again, whatever the encoding. Codex32 and SLIP-39 both
+//! implement it; [`Scheme`] picks one by name so callers (the Tauri
+//! commands) don't need per-scheme code paths. The concrete `codex32` and
+//! `slip39` APIs remain for callers that need the structured shares.
+
+use crate::codex32::{self, Codex32Config};
+use crate::slip39::{self, Slip39Config};
+use crate::ShamirError;
+use nostring_core::memory::LockedBuffer;
+use serde::{Deserialize, Serialize};
+
+/// A share in its scheme's text encoding: a `ms1…` string for Codex32,
+/// space-separated words for SLIP-39
+pub type ShareString = String;
+
+/// Split a secret into shares and combine them again.
+pub trait SecretSharing {
+    /// Split `secret` into `total` shares, any `threshold` of which
+    /// reconstruct it.
+    fn split(
+        &self,
+        secret: &[u8],
+        threshold: u8,
+        total: u8,
+    ) -> Result<Vec<ShareString>, ShamirError>;
+
+    /// Reconstruct the secret from enough shares of one split.
+    fn combine(&self, shares: &[ShareString]) -> Result<LockedBuffer, ShamirError>;
+}
+
+/// Codex32 (BIP-93) shares under a fixed identifier.
+///
+/// Secrets must be 16 or 32 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Codex32Sharing {
+    /// 4-character bech32 identifier stamped on every share
+    pub identifier: String,
+}
+
+impl Codex32Sharing {
+    /// Shares tagged with `identifier`
+    pub fn new(identifier: impl Into<String>) -> Self {
+        Self {
+            identifier: identifier.into(),
+        }
+    }
+}
+
+impl SecretSharing for Codex32Sharing {
+    fn split(
+        &self,
+        secret: &[u8],
+        threshold: u8,
+        total: u8,
+    ) -> Result<Vec<ShareString>, ShamirError> {
+        let config = Codex32Config::new(threshold, &self.identifier, total)?;
+        let shares = codex32::generate_shares(secret, secret.len(), &config)?;
+        Ok(shares.into_iter().map(|s| s.encoded).collect())
+    }
+
+    fn combine(&self, shares: &[ShareString]) -> Result<LockedBuffer, ShamirError> {
+        let parsed = shares
+            .iter()
+            .map(|s| codex32::parse_share(s.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        codex32::combine_shares_secure(&parsed)
+    }
+}
+
+/// Single-group SLIP-39 shares.
+///
+/// Secrets must be 16 to 32 bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Slip39Sharing {
+    /// Share set identifier; random when `None`
+    pub identifier: Option<u16>,
+}
+
+impl SecretSharing for Slip39Sharing {
+    fn split(
+        &self,
+        secret: &[u8],
+        threshold: u8,
+        total: u8,
+    ) -> Result<Vec<ShareString>, ShamirError> {
+        let config = Slip39Config {
+            identifier: self.identifier,
+            ..Slip39Config::with_groups(1, vec![(threshold, total)])
+        };
+        let mut groups = slip39::generate_shares(secret, &config)?;
+        Ok(groups
+            .remove(0)
+            .into_iter()
+            .map(|s| s.words.join(" "))
+            .collect())
+    }
+
+    fn combine(&self, shares: &[ShareString]) -> Result<LockedBuffer, ShamirError> {
+        let parsed = shares
+            .iter()
+            .map(|s| {
+                let words: Vec<String> = s.split_whitespace().map(str::to_lowercase).collect();
+                slip39::parse_mnemonic(&words)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(LockedBuffer::from_vec(slip39::combine_shares(&parsed)?))
+    }
+}
+
+/// The supported secret sharing schemes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    Codex32,
+    Slip39,
+}
+
+impl Scheme {
+    /// Guess the scheme of an encoded share: Codex32 strings start with
+    /// `ms1`, SLIP-39 shares are several words.
+    pub fn detect(share: &str) -> Option<Self> {
+        let share = share.trim();
+        if share.to_lowercase().starts_with("ms1") {
+            Some(Scheme::Codex32)
+        } else if share.split_whitespace().count() > 1 {
+            Some(Scheme::Slip39)
+        } else {
+            None
+        }
+    }
+
+    /// The backend for this scheme.
+    ///
+    /// `identifier` tags Codex32 shares (4 bech32 characters); SLIP-39
+    /// picks a random identifier and ignores it.
+    pub fn backend(self, identifier: &str) -> Box<dyn SecretSharing> {
+        match self {
+            Scheme::Codex32 => Box::new(Codex32Sharing::new(identifier)),
+            Scheme::Slip39 => Box::new(Slip39Sharing::default()),
+        }
+    }
+
+    /// Combine shares of any supported scheme.
+    ///
+    /// The scheme is detected from the shares, which must all use the same
+    /// one.
+    pub fn combine_detected(shares: &[ShareString]) -> Result<LockedBuffer, ShamirError> {
+        let mut schemes = shares.iter().map(|s| Scheme::detect(s));
+        let scheme = match schemes.next() {
+            Some(Some(scheme)) => scheme,
+            Some(None) => {
+                return Err(ShamirError::InvalidShare(
+                    "Could not detect share format".into(),
+                ))
+            }
+            None => return Err(ShamirError::InsufficientShares),
+        };
+        if schemes.any(|s| s != Some(scheme)) {
+            return Err(ShamirError::InvalidShare(
+                "Shares mix different schemes".into(),
+            ));
+        }
+        // The identifier only matters when splitting
+        scheme.backend("").combine(shares)
+    }
+}
+
+impl std::fmt::Display for Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scheme::Codex32 => write!(f, "codex32"),
+            Scheme::Slip39 => write!(f, "slip39"),
+        }
+    }
+}
+
+impl std::str::FromStr for Scheme {
+    type Err = ShamirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('-', "").as_str() {
+            "codex32" => Ok(Scheme::Codex32),
+            "slip39" => Ok(Scheme::Slip39),
+            other => Err(ShamirError::InvalidShare(format!(
+                "Unknown secret sharing scheme: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET_128: [u8; 16] = [0x42; 16];
+    const SECRET_256: [u8; 32] = [
+        0x0c, 0x94, 0x90, 0xbc, 0xc3, 0x9b, 0x4e, 0x52, 0x7d, 0xa4, 0x05, 0x11, 0x72, 0xf0, 0x8c,
+        0x1e, 0x60, 0x1b, 0x7e, 0x3f, 0x28, 0x99, 0xd1, 0x04, 0xaa, 0x5c, 0x31, 0xe7, 0x0b, 0x82,
+        0x6d, 0xf4,
+    ];
+
+    /// The contract every backend must meet
+    fn assert_contract(backend: &dyn SecretSharing) {
+        for secret in [&SECRET_128[..], &SECRET_256[..]] {
+            let shares = backend.split(secret, 3, 5).unwrap();
+            assert_eq!(shares.len(), 5);
+
+            // Any threshold-sized subset reconstructs the secret
+            let subset = vec![shares[4].clone(), shares[0].clone(), shares[2].clone()];
+            assert_eq!(backend.combine(&subset).unwrap().as_slice(), secret);
+            assert_eq!(backend.combine(&shares).unwrap().as_slice(), secret);
+
+            // Fewer don't
+            let too_few = backend.combine(&shares[..2]);
+            assert!(too_few.is_err() || too_few.unwrap().as_slice() != secret);
+        }
+
+        assert!(backend.split(&SECRET_128, 1, 3).is_err());
+        assert!(backend.split(&SECRET_128, 4, 3).is_err());
+        assert!(backend.combine(&["not a share".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_codex32_satisfies_contract() {
+        assert_contract(&Codex32Sharing::new("test"));
+    }
+
+    #[test]
+    fn test_slip39_satisfies_contract() {
+        assert_contract(&Slip39Sharing::default());
+    }
+
+    #[test]
+    fn test_scheme_dispatch() {
+        for scheme in [Scheme::Codex32, Scheme::Slip39] {
+            assert_eq!(scheme.to_string().parse::<Scheme>().unwrap(), scheme);
+
+            let shares = scheme.backend("test").split(&SECRET_128, 2, 3).unwrap();
+            assert!(shares.iter().all(|s| Scheme::detect(s) == Some(scheme)));
+            let recovered = Scheme::combine_detected(&shares[1..]).unwrap();
+            assert_eq!(recovered.as_slice(), SECRET_128);
+        }
+        assert_eq!("SLIP-39".parse::<Scheme>().unwrap(), Scheme::Slip39);
+        assert!("shamir".parse::<Scheme>().is_err());
+
+        // Mixed schemes are refused rather than half-combined
+        let codex32 = Scheme::Codex32
+            .backend("test")
+            .split(&SECRET_128, 2, 3)
+            .unwrap();
+        let slip39 = Scheme::Slip39.backend("").split(&SECRET_128, 2, 3).unwrap();
+        let mixed = vec![codex32[0].clone(), slip39[0].clone()];
+        assert!(Scheme::combine_detected(&mixed).is_err());
+        assert!(Scheme::combine_detected(&[]).is_err());
+    }
+}
@@ -1,6 +1,10 @@
 //! SLIP-39 Official 1024-word wordlist
 //!
 //! Source: https://github.com/satoshilabs/slips/blob/master/slip-0039/wordlist.txt
+//!
+//! SLIP-39 defines this single English list. Codex32 shares carry no words,
+//! so recovered BIP-39 entropy can be rendered in any BIP-39 language with
+//! [`crate::codex32::combine_shares_to_mnemonic`].
 
 /// The official SLIP-39 wordlist (1024 words, 10 bits per word)
 pub const SLIP39_WORDLIST: [&str; 1024] = [
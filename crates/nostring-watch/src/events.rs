@@ -18,6 +18,21 @@ pub enum WatchEvent {
         height: u32,
     },
 
+    /// A new UTXO was seen but doesn't have enough confirmations to count
+    /// as funding yet (reported every poll until it does)
+    UtxoPendingFunding {
+        /// Policy identifier
+        policy_id: String,
+        /// The pending UTXO
+        outpoint: OutPoint,
+        /// Value in satoshis
+        value: Amount,
+        /// Current confirmations (0 if mempool)
+        confirmations: u32,
+        /// Confirmations needed before `UtxoAppeared` fires
+        required: u32,
+    },
+
     /// A watched UTXO was spent
     UtxoSpent {
         /// Policy identifier
@@ -29,6 +44,9 @@ pub enum WatchEvent {
         /// Whether this appears to be an owner check-in or heir claim
         /// (heuristic based on output analysis)
         spend_type: SpendType,
+        /// Confidence in `spend_type` (0.0 - 1.0)
+        #[serde(default)]
+        confidence: f64,
     },
 
     /// Timelock is approaching expiry
@@ -65,6 +83,7 @@ impl WatchEvent {
     pub fn policy_id(&self) -> Option<&str> {
         match self {
             WatchEvent::UtxoAppeared { policy_id, .. } => Some(policy_id),
+            WatchEvent::UtxoPendingFunding { policy_id, .. } => Some(policy_id),
             WatchEvent::UtxoSpent { policy_id, .. } => Some(policy_id),
             WatchEvent::TimelockWarning { policy_id, .. } => Some(policy_id),
             WatchEvent::PollError { .. } => None,
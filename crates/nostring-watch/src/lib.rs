@@ -7,7 +7,8 @@
 //! - Periodic polling of inheritance addresses via Electrum
 //! - Detects UTXO appearances (new funding) and spends (check-in or claim)
 //! - Persistent state tracking across restarts
-//! - Event-based notifications for UI integration
+//! - Event-based notifications for UI integration, pulled via `poll` or
+//!   pushed via `run_with_callback` / `event_stream`
 //!
 //! # Example
 //!
@@ -21,6 +22,7 @@
 //!     state_path: PathBuf::from("~/.nostring/watch_state.json"),
 //!     poll_interval_secs: 600, // 10 minutes
 //!     min_poll_interval_secs: 60, // 1 minute minimum
+//!     ..Default::default()
 //! };
 //!
 //! let mut service = WatchService::new(client, config)?;
@@ -36,19 +38,28 @@
 pub mod events;
 pub mod spend_analysis;
 pub mod state;
+pub mod store;
 
 pub use events::{SpendType, WatchEvent};
-pub use spend_analysis::{analyze_spend, analyze_witness, DetectionMethod, SpendAnalysis};
+pub use spend_analysis::{
+    analyze_spend, analyze_transaction_inputs, analyze_witness, DetectionMethod,
+    InputSpendAnalysis, SpendAnalysis,
+};
 pub use state::{PolicyState, TrackedUtxo, WatchState};
+pub use store::{JsonFileStore, SqliteStore, WatchStateStore};
 
 use bitcoin::hashes::Hash;
-use bitcoin::{Network, OutPoint, ScriptBuf, Txid};
+use bitcoin::{Network, OutPoint, ScriptBuf, Transaction, Txid};
 use miniscript::descriptor::DescriptorPublicKey;
 use miniscript::Descriptor;
-use nostring_electrum::{ElectrumClient, Utxo};
+use nostring_core::clock::{Clock, SystemClock};
+use nostring_electrum::{ElectrumClient, ScriptHistoryItem, Utxo};
+use nostring_inherit::policy::{descriptor_timelocks, DescriptorKind, TimelockUnit};
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 /// Errors from the watch service
@@ -60,7 +71,8 @@ pub enum WatchError {
     #[error("State error: {0}")]
     State(#[from] state::StateError),
 
-    #[error("Invalid descriptor: {0}")]
+    /// Parser messages may echo keys back, so they are redacted on display
+    #[error("Invalid descriptor: {}", nostring_core::redact::redact_descriptor(.0))]
     InvalidDescriptor(String),
 
     #[error("Policy not found: {0}")]
@@ -68,6 +80,23 @@ pub enum WatchError {
 
     #[error("Poll interval too short (minimum {min} seconds)")]
     PollTooFrequent { min: u64 },
+
+    #[error("Electrum server timed out; backing off for {retry_after_secs} seconds")]
+    Timeout { retry_after_secs: u64 },
+}
+
+/// First backoff delay after an Electrum timeout
+const TIMEOUT_BACKOFF_BASE_SECS: u64 = 30;
+
+/// Upper bound on the timeout backoff delay
+const TIMEOUT_BACKOFF_MAX_SECS: u64 = 3600;
+
+/// Backoff delay after `consecutive` timeouts in a row (30s, 60s, 120s, ... capped at 1h)
+fn timeout_backoff_secs(consecutive: u32) -> u64 {
+    let exp = consecutive.saturating_sub(1).min(16);
+    TIMEOUT_BACKOFF_BASE_SECS
+        .saturating_mul(1 << exp)
+        .min(TIMEOUT_BACKOFF_MAX_SECS)
 }
 
 /// Configuration for the watch service
@@ -81,8 +110,19 @@ pub struct WatchConfig {
     pub min_poll_interval_secs: u64,
     /// Warning threshold in blocks (emit TimelockWarning when below)
     pub warning_threshold_blocks: i64,
+    /// Confirmations a new UTXO needs before it counts as funding the
+    /// policy (0 accepts mempool deposits)
+    pub min_confirmations: u32,
+    /// Unused receive indices scanned past each policy's current address,
+    /// so check-ins that rotate to the next index are followed (0 watches
+    /// the current address only)
+    pub rotation_gap_limit: u32,
 }
 
+/// Default [`WatchConfig::rotation_gap_limit`]: rotation moves one index
+/// per check-in, so a few spare indices cover check-ins the watch missed
+pub const DEFAULT_ROTATION_GAP_LIMIT: u32 = 5;
+
 impl Default for WatchConfig {
     fn default() -> Self {
         Self {
@@ -90,38 +130,106 @@ impl Default for WatchConfig {
             poll_interval_secs: 600,        // 10 minutes
             min_poll_interval_secs: 60,     // 1 minute minimum
             warning_threshold_blocks: 4320, // ~30 days
+            min_confirmations: 1,
+            rotation_gap_limit: DEFAULT_ROTATION_GAP_LIMIT,
         }
     }
 }
 
 /// UTXO monitoring service
-pub struct WatchService {
+///
+/// Generic over where its state is persisted; [`WatchService::new`] uses a
+/// [`JsonFileStore`] at `config.state_path`.
+pub struct WatchService<S: WatchStateStore = JsonFileStore> {
     client: ElectrumClient,
     config: WatchConfig,
+    store: S,
     state: WatchState,
     _network: Network,
+    /// Timeouts seen in a row, reset by a successful poll
+    consecutive_timeouts: u32,
+    /// Skip polling until this unix timestamp after a timeout
+    backoff_until: Option<u64>,
+    /// Stops [`WatchService::run_with_callback`] loops
+    shutdown: ShutdownHandle,
+    /// Time source for rate limiting, backoff and `first_seen` stamps
+    clock: Arc<dyn Clock>,
 }
 
-impl WatchService {
-    /// Create a new watch service
+/// Cloneable handle that stops a running watch loop
+///
+/// The loop finishes its current poll, then returns within a fraction of a
+/// second instead of sleeping out the rest of the interval.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle(Arc<AtomicBool>);
+
+impl ShutdownHandle {
+    /// Ask the loop to stop
+    pub fn shutdown(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether a shutdown was requested
+    pub fn is_shutdown(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// How often a sleeping watch loop checks for shutdown
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+impl WatchService<JsonFileStore> {
+    /// Create a new watch service persisting to a JSON file at `config.state_path`
     pub fn new(client: ElectrumClient, config: WatchConfig) -> Result<Self, WatchError> {
+        let store = JsonFileStore::new(config.state_path.clone());
+        Self::with_store(client, config, store)
+    }
+}
+
+impl<S: WatchStateStore> WatchService<S> {
+    /// Create a new watch service persisting through `store`
+    ///
+    /// `config.state_path` is ignored; the store decides where state lives.
+    pub fn with_store(
+        client: ElectrumClient,
+        config: WatchConfig,
+        store: S,
+    ) -> Result<Self, WatchError> {
         let network = client.network();
-        let state = WatchState::load(&config.state_path).unwrap_or_default();
+        let state = store.load().unwrap_or_default();
 
         Ok(Self {
             client,
             config,
+            store,
             state,
             _network: network,
+            consecutive_timeouts: 0,
+            backoff_until: None,
+            shutdown: ShutdownHandle::default(),
+            clock: Arc::new(SystemClock),
         })
     }
 
+    /// Use `clock` instead of the system clock
+    ///
+    /// Lets tests step past the poll rate limit or a timeout backoff
+    /// without sleeping.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
     /// Add a policy to watch
     ///
     /// # Arguments
     /// * `id` - Unique identifier for this policy
-    /// * `descriptor` - WSH descriptor string
+    /// * `descriptor` - `wsh()` or `tr()` descriptor string
     /// * `timelock_blocks` - Timelock duration in blocks
+    ///
+    /// The timelock unit is detected from the descriptor's `older()`. For a
+    /// time-based (512-second) timelock, the descriptor's earliest one is
+    /// watched and `timelock_blocks` is ignored.
     pub fn add_policy(
         &mut self,
         id: impl Into<String>,
@@ -129,13 +237,8 @@ impl WatchService {
         timelock_blocks: u32,
     ) -> Result<(), WatchError> {
         let id = id.into();
-        let descriptor = descriptor.into();
-
-        // Validate descriptor parses
-        let _: Descriptor<DescriptorPublicKey> = Descriptor::from_str(&descriptor)
-            .map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
-
-        let policy = PolicyState::new(&id, &descriptor, timelock_blocks);
+        let descriptor: String = descriptor.into();
+        let policy = new_policy_state(&id, &descriptor, timelock_blocks)?;
         self.state.add_policy(policy);
         self.save_state()?;
 
@@ -167,8 +270,22 @@ impl WatchService {
     ///
     /// This is the main entry point for checking UTXO state changes.
     pub fn poll(&mut self) -> Result<Vec<WatchEvent>, WatchError> {
+        self.poll_with(1)
+    }
+
+    /// Poll all watched policies using up to `max_parallel` worker threads
+    ///
+    /// Workers share the Electrum connection and the state (behind a
+    /// `Mutex`). Events are merged in the same policy order `poll` would
+    /// use, each policy's events in their original order, regardless of
+    /// which policy finishes first.
+    pub fn poll_concurrent(&mut self, max_parallel: usize) -> Result<Vec<WatchEvent>, WatchError> {
+        self.poll_with(max_parallel.max(1))
+    }
+
+    fn poll_with(&mut self, max_parallel: usize) -> Result<Vec<WatchEvent>, WatchError> {
         // Rate limiting
-        let now = current_timestamp();
+        let now = self.clock.now_secs();
         if let Some(last) = self.state.last_poll {
             let elapsed = now.saturating_sub(last);
             if elapsed < self.config.min_poll_interval_secs {
@@ -178,11 +295,21 @@ impl WatchService {
             }
         }
 
+        // A slow or unresponsive server gets backed off rather than hammered
+        if let Some(until) = self.backoff_until {
+            if now < until {
+                return Err(WatchError::Timeout {
+                    retry_after_secs: until - now,
+                });
+            }
+        }
+
         let mut events = Vec::new();
 
         // Get current block height
         let current_height = match self.client.get_height() {
             Ok(h) => h,
+            Err(nostring_electrum::Error::Timeout(_)) => return Err(self.enter_backoff(now)),
             Err(e) => {
                 events.push(WatchEvent::PollError {
                     message: format!("Failed to get block height: {}", e),
@@ -193,9 +320,24 @@ impl WatchService {
 
         // Poll each policy
         let policy_ids: Vec<String> = self.state.policy_ids();
-        for policy_id in policy_ids {
-            match self.poll_policy(&policy_id, current_height) {
+        let state = Mutex::new(std::mem::take(&mut self.state));
+        let results = poll_policies(
+            &self.client,
+            &state,
+            &policy_ids,
+            max_parallel,
+            &self.config,
+            current_height,
+            now,
+        );
+        self.state = state.into_inner().unwrap_or_else(|e| e.into_inner());
+
+        for (policy_id, result) in policy_ids.iter().zip(results) {
+            match result {
                 Ok(mut policy_events) => events.append(&mut policy_events),
+                Err(WatchError::Electrum(nostring_electrum::Error::Timeout(_))) => {
+                    return Err(self.enter_backoff(now));
+                }
                 Err(e) => {
                     events.push(WatchEvent::PollError {
                         message: format!("Error polling {}: {}", policy_id, e),
@@ -204,6 +346,9 @@ impl WatchService {
             }
         }
 
+        self.consecutive_timeouts = 0;
+        self.backoff_until = None;
+
         // Update poll timestamp
         self.state.update_poll(now, current_height);
         self.save_state()?;
@@ -211,213 +356,589 @@ impl WatchService {
         Ok(events)
     }
 
-    /// Poll a single policy
-    fn poll_policy(
-        &mut self,
-        policy_id: &str,
-        current_height: u32,
-    ) -> Result<Vec<WatchEvent>, WatchError> {
-        let mut events = Vec::new();
+    /// Handle for stopping [`run_with_callback`](Self::run_with_callback)
+    /// or [`event_stream`](Self::event_stream) from another thread
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
 
-        // Get policy state — extract needed values upfront to avoid borrow issues
-        let (descriptor_str, known_outpoints, utxo_heights, timelock_blocks) = {
-            let policy = self
-                .state
-                .get_policy(policy_id)
-                .ok_or_else(|| WatchError::PolicyNotFound(policy_id.to_string()))?;
-
-            let descriptor_str = policy.descriptor.clone();
-            let known_outpoints = policy.outpoints();
-            // Pre-compute utxo heights for timing analysis
-            let utxo_heights: Vec<(OutPoint, u32)> = policy
-                .utxos
-                .iter()
-                .map(|u| (u.outpoint, u.height))
-                .collect();
-            let timelock_blocks = policy.timelock_blocks;
-
-            (
-                descriptor_str,
-                known_outpoints,
-                utxo_heights,
-                timelock_blocks,
-            )
-        };
+    /// Poll forever on the configured interval, pushing each event to
+    /// `on_event` as soon as its poll completes.
+    ///
+    /// Rate limits and timeout backoff are respected: the loop waits them
+    /// out rather than surfacing them. Other errors are reported to the
+    /// callback as [`WatchEvent::PollError`]. Returns once the
+    /// [`shutdown_handle`](Self::shutdown_handle) is triggered.
+    pub fn run_with_callback(&mut self, mut on_event: impl FnMut(&WatchEvent)) {
+        let interval = Duration::from_secs(self.config.poll_interval_secs);
+        while !self.shutdown.is_shutdown() {
+            let result = self.poll();
+            let wait = dispatch_poll_result(result, interval, &mut on_event);
+            sleep_unless_shutdown(wait, &self.shutdown);
+        }
+        log::info!("Watch loop stopped");
+    }
 
-        // Parse descriptor and get script
-        let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(&descriptor_str)
-            .map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
+    /// Run the watch loop on a background thread, delivering events over a
+    /// channel holding up to `buffer` events.
+    ///
+    /// The loop stops when the receiver is dropped or the
+    /// [`shutdown_handle`](Self::shutdown_handle) is triggered.
+    pub fn event_stream(mut self, buffer: usize) -> tokio::sync::mpsc::Receiver<WatchEvent>
+    where
+        S: Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(buffer.max(1));
+        let shutdown = self.shutdown.clone();
+        std::thread::spawn(move || {
+            self.run_with_callback(|event| {
+                if tx.blocking_send(event.clone()).is_err() {
+                    // Receiver gone — nobody is listening any more
+                    shutdown.shutdown();
+                }
+            });
+        });
+        rx
+    }
 
-        // Derive address at index 0
-        let script = derive_script(&descriptor, 0)?;
+    /// Record a timeout and schedule the next allowed poll
+    fn enter_backoff(&mut self, now: u64) -> WatchError {
+        self.consecutive_timeouts = self.consecutive_timeouts.saturating_add(1);
+        let retry_after_secs = timeout_backoff_secs(self.consecutive_timeouts);
+        self.backoff_until = Some(now + retry_after_secs);
+        log::warn!(
+            "Electrum timeout ({} in a row); backing off {}s",
+            self.consecutive_timeouts,
+            retry_after_secs
+        );
+        WatchError::Timeout { retry_after_secs }
+    }
 
-        // Get current UTXOs from blockchain
-        let current_utxos: Vec<Utxo> = self.client.get_utxos_for_script(&script)?;
+    /// Save state through the configured store
+    fn save_state(&self) -> Result<(), WatchError> {
+        self.store.save(&self.state)?;
+        Ok(())
+    }
 
-        // Detect new UTXOs (appeared)
-        let now = current_timestamp();
-        for utxo in &current_utxos {
-            if !known_outpoints.contains(&utxo.outpoint) {
-                events.push(WatchEvent::UtxoAppeared {
-                    policy_id: policy_id.to_string(),
-                    outpoint: utxo.outpoint,
-                    value: utxo.value,
-                    height: utxo.height,
-                });
+    /// Force a state save (for testing)
+    pub fn flush(&self) -> Result<(), WatchError> {
+        self.save_state()
+    }
 
-                // Add to state
-                if let Some(policy_mut) = self.state.get_policy_mut(policy_id) {
-                    policy_mut.add_utxo(TrackedUtxo {
-                        outpoint: utxo.outpoint,
-                        value: utxo.value,
-                        height: utxo.height,
-                        first_seen: now,
-                    });
-                }
-            }
+    /// Get the current state (for inspection)
+    pub fn state(&self) -> &WatchState {
+        &self.state
+    }
+}
+
+/// Validate a descriptor for watching and build its initial state.
+///
+/// The descriptor must parse and be a kind we can watch. Its earliest
+/// `older()` decides the timelock unit.
+fn new_policy_state(
+    id: &str,
+    descriptor: &str,
+    timelock_blocks: u32,
+) -> Result<PolicyState, WatchError> {
+    let parsed: Descriptor<DescriptorPublicKey> = Descriptor::from_str(descriptor)
+        .map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
+    DescriptorKind::of(&parsed).map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
+    let timelocks =
+        descriptor_timelocks(&parsed).map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
+
+    let policy = match timelocks.first() {
+        Some(earliest) if earliest.unit() == TimelockUnit::Time => {
+            PolicyState::new(id, descriptor, earliest.value() as u32)
+                .with_timelock_unit(TimelockUnit::Time)
+        }
+        _ => PolicyState::new(id, descriptor, timelock_blocks),
+    };
+    Ok(policy)
+}
+
+/// Poll every policy in `policy_ids` on at most `max_parallel` threads
+///
+/// Results come back in `policy_ids` order, whichever policy finishes
+/// first.
+fn poll_policies<C: PolicyLookup + Sync + ?Sized>(
+    client: &C,
+    state: &Mutex<WatchState>,
+    policy_ids: &[String],
+    max_parallel: usize,
+    config: &WatchConfig,
+    current_height: u32,
+    now: u64,
+) -> Vec<Result<Vec<WatchEvent>, WatchError>> {
+    run_bounded(policy_ids, max_parallel, |policy_id| {
+        poll_policy(
+            client,
+            state,
+            policy_id,
+            current_height,
+            config.warning_threshold_blocks,
+            config.min_confirmations,
+            config.rotation_gap_limit,
+            now,
+        )
+    })
+}
+
+/// Poll a single policy
+///
+/// Scans the addresses from [`PolicyState::watched_indices`], so funds a
+/// check-in rotated to a later receive index (up to `gap_limit` past the
+/// current one) are picked up. The state lock is held only while reading
+/// the policy and applying the changes, never across Electrum requests, so
+/// other policies can be polled concurrently.
+#[allow(clippy::too_many_arguments)]
+fn poll_policy<C: PolicyLookup + ?Sized>(
+    client: &C,
+    state: &Mutex<WatchState>,
+    policy_id: &str,
+    current_height: u32,
+    warning_threshold_blocks: i64,
+    min_confirmations: u32,
+    gap_limit: u32,
+    now: u64,
+) -> Result<Vec<WatchEvent>, WatchError> {
+    let mut events = Vec::new();
+
+    // Get policy state — extract needed values upfront to avoid borrow issues
+    let (
+        descriptor_str,
+        known_outpoints,
+        utxo_heights,
+        utxo_indices,
+        watched_indices,
+        timelock_blocks,
+        unit,
+        funding_time_known,
+    ) = {
+        let state = lock_state(state);
+        let policy = state
+            .get_policy(policy_id)
+            .ok_or_else(|| WatchError::PolicyNotFound(policy_id.to_string()))?;
+
+        let descriptor_str = policy.descriptor.clone();
+        let known_outpoints = policy.outpoints();
+        // Pre-compute utxo heights for timing analysis
+        let utxo_heights: Vec<(OutPoint, u32)> = policy
+            .utxos
+            .iter()
+            .map(|u| (u.outpoint, u.height))
+            .collect();
+        let utxo_indices: Vec<(OutPoint, u32)> = policy
+            .utxos
+            .iter()
+            .map(|u| (u.outpoint, u.derivation_index))
+            .collect();
+        let timelock_blocks = policy.timelock_blocks;
+
+        (
+            descriptor_str,
+            known_outpoints,
+            utxo_heights,
+            utxo_indices,
+            policy.watched_indices(gap_limit),
+            timelock_blocks,
+            policy.timelock_unit,
+            policy.funding_time.is_some(),
+        )
+    };
+
+    // Parse descriptor and derive every watched address
+    let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(&descriptor_str)
+        .map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
+    let scripts = watched_indices
+        .iter()
+        .map(|&index| Ok((index, derive_script(&descriptor, index)?)))
+        .collect::<Result<Vec<(u32, ScriptBuf)>, WatchError>>()?;
+
+    // Get current UTXOs from blockchain, remembering which index holds each
+    let mut current_utxos: Vec<Utxo> = Vec::new();
+    let mut current_indices: Vec<(OutPoint, u32)> = Vec::new();
+    for (index, script) in &scripts {
+        for utxo in client.utxos_for_script(script)? {
+            current_indices.push((utxo.outpoint, *index));
+            current_utxos.push(utxo);
+        }
+    }
+
+    // Find how each vanished UTXO was spent before taking the lock
+    let current_outpoints: Vec<OutPoint> = current_utxos.iter().map(|u| u.outpoint).collect();
+    let vanished: Vec<OutPoint> = known_outpoints
+        .iter()
+        .filter(|known| !current_outpoints.contains(known))
+        .copied()
+        .collect();
+    // Block timing only tells owner from heir for block-based timelocks
+    let timing_timelock = (unit == TimelockUnit::Blocks).then_some(timelock_blocks);
+    // Spends are found through the history of the address that held them
+    let mut spends = Vec::with_capacity(vanished.len());
+    for (index, script) in &scripts {
+        let at_index: Vec<OutPoint> = vanished
+            .iter()
+            .filter(|op| index_of(&utxo_indices, op) == Some(*index))
+            .copied()
+            .collect();
+        if !at_index.is_empty() {
+            spends.extend(detect_spends(
+                client,
+                &at_index,
+                script,
+                &utxo_heights,
+                timing_timelock,
+            ));
+        }
+    }
+    spends.sort_by_key(|(op, ..)| vanished.iter().position(|v| v == op));
+
+    // Time-based timelocks run from the funding block's timestamp
+    let funding_block_time = if unit == TimelockUnit::Time && !funding_time_known {
+        current_utxos
+            .iter()
+            .map(|u| u.height)
+            .filter(|h| *h > 0)
+            .min()
+            .and_then(|h| client.block_time(h).ok().map(|t| (h, t as u64)))
+    } else {
+        None
+    };
+
+    let mut state = lock_state(state);
+
+    // Detect new UTXOs (appeared once deep enough, pending until then)
+    let (mut funding_events, funded) = new_utxo_events(
+        policy_id,
+        &current_utxos,
+        &known_outpoints,
+        current_height,
+        min_confirmations,
+    );
+    events.append(&mut funding_events);
+    if let Some(policy_mut) = state.get_policy_mut(policy_id) {
+        let previous_index = policy_mut.address_index;
+        for utxo in funded {
+            policy_mut.add_utxo(TrackedUtxo {
+                outpoint: utxo.outpoint,
+                value: utxo.value,
+                height: utxo.height,
+                first_seen: now,
+                derivation_index: index_of(&current_indices, &utxo.outpoint).unwrap_or(0),
+            });
+        }
+        if policy_mut.address_index != previous_index {
+            log::info!(
+                "Policy {} rotated to receive index {}",
+                policy_id,
+                policy_mut.address_index
+            );
         }
+    }
 
-        // Detect spent UTXOs
-        let current_outpoints: Vec<OutPoint> = current_utxos.iter().map(|u| u.outpoint).collect();
-        for known in &known_outpoints {
-            if !current_outpoints.contains(known) {
-                // Get UTXO height for timing analysis
-                let utxo_height = utxo_heights
-                    .iter()
-                    .find(|(op, _)| op == known)
-                    .map(|(_, h)| *h)
-                    .unwrap_or(0);
+    // Record spent UTXOs
+    for (outpoint, spend_type, confidence, spending_txid) in spends {
+        events.push(WatchEvent::UtxoSpent {
+            policy_id: policy_id.to_string(),
+            outpoint,
+            spending_txid,
+            spend_type,
+            confidence,
+        });
+
+        // Remove from state
+        if let Some(policy_mut) = state.get_policy_mut(policy_id) {
+            policy_mut.remove_utxo(&outpoint);
+        }
+    }
 
-                // UTXO was spent - determine how via witness + timing analysis
-                let (spend_type, spending_txid) =
-                    self.detect_spend_type_for_utxo(known, &script, utxo_height, timelock_blocks);
+    if let (Some(policy_mut), Some((height, time))) =
+        (state.get_policy_mut(policy_id), funding_block_time)
+    {
+        if policy_mut.funding_height == Some(height) {
+            policy_mut.funding_time = Some(time);
+        }
+    }
 
-                events.push(WatchEvent::UtxoSpent {
+    // Check timelock warning
+    if let Some(policy) = state.get_policy(policy_id) {
+        let remaining = match policy.timelock_unit {
+            TimelockUnit::Blocks => policy
+                .blocks_until_expiry(current_height)
+                .map(|blocks| (blocks, blocks as f64 * 10.0 / 60.0 / 24.0)),
+            // Warned against the same block threshold, at ~10 min per block
+            TimelockUnit::Time => policy
+                .seconds_until_expiry(now)
+                .map(|secs| (secs / 600, secs as f64 / 86_400.0)),
+        };
+        if let Some((blocks_remaining, days_remaining)) = remaining {
+            if blocks_remaining <= warning_threshold_blocks && blocks_remaining > 0 {
+                events.push(WatchEvent::TimelockWarning {
                     policy_id: policy_id.to_string(),
-                    outpoint: *known,
-                    spending_txid,
-                    spend_type,
+                    blocks_remaining,
+                    days_remaining,
                 });
-
-                // Remove from state
-                if let Some(policy_mut) = self.state.get_policy_mut(policy_id) {
-                    policy_mut.remove_utxo(known);
-                }
             }
         }
+    }
 
-        // Check timelock warning
-        if let Some(policy) = self.state.get_policy(policy_id) {
-            if let Some(blocks_remaining) = policy.blocks_until_expiry(current_height) {
-                if blocks_remaining <= self.config.warning_threshold_blocks && blocks_remaining > 0
-                {
-                    let days_remaining = blocks_remaining as f64 * 10.0 / 60.0 / 24.0;
-                    events.push(WatchEvent::TimelockWarning {
-                        policy_id: policy_id.to_string(),
-                        blocks_remaining,
-                        days_remaining,
-                    });
-                }
-            }
+    Ok(events)
+}
+
+/// Receive index recorded for `outpoint` in `indices`
+fn index_of(indices: &[(OutPoint, u32)], outpoint: &OutPoint) -> Option<u32> {
+    indices
+        .iter()
+        .find(|(op, _)| op == outpoint)
+        .map(|(_, index)| *index)
+}
+
+/// Confirmations of a UTXO at `height` (0 = mempool) with the tip at `current_height`
+fn confirmations(height: u32, current_height: u32) -> u32 {
+    if height == 0 {
+        0
+    } else {
+        current_height.saturating_sub(height) + 1
+    }
+}
+
+/// Events for UTXOs not yet tracked, plus the ones that now count as funding.
+///
+/// Depth is recomputed on every poll, so a deposit reports
+/// [`WatchEvent::UtxoPendingFunding`] each poll until it reaches
+/// `min_confirmations`, then [`WatchEvent::UtxoAppeared`] exactly once (it
+/// is tracked from then on). A pending deposit that gets double-spent
+/// simply disappears without ever being tracked.
+fn new_utxo_events<'a>(
+    policy_id: &str,
+    current_utxos: &'a [Utxo],
+    known_outpoints: &[OutPoint],
+    current_height: u32,
+    min_confirmations: u32,
+) -> (Vec<WatchEvent>, Vec<&'a Utxo>) {
+    let mut events = Vec::new();
+    let mut funded = Vec::new();
+
+    for utxo in current_utxos {
+        if known_outpoints.contains(&utxo.outpoint) {
+            continue;
         }
 
-        Ok(events)
+        let depth = confirmations(utxo.height, current_height);
+        if depth >= min_confirmations {
+            events.push(WatchEvent::UtxoAppeared {
+                policy_id: policy_id.to_string(),
+                outpoint: utxo.outpoint,
+                value: utxo.value,
+                height: utxo.height,
+            });
+            funded.push(utxo);
+        } else {
+            events.push(WatchEvent::UtxoPendingFunding {
+                policy_id: policy_id.to_string(),
+                outpoint: utxo.outpoint,
+                value: utxo.value,
+                confirmations: depth,
+                required: min_confirmations,
+            });
+        }
     }
 
-    /// Detect how a UTXO was spent by analyzing the spending transaction's witness.
-    ///
-    /// Fetches the script history to find the spending transaction, then
-    /// analyzes the witness data to determine owner vs heir path.
-    fn detect_spend_type_for_utxo(
-        &self,
-        outpoint: &OutPoint,
-        script: &ScriptBuf,
-        utxo_height: u32,
-        timelock_blocks: u32,
-    ) -> (SpendType, Txid) {
-        // Find the spending transaction by looking at script history
-        match self.find_spending_tx(outpoint, script) {
-            Some((spending_tx, spend_height)) => {
-                // Analyze the witness of the input that spent our UTXO
-                if let Some(analysis) = spend_analysis::analyze_transaction_for_outpoint(
-                    &spending_tx,
-                    &outpoint.txid,
-                    outpoint.vout,
-                ) {
-                    // If witness analysis is inconclusive, try timing
-                    if analysis.spend_type == SpendType::Unknown
-                        && spend_height > 0
-                        && utxo_height > 0
-                    {
-                        if let Some(timing_type) = spend_analysis::analyze_timing(
-                            spend_height,
-                            utxo_height,
-                            timelock_blocks,
-                        ) {
-                            return (timing_type, spending_tx.compute_txid());
-                        }
-                    }
-                    (analysis.spend_type, spending_tx.compute_txid())
-                } else {
-                    (SpendType::Unknown, spending_tx.compute_txid())
+    (events, funded)
+}
+
+/// Detect how each of the `spent` UTXOs was spent, as `(outpoint,
+/// spend_type, confidence, spending_txid)` in the order of `spent`.
+///
+/// Finds each spending transaction via script history and analyzes the
+/// witness of every input that spends one of `spent`, so a transaction
+/// sweeping several tracked UTXOs (e.g. a consolidating heir claim) is
+/// fetched once and each UTXO gets the spend type of its own input.
+/// `utxo_heights` feed the timing fallback for inconclusive witnesses,
+/// used only with a block-based `timelock_blocks`.
+fn detect_spends<C: SpendLookup + ?Sized>(
+    client: &C,
+    spent: &[OutPoint],
+    script: &ScriptBuf,
+    utxo_heights: &[(OutPoint, u32)],
+    timelock_blocks: Option<u32>,
+) -> Vec<(OutPoint, SpendType, f64, Txid)> {
+    let utxo_height = |outpoint: &OutPoint| {
+        utxo_heights
+            .iter()
+            .find(|(op, _)| op == outpoint)
+            .map(|(_, h)| *h)
+            .unwrap_or(0)
+    };
+
+    let mut spends: Vec<(OutPoint, SpendType, f64, Txid)> = Vec::with_capacity(spent.len());
+    for outpoint in spent {
+        if spends.iter().any(|(op, ..)| op == outpoint) {
+            // Already analyzed as another input of an earlier spending tx
+            continue;
+        }
+        let Some((spending_tx, spend_height)) = find_spending_tx(client, outpoint, script) else {
+            spends.push((*outpoint, SpendType::Unknown, 0.0, Txid::all_zeros()));
+            continue;
+        };
+        let spending_txid = spending_tx.compute_txid();
+
+        for input in spend_analysis::analyze_transaction_inputs(&spending_tx, spent) {
+            let mut spend_type = input.analysis.spend_type;
+            let mut confidence = input.analysis.confidence;
+
+            // If witness analysis is inconclusive, try timing
+            let utxo_height = utxo_height(&input.outpoint);
+            if spend_type == SpendType::Unknown && spend_height > 0 && utxo_height > 0 {
+                if let Some(timing_type) = timelock_blocks.and_then(|timelock| {
+                    spend_analysis::analyze_timing(spend_height, utxo_height, timelock)
+                }) {
+                    // Timing before expiry is definitive
+                    spend_type = timing_type;
+                    confidence = spend_analysis::TIMING_CONFIDENCE;
                 }
             }
-            None => (SpendType::Unknown, Txid::all_zeros()),
+            spends.push((input.outpoint, spend_type, confidence, spending_txid));
         }
     }
 
-    /// Find the transaction that spent a given outpoint by scanning script history.
-    fn find_spending_tx(
+    spends.sort_by_key(|(op, ..)| spent.iter().position(|s| s == op));
+    spends
+}
+
+/// Chain lookups needed to find a spending transaction
+///
+/// Implemented by [`ElectrumClient`]; tests substitute a mock.
+trait SpendLookup {
+    fn script_history(
         &self,
-        outpoint: &OutPoint,
         script: &ScriptBuf,
-    ) -> Option<(bitcoin::Transaction, u32)> {
-        // Get all transactions for this script
-        let history = self.client.get_script_history(script).ok()?;
-
-        for hist_item in &history {
-            // Skip the funding transaction itself
-            if hist_item.txid == outpoint.txid {
-                continue;
-            }
+    ) -> Result<Vec<ScriptHistoryItem>, nostring_electrum::Error>;
+    fn transactions(
+        &self,
+        txids: &[Txid],
+    ) -> Result<Vec<(Txid, Transaction)>, nostring_electrum::Error>;
+}
 
-            // Fetch the full transaction
-            if let Ok(tx) = self.client.get_transaction(&hist_item.txid) {
-                // Check if any input spends our outpoint
-                for input in &tx.input {
-                    if input.previous_output == *outpoint {
-                        return Some((tx, hist_item.height));
-                    }
-                }
-            }
-        }
-        None
+impl SpendLookup for ElectrumClient {
+    fn script_history(
+        &self,
+        script: &ScriptBuf,
+    ) -> Result<Vec<ScriptHistoryItem>, nostring_electrum::Error> {
+        // Polled every cycle; stable addresses are served from the cache
+        self.get_script_history_cached(script)
     }
 
-    /// Save state to disk
-    fn save_state(&self) -> Result<(), WatchError> {
-        self.state.save(&self.config.state_path)?;
-        Ok(())
+    fn transactions(
+        &self,
+        txids: &[Txid],
+    ) -> Result<Vec<(Txid, Transaction)>, nostring_electrum::Error> {
+        self.get_transactions(txids)
     }
+}
 
-    /// Force a state save (for testing)
-    pub fn flush(&self) -> Result<(), WatchError> {
-        self.save_state()
+/// Chain lookups a policy poll needs on top of [`SpendLookup`]
+///
+/// Implemented by [`ElectrumClient`]; tests substitute a mock.
+trait PolicyLookup: SpendLookup {
+    fn utxos_for_script(&self, script: &ScriptBuf) -> Result<Vec<Utxo>, nostring_electrum::Error>;
+    fn block_time(&self, height: u32) -> Result<u32, nostring_electrum::Error>;
+}
+
+impl PolicyLookup for ElectrumClient {
+    fn utxos_for_script(&self, script: &ScriptBuf) -> Result<Vec<Utxo>, nostring_electrum::Error> {
+        self.get_utxos_for_script(script)
     }
 
-    /// Get the current state (for inspection)
-    pub fn state(&self) -> &WatchState {
-        &self.state
+    fn block_time(&self, height: u32) -> Result<u32, nostring_electrum::Error> {
+        self.get_block_time(height)
     }
 }
 
+/// Find the transaction that spent a given outpoint by scanning script history.
+///
+/// Every candidate transaction is fetched in a single batch; ones the
+/// server can't return are skipped.
+fn find_spending_tx<C: SpendLookup + ?Sized>(
+    client: &C,
+    outpoint: &OutPoint,
+    script: &ScriptBuf,
+) -> Option<(Transaction, u32)> {
+    // Get all transactions for this script, skipping the funding transaction itself
+    let history = client.script_history(script).ok()?;
+    let candidates: Vec<&ScriptHistoryItem> =
+        history.iter().filter(|h| h.txid != outpoint.txid).collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let txids: Vec<Txid> = candidates.iter().map(|h| h.txid).collect();
+    let txs = client.transactions(&txids).ok()?;
+
+    // Check, in history order, whether any input spends our outpoint
+    candidates.iter().find_map(|hist_item| {
+        txs.iter()
+            .find(|(txid, _)| *txid == hist_item.txid)
+            .filter(|(_, tx)| tx.input.iter().any(|i| i.previous_output == *outpoint))
+            .map(|(_, tx)| (tx.clone(), hist_item.height))
+    })
+}
+
+/// Lock the shared state, recovering it if a worker panicked mid-poll
+fn lock_state(state: &Mutex<WatchState>) -> MutexGuard<'_, WatchState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Apply `f` to every item on at most `max_parallel` scoped threads.
+///
+/// Results come back in input order no matter which item finishes first.
+fn run_bounded<T, R, F>(items: &[T], max_parallel: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = max_parallel.clamp(1, items.len().max(1));
+    if workers == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|r| r.expect("every item is processed exactly once"))
+        .collect()
+}
+
 /// Derive a script from a descriptor at a given index
+///
+/// Yields a P2WSH script for `wsh()` and a P2TR script for `tr()`
+/// descriptors; any other descriptor type is rejected.
 fn derive_script(
     descriptor: &Descriptor<DescriptorPublicKey>,
     index: u32,
 ) -> Result<ScriptBuf, WatchError> {
     use miniscript::descriptor::DefiniteDescriptorKey;
 
+    DescriptorKind::of(descriptor).map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
+
     let derived: Descriptor<DefiniteDescriptorKey> = descriptor
         .at_derivation_index(index)
         .map_err(|e| WatchError::InvalidDescriptor(e.to_string()))?;
@@ -425,12 +946,44 @@ fn derive_script(
     Ok(derived.script_pubkey())
 }
 
-/// Get current unix timestamp
-fn current_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
+/// Deliver one poll's outcome to a watch-loop callback and decide how long
+/// to wait before the next poll.
+fn dispatch_poll_result(
+    result: Result<Vec<WatchEvent>, WatchError>,
+    interval: Duration,
+    on_event: &mut impl FnMut(&WatchEvent),
+) -> Duration {
+    match result {
+        Ok(events) => {
+            for event in &events {
+                on_event(event);
+            }
+            interval
+        }
+        // Polled too recently (e.g. by another caller); try again next interval
+        Err(WatchError::PollTooFrequent { .. }) => interval,
+        Err(WatchError::Timeout { retry_after_secs }) => {
+            interval.max(Duration::from_secs(retry_after_secs))
+        }
+        Err(e) => {
+            on_event(&WatchEvent::PollError {
+                message: e.to_string(),
+            });
+            interval
+        }
+    }
+}
+
+/// Sleep for `duration`, waking early if `shutdown` is triggered
+fn sleep_unless_shutdown(duration: Duration, shutdown: &ShutdownHandle) {
+    let deadline = Instant::now() + duration;
+    while !shutdown.is_shutdown() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        std::thread::sleep(remaining.min(SHUTDOWN_CHECK_INTERVAL));
+    }
 }
 
 #[cfg(test)]
@@ -444,6 +997,8 @@ mod tests {
             poll_interval_secs: 600,
             min_poll_interval_secs: 0, // Disable rate limiting for tests
             warning_threshold_blocks: 4320,
+            min_confirmations: 1,
+            rotation_gap_limit: DEFAULT_ROTATION_GAP_LIMIT,
         }
     }
 
@@ -453,11 +1008,144 @@ mod tests {
     #[test]
     fn test_config_defaults() {
         let config = WatchConfig::default();
+        assert_eq!(config.min_confirmations, 1);
         assert_eq!(config.poll_interval_secs, 600);
         assert_eq!(config.min_poll_interval_secs, 60);
         assert_eq!(config.warning_threshold_blocks, 4320);
     }
 
+    #[test]
+    fn test_run_bounded_preserves_order() {
+        // Several "policies", each emitting a sequence of events that must
+        // stay in order. The first three run at once and are forced to
+        // finish in reverse: each waits for the next one to be done.
+        let policy_ids: Vec<usize> = (0..6).collect();
+        let done: Vec<AtomicBool> = policy_ids.iter().map(|_| AtomicBool::new(false)).collect();
+        let finished = Mutex::new(Vec::new());
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let results = run_bounded(&policy_ids, 3, |&id| {
+            let n = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(n, Ordering::SeqCst);
+            if id < 2 {
+                let deadline = Instant::now() + Duration::from_secs(10);
+                while !done[id + 1].load(Ordering::SeqCst) {
+                    assert!(Instant::now() < deadline, "work should overlap");
+                    std::thread::yield_now();
+                }
+            }
+            finished.lock().unwrap().push(id);
+            active.fetch_sub(1, Ordering::SeqCst);
+            done[id].store(true, Ordering::SeqCst);
+            (0..3)
+                .map(|step| format!("policy-{}:{}", id, step))
+                .collect::<Vec<_>>()
+        });
+
+        let first_wave: Vec<usize> = finished
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .filter(|&id| id < 3)
+            .collect();
+        assert_eq!(first_wave, [2, 1, 0]);
+        // 0 waits on 1 while both are running; never more than 3 at once
+        assert!((2..=3).contains(&peak.load(Ordering::SeqCst)));
+        let merged: Vec<String> = results.into_iter().flatten().collect();
+        let expected: Vec<String> = policy_ids
+            .iter()
+            .flat_map(|id| (0..3).map(move |step| format!("policy-{}:{}", id, step)))
+            .collect();
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn test_run_bounded_edge_cases() {
+        let empty: Vec<u32> = Vec::new();
+        assert!(run_bounded(&empty, 4, |x| *x).is_empty());
+        assert_eq!(run_bounded(&[1, 2, 3], 0, |x| x * 2), vec![2, 4, 6]);
+        assert_eq!(run_bounded(&[1, 2, 3], 100, |x| x * 2), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn test_timeout_backoff_schedule() {
+        assert_eq!(timeout_backoff_secs(1), 30);
+        assert_eq!(timeout_backoff_secs(2), 60);
+        assert_eq!(timeout_backoff_secs(3), 120);
+        assert_eq!(timeout_backoff_secs(8), 3600);
+        assert_eq!(timeout_backoff_secs(u32::MAX), 3600);
+    }
+
+    #[test]
+    fn test_dispatch_fires_callback_per_event() {
+        let events = vec![
+            WatchEvent::PollError {
+                message: "first".into(),
+            },
+            WatchEvent::PollError {
+                message: "second".into(),
+            },
+            WatchEvent::PollError {
+                message: "third".into(),
+            },
+        ];
+        let interval = Duration::from_secs(600);
+
+        let mut seen = Vec::new();
+        let wait = dispatch_poll_result(Ok(events.clone()), interval, &mut |e: &WatchEvent| {
+            seen.push(e.clone())
+        });
+        assert_eq!(seen, events);
+        assert_eq!(wait, interval);
+
+        // Rate limiting is waited out silently
+        let mut count = 0;
+        let wait = dispatch_poll_result(
+            Err(WatchError::PollTooFrequent { min: 60 }),
+            interval,
+            &mut |_: &WatchEvent| count += 1,
+        );
+        assert_eq!((count, wait), (0, interval));
+
+        // A long backoff stretches the wait
+        let wait = dispatch_poll_result(
+            Err(WatchError::Timeout {
+                retry_after_secs: 3600,
+            }),
+            interval,
+            &mut |_: &WatchEvent| count += 1,
+        );
+        assert_eq!((count, wait), (0, Duration::from_secs(3600)));
+
+        // Anything else reaches the callback as a PollError
+        let wait = dispatch_poll_result(
+            Err(WatchError::PolicyNotFound("gone".into())),
+            interval,
+            &mut |e: &WatchEvent| {
+                assert!(matches!(e, WatchEvent::PollError { .. }));
+                count += 1
+            },
+        );
+        assert_eq!((count, wait), (1, interval));
+    }
+
+    #[test]
+    fn test_sleep_wakes_on_shutdown() {
+        let handle = ShutdownHandle::default();
+        let remote = handle.clone();
+        let waker = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            remote.shutdown();
+        });
+
+        let start = Instant::now();
+        sleep_unless_shutdown(Duration::from_secs(30), &handle);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(handle.is_shutdown());
+        waker.join().unwrap();
+    }
+
     #[test]
     fn test_derive_script() {
         // Test with a simple pk descriptor
@@ -468,6 +1156,113 @@ mod tests {
         assert!(!script.is_empty());
     }
 
+    #[test]
+    fn test_derive_script_taproot() {
+        let desc_str = "tr(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*)";
+        let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(desc_str).unwrap();
+
+        let script = derive_script(&descriptor, 0).unwrap();
+        assert!(script.is_p2tr());
+    }
+
+    #[test]
+    fn test_derive_script_rejects_unsupported() {
+        let desc_str = "pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*)";
+        let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(desc_str).unwrap();
+
+        assert!(matches!(
+            derive_script(&descriptor, 0),
+            Err(WatchError::InvalidDescriptor(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotated_checkins_stay_watched() {
+        use nostring_inherit::checkin::{CheckinDestination, CheckinTxBuilder, InheritanceUtxo};
+
+        let desc_str = "wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*))";
+        let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(desc_str).unwrap();
+        let mut policy = PolicyState::new("rotating", desc_str, 26280);
+        let mut utxo = TrackedUtxo {
+            outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            value: bitcoin::Amount::from_sat(100_000),
+            height: 900_000,
+            first_seen: 1_700_000_000,
+            derivation_index: 0,
+        };
+        policy.add_utxo(utxo.clone());
+
+        for round in 1..=3u32 {
+            let spent_script = derive_script(&descriptor, utxo.derivation_index).unwrap();
+            let checkin = CheckinTxBuilder::new(
+                InheritanceUtxo::new(utxo.outpoint, utxo.value, utxo.height, spent_script),
+                descriptor.clone(),
+                2,
+                utxo.derivation_index,
+            )
+            .with_destination(CheckinDestination::NextIndex);
+
+            // Each check-in moves the funds one index further...
+            let target = checkin.destination_script().unwrap();
+            assert_eq!(target, derive_script(&descriptor, round).unwrap());
+
+            // ...which the next poll still scans
+            let watched = policy.watched_indices(DEFAULT_ROTATION_GAP_LIMIT);
+            let found = watched
+                .iter()
+                .find(|&&i| derive_script(&descriptor, i).unwrap() == target);
+            assert_eq!(found, Some(&round));
+
+            // The poll tracks the new UTXO at its index and drops the spent one
+            let rotated = TrackedUtxo {
+                outpoint: OutPoint::new(Txid::from_byte_array([round as u8 + 1; 32]), 0),
+                height: utxo.height + 1_000,
+                derivation_index: round,
+                ..utxo.clone()
+            };
+            policy.add_utxo(rotated.clone());
+            policy.remove_utxo(&utxo.outpoint);
+            assert_eq!(policy.address_index, round);
+            utxo = rotated;
+        }
+
+        assert_eq!(policy.utxos.len(), 1);
+        assert_eq!(policy.watched_indices(0), vec![3]);
+    }
+
+    #[test]
+    fn test_new_policy_state_detects_timelock_unit() {
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let descriptor = |older: u32| {
+            format!(
+                "wsh(or_d(pk({xpub}/0/*),and_v(v:pk({xpub}/1/*),older({older}))))",
+                xpub = xpub,
+                older = older
+            )
+        };
+
+        let blocks = new_policy_state("blocks", &descriptor(26_280), 26_280).unwrap();
+        assert_eq!(blocks.timelock_unit, TimelockUnit::Blocks);
+        assert_eq!(blocks.timelock_blocks, 26_280);
+
+        // Type flag set: 100 × 512 s, whatever the caller passed
+        let time = new_policy_state("time", &descriptor((1 << 22) | 100), 26_280).unwrap();
+        assert_eq!(time.timelock_unit, TimelockUnit::Time);
+        assert_eq!(time.timelock_blocks, 100);
+        assert!(time.blocks_until_expiry(934_000).is_none());
+    }
+
+    #[test]
+    fn test_invalid_descriptor_error_is_redacted() {
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let err =
+            WatchError::InvalidDescriptor(format!("unexpected «frob» in wsh(frob({}/0/*))", xpub));
+        let message = err.to_string();
+
+        assert!(!message.contains(xpub), "xpub leaked: {}", message);
+        assert!(message.contains("xpub…/0/*"));
+    }
+
     #[test]
     fn test_watch_state_roundtrip() {
         let dir = tempdir().unwrap();
@@ -484,8 +1279,8 @@ mod tests {
     }
 
     #[test]
-    fn test_current_timestamp() {
-        let ts = current_timestamp();
+    fn test_system_clock_timestamp() {
+        let ts = SystemClock.now_secs();
         // Should be after 2024
         assert!(ts > 1700000000);
     }
@@ -519,6 +1314,8 @@ mod tests {
             poll_interval_secs: 600,
             min_poll_interval_secs: 60,
             warning_threshold_blocks: 4320,
+            min_confirmations: 1,
+            rotation_gap_limit: DEFAULT_ROTATION_GAP_LIMIT,
         };
 
         assert_eq!(config.min_poll_interval_secs, 60);
@@ -541,6 +1338,8 @@ mod tests {
             poll_interval_secs: 600,
             min_poll_interval_secs: 0, // Disable for test
             warning_threshold_blocks: 4320,
+            min_confirmations: 1,
+            rotation_gap_limit: DEFAULT_ROTATION_GAP_LIMIT,
         };
 
         // Connect to mainnet
@@ -576,6 +1375,49 @@ mod tests {
         println!("✓ Events: {:?}", events);
     }
 
+    #[test]
+    #[ignore = "requires network access"]
+    fn test_poll_concurrent_matches_serial() {
+        use nostring_electrum::ElectrumClient;
+
+        let descriptors = [
+            "wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*))",
+            "wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/1/*))",
+            "wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/2/*))",
+            "wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/3/*))",
+        ];
+
+        let run = |parallel: Option<usize>| {
+            let dir = tempdir().unwrap();
+            let client = ElectrumClient::new("ssl://blockstream.info:700", Network::Bitcoin)
+                .expect("Failed to connect to Electrum");
+            let mut service = WatchService::new(client, test_config(dir.path())).unwrap();
+            for (i, desc) in descriptors.iter().enumerate() {
+                service
+                    .add_policy(format!("policy-{}", i), *desc, 26280)
+                    .unwrap();
+            }
+            let events = match parallel {
+                Some(n) => service.poll_concurrent(n).expect("Concurrent poll failed"),
+                None => service.poll().expect("Serial poll failed"),
+            };
+            (events, service.state().policies.len())
+        };
+
+        let (serial, _) = run(None);
+        let (concurrent, policies) = run(Some(3));
+
+        assert_eq!(policies, descriptors.len());
+        assert!(concurrent.iter().all(|e| !e.is_error()), "{:?}", concurrent);
+        // Policy iteration order follows the state's map, so compare as sets
+        let sorted = |events: &[WatchEvent]| {
+            let mut v: Vec<String> = events.iter().map(|e| format!("{:?}", e)).collect();
+            v.sort();
+            v
+        };
+        assert_eq!(sorted(&serial), sorted(&concurrent));
+    }
+
     #[test]
     #[ignore = "requires network access"]
     fn test_poll_rate_limiting() {
@@ -587,6 +1429,8 @@ mod tests {
             poll_interval_secs: 600,
             min_poll_interval_secs: 60, // Enable rate limiting
             warning_threshold_blocks: 4320,
+            min_confirmations: 1,
+            rotation_gap_limit: DEFAULT_ROTATION_GAP_LIMIT,
         };
 
         let client = ElectrumClient::new("ssl://blockstream.info:700", Network::Bitcoin)
@@ -610,4 +1454,441 @@ mod tests {
             other => panic!("Expected PollTooFrequent, got {:?}", other),
         }
     }
+
+    #[test]
+    #[ignore = "requires network access"]
+    fn test_poll_rate_limit_follows_injected_clock() {
+        use nostring_core::clock::MockClock;
+        use nostring_electrum::ElectrumClient;
+
+        let dir = tempdir().unwrap();
+        let config = WatchConfig {
+            min_poll_interval_secs: 60,
+            ..test_config(dir.path())
+        };
+        let client = ElectrumClient::new("ssl://blockstream.info:700", Network::Bitcoin)
+            .expect("Failed to connect to Electrum");
+        let clock = MockClock::new(1_700_000_000);
+        let mut service = WatchService::new(client, config)
+            .unwrap()
+            .with_clock(clock.clone());
+
+        service.poll().expect("first poll");
+        assert_eq!(service.state.last_poll, Some(1_700_000_000));
+
+        // 59 seconds on the injected clock, however long the poll took
+        clock.advance(59);
+        assert!(matches!(
+            service.poll(),
+            Err(WatchError::PollTooFrequent { min: 60 })
+        ));
+
+        clock.advance(1);
+        service.poll().expect("poll after the interval");
+        assert_eq!(service.state.last_poll, Some(1_700_000_060));
+    }
+
+    /// Counts lookups so tests can check how the server is queried
+    struct MockLookup {
+        history: Vec<ScriptHistoryItem>,
+        txs: Vec<Transaction>,
+        batch_calls: std::cell::Cell<usize>,
+    }
+
+    impl SpendLookup for MockLookup {
+        fn script_history(
+            &self,
+            _script: &ScriptBuf,
+        ) -> Result<Vec<ScriptHistoryItem>, nostring_electrum::Error> {
+            Ok(self.history.clone())
+        }
+
+        fn transactions(
+            &self,
+            txids: &[Txid],
+        ) -> Result<Vec<(Txid, Transaction)>, nostring_electrum::Error> {
+            self.batch_calls.set(self.batch_calls.get() + 1);
+            // The server only knows some of the requested transactions
+            Ok(txids
+                .iter()
+                .filter_map(|txid| {
+                    self.txs
+                        .iter()
+                        .find(|tx| tx.compute_txid() == *txid)
+                        .map(|tx| (*txid, tx.clone()))
+                })
+                .collect())
+        }
+    }
+
+    /// Chain served from memory, with history kept per script
+    #[derive(Default)]
+    struct MockChain {
+        utxos: Mutex<Vec<Utxo>>,
+        history: Mutex<Vec<(ScriptBuf, ScriptHistoryItem)>>,
+        txs: Mutex<Vec<Transaction>>,
+    }
+
+    impl SpendLookup for MockChain {
+        fn script_history(
+            &self,
+            script: &ScriptBuf,
+        ) -> Result<Vec<ScriptHistoryItem>, nostring_electrum::Error> {
+            let history = self.history.lock().unwrap();
+            Ok(history
+                .iter()
+                .filter(|(s, _)| s == script)
+                .map(|(_, item)| item.clone())
+                .collect())
+        }
+
+        fn transactions(
+            &self,
+            txids: &[Txid],
+        ) -> Result<Vec<(Txid, Transaction)>, nostring_electrum::Error> {
+            let txs = self.txs.lock().unwrap();
+            Ok(txs
+                .iter()
+                .filter(|tx| txids.contains(&tx.compute_txid()))
+                .map(|tx| (tx.compute_txid(), tx.clone()))
+                .collect())
+        }
+    }
+
+    impl PolicyLookup for MockChain {
+        fn utxos_for_script(
+            &self,
+            script: &ScriptBuf,
+        ) -> Result<Vec<Utxo>, nostring_electrum::Error> {
+            let utxos = self.utxos.lock().unwrap();
+            Ok(utxos
+                .iter()
+                .filter(|u| u.script_pubkey == *script)
+                .cloned()
+                .collect())
+        }
+
+        fn block_time(&self, height: u32) -> Result<u32, nostring_electrum::Error> {
+            Ok(1_231_006_505 + height * 600)
+        }
+    }
+
+    #[test]
+    fn test_poll_follows_rotated_checkins() {
+        use nostring_inherit::checkin::{CheckinDestination, CheckinTxBuilder, InheritanceUtxo};
+
+        let desc_str = "wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*))";
+        let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(desc_str).unwrap();
+        let state = Mutex::new(WatchState::new());
+        lock_state(&state).add_policy(PolicyState::new("rotating", desc_str, 26280));
+        let chain = MockChain::default();
+        let poll = |height: u32| {
+            poll_policy(
+                &chain,
+                &state,
+                "rotating",
+                height,
+                144,
+                1,
+                DEFAULT_ROTATION_GAP_LIMIT,
+                1_700_000_000,
+            )
+            .unwrap()
+        };
+
+        let mut utxo = Utxo {
+            outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            value: bitcoin::Amount::from_sat(100_000),
+            height: 900_000,
+            script_pubkey: derive_script(&descriptor, 0).unwrap(),
+        };
+        chain.utxos.lock().unwrap().push(utxo.clone());
+        let events = poll(900_010);
+        assert!(matches!(events[..], [WatchEvent::UtxoAppeared { .. }]));
+
+        for round in 1..=3u32 {
+            // A rotating check-in moves the funds to the next index
+            let checkin = CheckinTxBuilder::new(
+                InheritanceUtxo::new(
+                    utxo.outpoint,
+                    utxo.value,
+                    utxo.height,
+                    utxo.script_pubkey.clone(),
+                ),
+                descriptor.clone(),
+                2,
+                round - 1,
+            )
+            .with_destination(CheckinDestination::NextIndex)
+            .build_unsigned_tx()
+            .unwrap();
+            let checkin_txid = checkin.compute_txid();
+            let rotated = Utxo {
+                outpoint: OutPoint::new(checkin_txid, 0),
+                value: checkin.output[0].value,
+                height: utxo.height + 1_000,
+                script_pubkey: checkin.output[0].script_pubkey.clone(),
+            };
+            assert_eq!(
+                rotated.script_pubkey,
+                derive_script(&descriptor, round).unwrap()
+            );
+            chain.history.lock().unwrap().push((
+                utxo.script_pubkey.clone(),
+                ScriptHistoryItem {
+                    txid: checkin_txid,
+                    height: rotated.height,
+                },
+            ));
+            chain.txs.lock().unwrap().push(checkin);
+            *chain.utxos.lock().unwrap() = vec![rotated.clone()];
+
+            // The next poll finds the funds at their new index and the spend
+            // at the old one
+            let events = poll(rotated.height + 10);
+            assert!(events.iter().any(|e| matches!(
+                e,
+                WatchEvent::UtxoAppeared { outpoint, .. } if *outpoint == rotated.outpoint
+            )));
+            assert!(events.iter().any(|e| matches!(
+                e,
+                WatchEvent::UtxoSpent { outpoint, spending_txid, .. }
+                    if *outpoint == utxo.outpoint && *spending_txid == checkin_txid
+            )));
+
+            let state = lock_state(&state);
+            let policy = state.get_policy("rotating").unwrap();
+            assert_eq!(policy.outpoints(), vec![rotated.outpoint]);
+            assert_eq!(policy.utxos[0].derivation_index, round);
+            assert_eq!(policy.address_index, round);
+            drop(state);
+            utxo = rotated;
+        }
+    }
+
+    #[test]
+    fn test_poll_policies_concurrent_matches_serial() {
+        let descriptors: Vec<String> = (0..6)
+            .map(|i| format!("wsh(pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/{}/*))", i))
+            .collect();
+        let chain = MockChain::default();
+        for (i, desc) in descriptors.iter().enumerate() {
+            let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(desc).unwrap();
+            chain.utxos.lock().unwrap().push(Utxo {
+                outpoint: OutPoint::new(Txid::from_byte_array([i as u8 + 1; 32]), 0),
+                value: bitcoin::Amount::from_sat(10_000 * (i as u64 + 1)),
+                height: 900_000,
+                script_pubkey: derive_script(&descriptor, 0).unwrap(),
+            });
+        }
+
+        let new_state = || {
+            let mut state = WatchState::new();
+            for (i, desc) in descriptors.iter().enumerate() {
+                state.add_policy(PolicyState::new(format!("policy-{}", i), desc, 26280));
+            }
+            Mutex::new(state)
+        };
+        let policy_ids = lock_state(&new_state()).policy_ids();
+        let config = WatchConfig::default();
+        let poll = |max_parallel: usize| {
+            let state = new_state();
+            let events: Vec<WatchEvent> = poll_policies(
+                &chain,
+                &state,
+                &policy_ids,
+                max_parallel,
+                &config,
+                900_010,
+                1_700_000_000,
+            )
+            .into_iter()
+            .flat_map(|result| result.unwrap())
+            .collect();
+            (events, state.into_inner().unwrap())
+        };
+
+        let (serial, serial_state) = poll(1);
+        let (concurrent, concurrent_state) = poll(4);
+        assert_eq!(serial.len(), descriptors.len());
+        assert_eq!(serial, concurrent);
+        for id in &policy_ids {
+            assert_eq!(
+                serial_state.get_policy(id).unwrap().outpoints(),
+                concurrent_state.get_policy(id).unwrap().outpoints()
+            );
+        }
+    }
+
+    fn tx_spending(prev: OutPoint) -> Transaction {
+        use bitcoin::{absolute, transaction, Amount, Sequence, TxIn, TxOut, Witness};
+
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: prev,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_find_spending_tx_uses_single_batch() {
+        let outpoint = OutPoint::new(Txid::from_byte_array([1; 32]), 0);
+        let unrelated: Vec<Transaction> = (0..5)
+            .map(|i| tx_spending(OutPoint::new(Txid::from_byte_array([2; 32]), i)))
+            .collect();
+        let spender = tx_spending(outpoint);
+        let missing = tx_spending(OutPoint::new(Txid::from_byte_array([3; 32]), 0));
+
+        let mut history = vec![ScriptHistoryItem {
+            txid: outpoint.txid,
+            height: 100,
+        }];
+        history.extend(unrelated.iter().map(|tx| ScriptHistoryItem {
+            txid: tx.compute_txid(),
+            height: 110,
+        }));
+        history.push(ScriptHistoryItem {
+            txid: missing.compute_txid(),
+            height: 115,
+        });
+        history.push(ScriptHistoryItem {
+            txid: spender.compute_txid(),
+            height: 120,
+        });
+
+        let mut txs = unrelated;
+        txs.push(spender.clone());
+        let lookup = MockLookup {
+            history,
+            txs,
+            batch_calls: std::cell::Cell::new(0),
+        };
+
+        let (found, height) = find_spending_tx(&lookup, &outpoint, &ScriptBuf::new()).unwrap();
+        assert_eq!(found.compute_txid(), spender.compute_txid());
+        assert_eq!(height, 120);
+        // Seven candidates fetched in one round trip; the missing one is skipped
+        assert_eq!(lookup.batch_calls.get(), 1);
+    }
+
+    #[test]
+    fn test_detect_spends_two_inputs_different_paths() {
+        use bitcoin::Witness;
+
+        // Owner-path and heir-path witnesses: [sig, script] / [sig, empty, script]
+        let sig = [0x30u8; 72];
+        let witness_script = [0x21u8, 0x02, 0xAA, 0xBB, 0xCC];
+        let owner_witness = Witness::from_slice(&[&sig[..], &witness_script[..]]);
+        let heir_witness = Witness::from_slice(&[&sig[..], &[][..], &witness_script[..]]);
+
+        let first = OutPoint::new(Txid::from_byte_array([1; 32]), 0);
+        let second = OutPoint::new(Txid::from_byte_array([4; 32]), 1);
+        let untouched = OutPoint::new(Txid::from_byte_array([5; 32]), 0);
+
+        let mut sweep = tx_spending(second);
+        sweep.input[0].witness = heir_witness;
+        let template = sweep.input[0].clone();
+        sweep.input.push(bitcoin::TxIn {
+            previous_output: first,
+            witness: owner_witness,
+            ..template
+        });
+        let sweep_txid = sweep.compute_txid();
+
+        let lookup = MockLookup {
+            history: vec![ScriptHistoryItem {
+                txid: sweep_txid,
+                height: 900,
+            }],
+            txs: vec![sweep],
+            batch_calls: std::cell::Cell::new(0),
+        };
+
+        let spends = detect_spends(
+            &lookup,
+            &[first, second],
+            &ScriptBuf::new(),
+            &[(first, 800), (second, 800), (untouched, 800)],
+            Some(26_280),
+        );
+
+        // One result per tracked UTXO, in tracked order, each with its own path
+        assert_eq!(spends.len(), 2);
+        assert_eq!(spends[0].0, first);
+        assert_eq!(spends[0].1, SpendType::OwnerCheckin);
+        assert_eq!(spends[1].0, second);
+        assert_eq!(spends[1].1, SpendType::HeirClaim);
+        assert!(spends.iter().all(|(.., txid)| *txid == sweep_txid));
+        // The shared spending tx is fetched once
+        assert_eq!(lookup.batch_calls.get(), 1);
+    }
+
+    fn mempool_deposit() -> Utxo {
+        Utxo {
+            outpoint: OutPoint::new(Txid::from_byte_array([9; 32]), 0),
+            value: bitcoin::Amount::from_sat(50_000),
+            height: 0,
+            script_pubkey: ScriptBuf::new(),
+        }
+    }
+
+    #[test]
+    fn test_zero_conf_deposit_pending_until_confirmed() {
+        let mut utxo = mempool_deposit();
+
+        // Seen in the mempool: pending, not tracked
+        let (events, funded) = new_utxo_events("p", &[utxo.clone()], &[], 900_000, 1);
+        assert!(funded.is_empty());
+        assert!(matches!(
+            events[..],
+            [WatchEvent::UtxoPendingFunding {
+                confirmations: 0,
+                required: 1,
+                ..
+            }]
+        ));
+
+        // Mined in the tip block: one confirmation is enough
+        utxo.height = 900_001;
+        let (events, funded) = new_utxo_events("p", &[utxo.clone()], &[], 900_001, 1);
+        assert_eq!(funded.len(), 1);
+        assert!(matches!(
+            events[..],
+            [WatchEvent::UtxoAppeared {
+                height: 900_001,
+                ..
+            }]
+        ));
+
+        // Once tracked it is never reported again
+        let (events, _) = new_utxo_events("p", &[utxo.clone()], &[utxo.outpoint], 900_010, 1);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_deposit_depth_recomputed_each_poll() {
+        let mut utxo = mempool_deposit();
+        utxo.height = 100;
+
+        for (tip, expect_funded) in [(100, false), (101, false), (102, true)] {
+            let (events, funded) = new_utxo_events("p", &[utxo.clone()], &[], tip, 3);
+            assert_eq!(!funded.is_empty(), expect_funded, "tip {}", tip);
+            if let WatchEvent::UtxoPendingFunding { confirmations, .. } = events[0] {
+                assert_eq!(confirmations, tip - 99);
+            }
+        }
+
+        // Zero required confirmations accepts the mempool deposit immediately
+        let (_, funded) = new_utxo_events("p", &[mempool_deposit()], &[], 100, 0);
+        assert_eq!(funded.len(), 1);
+    }
 }
@@ -21,11 +21,41 @@
 //!   - 2 items → owner
 //!   - 3+ items → heir claim
 //!
+//! A k-of-n owner compiles to either `or_d(multi(k, owners...), <recovery>)`
+//! or `or_i(...)` with `multi` on one side, whichever the policy compiler
+//! finds cheaper. Under `or_d` the witness script opens with
+//! `OP_k <keys> OP_n CHECKMULTISIG`:
+//!   - Owner path: `[<empty>, <sig_1>, ..., <sig_k>, <witness_script>]`
+//!   - Heir path: `[<heir stack>..., <empty> × (k + 1), <witness_script>]`
+//!
+//! Under `or_i` the script opens with `IF`, and the item below the witness
+//! script selects the branch (`1` for the first, empty for the second):
+//!   - Owner path: `[<empty>, <sig_1>, ..., <sig_k>, <selector>, <witness_script>]`
+//!   - Heir path: `[<heir stack>..., <selector>, <witness_script>]`
+//!
+//! For Taproot descriptors `tr(owner, {and_v(v:pk(heir), older(N)), ...})`
+//! the owner is the internal key:
+//!   - Key path (owner): `[<schnorr_sig>]` — a single 64/65-byte item
+//!   - Script path (heir): `[<stack_items>..., <leaf_script>, <control_block>]`,
+//!     recognised by the trailing control block
+//!
 //! This module also supports a timing-based fallback: if the spend occurred
 //! before the timelock expired, it MUST be the owner (heir can't spend yet).
+//!
+//! Given the descriptor, [`identify_heir`] goes one step further and works
+//! out *which* heir claimed: P2WSH signatures are checked against each heir
+//! key, and a Taproot script-path spend names its heirs in the revealed leaf.
 
 use crate::events::SpendType;
-use bitcoin::{Transaction, Witness};
+use bitcoin::bip32::Fingerprint;
+use bitcoin::hashes::Hash;
+use bitcoin::script::Instruction;
+use bitcoin::secp256k1::{Message, Secp256k1, Verification};
+use bitcoin::sighash::SighashCache;
+use bitcoin::{Amount, OutPoint, PublicKey, Script, Transaction, Witness};
+use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::{Descriptor, ToPublicKey};
+use nostring_inherit::policy::{heir_keys, multisig_owner_branch};
 use serde::{Deserialize, Serialize};
 
 /// Result of analyzing a spending transaction
@@ -39,6 +69,45 @@ pub struct SpendAnalysis {
     pub witness_stack_size: usize,
     /// Confidence level (0.0 - 1.0)
     pub confidence: f64,
+    /// Which heir claimed, for heir claims analyzed with their descriptor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heir: Option<HeirIdentification>,
+}
+
+/// Which heir signed a recovery-path spend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeirIdentification {
+    /// Master fingerprints of the heir keys known to have signed
+    pub signers: Vec<Fingerprint>,
+    /// Heirs who may have signed, when the witness doesn't prove which did
+    /// (several heirs sharing a Taproot leaf, or a signature that couldn't
+    /// be checked). Empty when `signers` is known.
+    pub candidates: Vec<Fingerprint>,
+}
+
+impl HeirIdentification {
+    /// The single heir who claimed, if that's certain
+    pub fn heir(&self) -> Option<Fingerprint> {
+        match self.signers[..] {
+            [fingerprint] => Some(fingerprint),
+            _ => None,
+        }
+    }
+
+    /// Whether several heirs could have made the claim
+    pub fn is_ambiguous(&self) -> bool {
+        self.signers.is_empty() && self.candidates.len() > 1
+    }
+}
+
+/// Analysis of one input of a transaction that spends a tracked UTXO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputSpendAnalysis {
+    /// Position of the input in the spending transaction
+    pub input_index: usize,
+    /// The tracked UTXO this input spends
+    pub outpoint: OutPoint,
+    pub analysis: SpendAnalysis,
 }
 
 /// How the spend type was determined
@@ -54,7 +123,8 @@ pub enum DetectionMethod {
 
 /// Analyze a spending transaction's input witness to determine spend type.
 ///
-/// For P2WSH, the witness structure is: `[stack_items..., witness_script]`
+/// For P2WSH, the witness structure is: `[stack_items..., witness_script]`.
+/// Taproot key-path and script-path witnesses are detected first.
 ///
 /// # Arguments
 /// * `witness` - The witness data from the spending input
@@ -70,9 +140,18 @@ pub fn analyze_witness(witness: &Witness) -> SpendAnalysis {
             method: DetectionMethod::Indeterminate,
             witness_stack_size: 0,
             confidence: 0.0,
+            heir: None,
         };
     }
 
+    if let Some(analysis) = analyze_taproot_witness(&items) {
+        return analysis;
+    }
+
+    if let Some(analysis) = analyze_multisig_owner_witness(&items) {
+        return analysis;
+    }
+
     // For P2WSH, last item is the witness script
     // Stack items = everything except the last element
     let stack_size = items.len().saturating_sub(1);
@@ -89,6 +168,7 @@ pub fn analyze_witness(witness: &Witness) -> SpendAnalysis {
                 method: DetectionMethod::WitnessAnalysis,
                 witness_stack_size: stack_size,
                 confidence: if looks_like_sig { 0.95 } else { 0.7 },
+                heir: None,
             }
         }
         // 2+ stack items → heir path (signature + empty dummy for owner branch)
@@ -106,6 +186,7 @@ pub fn analyze_witness(witness: &Witness) -> SpendAnalysis {
                     method: DetectionMethod::WitnessAnalysis,
                     witness_stack_size: stack_size,
                     confidence: 0.9,
+                    heir: None,
                 }
             } else {
                 // Multiple items but no empty dummy — unusual, could be
@@ -115,6 +196,7 @@ pub fn analyze_witness(witness: &Witness) -> SpendAnalysis {
                     method: DetectionMethod::Indeterminate,
                     witness_stack_size: stack_size,
                     confidence: 0.3,
+                    heir: None,
                 }
             }
         }
@@ -124,10 +206,271 @@ pub fn analyze_witness(witness: &Witness) -> SpendAnalysis {
             method: DetectionMethod::Indeterminate,
             witness_stack_size: stack_size,
             confidence: 0.0,
+            heir: None,
+        },
+    }
+}
+
+/// Recognise P2WSH spends of a k-of-n owner policy, returning `None` if the
+/// witness script has no CHECKMULTISIG owner branch.
+fn analyze_multisig_owner_witness(items: &[&[u8]]) -> Option<SpendAnalysis> {
+    let (script, stack) = items.split_last()?;
+    let branch = multisig_owner_branch(Script::from_bytes(script))?;
+    let k = branch.threshold;
+
+    let (spend_type, method, confidence) = match branch.selector {
+        // or_d: CHECKMULTISIG consumes the top k + 1 stack items, k
+        // signatures above the dummy on the owner path, k + 1 empties on
+        // the heir path.
+        None => {
+            let top = stack.len().checked_sub(k + 1).map(|i| &stack[i..]);
+            match top {
+                Some([dummy, sigs @ ..]) if is_multisig_owner_stack(dummy, sigs) => (
+                    SpendType::OwnerCheckin,
+                    DetectionMethod::WitnessAnalysis,
+                    0.9,
+                ),
+                Some(top) if stack.len() > k + 1 && top.iter().all(|item| item.is_empty()) => {
+                    (SpendType::HeirClaim, DetectionMethod::WitnessAnalysis, 0.9)
+                }
+                _ => (SpendType::Unknown, DetectionMethod::Indeterminate, 0.3),
+            }
+        }
+        // or_i: the top item picks the branch, `1` for the first and empty
+        // for the second.
+        Some(owner_first) => match stack.split_last() {
+            Some((selector, rest)) if selector.is_empty() || *selector == [1] => {
+                let owner_taken = selector.is_empty() != owner_first;
+                match rest {
+                    [dummy, sigs @ ..]
+                        if owner_taken
+                            && sigs.len() == k
+                            && is_multisig_owner_stack(dummy, sigs) =>
+                    {
+                        (
+                            SpendType::OwnerCheckin,
+                            DetectionMethod::WitnessAnalysis,
+                            0.9,
+                        )
+                    }
+                    _ if owner_taken => (SpendType::Unknown, DetectionMethod::Indeterminate, 0.3),
+                    _ => (SpendType::HeirClaim, DetectionMethod::WitnessAnalysis, 0.9),
+                }
+            }
+            _ => (SpendType::Unknown, DetectionMethod::Indeterminate, 0.3),
         },
+    };
+    Some(SpendAnalysis {
+        spend_type,
+        method,
+        witness_stack_size: stack.len(),
+        confidence,
+        heir: None,
+    })
+}
+
+/// A CHECKMULTISIG owner stack: an empty dummy under signature-sized items.
+fn is_multisig_owner_stack(dummy: &[u8], sigs: &[&[u8]]) -> bool {
+    dummy.is_empty() && sigs.iter().all(|s| s.len() >= 64)
+}
+
+/// Drop the BIP-341 annex if present (last item starting with 0x50)
+fn strip_annex<'a, 'b>(items: &'a [&'b [u8]]) -> &'a [&'b [u8]] {
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() && last.first() == Some(&0x50) => rest,
+        _ => items,
+    }
+}
+
+/// Recognise Taproot witnesses, returning `None` if the stack isn't one.
+fn analyze_taproot_witness(items: &[&[u8]]) -> Option<SpendAnalysis> {
+    match strip_annex(items) {
+        // Key path: a lone Schnorr signature (64 bytes, or 65 with sighash byte)
+        [sig] if sig.len() == 64 || sig.len() == 65 => Some(SpendAnalysis {
+            spend_type: SpendType::OwnerCheckin,
+            method: DetectionMethod::WitnessAnalysis,
+            witness_stack_size: 1,
+            confidence: 0.95,
+            heir: None,
+        }),
+        // Script path: [stack..., leaf_script, control_block]. In NoString
+        // descriptors every leaf is a recovery path, so this is an heir claim.
+        [stack @ .., _script, control] if is_control_block(control) => Some(SpendAnalysis {
+            spend_type: SpendType::HeirClaim,
+            method: DetectionMethod::WitnessAnalysis,
+            witness_stack_size: stack.len(),
+            confidence: 0.9,
+            heir: None,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether `item` is shaped like a BIP-341 control block: a tapscript leaf
+/// version byte, a 32-byte internal key, then 0-128 32-byte merkle nodes.
+fn is_control_block(item: &[u8]) -> bool {
+    const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+    item.len() >= 33
+        && (item.len() - 33) % 32 == 0
+        && (item.len() - 33) / 32 <= 128
+        && item[0] & 0xfe == TAPSCRIPT_LEAF_VERSION
+}
+
+/// Work out which heir signed input `input_index` of `tx`.
+///
+/// Heir keys are read from `descriptor` (see [`heir_keys`]) and derived at
+/// `derivation_index`; multipath keys contribute every path. For P2WSH,
+/// each ECDSA signature in the witness is verified against every heir key,
+/// which needs `spent_value`, the amount of the UTXO the input spends. A
+/// Taproot script-path spend reveals its leaf, so the heirs are the keys
+/// in that leaf; several heirs sharing a leaf stay as candidates, since
+/// Schnorr signatures can't be checked without every input's prevout.
+///
+/// Returns `None` if the descriptor has no heir keys or the input doesn't
+/// exist. A single remaining candidate is reported as the signer.
+pub fn identify_heir(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    derivation_index: u32,
+    tx: &Transaction,
+    input_index: usize,
+    spent_value: Amount,
+) -> Option<HeirIdentification> {
+    let secp = Secp256k1::verification_only();
+    let heirs: Vec<(Fingerprint, PublicKey)> = heir_keys(descriptor)
+        .ok()?
+        .into_iter()
+        .flat_map(DescriptorPublicKey::into_single_keys)
+        .filter_map(|key| {
+            let fingerprint = key.master_fingerprint();
+            let pubkey = key
+                .at_derivation_index(derivation_index)
+                .ok()?
+                .to_public_key();
+            Some((fingerprint, pubkey))
+        })
+        .collect();
+    if heirs.is_empty() {
+        return None;
+    }
+    let input = tx.input.get(input_index)?;
+    let items: Vec<&[u8]> = input.witness.iter().collect();
+
+    let (signers, candidates) = match descriptor {
+        Descriptor::Tr(_) => (Vec::new(), leaf_heirs(&items, &heirs)),
+        _ => {
+            let signers = ecdsa_signers(&secp, &items, &heirs, tx, input_index, spent_value);
+            // A pkh() heir pushes its key, narrowing the field even when no
+            // signature checks out
+            let pushed: Vec<(Fingerprint, PublicKey)> = heirs
+                .iter()
+                .filter(|(_, pubkey)| items.contains(&&pubkey.to_bytes()[..]))
+                .copied()
+                .collect();
+            let candidates = if pushed.is_empty() { &heirs } else { &pushed };
+            (signers, fingerprints(candidates))
+        }
+    };
+
+    Some(if !signers.is_empty() {
+        HeirIdentification {
+            signers,
+            candidates: Vec::new(),
+        }
+    } else if candidates.len() == 1 {
+        HeirIdentification {
+            signers: candidates,
+            candidates: Vec::new(),
+        }
+    } else {
+        HeirIdentification {
+            signers: Vec::new(),
+            candidates,
+        }
+    })
+}
+
+/// Fingerprints of `keys`, deduplicated in order
+fn fingerprints(keys: &[(Fingerprint, PublicKey)]) -> Vec<Fingerprint> {
+    let mut out: Vec<Fingerprint> = Vec::new();
+    for (fingerprint, _) in keys {
+        if !out.contains(fingerprint) {
+            out.push(*fingerprint);
+        }
+    }
+    out
+}
+
+/// Heir keys with a valid ECDSA signature in a P2WSH witness
+fn ecdsa_signers<C: Verification>(
+    secp: &Secp256k1<C>,
+    items: &[&[u8]],
+    heirs: &[(Fingerprint, PublicKey)],
+    tx: &Transaction,
+    input_index: usize,
+    spent_value: Amount,
+) -> Vec<Fingerprint> {
+    let Some((script, stack)) = items.split_last() else {
+        return Vec::new();
+    };
+    let script = Script::from_bytes(script);
+    let mut cache = SighashCache::new(tx);
+
+    let mut signed = Vec::new();
+    for item in stack {
+        let Ok(sig) = bitcoin::ecdsa::Signature::from_slice(item) else {
+            continue;
+        };
+        let Ok(sighash) =
+            cache.p2wsh_signature_hash(input_index, script, spent_value, sig.sighash_type)
+        else {
+            continue;
+        };
+        let msg = Message::from_digest(sighash.to_byte_array());
+        signed.extend(
+            heirs
+                .iter()
+                .filter(|(_, pubkey)| {
+                    secp.verify_ecdsa(&msg, &sig.signature, &pubkey.inner)
+                        .is_ok()
+                })
+                .copied(),
+        );
     }
+    fingerprints(&signed)
 }
 
+/// Heir keys in the leaf script of a Taproot script-path witness
+fn leaf_heirs(items: &[&[u8]], heirs: &[(Fingerprint, PublicKey)]) -> Vec<Fingerprint> {
+    let [.., leaf, control] = strip_annex(items) else {
+        return Vec::new();
+    };
+    if !is_control_block(control) {
+        return Vec::new();
+    }
+    let pushed: Vec<&[u8]> = Script::from_bytes(leaf)
+        .instructions()
+        .filter_map(|instruction| match instruction {
+            Ok(Instruction::PushBytes(bytes)) => Some(bytes.as_bytes()),
+            _ => None,
+        })
+        .collect();
+    let in_leaf: Vec<(Fingerprint, PublicKey)> = heirs
+        .iter()
+        .filter(|(_, pubkey)| {
+            let xonly = pubkey.inner.x_only_public_key().0.serialize();
+            pushed.contains(&&xonly[..])
+        })
+        .copied()
+        .collect();
+    fingerprints(&in_leaf)
+}
+
+/// Confidence in a spend type backed by timing: a spend confirmed before
+/// the timelock expired can only be the owner's. Short of 1.0 because the
+/// block heights it rests on come from the Electrum server.
+pub const TIMING_CONFIDENCE: f64 = 0.99;
+
 /// Analyze spend type using timelock timing as a heuristic.
 ///
 /// If the UTXO was spent before the timelock expired, it MUST be the owner
@@ -181,7 +524,8 @@ pub fn analyze_spend(
                 spend_type: timing_type,
                 method: DetectionMethod::TimelockTiming,
                 witness_stack_size: analysis.witness_stack_size,
-                confidence: 0.99, // Timing before expiry is definitive
+                confidence: TIMING_CONFIDENCE, // Timing before expiry is definitive
+                heir: None,
             };
         }
     }
@@ -196,7 +540,7 @@ pub fn analyze_spend(
             analyze_timing(spend_height, utxo_height, timelock_blocks)
         {
             // Both witness and timing agree → very high confidence
-            analysis.confidence = 0.99;
+            analysis.confidence = TIMING_CONFIDENCE;
         }
     }
 
@@ -218,12 +562,32 @@ pub fn analyze_transaction_for_outpoint(
     spent_txid: &bitcoin::Txid,
     spent_vout: u32,
 ) -> Option<SpendAnalysis> {
-    for input in &tx.input {
-        if &input.previous_output.txid == spent_txid && input.previous_output.vout == spent_vout {
-            return Some(analyze_witness(&input.witness));
-        }
-    }
-    None
+    let outpoint = OutPoint::new(*spent_txid, spent_vout);
+    analyze_transaction_inputs(tx, &[outpoint])
+        .pop()
+        .map(|input| input.analysis)
+}
+
+/// Analyze every input of `tx` that spends one of the `tracked` UTXOs.
+///
+/// A transaction can sweep several inheritance UTXOs at once (e.g. a
+/// consolidating heir claim), and each input may take a different path, so
+/// every matching input is analyzed on its own witness. Results are in
+/// input order; inputs spending anything else are skipped.
+pub fn analyze_transaction_inputs(
+    tx: &Transaction,
+    tracked: &[OutPoint],
+) -> Vec<InputSpendAnalysis> {
+    tx.input
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| tracked.contains(&input.previous_output))
+        .map(|(input_index, input)| InputSpendAnalysis {
+            input_index,
+            outpoint: input.previous_output,
+            analysis: analyze_witness(&input.witness),
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -398,6 +762,127 @@ mod tests {
         assert_eq!(analysis.witness_stack_size, 3);
     }
 
+    /// Witness script of a 2-of-3 owner, single-heir policy
+    fn two_of_three_owner_script(layout: &str) -> Vec<u8> {
+        use nostring_inherit::test_utils::{test_co_owner_keys, test_heir_key};
+        use std::str::FromStr;
+
+        // Spelled out rather than compiled, so each layout is covered no
+        // matter which one the policy compiler picks
+        let keys = test_co_owner_keys();
+        let owners = format!("multi(2,{},{},{})", keys[0], keys[1], keys[2]);
+        let heir = format!("and_v(v:pkh({}),older(26280))", test_heir_key());
+        let ms = match layout {
+            "or_d" => format!("or_d({},{})", owners, heir),
+            "or_i" => format!("or_i({},{})", owners, heir),
+            _ => format!("or_i({},{})", heir, owners),
+        };
+
+        Descriptor::<DescriptorPublicKey>::from_str(&format!("wsh({})", ms))
+            .unwrap()
+            .into_single_descriptors()
+            .unwrap()
+            .remove(0)
+            .at_derivation_index(0)
+            .unwrap()
+            .explicit_script()
+            .unwrap()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_multisig_owner_witness_detection() {
+        let script = two_of_three_owner_script("or_d");
+
+        // [dummy, sig_a, sig_c, script]
+        let mut witness = Witness::new();
+        witness.push([]);
+        witness.push([0x30u8; 71]);
+        witness.push([0x30u8; 72]);
+        witness.push(&script);
+
+        let analysis = analyze_witness(&witness);
+        assert_eq!(analysis.spend_type, SpendType::OwnerCheckin);
+        assert_eq!(analysis.method, DetectionMethod::WitnessAnalysis);
+        assert_eq!(analysis.witness_stack_size, 3);
+    }
+
+    #[test]
+    fn test_multisig_owner_heir_witness_detection() {
+        let script = two_of_three_owner_script("or_d");
+
+        // [heir_sig, heir_pubkey, dummy, empty, empty, script]
+        let mut witness = Witness::new();
+        witness.push([0x30u8; 71]);
+        witness.push([0x02u8; 33]);
+        witness.push([]);
+        witness.push([]);
+        witness.push([]);
+        witness.push(&script);
+
+        let analysis = analyze_witness(&witness);
+        assert_eq!(analysis.spend_type, SpendType::HeirClaim);
+        assert_eq!(analysis.method, DetectionMethod::WitnessAnalysis);
+
+        // Only one owner signature: not a valid spend of either branch
+        let mut witness = Witness::new();
+        witness.push([]);
+        witness.push([]);
+        witness.push([0x30u8; 71]);
+        witness.push(&script);
+        assert_eq!(analyze_witness(&witness).spend_type, SpendType::Unknown);
+    }
+
+    #[test]
+    fn test_multisig_owner_or_i_witness_detection() {
+        for (layout, owner_selector, heir_selector) in [
+            ("or_i", vec![1u8], vec![]),
+            ("or_i_heir_first", vec![], vec![1u8]),
+        ] {
+            let script = two_of_three_owner_script(layout);
+
+            // [dummy, sig_a, sig_c, selector, script]
+            let mut witness = Witness::new();
+            witness.push([]);
+            witness.push([0x30u8; 71]);
+            witness.push([0x30u8; 72]);
+            witness.push(&owner_selector);
+            witness.push(&script);
+            let analysis = analyze_witness(&witness);
+            assert_eq!(analysis.spend_type, SpendType::OwnerCheckin, "{}", layout);
+            assert_eq!(analysis.method, DetectionMethod::WitnessAnalysis);
+
+            // [heir_sig, heir_pubkey, selector, script]
+            let mut witness = Witness::new();
+            witness.push([0x30u8; 71]);
+            witness.push([0x02u8; 33]);
+            witness.push(&heir_selector);
+            witness.push(&script);
+            assert_eq!(
+                analyze_witness(&witness).spend_type,
+                SpendType::HeirClaim,
+                "{}",
+                layout
+            );
+
+            // Owner branch with one signature short
+            let mut witness = Witness::new();
+            witness.push([]);
+            witness.push([0x30u8; 71]);
+            witness.push(&owner_selector);
+            witness.push(&script);
+            assert_eq!(analyze_witness(&witness).spend_type, SpendType::Unknown);
+
+            // A selector that is neither 1 nor empty
+            let mut witness = Witness::new();
+            witness.push([0x30u8; 71]);
+            witness.push([0x02u8; 33]);
+            witness.push([2u8]);
+            witness.push(&script);
+            assert_eq!(analyze_witness(&witness).spend_type, SpendType::Unknown);
+        }
+    }
+
     #[test]
     fn test_analyze_transaction_for_outpoint() {
         use bitcoin::absolute::LockTime;
@@ -439,6 +924,294 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_analyze_transaction_inputs_per_input() {
+        use bitcoin::absolute::LockTime;
+        use bitcoin::hashes::Hash;
+        use bitcoin::transaction::Version;
+        use bitcoin::{ScriptBuf, Sequence, TxIn, TxOut, Txid};
+
+        let input = |outpoint: OutPoint, witness: Witness| TxIn {
+            previous_output: outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness,
+        };
+        let owner_utxo = OutPoint::new(Txid::from_byte_array([0x01; 32]), 0);
+        let fee_utxo = OutPoint::new(Txid::from_byte_array([0x02; 32]), 1);
+        let heir_utxo = OutPoint::new(Txid::from_byte_array([0x03; 32]), 0);
+
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![
+                input(owner_utxo, mock_owner_witness()),
+                input(fee_utxo, Witness::new()),
+                input(heir_utxo, mock_heir_witness()),
+            ],
+            output: vec![TxOut {
+                value: bitcoin::Amount::from_sat(90_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+
+        let inputs = analyze_transaction_inputs(&tx, &[heir_utxo, owner_utxo]);
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0].input_index, 0);
+        assert_eq!(inputs[0].outpoint, owner_utxo);
+        assert_eq!(inputs[0].analysis.spend_type, SpendType::OwnerCheckin);
+        assert_eq!(inputs[1].input_index, 2);
+        assert_eq!(inputs[1].outpoint, heir_utxo);
+        assert_eq!(inputs[1].analysis.spend_type, SpendType::HeirClaim);
+
+        assert!(analyze_transaction_inputs(&tx, &[]).is_empty());
+    }
+
+    /// (account xpriv, descriptor key, master fingerprint) for a fixed seed
+    fn test_account(seed: u8) -> (bitcoin::bip32::Xpriv, DescriptorPublicKey, Fingerprint) {
+        use bitcoin::bip32::{DerivationPath, Xpriv, Xpub};
+        use std::str::FromStr;
+
+        let secp = Secp256k1::new();
+        let master = Xpriv::new_master(bitcoin::Network::Bitcoin, &[seed; 32]).unwrap();
+        let fingerprint = master.fingerprint(&secp);
+        let path = DerivationPath::from_str("m/84'/0'/0'").unwrap();
+        let xpriv = master.derive_priv(&secp, &path).unwrap();
+        let key = DescriptorPublicKey::from_str(&format!(
+            "[{}/84'/0'/0']{}/0/*",
+            fingerprint,
+            Xpub::from_priv(&secp, &xpriv)
+        ))
+        .unwrap();
+        (xpriv, key, fingerprint)
+    }
+
+    /// Unsigned transaction with one input, spending with `sequence`
+    fn claim_tx(sequence: bitcoin::Sequence) -> Transaction {
+        use bitcoin::absolute::LockTime;
+        use bitcoin::transaction::Version;
+        use bitcoin::{ScriptBuf, TxIn, TxOut, Txid};
+
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::from_byte_array([0x07; 32]), 0),
+                script_sig: ScriptBuf::new(),
+                sequence,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(99_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_identify_heir_in_two_heir_policy() {
+        use bitcoin::bip32::{DerivationPath, Xpriv};
+        use bitcoin::sighash::EcdsaSighashType;
+        use nostring_inherit::policy::{InheritancePolicy, PathInfo, Timelock};
+        use std::str::FromStr;
+
+        let secp = Secp256k1::new();
+        let (_, owner, _) = test_account(1);
+        let (heir_a_xpriv, heir_a, heir_a_fingerprint) = test_account(2);
+        let (heir_b_xpriv, heir_b, heir_b_fingerprint) = test_account(3);
+
+        let descriptor = InheritancePolicy::cascade(
+            owner,
+            vec![
+                (Timelock::six_months(), PathInfo::Single(heir_a)),
+                (Timelock::one_year(), PathInfo::Single(heir_b)),
+            ],
+        )
+        .unwrap()
+        .to_wsh_descriptor()
+        .unwrap();
+        let witness_script = descriptor
+            .at_derivation_index(0)
+            .unwrap()
+            .explicit_script()
+            .unwrap();
+        let spent_value = Amount::from_sat(100_000);
+
+        let mut tx = claim_tx(Timelock::one_year().to_sequence());
+        let sign = |xpriv: &Xpriv, tx: &Transaction| {
+            let path = DerivationPath::from_str("m/0/0").unwrap();
+            let key = xpriv.derive_priv(&secp, &path).unwrap().private_key;
+            let sighash = SighashCache::new(tx)
+                .p2wsh_signature_hash(0, &witness_script, spent_value, EcdsaSighashType::All)
+                .unwrap();
+            let sig = secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &key);
+            bitcoin::ecdsa::Signature::sighash_all(sig)
+                .serialize()
+                .to_vec()
+        };
+        let claim_witness = |sig: Vec<u8>| {
+            let mut witness = Witness::new();
+            witness.push(sig);
+            witness.push([]);
+            witness.push([]);
+            witness.push(witness_script.as_bytes());
+            witness
+        };
+
+        // Heir B claims
+        tx.input[0].witness = claim_witness(sign(&heir_b_xpriv, &tx));
+        assert_eq!(
+            analyze_witness(&tx.input[0].witness).spend_type,
+            SpendType::HeirClaim
+        );
+        let heir = identify_heir(&descriptor, 0, &tx, 0, spent_value).unwrap();
+        assert_eq!(heir.heir(), Some(heir_b_fingerprint));
+        assert!(!heir.is_ambiguous());
+
+        // A signature over the wrong amount proves nothing: both heirs remain
+        let heir = identify_heir(&descriptor, 0, &tx, 0, Amount::from_sat(1)).unwrap();
+        assert_eq!(heir.heir(), None);
+        assert!(heir.is_ambiguous());
+        assert_eq!(heir.candidates.len(), 2);
+        assert!(heir.candidates.contains(&heir_a_fingerprint));
+
+        // Heir A claims instead
+        tx.input[0].witness = claim_witness(sign(&heir_a_xpriv, &tx));
+        let heir = identify_heir(&descriptor, 0, &tx, 0, spent_value).unwrap();
+        assert_eq!(heir.signers, vec![heir_a_fingerprint]);
+
+        assert!(identify_heir(&descriptor, 0, &tx, 1, spent_value).is_none());
+    }
+
+    #[test]
+    fn test_identify_heirs_sharing_a_branch() {
+        use bitcoin::bip32::{ChildNumber, Xpriv};
+        use bitcoin::sighash::EcdsaSighashType;
+        use nostring_inherit::policy::{InheritancePolicy, PathInfo, Timelock};
+        use std::collections::HashMap;
+
+        let secp = Secp256k1::new();
+        let (_, owner, _) = test_account(1);
+        let (heir_a_xpriv, heir_a, heir_a_fingerprint) = test_account(2);
+        let (heir_b_xpriv, heir_b, heir_b_fingerprint) = test_account(3);
+
+        // Both heirs must sign together
+        let descriptor = InheritancePolicy::cascade(
+            owner,
+            vec![(
+                Timelock::six_months(),
+                PathInfo::multi(2, vec![heir_a, heir_b]).unwrap(),
+            )],
+        )
+        .unwrap()
+        .to_wsh_descriptor()
+        .unwrap();
+
+        // Funds sit on a rotated receive address
+        let index = 3;
+        let derived = descriptor.derived_descriptor(&secp, index).unwrap();
+        let witness_script = derived.explicit_script().unwrap();
+        let spent_value = Amount::from_sat(100_000);
+        let sequence = Timelock::six_months().to_sequence();
+        let mut tx = claim_tx(sequence);
+
+        let sign = |xpriv: &Xpriv, tx: &Transaction| {
+            let path = [
+                ChildNumber::Normal { index: 0 },
+                ChildNumber::Normal { index },
+            ];
+            let key = xpriv.derive_priv(&secp, &path).unwrap().private_key;
+            let sighash = SighashCache::new(tx)
+                .p2wsh_signature_hash(0, &witness_script, spent_value, EcdsaSighashType::All)
+                .unwrap();
+            let sig = secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &key);
+            (
+                PublicKey::new(key.public_key(&secp)),
+                bitcoin::ecdsa::Signature::sighash_all(sig),
+            )
+        };
+        let sigs: HashMap<PublicKey, bitcoin::ecdsa::Signature> =
+            [sign(&heir_a_xpriv, &tx), sign(&heir_b_xpriv, &tx)]
+                .into_iter()
+                .collect();
+        derived.satisfy(&mut tx.input[0], (sigs, sequence)).unwrap();
+        assert_eq!(
+            analyze_witness(&tx.input[0].witness).spend_type,
+            SpendType::HeirClaim
+        );
+
+        // Both signatures check out: two signers, so no single heir
+        let heir = identify_heir(&descriptor, index, &tx, 0, spent_value).unwrap();
+        assert_eq!(heir.signers.len(), 2);
+        assert!(heir.signers.contains(&heir_a_fingerprint));
+        assert!(heir.signers.contains(&heir_b_fingerprint));
+        assert_eq!(heir.heir(), None);
+        assert!(!heir.is_ambiguous());
+
+        // Derived at the wrong index nothing verifies, and both stay candidates
+        let heir = identify_heir(&descriptor, 0, &tx, 0, spent_value).unwrap();
+        assert!(heir.signers.is_empty());
+        assert!(heir.is_ambiguous());
+        assert_eq!(heir.candidates.len(), 2);
+    }
+
+    #[test]
+    fn test_identify_heirs_sharing_a_taproot_leaf() {
+        use bitcoin::taproot::LeafVersion;
+        use nostring_inherit::policy::Timelock;
+        use std::str::FromStr;
+
+        let secp = Secp256k1::verification_only();
+        let (_, owner, _) = test_account(1);
+        let (_, heir_a, heir_a_fingerprint) = test_account(2);
+        let (_, heir_b, heir_b_fingerprint) = test_account(3);
+        let (_, heir_c, heir_c_fingerprint) = test_account(4);
+
+        // A alone after six months; B or C after a year, from one leaf
+        let descriptor = Descriptor::<DescriptorPublicKey>::from_str(&format!(
+            "tr({},{{and_v(v:pk({}),older(26280)),and_v(v:multi_a(1,{},{}),older(52560))}})",
+            owner, heir_a, heir_b, heir_c
+        ))
+        .unwrap();
+        let index = 2;
+        let derived = descriptor.derived_descriptor(&secp, index).unwrap();
+        let Descriptor::Tr(tr) = &derived else {
+            panic!("not a Taproot descriptor");
+        };
+        let spend_info = tr.spend_info();
+
+        // Script-path witness for each leaf; signatures aren't checked
+        let identify = |leaf: usize| {
+            let (_, ms) = tr.iter_scripts().nth(leaf).unwrap();
+            let script = ms.encode();
+            let control = spend_info
+                .control_block(&(script.clone(), LeafVersion::TapScript))
+                .unwrap();
+            let mut tx = claim_tx(Timelock::one_year().to_sequence());
+            tx.input[0].witness.push([0x01u8; 64]);
+            tx.input[0].witness.push(script.as_bytes());
+            tx.input[0].witness.push(control.serialize());
+            assert_eq!(
+                analyze_witness(&tx.input[0].witness).spend_type,
+                SpendType::HeirClaim
+            );
+            identify_heir(&descriptor, index, &tx, 0, Amount::from_sat(100_000)).unwrap()
+        };
+
+        // A's own leaf names A
+        let heir = identify(0);
+        assert_eq!(heir.heir(), Some(heir_a_fingerprint));
+
+        // The shared leaf can't tell B from C
+        let heir = identify(1);
+        assert_eq!(heir.heir(), None);
+        assert!(heir.is_ambiguous());
+        assert_eq!(
+            heir.candidates,
+            vec![heir_b_fingerprint, heir_c_fingerprint]
+        );
+    }
+
     #[test]
     fn test_witness_with_short_signature() {
         // Non-standard signature length — still detectable as owner
@@ -470,4 +1243,43 @@ mod tests {
         assert_eq!(heir_json, "\"HeirClaim\"");
         assert_eq!(unknown_json, "\"Unknown\"");
     }
+
+    #[test]
+    fn test_taproot_key_path_detection() {
+        let mut witness = Witness::new();
+        witness.push([0x11u8; 64]);
+
+        let analysis = analyze_witness(&witness);
+        assert_eq!(analysis.spend_type, SpendType::OwnerCheckin);
+        assert_eq!(analysis.method, DetectionMethod::WitnessAnalysis);
+        assert_eq!(analysis.witness_stack_size, 1);
+        assert!(analysis.confidence >= 0.9);
+    }
+
+    #[test]
+    fn test_taproot_script_path_detection() {
+        // [heir_sig, leaf_script, control_block(internal key + 1 merkle node)]
+        let mut control_block = vec![0xc0];
+        control_block.extend_from_slice(&[0x22; 64]);
+
+        let mut witness = Witness::new();
+        witness.push([0x11u8; 64]);
+        witness.push([0x20, 0xAA, 0xBB, 0xCC]);
+        witness.push(&control_block);
+
+        let analysis = analyze_witness(&witness);
+        assert_eq!(analysis.spend_type, SpendType::HeirClaim);
+        assert_eq!(analysis.witness_stack_size, 1);
+        assert!(analysis.confidence >= 0.85);
+    }
+
+    #[test]
+    fn test_p2wsh_not_mistaken_for_taproot() {
+        // A P2WSH owner witness must still go through the witness-script path
+        let analysis = analyze_witness(&mock_owner_witness());
+        assert_eq!(analysis.spend_type, SpendType::OwnerCheckin);
+        assert_eq!(analysis.witness_stack_size, 1);
+
+        assert!(!is_control_block(&[0x21, 0x02, 0xAA, 0xBB, 0xCC]));
+    }
 }
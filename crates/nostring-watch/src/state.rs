@@ -3,11 +3,12 @@
 //! Tracks known UTXOs and last poll times to detect changes.
 
 use bitcoin::{Amount, OutPoint};
+use nostring_inherit::policy::{TimelockUnit, SECONDS_PER_TIME_UNIT};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::io;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Errors from state operations
@@ -19,10 +20,37 @@ pub enum StateError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
     #[error("Policy not found: {0}")]
     PolicyNotFound(String),
 }
 
+/// `<file>.bak` — the state as it was before the most recent save
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling_path(path, "bak")
+}
+
+/// Write `contents` to `<path>.tmp`, sync it, and rename it over `path`
+fn write_atomic(path: &Path, contents: &str) -> Result<(), StateError> {
+    let tmp = sibling_path(path, "tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
 /// A tracked UTXO
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TrackedUtxo {
@@ -36,6 +64,9 @@ pub struct TrackedUtxo {
     pub height: u32,
     /// When we first saw this UTXO (unix timestamp)
     pub first_seen: u64,
+    /// Receive index of the descriptor address holding it
+    #[serde(default)]
+    pub derivation_index: u32,
 }
 
 /// Serde helper for OutPoint
@@ -91,8 +122,18 @@ pub struct PolicyState {
     pub utxos: Vec<TrackedUtxo>,
     /// Block height when UTXO was first funded (for timelock calculation)
     pub funding_height: Option<u32>,
-    /// Timelock in blocks (from policy)
+    /// Timelock from the policy, in `timelock_unit`s
     pub timelock_blocks: u32,
+    /// Whether `timelock_blocks` counts blocks or 512-second intervals
+    #[serde(default)]
+    pub timelock_unit: TimelockUnit,
+    /// Timestamp of the block at `funding_height` (for time-based timelocks)
+    #[serde(default)]
+    pub funding_time: Option<u64>,
+    /// Highest receive index that has held funds. Check-ins that rotate to
+    /// the next address move it forward; polls scan from here.
+    #[serde(default)]
+    pub address_index: u32,
 }
 
 impl PolicyState {
@@ -104,9 +145,18 @@ impl PolicyState {
             utxos: Vec::new(),
             funding_height: None,
             timelock_blocks,
+            timelock_unit: TimelockUnit::Blocks,
+            funding_time: None,
+            address_index: 0,
         }
     }
 
+    /// Use `unit` for `timelock_blocks`
+    pub fn with_timelock_unit(mut self, unit: TimelockUnit) -> Self {
+        self.timelock_unit = unit;
+        self
+    }
+
     /// Check if a UTXO is already tracked
     pub fn has_utxo(&self, outpoint: &OutPoint) -> bool {
         self.utxos.iter().any(|u| &u.outpoint == outpoint)
@@ -120,7 +170,9 @@ impl PolicyState {
                 || utxo.height < self.funding_height.unwrap_or(u32::MAX)
             {
                 self.funding_height = Some(utxo.height);
+                self.funding_time = None;
             }
+            self.address_index = self.address_index.max(utxo.derivation_index);
             self.utxos.push(utxo);
         }
     }
@@ -139,13 +191,49 @@ impl PolicyState {
         self.utxos.iter().map(|u| u.outpoint).collect()
     }
 
-    /// Calculate blocks remaining until timelock expires
+    /// Receive indices a poll scans, ascending: every index still holding a
+    /// tracked UTXO, plus `address_index` and the `gap_limit` unused
+    /// indices after it, where a rotated check-in would land.
+    pub fn watched_indices(&self, gap_limit: u32) -> Vec<u32> {
+        let mut indices: Vec<u32> = self
+            .utxos
+            .iter()
+            .map(|u| u.derivation_index)
+            .chain(self.address_index..=self.address_index.saturating_add(gap_limit))
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// Calculate blocks remaining until a block-based timelock expires
+    ///
+    /// `None` for time-based timelocks; see `seconds_until_expiry`.
     pub fn blocks_until_expiry(&self, current_height: u32) -> Option<i64> {
+        if self.timelock_unit != TimelockUnit::Blocks {
+            return None;
+        }
         self.funding_height.map(|funding| {
             let expiry = funding as i64 + self.timelock_blocks as i64;
             expiry - current_height as i64
         })
     }
+
+    /// Calculate seconds remaining until a time-based timelock expires
+    ///
+    /// Measured from the funding block's timestamp to `now`; consensus uses
+    /// median-time-past, which runs about an hour behind. `None` for
+    /// block-based timelocks or before the funding block time is known.
+    pub fn seconds_until_expiry(&self, now: u64) -> Option<i64> {
+        if self.timelock_unit != TimelockUnit::Time {
+            return None;
+        }
+        self.funding_time.map(|funding| {
+            let expiry =
+                funding as i64 + self.timelock_blocks as i64 * SECONDS_PER_TIME_UNIT as i64;
+            expiry - now as i64
+        })
+    }
 }
 
 /// Full watch state (all policies)
@@ -166,25 +254,59 @@ impl WatchState {
     }
 
     /// Load state from file, or create empty if not exists
+    ///
+    /// If the file is truncated or corrupt, falls back to the `.bak` copy
+    /// taken before the last save.
     pub fn load(path: &Path) -> Result<Self, StateError> {
-        if path.exists() {
-            let contents = fs::read_to_string(path)?;
-            let state: WatchState = serde_json::from_str(&contents)?;
-            Ok(state)
-        } else {
-            Ok(Self::new())
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        match Self::read(path) {
+            Ok(state) => Ok(state),
+            Err(primary_err) => {
+                let backup = backup_path(path);
+                if !backup.exists() {
+                    return Err(primary_err);
+                }
+                log::warn!(
+                    "State file {} is unreadable ({}); recovering from {}",
+                    path.display(),
+                    primary_err,
+                    backup.display()
+                );
+                Self::read(&backup).map_err(|_| primary_err)
+            }
         }
     }
 
+    fn read(path: &Path) -> Result<Self, StateError> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
     /// Save state to file
+    ///
+    /// Writes to a temp file in the same directory and renames it into
+    /// place, so a crash leaves either the old or the new state — never a
+    /// half-written file. The previous state is kept as `<file>.bak`,
+    /// written the same way.
     pub fn save(&self, path: &Path) -> Result<(), StateError> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
         let contents = serde_json::to_string_pretty(self)?;
-        fs::write(path, contents)?;
-        Ok(())
+
+        // Only back up a primary that parses, so a corrupt file never
+        // overwrites a good backup
+        if let Ok(previous) = fs::read_to_string(path) {
+            if serde_json::from_str::<Self>(&previous).is_ok() {
+                write_atomic(&backup_path(path), &previous)?;
+            }
+        }
+
+        write_atomic(path, &contents)
     }
 
     /// Add or update a policy
@@ -240,6 +362,7 @@ mod tests {
             value: Amount::from_sat(100000),
             height: 934000,
             first_seen: 1700000000,
+            derivation_index: 0,
         };
 
         policy.add_utxo(utxo.clone());
@@ -278,6 +401,70 @@ mod tests {
         assert!(remaining < 0);
     }
 
+    #[test]
+    fn test_seconds_until_expiry() {
+        // 30 days of 512-second intervals
+        let mut policy =
+            PolicyState::new("test", "wsh(...)", 5_063).with_timelock_unit(TimelockUnit::Time);
+        policy.funding_height = Some(930000);
+        assert!(policy.seconds_until_expiry(1_700_000_000).is_none());
+        assert!(policy.blocks_until_expiry(934000).is_none());
+
+        policy.funding_time = Some(1_700_000_000);
+        let remaining = policy.seconds_until_expiry(1_700_086_400).unwrap();
+        assert_eq!(remaining, 5_063 * 512 - 86_400);
+        assert!(policy.seconds_until_expiry(1_703_000_000).unwrap() < 0);
+
+        // An earlier funding UTXO invalidates the known block time
+        policy.add_utxo(TrackedUtxo {
+            outpoint: test_outpoint(),
+            value: Amount::from_sat(100_000),
+            height: 929000,
+            first_seen: 1700000000,
+            derivation_index: 0,
+        });
+        assert_eq!(policy.funding_time, None);
+
+        // Block-based policies don't report seconds
+        let blocks = PolicyState::new("test", "wsh(...)", 26280);
+        assert!(blocks.seconds_until_expiry(1_700_000_000).is_none());
+    }
+
+    #[test]
+    fn test_policy_state_defaults_to_blocks() {
+        // State files from before time-based timelocks
+        let json = r#"{"id":"p","descriptor":"wsh(...)","utxos":[],"funding_height":null,"timelock_blocks":26280}"#;
+        let policy: PolicyState = serde_json::from_str(json).unwrap();
+        assert_eq!(policy.timelock_unit, TimelockUnit::Blocks);
+        assert_eq!(policy.funding_time, None);
+        assert_eq!(policy.address_index, 0);
+    }
+
+    #[test]
+    fn test_watched_indices_follow_rotation() {
+        let mut policy = PolicyState::new("test", "wsh(...)", 26280);
+        assert_eq!(policy.watched_indices(2), vec![0, 1, 2]);
+        assert_eq!(policy.watched_indices(0), vec![0]);
+
+        // A check-in rotated the funds to index 1; the old UTXO is still
+        // tracked until its spend is seen
+        for (vout, derivation_index) in [(0, 0), (1, 1)] {
+            policy.add_utxo(TrackedUtxo {
+                outpoint: OutPoint::new(test_outpoint().txid, vout),
+                value: Amount::from_sat(100_000),
+                height: 934000,
+                first_seen: 1700000000,
+                derivation_index,
+            });
+        }
+        assert_eq!(policy.address_index, 1);
+        assert_eq!(policy.watched_indices(2), vec![0, 1, 2, 3]);
+
+        // Once it is spent, scanning moves on from the new address
+        policy.remove_utxo(&OutPoint::new(test_outpoint().txid, 0));
+        assert_eq!(policy.watched_indices(2), vec![1, 2, 3]);
+    }
+
     #[test]
     fn test_watch_state_persistence() {
         let dir = tempdir().unwrap();
@@ -297,6 +484,68 @@ mod tests {
         assert_eq!(loaded.last_height, Some(934000));
     }
 
+    #[test]
+    fn test_save_is_atomic_and_keeps_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("watch_state.json");
+
+        let mut state = WatchState::new();
+        state.add_policy(PolicyState::new("policy1", "wsh(pk(...))", 26280));
+        state.save(&path).unwrap();
+        assert!(
+            !backup_path(&path).exists(),
+            "nothing to back up on first save"
+        );
+
+        state.add_policy(PolicyState::new("policy2", "wsh(pk(...))", 52560));
+        state.save(&path).unwrap();
+
+        // Backup holds the previous state; no temp file left behind
+        let backup = WatchState::load(&backup_path(&path)).unwrap();
+        assert_eq!(backup.policies.len(), 1);
+        assert!(!dir.path().join("watch_state.json.tmp").exists());
+        assert!(!dir.path().join("watch_state.json.bak.tmp").exists());
+        assert_eq!(WatchState::load(&path).unwrap().policies.len(), 2);
+    }
+
+    #[test]
+    fn test_load_recovers_from_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("watch_state.json");
+
+        let mut state = WatchState::new();
+        state.add_policy(PolicyState::new("policy1", "wsh(pk(...))", 26280));
+        state.save(&path).unwrap();
+        state.update_poll(1700000000, 934000);
+        state.save(&path).unwrap();
+
+        // Simulate a torn write: primary truncated halfway
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &contents[..contents.len() / 2]).unwrap();
+
+        let recovered = WatchState::load(&path).unwrap();
+        assert!(recovered.get_policy("policy1").is_some());
+        assert_eq!(recovered.last_poll, None, "backup predates the last save");
+
+        // A corrupt primary must not clobber the good backup on the next save
+        let mut next = WatchState::new();
+        next.add_policy(PolicyState::new("policy1", "wsh(pk(...))", 26280));
+        fs::write(&path, "{ not json").unwrap();
+        next.save(&path).unwrap();
+        assert!(WatchState::load(&backup_path(&path))
+            .unwrap()
+            .get_policy("policy1")
+            .is_some());
+    }
+
+    #[test]
+    fn test_load_corrupt_without_backup_errors() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("watch_state.json");
+        fs::write(&path, "{ truncated").unwrap();
+        assert!(matches!(WatchState::load(&path), Err(StateError::Json(_))));
+    }
+
     #[test]
     fn test_tracked_utxo_serde() {
         let utxo = TrackedUtxo {
@@ -304,6 +553,7 @@ mod tests {
             value: Amount::from_sat(100000),
             height: 934000,
             first_seen: 1700000000,
+            derivation_index: 0,
         };
 
         let json = serde_json::to_string(&utxo).unwrap();
@@ -0,0 +1,177 @@
+//! Storage backends for the watch state
+//!
+//! [`WatchService`](crate::WatchService) persists its state through a
+//! [`WatchStateStore`]. The default [`JsonFileStore`] writes a standalone
+//! JSON file; [`SqliteStore`] keeps the state in a SQLite database so the
+//! desktop app can share its existing `nostring.db`.
+
+use crate::state::{StateError, WatchState};
+use rusqlite::{Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+/// Somewhere the watch state can be loaded from and saved to
+pub trait WatchStateStore {
+    /// Load the saved state, or an empty state if nothing was saved yet
+    fn load(&self) -> Result<WatchState, StateError>;
+
+    /// Persist the given state, replacing whatever was saved before
+    fn save(&self, state: &WatchState) -> Result<(), StateError>;
+}
+
+/// JSON file store (the original behavior)
+#[derive(Debug, Clone)]
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    /// Store state at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the backing file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl WatchStateStore for JsonFileStore {
+    fn load(&self) -> Result<WatchState, StateError> {
+        WatchState::load(&self.path)
+    }
+
+    fn save(&self, state: &WatchState) -> Result<(), StateError> {
+        state.save(&self.path)
+    }
+}
+
+/// SQLite store — the state is kept as a single JSON row in `watch_state`
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StateError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Use an already-open connection, creating the table if needed
+    pub fn from_connection(conn: Connection) -> Result<Self, StateError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS watch_state (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                state_json TEXT NOT NULL
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+}
+
+impl WatchStateStore for SqliteStore {
+    fn load(&self) -> Result<WatchState, StateError> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT state_json FROM watch_state WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match json {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(WatchState::new()),
+        }
+    }
+
+    fn save(&self, state: &WatchState) -> Result<(), StateError> {
+        let json = serde_json::to_string(state)?;
+        self.conn.execute(
+            "INSERT INTO watch_state (id, state_json) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET state_json = excluded.state_json",
+            [json],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{PolicyState, TrackedUtxo};
+    use bitcoin::{Amount, OutPoint};
+    use std::str::FromStr;
+    use tempfile::tempdir;
+
+    /// Same round-trip assertions for every backend
+    fn assert_roundtrip(store: &dyn WatchStateStore) {
+        // Empty before the first save
+        let empty = store.load().unwrap();
+        assert!(empty.policies.is_empty());
+        assert!(empty.last_poll.is_none());
+
+        let mut policy = PolicyState::new("policy1", "wsh(pk(...))", 26280);
+        policy.add_utxo(TrackedUtxo {
+            outpoint: OutPoint::from_str(
+                "1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef:0",
+            )
+            .unwrap(),
+            value: Amount::from_sat(100_000),
+            height: 934000,
+            first_seen: 1700000000,
+            derivation_index: 0,
+        });
+        let mut state = WatchState::new();
+        state.add_policy(policy);
+        state.update_poll(1700000000, 934000);
+        store.save(&state).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.policies.len(), 1);
+        let loaded_policy = loaded.get_policy("policy1").unwrap();
+        assert_eq!(
+            loaded_policy.utxos,
+            state.get_policy("policy1").unwrap().utxos
+        );
+        assert_eq!(loaded_policy.funding_height, Some(934000));
+        assert_eq!(loaded.last_poll, Some(1700000000));
+        assert_eq!(loaded.last_height, Some(934000));
+
+        // Saving again overwrites
+        state.remove_policy("policy1");
+        state.update_poll(1700000600, 934001);
+        store.save(&state).unwrap();
+        let loaded = store.load().unwrap();
+        assert!(loaded.policies.is_empty());
+        assert_eq!(loaded.last_height, Some(934001));
+    }
+
+    #[test]
+    fn test_json_file_store_roundtrip() {
+        let dir = tempdir().unwrap();
+        let store = JsonFileStore::new(dir.path().join("nested/watch_state.json"));
+        assert_roundtrip(&store);
+    }
+
+    #[test]
+    fn test_sqlite_store_roundtrip() {
+        let dir = tempdir().unwrap();
+        let store = SqliteStore::open(dir.path().join("nostring.db")).unwrap();
+        assert_roundtrip(&store);
+    }
+
+    #[test]
+    fn test_sqlite_store_persists_across_connections() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nostring.db");
+
+        let mut state = WatchState::new();
+        state.update_poll(1700000000, 934000);
+        SqliteStore::open(&path).unwrap().save(&state).unwrap();
+
+        let loaded = SqliteStore::open(&path).unwrap().load().unwrap();
+        assert_eq!(loaded.last_height, Some(934000));
+    }
+}
@@ -0,0 +1,455 @@
+//! Tamper-evident audit log of sensitive actions.
+//!
+//! Every seed unlock (and failed attempt), nsec split and revoke, broadcast,
+//! heir change and service key rotation appends a row to the `audit_log`
+//! table. Each row stores the hash of the row before it and its own hash
+//! over that link plus its content, so editing, reordering or deleting an
+//! earlier row breaks every hash after it. Owners can export the log as
+//! evidence in an estate dispute.
+//!
+//! The hashes are HMACs keyed from an app secret kept in a file next to the
+//! database, never in it: someone who can edit the database but not read
+//! that file can't re-hash the rows after an edit. Verifying the chain
+//! needs the same key, so it is done by the install that wrote it.
+//!
+//! Rows describe what happened, never secret values: no passwords, seeds,
+//! nsecs or shares. Deleting the newest rows can't be detected from the log
+//! alone; keep exported copies to compare against.
+
+use crate::commands::CommandResult;
+use crate::db::{self, AuditLogRow};
+use crate::state::AppState;
+use nostring_core::canonical::to_canonical_json;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use zeroize::Zeroizing;
+
+/// `prev_hash` of the first row
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A sensitive action worth recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    SeedUnlock,
+    SeedUnlockFailed,
+    NsecSplit,
+    NsecRevoke,
+    Broadcast,
+    HeirAdd,
+    HeirRemove,
+    ServiceKeyRotate,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SeedUnlock => "seed_unlock",
+            Self::SeedUnlockFailed => "seed_unlock_failed",
+            Self::NsecSplit => "nsec_split",
+            Self::NsecRevoke => "nsec_revoke",
+            Self::Broadcast => "broadcast",
+            Self::HeirAdd => "heir_add",
+            Self::HeirRemove => "heir_remove",
+            Self::ServiceKeyRotate => "service_key_rotate",
+        }
+    }
+}
+
+/// Context the audit key is derived from the app secret under
+const AUDIT_KEY_CONTEXT: &[u8] = b"nostring-audit-log-v1";
+
+/// `<db>.secret` — the install's app secret, next to the database
+pub fn app_secret_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".secret");
+    db_path.with_file_name(name)
+}
+
+/// HMAC key of the audit chain
+pub struct AuditKey(Zeroizing<[u8; 32]>);
+
+impl AuditKey {
+    /// Derive the key from `app_secret`
+    pub fn from_app_secret(app_secret: &[u8]) -> Self {
+        Self(Zeroizing::new(hmac_sha256(app_secret, AUDIT_KEY_CONTEXT)))
+    }
+
+    /// Derive the key from the app secret beside `db_path`, creating a
+    /// random secret (readable only by the user) on first run.
+    pub fn load_or_create(db_path: &Path) -> std::io::Result<Self> {
+        let path = app_secret_path(db_path);
+        let secret = match std::fs::read(&path) {
+            Ok(secret) => Zeroizing::new(secret),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut secret = Zeroizing::new(vec![0u8; 32]);
+                getrandom::getrandom(&mut secret).map_err(std::io::Error::other)?;
+                write_private(&path, &secret)?;
+                secret
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(Self::from_app_secret(&secret))
+    }
+}
+
+impl std::fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuditKey(redacted)")
+    }
+}
+
+/// Create `path` with `contents`, readable and writable only by the user
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(key);
+    engine.input(data);
+    hmac::Hmac::<sha256::Hash>::from_engine(engine).to_byte_array()
+}
+
+/// Fields covered by a row's hash
+#[derive(Serialize)]
+struct HashedEntry<'a> {
+    prev_hash: &'a str,
+    timestamp: u64,
+    action: &'a str,
+    detail: &'a str,
+}
+
+/// HMAC-SHA256 (hex) under `key` of the canonical JSON of a row's link
+/// and content.
+pub fn entry_hash(
+    key: &AuditKey,
+    prev_hash: &str,
+    timestamp: u64,
+    action: &str,
+    detail: &str,
+) -> Result<String, String> {
+    let bytes = to_canonical_json(&HashedEntry {
+        prev_hash,
+        timestamp,
+        action,
+        detail,
+    })?;
+    Ok(hex::encode(hmac_sha256(&key.0[..], &bytes)))
+}
+
+/// Append a row chained to the current newest one.
+pub fn append(
+    conn: &Connection,
+    key: &AuditKey,
+    timestamp: u64,
+    action: AuditAction,
+    detail: &str,
+) -> Result<AuditLogRow, String> {
+    let prev_hash = db::audit_log_last_hash(conn)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| GENESIS_HASH.to_string());
+    let hash = entry_hash(key, &prev_hash, timestamp, action.as_str(), detail)?;
+    let id = db::audit_log_insert(conn, timestamp, action.as_str(), detail, &prev_hash, &hash)
+        .map_err(|e| e.to_string())?;
+    Ok(AuditLogRow {
+        id,
+        timestamp,
+        action: action.as_str().to_string(),
+        detail: detail.to_string(),
+        prev_hash,
+        hash,
+    })
+}
+
+/// Outcome of re-computing the hash chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub entries: usize,
+    /// Id of the first row whose link or hash doesn't check out
+    pub first_broken_id: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// Re-compute the chain over `rows` (oldest first) and report the first
+/// broken link.
+pub fn verify_chain(rows: &[AuditLogRow], key: &AuditKey) -> AuditVerification {
+    let broken = |row: &AuditLogRow, reason: &str| AuditVerification {
+        valid: false,
+        entries: rows.len(),
+        first_broken_id: Some(row.id),
+        reason: Some(reason.to_string()),
+    };
+
+    let mut expected_prev = GENESIS_HASH;
+    for row in rows {
+        if row.prev_hash != expected_prev {
+            return broken(row, "previous entry was removed or altered");
+        }
+        match entry_hash(key, &row.prev_hash, row.timestamp, &row.action, &row.detail) {
+            Ok(hash) if hash == row.hash => {}
+            _ => return broken(row, "entry was altered"),
+        }
+        expected_prev = &row.hash;
+    }
+    AuditVerification {
+        valid: true,
+        entries: rows.len(),
+        first_broken_id: None,
+        reason: None,
+    }
+}
+
+/// The audit log as exported for safekeeping
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditLogExport {
+    pub exported_at: u64,
+    pub verification: AuditVerification,
+    pub entries: Vec<AuditLogRow>,
+}
+
+/// Check the audit log's hash chain.
+#[tauri::command]
+pub async fn verify_audit_log(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<AuditVerification>, ()> {
+    let conn = state.db.lock().unwrap();
+    match db::audit_log_list(&conn) {
+        Ok(rows) => Ok(CommandResult::ok(verify_chain(&rows, &state.audit_key))),
+        Err(e) => Ok(CommandResult::err(format!(
+            "Failed to read audit log: {}",
+            e
+        ))),
+    }
+}
+
+/// Export the audit log, with its verification result, as pretty JSON.
+#[tauri::command]
+pub async fn export_audit_log(state: State<'_, AppState>) -> Result<CommandResult<String>, ()> {
+    let unlocked = state.unlocked.lock().unwrap();
+    if !*unlocked {
+        return Ok(CommandResult::err("Wallet is locked"));
+    }
+    drop(unlocked);
+
+    let rows = {
+        let conn = state.db.lock().unwrap();
+        match db::audit_log_list(&conn) {
+            Ok(rows) => rows,
+            Err(e) => {
+                return Ok(CommandResult::err(format!(
+                    "Failed to read audit log: {}",
+                    e
+                )))
+            }
+        }
+    };
+    let export = AuditLogExport {
+        exported_at: state.clock.now_secs(),
+        verification: verify_chain(&rows, &state.audit_key),
+        entries: rows,
+    };
+    match serde_json::to_string_pretty(&export) {
+        Ok(json) => Ok(CommandResult::ok(json)),
+        Err(e) => Ok(CommandResult::err(format!(
+            "Failed to serialize audit log: {}",
+            e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn temp_db() -> (Connection, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let conn = db::open_db(file.path()).unwrap();
+        (conn, file)
+    }
+
+    fn key() -> AuditKey {
+        AuditKey::from_app_secret(&[7u8; 32])
+    }
+
+    fn seed_log(conn: &Connection) {
+        let key = key();
+        append(conn, &key, 1_700_000_000, AuditAction::SeedUnlock, "seed").unwrap();
+        append(
+            conn,
+            &key,
+            1_700_000_100,
+            AuditAction::HeirAdd,
+            "a1b2c3d4 Spouse",
+        )
+        .unwrap();
+        append(
+            conn,
+            &key,
+            1_700_000_200,
+            AuditAction::Broadcast,
+            "check-in abcd accepted",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_chain_integrity() {
+        let (conn, _f) = temp_db();
+        assert!(verify_chain(&[], &key()).valid);
+
+        seed_log(&conn);
+        let rows = db::audit_log_list(&conn).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].prev_hash, GENESIS_HASH);
+        assert_eq!(rows[1].prev_hash, rows[0].hash);
+        assert_eq!(rows[2].prev_hash, rows[1].hash);
+        assert_eq!(rows[1].action, "heir_add");
+
+        let verification = verify_chain(&rows, &key());
+        assert!(verification.valid);
+        assert_eq!(verification.entries, 3);
+        assert_eq!(verification.first_broken_id, None);
+    }
+
+    #[test]
+    fn test_edited_entry_detected() {
+        let (conn, _f) = temp_db();
+        seed_log(&conn);
+        conn.execute(
+            "UPDATE audit_log SET detail = 'ffffffff Stranger' WHERE id = 2",
+            [],
+        )
+        .unwrap();
+
+        let verification = verify_chain(&db::audit_log_list(&conn).unwrap(), &key());
+        assert!(!verification.valid);
+        assert_eq!(verification.first_broken_id, Some(2));
+        assert_eq!(verification.reason.as_deref(), Some("entry was altered"));
+    }
+
+    #[test]
+    fn test_rehashed_or_deleted_entry_breaks_next_link() {
+        let (conn, _f) = temp_db();
+        seed_log(&conn);
+
+        // Re-hashing an edited row hides the edit in that row only
+        let rows = db::audit_log_list(&conn).unwrap();
+        let forged = entry_hash(
+            &key(),
+            &rows[0].hash,
+            rows[1].timestamp,
+            "heir_add",
+            "forged",
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE audit_log SET detail = 'forged', hash = ?1 WHERE id = 2",
+            [&forged],
+        )
+        .unwrap();
+        let verification = verify_chain(&db::audit_log_list(&conn).unwrap(), &key());
+        assert_eq!(verification.first_broken_id, Some(3));
+
+        // A deleted row is caught at the one after it
+        let (conn, _f) = temp_db();
+        seed_log(&conn);
+        conn.execute("DELETE FROM audit_log WHERE id = 2", [])
+            .unwrap();
+        let verification = verify_chain(&db::audit_log_list(&conn).unwrap(), &key());
+        assert_eq!(verification.first_broken_id, Some(3));
+        assert_eq!(
+            verification.reason.as_deref(),
+            Some("previous entry was removed or altered")
+        );
+    }
+
+    #[test]
+    fn test_rehashed_under_other_key_is_rejected() {
+        let (conn, _f) = temp_db();
+        seed_log(&conn);
+
+        // Without the app secret an edited row can't be re-hashed to pass
+        let other = AuditKey::from_app_secret(&[8u8; 32]);
+        let rows = db::audit_log_list(&conn).unwrap();
+        let forged = entry_hash(
+            &other,
+            &rows[0].hash,
+            rows[1].timestamp,
+            "heir_add",
+            "forged",
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE audit_log SET detail = 'forged', hash = ?1 WHERE id = 2",
+            [&forged],
+        )
+        .unwrap();
+        let verification = verify_chain(&db::audit_log_list(&conn).unwrap(), &key());
+        assert_eq!(verification.first_broken_id, Some(2));
+        assert!(!verify_chain(&rows, &other).valid);
+    }
+
+    #[test]
+    fn test_app_secret_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let (conn, _f) = temp_db();
+        append(
+            &conn,
+            &AuditKey::load_or_create(&db_path).unwrap(),
+            1,
+            AuditAction::SeedUnlock,
+            "seed",
+        )
+        .unwrap();
+
+        let rows = db::audit_log_list(&conn).unwrap();
+        assert!(app_secret_path(&db_path).exists());
+        assert!(verify_chain(&rows, &AuditKey::load_or_create(&db_path).unwrap()).valid);
+    }
+
+    #[test]
+    fn test_state_audit_uses_clock() {
+        use nostring_core::clock::MockClock;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"))
+            .with_clock(MockClock::new(1_700_000_000));
+        state.audit(AuditAction::SeedUnlockFailed, "seed");
+        state.audit(AuditAction::SeedUnlock, "seed");
+
+        let rows = db::audit_log_list(&state.db.lock().unwrap()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].action, "seed_unlock_failed");
+        assert_eq!(rows[1].timestamp, 1_700_000_000);
+        assert!(verify_chain(&rows, &state.audit_key).valid);
+    }
+
+    #[test]
+    fn test_append_continues_after_reopen() {
+        let file = NamedTempFile::new().unwrap();
+        {
+            let conn = db::open_db(file.path()).unwrap();
+            append(&conn, &key(), 1, AuditAction::NsecSplit, "3-of-5").unwrap();
+        }
+        let conn = db::open_db(file.path()).unwrap();
+        append(&conn, &key(), 2, AuditAction::NsecRevoke, "").unwrap();
+        assert!(verify_chain(&db::audit_log_list(&conn).unwrap(), &key()).valid);
+    }
+}
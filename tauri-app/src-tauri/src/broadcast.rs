@@ -0,0 +1,270 @@
+//! Broadcast a transaction over several independent paths.
+//!
+//! A check-in or heir claim that never reaches the network is as bad as one
+//! that was never signed, and a single Electrum server can be down, lagging
+//! or censoring. Besides the Electrum pool, the raw transaction can be
+//! POSTed to an Esplora-style HTTP endpoint (`POST /tx` with a hex body, as
+//! served by mempool.space). The HTTP path is opt-in: it hands the
+//! transaction and the user's IP to whoever runs the endpoint. The
+//! broadcast succeeds if any path accepts it.
+
+use crate::state::AppState;
+use bitcoin::consensus::encode::serialize_hex;
+use bitcoin::{Transaction, Txid};
+use nostring_electrum::BroadcastOutcome;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Time allowed for the HTTP endpoint to answer
+const HTTP_BROADCAST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What one broadcast path answered.
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastPathResult {
+    /// `electrum`, or the HTTP endpoint's URL
+    pub path: String,
+    /// Accepted, or already known to that path
+    pub accepted: bool,
+    pub message: String,
+}
+
+/// Aggregated result of broadcasting over every path.
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastReport {
+    pub txid: Txid,
+    pub paths: Vec<BroadcastPathResult>,
+}
+
+impl BroadcastReport {
+    /// Whether at least one path accepted the transaction.
+    pub fn accepted(&self) -> bool {
+        self.paths.iter().any(|p| p.accepted)
+    }
+
+    /// Every rejection, one per path, for the error shown when none accepted.
+    pub fn errors(&self) -> String {
+        self.paths
+            .iter()
+            .filter(|p| !p.accepted)
+            .map(|p| format!("{}: {}", p.path, p.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// The txid if any path accepted, otherwise every rejection.
+    pub fn result(&self) -> Result<Txid, String> {
+        if self.accepted() {
+            Ok(self.txid)
+        } else {
+            Err(self.errors())
+        }
+    }
+}
+
+fn path_result(path: &str, outcome: Result<BroadcastOutcome, String>) -> BroadcastPathResult {
+    let (accepted, message) = match outcome {
+        Ok(BroadcastOutcome::Accepted(txid)) => (true, format!("accepted {}", txid)),
+        Ok(BroadcastOutcome::AlreadyKnown(txid)) => (true, format!("already knew {}", txid)),
+        Err(e) => (false, e),
+    };
+    if accepted {
+        log::info!("Broadcast via {}: {}", path, message);
+    } else {
+        log::warn!("Broadcast via {} failed: {}", path, message);
+    }
+    BroadcastPathResult {
+        path: path.to_string(),
+        accepted,
+        message,
+    }
+}
+
+/// Broadcast `tx` through `electrum` and, when set, the HTTP endpoint.
+///
+/// Every path is tried even after one accepts, so the transaction
+/// propagates from more than one place.
+pub async fn broadcast_multi(
+    tx: &Transaction,
+    electrum: impl FnOnce() -> Result<BroadcastOutcome, String>,
+    http_url: Option<&str>,
+) -> BroadcastReport {
+    let mut paths = vec![path_result("electrum", electrum())];
+    if let Some(url) = http_url {
+        paths.push(path_result(url, http_broadcast(url, tx).await));
+    }
+    BroadcastReport {
+        txid: tx.compute_txid(),
+        paths,
+    }
+}
+
+/// Broadcast `tx` through the app's Electrum pool and configured HTTP
+/// endpoint.
+pub async fn broadcast_tx(state: &AppState, tx: &Transaction) -> BroadcastReport {
+    let http_url = state.http_broadcast_url();
+    let electrum = || {
+        state
+            .electrum_client()
+            .and_then(|client| client.broadcast_idempotent(tx))
+            .map_err(|e| e.to_string())
+    };
+    broadcast_multi(tx, electrum, http_url.as_deref()).await
+}
+
+/// `POST` the raw transaction hex to an Esplora-style endpoint.
+async fn http_broadcast(url: &str, tx: &Transaction) -> Result<BroadcastOutcome, String> {
+    let txid = tx.compute_txid();
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(HTTP_BROADCAST_TIMEOUT)
+        .header("content-type", "text/plain")
+        .body(serialize_hex(tx))
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if status.is_success() {
+        return Ok(BroadcastOutcome::Accepted(txid));
+    }
+    // Esplora relays bitcoind's rejection text, "already known" included
+    nostring_electrum::broadcast_error_outcome(txid, &body)
+        .map_err(|_| format!("HTTP {}: {}", status, body.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Amount, ScriptBuf, TxOut};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn test_tx() -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    /// Answer every request with `status` and `body`, recording each request.
+    async fn spawn_endpoint(status: &str, body: &str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let response = format!(
+            "HTTP/1.1 {}\r\ncontent-length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/tx", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let r = Arc::clone(&requests);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let request = read_request(&mut socket).await;
+                r.lock().unwrap().push(request);
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    /// Read headers plus `content-length` bytes of body.
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|l| {
+                        let (name, value) = l.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if buf.len() >= end + 4 + length {
+                    return text;
+                }
+            }
+        }
+        String::from_utf8_lossy(&buf).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_http_accepts_when_electrum_fails() {
+        let (url, requests) = spawn_endpoint("200 OK", "").await;
+        let tx = test_tx();
+
+        let report =
+            broadcast_multi(&tx, || Err("connection refused".to_string()), Some(&url)).await;
+
+        assert!(report.accepted());
+        assert_eq!(report.result(), Ok(tx.compute_txid()));
+        assert_eq!(report.paths.len(), 2);
+        assert!(!report.paths[0].accepted);
+        assert!(report.paths[1].accepted);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("POST /api/tx "));
+        assert!(requests[0].ends_with(&serialize_hex(&tx)));
+    }
+
+    #[tokio::test]
+    async fn test_all_paths_rejecting_fails() {
+        let (url, _) = spawn_endpoint("400 Bad Request", "bad-txns-in-belowout").await;
+        let tx = test_tx();
+
+        let report = broadcast_multi(&tx, || Err("electrum down".to_string()), Some(&url)).await;
+
+        assert!(!report.accepted());
+        let errors = report.result().unwrap_err();
+        assert!(errors.contains("electrum: electrum down"));
+        assert!(errors.contains("HTTP 400"));
+    }
+
+    #[tokio::test]
+    async fn test_http_already_known_counts_as_accepted() {
+        let body = "sendrawtransaction RPC error: {\"code\":-27,\"message\":\"Transaction already in block chain\"}";
+        let (url, _) = spawn_endpoint("400 Bad Request", body).await;
+        let tx = test_tx();
+
+        let report = broadcast_multi(&tx, || Err("electrum down".to_string()), Some(&url)).await;
+
+        assert!(report.accepted());
+        assert!(report.paths[1].message.starts_with("already knew"));
+    }
+
+    #[tokio::test]
+    async fn test_electrum_only_without_http_url() {
+        let tx = test_tx();
+        let txid = tx.compute_txid();
+
+        let report = broadcast_multi(&tx, || Ok(BroadcastOutcome::Accepted(txid)), None).await;
+
+        assert!(report.accepted());
+        assert_eq!(report.paths.len(), 1);
+        assert_eq!(report.paths[0].path, "electrum");
+    }
+}
@@ -0,0 +1,183 @@
+//! Cancellation for long-running commands.
+//!
+//! Commands that talk to Electrum or Nostr relays take an optional `op_id`.
+//! While they run, a [`CancellationToken`] is registered under that id in
+//! [`AppState::operations`], and `cancel_operation(op_id)` trips it. The
+//! command checks the token at each await point and returns
+//! [`Cancelled`] straight away, so the UI's "Cancel" button works instead of
+//! leaving a spinner up until the network times out.
+//!
+//! Blocking Electrum calls run on the blocking pool; cancelling abandons
+//! the call rather than interrupting it, and its result is discarded.
+
+use crate::commands::CommandResult;
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::State;
+use tokio_util::sync::CancellationToken;
+
+/// The operation was cancelled by the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Tokens of the operations currently running, by `op_id`
+#[derive(Debug, Default)]
+pub struct OperationRegistry {
+    /// `op_id` → (registration number, token)
+    tokens: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    next: AtomicU64,
+}
+
+impl OperationRegistry {
+    /// Register an operation; it is unregistered when the guard drops.
+    ///
+    /// Without an `op_id` the operation can't be cancelled, but the same
+    /// code path runs.
+    pub fn begin(&self, op_id: Option<String>) -> OperationGuard<'_> {
+        let token = CancellationToken::new();
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        if let Some(id) = &op_id {
+            let previous = self
+                .tokens
+                .lock()
+                .unwrap()
+                .insert(id.clone(), (seq, token.clone()));
+            if previous.is_some() {
+                log::warn!("Operation id {} reused while still running", id);
+            }
+        }
+        OperationGuard {
+            registry: self,
+            op_id,
+            seq,
+            token,
+        }
+    }
+
+    /// Cancel a running operation. Returns false if none has that id.
+    pub fn cancel(&self, op_id: &str) -> bool {
+        match self.tokens.lock().unwrap().get(op_id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A registered operation, unregistered on drop
+pub struct OperationGuard<'a> {
+    registry: &'a OperationRegistry,
+    op_id: Option<String>,
+    seq: u64,
+    token: CancellationToken,
+}
+
+impl OperationGuard<'_> {
+    /// Run `fut`, giving up as soon as the operation is cancelled.
+    pub async fn run<F: Future>(&self, fut: F) -> Result<F::Output, Cancelled> {
+        tokio::select! {
+            biased;
+            _ = self.token.cancelled() => Err(Cancelled),
+            output = fut => Ok(output),
+        }
+    }
+
+    /// Run a blocking closure (e.g. Electrum calls) on the blocking pool,
+    /// giving up as soon as the operation is cancelled.
+    pub async fn run_blocking<T, F>(&self, f: F) -> Result<T, Cancelled>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        match self.run(tokio::task::spawn_blocking(f)).await? {
+            Ok(output) => Ok(output),
+            // The closure panicked; surface it like the inline call would
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// Whether the operation has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = &self.op_id {
+            let mut tokens = self.registry.tokens.lock().unwrap();
+            // Only remove our own token if the id was reused meanwhile
+            if tokens.get(id).is_some_and(|(seq, _)| *seq == self.seq) {
+                tokens.remove(id);
+            }
+        }
+    }
+}
+
+/// Cancel a running command started with `op_id`.
+///
+/// Returns false if no operation with that id is running (e.g. it already
+/// finished).
+#[tauri::command]
+pub async fn cancel_operation(
+    op_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<bool>, ()> {
+    Ok(CommandResult::ok(state.operations.cancel(&op_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_cancelled_operation_returns_promptly() {
+        let registry = OperationRegistry::default();
+        let op = registry.begin(Some("refresh-1".into()));
+
+        let canceller = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(registry.cancel("refresh-1"));
+        };
+        let started = Instant::now();
+        let (result, ()) = tokio::join!(
+            op.run_blocking(|| std::thread::sleep(Duration::from_secs(2))),
+            canceller
+        );
+
+        assert_eq!(result, Err(Cancelled));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(op.is_cancelled());
+        assert_eq!(Cancelled.to_string(), "Operation cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_operation_unregistered_when_done() {
+        let registry = OperationRegistry::default();
+        {
+            let op = registry.begin(Some("fetch".into()));
+            assert_eq!(op.run(async { 7 }).await, Ok(7));
+        }
+        assert!(!registry.cancel("fetch"));
+
+        // Anonymous operations run normally and can't be cancelled
+        let op = registry.begin(None);
+        assert_eq!(op.run_blocking(|| 1 + 1).await, Ok(2));
+        assert!(!registry.cancel(""));
+    }
+}
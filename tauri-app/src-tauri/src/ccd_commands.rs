@@ -6,13 +6,16 @@
 //! - Check-in PSBT generation (key-path spend to reset timelocks)
 //! - Heartbeat status evaluation (deadman switch monitoring)
 
+use crate::audit::AuditAction;
 use crate::db;
 use crate::state::AppState;
 use bitcoin::secp256k1::PublicKey;
 use nostring_ccd::register_cosigner_with_chain_code;
 use nostring_ccd::types::ChainCode;
+use nostring_electrum::ElectrumClient;
+use nostring_inherit::checkin::ChainPoint;
 use nostring_inherit::heartbeat::{evaluate_heartbeat, HeartbeatConfig};
-use nostring_inherit::policy::Timelock;
+use nostring_inherit::policy::{Timelock, TimelockUnit};
 use nostring_inherit::taproot::create_inheritable_vault;
 use nostring_inherit::taproot_checkin::{build_taproot_checkin_psbt, TaprootCheckinConfig};
 use serde::{Deserialize, Serialize};
@@ -127,7 +130,7 @@ pub async fn get_ccd_load_error(state: State<'_, AppState>) -> Result<Option<Str
 const DEFAULT_FEE_RATE: f64 = 10.0;
 
 /// Estimate fee rate from Electrum, with fallback.
-fn estimate_fee_rate(client: &nostring_electrum::ElectrumClient) -> f64 {
+fn estimate_fee_rate(client: &ElectrumClient) -> f64 {
     // Target 6 blocks (~1 hour) for check-in — not urgent
     client
         .estimate_fee_rate(6)
@@ -239,7 +242,7 @@ pub async fn create_ccd_vault(
         }
     };
 
-    let owner_xpub = match bitcoin::bip32::Xpub::from_str(&owner_xpub_str) {
+    let owner_xpub = match crate::state::owner_account_xpub(&owner_xpub_str) {
         Ok(x) => x,
         Err(e) => return Ok(CcdResult::err(format!("Invalid owner xpub: {}", e))),
     };
@@ -334,11 +337,8 @@ pub async fn build_checkin_psbt(state: State<'_, AppState>) -> Result<CcdResult<
         }
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
     // Connect to Electrum
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => {
             return Ok(CcdResult::err(format!(
@@ -398,6 +398,22 @@ pub async fn build_checkin_psbt(state: State<'_, AppState>) -> Result<CcdResult<
     }
 }
 
+/// The block at `height`, as `timelock` needs it.
+///
+/// Time-based timelocks run on block timestamps, so only they cost a
+/// header lookup; block-based ones leave `time` at 0.
+pub(crate) fn chain_point(
+    client: &ElectrumClient,
+    height: u32,
+    timelock: &Timelock,
+) -> Result<ChainPoint, nostring_electrum::Error> {
+    let time = match timelock.unit() {
+        TimelockUnit::Blocks => 0,
+        TimelockUnit::Time => client.get_block_time(height)? as u64,
+    };
+    Ok(ChainPoint { height, time })
+}
+
 /// Get heartbeat status for the current vault.
 ///
 /// Evaluates the vault's timelock against the current blockchain height
@@ -414,11 +430,8 @@ pub async fn get_heartbeat_status(
         }
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
     // Get current block height
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => {
             return Ok(CcdResult::err(format!(
@@ -447,21 +460,27 @@ pub async fn get_heartbeat_status(
         ));
     }
 
-    let config = HeartbeatConfig::default();
-    let status = evaluate_heartbeat(&vault, last_checkin_height, current_height, &config);
+    let (utxo, current) = match (
+        chain_point(&client, last_checkin_height, &vault.timelock),
+        chain_point(&client, current_height, &vault.timelock),
+    ) {
+        (Ok(utxo), Ok(current)) => (utxo, current),
+        (Err(e), _) | (_, Err(e)) => {
+            return Ok(CcdResult::err(format!("Failed to get block time: {}", e)))
+        }
+    };
 
-    let timelock_blocks = vault.timelock.blocks() as u64;
-    let expiry = last_checkin_height as u64 + timelock_blocks;
-    let remaining = expiry as i64 - current_height as i64;
-    let days = remaining as f64 * 10.0 / 1440.0; // ~10 min per block
+    let config = HeartbeatConfig::default();
+    let status = evaluate_heartbeat(&vault, utxo, current, &config);
+    let timelock_status = &status.timelock_status;
 
     let action_str = format!("{:?}", status.action);
 
     Ok(CcdResult::ok(HeartbeatInfo {
         current_block: current_height as u64,
-        expiry_block: expiry,
-        blocks_remaining: remaining,
-        days_remaining: days,
+        expiry_block: timelock_status.unlock_height as u64,
+        blocks_remaining: timelock_status.blocks_remaining as i64,
+        days_remaining: timelock_status.seconds_remaining as f64 / 86_400.0,
         action: action_str,
         elapsed_fraction: status.elapsed_fraction,
     }))
@@ -510,11 +529,8 @@ pub async fn start_signing_session(
         Err(e) => return Ok(CcdResult::err(format!("Tweak computation failed: {}", e))),
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
     // Find vault UTXOs to determine num_inputs
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => return Ok(CcdResult::err(format!("Electrum connection failed: {}", e))),
     };
@@ -796,10 +812,8 @@ pub async fn finalize_and_broadcast(
     };
 
     // Broadcast
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
 
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => return Ok(CcdResult::err(format!("Electrum connection failed: {}", e))),
     };
@@ -816,9 +830,10 @@ pub async fn finalize_and_broadcast(
     }
 
     // Log the check-in
-    state.log_checkin(&txid.to_string());
+    state.log_checkin(&signed_tx);
 
     log::info!("MuSig2 check-in broadcast: {}", txid);
+    state.audit(AuditAction::Broadcast, &format!("MuSig2 check-in {}", txid));
 
     Ok(CcdResult::ok(txid.to_string()))
 }
@@ -7,14 +7,21 @@
 //! Every mutation writes through to SQLite via `AppState` helpers so
 //! state survives app restarts.
 
-use crate::state::{AppState, PolicyStatus};
+use crate::audit::AuditAction;
+use crate::fees::FeeStrategy;
+use crate::state::{AppState, PolicyStatus, HEIR_CLAIM_MIN_CONFIDENCE_KEY};
+use crate::units::{format_amount, DisplayUnit};
 use bitcoin::psbt::Psbt;
-use nostring_core::crypto::{decrypt_seed, encrypt_seed, EncryptedSeed};
-use nostring_core::seed::{derive_seed, generate_mnemonic, parse_mnemonic, WordCount};
+use nostring_core::crypto::{
+    decrypt_bytes, decrypt_seed, encrypt_bytes, encrypt_seed, EncryptedSeed,
+};
+use nostring_core::seed::{
+    derive_seed, generate_mnemonic, language_from_name, parse_mnemonic, Language, WordCount,
+};
 use nostring_electrum::ElectrumClient;
 use serde::{Deserialize, Serialize};
 use tauri::State;
-use zeroize::Zeroize;
+use zeroize::Zeroizing;
 
 /// Password hash for watch-only mode (protects local DB metadata).
 /// Uses iterated HMAC-SHA256 (10,000 rounds) with random salt.
@@ -109,8 +116,14 @@ impl<T> CommandResult<T> {
 // ============================================================================
 
 /// Generate a new BIP-39 mnemonic
+///
+/// `language` names the wordlist (e.g. "spanish", "japanese"); English by
+/// default. Imports detect the language from the words.
 #[tauri::command]
-pub async fn create_seed(word_count: Option<usize>) -> CommandResult<String> {
+pub async fn create_seed(
+    word_count: Option<usize>,
+    language: Option<String>,
+) -> CommandResult<String> {
     let wc = match word_count.unwrap_or(24) {
         12 => WordCount::Words12,
         15 => WordCount::Words15,
@@ -120,7 +133,15 @@ pub async fn create_seed(word_count: Option<usize>) -> CommandResult<String> {
         _ => return CommandResult::err("Word count must be 12, 15, 18, 21, or 24"),
     };
 
-    match generate_mnemonic(wc) {
+    let language = match language.as_deref() {
+        Some(name) => match language_from_name(name) {
+            Ok(l) => l,
+            Err(e) => return CommandResult::err(e.to_string()),
+        },
+        None => Language::English,
+    };
+
+    match generate_mnemonic(wc, language) {
         Ok(mnemonic) => CommandResult::ok(mnemonic.to_string()),
         Err(e) => CommandResult::err(format!("Failed to generate mnemonic: {}", e)),
     }
@@ -164,46 +185,62 @@ pub struct PasswordStrengthResult {
 /// Import and encrypt a seed (persisted to SQLite)
 #[tauri::command]
 pub async fn import_seed(
-    mut mnemonic: String,
-    mut password: String,
+    mnemonic: String,
+    password: String,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<bool>, ()> {
-    let parsed = match parse_mnemonic(&mnemonic) {
+    // Both are wiped when dropped, including on early returns
+    let mnemonic = Zeroizing::new(mnemonic);
+    let password = Zeroizing::new(password);
+
+    let parsed = match parse_mnemonic(mnemonic.as_str()) {
         Ok(m) => m,
         Err(e) => {
-            // Zeroize sensitive inputs before returning
-            mnemonic.zeroize();
-            password.zeroize();
             return Ok(CommandResult::err(format!("Invalid mnemonic: {}", e)));
         }
     };
 
-    // Zeroize the mnemonic string — we have the parsed form now
-    mnemonic.zeroize();
+    // Wipe the mnemonic string now — we have the parsed form
+    drop(mnemonic);
 
     // derive_seed returns Zeroizing<[u8; 64]> — auto-zeroized on drop
     let seed = derive_seed(&parsed, "");
+    let entropy = zeroize::Zeroizing::new(parsed.to_entropy());
 
-    let result = match encrypt_seed(&seed, &password) {
-        Ok(encrypted) => {
-            let encrypted_bytes = encrypted.to_bytes();
-
-            // Write-through: memory + SQLite
-            state.set_encrypted_seed(encrypted_bytes);
-            state.set_watch_only(false);
-
-            let mut unlocked = state.unlocked.lock().unwrap();
-            *unlocked = true;
+    Ok(
+        match store_wallet_seed(&state, &seed, Some(&entropy[..]), password.as_str()) {
+            Ok(()) => CommandResult::ok(true),
+            Err(e) => CommandResult::err(e),
+        },
+    )
+}
 
-            Ok(CommandResult::ok(true))
-        }
-        Err(e) => Ok(CommandResult::err(format!("Failed to encrypt seed: {}", e))),
-    };
+/// Encrypt and store the wallet seed, and unlock the wallet.
+///
+/// `entropy` is the mnemonic's BIP-39 entropy when the words are known;
+/// seed shares are split from it. Without it, any entropy stored for a
+/// previous seed is forgotten.
+fn store_wallet_seed(
+    state: &AppState,
+    seed: &[u8; 64],
+    entropy: Option<&[u8]>,
+    password: &str,
+) -> Result<(), String> {
+    let encrypted =
+        encrypt_seed(seed, password).map_err(|e| format!("Failed to encrypt seed: {}", e))?;
+    let encrypted_entropy = entropy
+        .map(|entropy| encrypt_bytes(entropy, password))
+        .transpose()
+        .map_err(|e| format!("Failed to encrypt seed: {}", e))?;
 
-    // Zeroize password after use (seed is auto-zeroized via Zeroizing wrapper)
-    password.zeroize();
+    // Write-through: memory + SQLite
+    state.set_encrypted_seed(encrypted.to_bytes());
+    state.set_encrypted_entropy(encrypted_entropy.as_deref());
+    state.set_watch_only(false);
 
-    result
+    let mut unlocked = state.unlocked.lock().unwrap();
+    *unlocked = true;
+    Ok(())
 }
 
 /// Import a watch-only wallet (xpub only, no private keys).
@@ -212,7 +249,7 @@ pub async fn import_seed(
 #[tauri::command]
 pub async fn import_watch_only(
     xpub: String,
-    mut password: String,
+    password: String,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<bool>, ()> {
     if !xpub.starts_with("xpub")
@@ -227,8 +264,7 @@ pub async fn import_watch_only(
     }
 
     // Store password hash for unlock verification
-    let pw_hash = hash_password(&password);
-    password.zeroize();
+    let pw_hash = hash_password(Zeroizing::new(password).as_str());
 
     state.set_owner_xpub(&xpub);
     state.set_watch_only(true);
@@ -240,6 +276,171 @@ pub async fn import_watch_only(
     Ok(CommandResult::ok(true))
 }
 
+/// Account key extracted from a hardware wallet export
+#[derive(Debug, Clone, PartialEq)]
+struct HardwareExport {
+    fingerprint: Fingerprint,
+    derivation_path: DerivationPath,
+    xpub: Xpub,
+}
+
+impl HardwareExport {
+    /// The account key as a `[fingerprint/path]xpub` key expression, which
+    /// keeps the origin when stored as the owner key.
+    fn key_expression(&self) -> String {
+        let path = self.derivation_path.to_string();
+        let path = path.trim_start_matches('m').trim_start_matches('/');
+        if path.is_empty() {
+            format!("[{}]{}", self.fingerprint, self.xpub)
+        } else {
+            format!("[{}/{}]{}", self.fingerprint, path, self.xpub)
+        }
+    }
+}
+
+/// Account sections of a Coldcard generic export, in order of preference
+const COLDCARD_ACCOUNT_SECTIONS: &[&str] = &["bip84", "bip48_2", "bip86", "bip49", "bip44"];
+
+/// Parse a hardware wallet export into its account key.
+///
+/// Accepts Coldcard's generic wallet export (JSON with `xfp` and per-script
+/// `bipNN` sections) or a descriptor; for descriptors the first key with
+/// an origin is used.
+fn parse_hardware_export(data: &str) -> Result<HardwareExport, String> {
+    let data = data.trim();
+    if data.starts_with('{') {
+        parse_coldcard_export(data)
+    } else {
+        parse_descriptor_export(data)
+    }
+}
+
+fn parse_coldcard_export(data: &str) -> Result<HardwareExport, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(data).map_err(|e| format!("Invalid export JSON: {}", e))?;
+
+    let (name, section) = COLDCARD_ACCOUNT_SECTIONS
+        .iter()
+        .find_map(|name| json.get(*name).map(|section| (*name, section)))
+        .ok_or("Export has no supported account section (bip84, bip48_2, bip86, bip49, bip44)")?;
+
+    let field = |key: &str| {
+        section
+            .get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Export section '{}' is missing '{}'", name, key))
+    };
+
+    // Sections may carry their own xfp; fall back to the top-level one
+    let xfp = match section.get("xfp").and_then(|v| v.as_str()) {
+        Some(xfp) => xfp,
+        None => json
+            .get("xfp")
+            .and_then(|v| v.as_str())
+            .ok_or("Export is missing the master fingerprint 'xfp'")?,
+    };
+    let fingerprint = Fingerprint::from_str(xfp)
+        .map_err(|e| format!("Invalid master fingerprint '{}': {}", xfp, e))?;
+
+    let deriv = field("deriv")?;
+    let derivation_path = DerivationPath::from_str(deriv)
+        .map_err(|e| format!("Invalid derivation path '{}': {}", deriv, e))?;
+
+    // `xpub` is always in xpub/tpub encoding; `_pub` holds the SLIP-132 form
+    let xpub = Xpub::from_str(field("xpub")?).map_err(|e| format!("Invalid xpub: {}", e))?;
+
+    Ok(HardwareExport {
+        fingerprint,
+        derivation_path,
+        xpub,
+    })
+}
+
+fn parse_descriptor_export(data: &str) -> Result<HardwareExport, String> {
+    use miniscript::descriptor::{DescriptorPublicKey, DescriptorXKey};
+    use miniscript::{Descriptor, ForEachKey};
+
+    let origin_of = |key: &DescriptorPublicKey| -> Option<HardwareExport> {
+        let from_xkey = |xkey: &DescriptorXKey<Xpub>| {
+            xkey.origin
+                .as_ref()
+                .map(|(fingerprint, path)| HardwareExport {
+                    fingerprint: *fingerprint,
+                    derivation_path: path.clone(),
+                    xpub: xkey.xkey,
+                })
+        };
+        match key {
+            DescriptorPublicKey::XPub(xkey) => from_xkey(xkey),
+            DescriptorPublicKey::MultiXPub(multi) => {
+                multi
+                    .origin
+                    .as_ref()
+                    .map(|(fingerprint, path)| HardwareExport {
+                        fingerprint: *fingerprint,
+                        derivation_path: path.clone(),
+                        xpub: multi.xkey,
+                    })
+            }
+            DescriptorPublicKey::Single(_) => None,
+        }
+    };
+
+    // A bare key expression, e.g. `[d34db33f/84'/0'/0']xpub...`
+    if data.starts_with('[') {
+        let key = DescriptorPublicKey::from_str(data).map_err(|e| format!("Invalid key: {}", e))?;
+        return origin_of(&key).ok_or_else(|| "Key has no extended public key".to_string());
+    }
+
+    nostring_inherit::policy::validate_checksum(data)
+        .map_err(|e| format!("Invalid descriptor: {}", e))?;
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(data)
+        .map_err(|e| format!("Invalid descriptor: {}", e))?;
+
+    let mut found = None;
+    descriptor.for_each_key(|key| {
+        if found.is_none() {
+            found = origin_of(key);
+        }
+        true
+    });
+    found.ok_or_else(|| "Descriptor has no key with a fingerprint and derivation path".to_string())
+}
+
+/// Import a watch-only wallet from a hardware wallet export.
+///
+/// Takes a Coldcard generic wallet export (JSON) or a descriptor and stores
+/// the account key with its origin (`[fingerprint/path]xpub`), so the
+/// derivation the device uses is preserved. Returns the stored key.
+#[tauri::command]
+pub async fn import_hardware_export(
+    json_or_descriptor: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<String>, ()> {
+    let export = match parse_hardware_export(&json_or_descriptor) {
+        Ok(export) => export,
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
+
+    let network = *state.network.lock().unwrap();
+    if let Err(msg) = check_xpub_network(&export.xpub, network) {
+        return Ok(CommandResult::err(msg));
+    }
+
+    let key = export.key_expression();
+    let pw_hash = hash_password(Zeroizing::new(password).as_str());
+
+    state.set_owner_xpub(&key);
+    state.set_watch_only(true);
+    state.persist_config("password_hash", &pw_hash);
+
+    let mut unlocked = state.unlocked.lock().unwrap();
+    *unlocked = true;
+
+    Ok(CommandResult::ok(key))
+}
+
 /// Check if a wallet is configured (seed **or** watch-only xpub).
 #[tauri::command]
 pub async fn has_seed(state: State<'_, AppState>) -> Result<bool, ()> {
@@ -265,9 +466,11 @@ pub async fn is_watch_only(state: State<'_, AppState>) -> Result<bool, ()> {
 /// Unlock (decrypt) the seed with password, or verify password for watch-only.
 #[tauri::command]
 pub async fn unlock_seed(
-    mut password: String,
+    password: String,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<bool>, ()> {
+    let password = Zeroizing::new(password);
+
     // Check watch-only mode first
     let is_wo = *state.watch_only.lock().unwrap();
     if is_wo {
@@ -276,13 +479,16 @@ pub async fn unlock_seed(
         let stored_hash = crate::db::config_get(&conn, "password_hash").ok().flatten();
         drop(conn);
 
-        let result = match stored_hash {
+        return match stored_hash {
             Some(hash) => {
-                if verify_password_hash(&password, &hash) {
+                if verify_password_hash(password.as_str(), &hash) {
                     let mut unlocked = state.unlocked.lock().unwrap();
                     *unlocked = true;
+                    drop(unlocked);
+                    state.audit(AuditAction::SeedUnlock, "watch-only");
                     Ok(CommandResult::ok(true))
                 } else {
+                    state.audit(AuditAction::SeedUnlockFailed, "watch-only");
                     Ok(CommandResult::err("Incorrect password"))
                 }
             }
@@ -290,18 +496,17 @@ pub async fn unlock_seed(
                 // Legacy watch-only without password hash — auto-unlock
                 let mut unlocked = state.unlocked.lock().unwrap();
                 *unlocked = true;
+                drop(unlocked);
+                state.audit(AuditAction::SeedUnlock, "watch-only, no password set");
                 Ok(CommandResult::ok(true))
             }
         };
-
-        password.zeroize();
-        return result;
     }
 
     // Seed-based: decrypt to verify password
     let seed_lock = state.encrypted_seed.lock().unwrap();
 
-    let result = match &*seed_lock {
+    match &*seed_lock {
         None => Ok(CommandResult::err("No wallet configured")),
         Some(encrypted_bytes) => {
             let encrypted = match EncryptedSeed::from_bytes(encrypted_bytes) {
@@ -309,20 +514,23 @@ pub async fn unlock_seed(
                 Err(_) => return Ok(CommandResult::err("Corrupted seed data")),
             };
 
-            match decrypt_seed(&encrypted, &password) {
+            match decrypt_seed(&encrypted, password.as_str()) {
                 Ok(_decrypted_seed) => {
                     drop(seed_lock);
                     let mut unlocked = state.unlocked.lock().unwrap();
                     *unlocked = true;
+                    drop(unlocked);
+                    state.audit(AuditAction::SeedUnlock, "seed");
                     Ok(CommandResult::ok(true))
                 }
-                Err(_) => Ok(CommandResult::err("Incorrect password")),
+                Err(_) => {
+                    drop(seed_lock);
+                    state.audit(AuditAction::SeedUnlockFailed, "seed");
+                    Ok(CommandResult::err("Incorrect password"))
+                }
             }
         }
-    };
-
-    password.zeroize();
-    result
+    }
 }
 
 /// Lock the wallet (clear unlocked state — ephemeral only, no DB change)
@@ -333,6 +541,94 @@ pub async fn lock_wallet(state: State<'_, AppState>) -> Result<(), ()> {
     Ok(())
 }
 
+/// Export the encrypted seed as a QR payload for moving to another install.
+///
+/// Requires the wallet to be unlocked and the password to be re-entered.
+/// The payload is the seed re-encrypted under the same password (fresh salt
+/// and nonce) — never plaintext.
+#[tauri::command]
+pub async fn export_seed_qr(
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<String>, ()> {
+    let password = Zeroizing::new(password);
+    if !*state.unlocked.lock().unwrap() {
+        return Ok(CommandResult::err("Wallet is locked. Unlock it first."));
+    }
+
+    let encrypted = {
+        let seed_lock = state.encrypted_seed.lock().unwrap();
+        match &*seed_lock {
+            Some(bytes) => match EncryptedSeed::from_bytes(bytes) {
+                Ok(e) => e,
+                Err(_) => return Ok(CommandResult::err("Corrupted seed data")),
+            },
+            None => {
+                return Ok(CommandResult::err(
+                    "No seed to export (watch-only wallets have no seed)",
+                ));
+            }
+        }
+    };
+
+    let result = match decrypt_seed(&encrypted, password.as_str()) {
+        Ok(seed) => match nostring_core::seed::encrypt_seed_qr(&seed, password.as_str()) {
+            Ok(payload) => CommandResult::ok(payload),
+            Err(e) => CommandResult::err(format!("Failed to export seed: {}", e)),
+        },
+        Err(_) => CommandResult::err("Incorrect password"),
+    };
+
+    Ok(result)
+}
+
+/// Import a seed from a QR payload (persisted to SQLite).
+///
+/// Accepts either a NoString encrypted seed QR (decrypted with `password`)
+/// or a Standard SeedQR digit string. Either way the seed is stored
+/// encrypted under `password`.
+#[tauri::command]
+pub async fn import_seed_qr(
+    payload: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<bool>, ()> {
+    use nostring_core::seed::{decrypt_seed_qr, is_encrypted_seed_qr, seedqr_to_mnemonic};
+
+    let payload = Zeroizing::new(payload);
+    let password = Zeroizing::new(password);
+
+    // An encrypted seed QR carries only the seed; a SeedQR has the words
+    let seed = if is_encrypted_seed_qr(payload.as_str()) {
+        decrypt_seed_qr(payload.as_str(), password.as_str()).map(|seed| (seed, None))
+    } else {
+        seedqr_to_mnemonic(payload.as_str()).map(|m| {
+            (
+                derive_seed(&m, ""),
+                Some(zeroize::Zeroizing::new(m.to_entropy())),
+            )
+        })
+    };
+    drop(payload);
+
+    let (seed, entropy) = match seed {
+        Ok(s) => s,
+        Err(e) => return Ok(CommandResult::err(format!("Invalid seed QR: {}", e))),
+    };
+
+    Ok(
+        match store_wallet_seed(
+            &state,
+            &seed,
+            entropy.as_deref().map(|e| &e[..]),
+            password.as_str(),
+        ) {
+            Ok(()) => CommandResult::ok(true),
+            Err(e) => CommandResult::err(e),
+        },
+    )
+}
+
 // ============================================================================
 // Service Key Commands (Notification Identity)
 // ============================================================================
@@ -353,6 +649,101 @@ pub async fn generate_service_key(state: State<'_, AppState>) -> Result<CommandR
     Ok(CommandResult::ok(npub))
 }
 
+/// Outcome of rotating the service key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceKeyRotation {
+    pub old_npub: String,
+    pub new_npub: String,
+    pub migration_event_id: String,
+}
+
+/// Replace a (possibly compromised) service key.
+///
+/// The old key signs a migration event naming the new one, published to
+/// the default relays, so heirs who only know the old npub can follow it.
+/// The old npub stays recorded as retired. Nothing changes if no relay
+/// accepts the migration event.
+#[tauri::command]
+pub async fn rotate_service_key(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<ServiceKeyRotation>, ()> {
+    use nostring_notify::nostr_relay;
+
+    Ok(
+        match rotate_service_key_with(&state, |event| async move {
+            nostr_relay::publish_key_migration(&event, &[])
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+        {
+            Ok(rotation) => CommandResult::ok(rotation),
+            Err(e) => CommandResult::err(e),
+        },
+    )
+}
+
+/// Body of [`rotate_service_key`]; `publish` sends the migration event and
+/// returns its id.
+async fn rotate_service_key_with<F, Fut>(
+    state: &AppState,
+    publish: F,
+) -> Result<ServiceKeyRotation, String>
+where
+    F: FnOnce(nostr_sdk::Event) -> Fut,
+    Fut: std::future::Future<Output = Result<String, String>>,
+{
+    use nostr_sdk::prelude::*;
+
+    {
+        let unlocked = state.unlocked.lock().unwrap();
+        if !*unlocked {
+            return Err("Wallet is locked".into());
+        }
+    }
+    let Some(old_secret) = state.service_secret() else {
+        return Err("No service key generated".into());
+    };
+    let old_keys =
+        Keys::parse(old_secret.as_str()).map_err(|e| format!("Invalid service key: {}", e))?;
+    let new_keys = Keys::generate();
+
+    let event =
+        nostring_notify::nostr_relay::key_migration_event(&old_keys, &new_keys.public_key())
+            .map_err(|e| e.to_string())?;
+    let migration_event_id = publish(event)
+        .await
+        .map_err(|e| format!("Failed to publish key migration: {}", e))?;
+
+    let old_npub = old_keys.public_key().to_bech32().unwrap_or_default();
+    let new_npub = new_keys.public_key().to_bech32().unwrap_or_default();
+    state.retire_service_key(crate::state::RetiredServiceKey {
+        npub: old_npub.clone(),
+        replaced_by: new_npub.clone(),
+        retired_at: state.clock.now_secs(),
+        migration_event_id: migration_event_id.clone(),
+    });
+    state.set_service_key(&new_keys.secret_key().to_secret_hex(), &new_npub);
+    state.audit(
+        AuditAction::ServiceKeyRotate,
+        &format!("{} -> {}", old_npub, new_npub),
+    );
+
+    Ok(ServiceKeyRotation {
+        old_npub,
+        new_npub,
+        migration_event_id,
+    })
+}
+
+/// Service keys replaced by [`rotate_service_key`], oldest first.
+#[tauri::command]
+pub async fn get_retired_service_keys(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::state::RetiredServiceKey>, ()> {
+    Ok(state.retired_service_keys())
+}
+
 /// Get the service key's npub.
 #[tauri::command]
 pub async fn get_service_npub(state: State<'_, AppState>) -> Result<Option<String>, ()> {
@@ -371,15 +762,175 @@ pub async fn get_policy_status(state: State<'_, AppState>) -> Result<Option<Poli
     Ok(status_lock.clone())
 }
 
+/// Explain a descriptor in plain English: who can spend at any time and
+/// who can spend after which timelock.
+#[tauri::command]
+pub async fn explain_descriptor(
+    descriptor: String,
+) -> CommandResult<nostring_inherit::policy::PolicyExplanation> {
+    match nostring_inherit::policy::explain(&descriptor) {
+        Ok(explanation) => CommandResult::ok(explanation),
+        Err(e) => CommandResult::err(format!("Could not explain descriptor: {}", e)),
+    }
+}
+
 /// Refresh policy status from blockchain
+///
+/// Pass `op_id` to make the refresh cancellable with `cancel_operation`.
 #[tauri::command]
 pub async fn refresh_policy_status(
+    op_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<PolicyStatus>, ()> {
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
+    let op = state.operations.begin(op_id);
+
+    // Watch-only wallets may only have an xpub + heirs; derive the descriptor
+    let config = state.ensure_inheritance_config();
+
+    let connect = state.electrum_connector();
+    let descriptor = config.as_ref().map(|c| c.descriptor.clone());
+    let address_index = state.checkin_address_index();
+    let fetched_at = state.clock.now_secs();
+    let chain = op
+        .run_blocking(move || {
+            let client = connect().map_err(|e| format!("Failed to connect to Electrum: {}", e))?;
+            let block_height = client
+                .get_height()
+                .map_err(|e| format!("Failed to get block height: {}", e))?
+                as u64;
+            let utxos = descriptor.and_then(|d| inheritance_utxos(&client, &d, address_index));
+            Ok::<_, String>(crate::db::ChainSnapshot {
+                block_height,
+                balance_sats: utxos
+                    .as_ref()
+                    .map(|utxos| utxos.iter().map(|u| u.value_sats).sum()),
+                utxos: utxos.unwrap_or_default(),
+                fetched_at,
+            })
+        })
+        .await;
+    let fetched = match chain {
+        Ok(fetched) => fetched,
+        Err(cancelled) => return Ok(CommandResult::err(cancelled.to_string())),
+    };
+
+    let timelock_blocks = config.as_ref().map(|c| c.timelock_blocks);
+    let descriptor = config.as_ref().map(|c| c.descriptor.as_str());
+    match resolve_policy_status(&state, descriptor, timelock_blocks, fetched) {
+        Ok(status) => {
+            *state.policy_status.lock().unwrap() = Some(status.clone());
+            Ok(CommandResult::ok(status))
+        }
+        Err(e) => Ok(CommandResult::err(e)),
+    }
+}
+
+/// Turn a chain fetch into a policy status, caching successful fetches.
+///
+/// The cache is kept per `descriptor`. When the fetch failed, falls back to
+/// the last snapshot cached for it, flagged `stale`; only errors if nothing
+/// was cached. When only the balance
+/// couldn't be fetched, the last known balance is kept and flagged `stale`
+/// as of the fetch that saw it.
+fn resolve_policy_status(
+    state: &AppState,
+    descriptor: Option<&str>,
+    timelock_blocks: Option<u16>,
+    fetched: Result<crate::db::ChainSnapshot, String>,
+) -> Result<PolicyStatus, String> {
+    let descriptor = descriptor.unwrap_or_default();
+    let conn = state.db.lock().unwrap();
+    let (snapshot, stale) = match fetched {
+        Ok(mut snapshot) => {
+            let mut stale = false;
+            if snapshot.balance_sats.is_none() {
+                let cached = crate::db::chain_cache_get(&conn, descriptor).ok().flatten();
+                if let Some(cached) = cached.filter(|c| c.balance_sats.is_some()) {
+                    snapshot.balance_sats = cached.balance_sats;
+                    snapshot.utxos = cached.utxos;
+                    snapshot.fetched_at = cached.fetched_at;
+                    stale = true;
+                }
+            }
+            if let Err(e) = crate::db::chain_cache_put(&conn, descriptor, &snapshot) {
+                log::warn!("Failed to cache chain state: {}", e);
+            }
+            (snapshot, stale)
+        }
+        Err(e) => match crate::db::chain_cache_get(&conn, descriptor).ok().flatten() {
+            Some(cached) => {
+                log::warn!("{}; showing cached chain state", e);
+                (cached, true)
+            }
+            None => return Err(e),
+        },
+    };
+    let last_checkin = crate::db::checkin_last(&conn).ok().flatten();
+    drop(conn);
+
+    Ok(PolicyStatus::from_snapshot(
+        &snapshot,
+        timelock_blocks,
+        last_checkin,
+        state.display_unit(),
+        stale,
+    ))
+}
+
+/// Unspent outputs at the inheritance addresses up to receive index
+/// `through` (funds a rotating check-in may have left behind included), or
+/// `None` if they couldn't be fetched.
+fn inheritance_utxos(
+    client: &ElectrumClient,
+    descriptor: &str,
+    through: u32,
+) -> Option<Vec<crate::db::CachedUtxo>> {
+    use miniscript::descriptor::DescriptorPublicKey;
+    use miniscript::Descriptor;
+
+    let descriptor: Descriptor<DescriptorPublicKey> = descriptor.parse().ok()?;
+    let mut found = Vec::new();
+    for (_, script) in crate::state::checkin_scripts(&descriptor, through) {
+        match client.get_utxos_for_script(&script) {
+            Ok(utxos) => found.extend(utxos.iter().map(|u| crate::db::CachedUtxo {
+                outpoint: u.outpoint.to_string(),
+                value_sats: u.value.to_sat(),
+                height: u.height,
+            })),
+            Err(e) => {
+                log::warn!("Failed to fetch inheritance balance: {}", e);
+                return None;
+            }
+        }
+    }
+    Some(found)
+}
+
+/// Number of recent headers used to calibrate the block interval (~1 day)
+const EXPIRY_CALIBRATION_BLOCKS: u32 = 144;
+
+/// Timelock expiry as both a naive and a header-calibrated calendar estimate
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpiryEstimate {
+    pub current_block: u64,
+    pub expiry_block: u64,
+    pub blocks_remaining: i64,
+    /// Unix timestamp assuming exactly 10-minute blocks
+    pub naive_expiry: i64,
+    /// Unix timestamp using the average interval of recent blocks
+    pub calibrated_expiry: i64,
+    /// Average block interval (seconds) used for the calibrated estimate
+    pub avg_block_secs: f64,
+}
+
+/// Estimate the calendar date at which the heir's timelock expires
+#[tauri::command]
+pub async fn get_expiry_estimate(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<ExpiryEstimate>, ()> {
+    use nostring_inherit::checkin;
 
-    let client = match ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => {
             return Ok(CommandResult::err(format!(
@@ -399,133 +950,389 @@ pub async fn refresh_policy_status(
         }
     };
 
-    let config_lock = state.inheritance_config.lock().unwrap();
-    let (expiry_block, blocks_remaining, days_remaining, urgency) =
-        if let Some(config) = &*config_lock {
-            let timelock = config.timelock_blocks as u64;
-            let expiry = current_block + timelock;
-            let remaining = expiry.saturating_sub(current_block) as i64;
-            let days = remaining as f64 * 10.0 / 60.0 / 24.0;
-
-            let urgency = if remaining > 4320 {
-                "ok"
-            } else if remaining > 1008 {
-                "warning"
-            } else {
-                "critical"
-            };
+    let cached_expiry = state
+        .policy_status
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.expiry_block);
+    let expiry_block = match cached_expiry {
+        Some(expiry) => expiry,
+        None => match &*state.inheritance_config.lock().unwrap() {
+            Some(config) => current_block + config.timelock_blocks as u64,
+            None => return Ok(CommandResult::err("No inheritance policy configured")),
+        },
+    };
 
-            (expiry, remaining, days, urgency.to_string())
-        } else {
-            (current_block + 26280, 26280, 182.5, "ok".to_string())
-        };
-    drop(config_lock);
+    let block_times = client
+        .get_recent_block_times(EXPIRY_CALIBRATION_BLOCKS)
+        .unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to fetch recent headers, using 10-minute blocks: {}",
+                e
+            );
+            Vec::new()
+        });
 
-    // Get last check-in from DB
-    let last_checkin = {
-        let conn = state.db.lock().unwrap();
-        crate::db::checkin_last(&conn).ok().flatten()
-    };
+    let naive = checkin::estimate_expiry_date(current_block as u32, expiry_block as u32, &[]);
+    let calibrated =
+        checkin::estimate_expiry_date(current_block as u32, expiry_block as u32, &block_times);
 
-    let status = PolicyStatus {
+    Ok(CommandResult::ok(ExpiryEstimate {
         current_block,
         expiry_block,
-        blocks_remaining,
-        days_remaining,
-        urgency,
-        last_checkin,
-    };
-
-    let mut status_lock = state.policy_status.lock().unwrap();
-    *status_lock = Some(status.clone());
-
-    Ok(CommandResult::ok(status))
+        blocks_remaining: expiry_block as i64 - current_block as i64,
+        naive_expiry: naive.timestamp(),
+        calibrated_expiry: calibrated.timestamp(),
+        avg_block_secs: checkin::average_block_interval(&block_times)
+            .unwrap_or(checkin::TARGET_BLOCK_SECS),
+    }))
 }
 
 // ============================================================================
 // Check-in Commands
 // ============================================================================
 
+/// Inheritance UTXOs at each receive index up to `through` that holds any,
+/// newest index first. Rotating check-ins and deposits to old addresses
+/// can leave funds below the current check-in index.
+fn funded_checkin_utxos(
+    client: &ElectrumClient,
+    descriptor: &miniscript::Descriptor<miniscript::DescriptorPublicKey>,
+    through: u32,
+) -> Result<Vec<(u32, Vec<nostring_inherit::checkin::InheritanceUtxo>)>, String> {
+    use nostring_inherit::checkin::InheritanceUtxo as InhUtxo;
+
+    let mut funded = Vec::new();
+    for (index, script) in crate::state::checkin_scripts(descriptor, through)
+        .into_iter()
+        .rev()
+    {
+        let utxos = client
+            .get_utxos_for_script(&script)
+            .map_err(|e| format!("Failed to get UTXOs: {}", e))?;
+        if !utxos.is_empty() {
+            let utxos = utxos
+                .iter()
+                .map(|u| InhUtxo::new(u.outpoint, u.value, u.height, script.clone()))
+                .collect();
+            funded.push((index, utxos));
+        }
+    }
+    Ok(funded)
+}
+
+/// Warn that a check-in spends only `spent` of `total` inheritance UTXOs.
+/// The rest keep their old timelock and unlock for the heirs first.
+fn warn_unrefreshed(spent: usize, total: usize) {
+    if spent < total {
+        log::warn!(
+            "Check-in refreshes only {} of {} inheritance UTXOs; the other {} keep \
+             their old timelock and unlock for the heirs first. Consolidate to refresh them all.",
+            spent,
+            total,
+            total - spent
+        );
+    }
+}
+
+/// Build a check-in transaction builder for the inheritance addresses.
+///
+/// Spends the first UTXO at the newest funded receive index up to the
+/// current check-in index, or, when `consolidate` is set, every UTXO at
+/// every one of those indexes. With rotation on, the check-in sends the
+/// funds on to the next index.
+fn checkin_builder(
+    state: &AppState,
+    fee_rate: u64,
+    consolidate: bool,
+) -> Result<nostring_inherit::checkin::CheckinTxBuilder, String> {
+    use miniscript::descriptor::DescriptorPublicKey;
+    use miniscript::Descriptor;
+    use nostring_inherit::checkin::{CheckinDestination, CheckinTxBuilder};
+
+    let config = state.ensure_inheritance_config().ok_or(
+        "No heirs configured yet. Add at least one heir in the Heirs tab to create your inheritance policy.",
+    )?;
+
+    let client = state
+        .electrum_client()
+        .map_err(|e| format!("Failed to connect to Electrum: {}", e))?;
+
+    let network = *state.network.lock().unwrap();
+    let descriptor: Descriptor<DescriptorPublicKey> =
+        nostring_inherit::parse_and_validate_descriptor(&config.descriptor, network)
+            .map_err(|e| format!("Invalid descriptor: {}", e))?;
+
+    let funded = funded_checkin_utxos(&client, &descriptor, state.checkin_address_index())?;
+    let total: usize = funded.iter().map(|(_, utxos)| utxos.len()).sum();
+    let mut funded = funded.into_iter();
+    let (index, mut utxos) = funded
+        .next()
+        .ok_or("No UTXOs found for inheritance address. Please deposit funds first.")?;
+    let first = utxos.remove(0);
+
+    let mut builder =
+        CheckinTxBuilder::new(first, descriptor, fee_rate, index).with_network(network);
+    if state.checkin_rotation() {
+        builder = builder.with_destination(CheckinDestination::NextIndex);
+    }
+    if !consolidate {
+        warn_unrefreshed(1, total);
+        return Ok(builder);
+    }
+    builder = builder.with_consolidation(utxos);
+    for (index, utxos) in funded {
+        builder = builder.with_consolidation_at(index, utxos);
+    }
+    Ok(builder)
+}
+
+/// Fee rate for a check-in built now, in sat/vB.
+///
+/// Uses `strategy` (a [`FeeStrategy`] string), or the configured default
+/// without one. Fewer than the auto-broadcast threshold of blocks left
+/// escalates to [`FeeStrategy::Priority`]: a check-in stuck past expiry
+/// lets the heirs claim.
+fn checkin_fee_rate(state: &AppState, strategy: Option<&str>) -> Result<u64, String> {
+    let strategy = match strategy {
+        Some(s) => s.parse::<FeeStrategy>()?,
+        None => state.fee_strategy(),
+    };
+    let blocks_remaining = state
+        .policy_status
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.blocks_remaining);
+    let effective = strategy.near_expiry(blocks_remaining, DEFAULT_AUTO_BROADCAST_THRESHOLD_BLOCKS);
+    if effective != strategy {
+        log::info!(
+            "Check-in close to expiry: fee strategy {} escalated to {}",
+            strategy,
+            effective
+        );
+    }
+    effective.resolve(|target| {
+        state
+            .electrum_client()
+            .and_then(|client| client.estimate_fee_rate(target))
+            .map_err(|e| format!("Failed to estimate fee rate: {}", e))
+    })
+}
+
+/// Unsigned check-in PSBT from [`initiate_checkin`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InitiatedCheckin {
+    pub psbt_base64: String,
+    /// Set when the fee rate looks like a typo; show it before signing
+    pub fee_rate_warning: Option<String>,
+}
+
 /// Initiate a check-in (creates unsigned PSBT)
+///
+/// With `consolidate`, every UTXO at the inheritance address is swept into
+/// the single recreated output instead of only the first one. The fee rate
+/// follows `fee_strategy`, or the configured default (see
+/// [`checkin_fee_rate`]).
 #[tauri::command]
-pub async fn initiate_checkin(state: State<'_, AppState>) -> Result<CommandResult<String>, ()> {
+pub async fn initiate_checkin(
+    consolidate: Option<bool>,
+    fee_strategy: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<InitiatedCheckin>, ()> {
     let unlocked = state.unlocked.lock().unwrap();
     if !*unlocked {
         return Ok(CommandResult::err("Wallet is locked"));
     }
     drop(unlocked);
 
-    let config = {
-        let config_lock = state.inheritance_config.lock().unwrap();
-        match &*config_lock {
-            Some(c) => c.clone(),
-            None => {
-                return Ok(CommandResult::err(
-                    "No heirs configured yet. Add at least one heir in the Heirs tab to create your inheritance policy.",
-                ))
-            }
-        }
+    let fee_rate = match checkin_fee_rate(&state, fee_strategy.as_deref()) {
+        Ok(rate) => rate,
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
+    let builder = match checkin_builder(&state, fee_rate, consolidate.unwrap_or(false)) {
+        Ok(b) => b,
+        Err(e) => return Ok(CommandResult::err(e)),
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
+    match builder.build_psbt_base64() {
+        Ok(psbt_base64) => Ok(CommandResult::ok(InitiatedCheckin {
+            psbt_base64,
+            fee_rate_warning: builder.fee_rate_warning(),
+        })),
+        Err(e) => Ok(CommandResult::err(format!("Failed to build PSBT: {}", e))),
+    }
+}
 
-    let client = match ElectrumClient::new(&electrum_url, network) {
-        Ok(c) => c,
-        Err(e) => {
-            return Ok(CommandResult::err(format!(
-                "Failed to connect to Electrum: {}",
-                e
-            )))
-        }
+/// Cost of sweeping every inheritance UTXO in one check-in
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConsolidationInfo {
+    pub input_count: usize,
+    pub total_sats: u64,
+    pub fee_sats: u64,
+    pub output_sats: u64,
+    pub fee_percent: f64,
+    /// Set when the fee exceeds the acceptable share of the swept value
+    pub warning: Option<String>,
+    /// Set when the fee rate itself looks like a typo
+    pub fee_rate_warning: Option<String>,
+    /// `total_sats`, `fee_sats` and `output_sats` in the user's display unit
+    pub total_display: String,
+    pub fee_display: String,
+    pub output_display: String,
+}
+
+/// Estimate a consolidating check-in at `fee_rate` sat/vB
+#[tauri::command]
+pub async fn estimate_consolidation(
+    fee_rate: u64,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<ConsolidationInfo>, ()> {
+    use nostring_inherit::checkin::DEFAULT_MAX_CONSOLIDATION_FEE_PERCENT;
+
+    let (estimate, fee_rate_warning) = match checkin_builder(&state, fee_rate, true).and_then(|b| {
+        let fee_rate_warning = b.fee_rate_warning();
+        b.estimate()
+            .map(|e| (e, fee_rate_warning))
+            .map_err(|e| format!("Failed to estimate: {}", e))
+    }) {
+        Ok(e) => e,
+        Err(e) => return Ok(CommandResult::err(e)),
     };
 
+    let fee_percent = estimate.fee_percent();
+    let warning = estimate
+        .exceeds(DEFAULT_MAX_CONSOLIDATION_FEE_PERCENT)
+        .then(|| {
+            format!(
+                "Consolidating {} UTXOs costs {:.1}% of their value (above {}%). Consider waiting for lower fees.",
+                estimate.input_count, fee_percent, DEFAULT_MAX_CONSOLIDATION_FEE_PERCENT
+            )
+        });
+
+    let unit = state.display_unit();
+    Ok(CommandResult::ok(ConsolidationInfo {
+        input_count: estimate.input_count,
+        total_sats: estimate.total_value.to_sat(),
+        fee_sats: estimate.fee.to_sat(),
+        output_sats: estimate.output_value.to_sat(),
+        fee_percent,
+        warning,
+        fee_rate_warning,
+        total_display: format_amount(estimate.total_value.to_sat(), unit),
+        fee_display: format_amount(estimate.fee.to_sat(), unit),
+        output_display: format_amount(estimate.output_value.to_sat(), unit),
+    }))
+}
+
+/// Claim fee above this share of the last known balance triggers a warning
+const MAX_CLAIM_FEE_PERCENT: f64 = 5.0;
+
+/// Claim fee that triggers a warning when the balance is unknown
+const HIGH_CLAIM_FEE_SATS: u64 = 100_000;
+
+/// What it would cost an heir to claim the inheritance UTXO
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeirClaimFeeEstimate {
+    pub vsize: usize,
+    pub fee_rate: u64,
+    pub fee_sats: u64,
+    /// `fee_sats` in the user's display unit
+    pub fee_display: String,
+    /// Set when claiming would eat a large share of the funds
+    pub warning: Option<String>,
+}
+
+/// Estimate the fee for an heir claim at `fee_rate` sat/vB
+///
+/// Based on the recovery-path witness size of the current policy, so owners
+/// can see whether a large heir threshold makes claiming too expensive.
+#[tauri::command]
+pub async fn estimate_heir_claim_fee(
+    fee_rate: u64,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<HeirClaimFeeEstimate>, ()> {
     use miniscript::descriptor::DescriptorPublicKey;
     use miniscript::Descriptor;
-    use std::str::FromStr;
+    use nostring_inherit::policy::estimate_claim_vsize;
 
+    let config = match state.ensure_inheritance_config() {
+        Some(c) => c,
+        None => return Ok(CommandResult::err("No inheritance policy configured")),
+    };
     let descriptor: Descriptor<DescriptorPublicKey> = match Descriptor::from_str(&config.descriptor)
     {
         Ok(d) => d,
         Err(e) => return Ok(CommandResult::err(format!("Invalid descriptor: {}", e))),
     };
+    let vsize = match estimate_claim_vsize(&descriptor) {
+        Ok(v) => v,
+        Err(e) => return Ok(CommandResult::err(format!("Failed to estimate: {}", e))),
+    };
 
-    use miniscript::descriptor::DefiniteDescriptorKey;
-    let derived: Descriptor<DefiniteDescriptorKey> = match descriptor.at_derivation_index(0) {
-        Ok(d) => d,
-        Err(e) => {
-            return Ok(CommandResult::err(format!(
-                "Failed to derive script: {}",
-                e
-            )))
+    let fee_sats = (vsize as u64).saturating_mul(fee_rate);
+    let balance = state
+        .policy_status
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|s| s.balance_sats);
+    let unit = state.display_unit();
+    let warning = match balance {
+        Some(balance) if balance > 0 => {
+            let percent = fee_sats as f64 / balance as f64 * 100.0;
+            (percent > MAX_CLAIM_FEE_PERCENT).then(|| {
+                format!(
+                    "At {} sat/vB an heir claim costs {:.1}% of the inheritance balance (above {}%).",
+                    fee_rate, percent, MAX_CLAIM_FEE_PERCENT
+                )
+            })
         }
+        _ => (fee_sats > HIGH_CLAIM_FEE_SATS).then(|| {
+            format!(
+                "At {} sat/vB an heir claim costs {}.",
+                fee_rate,
+                format_amount(fee_sats, unit)
+            )
+        }),
     };
-    let script = derived.script_pubkey();
 
-    let utxos = match client.get_utxos_for_script(&script) {
-        Ok(u) => u,
-        Err(e) => return Ok(CommandResult::err(format!("Failed to get UTXOs: {}", e))),
-    };
+    Ok(CommandResult::ok(HeirClaimFeeEstimate {
+        vsize,
+        fee_rate,
+        fee_sats,
+        fee_display: format_amount(fee_sats, unit),
+        warning,
+    }))
+}
 
-    if utxos.is_empty() {
-        return Ok(CommandResult::err(
-            "No UTXOs found for inheritance address. Please deposit funds first.",
-        ));
+/// Transaction of a signed PSBT, finalizing it first if the signer didn't.
+///
+/// Offline signers often return only partial signatures; those inputs get
+/// their witness built from the inheritance `descriptor`.
+fn extract_signed_tx(
+    psbt: &Psbt,
+    descriptor: Option<&str>,
+    network: bitcoin::Network,
+) -> Result<bitcoin::Transaction, String> {
+    let finalized = psbt
+        .inputs
+        .iter()
+        .all(|i| i.final_script_witness.is_some() || i.final_script_sig.is_some());
+    if finalized {
+        return psbt
+            .clone()
+            .extract_tx()
+            .map_err(|e| format!("PSBT not fully signed: {}", e));
     }
 
-    let utxo = &utxos[0];
-
-    use nostring_inherit::checkin::{CheckinTxBuilder, InheritanceUtxo as InhUtxo};
-
-    let inheritance_utxo = InhUtxo::new(utxo.outpoint, utxo.value, utxo.height, script.to_owned());
-
-    let fee_rate = 10;
-    let builder = CheckinTxBuilder::new(inheritance_utxo, descriptor, fee_rate, 0);
-
-    match builder.build_psbt_base64() {
-        Ok(psbt_base64) => Ok(CommandResult::ok(psbt_base64)),
-        Err(e) => Ok(CommandResult::err(format!("Failed to build PSBT: {}", e))),
-    }
+    let descriptor = descriptor
+        .ok_or("PSBT is not finalized and there is no inheritance descriptor to finalize it")?;
+    let descriptor = nostring_inherit::parse_and_validate_descriptor(descriptor, network)
+        .map_err(|e| format!("Invalid descriptor: {}", e))?;
+    nostring_inherit::finalize_psbt(psbt.clone(), &descriptor)
+        .map_err(|e| format!("PSBT not fully signed: {}", e))
 }
 
 /// Complete a check-in with signed PSBT
@@ -560,15 +1367,18 @@ pub async fn broadcast_signed_psbt(
         Err(e) => return Ok(CommandResult::err(format!("Invalid PSBT: {}", e))),
     };
 
-    let tx = match psbt.extract_tx() {
+    let config = state.ensure_inheritance_config();
+    let network = *state.network.lock().unwrap();
+    let tx = match extract_signed_tx(
+        &psbt,
+        config.as_ref().map(|c| c.descriptor.as_str()),
+        network,
+    ) {
         Ok(t) => t,
-        Err(e) => return Ok(CommandResult::err(format!("PSBT not fully signed: {}", e))),
+        Err(e) => return Ok(CommandResult::err(e)),
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
-    let client = match ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => {
             return Ok(CommandResult::err(format!(
@@ -578,12 +1388,47 @@ pub async fn broadcast_signed_psbt(
         }
     };
 
-    match client.broadcast(&tx) {
+    let timelock_blocks = config.as_ref().map(|config| config.timelock_blocks as u32);
+    if let Err(e) = nostring_inherit::checkin::check_psbt_timelock(
+        &psbt,
+        timelock_blocks,
+        || {
+            client
+                .get_height()
+                .map_err(|e| format!("Failed to get block height: {}", e))
+        },
+        |txid, script| {
+            client
+                .get_tx_height(txid, script)
+                .map_err(|e| format!("Failed to look up input confirmation: {}", e))
+        },
+    ) {
+        return Ok(CommandResult::err(e.to_string()));
+    }
+
+    let report = crate::broadcast::broadcast_multi(
+        &tx,
+        || client.broadcast_idempotent(&tx).map_err(|e| e.to_string()),
+        state.http_broadcast_url().as_deref(),
+    )
+    .await;
+    match report.result() {
         Ok(txid) => {
             log::info!("Check-in broadcast successful: {}", txid);
-
-            // Log the check-in to SQLite
-            state.log_checkin(&txid.to_string());
+            state.audit(AuditAction::Broadcast, &format!("check-in {}", txid));
+
+            // Log the check-in to SQLite (advances a rotating check-in)
+            state.log_checkin(&tx);
+
+            // Signed receipt as proof of the check-in date
+            match client.get_height() {
+                Ok(height) => crate::receipt_commands::issue_checkin_receipt(
+                    &state,
+                    &txid.to_string(),
+                    height,
+                ),
+                Err(e) => log::warn!("No check-in receipt for {}: {}", txid, e),
+            }
 
             // Invalidate all pre-signed check-ins — manual check-in
             // spends the UTXO they were built to spend
@@ -629,17 +1474,32 @@ pub struct SpendEventInfo {
     pub method: String,
     pub policy_id: Option<String>,
     pub outpoint: Option<String>,
+    /// Fingerprint of the heir who claimed, when the witness proves it
+    #[serde(default)]
+    pub heir_fingerprint: Option<String>,
+    /// That heir's label in the heir registry
+    #[serde(default)]
+    pub heir_label: Option<String>,
+    /// Heirs who may have claimed, when several share the spending path
+    #[serde(default)]
+    pub heir_candidates: Vec<String>,
 }
 
 /// Detect the spend type of a transaction by analyzing its witness data.
 ///
-/// Fetches the transaction via Electrum and analyzes the witness to determine
-/// whether the owner or heir spent the funds.
+/// Fetches the transaction via Electrum and analyzes the witness of every
+/// input that spends the inheritance address, returning one event per
+/// input: a consolidating transaction can spend several inheritance UTXOs,
+/// each by a different path. Without a policy, or if no input matches it,
+/// the first input is analyzed.
+///
+/// Heir claims on the inheritance address also name the heir who signed,
+/// with their label from the heir registry.
 #[tauri::command]
 pub async fn detect_spend_type(
     txid: String,
     state: State<'_, AppState>,
-) -> Result<CommandResult<SpendEventInfo>, ()> {
+) -> Result<CommandResult<Vec<SpendEventInfo>>, ()> {
     use nostring_watch::spend_analysis;
     use std::str::FromStr;
 
@@ -648,10 +1508,7 @@ pub async fn detect_spend_type(
         Err(e) => return Ok(CommandResult::err(format!("Invalid txid: {}", e))),
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
-    let client = match ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => {
             return Ok(CommandResult::err(format!(
@@ -666,63 +1523,144 @@ pub async fn detect_spend_type(
         Ok(t) => t,
         Err(e) => return Ok(CommandResult::err(format!("Transaction not found: {}", e))),
     };
-
-    // Analyze the first input's witness (the one that spends the inheritance UTXO)
-    let analysis = if !tx.input.is_empty() {
-        spend_analysis::analyze_witness(&tx.input[0].witness)
-    } else {
+    if tx.input.is_empty() {
         return Ok(CommandResult::err("Transaction has no inputs"));
-    };
-
-    let spend_type_str = match analysis.spend_type {
-        nostring_watch::SpendType::OwnerCheckin => "owner_checkin",
-        nostring_watch::SpendType::HeirClaim => "heir_claim",
-        nostring_watch::SpendType::Unknown => "unknown",
-    };
+    }
 
-    let method_str = match analysis.method {
-        spend_analysis::DetectionMethod::WitnessAnalysis => "witness_analysis",
-        spend_analysis::DetectionMethod::TimelockTiming => "timelock_timing",
-        spend_analysis::DetectionMethod::Indeterminate => "indeterminate",
-    };
+    let descriptor = state.ensure_inheritance_config().and_then(|config| {
+        let network = *state.network.lock().unwrap();
+        nostring_inherit::parse_and_validate_descriptor(&config.descriptor, network).ok()
+    });
+    let address_index = state.checkin_address_index();
+    let tracked = descriptor
+        .as_ref()
+        .map(|d| inheritance_inputs(&client, d, address_index, &tx))
+        .unwrap_or_default();
+    let outpoints: Vec<bitcoin::OutPoint> =
+        tracked.iter().map(|(outpoint, _, _)| *outpoint).collect();
+    let mut inputs = spend_analysis::analyze_transaction_inputs(&tx, &outpoints);
+    if inputs.is_empty() {
+        inputs = spend_analysis::analyze_transaction_inputs(&tx, &[tx.input[0].previous_output]);
+    }
 
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs();
 
-    // Log the spend event to DB
-    {
-        let conn = state.db.lock().unwrap();
-        let _ = crate::db::spend_event_insert(
-            &conn,
-            now,
-            &txid,
-            spend_type_str,
-            analysis.confidence,
-            method_str,
-            None,
-            None,
-        );
+    let mut events = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let analysis = input.analysis;
+        let spend_type_str = match analysis.spend_type {
+            nostring_watch::SpendType::OwnerCheckin => "owner_checkin",
+            nostring_watch::SpendType::HeirClaim => "heir_claim",
+            nostring_watch::SpendType::Unknown => "unknown",
+        };
+
+        let method_str = match analysis.method {
+            spend_analysis::DetectionMethod::WitnessAnalysis => "witness_analysis",
+            spend_analysis::DetectionMethod::TimelockTiming => "timelock_timing",
+            spend_analysis::DetectionMethod::Indeterminate => "indeterminate",
+        };
+        let outpoint = input.outpoint.to_string();
+
+        let spent = tracked
+            .iter()
+            .find(|(op, _, _)| *op == input.outpoint)
+            .map(|(_, value, index)| (*value, *index));
+        let heir = match (&descriptor, spent) {
+            (Some(descriptor), Some((value, index)))
+                if analysis.spend_type == nostring_watch::SpendType::HeirClaim =>
+            {
+                spend_analysis::identify_heir(descriptor, index, &tx, input.input_index, value)
+            }
+            _ => None,
+        };
+        let heir_fingerprint = heir
+            .as_ref()
+            .and_then(|h| h.heir())
+            .map(|fp| fp.to_string());
+        let heir_candidates: Vec<String> = heir
+            .as_ref()
+            .map(|h| h.candidates.iter().map(|fp| fp.to_string()).collect())
+            .unwrap_or_default();
+
+        // Log the spend event to DB
+        {
+            let conn = state.db.lock().unwrap();
+            let _ = crate::db::spend_event_insert(
+                &conn,
+                now,
+                &txid,
+                spend_type_str,
+                analysis.confidence,
+                method_str,
+                None,
+                Some(&outpoint),
+                heir_fingerprint.as_deref(),
+                &heir_candidates,
+            );
+        }
+        let heir_label = heir_label(&state, heir_fingerprint.as_deref());
+
+        events.push(SpendEventInfo {
+            id: 0,
+            timestamp: now,
+            txid: txid.clone(),
+            spend_type: spend_type_str.to_string(),
+            confidence: analysis.confidence,
+            method: method_str.to_string(),
+            policy_id: None,
+            outpoint: Some(outpoint),
+            heir_fingerprint,
+            heir_label,
+            heir_candidates,
+        });
     }
 
-    Ok(CommandResult::ok(SpendEventInfo {
-        id: 0,
-        timestamp: now,
-        txid,
-        spend_type: spend_type_str.to_string(),
-        confidence: analysis.confidence,
-        method: method_str.to_string(),
-        policy_id: None,
-        outpoint: None,
-    }))
+    Ok(CommandResult::ok(events))
+}
+
+/// Outpoints spent by `tx` that were paid to an inheritance address up to
+/// receive index `through`, with their values and receive index.
+///
+/// Empty if the previous transactions can't be fetched.
+fn inheritance_inputs(
+    client: &ElectrumClient,
+    descriptor: &miniscript::Descriptor<miniscript::DescriptorPublicKey>,
+    through: u32,
+    tx: &bitcoin::Transaction,
+) -> Vec<(bitcoin::OutPoint, bitcoin::Amount, u32)> {
+    let scripts = crate::state::checkin_scripts(descriptor, through);
+    if scripts.is_empty() {
+        return Vec::new();
+    }
+
+    let prev_txids: Vec<bitcoin::Txid> = tx.input.iter().map(|i| i.previous_output.txid).collect();
+    let prev_txs = client.get_transactions(&prev_txids).unwrap_or_default();
+    tx.input
+        .iter()
+        .map(|i| i.previous_output)
+        .filter_map(|outpoint| {
+            let out = prev_txs
+                .iter()
+                .filter(|(txid, _)| *txid == outpoint.txid)
+                .find_map(|(_, prev)| prev.output.get(outpoint.vout as usize))?;
+            let (index, _) = scripts
+                .iter()
+                .find(|(_, script)| *script == out.script_pubkey)?;
+            Some((outpoint, out.value, *index))
+        })
+        .collect()
 }
 
 /// Get all spend events from the database.
 #[tauri::command]
 pub async fn get_spend_events(state: State<'_, AppState>) -> Result<Vec<SpendEventInfo>, ()> {
-    let conn = state.db.lock().unwrap();
-    let rows = crate::db::spend_event_list(&conn).unwrap_or_default();
+    let rows = {
+        let conn = state.db.lock().unwrap();
+        crate::db::spend_event_list(&conn).unwrap_or_default()
+    };
 
     Ok(rows
         .into_iter()
@@ -735,15 +1673,72 @@ pub async fn get_spend_events(state: State<'_, AppState>) -> Result<Vec<SpendEve
             method: r.method,
             policy_id: r.policy_id,
             outpoint: r.outpoint,
+            heir_label: heir_label(&state, r.heir_fingerprint.as_deref()),
+            heir_fingerprint: r.heir_fingerprint,
+            heir_candidates: r.heir_candidates,
         })
         .collect())
 }
 
+/// Registry label of the heir with `fingerprint`, looked up when shown so
+/// renames are reflected.
+fn heir_label(state: &AppState, fingerprint: Option<&str>) -> Option<String> {
+    let conn = state.db.lock().unwrap();
+    crate::db::heir_get(&conn, fingerprint?)
+        .ok()
+        .flatten()
+        .map(|h| h.label)
+}
+
+/// Get the merged inheritance timeline: check-ins, detected spends, backup
+/// deliveries and relay publications, oldest first.
+#[tauri::command]
+pub async fn get_inheritance_timeline(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Vec<crate::db::TimelineEvent>>, ()> {
+    let conn = state.db.lock().unwrap();
+    match crate::db::inheritance_timeline(&conn) {
+        Ok(events) => Ok(CommandResult::ok(events)),
+        Err(e) => Ok(CommandResult::err(format!(
+            "Failed to load timeline: {}",
+            e
+        ))),
+    }
+}
+
 /// Check if any heir claims have been detected (for alert display).
+///
+/// Only detections with at least `min_confidence` count; defaults to the
+/// configured threshold. Weaker ones still show in `get_spend_events`.
 #[tauri::command]
-pub async fn check_heir_claims(state: State<'_, AppState>) -> Result<bool, ()> {
+pub async fn check_heir_claims(
+    min_confidence: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<bool, ()> {
+    let min_confidence = min_confidence.unwrap_or_else(|| state.heir_claim_min_confidence());
     let conn = state.db.lock().unwrap();
-    Ok(crate::db::has_heir_claims(&conn).unwrap_or(false))
+    Ok(crate::db::has_heir_claims(&conn, min_confidence).unwrap_or(false))
+}
+
+/// Get the confidence an heir claim needs to raise the alert
+#[tauri::command]
+pub async fn get_heir_claim_min_confidence(state: State<'_, AppState>) -> Result<f64, ()> {
+    Ok(state.heir_claim_min_confidence())
+}
+
+/// Set the heir-claim alert threshold (0.0–1.0, persisted to SQLite)
+#[tauri::command]
+pub async fn set_heir_claim_min_confidence(
+    min_confidence: f64,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<f64>, ()> {
+    if !(0.0..=1.0).contains(&min_confidence) {
+        return Ok(CommandResult::err(
+            "Confidence threshold must be between 0 and 1",
+        ));
+    }
+    state.persist_config(HEIR_CLAIM_MIN_CONFIDENCE_KEY, &min_confidence.to_string());
+    Ok(CommandResult::ok(min_confidence))
 }
 
 // ============================================================================
@@ -765,7 +1760,8 @@ pub async fn get_network(state: State<'_, AppState>) -> Result<String, ()> {
 }
 
 /// Set the Bitcoin network. Validates input, updates state, persists to SQLite,
-/// and auto-sets the default Electrum server URL for the chosen network.
+/// and switches to the default Electrum servers for the chosen network
+/// (connections rotate through all of them).
 #[tauri::command]
 pub async fn set_network(
     network: String,
@@ -784,10 +1780,13 @@ pub async fn set_network(
         }
     };
 
-    // Write-through: memory + SQLite
+    // Write-through: memory + SQLite. Picking a standard network leaves
+    // any custom signet.
+    state.clear_custom_signet();
     state.set_network(net);
 
-    // Auto-set default Electrum URL for the network
+    // Auto-set default Electrum URL for the network; a default URL seeds
+    // the pool with every default server
     let default_url = nostring_electrum::default_server(net);
     state.set_electrum_url(default_url);
 
@@ -803,6 +1802,29 @@ pub async fn set_network(
     Ok(CommandResult::ok(label.to_string()))
 }
 
+/// Switch to a custom (private) signet.
+///
+/// `challenge` is the hex block-signing challenge script; `genesis` is the
+/// genesis block hash for signets that don't share the default one. Electrum
+/// servers must then report that genesis. The Electrum URL is left as is:
+/// point it at a server for the custom signet with `set_electrum_url`.
+#[tauri::command]
+pub async fn set_custom_signet(
+    challenge: String,
+    genesis: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<String>, ()> {
+    let signet = match nostring_electrum::CustomSignet::from_hex(&challenge, genesis.as_deref()) {
+        Ok(signet) => signet,
+        Err(e) => return Ok(CommandResult::err(e.to_string())),
+    };
+    state.set_custom_signet(&signet);
+
+    let genesis = signet.genesis_hash().to_string();
+    log::info!("Network switched to custom signet (genesis {})", genesis);
+    Ok(CommandResult::ok(genesis))
+}
+
 /// Get Electrum server URL
 #[tauri::command]
 pub async fn get_electrum_url(state: State<'_, AppState>) -> Result<String, ()> {
@@ -817,6 +1839,122 @@ pub async fn set_electrum_url(url: String, state: State<'_, AppState>) -> Result
     Ok(())
 }
 
+/// Get the HTTP endpoint transactions are also broadcast to (`None` when
+/// disabled)
+#[tauri::command]
+pub async fn get_http_broadcast_url(state: State<'_, AppState>) -> Result<Option<String>, ()> {
+    Ok(state.http_broadcast_url())
+}
+
+/// Set the HTTP broadcast endpoint (persisted to SQLite). `None` or an
+/// empty URL disables it.
+#[tauri::command]
+pub async fn set_http_broadcast_url(
+    url: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), ()> {
+    match url {
+        Some(url) => state.persist_config(crate::state::HTTP_BROADCAST_URL_KEY, url.trim()),
+        None => state.delete_config(crate::state::HTTP_BROADCAST_URL_KEY),
+    }
+    Ok(())
+}
+
+/// Get the default check-in fee strategy (`economy`, `normal`, `priority`
+/// or `fixed:<sat/vB>`)
+#[tauri::command]
+pub async fn get_fee_strategy(state: State<'_, AppState>) -> Result<String, ()> {
+    Ok(state.fee_strategy().to_string())
+}
+
+/// Set the default check-in fee strategy (persisted to SQLite)
+#[tauri::command]
+pub async fn set_fee_strategy(
+    strategy: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<String>, ()> {
+    match strategy.parse::<FeeStrategy>() {
+        Ok(strategy) => {
+            state.set_fee_strategy(strategy);
+            Ok(CommandResult::ok(strategy.to_string()))
+        }
+        Err(e) => Ok(CommandResult::err(e)),
+    }
+}
+
+/// Whether check-ins rotate to the next inheritance address
+#[tauri::command]
+pub async fn get_checkin_rotation(state: State<'_, AppState>) -> Result<bool, ()> {
+    Ok(state.checkin_rotation())
+}
+
+/// Turn check-in address rotation on or off (persisted to SQLite).
+///
+/// Rotating sends each check-in to the descriptor's next receive address,
+/// so check-ins don't all reuse one address on-chain. The policy is the
+/// same at every index.
+#[tauri::command]
+pub async fn set_checkin_rotation(
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<bool>, ()> {
+    state.set_checkin_rotation(enabled);
+    Ok(CommandResult::ok(enabled))
+}
+
+/// Get the unit amounts are displayed in (`sat`, `btc` or `msat`)
+#[tauri::command]
+pub async fn get_display_unit(state: State<'_, AppState>) -> Result<DisplayUnit, ()> {
+    Ok(state.display_unit())
+}
+
+/// Set the display unit (persisted to SQLite)
+#[tauri::command]
+pub async fn set_display_unit(
+    unit: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<DisplayUnit>, ()> {
+    match unit.parse::<DisplayUnit>() {
+        Ok(unit) => {
+            state.set_display_unit(unit);
+            Ok(CommandResult::ok(unit))
+        }
+        Err(e) => Ok(CommandResult::err(e)),
+    }
+}
+
+/// Get the coin type for test-network keys (`standard` or `legacy_mainnet`)
+#[tauri::command]
+pub async fn get_coin_type(state: State<'_, AppState>) -> Result<String, ()> {
+    Ok(crate::state::coin_type_name(state.coin_type()).to_string())
+}
+
+/// Set the coin type for test-network keys (persisted to SQLite)
+///
+/// `legacy_mainnet` keeps coin type 0' on testnet, signet and regtest, for
+/// wallets set up before NoString followed BIP-44 there. It only changes
+/// the default path of keys added afterwards.
+#[tauri::command]
+pub async fn set_coin_type(
+    coin_type: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<String>, ()> {
+    let coin_type = match coin_type.trim() {
+        "standard" => nostring_core::keys::CoinType::Standard,
+        "legacy_mainnet" => nostring_core::keys::CoinType::LegacyMainnet,
+        other => {
+            return Ok(CommandResult::err(format!(
+                "Unknown coin type '{}' (expected standard or legacy_mainnet)",
+                other
+            )))
+        }
+    };
+    state.set_coin_type(coin_type);
+    Ok(CommandResult::ok(
+        crate::state::coin_type_name(coin_type).to_string(),
+    ))
+}
+
 // ============================================================================
 // Heir Management Commands
 // ============================================================================
@@ -838,6 +1976,10 @@ pub struct HeirInfo {
     pub email: Option<String>,
     /// Per-heir timelock in months (optional, v0.4)
     pub timelock_months: Option<u32>,
+    /// Relay-policy warnings for the inheritance policy with this heir
+    /// added (set by `add_heir` only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_warnings: Vec<String>,
 }
 
 impl From<&HeirKey> for HeirInfo {
@@ -850,6 +1992,7 @@ impl From<&HeirKey> for HeirInfo {
             npub: None,
             email: None,
             timelock_months: None,
+            policy_warnings: Vec::new(),
         }
     }
 }
@@ -870,17 +2013,49 @@ impl HeirInfo {
             npub,
             email,
             timelock_months,
+            policy_warnings: Vec::new(),
         }
     }
 }
 
+/// Check that an xpub's version bytes match the wallet network.
+///
+/// xpubs are mainnet-only; tpubs cover testnet, signet, and regtest. A
+/// mismatch would produce a policy the heir can never sign for.
+fn check_xpub_network(xpub: &Xpub, network: bitcoin::Network) -> Result<(), String> {
+    use bitcoin::NetworkKind;
+
+    let expected = NetworkKind::from(network);
+    if xpub.network == expected {
+        return Ok(());
+    }
+
+    let describe = |kind: NetworkKind| match kind {
+        NetworkKind::Main => "mainnet (xpub)",
+        NetworkKind::Test => "testnet/signet/regtest (tpub)",
+    };
+    Err(format!(
+        "Network mismatch: this key is for {} but the wallet is on {}. \
+         Use a key from the same network, or enable the cross-network override for testing.",
+        describe(xpub.network),
+        network
+    ))
+}
+
 /// Add a new heir (persisted to SQLite)
+///
+/// Rejects keys whose network doesn't match the wallet's unless
+/// `allow_network_mismatch` is set (advanced cross-network testing only).
+/// `derivation_path` sets the account path of a bare xpub (e.g. `m/86'/0'/0'`
+/// for a Taproot account); descriptor keys carry their own in the origin.
 #[tauri::command]
 pub async fn add_heir(
     label: String,
     xpub_or_descriptor: String,
     timelock_months: Option<u32>,
     npub: Option<String>,
+    allow_network_mismatch: Option<bool>,
+    derivation_path: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<HeirInfo>, ()> {
     let unlocked = state.unlocked.lock().unwrap();
@@ -889,33 +2064,41 @@ pub async fn add_heir(
     }
     drop(unlocked);
 
-    let heir = if xpub_or_descriptor.starts_with('[') {
-        match HeirKey::from_descriptor_str(&label, &xpub_or_descriptor) {
-            Ok(h) => h,
-            Err(e) => return Ok(CommandResult::err(format!("Invalid descriptor: {}", e))),
-        }
-    } else {
-        let xpub = match Xpub::from_str(&xpub_or_descriptor) {
-            Ok(x) => x,
-            Err(e) => return Ok(CommandResult::err(format!("Invalid xpub: {}", e))),
-        };
-
-        let fingerprint = xpub.fingerprint();
-        let derivation_path = DerivationPath::from_str("m/84'/0'/0'").unwrap();
-
-        HeirKey::new(&label, fingerprint, xpub, Some(derivation_path))
+    let derivation_path = derivation_path.filter(|p| !p.trim().is_empty());
+    let heir = match parse_heir_key(
+        &label,
+        &xpub_or_descriptor,
+        derivation_path.as_deref(),
+        &state.default_account_path(),
+    ) {
+        Ok(h) => h,
+        Err(e) => return Ok(CommandResult::err(e)),
     };
 
+    if !allow_network_mismatch.unwrap_or(false) {
+        let network = *state.network.lock().unwrap();
+        if let Err(msg) = check_xpub_network(&heir.xpub, network) {
+            return Ok(CommandResult::err(msg));
+        }
+    }
+
     let mut heir_info = HeirInfo::from(&heir);
     heir_info.timelock_months = timelock_months;
 
     // Write-through: memory + SQLite
     state.persist_heir(&heir, timelock_months);
     let fp = heir.fingerprint.to_string();
+    state.audit(AuditAction::HeirAdd, &format!("{} {}", fp, heir.label));
     let mut registry = state.heir_registry.lock().unwrap();
     registry.add(heir);
     drop(registry);
 
+    // Warn (without refusing) when the grown policy won't relay
+    heir_info.policy_warnings = state.derived_policy_warnings();
+    for warning in &heir_info.policy_warnings {
+        log::warn!("Adding heir {}: {}", fp, warning);
+    }
+
     // Persist npub if provided
     if let Some(ref n) = npub {
         if !n.is_empty() {
@@ -927,38 +2110,309 @@ pub async fn add_heir(
     Ok(CommandResult::ok(heir_info))
 }
 
-/// List all heirs (with contact info from DB)
-#[tauri::command]
-pub async fn list_heirs(state: State<'_, AppState>) -> Result<Vec<HeirInfo>, ()> {
-    let registry = state.heir_registry.lock().unwrap();
-    let conn = state.db.lock().unwrap();
-
-    let heirs: Vec<HeirInfo> = registry
-        .list()
-        .iter()
-        .map(|heir| {
-            let fp = heir.fingerprint.to_string();
-            let row = crate::db::heir_get(&conn, &fp).ok().flatten();
-            let (npub, email, timelock_months) = row
-                .map(|r| (r.npub, r.email, r.timelock_months))
-                .unwrap_or((None, None, None));
-            HeirInfo::from_key_with_contact(heir, npub, email, timelock_months)
+/// Parse a bare xpub or a `[fingerprint/path]xpub` key into an heir.
+///
+/// Bare xpubs use `derivation_path`, or `default_path` (the network's
+/// BIP-84 account path, see [`AppState::default_account_path`]) if unset.
+/// Descriptor keys take the path from their origin; an explicit
+/// `derivation_path` must then agree with it.
+fn parse_heir_key(
+    label: &str,
+    xpub_or_descriptor: &str,
+    derivation_path: Option<&str>,
+    default_path: &DerivationPath,
+) -> Result<HeirKey, String> {
+    let requested_path = derivation_path
+        .map(|p| {
+            DerivationPath::from_str(p.trim())
+                .map_err(|e| format!("Invalid derivation path '{}': {}", p, e))
         })
-        .collect();
+        .transpose()?;
+
+    if xpub_or_descriptor.starts_with('[') {
+        let heir = HeirKey::from_descriptor_str(label, xpub_or_descriptor)
+            .map_err(|e| format!("Invalid descriptor: {}", e))?;
+        if let Some(path) = requested_path {
+            if path != heir.derivation_path {
+                return Err(format!(
+                    "Derivation path {} doesn't match the key origin path {}",
+                    path, heir.derivation_path
+                ));
+            }
+        }
+        Ok(heir)
+    } else {
+        let xpub =
+            Xpub::from_str(xpub_or_descriptor).map_err(|e| format!("Invalid xpub: {}", e))?;
 
-    drop(conn);
-    Ok(heirs)
+        let fingerprint = xpub.fingerprint();
+        let derivation_path = requested_path.unwrap_or_else(|| default_path.clone());
+
+        Ok(HeirKey::new(
+            label,
+            fingerprint,
+            xpub,
+            Some(derivation_path),
+        ))
+    }
 }
 
-/// Remove an heir by fingerprint (persisted to SQLite)
-#[tauri::command]
-pub async fn remove_heir(
-    fingerprint: String,
-    state: State<'_, AppState>,
-) -> Result<CommandResult<bool>, ()> {
-    let unlocked = state.unlocked.lock().unwrap();
-    if !*unlocked {
-        return Ok(CommandResult::err("Wallet is locked"));
+/// Outcome of importing one heir (one CSV row or one descriptor key)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeirImportResult {
+    /// 1-based CSV line, or key position within the descriptor
+    pub row: usize,
+    pub label: String,
+    /// The imported heir, when the row was valid
+    pub heir: Option<HeirInfo>,
+    /// Why the row was rejected
+    pub error: Option<String>,
+}
+
+/// A validated import row, not yet persisted
+struct ParsedHeirRow {
+    heir: HeirKey,
+    email: Option<String>,
+    npub: Option<String>,
+    timelock_months: Option<u32>,
+}
+
+/// `(row, label, parsed)` for each import row
+type HeirImportRows = Vec<(usize, String, Result<ParsedHeirRow, String>)>;
+
+/// Parse bulk heir input into per-row results.
+///
+/// `format` is `"descriptor"` (every key with an origin becomes an heir,
+/// labelled "Heir 1", "Heir 2", …) or `"csv"`
+/// (`label,xpub,email,npub,timelock_months`, header line optional), whose
+/// bare xpubs get `default_path`.
+fn parse_heir_import(
+    data: &str,
+    format: &str,
+    default_path: &DerivationPath,
+) -> Result<HeirImportRows, String> {
+    match format {
+        "descriptor" => parse_heir_descriptor(data),
+        "csv" => Ok(parse_heir_csv(data, default_path)),
+        other => Err(format!(
+            "Unknown import format '{}' (expected 'descriptor' or 'csv')",
+            other
+        )),
+    }
+}
+
+fn parse_heir_descriptor(data: &str) -> Result<HeirImportRows, String> {
+    use miniscript::descriptor::DescriptorPublicKey;
+    use miniscript::{Descriptor, ForEachKey};
+
+    nostring_inherit::policy::validate_checksum(data)
+        .map_err(|e| format!("Invalid descriptor: {}", e))?;
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(data.trim())
+        .map_err(|e| format!("Invalid descriptor: {}", e))?;
+
+    // The same key may appear in several branches; import it once
+    let mut keys: Vec<String> = Vec::new();
+    descriptor.for_each_key(|key| {
+        let key = key.to_string();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+        true
+    });
+
+    Ok(keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let label = format!("Heir {}", i + 1);
+            let parsed = HeirKey::from_descriptor_str(&label, key)
+                .map(|heir| ParsedHeirRow {
+                    heir,
+                    email: None,
+                    npub: None,
+                    timelock_months: None,
+                })
+                .map_err(|e| format!("Invalid key: {}", e));
+            (i + 1, label, parsed)
+        })
+        .collect())
+}
+
+fn parse_heir_csv(data: &str, default_path: &DerivationPath) -> HeirImportRows {
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(i, line)| {
+            !line.is_empty() && !(*i == 1 && line.to_ascii_lowercase().starts_with("label,"))
+        })
+        .map(|(row, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let label = fields[0].to_string();
+            (row, label, parse_heir_csv_row(&fields, default_path))
+        })
+        .collect()
+}
+
+fn parse_heir_csv_row(
+    fields: &[&str],
+    default_path: &DerivationPath,
+) -> Result<ParsedHeirRow, String> {
+    if fields.len() < 2 || fields.len() > 5 {
+        return Err(format!(
+            "Expected label,xpub,email,npub,timelock_months — got {} column(s)",
+            fields.len()
+        ));
+    }
+    let optional = |i: usize| {
+        fields
+            .get(i)
+            .filter(|f| !f.is_empty())
+            .map(|f| f.to_string())
+    };
+
+    let label = fields[0];
+    if label.is_empty() {
+        return Err("Label is required".into());
+    }
+    let heir = parse_heir_key(label, fields[1], None, default_path)?;
+
+    let email = optional(2);
+    if let Some(ref e) = email {
+        if !e.contains('@') || !e.contains('.') {
+            return Err(format!("Invalid email: {}", e));
+        }
+    }
+
+    let npub = optional(3);
+    if let Some(ref n) = npub {
+        if !n.starts_with("npub1") || nostr_sdk::prelude::PublicKey::parse(n).is_err() {
+            return Err(format!("Invalid npub: {}", n));
+        }
+    }
+
+    let timelock_months = match optional(4) {
+        Some(m) => match m.parse::<u32>() {
+            Ok(months) if months > 0 => Some(months),
+            _ => return Err(format!("Invalid timelock_months: {}", m)),
+        },
+        None => None,
+    };
+
+    Ok(ParsedHeirRow {
+        heir,
+        email,
+        npub,
+        timelock_months,
+    })
+}
+
+/// Import several heirs at once from a descriptor or CSV.
+///
+/// Every row is validated independently: invalid rows are reported in the
+/// result list and don't prevent the valid ones from being added.
+#[tauri::command]
+pub async fn import_heirs(
+    data: String,
+    format: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Vec<HeirImportResult>>, ()> {
+    let unlocked = state.unlocked.lock().unwrap();
+    if !*unlocked {
+        return Ok(CommandResult::err("Wallet is locked"));
+    }
+    drop(unlocked);
+
+    let rows = match parse_heir_import(&data, &format, &state.default_account_path()) {
+        Ok(rows) => rows,
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
+    let network = *state.network.lock().unwrap();
+
+    let mut results = Vec::with_capacity(rows.len());
+    for (row, label, parsed) in rows {
+        let parsed = parsed.and_then(|p| {
+            check_xpub_network(&p.heir.xpub, network)?;
+            if state
+                .heir_registry
+                .lock()
+                .unwrap()
+                .get(&p.heir.fingerprint)
+                .is_some()
+            {
+                return Err(format!("Heir {} already exists", p.heir.fingerprint));
+            }
+            Ok(p)
+        });
+
+        match parsed {
+            Ok(p) => {
+                let mut heir_info = HeirInfo::from(&p.heir);
+                heir_info.timelock_months = p.timelock_months;
+                heir_info.email = p.email.clone();
+                heir_info.npub = p.npub.clone();
+
+                state.persist_heir(&p.heir, p.timelock_months);
+                let fp = p.heir.fingerprint.to_string();
+                state.audit(
+                    AuditAction::HeirAdd,
+                    &format!("{} {} (imported)", fp, p.heir.label),
+                );
+                state.heir_registry.lock().unwrap().add(p.heir);
+                if p.npub.is_some() || p.email.is_some() {
+                    state.update_heir_contact(&fp, p.npub.as_deref(), p.email.as_deref());
+                }
+
+                results.push(HeirImportResult {
+                    row,
+                    label,
+                    heir: Some(heir_info),
+                    error: None,
+                });
+            }
+            Err(e) => results.push(HeirImportResult {
+                row,
+                label,
+                heir: None,
+                error: Some(e),
+            }),
+        }
+    }
+
+    Ok(CommandResult::ok(results))
+}
+
+/// List all heirs (with contact info from DB)
+#[tauri::command]
+pub async fn list_heirs(state: State<'_, AppState>) -> Result<Vec<HeirInfo>, ()> {
+    let registry = state.heir_registry.lock().unwrap();
+    let conn = state.db.lock().unwrap();
+
+    let heirs: Vec<HeirInfo> = registry
+        .list()
+        .iter()
+        .map(|heir| {
+            let fp = heir.fingerprint.to_string();
+            let row = crate::db::heir_get(&conn, &fp).ok().flatten();
+            let (npub, email, timelock_months) = row
+                .map(|r| (r.npub, r.email, r.timelock_months))
+                .unwrap_or((None, None, None));
+            HeirInfo::from_key_with_contact(heir, npub, email, timelock_months)
+        })
+        .collect();
+
+    drop(conn);
+    Ok(heirs)
+}
+
+/// Remove an heir by fingerprint (persisted to SQLite)
+#[tauri::command]
+pub async fn remove_heir(
+    fingerprint: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<bool>, ()> {
+    let unlocked = state.unlocked.lock().unwrap();
+    if !*unlocked {
+        return Ok(CommandResult::err("Wallet is locked"));
     }
     drop(unlocked);
 
@@ -971,7 +2425,11 @@ pub async fn remove_heir(
     state.remove_heir_db(&fingerprint);
     let mut registry = state.heir_registry.lock().unwrap();
     match registry.remove(&fp) {
-        Some(_) => Ok(CommandResult::ok(true)),
+        Some(_) => {
+            drop(registry);
+            state.audit(AuditAction::HeirRemove, &fingerprint);
+            Ok(CommandResult::ok(true))
+        }
         None => Ok(CommandResult::err("Heir not found")),
     }
 }
@@ -1036,6 +2494,82 @@ pub async fn set_heir_contact(
     }
 }
 
+/// Delivery channels an heir can opt into
+pub const HEIR_DELIVERY_CHANNELS: &[&str] = &["nostr", "email"];
+
+/// Check a channel preference: known channels, at least one, no repeats.
+fn validate_heir_channels(channels: &[String]) -> Result<Vec<String>, String> {
+    if channels.is_empty() {
+        return Err("Choose at least one delivery channel.".into());
+    }
+    let mut valid = Vec::with_capacity(channels.len());
+    for channel in channels {
+        let channel = channel.trim().to_lowercase();
+        if !HEIR_DELIVERY_CHANNELS.contains(&channel.as_str()) {
+            return Err(format!(
+                "Unknown delivery channel '{}'. Use: {}",
+                channel,
+                HEIR_DELIVERY_CHANNELS.join(", ")
+            ));
+        }
+        if !valid.contains(&channel) {
+            valid.push(channel);
+        }
+    }
+    Ok(valid)
+}
+
+/// Check that `heir` has contact info for every channel in `channels`.
+fn check_heir_channel_contacts(
+    heir: &crate::db::HeirRow,
+    channels: &[String],
+) -> Result<(), String> {
+    match channels.iter().find(|c| !heir.has_contact(c)) {
+        Some(channel) => Err(format!(
+            "{} has no {} contact. Add one before choosing that channel.",
+            heir.label, channel
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Choose which channels an heir receives descriptor deliveries on.
+///
+/// Each channel needs contact info on file for the heir. `None` resets to
+/// every configured channel.
+#[tauri::command]
+pub async fn set_heir_channels(
+    fingerprint: String,
+    channels: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Option<Vec<String>>>, ()> {
+    let unlocked = state.unlocked.lock().unwrap();
+    if !*unlocked {
+        return Ok(CommandResult::err("Wallet is locked"));
+    }
+    drop(unlocked);
+
+    let channels = match channels.as_deref().map(validate_heir_channels).transpose() {
+        Ok(c) => c,
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
+
+    let conn = state.db.lock().unwrap();
+    let heir = match crate::db::heir_get(&conn, &fingerprint) {
+        Ok(Some(heir)) => heir,
+        Ok(None) => return Ok(CommandResult::err("Heir not found with that fingerprint.")),
+        Err(e) => return Ok(CommandResult::err(format!("Database error: {}", e))),
+    };
+    if let Err(e) = check_heir_channel_contacts(&heir, channels.as_deref().unwrap_or_default()) {
+        return Ok(CommandResult::err(e));
+    }
+    match crate::db::heir_set_channels(&conn, &fingerprint, channels.as_deref()) {
+        Ok(true) => Ok(CommandResult::ok(channels)),
+        Ok(false) => Ok(CommandResult::err("Heir not found with that fingerprint.")),
+        Err(e) => Ok(CommandResult::err(format!("Database error: {}", e))),
+    }
+}
+
 /// Get contact info for an heir.
 #[tauri::command]
 pub async fn get_heir_contact(
@@ -1052,6 +2586,7 @@ pub async fn get_heir_contact(
             label: r.label,
             npub: r.npub,
             email: r.email,
+            channels: r.channels,
         })),
         None => Ok(CommandResult::err("Heir not found")),
     }
@@ -1064,22 +2599,37 @@ pub struct HeirContactInfo {
     pub label: String,
     pub npub: Option<String>,
     pub email: Option<String>,
+    /// Delivery channels the heir accepts (`None` = all configured)
+    pub channels: Option<Vec<String>>,
 }
 
-/// Validate an xpub string
+/// Validate an xpub string (format and network)
 #[tauri::command]
-pub async fn validate_xpub(xpub: String) -> CommandResult<bool> {
-    if xpub.starts_with('[') {
+pub async fn validate_xpub(
+    xpub: String,
+    allow_network_mismatch: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<bool>, ()> {
+    let parsed = if xpub.starts_with('[') {
         match HeirKey::from_descriptor_str("test", &xpub) {
-            Ok(_) => return CommandResult::ok(true),
-            Err(e) => return CommandResult::err(format!("Invalid descriptor: {}", e)),
+            Ok(h) => h.xpub,
+            Err(e) => return Ok(CommandResult::err(format!("Invalid descriptor: {}", e))),
         }
-    }
+    } else {
+        match Xpub::from_str(&xpub) {
+            Ok(x) => x,
+            Err(e) => return Ok(CommandResult::err(format!("Invalid xpub: {}", e))),
+        }
+    };
 
-    match Xpub::from_str(&xpub) {
-        Ok(_) => CommandResult::ok(true),
-        Err(e) => CommandResult::err(format!("Invalid xpub: {}", e)),
+    if !allow_network_mismatch.unwrap_or(false) {
+        let network = *state.network.lock().unwrap();
+        if let Err(msg) = check_xpub_network(&parsed, network) {
+            return Ok(CommandResult::err(msg));
+        }
     }
+
+    Ok(CommandResult::ok(true))
 }
 
 // ============================================================================
@@ -1087,6 +2637,7 @@ pub async fn validate_xpub(xpub: String) -> CommandResult<bool> {
 // ============================================================================
 
 use nostring_shamir::codex32::{parse_share, Codex32Config, Codex32Share};
+use nostring_shamir::Scheme;
 
 // ============================================================================
 // nsec Shamir Inheritance Commands
@@ -1105,6 +2656,8 @@ pub struct HeirShareInfo {
 pub struct NsecSplitResult {
     /// The owner's npub (so heirs know which identity they're recovering)
     pub owner_npub: String,
+    /// Codex32 identifier shared by every share of this split (derived from the npub)
+    pub identifier: String,
     /// One share per heir — give to each heir for safekeeping
     pub pre_distributed: Vec<HeirShareInfo>,
     /// Locked shares — included in the descriptor backup
@@ -1113,10 +2666,47 @@ pub struct NsecSplitResult {
     pub threshold: u8,
     /// Total shares generated
     pub total_shares: u8,
+    /// Heirs who must pool their shares with the locked shares once the
+    /// backup is released (1 for the default scheme)
+    pub heirs_required: u8,
     /// Whether this was a re-split (previous config existed)
     pub was_resplit: bool,
     /// The previous npub if re-splitting (may differ if owner changed identity)
     pub previous_npub: Option<String>,
+    /// Relay publication of the locked shares, when requested. The split
+    /// stands whatever this says.
+    #[serde(default)]
+    pub relay_publish: Option<SplitRelayPublish>,
+}
+
+/// How publishing the locked shares went after a split
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SplitRelayPublish {
+    /// Every heir got their shares
+    Published(RelayPublishStatus),
+    /// Some heirs didn't; `publish_locked_shares_to_relays` resumes where
+    /// this stopped
+    Partial(RelayPublishStatus),
+    /// Nothing was published
+    Failed { error: String },
+}
+
+impl SplitRelayPublish {
+    fn from_result(result: Result<RelayPublishStatus, String>) -> Self {
+        match result {
+            Ok(status) if status.heir_results.iter().any(|h| h.error.is_some()) => {
+                Self::Partial(status)
+            }
+            Ok(status) => Self::Published(status),
+            Err(error) => Self::Failed { error },
+        }
+    }
+
+    /// Whether the UI should offer to retry the publish
+    pub fn needs_retry(&self) -> bool {
+        !matches!(self, Self::Published(_))
+    }
 }
 
 /// Revoke nsec inheritance — clears locked shares and owner npub.
@@ -1138,8 +2728,16 @@ pub async fn revoke_nsec_inheritance(
     // Clear nsec inheritance data from SQLite
     state.delete_config("nsec_locked_shares");
     state.delete_config("nsec_owner_npub");
+    state.delete_config("nsec_share_identifier");
+    state.delete_config("nsec_split_threshold");
+    state.delete_config("nsec_split_total");
+    state.delete_config("last_relay_split_id");
 
     log::info!("nsec inheritance revoked — locked shares and owner npub cleared");
+    state.audit(
+        AuditAction::NsecRevoke,
+        "locked shares and owner npub cleared",
+    );
 
     Ok(CommandResult::ok(true))
 }
@@ -1159,18 +2757,134 @@ pub async fn revoke_nsec_inheritance(
 ///   - Locked: N+1
 ///   - All heirs colluding have N shares but need N+1 → blocked
 ///   - After inheritance: heir has 1 + (N+1) locked = N+2 > threshold ✓
+///
+/// With `publish_to_relays`, the new locked shares are then published as
+/// by `publish_locked_shares_to_relays`. A failed or partial publish is
+/// reported in `relay_publish` rather than failing the split, which is
+/// already persisted; the UI can retry just the publish.
 #[tauri::command]
 pub async fn split_nsec(
     nsec_input: String,
+    publish_to_relays: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<NsecSplitResult>, ()> {
+    let result = split_nsec_with_params(&state, &nsec_input, None);
+    if !publish_to_relays.unwrap_or(false) {
+        return Ok(result);
+    }
+    Ok(publish_after_split(result, || {
+        publish_locked_shares(&state, true, false, state.relay_privacy_mode(), None)
+    })
+    .await)
+}
+
+/// Run `publish` after a successful split and attach its outcome.
+async fn publish_after_split<F, Fut>(
+    mut result: CommandResult<NsecSplitResult>,
+    publish: F,
+) -> CommandResult<NsecSplitResult>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<RelayPublishStatus, String>>,
+{
+    if let Some(split) = result.data.as_mut() {
+        let outcome = SplitRelayPublish::from_result(publish().await);
+        if outcome.needs_retry() {
+            log::warn!(
+                "nsec split saved, but relay publish incomplete: {:?}",
+                outcome
+            );
+        }
+        split.relay_publish = Some(outcome);
+    }
+    result
+}
+
+/// Split an nsec with an owner-chosen `threshold`-of-`total` scheme.
+///
+/// One share still goes to each of the N heirs and the remaining
+/// `total - N` are locked in the descriptor backup. See
+/// [`validate_split_params`] for the rules. Tradeoffs versus the default
+/// (N+1)-of-(2N+1):
+///   - Fewer locked shares means more heirs must cooperate after the
+///     backup is released (`threshold - locked`), so one heir can't act
+///     alone — at the cost of recovery failing if too many heirs are
+///     unreachable.
+///   - More locked shares than the threshold means the backup alone
+///     reveals the nsec; keep it as safe as the owner's seed.
+///   - Adding heirs later can break the invariant; re-split after
+///     changing heirs.
+///
+/// The chosen parameters are persisted alongside the locked shares.
+#[tauri::command]
+pub async fn split_nsec_custom(
+    nsec_input: String,
+    threshold: u8,
+    total: u8,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<NsecSplitResult>, ()> {
+    Ok(split_nsec_with_params(
+        &state,
+        &nsec_input,
+        Some((threshold, total)),
+    ))
+}
+
+/// Check a custom `threshold`-of-`total` split for `heir_count` heirs.
+///
+/// Enforces Codex32 limits (threshold 2-9, total ≤ 31) and the security
+/// invariant: all pre-distributed holders colluding (one share per heir)
+/// must stay below the threshold, and at least one share must be locked.
+pub fn validate_split_params(threshold: u8, total: u8, heir_count: usize) -> Result<(), String> {
+    if !(2..=9).contains(&threshold) {
+        return Err(format!(
+            "Threshold must be between 2 and 9 for Codex32 (got {})",
+            threshold
+        ));
+    }
+    if total > 31 {
+        return Err(format!(
+            "Codex32 supports at most 31 shares (got {})",
+            total
+        ));
+    }
+    if total < threshold {
+        return Err(format!(
+            "Total shares ({}) must be at least the threshold ({})",
+            total, threshold
+        ));
+    }
+    if heir_count >= threshold as usize {
+        return Err(format!(
+            "Insecure split: {} heirs colluding would hold {} shares, meeting the threshold of {}. \
+             Raise the threshold above the number of heirs.",
+            heir_count, heir_count, threshold
+        ));
+    }
+    if heir_count >= total as usize {
+        return Err(format!(
+            "Total shares ({}) must exceed the number of heirs ({}) so at least one share is locked",
+            total, heir_count
+        ));
+    }
+    Ok(())
+}
+
+/// Shared body of [`split_nsec`] and [`split_nsec_custom`].
+///
+/// `custom` is `(threshold, total)`; `None` uses (N+1)-of-(2N+1).
+fn split_nsec_with_params(
+    state: &AppState,
+    nsec_input: &str,
+    custom: Option<(u8, u8)>,
+) -> CommandResult<NsecSplitResult> {
     use nostr_sdk::prelude::*;
-    use nostring_shamir::codex32::generate_shares;
+    use nostring_shamir::codex32::{generate_shares, identifier_from_npub};
 
     // Require wallet to be unlocked
     let unlocked = state.unlocked.lock().unwrap();
     if !*unlocked {
-        return Ok(CommandResult::err("Wallet is locked. Unlock first."));
+        return CommandResult::err("Wallet is locked. Unlock first.");
     }
     drop(unlocked);
 
@@ -1191,15 +2905,15 @@ pub async fn split_nsec(
     }
 
     // Parse and validate the nsec
-    let keys = match Keys::parse(&nsec_input) {
+    let keys = match Keys::parse(nsec_input) {
         Ok(k) => k,
-        Err(e) => return Ok(CommandResult::err(format!("Invalid nsec: {}", e))),
+        Err(e) => return CommandResult::err(format!("Invalid nsec: {}", e)),
     };
 
     let owner_npub = keys.public_key().to_bech32().unwrap_or_default();
 
-    // Get the raw 32-byte secret
-    let mut secret_bytes = keys.secret_key().as_secret_bytes().to_vec();
+    // Get the raw 32-byte secret (wiped on drop, including early returns)
+    let secret_bytes = Zeroizing::new(keys.secret_key().as_secret_bytes().to_vec());
 
     // Count heirs
     let heir_count = {
@@ -1208,49 +2922,48 @@ pub async fn split_nsec(
     };
 
     if heir_count == 0 {
-        secret_bytes.zeroize();
-        return Ok(CommandResult::err(
-            "Add at least one heir before splitting your nsec.",
-        ));
+        return CommandResult::err("Add at least one heir before splitting your nsec.");
     }
 
     let n = heir_count as u8;
-    let threshold = n + 1;
-    let total_shares = 2 * n + 1;
-
-    // Validate Codex32 limits (threshold 2-9, total ≤ 31)
-    if threshold > 9 {
-        secret_bytes.zeroize();
-        return Ok(CommandResult::err(
-            "Too many heirs for Codex32 (max 8 heirs → threshold 9). Use fewer heirs or contact support.",
-        ));
-    }
+    let (threshold, total_shares) = match custom {
+        Some((threshold, total)) => {
+            if let Err(e) = validate_split_params(threshold, total, heir_count) {
+                return CommandResult::err(e);
+            }
+            (threshold, total)
+        }
+        None => {
+            // Validate Codex32 limits (threshold 2-9, total ≤ 31)
+            if n + 1 > 9 {
+                return CommandResult::err(
+                    "Too many heirs for Codex32 (max 8 heirs → threshold 9). Use fewer heirs or contact support.",
+                );
+            }
+            (n + 1, 2 * n + 1)
+        }
+    };
 
-    // Generate Codex32 shares
-    // Use lowercase bech32-safe identifier
-    let config = match Codex32Config::new(threshold, "nsec", total_shares) {
+    // Generate Codex32 shares, tagged with an identifier unique to this owner
+    let identifier = identifier_from_npub(&owner_npub);
+    let config = match Codex32Config::new(threshold, &identifier, total_shares) {
         Ok(c) => c,
         Err(e) => {
-            secret_bytes.zeroize();
-            return Ok(CommandResult::err(format!("Shamir config error: {}", e)));
+            return CommandResult::err(format!("Shamir config error: {}", e));
         }
     };
 
-    let shares = match generate_shares(&secret_bytes, &config) {
+    let shares = match generate_shares(&secret_bytes, 32, &config) {
         Ok(s) => s,
         Err(e) => {
-            secret_bytes.zeroize();
-            return Ok(CommandResult::err(format!(
-                "Share generation failed: {}",
-                e
-            )));
+            return CommandResult::err(format!("Share generation failed: {}", e));
         }
     };
 
     // ZERO the raw nsec from memory immediately
-    secret_bytes.zeroize();
+    drop(secret_bytes);
 
-    // Split into pre-distributed (first N) and locked (remaining N+1)
+    // Split into pre-distributed (first N) and locked (the rest)
     let heir_labels: Vec<(String, String)> = {
         let registry = state.heir_registry.lock().unwrap();
         registry
@@ -1274,6 +2987,7 @@ pub async fn split_nsec(
         .iter()
         .map(|s| s.encoded.clone())
         .collect();
+    let heirs_required = threshold.saturating_sub(locked_shares.len() as u8).max(1);
 
     // Persist locked shares + owner npub to SQLite
     // (locked shares alone can't reconstruct — they need heir shares too...
@@ -1281,20 +2995,38 @@ pub async fn split_nsec(
     let locked_json = serde_json::to_string(&locked_shares).unwrap_or_default();
     state.persist_config("nsec_locked_shares", &locked_json);
     state.persist_config("nsec_owner_npub", &owner_npub);
+    state.persist_config("nsec_share_identifier", &identifier);
+    state.persist_config("nsec_split_threshold", &threshold.to_string());
+    state.persist_config("nsec_split_total", &total_shares.to_string());
+    // New shares start a new relay split
+    state.delete_config("last_relay_split_id");
 
     if was_resplit {
         log::info!("nsec re-split complete — old shares are now invalid");
     }
+    state.audit(
+        AuditAction::NsecSplit,
+        &format!(
+            "{}-of-{} for {}{}",
+            threshold,
+            total_shares,
+            owner_npub,
+            if was_resplit { " (re-split)" } else { "" }
+        ),
+    );
 
-    Ok(CommandResult::ok(NsecSplitResult {
+    CommandResult::ok(NsecSplitResult {
         owner_npub,
+        identifier,
         pre_distributed,
         locked_shares,
         threshold,
         total_shares,
+        heirs_required,
         was_resplit,
         previous_npub,
-    }))
+        relay_publish: None,
+    })
 }
 
 /// Get nsec inheritance status (is it configured? what npub?).
@@ -1303,6 +3035,10 @@ pub struct NsecInheritanceStatus {
     pub configured: bool,
     pub owner_npub: Option<String>,
     pub locked_share_count: usize,
+    /// Threshold of the current split
+    pub threshold: Option<u8>,
+    /// Total shares of the current split
+    pub total_shares: Option<u8>,
 }
 
 #[tauri::command]
@@ -1316,6 +3052,14 @@ pub async fn get_nsec_inheritance_status(
     let locked_json = crate::db::config_get(&conn, "nsec_locked_shares")
         .ok()
         .flatten();
+    let threshold = crate::db::config_get(&conn, "nsec_split_threshold")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok());
+    let total_shares = crate::db::config_get(&conn, "nsec_split_total")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok());
     drop(conn);
 
     let locked_count = locked_json
@@ -1328,6 +3072,8 @@ pub async fn get_nsec_inheritance_status(
         configured: owner_npub.is_some() && locked_count > 0,
         owner_npub,
         locked_share_count: locked_count,
+        threshold,
+        total_shares,
     })
 }
 
@@ -1349,7 +3095,8 @@ pub async fn get_locked_shares(state: State<'_, AppState>) -> Result<Option<Vec<
 /// from the descriptor backup. If threshold is met, the nsec is revealed.
 #[tauri::command]
 pub async fn recover_nsec(shares: Vec<String>) -> CommandResult<RecoveredNsec> {
-    use nostring_shamir::codex32::combine_shares;
+    use nostring_core::memory::LockedBuffer;
+    use nostring_shamir::codex32::combine_shares_secure;
 
     if shares.len() < 2 {
         return CommandResult::err("Need at least 2 shares to recover.");
@@ -1364,8 +3111,20 @@ pub async fn recover_nsec(shares: Vec<String>) -> CommandResult<RecoveredNsec> {
         }
     }
 
-    // Attempt reconstruction
-    let mut recovered_bytes = match combine_shares(&parsed) {
+    // Shares carry an owner-specific identifier; refuse to mix splits
+    let mut identifiers: Vec<&str> = parsed.iter().map(|s| s.identifier.as_str()).collect();
+    identifiers.sort_unstable();
+    identifiers.dedup();
+    if identifiers.len() > 1 {
+        return CommandResult::err(format!(
+            "Shares come from different splits (identifiers: {}). Use only shares with the same identifier.",
+            identifiers.join(", ")
+        ));
+    }
+
+    // Attempt reconstruction; the secret and its hex form stay in locked
+    // memory and are zeroized when they go out of scope
+    let recovered_bytes = match combine_shares_secure(&parsed) {
         Ok(bytes) => bytes,
         Err(e) => {
             return CommandResult::err(format!(
@@ -1374,13 +3133,13 @@ pub async fn recover_nsec(shares: Vec<String>) -> CommandResult<RecoveredNsec> {
             ))
         }
     };
+    let recovered_hex = LockedBuffer::from(hex::encode(recovered_bytes.as_slice()));
+    let recovered_hex = recovered_hex.as_str().unwrap_or_default();
 
     // Verify it's a valid Nostr secret key
-    let recovered_hex = hex::encode(&recovered_bytes);
-    let keys = match nostr_sdk::prelude::Keys::parse(&recovered_hex) {
+    let keys = match nostr_sdk::prelude::Keys::parse(recovered_hex) {
         Ok(k) => k,
         Err(e) => {
-            recovered_bytes.zeroize();
             return CommandResult::err(format!(
                 "Shares reconstructed but result is not a valid Nostr key: {}",
                 e
@@ -1392,12 +3151,9 @@ pub async fn recover_nsec(shares: Vec<String>) -> CommandResult<RecoveredNsec> {
     let nsec = keys
         .secret_key()
         .to_bech32()
-        .unwrap_or_else(|_| recovered_hex.clone());
+        .unwrap_or_else(|_| recovered_hex.to_string());
     let npub = keys.public_key().to_bech32().unwrap_or_default();
 
-    // Zero the intermediate bytes
-    recovered_bytes.zeroize();
-
     CommandResult::ok(RecoveredNsec { nsec, npub })
 }
 
@@ -1425,8 +3181,23 @@ pub async fn configure_notifications(
     email_smtp_host: Option<String>,
     email_smtp_user: Option<String>,
     email_smtp_password: Option<String>,
+    email_smtp_port: Option<u16>,
+    email_tls_mode: Option<String>,
+    email_plaintext_only: Option<bool>,
+    thresholds: Option<NotifyThresholds>,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<bool>, ()> {
+    if let Some(ref mode) = email_tls_mode {
+        if let Err(e) = mode.parse::<nostring_notify::TlsMode>() {
+            return Ok(CommandResult::err(e));
+        }
+    }
+    if let Some(ref thresholds) = thresholds {
+        if let Err(e) = thresholds.to_notify_config().validate() {
+            return Ok(CommandResult::err(e.to_string()));
+        }
+    }
+
     // Persist notification settings
     if let Some(ref npub) = owner_npub {
         state.persist_config("notify_owner_npub", npub);
@@ -1443,33 +3214,180 @@ pub async fn configure_notifications(
     if let Some(ref pass) = email_smtp_password {
         state.persist_config("notify_email_smtp_password", pass);
     }
+    if let Some(port) = email_smtp_port {
+        state.persist_config("notify_email_smtp_port", &port.to_string());
+    }
+    if let Some(ref mode) = email_tls_mode {
+        state.persist_config("notify_email_tls_mode", mode);
+    }
+    if let Some(plaintext_only) = email_plaintext_only {
+        state.persist_config(
+            "notify_email_plaintext_only",
+            if plaintext_only { "true" } else { "false" },
+        );
+    }
+    if let Some(thresholds) = thresholds {
+        thresholds.persist(&state);
+    }
 
     Ok(CommandResult::ok(true))
 }
 
-/// Get current notification settings.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NotificationSettings {
-    pub owner_npub: Option<String>,
-    pub email_address: Option<String>,
-    pub email_smtp_host: Option<String>,
-    pub service_npub: Option<String>,
+/// When owners get reminded and heirs get the descriptor.
+///
+/// Stored as one config row per field; rows not set take the
+/// `NotifyConfig` defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifyThresholds {
+    /// Days before expiry for each owner notification level
+    pub reminder_days: u32,
+    pub warning_days: u32,
+    pub urgent_days: u32,
+    pub critical_days: u32,
+    /// Deliver the descriptor to heirs at or below this many blocks remaining
+    pub critical_threshold_blocks: u32,
+    /// Cooldown for heirs whose last delivery predates content hashing
+    pub heir_delivery_cooldown_secs: u64,
 }
 
-#[tauri::command]
-pub async fn get_notification_settings(
-    state: State<'_, AppState>,
-) -> Result<NotificationSettings, ()> {
-    let conn = state.db.lock().unwrap();
-    let owner_npub = crate::db::config_get(&conn, "notify_owner_npub")
-        .ok()
-        .flatten();
+impl Default for NotifyThresholds {
+    fn default() -> Self {
+        Self {
+            reminder_days: 30,
+            warning_days: 7,
+            urgent_days: 1,
+            critical_days: 0,
+            critical_threshold_blocks: nostring_notify::DEFAULT_CRITICAL_THRESHOLD_BLOCKS,
+            heir_delivery_cooldown_secs: nostring_notify::DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS,
+        }
+    }
+}
+
+impl NotifyThresholds {
+    /// Read the threshold rows, defaulting any that aren't set.
+    fn load(conn: &rusqlite::Connection) -> Result<Self, String> {
+        fn row<T: std::str::FromStr>(
+            conn: &rusqlite::Connection,
+            key: &str,
+            default: T,
+        ) -> Result<T, String> {
+            match crate::db::config_get(conn, key).map_err(|e| e.to_string())? {
+                Some(v) => v
+                    .parse()
+                    .map_err(|_| format!("Invalid value '{}' for {}", v, key)),
+                None => Ok(default),
+            }
+        }
+
+        let d = Self::default();
+        Ok(Self {
+            reminder_days: row(conn, "notify_reminder_days", d.reminder_days)?,
+            warning_days: row(conn, "notify_warning_days", d.warning_days)?,
+            urgent_days: row(conn, "notify_urgent_days", d.urgent_days)?,
+            critical_days: row(conn, "notify_critical_days", d.critical_days)?,
+            critical_threshold_blocks: row(
+                conn,
+                "notify_critical_threshold_blocks",
+                d.critical_threshold_blocks,
+            )?,
+            heir_delivery_cooldown_secs: row(
+                conn,
+                "notify_heir_delivery_cooldown_secs",
+                d.heir_delivery_cooldown_secs,
+            )?,
+        })
+    }
+
+    fn persist(&self, state: &AppState) {
+        let rows: [(&str, String); 6] = [
+            ("notify_reminder_days", self.reminder_days.to_string()),
+            ("notify_warning_days", self.warning_days.to_string()),
+            ("notify_urgent_days", self.urgent_days.to_string()),
+            ("notify_critical_days", self.critical_days.to_string()),
+            (
+                "notify_critical_threshold_blocks",
+                self.critical_threshold_blocks.to_string(),
+            ),
+            (
+                "notify_heir_delivery_cooldown_secs",
+                self.heir_delivery_cooldown_secs.to_string(),
+            ),
+        ];
+        for (key, value) in rows {
+            state.persist_config(key, &value);
+        }
+    }
+
+    /// A `NotifyConfig` with these thresholds and no channels.
+    fn to_notify_config(self) -> nostring_notify::NotifyConfig {
+        use nostring_notify::{NotificationLevel, Threshold};
+        nostring_notify::NotifyConfig {
+            thresholds: vec![
+                Threshold::custom(self.reminder_days, NotificationLevel::Reminder),
+                Threshold::custom(self.warning_days, NotificationLevel::Warning),
+                Threshold::custom(self.urgent_days, NotificationLevel::Urgent),
+                Threshold::custom(self.critical_days, NotificationLevel::Critical),
+            ],
+            email: None,
+            nostr: None,
+            critical_threshold_blocks: self.critical_threshold_blocks,
+            heir_delivery_cooldown_secs: self.heir_delivery_cooldown_secs,
+        }
+    }
+}
+
+/// The stored notification thresholds as a validated `NotifyConfig`
+/// (without channels).
+fn load_notify_config(state: &AppState) -> Result<nostring_notify::NotifyConfig, String> {
+    let thresholds = {
+        let conn = state.db.lock().unwrap();
+        NotifyThresholds::load(&conn)?
+    };
+    let config = thresholds.to_notify_config();
+    config.validate().map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// Get current notification settings.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    pub owner_npub: Option<String>,
+    pub email_address: Option<String>,
+    pub email_smtp_host: Option<String>,
+    pub email_smtp_port: Option<u16>,
+    pub email_tls_mode: Option<String>,
+    /// Emails are sent without the HTML alternative part
+    pub email_plaintext_only: bool,
+    pub service_npub: Option<String>,
+    pub thresholds: NotifyThresholds,
+}
+
+#[tauri::command]
+pub async fn get_notification_settings(
+    state: State<'_, AppState>,
+) -> Result<NotificationSettings, ()> {
+    let conn = state.db.lock().unwrap();
+    let owner_npub = crate::db::config_get(&conn, "notify_owner_npub")
+        .ok()
+        .flatten();
     let email_address = crate::db::config_get(&conn, "notify_email_address")
         .ok()
         .flatten();
     let email_smtp_host = crate::db::config_get(&conn, "notify_email_smtp_host")
         .ok()
         .flatten();
+    let email_smtp_port = crate::db::config_get(&conn, "notify_email_smtp_port")
+        .ok()
+        .flatten()
+        .and_then(|p| p.parse().ok());
+    let email_tls_mode = crate::db::config_get(&conn, "notify_email_tls_mode")
+        .ok()
+        .flatten();
+    let email_plaintext_only = crate::db::config_get(&conn, "notify_email_plaintext_only")
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    let thresholds = NotifyThresholds::load(&conn).unwrap_or_default();
     drop(conn);
     let service_npub = state.service_npub.lock().unwrap().clone();
 
@@ -1477,7 +3395,11 @@ pub async fn get_notification_settings(
         owner_npub,
         email_address,
         email_smtp_host,
+        email_smtp_port,
+        email_tls_mode,
+        email_plaintext_only,
         service_npub,
+        thresholds,
     })
 }
 
@@ -1489,16 +3411,10 @@ pub async fn send_test_notification(
     state: State<'_, AppState>,
 ) -> Result<CommandResult<String>, ()> {
     // Get the service key (sender)
-    let service_secret = {
-        let sk = state.service_key.lock().unwrap();
-        match &*sk {
-            Some(s) => s.clone(),
-            None => {
-                return Ok(CommandResult::err(
-                    "No service key generated. Go to Settings → Notifications to set up.",
-                ))
-            }
-        }
+    let Some(service_secret) = state.service_secret() else {
+        return Ok(CommandResult::err(
+            "No service key generated. Go to Settings → Notifications to set up.",
+        ));
     };
 
     // Get the owner's npub (recipient)
@@ -1524,7 +3440,7 @@ pub async fn send_test_notification(
             "wss://relay.nostr.band".into(),
             "wss://nos.lol".into(),
         ],
-        secret_key: Some(service_secret),
+        secret_key: Some(service_secret.to_string()),
     };
 
     // Create a test message
@@ -1549,9 +3465,15 @@ pub async fn send_test_notification(
 /// - Critical level (timelock expired or <1 day) → deliver descriptor backup
 ///   to HEIRS via their configured npub/email channels
 ///
-/// Rate limiting: heirs won't be spammed — a 24h cooldown prevents re-delivery.
+/// Rate limiting: heirs won't be spammed — a backup identical to the last one
+/// delivered is never re-sent (legacy log entries without a content hash fall
+/// back to a 24h cooldown). Pass `force = true` to re-send anyway, e.g. when
+/// testing recovery.
 #[tauri::command]
-pub async fn check_and_notify(state: State<'_, AppState>) -> Result<CommandResult<String>, ()> {
+pub async fn check_and_notify(
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<String>, ()> {
     // Need policy status
     let status = {
         let s = state.policy_status.lock().unwrap();
@@ -1565,19 +3487,23 @@ pub async fn check_and_notify(state: State<'_, AppState>) -> Result<CommandResul
         }
     };
 
-    // Get service key
-    let service_secret = {
-        let sk = state.service_key.lock().unwrap();
-        match &*sk {
-            Some(s) => s.clone(),
-            None => {
-                return Ok(CommandResult::ok(
-                    "No service key — skipping notifications.".to_string(),
-                ))
-            }
+    let notify_config = match load_notify_config(&state) {
+        Ok(config) => config,
+        Err(e) => {
+            return Ok(CommandResult::err(format!(
+                "Invalid notification settings: {}",
+                e
+            )))
         }
     };
 
+    // Get service key
+    let Some(service_secret) = state.service_secret() else {
+        return Ok(CommandResult::ok(
+            "No service key — skipping notifications.".to_string(),
+        ));
+    };
+
     // Get owner npub
     let owner_npub = {
         let conn = state.db.lock().unwrap();
@@ -1595,47 +3521,19 @@ pub async fn check_and_notify(state: State<'_, AppState>) -> Result<CommandResul
             "wss://relay.nostr.band".into(),
             "wss://nos.lol".into(),
         ],
-        secret_key: Some(service_secret.clone()),
+        secret_key: Some(service_secret.to_string()),
     });
 
-    // Get email config
-    let email_config = {
-        let conn = state.db.lock().unwrap();
-        let address = crate::db::config_get(&conn, "notify_email_address")
-            .ok()
-            .flatten();
-        let host = crate::db::config_get(&conn, "notify_email_smtp_host")
-            .ok()
-            .flatten();
-        let user = crate::db::config_get(&conn, "notify_email_smtp_user")
-            .ok()
-            .flatten();
-        let pass = crate::db::config_get(&conn, "notify_email_smtp_password")
-            .ok()
-            .flatten();
-        match (address, host, user, pass) {
-            (Some(addr), Some(h), Some(u), Some(p)) => Some(nostring_notify::EmailConfig {
-                enabled: true,
-                smtp_host: h,
-                smtp_port: 587,
-                smtp_user: u.clone(),
-                smtp_password: p,
-                from_address: u,
-                to_address: addr,
-                plaintext: false,
-            }),
-            _ => None,
-        }
-    };
+    let email_config = load_email_config(&state);
 
     let mut results = Vec::new();
 
     // ── Phase 1: Owner notifications (existing behavior) ──
     if nostr_config.is_some() || email_config.is_some() {
         let config = nostring_notify::NotifyConfig {
-            thresholds: nostring_notify::NotifyConfig::default().thresholds,
             email: email_config.clone(),
             nostr: nostr_config,
+            ..notify_config.clone()
         };
 
         let service = nostring_notify::NotificationService::new(config);
@@ -1655,94 +3553,353 @@ pub async fn check_and_notify(state: State<'_, AppState>) -> Result<CommandResul
     }
 
     // ── Phase 2: Heir descriptor delivery (v0.2 escalation) ──
-    // Only trigger when timelock is critical (≤1 day / ≤144 blocks)
-    let is_critical = status.blocks_remaining <= 144;
-
-    if is_critical {
-        let heir_delivery_result =
-            deliver_descriptor_to_heirs(&state, &service_secret, email_config.as_ref()).await;
+    // Only trigger when timelock is critical (`critical_threshold_blocks`,
+    // ~1 day by default)
+    if notify_config.escalates_to_heirs(status.blocks_remaining) {
+        let heir_delivery_result = deliver_descriptor_to_heirs(
+            &state,
+            service_secret.as_str(),
+            email_config.as_ref(),
+            notify_config.heir_delivery_cooldown_secs,
+            force.unwrap_or(false),
+        )
+        .await;
         results.push(heir_delivery_result);
     }
 
     Ok(CommandResult::ok(results.join(" | ")))
 }
 
+/// SMTP settings from the notification config, if fully configured.
+fn load_email_config(state: &AppState) -> Option<nostring_notify::EmailConfig> {
+    let conn = state.db.lock().unwrap();
+    let address = crate::db::config_get(&conn, "notify_email_address")
+        .ok()
+        .flatten();
+    let host = crate::db::config_get(&conn, "notify_email_smtp_host")
+        .ok()
+        .flatten();
+    let user = crate::db::config_get(&conn, "notify_email_smtp_user")
+        .ok()
+        .flatten();
+    let pass = crate::db::config_get(&conn, "notify_email_smtp_password")
+        .ok()
+        .flatten();
+    let tls_mode: nostring_notify::TlsMode = crate::db::config_get(&conn, "notify_email_tls_mode")
+        .ok()
+        .flatten()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or_default();
+    let port = crate::db::config_get(&conn, "notify_email_smtp_port")
+        .ok()
+        .flatten()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or_else(|| tls_mode.default_port());
+    match (address, host, user, pass) {
+        (Some(addr), Some(h), Some(u), Some(p)) => Some(nostring_notify::EmailConfig {
+            enabled: true,
+            smtp_host: h,
+            smtp_port: port,
+            smtp_user: u.clone(),
+            smtp_password: p,
+            from_address: u,
+            to_address: addr,
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            tls_mode,
+            plaintext_only: crate::db::config_get(&conn, "notify_email_plaintext_only")
+                .ok()
+                .flatten()
+                .is_some_and(|v| v == "true"),
+        }),
+        _ => None,
+    }
+}
+
+/// Outcome of checking whether an heir delivery should go out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeliveryDecision {
+    /// Send the backup.
+    Send,
+    /// Skip: the last successful delivery had identical content.
+    SkipUnchanged,
+    /// Skip: delivered recently and the content hash is unknown (pre-v0.5 log).
+    SkipCooldown,
+}
+
+/// Decide whether to deliver a backup given the last successful delivery
+/// (`(timestamp, content_hash)`) on that heir + channel.
+///
+/// Identical content is suppressed regardless of age; changed content is
+/// sent immediately. The time cooldown only applies to legacy log rows that
+/// predate content hashing.
+fn delivery_decision(
+    last_success: Option<&(u64, Option<String>)>,
+    content_hash: &str,
+    now: u64,
+    cooldown_secs: u64,
+    force: bool,
+) -> DeliveryDecision {
+    if force {
+        return DeliveryDecision::Send;
+    }
+    match last_success {
+        None => DeliveryDecision::Send,
+        Some((_, Some(hash))) if hash == content_hash => DeliveryDecision::SkipUnchanged,
+        Some((_, Some(_))) => DeliveryDecision::Send,
+        Some((ts, None)) if now.saturating_sub(*ts) < cooldown_secs => {
+            DeliveryDecision::SkipCooldown
+        }
+        Some((_, None)) => DeliveryDecision::Send,
+    }
+}
+
+/// Attempts per heir + channel before a failed delivery is given up on
+const DELIVERY_MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry; doubles with each further attempt
+const DELIVERY_RETRY_BASE_SECS: u64 = 300;
+
+/// When to retry after failed attempt number `attempt`, or `None` once
+/// `DELIVERY_MAX_ATTEMPTS` is reached.
+fn delivery_retry_at(now: u64, attempt: u32) -> Option<u64> {
+    if attempt >= DELIVERY_MAX_ATTEMPTS {
+        return None;
+    }
+    Some(now + (DELIVERY_RETRY_BASE_SECS << (attempt - 1)))
+}
+
+/// Summary of one pass over the delivery retry queue.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct DeliveryRetryReport {
+    pub delivered: u32,
+    /// Retries dropped because the heir already has this exact backup
+    pub unchanged: u32,
+    /// Retries that failed again and are rescheduled
+    pub failed: u32,
+    /// Retries that failed on the last allowed attempt
+    pub gave_up: u32,
+    /// Retries dropped because the heir or their contact was removed
+    pub dropped: u32,
+}
+
+/// Re-attempt every failed delivery whose retry is due.
+///
+/// `send` delivers the current backup to one heir on one channel. A retry
+/// is skipped when the heir's last successful delivery on that channel
+/// already has `content_hash`.
+async fn retry_due_deliveries<F, Fut>(
+    state: &AppState,
+    content_hash: &str,
+    mut send: F,
+) -> DeliveryRetryReport
+where
+    F: FnMut(crate::db::HeirRow, String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    let now = state.clock.now_secs();
+    let cooldown_secs = load_notify_config(state)
+        .map(|c| c.heir_delivery_cooldown_secs)
+        .unwrap_or(nostring_notify::DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS);
+    let (due, heirs) = {
+        let conn = state.db.lock().unwrap();
+        (
+            crate::db::delivery_retry_due(&conn, now).unwrap_or_default(),
+            crate::db::heir_list(&conn).unwrap_or_default(),
+        )
+    };
+
+    let mut report = DeliveryRetryReport::default();
+    for retry in due {
+        {
+            let conn = state.db.lock().unwrap();
+            let _ = crate::db::delivery_retry_clear(&conn, retry.id);
+        }
+
+        let heir = heirs
+            .iter()
+            .find(|h| h.fingerprint == retry.heir_fingerprint);
+        let has_contact = heir.is_some_and(|h| {
+            h.accepts_channel(&retry.channel)
+                && match retry.channel.as_str() {
+                    "nostr" => h.npub.is_some(),
+                    "email" => h.email.is_some(),
+                    _ => false,
+                }
+        });
+        let Some(heir) = heir.filter(|_| has_contact) else {
+            report.dropped += 1;
+            continue;
+        };
+
+        let last = state.last_heir_delivery(&heir.fingerprint, &retry.channel);
+        let decision = delivery_decision(last.as_ref(), content_hash, now, cooldown_secs, false);
+        if decision == DeliveryDecision::SkipUnchanged {
+            report.unchanged += 1;
+            continue;
+        }
+
+        let attempt = retry.attempt + 1;
+        match send(heir.clone(), retry.channel.clone()).await {
+            Ok(()) => {
+                log::info!(
+                    "Descriptor delivered to heir {} via {} on attempt {}",
+                    heir.label,
+                    retry.channel,
+                    attempt
+                );
+                state.log_delivery_attempt(
+                    &heir.fingerprint,
+                    &retry.channel,
+                    true,
+                    None,
+                    Some(content_hash),
+                    attempt,
+                    None,
+                );
+                report.delivered += 1;
+            }
+            Err(e) => {
+                let next_retry_at = delivery_retry_at(now, attempt);
+                log::error!(
+                    "Retry {} of delivery to heir {} via {} failed: {}",
+                    attempt,
+                    heir.label,
+                    retry.channel,
+                    e
+                );
+                state.log_delivery_attempt(
+                    &heir.fingerprint,
+                    &retry.channel,
+                    false,
+                    Some(&e),
+                    Some(content_hash),
+                    attempt,
+                    next_retry_at,
+                );
+                if next_retry_at.is_some() {
+                    report.failed += 1;
+                } else {
+                    report.gave_up += 1;
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Retry failed heir deliveries that are due, with exponential backoff.
+///
+/// Meant to be called periodically. Failed deliveries are retried up to
+/// `DELIVERY_MAX_ATTEMPTS` times, always with the current backup.
+#[tauri::command]
+pub async fn retry_failed_deliveries(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<DeliveryRetryReport>, ()> {
+    let Some(service_secret) = state.service_secret() else {
+        return Ok(CommandResult::ok(DeliveryRetryReport::default()));
+    };
+    let backup_json = match signed_backup_json(&state, service_secret.as_str()) {
+        Ok(Some(j)) => j,
+        Ok(None) => return Ok(CommandResult::ok(DeliveryRetryReport::default())),
+        Err(e) => {
+            return Ok(CommandResult::err(format!(
+                "Heir delivery retry failed: {}",
+                e
+            )))
+        }
+    };
+    let content_hash = match backup_content_hash(&backup_json) {
+        Ok(h) => h,
+        Err(e) => {
+            return Ok(CommandResult::err(format!(
+                "Heir delivery retry failed: {}",
+                e
+            )))
+        }
+    };
+
+    let email_config = load_email_config(&state);
+    let relays: Vec<String> = vec![
+        "wss://relay.damus.io".into(),
+        "wss://relay.nostr.band".into(),
+        "wss://nos.lol".into(),
+    ];
+    let (backup_json, relays, email_config, service_secret) = (
+        backup_json.as_str(),
+        relays.as_slice(),
+        email_config.as_ref(),
+        service_secret.as_str(),
+    );
+
+    let report = retry_due_deliveries(&state, &content_hash, move |heir, channel| async move {
+        let message =
+            nostring_notify::templates::generate_heir_delivery_message(&heir.label, backup_json);
+        match (channel.as_str(), heir.npub, heir.email, email_config) {
+            ("nostr", Some(npub), _, _) => nostring_notify::nostr_dm::send_dm_to_recipient(
+                service_secret,
+                &npub,
+                relays,
+                &message,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+            ("email", _, Some(email), Some(smtp_config)) => {
+                nostring_notify::smtp::send_email_to_recipient(smtp_config, &email, &message)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            ("email", _, Some(_), None) => Err("Email is not configured".to_string()),
+            _ => Err(format!("No {} contact for heir", channel)),
+        }
+    })
+    .await;
+
+    Ok(CommandResult::ok(report))
+}
+
+/// Channels a descriptor delivery goes out on for `heir`: those with
+/// contact info on file and configured, minus any the heir opted out of.
+fn heir_delivery_channels(heir: &crate::db::HeirRow, email_configured: bool) -> Vec<&'static str> {
+    let mut channels = Vec::new();
+    if heir.has_contact("nostr") && heir.accepts_channel("nostr") {
+        channels.push("nostr");
+    }
+    if heir.has_contact("email") && email_configured && heir.accepts_channel("email") {
+        channels.push("email");
+    }
+    channels
+}
+
 /// Deliver the descriptor backup to all heirs with configured contact info.
 ///
 /// This is the core inheritance mechanism — when the owner hasn't checked in
 /// and the timelock is critical, heirs receive everything they need.
 ///
-/// Rate limited: 24h cooldown per heir per channel to prevent spam.
+/// De-duplicated per heir per channel by SHA-256 of the backup's canonical
+/// JSON, so heirs only receive a new message when the descriptor, heirs, or
+/// shares change.
+/// `force` re-sends regardless.
 async fn deliver_descriptor_to_heirs(
     state: &State<'_, AppState>,
     service_secret: &str,
     email_config: Option<&nostring_notify::EmailConfig>,
+    cooldown_secs: u64,
+    force: bool,
 ) -> String {
-    // 24-hour cooldown between deliveries to the same heir on the same channel
-    const DELIVERY_COOLDOWN_SECS: u64 = 86400;
-
-    // Get the descriptor backup data
-    let backup_data = {
-        let config = {
-            let config_lock = state.inheritance_config.lock().unwrap();
-            match &*config_lock {
-                Some(c) => c.clone(),
-                None => {
-                    return "Heir delivery skipped: no inheritance policy configured.".to_string()
-                }
-            }
-        };
-
-        let heirs: Vec<DescriptorBackupHeir> = {
-            let registry = state.heir_registry.lock().unwrap();
-            registry
-                .list()
-                .iter()
-                .map(|h| DescriptorBackupHeir {
-                    label: h.label.clone(),
-                    xpub: h.xpub.to_string(),
-                    timelock_months: config.timelock_blocks as f64 * 10.0 / 60.0 / 24.0 / 30.0,
-                })
-                .collect()
-        };
-
-        let address = {
-            use miniscript::descriptor::DescriptorPublicKey;
-            use miniscript::Descriptor;
-            let desc: Result<Descriptor<DescriptorPublicKey>, _> = config.descriptor.parse();
-            desc.ok()
-                .and_then(|d| d.at_derivation_index(0).ok())
-                .and_then(|d| {
-                    let network = *state.network.lock().unwrap();
-                    d.address(network).map(|a| a.to_string()).ok()
-                })
-        };
-
-        let conn = state.db.lock().unwrap();
-        let nsec_owner_npub = crate::db::config_get(&conn, "nsec_owner_npub")
-            .ok()
-            .flatten();
-        let locked_shares = crate::db::config_get(&conn, "nsec_locked_shares")
-            .ok()
-            .flatten()
-            .and_then(|j| serde_json::from_str::<Vec<String>>(&j).ok());
-        drop(conn);
-
-        DescriptorBackupData {
-            descriptor: config.descriptor,
-            network: config.network,
-            timelock_blocks: config.timelock_blocks,
-            address,
-            heirs,
-            nsec_owner_npub,
-            locked_shares,
-        }
+    // Same assembly as the preview, so what heirs receive can't drift from it
+    let backup_json = match signed_backup_json(state, service_secret) {
+        Ok(Some(j)) => j,
+        Ok(None) => return "Heir delivery skipped: no inheritance policy configured.".to_string(),
+        Err(e) => return format!("Heir delivery failed: {}", e),
     };
 
-    let backup_json = match serde_json::to_string_pretty(&backup_data) {
-        Ok(j) => j,
-        Err(e) => return format!("Heir delivery failed: could not serialize backup: {}", e),
+    let content_hash = match backup_content_hash(&backup_json) {
+        Ok(h) => h,
+        Err(e) => return format!("Heir delivery failed: {}", e),
     };
+    let now = state.clock.now_secs();
 
     // Get heirs with contact info from DB
     let heir_contacts = {
@@ -1757,16 +3914,21 @@ async fn deliver_descriptor_to_heirs(
     ];
 
     let mut delivered = 0u32;
+    let mut unchanged = 0u32;
     let mut skipped = 0u32;
     let mut failed = 0u32;
 
     for heir in &heir_contacts {
         let message =
             nostring_notify::templates::generate_heir_delivery_message(&heir.label, &backup_json);
+        let channels = heir_delivery_channels(heir, email_config.is_some());
 
         // Nostr DM delivery
-        if let Some(ref npub) = heir.npub {
-            if state.can_deliver_to_heir(&heir.fingerprint, "nostr", DELIVERY_COOLDOWN_SECS) {
+        if let Some(npub) = heir.npub.as_ref().filter(|_| channels.contains(&"nostr")) {
+            let last = state.last_heir_delivery(&heir.fingerprint, "nostr");
+            let decision =
+                delivery_decision(last.as_ref(), &content_hash, now, cooldown_secs, force);
+            if decision == DeliveryDecision::Send {
                 match nostring_notify::nostr_dm::send_dm_to_recipient(
                     service_secret,
                     npub,
@@ -1777,7 +3939,13 @@ async fn deliver_descriptor_to_heirs(
                 {
                     Ok(_) => {
                         log::info!("Descriptor delivered to heir {} via Nostr DM", heir.label);
-                        state.log_delivery(&heir.fingerprint, "nostr", true, None);
+                        state.log_delivery(
+                            &heir.fingerprint,
+                            "nostr",
+                            true,
+                            None,
+                            Some(&content_hash),
+                        );
                         delivered += 1;
                     }
                     Err(e) => {
@@ -1787,10 +3955,24 @@ async fn deliver_descriptor_to_heirs(
                             heir.label,
                             err_msg
                         );
-                        state.log_delivery(&heir.fingerprint, "nostr", false, Some(&err_msg));
+                        state.log_delivery_attempt(
+                            &heir.fingerprint,
+                            "nostr",
+                            false,
+                            Some(&err_msg),
+                            Some(&content_hash),
+                            1,
+                            delivery_retry_at(now, 1),
+                        );
                         failed += 1;
                     }
                 }
+            } else if decision == DeliveryDecision::SkipUnchanged {
+                log::info!(
+                    "Skipping Nostr delivery to heir {} (backup unchanged)",
+                    heir.label
+                );
+                unchanged += 1;
             } else {
                 log::info!(
                     "Skipping Nostr delivery to heir {} (cooldown active)",
@@ -1801,8 +3983,12 @@ async fn deliver_descriptor_to_heirs(
         }
 
         // Email delivery
-        if let (Some(ref heir_email), Some(smtp_config)) = (&heir.email, email_config) {
-            if state.can_deliver_to_heir(&heir.fingerprint, "email", DELIVERY_COOLDOWN_SECS) {
+        let heir_email = heir.email.as_ref().filter(|_| channels.contains(&"email"));
+        if let (Some(heir_email), Some(smtp_config)) = (heir_email, email_config) {
+            let last = state.last_heir_delivery(&heir.fingerprint, "email");
+            let decision =
+                delivery_decision(last.as_ref(), &content_hash, now, cooldown_secs, force);
+            if decision == DeliveryDecision::Send {
                 match nostring_notify::smtp::send_email_to_recipient(
                     smtp_config,
                     heir_email,
@@ -1812,7 +3998,13 @@ async fn deliver_descriptor_to_heirs(
                 {
                     Ok(_) => {
                         log::info!("Descriptor delivered to heir {} via email", heir.label);
-                        state.log_delivery(&heir.fingerprint, "email", true, None);
+                        state.log_delivery(
+                            &heir.fingerprint,
+                            "email",
+                            true,
+                            None,
+                            Some(&content_hash),
+                        );
                         delivered += 1;
                     }
                     Err(e) => {
@@ -1822,10 +4014,24 @@ async fn deliver_descriptor_to_heirs(
                             heir.label,
                             err_msg
                         );
-                        state.log_delivery(&heir.fingerprint, "email", false, Some(&err_msg));
+                        state.log_delivery_attempt(
+                            &heir.fingerprint,
+                            "email",
+                            false,
+                            Some(&err_msg),
+                            Some(&content_hash),
+                            1,
+                            delivery_retry_at(now, 1),
+                        );
                         failed += 1;
                     }
                 }
+            } else if decision == DeliveryDecision::SkipUnchanged {
+                log::info!(
+                    "Skipping email delivery to heir {} (backup unchanged)",
+                    heir.label
+                );
+                unchanged += 1;
             } else {
                 log::info!(
                     "Skipping email delivery to heir {} (cooldown active)",
@@ -1837,8 +4043,8 @@ async fn deliver_descriptor_to_heirs(
     }
 
     format!(
-        "Heir descriptor delivery: {} sent, {} skipped (cooldown), {} failed",
-        delivered, skipped, failed
+        "Heir descriptor delivery: {} sent, {} unchanged, {} skipped (cooldown), {} failed",
+        delivered, unchanged, skipped, failed
     )
 }
 
@@ -1853,37 +4059,86 @@ pub struct DescriptorBackupData {
     pub network: String,
     pub timelock_blocks: u16,
     pub address: Option<String>,
+    /// Receive index `address` is derived at, once a rotating check-in
+    /// moved the funds past index 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_index: Option<u32>,
     pub heirs: Vec<DescriptorBackupHeir>,
     pub nsec_owner_npub: Option<String>,
     pub locked_shares: Option<Vec<String>>,
+    /// Hex Schnorr signature by the service key over the canonical JSON of
+    /// every other field. Lets heirs detect a swapped descriptor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// npub of the service key that produced `signature`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_npub: Option<String>,
+    /// Set when the previous backup was made from a different descriptor,
+    /// so copies already given to heirs must be replaced. Not signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_warning: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// SHA-256 of the backup's canonical JSON, excluding the signature fields.
+fn descriptor_backup_digest(backup: &DescriptorBackupData) -> Result<[u8; 32], String> {
+    nostring_core::manifest::signed_digest(backup).map_err(|e| e.to_string())
+}
+
+/// Schnorr-sign a digest with the service key (hex secret).
+///
+/// Returns `(signature_hex, signer_npub)`.
+pub(crate) fn sign_digest_with_service_key(
+    digest: [u8; 32],
+    service_secret: &str,
+) -> Result<(String, String), String> {
+    nostring_core::manifest::sign_digest(digest, service_secret).map_err(|e| e.to_string())
+}
+
+/// Check a Schnorr signature over `digest` against a signer npub.
+pub(crate) fn verify_digest_signature(
+    digest: [u8; 32],
+    sig_hex: &str,
+    npub: &str,
+) -> Result<(), String> {
+    nostring_core::manifest::verify_digest(digest, sig_hex, npub).map_err(|e| e.to_string())
+}
+
+/// Sign a descriptor backup with the service key (hex secret).
+fn sign_descriptor_backup(
+    backup: &mut DescriptorBackupData,
+    service_secret: &str,
+) -> Result<(), String> {
+    let (signature, npub) =
+        sign_digest_with_service_key(descriptor_backup_digest(backup)?, service_secret)?;
+    backup.signature = Some(signature);
+    backup.signer_npub = Some(npub);
+    Ok(())
+}
+
+/// Check a backup's signature against its embedded `signer_npub`.
+fn verify_descriptor_backup_data(backup: &DescriptorBackupData) -> Result<(), String> {
+    let (Some(sig_hex), Some(npub)) = (&backup.signature, &backup.signer_npub) else {
+        return Err("Backup is not signed".into());
+    };
+    nostring_inherit::policy::validate_checksum(&backup.descriptor)
+        .map_err(|e| format!("Backup descriptor is corrupted: {}", e))?;
+
+    verify_digest_signature(descriptor_backup_digest(backup)?, sig_hex, npub)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct DescriptorBackupHeir {
     pub label: String,
     pub xpub: String,
     pub timelock_months: f64,
 }
 
-/// Get all data needed to generate the descriptor backup file.
+/// Gather the descriptor backup from the current policy, heirs and locked
+/// nsec shares. Unsigned; `None` when no inheritance policy is configured.
 ///
-/// Returns the inheritance descriptor, heir info, and any locked
-/// Shamir shares for nsec inheritance.
-#[tauri::command]
-pub async fn get_descriptor_backup(
-    state: State<'_, AppState>,
-) -> Result<CommandResult<DescriptorBackupData>, ()> {
-    let config = {
-        let config_lock = state.inheritance_config.lock().unwrap();
-        match &*config_lock {
-            Some(c) => c.clone(),
-            None => {
-                return Ok(CommandResult::err(
-                    "No inheritance policy configured. Add heirs first.",
-                ))
-            }
-        }
-    };
+/// Shared by the backup download, heir delivery and the delivery preview.
+pub(crate) fn assemble_descriptor_backup(state: &AppState) -> Option<DescriptorBackupData> {
+    let config = state.inheritance_config.lock().unwrap().clone()?;
 
     // Build heir list
     let heirs: Vec<DescriptorBackupHeir> = {
@@ -1899,16 +4154,19 @@ pub async fn get_descriptor_backup(
             .collect()
     };
 
-    // Derive inheritance address (index 0)
+    // Derive the inheritance address the funds currently sit on
+    let index = state.checkin_address_index();
     let address = {
         use miniscript::descriptor::DescriptorPublicKey;
         use miniscript::Descriptor;
         let desc: Result<Descriptor<DescriptorPublicKey>, _> = config.descriptor.parse();
         desc.ok()
-            .and_then(|d| d.at_derivation_index(0).ok())
-            .and_then(|d| {
+            .and_then(|d| crate::state::checkin_scripts(&d, index).pop())
+            .and_then(|(_, script)| {
                 let network = *state.network.lock().unwrap();
-                d.address(network).map(|a| a.to_string()).ok()
+                bitcoin::Address::from_script(&script, network)
+                    .map(|a| a.to_string())
+                    .ok()
             })
     };
 
@@ -1923,117 +4181,332 @@ pub async fn get_descriptor_backup(
         .and_then(|j| serde_json::from_str::<Vec<String>>(&j).ok());
     drop(conn);
 
-    Ok(CommandResult::ok(DescriptorBackupData {
+    Some(DescriptorBackupData {
         descriptor: config.descriptor,
         network: config.network,
         timelock_blocks: config.timelock_blocks,
         address,
+        address_index: (index > 0).then_some(index),
         heirs,
         nsec_owner_npub,
         locked_shares,
-    }))
+        signature: None,
+        signer_npub: None,
+        stale_warning: None,
+    })
 }
 
-/// Generate Codex32 shares for a seed
-///
-/// Requires the wallet password to decrypt the seed for splitting.
-/// The decrypted seed is held in memory only during share generation,
-/// then zeroized.
-#[tauri::command]
-pub async fn generate_codex32_shares(
-    threshold: u8,
-    total_shares: u8,
-    mut password: String,
-    identifier: Option<String>,
-    state: State<'_, AppState>,
-) -> Result<CommandResult<Vec<String>>, ()> {
-    let unlocked = state.unlocked.lock().unwrap();
-    if !*unlocked {
-        password.zeroize();
-        return Ok(CommandResult::err("Wallet is locked"));
+/// The signed backup JSON exactly as delivered to heirs.
+fn signed_backup_json(state: &AppState, service_secret: &str) -> Result<Option<String>, String> {
+    assemble_descriptor_backup(state)
+        .map(|backup| sign_and_serialize_backup(backup, service_secret))
+        .transpose()
+}
+
+/// SHA-256 of a backup's canonical JSON, which delivery de-duplication
+/// keys on, so whitespace and key order in the rendered JSON don't count
+/// as a change.
+fn backup_content_hash(backup_json: &str) -> Result<String, String> {
+    use bitcoin::hashes::{sha256, Hash};
+
+    let value: serde_json::Value =
+        serde_json::from_str(backup_json).map_err(|e| format!("invalid backup JSON: {}", e))?;
+    let canonical = nostring_core::canonical::to_canonical_json(&value)?;
+    Ok(sha256::Hash::hash(&canonical).to_string())
+}
+
+/// Sign a backup and render it as the pretty JSON embedded in heir messages.
+fn sign_and_serialize_backup(
+    mut backup: DescriptorBackupData,
+    service_secret: &str,
+) -> Result<String, String> {
+    sign_descriptor_backup(&mut backup, service_secret)
+        .map_err(|e| format!("could not sign backup: {}", e))?;
+    serde_json::to_string_pretty(&backup).map_err(|e| format!("could not serialize backup: {}", e))
+}
+
+/// What an heir would receive, for review before a real delivery.
+#[derive(Debug, Serialize, Clone)]
+pub struct HeirDeliveryPreview {
+    pub heir_label: String,
+    /// Channels a real delivery would use ("nostr", "email")
+    pub channels: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Build the exact message heir delivery would send to one heir, without
+/// sending anything or touching the delivery log.
+#[tauri::command]
+pub async fn preview_heir_delivery(
+    fingerprint: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<HeirDeliveryPreview>, ()> {
+    let Some(service_secret) = state.service_secret() else {
+        return Ok(CommandResult::err(
+            "No service key generated. Go to Settings → Notifications to set up.",
+        ));
+    };
+
+    let heir = {
+        let conn = state.db.lock().unwrap();
+        crate::db::heir_list(&conn)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|h| h.fingerprint == fingerprint)
+    };
+    let Some(heir) = heir else {
+        return Ok(CommandResult::err(format!(
+            "Heir not found: {}",
+            fingerprint
+        )));
+    };
+
+    let backup_json = match signed_backup_json(&state, service_secret.as_str()) {
+        Ok(Some(j)) => j,
+        Ok(None) => return Ok(CommandResult::err("No inheritance policy configured.")),
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
+
+    let mut channels = Vec::new();
+    if heir.npub.is_some() {
+        channels.push("nostr".to_string());
+    }
+    if heir.email.is_some() {
+        channels.push("email".to_string());
     }
-    drop(unlocked);
 
-    if !(2..=9).contains(&threshold) {
-        password.zeroize();
-        return Ok(CommandResult::err("Threshold must be 2-9"));
+    let message =
+        nostring_notify::templates::generate_heir_delivery_message(&heir.label, &backup_json);
+
+    Ok(CommandResult::ok(HeirDeliveryPreview {
+        heir_label: heir.label,
+        channels,
+        subject: message.subject,
+        body: message.body,
+    }))
+}
+
+/// Get all data needed to generate the descriptor backup file.
+///
+/// Returns the inheritance descriptor, heir info, and any locked
+/// Shamir shares for nsec inheritance.
+#[tauri::command]
+pub async fn get_descriptor_backup(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<DescriptorBackupData>, ()> {
+    let Some(mut backup) = assemble_descriptor_backup(&state) else {
+        return Ok(CommandResult::err(
+            "No inheritance policy configured. Add heirs first.",
+        ));
+    };
+    let network = *state.network.lock().unwrap();
+    if let Err(e) = nostring_inherit::parse_and_validate_descriptor(&backup.descriptor, network) {
+        return Ok(CommandResult::err(format!(
+            "Stored descriptor is corrupted, not backing it up: {}",
+            e
+        )));
     }
-    if total_shares < threshold {
-        password.zeroize();
-        return Ok(CommandResult::err("Total shares must be >= threshold"));
+
+    // Sign with the service key so heirs can detect tampering
+    if let Some(secret) = state.service_secret() {
+        if let Err(e) = sign_descriptor_backup(&mut backup, secret.as_str()) {
+            return Ok(CommandResult::err(format!("Failed to sign backup: {}", e)));
+        }
+    } else {
+        log::warn!("No service key — descriptor backup will be unsigned");
     }
-    if total_shares > 31 {
-        password.zeroize();
-        return Ok(CommandResult::err("Maximum 31 shares supported"));
+
+    if state.backup_is_stale() {
+        log::warn!("Descriptor changed since the last backup — earlier backups are stale");
+        backup.stale_warning = Some(
+            "Your inheritance descriptor changed since your last backup. \
+             Backups already given to heirs are out of date; send them this one."
+                .to_string(),
+        );
     }
+    state.record_backup_descriptor(&backup.descriptor);
 
-    let id = identifier.unwrap_or_else(|| "SEED".to_string());
+    Ok(CommandResult::ok(backup))
+}
 
-    let config = match Codex32Config::new(threshold, &id, total_shares) {
-        Ok(c) => c,
-        Err(e) => {
-            password.zeroize();
-            return Ok(CommandResult::err(format!("Invalid config: {}", e)));
-        }
+/// Whether the descriptor changed since the last backup was generated.
+#[tauri::command]
+pub async fn backup_is_stale(state: State<'_, AppState>) -> Result<bool, ()> {
+    Ok(state.backup_is_stale())
+}
+
+/// Verify a descriptor backup file received as an heir.
+///
+/// Checks the signature against the embedded `signer_npub` and returns that
+/// npub on success. Compare it with the npub the owner shared out of band.
+#[tauri::command]
+pub async fn verify_descriptor_backup(json: String) -> CommandResult<String> {
+    let backup: DescriptorBackupData = match serde_json::from_str(&json) {
+        Ok(b) => b,
+        Err(e) => return CommandResult::err(format!("Invalid backup JSON: {}", e)),
     };
 
-    // Decrypt the seed using the provided password
-    let encrypted_bytes = {
-        let seed_lock = state.encrypted_seed.lock().unwrap();
-        match &*seed_lock {
-            Some(bytes) => bytes.clone(),
-            None => {
-                password.zeroize();
-                return Ok(CommandResult::err(
-                    "No seed loaded. This feature requires a seed-based wallet (not watch-only).",
-                ));
-            }
+    match verify_descriptor_backup_data(&backup) {
+        Ok(()) => CommandResult::ok(backup.signer_npub.unwrap_or_default()),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Generate Codex32 shares for a seed
+///
+/// The shares hold the seed's BIP-39 entropy, so combining them gives back
+/// the seed words. Requires the wallet password to decrypt the entropy,
+/// which is held in memory only during share generation, then zeroized.
+#[tauri::command]
+pub async fn generate_codex32_shares(
+    threshold: u8,
+    total_shares: u8,
+    password: String,
+    identifier: Option<String>,
+    secret_len: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Vec<String>>, ()> {
+    Ok(split_wallet_seed(
+        &state,
+        Scheme::Codex32,
+        threshold,
+        total_shares,
+        &Zeroizing::new(password),
+        identifier,
+        secret_len,
+    ))
+}
+
+/// Generate seed shares in any supported scheme (`"codex32"` or
+/// `"slip39"`)
+///
+/// Same as `generate_codex32_shares` otherwise; SLIP-39 shares come back as
+/// space-separated words.
+#[tauri::command]
+pub async fn generate_seed_shares(
+    scheme: String,
+    threshold: u8,
+    total_shares: u8,
+    password: String,
+    identifier: Option<String>,
+    secret_len: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Vec<String>>, ()> {
+    let scheme: Scheme = match scheme.parse() {
+        Ok(s) => s,
+        Err(e) => return Ok(CommandResult::err(e.to_string())),
+    };
+    Ok(split_wallet_seed(
+        &state,
+        scheme,
+        threshold,
+        total_shares,
+        &Zeroizing::new(password),
+        identifier,
+        secret_len,
+    ))
+}
+
+/// Decrypt the wallet's BIP-39 entropy and split it with `scheme`.
+///
+/// `secret_len`, if given, must match the entropy length (16 bytes for 12
+/// words, 32 for 24).
+fn split_wallet_seed(
+    state: &AppState,
+    scheme: Scheme,
+    threshold: u8,
+    total_shares: u8,
+    password: &str,
+    identifier: Option<String>,
+    secret_len: Option<usize>,
+) -> CommandResult<Vec<String>> {
+    let unlocked = state.unlocked.lock().unwrap();
+    if !*unlocked {
+        return CommandResult::err("Wallet is locked");
+    }
+    drop(unlocked);
+
+    if let Some(len) = secret_len {
+        if len != 16 && len != 32 {
+            return CommandResult::err("Secret length must be 16 or 32 bytes");
+        }
+    }
+
+    match scheme {
+        Scheme::Codex32 if !(2..=9).contains(&threshold) => {
+            return CommandResult::err("Threshold must be 2-9");
         }
+        Scheme::Slip39 if !(2..=16).contains(&threshold) => {
+            return CommandResult::err("Threshold must be 2-16");
+        }
+        _ => {}
+    }
+    if total_shares < threshold {
+        return CommandResult::err("Total shares must be >= threshold");
+    }
+    let max_shares = match scheme {
+        Scheme::Codex32 => 31,
+        Scheme::Slip39 => 16,
     };
+    if total_shares > max_shares {
+        return CommandResult::err(format!("Maximum {} shares supported", max_shares));
+    }
 
-    let encrypted = match EncryptedSeed::from_bytes(&encrypted_bytes) {
-        Ok(e) => e,
-        Err(_) => {
-            password.zeroize();
-            return Ok(CommandResult::err("Corrupted seed data"));
+    let id = identifier.unwrap_or_else(|| "SEED".to_string());
+    if scheme == Scheme::Codex32 {
+        if let Err(e) = Codex32Config::new(threshold, &id, total_shares) {
+            return CommandResult::err(format!("Invalid config: {}", e));
         }
+    }
+
+    if state.encrypted_seed.lock().unwrap().is_none() {
+        return CommandResult::err(
+            "No seed loaded. This feature requires a seed-based wallet (not watch-only).",
+        );
+    }
+    let Some(encrypted_entropy) = state.encrypted_entropy() else {
+        return CommandResult::err(
+            "This wallet's seed words aren't stored. Re-import the seed words to create shares.",
+        );
     };
 
-    let decrypted_seed = match decrypt_seed(&encrypted, &password) {
-        Ok(seed) => seed,
+    // Decrypt the entropy using the provided password
+    let entropy = match decrypt_bytes(&encrypted_entropy, password) {
+        Ok(entropy) => entropy,
         Err(_) => {
-            password.zeroize();
-            return Ok(CommandResult::err("Incorrect password"));
+            return CommandResult::err("Incorrect password");
         }
     };
 
     // Password no longer needed
-    password.zeroize();
-
-    // Use first 32 bytes of the 64-byte derived seed for Codex32
-    // (Codex32 supports 16 or 32 byte secrets)
-    let seed_bytes = &decrypted_seed[..32];
+    drop(password);
 
-    use nostring_shamir::codex32::generate_shares;
+    if secret_len.is_some_and(|len| len != entropy.len()) {
+        return CommandResult::err(format!(
+            "The seed's entropy is {} bytes; shares must cover all of it",
+            entropy.len()
+        ));
+    }
 
-    let result = match generate_shares(seed_bytes, &config) {
-        Ok(shares) => {
-            let share_strings: Vec<String> = shares.iter().map(|s| s.encoded.clone()).collect();
-            Ok(CommandResult::ok(share_strings))
-        }
-        Err(e) => Ok(CommandResult::err(format!(
-            "Failed to generate shares: {}",
-            e
-        ))),
-    };
+    // entropy is Zeroizing<Vec<u8>> — auto-zeroized on drop
+    match scheme.backend(&id).split(&entropy, threshold, total_shares) {
+        Ok(shares) => CommandResult::ok(shares),
+        Err(e) => CommandResult::err(format!("Failed to generate shares: {}", e)),
+    }
+}
 
-    // decrypted_seed is Zeroizing<[u8; 64]> — auto-zeroized on drop
-    result
+/// Secret recovered from Codex32 shares
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CombinedSecret {
+    pub secret_hex: String,
+    /// Reconstructed length in bytes (16 or 32)
+    pub secret_len: usize,
 }
 
 /// Combine Codex32 shares to recover a seed
 #[tauri::command]
-pub async fn combine_codex32_shares(shares: Vec<String>) -> CommandResult<String> {
+pub async fn combine_codex32_shares(shares: Vec<String>) -> CommandResult<CombinedSecret> {
     if shares.len() < 2 {
         return CommandResult::err("Need at least 2 shares to recover");
     }
@@ -2046,19 +4519,66 @@ pub async fn combine_codex32_shares(shares: Vec<String>) -> CommandResult<String
         }
     }
 
-    use nostring_shamir::codex32::combine_shares;
+    use nostring_shamir::codex32::combine_shares_secure;
 
-    match combine_shares(&parsed_shares) {
-        Ok(mut seed_bytes) => {
-            let hex_str = hex::encode(&seed_bytes);
-            // Zeroize recovered seed bytes from memory
-            seed_bytes.zeroize();
-            CommandResult::ok(hex_str)
-        }
+    // The recovered seed is locked in memory and zeroized on drop
+    match combine_shares_secure(&parsed_shares) {
+        Ok(seed_bytes) => CommandResult::ok(CombinedSecret {
+            secret_hex: hex::encode(seed_bytes.as_slice()),
+            secret_len: seed_bytes.len(),
+        }),
+        Err(e) => CommandResult::err(format!("Failed to combine shares: {}", e)),
+    }
+}
+
+/// Combine seed shares of any supported scheme
+///
+/// The scheme is detected from the shares themselves.
+#[tauri::command]
+pub async fn combine_seed_shares(shares: Vec<String>) -> CommandResult<CombinedSecret> {
+    if shares.len() < 2 {
+        return CommandResult::err("Need at least 2 shares to recover");
+    }
+
+    // The recovered seed is locked in memory and zeroized on drop
+    match Scheme::combine_detected(&shares) {
+        Ok(seed_bytes) => CommandResult::ok(CombinedSecret {
+            secret_hex: hex::encode(seed_bytes.as_slice()),
+            secret_len: seed_bytes.len(),
+        }),
         Err(e) => CommandResult::err(format!("Failed to combine shares: {}", e)),
     }
 }
 
+/// Report how many Codex32 shares have been collected towards recovery
+///
+/// Lets the heir see "2 of 3 shares collected" while gathering shares.
+/// Shares from different splits are rejected rather than mixed.
+#[tauri::command]
+pub async fn check_recovery_progress(
+    shares: Vec<String>,
+) -> CommandResult<nostring_shamir::codex32::RecoveryProgress> {
+    use nostring_shamir::codex32::recovery_progress;
+    use nostring_shamir::ShamirError;
+
+    let mut parsed_shares: Vec<Codex32Share> = Vec::new();
+    for share_str in &shares {
+        match parse_share(share_str) {
+            Ok(share) => parsed_shares.push(share),
+            Err(e) => return CommandResult::err(format!("Invalid share '{}': {}", share_str, e)),
+        }
+    }
+
+    match recovery_progress(&parsed_shares) {
+        Ok(progress) if !progress.same_split => CommandResult::err(format!(
+            "{}: shares come from different splits",
+            ShamirError::VerificationFailed
+        )),
+        Ok(progress) => CommandResult::ok(progress),
+        Err(e) => CommandResult::err(format!("Failed to check progress: {}", e)),
+    }
+}
+
 // ============================================================================
 // Relay Storage Commands (v0.3.1 — locked share relay backup)
 // ============================================================================
@@ -2070,6 +4590,8 @@ pub struct RelayPublishStatus {
     pub heirs_targeted: usize,
     pub split_id: String,
     pub heir_results: Vec<RelayHeirStatus>,
+    /// Heir+relay pairs skipped because an earlier run already published them
+    pub skipped: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -2088,29 +4610,66 @@ pub struct RelayHeirStatus {
 /// beyond the descriptor backup file.
 ///
 /// The encrypted shares are useless without threshold — this is defense in depth.
+///
+/// With `resume` (the default) the previous split is continued and every
+/// heir+relay pair that already holds all shares is skipped, so a re-run
+/// after a partial failure only fills the gaps. `force_republish` sends to
+/// every pair regardless; `resume: false` starts a new split.
+///
+/// `privacy_mode: "giftwrap"` publishes NIP-59 gift wraps instead, so the
+/// events can't be linked to the service key. Switching modes starts a new
+/// split.
+///
+/// Pass `op_id` to make publishing cancellable with `cancel_operation`;
+/// pairs published before the cancel are kept and skipped on resume.
 #[tauri::command]
 pub async fn publish_locked_shares_to_relays(
+    resume: Option<bool>,
+    force_republish: Option<bool>,
+    privacy_mode: Option<nostring_notify::nostr_relay::PrivacyMode>,
+    op_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<RelayPublishStatus>, ()> {
+    Ok(
+        match publish_locked_shares(
+            &state,
+            resume.unwrap_or(true),
+            force_republish.unwrap_or(false),
+            privacy_mode.unwrap_or_default(),
+            op_id,
+        )
+        .await
+        {
+            Ok(status) => CommandResult::ok(status),
+            Err(e) => CommandResult::err(e),
+        },
+    )
+}
+
+/// Body of [`publish_locked_shares_to_relays`], also run by [`split_nsec`].
+async fn publish_locked_shares(
+    state: &AppState,
+    resume: bool,
+    force_republish: bool,
+    privacy_mode: nostring_notify::nostr_relay::PrivacyMode,
+    op_id: Option<String>,
+) -> Result<RelayPublishStatus, String> {
+    use nostring_notify::nostr_relay;
+    use std::collections::HashSet;
+
+    let op = state.operations.begin(op_id);
+
     // Require wallet to be unlocked
     {
         let unlocked = state.unlocked.lock().unwrap();
         if !*unlocked {
-            return Ok(CommandResult::err("Wallet is locked. Unlock first."));
+            return Err("Wallet is locked. Unlock first.".into());
         }
     }
 
     // Get the service key (sender)
-    let service_secret = {
-        let sk = state.service_key.lock().unwrap();
-        match &*sk {
-            Some(s) => s.clone(),
-            None => {
-                return Ok(CommandResult::err(
-                    "No service key generated. Go to Settings → Notifications to set up.",
-                ))
-            }
-        }
+    let Some(service_secret) = state.service_secret() else {
+        return Err("No service key generated. Go to Settings → Notifications to set up.".into());
     };
 
     // Get locked shares from DB
@@ -2123,13 +4682,11 @@ pub async fn publish_locked_shares_to_relays(
     };
 
     let Some(locked_shares) = locked_shares else {
-        return Ok(CommandResult::err(
-            "No locked shares found. Split your nsec first in the Inheritance tab.",
-        ));
+        return Err("No locked shares found. Split your nsec first in the Inheritance tab.".into());
     };
 
     if locked_shares.is_empty() {
-        return Ok(CommandResult::err("Locked shares list is empty."));
+        return Err("Locked shares list is empty.".into());
     }
 
     // Get heirs with npub from DB
@@ -2143,135 +4700,349 @@ pub async fn publish_locked_shares_to_relays(
     };
 
     if heir_contacts.is_empty() {
-        return Ok(CommandResult::err(
-            "No heirs have npub configured. Set heir npub in the Heirs tab.",
-        ));
+        return Err("No heirs have npub configured. Set heir npub in the Heirs tab.".into());
     }
 
-    // Generate a split_id
-    let split_id = nostring_notify::nostr_relay::generate_split_id();
+    // Resuming continues the previous split so finished heir+relay pairs
+    // can be skipped; otherwise start a fresh split_id
+    let previous_split = if resume && state.relay_privacy_mode() == privacy_mode {
+        let conn = state.db.lock().unwrap();
+        crate::db::config_get(&conn, "last_relay_split_id")
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+    let split_id = previous_split.unwrap_or_else(nostr_relay::generate_split_id);
 
-    // Build heir list for publish
-    let heirs: Vec<(String, String)> = heir_contacts
+    let candidates: Vec<String> = nostr_relay::DEFAULT_RELAYS
         .iter()
-        .map(|(_, label, npub)| (npub.clone(), label.clone()))
+        .map(|s| s.to_string())
         .collect();
+    let health = match op.run(nostr_relay::probe_relays(&candidates)).await {
+        Ok(health) => health,
+        Err(cancelled) => return Err(cancelled.to_string()),
+    };
+    let relays =
+        match nostr_relay::require_healthy(&health, nostr_relay::DEFAULT_MIN_HEALTHY_RELAYS) {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Failed to publish shares: {}", e)),
+        };
 
-    // Publish to relays
-    let result = nostring_notify::nostr_relay::publish_all_shares(
-        &service_secret,
-        &heirs,
-        &locked_shares,
-        &split_id,
-        None, // use default relays
-    )
-    .await;
-
-    match result {
-        Ok(publish_result) => {
-            // Log publications to SQLite
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-
-            let conn = state.db.lock().unwrap();
-            for hr in &publish_result.heir_results {
-                // Find the fingerprint for this heir
-                let fp = heir_contacts
-                    .iter()
-                    .find(|(_, _, npub)| npub == &hr.heir_npub)
-                    .map(|(fp, _, _)| fp.as_str())
-                    .unwrap_or("unknown");
-
-                for (i, eid) in hr.event_ids.iter().enumerate() {
-                    for relay in &publish_result.successful_relays {
-                        let _ = crate::db::relay_publication_insert(
-                            &conn,
-                            &split_id,
-                            fp,
-                            &hr.heir_npub,
-                            relay,
-                            Some(eid),
-                            i as i32,
-                            locked_shares.len() as i32,
-                            now,
-                            true,
-                            None,
-                        );
-                    }
-                }
+    let completed = if force_republish {
+        HashSet::new()
+    } else {
+        let conn = state.db.lock().unwrap();
+        crate::db::relay_publication_completed(&conn, &split_id, locked_shares.len())
+            .unwrap_or_default()
+    };
+    let npubs: Vec<String> = heir_contacts
+        .iter()
+        .map(|(_, _, npub)| npub.clone())
+        .collect();
+    let pending = pending_relay_publications(&npubs, &relays, &completed);
+    let skipped = npubs.len() * relays.len() - pending.iter().map(|(_, r)| r.len()).sum::<usize>();
+    if skipped > 0 {
+        log::info!(
+            "Resuming split {}: {} heir/relay pairs already published",
+            split_id,
+            skipped
+        );
+    }
 
-                if let Some(ref err) = hr.error {
-                    for relay in &publish_result.failed_relays {
-                        let _ = crate::db::relay_publication_insert(
-                            &conn,
-                            &split_id,
-                            fp,
-                            &hr.heir_npub,
-                            relay,
-                            None,
-                            0,
-                            locked_shares.len() as i32,
-                            now,
-                            false,
-                            Some(err),
-                        );
-                    }
-                }
+    let mut heir_results = Vec::new();
+    let mut shares_published = 0;
+    for (i, heir_relays) in pending {
+        let (fp, label, npub) = &heir_contacts[i];
+        let publish = match privacy_mode {
+            nostr_relay::PrivacyMode::Standard => {
+                op.run(nostr_relay::publish_shares_to_relays(
+                    service_secret.as_str(),
+                    npub,
+                    label,
+                    &locked_shares,
+                    &split_id,
+                    &heir_relays,
+                ))
+                .await
+            }
+            nostr_relay::PrivacyMode::GiftWrap => {
+                op.run(nostr_relay::publish_share_giftwrapped(
+                    service_secret.as_str(),
+                    npub,
+                    label,
+                    &locked_shares,
+                    &split_id,
+                    &heir_relays,
+                ))
+                .await
+            }
+        };
+        let Ok(publish) = publish else {
+            // Keep the split so a resumed run skips what was published
+            state.persist_config("last_relay_split_id", &split_id);
+            state.set_relay_privacy_mode(privacy_mode);
+            return Err(crate::cancel::Cancelled.to_string());
+        };
+        let mut result = publish.unwrap_or_else(|e| nostr_relay::HeirPublishResult {
+            heir_npub: npub.clone(),
+            heir_label: label.clone(),
+            shares_published: 0,
+            event_ids: Vec::new(),
+            error: Some(e.to_string()),
+            publications: Vec::new(),
+        });
+
+        // Log each share's outcome on each relay to SQLite. An heir that
+        // failed before any share went out failed every share everywhere
+        let publications = if result.publications.is_empty() {
+            let error = result.error.clone().unwrap_or_default();
+            (0..locked_shares.len())
+                .map(|index| nostr_relay::SharePublication {
+                    index,
+                    event_id: None,
+                    accepted: Vec::new(),
+                    failed: heir_relays
+                        .iter()
+                        .map(|relay| (relay.clone(), error.clone()))
+                        .collect(),
+                })
+                .collect()
+        } else {
+            std::mem::take(&mut result.publications)
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let conn = state.db.lock().unwrap();
+        for publication in &publications {
+            let outcomes = publication
+                .accepted
+                .iter()
+                .map(|relay| (relay, None))
+                .chain(publication.failed.iter().map(|(relay, e)| (relay, Some(e))));
+            for (relay, error) in outcomes {
+                let _ = crate::db::relay_publication_insert(
+                    &conn,
+                    &split_id,
+                    fp,
+                    npub,
+                    relay,
+                    publication.event_id.as_deref(),
+                    publication.index as i32,
+                    locked_shares.len() as i32,
+                    now,
+                    error.is_none(),
+                    error.map(String::as_str),
+                );
             }
+        }
+        drop(conn);
 
-            // Persist the split_id for later reference
-            let _ = crate::db::config_set(&conn, "last_relay_split_id", &split_id);
-            drop(conn);
-
-            let status = RelayPublishStatus {
-                shares_published: publish_result.shares_published,
-                heirs_targeted: heir_contacts.len(),
-                split_id,
-                heir_results: publish_result
-                    .heir_results
-                    .into_iter()
-                    .map(|hr| RelayHeirStatus {
-                        label: hr.heir_label,
-                        npub: hr.heir_npub,
-                        shares_published: hr.shares_published,
-                        event_ids: hr.event_ids,
-                        error: hr.error,
-                    })
-                    .collect(),
-            };
+        shares_published += result.shares_published;
+        heir_results.push(RelayHeirStatus {
+            label: result.heir_label,
+            npub: result.heir_npub,
+            shares_published: result.shares_published,
+            event_ids: result.event_ids,
+            error: result.error,
+        });
+    }
 
-            Ok(CommandResult::ok(status))
+    // Advertise the relays in a NIP-65 list so heirs can find the shares
+    // without knowing our relay choice. The list is signed by the service
+    // key, which would undo the gift wrap's sender hiding
+    if privacy_mode == nostr_relay::PrivacyMode::Standard {
+        if let Ok(Err(e)) = op
+            .run(nostr_relay::publish_relay_list(
+                service_secret.as_str(),
+                &relays,
+            ))
+            .await
+        {
+            log::warn!("Failed to publish NIP-65 relay list: {}", e);
         }
-        Err(e) => Ok(CommandResult::err(format!(
-            "Failed to publish shares: {}",
-            e
-        ))),
     }
+
+    // Persist the split_id (and how it was published) for later reference
+    state.persist_config("last_relay_split_id", &split_id);
+    state.set_relay_privacy_mode(privacy_mode);
+
+    Ok(RelayPublishStatus {
+        shares_published,
+        heirs_targeted: heir_contacts.len(),
+        split_id,
+        heir_results,
+        skipped,
+    })
 }
 
-/// Fetch locked shares from Nostr relays (heir recovery tool).
+/// Heir+relay pairs still missing shares, grouped by heir.
+///
+/// Returns `(index into heir_npubs, relays to publish to)` for every heir
+/// with at least one relay not in `completed`.
+fn pending_relay_publications(
+    heir_npubs: &[String],
+    relays: &[String],
+    completed: &std::collections::HashSet<(String, String)>,
+) -> Vec<(usize, Vec<String>)> {
+    heir_npubs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, npub)| {
+            let missing: Vec<String> = relays
+                .iter()
+                .filter(|relay| !completed.contains(&(npub.clone(), relay.to_string())))
+                .cloned()
+                .collect();
+            (!missing.is_empty()).then_some((i, missing))
+        })
+        .collect()
+}
+
+/// Probe relays for reachability and connection latency.
+///
+/// Defaults to the relays locked shares are published to.
+#[tauri::command]
+pub async fn check_relay_health(
+    relays: Option<Vec<String>>,
+) -> CommandResult<Vec<nostring_notify::nostr_relay::RelayHealth>> {
+    use nostring_notify::nostr_relay;
+
+    let relays = relays.unwrap_or_else(|| {
+        nostr_relay::DEFAULT_RELAYS
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    });
+    if relays.is_empty() {
+        return CommandResult::err("No relays to check");
+    }
+
+    CommandResult::ok(nostr_relay::probe_relays(&relays).await)
+}
+
+/// Fetch locked shares from Nostr relays (heir recovery tool).
 ///
 /// The heir provides their nsec and the service key's npub to find
-/// and decrypt the encrypted shares published to relays.
+/// and decrypt the encrypted shares published to relays. `relays` are the
+/// relays the heir was told about, or the defaults if omitted; they are
+/// searched for the service key's NIP-65 relay list, and the relays it
+/// names are fetched from instead when one is found. Pass
+/// `privacy_mode: "giftwrap"` if the owner published gift-wrapped shares;
+/// no relay list is published in that mode, so discovery is skipped.
+/// If the service key was rotated, `sender_npub`'s migration chain is
+/// followed and shares are taken from the newest key that has any.
+/// Pass `op_id` to make the fetch cancellable with `cancel_operation`.
+///
+/// Standard shares are fetched a couple of relays at a time, so slow
+/// connections still make progress; with `min_shares_needed` the fetch
+/// stops as soon as that many shares are in hand.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn fetch_locked_shares_from_relays(
     heir_nsec: String,
     sender_npub: String,
     split_id: Option<String>,
-) -> CommandResult<FetchedSharesResult> {
+    privacy_mode: Option<nostring_notify::nostr_relay::PrivacyMode>,
+    min_shares_needed: Option<usize>,
+    relays: Option<Vec<String>>,
+    op_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<FetchedSharesResult>, ()> {
     use nostring_notify::nostr_relay;
 
-    let result = nostr_relay::fetch_shares_from_relays(
-        &heir_nsec,
-        &sender_npub,
-        None, // use default relays
-        split_id.as_deref(),
-    )
-    .await;
+    let op = state.operations.begin(op_id);
+    let privacy_mode = privacy_mode.unwrap_or_default();
+    let configured = relays.filter(|r| !r.is_empty());
+
+    // Shares published after a key rotation are signed by the new key:
+    // try the newest key in the migration chain first, then older ones
+    let senders: Vec<String> = match op
+        .run(nostr_relay::fetch_key_migrations(&sender_npub))
+        .await
+    {
+        Ok(Ok(migrations)) => migrations
+            .into_iter()
+            .rev()
+            .chain(std::iter::once(sender_npub.clone()))
+            .collect(),
+        Ok(Err(e)) => {
+            log::warn!("Couldn't follow service key migrations: {}", e);
+            vec![sender_npub.clone()]
+        }
+        Err(cancelled) => return Ok(CommandResult::err(cancelled.to_string())),
+    };
+
+    let mut last = None;
+    for sender in &senders {
+        let discovered = if privacy_mode == nostr_relay::PrivacyMode::Standard {
+            let discovery = state
+                .relay_discovery
+                .discover(sender, configured.as_deref());
+            match op.run(discovery).await {
+                Ok(Ok(relays)) => relays,
+                Ok(Err(e)) => {
+                    log::warn!("Relay discovery failed, using configured relays: {}", e);
+                    Vec::new()
+                }
+                Err(cancelled) => return Ok(CommandResult::err(cancelled.to_string())),
+            }
+        } else {
+            Vec::new()
+        };
+        // Nothing discovered: fetch from the configured relays (or defaults)
+        let relays = if discovered.is_empty() {
+            configured.as_deref()
+        } else {
+            Some(discovered.as_slice())
+        };
+
+        let fetch = match privacy_mode {
+            nostr_relay::PrivacyMode::Standard => {
+                op.run(nostr_relay::fetch_shares_progressively(
+                    &heir_nsec,
+                    sender,
+                    relays,
+                    split_id.as_deref(),
+                    min_shares_needed,
+                    |share| {
+                        log::info!(
+                            "Found share {} of split {}",
+                            share.index + 1,
+                            share.split_id
+                        )
+                    },
+                ))
+                .await
+            }
+            nostr_relay::PrivacyMode::GiftWrap => {
+                op.run(nostr_relay::fetch_giftwrapped_shares(
+                    &heir_nsec,
+                    sender,
+                    relays,
+                    split_id.as_deref(),
+                ))
+                .await
+            }
+        };
+        let result = match fetch {
+            Ok(result) => result,
+            Err(cancelled) => return Ok(CommandResult::err(cancelled.to_string())),
+        };
+        let found = matches!(&result, Ok(r) if !r.shares.is_empty());
+        last = Some(result);
+        if found {
+            if *sender != sender_npub {
+                log::info!("Shares found under migrated service key {}", sender);
+            }
+            break;
+        }
+    }
+    let result = last.expect("the migration chain includes sender_npub");
 
-    match result {
+    Ok(match result {
         Ok(fetch_result) => {
             let shares: Vec<String> = fetch_result
                 .shares
@@ -2286,7 +5057,7 @@ pub async fn fetch_locked_shares_from_relays(
             })
         }
         Err(e) => CommandResult::err(format!("Failed to fetch shares: {}", e)),
-    }
+    })
 }
 
 /// Result of fetching shares from relays
@@ -2384,6 +5155,19 @@ pub struct PresignedCheckinStatus {
     pub low_warning: bool,
     /// Whether the stack is empty
     pub empty: bool,
+    /// Blocks until the timelock expires once the whole stack is used
+    /// (`None` without a policy status or inheritance config)
+    pub runway_blocks: Option<u64>,
+    /// Whether the stack runs out before the requested horizon
+    pub needs_more: bool,
+    /// Active PSBTs to keep for the horizon, at most
+    /// `MAX_PRESIGNED_CHAIN` (`None` without an inheritance config)
+    pub recommended_total: Option<u32>,
+    /// Whether the horizon needs more check-ins than one chain holds; a
+    /// full stack then still `needs_more`, to be topped up as it runs low
+    pub exceeds_max: bool,
+    /// Whether fewer than `recommended_total` are active
+    pub should_replenish: bool,
     /// The active PSBTs
     pub active: Vec<PresignedCheckinInfo>,
 }
@@ -2418,6 +5202,159 @@ impl From<&PresignedCheckinRow> for PresignedCheckinInfo {
     }
 }
 
+/// Decode a base64 PSBT and extract its transaction, which only succeeds
+/// once it is fully signed.
+fn decode_signed_psbt(psbt_base64: &str) -> Result<bitcoin::Transaction, String> {
+    use base64::prelude::*;
+    let psbt_bytes = BASE64_STANDARD
+        .decode(psbt_base64.trim())
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+    let psbt = Psbt::deserialize(&psbt_bytes).map_err(|e| format!("Invalid PSBT: {}", e))?;
+    psbt.extract_tx().map_err(|e| {
+        format!(
+            "PSBT is not fully signed: {}. Sign it on your hardware wallet first.",
+            e
+        )
+    })
+}
+
+/// Check a pre-signed chain link by link.
+///
+/// Every PSBT must be fully signed and spend an output of the transaction
+/// before it; the first one must spend `prior_txid` (the tail of the
+/// existing stack) when given. Returns every problem found, not just the
+/// first.
+fn validate_presigned_chain(
+    psbts: &[String],
+    prior_txid: Option<bitcoin::Txid>,
+) -> Result<Vec<(String, bitcoin::OutPoint)>, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut links = Vec::with_capacity(psbts.len());
+    let mut prev_txid = prior_txid;
+
+    for (i, psbt_base64) in psbts.iter().enumerate() {
+        let tx = match decode_signed_psbt(psbt_base64) {
+            Ok(tx) => tx,
+            Err(e) => {
+                errors.push(format!("PSBT #{}: {}", i, e));
+                prev_txid = None;
+                continue;
+            }
+        };
+
+        let spends = match prev_txid {
+            Some(prev) => tx
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .find(|outpoint| outpoint.txid == prev),
+            None => tx.input.first().map(|input| input.previous_output),
+        };
+        match spends {
+            Some(outpoint) => links.push((psbt_base64.trim().to_string(), outpoint)),
+            None if i == 0 => errors
+                .push("PSBT #0 does not spend the last check-in already in the stack".to_string()),
+            None => errors.push(format!(
+                "PSBT #{} does not spend PSBT #{}'s output",
+                i,
+                i - 1
+            )),
+        }
+        prev_txid = Some(tx.compute_txid());
+    }
+
+    if errors.is_empty() {
+        Ok(links)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Outcome of a chain import
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChainImportResult {
+    pub imported: usize,
+    pub errors: Vec<String>,
+}
+
+/// Validate `psbts` against the existing stack and insert them as one
+/// transaction, continuing its sequence numbering. Nothing is stored if any
+/// link is invalid.
+fn import_presigned_chain_into(
+    conn: &rusqlite::Connection,
+    psbts: &[String],
+    now: u64,
+) -> ChainImportResult {
+    use crate::db::NewPresignedCheckin;
+
+    let failed = |errors| ChainImportResult {
+        imported: 0,
+        errors,
+    };
+    if psbts.is_empty() {
+        return failed(vec!["No PSBTs to import".to_string()]);
+    }
+
+    let all = crate::db::presigned_checkin_list_all(conn).unwrap_or_default();
+    let next_sequence = all.iter().map(|r| r.sequence_index + 1).max().unwrap_or(0);
+    let prior_txid = crate::db::presigned_checkin_list_active(conn)
+        .unwrap_or_default()
+        .last()
+        .and_then(|row| decode_signed_psbt(&row.psbt_base64).ok())
+        .map(|tx| tx.compute_txid());
+
+    let links = match validate_presigned_chain(psbts, prior_txid) {
+        Ok(links) => links,
+        Err(errors) => return failed(errors),
+    };
+    let rows: Vec<NewPresignedCheckin> = links
+        .into_iter()
+        .enumerate()
+        .map(|(i, (psbt_base64, spends))| NewPresignedCheckin {
+            psbt_base64,
+            sequence_index: next_sequence + i as i64,
+            spending_txid: Some(spends.txid.to_string()),
+            spending_vout: Some(spends.vout as i64),
+        })
+        .collect();
+
+    match crate::db::presigned_checkin_add_chain(conn, &rows, now) {
+        Ok(imported) => ChainImportResult {
+            imported,
+            errors: Vec::new(),
+        },
+        Err(e) => failed(vec![format!("Database error: {}", e)]),
+    }
+}
+
+/// Import a whole chain of signed check-in PSBTs at once.
+///
+/// `psbts` are in chain order, as produced by `generate_checkin_psbt_chain`.
+/// The chain is validated first and stored atomically: if any link is
+/// invalid, nothing is imported and `errors` lists what's wrong.
+#[tauri::command]
+pub async fn import_presigned_chain(
+    psbts: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<ChainImportResult>, ()> {
+    let unlocked = state.unlocked.lock().unwrap();
+    if !*unlocked {
+        return Ok(CommandResult::err("Wallet is locked"));
+    }
+    drop(unlocked);
+
+    let now = state.clock.now_secs();
+    let conn = state.db.lock().unwrap();
+    let result = import_presigned_chain_into(&conn, &psbts, now);
+    if result.imported > 0 {
+        log::info!(
+            "Imported a chain of {} pre-signed check-ins",
+            result.imported
+        );
+    }
+    Ok(CommandResult::ok(result))
+}
+
 /// Add a pre-signed (already signed) check-in PSBT to the stack.
 ///
 /// The user signs multiple sequential check-in PSBTs on their hardware wallet,
@@ -2439,27 +5376,8 @@ pub async fn add_presigned_checkin(
     }
     drop(unlocked);
 
-    // Validate the PSBT is parseable
-    use base64::prelude::*;
-    let psbt_bytes = match BASE64_STANDARD.decode(&signed_psbt_base64) {
-        Ok(b) => b,
-        Err(e) => return Ok(CommandResult::err(format!("Invalid base64: {}", e))),
-    };
-
-    let psbt: Psbt = match Psbt::deserialize(&psbt_bytes) {
-        Ok(p) => p,
-        Err(e) => return Ok(CommandResult::err(format!("Invalid PSBT: {}", e))),
-    };
-
-    // Verify the PSBT can extract a transaction (i.e., it's signed)
-    match psbt.extract_tx() {
-        Ok(_) => {}
-        Err(e) => {
-            return Ok(CommandResult::err(format!(
-                "PSBT is not fully signed: {}. Sign it on your hardware wallet first.",
-                e
-            )))
-        }
+    if let Err(e) = decode_signed_psbt(&signed_psbt_base64) {
+        return Ok(CommandResult::err(e));
     }
 
     let now = std::time::SystemTime::now()
@@ -2488,22 +5406,124 @@ pub async fn add_presigned_checkin(
     }
 }
 
+/// Default auto-broadcast threshold: 30 days
+const DEFAULT_AUTO_BROADCAST_THRESHOLD_BLOCKS: i64 = 4320;
+
+/// Most sequential check-ins `generate_checkin_psbt_chain` builds at once
+const MAX_PRESIGNED_CHAIN: u32 = 12;
+
+/// How many pre-signed check-ins a protection horizon calls for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PsbtChainRecommendation {
+    /// Sequential check-ins needed to cover the whole horizon
+    pub checkins_needed: u32,
+    /// What to generate now: `checkins_needed`, capped at
+    /// `MAX_PRESIGNED_CHAIN`
+    pub recommended: u32,
+    /// Whether the horizon needs more than one chain; generate the rest
+    /// once this one runs low
+    pub exceeds_max: bool,
+}
+
+impl PsbtChainRecommendation {
+    fn new(checkins_needed: u32) -> Self {
+        Self {
+            checkins_needed,
+            recommended: checkins_needed.min(MAX_PRESIGNED_CHAIN),
+            exceeds_max: checkins_needed > MAX_PRESIGNED_CHAIN,
+        }
+    }
+}
+
+/// Recommend how many pre-signed check-ins to generate so the heirs stay
+/// locked out for `horizon_days`.
+///
+/// Each check-in resets the lock to `timelock_blocks` and is auto-broadcast
+/// at `threshold_blocks` remaining (default 30 days).
+#[tauri::command]
+pub async fn recommend_psbt_chain_length(
+    horizon_days: u32,
+    timelock_blocks: u32,
+    threshold_blocks: Option<i64>,
+) -> Result<CommandResult<PsbtChainRecommendation>, ()> {
+    use nostring_inherit::checkin::{recommended_chain_length, BLOCKS_PER_DAY};
+
+    if horizon_days == 0 || timelock_blocks == 0 {
+        return Ok(CommandResult::err(
+            "Horizon and timelock must be greater than zero",
+        ));
+    }
+    let threshold = threshold_blocks
+        .unwrap_or(DEFAULT_AUTO_BROADCAST_THRESHOLD_BLOCKS)
+        .max(0) as u32;
+    if threshold >= timelock_blocks {
+        return Ok(CommandResult::err(
+            "Auto-broadcast threshold must be shorter than the timelock",
+        ));
+    }
+
+    let needed = recommended_chain_length(
+        horizon_days.saturating_mul(BLOCKS_PER_DAY),
+        timelock_blocks,
+        threshold,
+    );
+    Ok(CommandResult::ok(PsbtChainRecommendation::new(needed)))
+}
+
 /// List the pre-signed check-in stack status.
+///
+/// Also estimates whether the stack lasts `horizon_blocks` (default one
+/// year) when check-ins are auto-broadcast at `threshold_blocks`.
 #[tauri::command]
 pub async fn get_presigned_checkin_status(
+    horizon_blocks: Option<u32>,
+    threshold_blocks: Option<i64>,
     state: State<'_, AppState>,
 ) -> Result<PresignedCheckinStatus, ()> {
+    use nostring_inherit::checkin::{checkin_runway, recommended_chain_length, BLOCKS_PER_YEAR};
+
+    let blocks_remaining = state
+        .policy_status
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.blocks_remaining);
+    let timelock_blocks = state
+        .ensure_inheritance_config()
+        .map(|config| config.timelock_blocks as u32);
+
     let conn = state.db.lock().unwrap();
 
     let active = crate::db::presigned_checkin_list_active(&conn).unwrap_or_default();
     let all = crate::db::presigned_checkin_list_all(&conn).unwrap_or_default();
     let active_count = active.len() as i64;
 
+    let threshold = threshold_blocks
+        .unwrap_or(DEFAULT_AUTO_BROADCAST_THRESHOLD_BLOCKS)
+        .max(0) as u32;
+    let horizon = horizon_blocks.unwrap_or(BLOCKS_PER_YEAR);
+    let runway = blocks_remaining
+        .zip(timelock_blocks)
+        .map(|(remaining, timelock)| {
+            checkin_runway(remaining, timelock, threshold, active.len() as u32, horizon)
+        });
+    // From the current lock when known, otherwise from a fresh reset
+    let recommendation = runway
+        .map(|r| r.checkins_needed)
+        .or_else(|| timelock_blocks.map(|t| recommended_chain_length(horizon, t, threshold)))
+        .map(PsbtChainRecommendation::new);
+    let recommended_total = recommendation.map(|r| r.recommended);
+
     let status = PresignedCheckinStatus {
         active_count,
         total_count: all.len(),
         low_warning: active_count > 0 && active_count < 2,
         empty: active_count == 0,
+        runway_blocks: runway.map(|r| r.runway_blocks),
+        needs_more: runway.is_some_and(|r| r.needs_more),
+        recommended_total,
+        exceeds_max: recommendation.is_some_and(|r| r.exceeds_max),
+        should_replenish: recommended_total.is_some_and(|r| (active_count as u32) < r),
         active: active.iter().map(PresignedCheckinInfo::from).collect(),
     };
 
@@ -2532,8 +5552,7 @@ pub async fn auto_broadcast_checkin(
     }
     drop(unlocked);
 
-    // Default threshold: 30 days (4320 blocks)
-    let threshold = threshold_blocks.unwrap_or(4320);
+    let threshold = threshold_blocks.unwrap_or(DEFAULT_AUTO_BROADCAST_THRESHOLD_BLOCKS);
 
     // Check current policy status
     let status = {
@@ -2603,21 +5622,9 @@ pub async fn auto_broadcast_checkin(
         }
     };
 
-    // Broadcast
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
-    let client = match ElectrumClient::new(&electrum_url, network) {
-        Ok(c) => c,
-        Err(e) => {
-            return Ok(CommandResult::err(format!(
-                "Failed to connect to Electrum: {}",
-                e
-            )))
-        }
-    };
-
-    match client.broadcast(&tx) {
+    // Broadcast over Electrum and HTTP; one acceptance is enough
+    let report = crate::broadcast::broadcast_tx(&state, &tx).await;
+    match report.result() {
         Ok(txid) => {
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -2637,8 +5644,12 @@ pub async fn auto_broadcast_checkin(
                 );
             }
 
-            // Log the check-in
-            state.log_checkin(&txid_str);
+            // Log the check-in (advances a rotating check-in)
+            state.log_checkin(&tx);
+            state.audit(
+                AuditAction::Broadcast,
+                &format!("pre-signed check-in {}", txid_str),
+            );
 
             // Check remaining stack and warn
             let remaining = {
@@ -2735,10 +5746,14 @@ pub async fn delete_presigned_checkin(
 /// The user exports these to their hardware wallet, signs them all,
 /// then imports the signed versions via `add_presigned_checkin`.
 ///
+/// Signing fixes the fee, so the whole chain uses the rate resolved now
+/// from `fee_strategy` or the configured default (see [`checkin_fee_rate`]).
+///
 /// Returns base64-encoded unsigned PSBTs.
 #[tauri::command]
 pub async fn generate_checkin_psbt_chain(
     count: usize,
+    fee_strategy: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResult<Vec<String>>, ()> {
     let unlocked = state.unlocked.lock().unwrap();
@@ -2747,10 +5762,11 @@ pub async fn generate_checkin_psbt_chain(
     }
     drop(unlocked);
 
-    if count == 0 || count > 12 {
-        return Ok(CommandResult::err(
-            "Count must be 1-12 (more than 12 sequential check-ins is impractical)",
-        ));
+    if count == 0 || count > MAX_PRESIGNED_CHAIN as usize {
+        return Ok(CommandResult::err(format!(
+            "Count must be 1-{max} (more than {max} sequential check-ins is impractical)",
+            max = MAX_PRESIGNED_CHAIN
+        )));
     }
 
     let config = {
@@ -2765,10 +5781,7 @@ pub async fn generate_checkin_psbt_chain(
         }
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
-    let client = match ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => {
             return Ok(CommandResult::err(format!(
@@ -2778,47 +5791,61 @@ pub async fn generate_checkin_psbt_chain(
         }
     };
 
-    use miniscript::descriptor::DescriptorPublicKey;
-    use miniscript::Descriptor;
-
-    let descriptor: Descriptor<DescriptorPublicKey> = match config.descriptor.parse() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResult::err(format!("Invalid descriptor: {}", e))),
-    };
+    let network = *state.network.lock().unwrap();
+    let descriptor =
+        match nostring_inherit::parse_and_validate_descriptor(&config.descriptor, network) {
+            Ok(d) => d,
+            Err(e) => return Ok(CommandResult::err(format!("Invalid descriptor: {}", e))),
+        };
 
-    let derived = match descriptor.at_derivation_index(0) {
-        Ok(d) => d,
-        Err(e) => {
-            return Ok(CommandResult::err(format!(
-                "Failed to derive script: {}",
-                e
-            )))
-        }
+    // With rotation on, each check-in in the chain moves the funds to the
+    // next receive index, so the chain walks the indexes forward
+    let rotate = state.checkin_rotation();
+    let mut index = state.checkin_address_index();
+    let last_index = index.saturating_add(if rotate { count as u32 } else { 0 });
+    let scripts = crate::state::checkin_scripts(&descriptor, last_index);
+    let script_at = |index: u32| {
+        scripts
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, script)| script.clone())
     };
-    let script = derived.script_pubkey();
 
-    // Get current UTXOs
-    let utxos = match client.get_utxos_for_script(&script) {
-        Ok(u) => u,
-        Err(e) => return Ok(CommandResult::err(format!("Failed to get UTXOs: {}", e))),
+    // The chain starts from the first UTXO at the newest funded index
+    let funded = match funded_checkin_utxos(&client, &descriptor, index) {
+        Ok(f) => f,
+        Err(e) => return Ok(CommandResult::err(e)),
     };
-
-    if utxos.is_empty() {
+    let Some((start, utxos)) = funded.first() else {
         return Ok(CommandResult::err(
             "No UTXOs found for inheritance address. Deposit funds first.",
         ));
-    }
+    };
+    warn_unrefreshed(1, funded.iter().map(|(_, utxos)| utxos.len()).sum());
+    index = *start;
+    let Some(mut script) = script_at(index) else {
+        return Ok(CommandResult::err("Failed to derive script"));
+    };
 
-    let utxo = &utxos[0];
-    let fee_rate = 10u64;
+    let fee_rate = match checkin_fee_rate(&state, fee_strategy.as_deref()) {
+        Ok(rate) => rate,
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
 
-    use nostring_inherit::checkin::{CheckinTxBuilder, InheritanceUtxo as InhUtxo};
+    use nostring_inherit::checkin::{
+        CheckinDestination, CheckinTxBuilder, InheritanceUtxo as InhUtxo,
+    };
 
     let mut psbts: Vec<String> = Vec::with_capacity(count);
-    let mut current_utxo = InhUtxo::new(utxo.outpoint, utxo.value, utxo.height, script.to_owned());
+    let mut current_utxo = utxos[0].clone();
 
     for i in 0..count {
-        let builder = CheckinTxBuilder::new(current_utxo.clone(), descriptor.clone(), fee_rate, 0);
+        let mut builder =
+            CheckinTxBuilder::new(current_utxo.clone(), descriptor.clone(), fee_rate, index)
+                .with_network(network);
+        if rotate {
+            builder = builder.with_destination(CheckinDestination::NextIndex);
+        }
 
         let psbt = match builder.build_psbt() {
             Ok(p) => p,
@@ -2848,6 +5875,13 @@ pub async fn generate_checkin_psbt_chain(
         };
 
         let txid = tx.compute_txid();
+        if rotate {
+            index += 1;
+            script = match script_at(index) {
+                Some(script) => script,
+                None => return Ok(CommandResult::err("Failed to derive script")),
+            };
+        }
 
         // Find the output that goes back to our script (the check-in output)
         let (vout, value) = tx
@@ -2883,6 +5917,8 @@ pub async fn generate_checkin_psbt_chain(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{test_heir_row, test_xpub};
+    use bitcoin::NetworkKind;
 
     #[test]
     fn test_password_hash_roundtrip() {
@@ -2939,4 +5975,1247 @@ mod tests {
         assert!(verify_password_hash("пароль🔑", &hash));
         assert!(!verify_password_hash("пароль", &hash));
     }
+
+    #[test]
+    fn test_delivery_decision_suppresses_unchanged_backup() {
+        let last = (1_000, Some("hash_v1".to_string()));
+
+        // Same content: suppressed even long after the cooldown
+        assert_eq!(
+            delivery_decision(Some(&last), "hash_v1", 1_000_000, 86400, false),
+            DeliveryDecision::SkipUnchanged
+        );
+
+        // Changed content: sent even inside the cooldown window
+        assert_eq!(
+            delivery_decision(Some(&last), "hash_v2", 1_001, 86400, false),
+            DeliveryDecision::Send
+        );
+
+        // Force overrides de-duplication
+        assert_eq!(
+            delivery_decision(Some(&last), "hash_v1", 1_001, 86400, true),
+            DeliveryDecision::Send
+        );
+    }
+
+    #[test]
+    fn test_delivery_decision_first_and_legacy() {
+        // Never delivered
+        assert_eq!(
+            delivery_decision(None, "hash", 1_000, 86400, false),
+            DeliveryDecision::Send
+        );
+
+        // Legacy row without a hash falls back to the time cooldown
+        let legacy = (1_000, None);
+        assert_eq!(
+            delivery_decision(Some(&legacy), "hash", 2_000, 86400, false),
+            DeliveryDecision::SkipCooldown
+        );
+        assert_eq!(
+            delivery_decision(Some(&legacy), "hash", 1_000 + 86400, 86400, false),
+            DeliveryDecision::Send
+        );
+    }
+
+    /// A "signed" check-in PSBT spending `prev`, with its txid
+    fn signed_checkin_link(prev: bitcoin::OutPoint) -> (String, bitcoin::Txid) {
+        use base64::prelude::*;
+        use bitcoin::{
+            absolute::LockTime, transaction::Version, Amount, ScriptBuf, Sequence, Transaction,
+            TxIn, TxOut, Witness,
+        };
+
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: prev,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(99_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        let txid = tx.compute_txid();
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: ScriptBuf::new(),
+        });
+        psbt.inputs[0].final_script_witness = Some(Witness::from_slice(&[vec![1u8; 64]]));
+        (BASE64_STANDARD.encode(psbt.serialize()), txid)
+    }
+
+    fn funding_outpoint() -> bitcoin::OutPoint {
+        use bitcoin::hashes::Hash;
+        bitcoin::OutPoint::new(bitcoin::Txid::from_byte_array([9; 32]), 0)
+    }
+
+    #[test]
+    fn test_extract_signed_tx_requires_descriptor_for_unfinalized() {
+        use base64::prelude::*;
+
+        let (encoded, txid) = signed_checkin_link(funding_outpoint());
+        let mut psbt = Psbt::deserialize(&BASE64_STANDARD.decode(encoded).unwrap()).unwrap();
+
+        // Finalized by the signer: extracted as-is, no descriptor needed
+        let tx = extract_signed_tx(&psbt, None, bitcoin::Network::Testnet).unwrap();
+        assert_eq!(tx.compute_txid(), txid);
+
+        // Only partially signed: finalizing needs the inheritance descriptor
+        psbt.inputs[0].final_script_witness = None;
+        let err = extract_signed_tx(&psbt, None, bitcoin::Network::Testnet).unwrap_err();
+        assert!(err.contains("not finalized"), "{}", err);
+        let err =
+            extract_signed_tx(&psbt, Some("wsh(garbage)"), bitcoin::Network::Testnet).unwrap_err();
+        assert!(err.contains("Invalid descriptor"), "{}", err);
+    }
+
+    #[test]
+    fn test_seed_shares_rebuild_the_mnemonic() {
+        use nostring_core::seed::compact_seedqr_to_mnemonic;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        let twelve = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let twenty_four = "letter advice cage absurd amount doctor acoustic avoid letter advice \
+                           cage absurd amount doctor acoustic avoid letter advice cage absurd \
+                           amount doctor acoustic bless";
+
+        for words in [twelve, twenty_four] {
+            let mnemonic = parse_mnemonic(words).unwrap();
+            let entropy = mnemonic.to_entropy();
+            store_wallet_seed(
+                &state,
+                &derive_seed(&mnemonic, ""),
+                Some(&entropy[..]),
+                "pw",
+            )
+            .unwrap();
+
+            for scheme in [Scheme::Codex32, Scheme::Slip39] {
+                let split = split_wallet_seed(&state, scheme, 2, 3, "pw", None, None);
+                let shares = split.data.unwrap_or_else(|| panic!("{:?}", split.error));
+
+                // Any two shares give back exactly the entropy, and the words
+                let secret = Scheme::combine_detected(&shares[1..]).unwrap();
+                assert_eq!(secret.as_slice(), &entropy[..], "{:?}", scheme);
+                let rebuilt = compact_seedqr_to_mnemonic(secret.as_slice()).unwrap();
+                assert_eq!(rebuilt.to_string(), mnemonic.to_string());
+            }
+
+            // An explicit length must match the entropy
+            let other_len = if entropy.len() == 16 { 32 } else { 16 };
+            let split =
+                split_wallet_seed(&state, Scheme::Codex32, 2, 3, "pw", None, Some(other_len));
+            assert!(split.error.unwrap().contains("entropy"));
+        }
+
+        let split = split_wallet_seed(&state, Scheme::Codex32, 2, 3, "wrong", None, None);
+        assert_eq!(split.error.as_deref(), Some("Incorrect password"));
+
+        // A seed imported without its words has no entropy to split
+        let seed = derive_seed(&parse_mnemonic(twelve).unwrap(), "");
+        store_wallet_seed(&state, &seed, None, "pw").unwrap();
+        assert!(state.encrypted_entropy().is_none());
+        let split = split_wallet_seed(&state, Scheme::Slip39, 2, 3, "pw", None, None);
+        assert!(split.error.unwrap().contains("seed words"));
+    }
+
+    #[test]
+    fn test_import_valid_presigned_chain() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let conn = crate::db::open_db(file.path()).unwrap();
+
+        let (psbt0, txid0) = signed_checkin_link(funding_outpoint());
+        let (psbt1, txid1) = signed_checkin_link(bitcoin::OutPoint::new(txid0, 0));
+        let (psbt2, txid2) = signed_checkin_link(bitcoin::OutPoint::new(txid1, 0));
+
+        let result = import_presigned_chain_into(&conn, &[psbt0, psbt1, psbt2], 1_000);
+        assert_eq!(result.imported, 3);
+        assert!(result.errors.is_empty());
+
+        let rows = crate::db::presigned_checkin_list_active(&conn).unwrap();
+        let sequence: Vec<i64> = rows.iter().map(|r| r.sequence_index).collect();
+        assert_eq!(sequence, vec![0, 1, 2]);
+        assert_eq!(rows[2].spending_txid, Some(txid1.to_string()));
+
+        // A follow-up chain must continue from the last imported link
+        let (orphan, _) = signed_checkin_link(funding_outpoint());
+        assert_eq!(
+            import_presigned_chain_into(&conn, &[orphan], 2_000).imported,
+            0
+        );
+
+        let (psbt3, _) = signed_checkin_link(bitcoin::OutPoint::new(txid2, 0));
+        assert_eq!(
+            import_presigned_chain_into(&conn, &[psbt3], 2_000).imported,
+            1
+        );
+        let last = crate::db::presigned_checkin_list_active(&conn).unwrap();
+        assert_eq!(last.last().unwrap().sequence_index, 3);
+    }
+
+    #[test]
+    fn test_import_broken_chain_rolls_back() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let conn = crate::db::open_db(file.path()).unwrap();
+
+        let (psbt0, _) = signed_checkin_link(funding_outpoint());
+        // Middle link spends something other than PSBT #0's output
+        let (psbt1, txid1) = signed_checkin_link(funding_outpoint());
+        let (psbt2, _) = signed_checkin_link(bitcoin::OutPoint::new(txid1, 0));
+
+        let result = import_presigned_chain_into(&conn, &[psbt0, psbt1, psbt2], 1_000);
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].contains("PSBT #1"));
+        assert!(crate::db::presigned_checkin_list_all(&conn)
+            .unwrap()
+            .is_empty());
+
+        // Garbage is reported, not silently dropped
+        let result = import_presigned_chain_into(&conn, &["not a psbt".to_string()], 1_000);
+        assert_eq!(result.imported, 0);
+        assert!(result.errors[0].contains("Invalid base64"));
+    }
+
+    #[test]
+    fn test_legacy_delivery_cooldown_with_mock_clock() {
+        use nostring_core::clock::{Clock, MockClock};
+
+        let dir = tempfile::tempdir().unwrap();
+        let clock = MockClock::new(1_700_000_000);
+        let state = AppState::from_db_path(dir.path().join("test.db")).with_clock(clock.clone());
+
+        // A pre-hashing delivery row, stamped by the mock clock
+        state.log_delivery("abcd1234", "nostr", true, None, None);
+        let decide = || {
+            let last = state.last_heir_delivery("abcd1234", "nostr");
+            delivery_decision(
+                last.as_ref(),
+                "hash",
+                state.clock.now_secs(),
+                nostring_notify::DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS,
+                false,
+            )
+        };
+        assert_eq!(decide(), DeliveryDecision::SkipCooldown);
+
+        clock.advance(nostring_notify::DEFAULT_HEIR_DELIVERY_COOLDOWN_SECS - 1);
+        assert_eq!(decide(), DeliveryDecision::SkipCooldown);
+
+        clock.advance(1);
+        assert_eq!(decide(), DeliveryDecision::Send);
+
+        // Other heirs and channels have their own history
+        assert!(state.last_heir_delivery("abcd1234", "email").is_none());
+    }
+
+    #[test]
+    fn test_notify_thresholds_from_config_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+
+        // No rows: the stock escalation at 144 blocks
+        let config = load_notify_config(&state).unwrap();
+        assert!(config.escalates_to_heirs(144));
+        assert!(!config.escalates_to_heirs(300));
+        assert_eq!(config.heir_delivery_cooldown_secs, 86_400);
+
+        // Three days' notice for heirs instead of one
+        NotifyThresholds {
+            critical_threshold_blocks: 432,
+            heir_delivery_cooldown_secs: 3_600,
+            ..NotifyThresholds::default()
+        }
+        .persist(&state);
+        let config = load_notify_config(&state).unwrap();
+        assert!(config.escalates_to_heirs(300));
+        assert!(!config.escalates_to_heirs(433));
+        assert_eq!(config.heir_delivery_cooldown_secs, 3_600);
+    }
+
+    #[test]
+    fn test_non_monotonic_notify_thresholds_rejected_at_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+
+        // Urgent earlier than Warning
+        state.persist_config("notify_urgent_days", "14");
+        let err = load_notify_config(&state).unwrap_err();
+        assert!(err.contains("escalate"), "{}", err);
+
+        state.persist_config("notify_urgent_days", "soon");
+        let err = load_notify_config(&state).unwrap_err();
+        assert!(err.contains("notify_urgent_days"), "{}", err);
+
+        state.persist_config("notify_urgent_days", "2");
+        assert!(load_notify_config(&state).is_ok());
+    }
+
+    #[test]
+    fn test_failed_refresh_returns_cached_status() {
+        use nostring_core::clock::MockClock;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"))
+            .with_clock(MockClock::new(1_700_000_000));
+
+        // Nothing cached yet: the error surfaces
+        let err =
+            resolve_policy_status(&state, None, Some(26280), Err("offline".into())).unwrap_err();
+        assert_eq!(err, "offline");
+
+        let snapshot = crate::db::ChainSnapshot {
+            block_height: 880_000,
+            balance_sats: Some(250_000),
+            utxos: vec![crate::db::CachedUtxo {
+                outpoint: format!("{}:1", "cd".repeat(32)),
+                value_sats: 250_000,
+                height: 879_000,
+            }],
+            fetched_at: 1_700_000_000,
+        };
+        let fresh = resolve_policy_status(&state, None, Some(26280), Ok(snapshot)).unwrap();
+        assert!(!fresh.stale);
+        assert_eq!(fresh.last_updated, Some(1_700_000_000));
+
+        let cached = resolve_policy_status(
+            &state,
+            None,
+            Some(26280),
+            Err("Failed to connect to Electrum".into()),
+        )
+        .unwrap();
+        assert!(cached.stale);
+        assert_eq!(cached.last_updated, Some(1_700_000_000));
+        assert_eq!(cached.current_block, 880_000);
+        assert_eq!(cached.balance_sats, Some(250_000));
+        assert_eq!(cached.blocks_remaining, fresh.blocks_remaining);
+
+        // A restart starts from the cached state, still flagged stale
+        drop(state);
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        let status = state.policy_status.lock().unwrap().clone().unwrap();
+        assert!(status.stale);
+        assert_eq!(status.balance_sats, Some(250_000));
+    }
+
+    #[test]
+    fn test_failed_balance_fetch_keeps_cached_balance() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+
+        let snapshot = crate::db::ChainSnapshot {
+            block_height: 880_000,
+            balance_sats: Some(250_000),
+            utxos: vec![crate::db::CachedUtxo {
+                outpoint: format!("{}:1", "cd".repeat(32)),
+                value_sats: 250_000,
+                height: 879_000,
+            }],
+            fetched_at: 1_700_000_000,
+        };
+        resolve_policy_status(&state, None, Some(26280), Ok(snapshot.clone())).unwrap();
+
+        // The height came through but the UTXO lookup failed
+        let partial = crate::db::ChainSnapshot {
+            block_height: 880_010,
+            balance_sats: None,
+            utxos: Vec::new(),
+            fetched_at: 1_700_006_000,
+        };
+        let status = resolve_policy_status(&state, None, Some(26280), Ok(partial)).unwrap();
+        assert_eq!(status.current_block, 880_010);
+        assert_eq!(status.balance_sats, Some(250_000));
+        assert!(status.stale);
+        assert_eq!(status.last_updated, Some(1_700_000_000));
+
+        let conn = state.db.lock().unwrap();
+        let cached = crate::db::chain_cache_get(&conn, "").unwrap().unwrap();
+        assert_eq!(cached.balance_sats, Some(250_000));
+        assert_eq!(cached.utxos, snapshot.utxos);
+    }
+
+    #[test]
+    fn test_custom_signet_parameters() {
+        use bitcoin::hashes::Hash;
+        use bitcoin::Network;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        let default_signet = bitcoin::constants::genesis_block(Network::Signet).block_hash();
+
+        // Standard networks use their own genesis
+        state.set_network(Network::Signet);
+        assert_eq!(state.expected_genesis(), None);
+
+        let genesis = bitcoin::BlockHash::from_byte_array([0x42; 32]);
+        let signet =
+            nostring_electrum::CustomSignet::from_hex("51", Some(&genesis.to_string())).unwrap();
+        state.set_network(Network::Bitcoin);
+        state.set_custom_signet(&signet);
+        assert_eq!(*state.network.lock().unwrap(), Network::Signet);
+        assert_eq!(state.expected_genesis(), Some(genesis));
+        assert_eq!(state.custom_signet(), Some(signet));
+
+        // Addresses on a custom signet use the signet (test) parameters
+        let heir = HeirKey::new(
+            "Heir",
+            test_xpub(NetworkKind::Main, 2).fingerprint(),
+            test_xpub(NetworkKind::Main, 2),
+            Some(DerivationPath::from_str("m/84'/1'/0'").unwrap()),
+        );
+        let tpub = test_xpub(NetworkKind::Test, 7).to_string();
+        let (_, address) =
+            crate::state::derive_inheritance_descriptor(&tpub, &[heir], 144, Network::Signet)
+                .unwrap();
+        assert!(address.to_string().starts_with("tb1"), "{}", address);
+
+        // Without a genesis override, the default signet genesis is expected
+        let shared = nostring_electrum::CustomSignet::from_hex("51", None).unwrap();
+        state.set_custom_signet(&shared);
+        assert_eq!(state.expected_genesis(), Some(default_signet));
+
+        state.clear_custom_signet();
+        assert_eq!(state.expected_genesis(), None);
+    }
+
+    #[test]
+    fn test_delivery_retry_backoff() {
+        assert_eq!(
+            delivery_retry_at(1000, 1),
+            Some(1000 + DELIVERY_RETRY_BASE_SECS)
+        );
+        assert_eq!(
+            delivery_retry_at(1000, 3),
+            Some(1000 + 4 * DELIVERY_RETRY_BASE_SECS)
+        );
+        assert_eq!(delivery_retry_at(1000, DELIVERY_MAX_ATTEMPTS), None);
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_retried_until_success() {
+        use nostring_core::clock::MockClock;
+        use std::cell::Cell;
+
+        let dir = tempfile::tempdir().unwrap();
+        let clock = MockClock::new(1_700_000_000);
+        let state = AppState::from_db_path(dir.path().join("test.db")).with_clock(clock.clone());
+        {
+            let conn = state.db.lock().unwrap();
+            crate::db::heir_upsert(
+                &conn,
+                &crate::db::HeirRow {
+                    npub: Some("npub1alice".into()),
+                    ..test_heir_row("abcd1234", "Alice")
+                },
+            )
+            .unwrap();
+        }
+
+        // The initial delivery failed and was queued for retry
+        state.log_delivery_attempt(
+            "abcd1234",
+            "nostr",
+            false,
+            Some("relay down"),
+            Some("hash"),
+            1,
+            delivery_retry_at(1_700_000_000, 1),
+        );
+
+        // Not due yet
+        let calls = Cell::new(0u32);
+        let send = |_heir: crate::db::HeirRow, _channel: String| {
+            calls.set(calls.get() + 1);
+            let fail = calls.get() < 2;
+            async move {
+                if fail {
+                    Err("relay down".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        };
+        let report = retry_due_deliveries(&state, "hash", send).await;
+        assert_eq!(report, DeliveryRetryReport::default());
+        assert_eq!(calls.get(), 0);
+
+        // First retry fails again and is rescheduled with a longer backoff
+        clock.advance(DELIVERY_RETRY_BASE_SECS);
+        let report = retry_due_deliveries(&state, "hash", send).await;
+        assert_eq!(report.failed, 1);
+        clock.advance(DELIVERY_RETRY_BASE_SECS);
+        let report = retry_due_deliveries(&state, "hash", send).await;
+        assert_eq!(report, DeliveryRetryReport::default());
+
+        // Second retry succeeds and the queue drains
+        clock.advance(DELIVERY_RETRY_BASE_SECS);
+        let report = retry_due_deliveries(&state, "hash", send).await;
+        assert_eq!(report.delivered, 1);
+        assert_eq!(calls.get(), 2);
+        let last = state.last_heir_delivery("abcd1234", "nostr").unwrap();
+        assert_eq!(last.1.as_deref(), Some("hash"));
+
+        clock.advance(DELIVERY_RETRY_BASE_SECS * 100);
+        let report = retry_due_deliveries(&state, "hash", send).await;
+        assert_eq!(report, DeliveryRetryReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retry_respects_content_dedup() {
+        use nostring_core::clock::MockClock;
+
+        let dir = tempfile::tempdir().unwrap();
+        let clock = MockClock::new(1_700_000_000);
+        let state = AppState::from_db_path(dir.path().join("test.db")).with_clock(clock.clone());
+        {
+            let conn = state.db.lock().unwrap();
+            crate::db::heir_upsert(
+                &conn,
+                &crate::db::HeirRow {
+                    email: Some("alice@example.com".into()),
+                    ..test_heir_row("abcd1234", "Alice")
+                },
+            )
+            .unwrap();
+            // Email went through; a stale nostr retry for a removed contact remains
+            crate::db::delivery_log_insert_attempt(
+                &conn,
+                "abcd1234",
+                "email",
+                1_700_000_000,
+                true,
+                None,
+                Some("hash"),
+                1,
+                None,
+            )
+            .unwrap();
+            crate::db::delivery_log_insert_attempt(
+                &conn,
+                "abcd1234",
+                "nostr",
+                1_700_000_000,
+                false,
+                Some("relay down"),
+                Some("hash"),
+                1,
+                Some(1_700_000_000),
+            )
+            .unwrap();
+        }
+        state.log_delivery_attempt(
+            "abcd1234",
+            "email",
+            false,
+            Some("smtp timeout"),
+            Some("hash"),
+            1,
+            Some(1_700_000_000),
+        );
+        // The heir already received this backup by email, and no longer
+        // has an npub for the nostr retry
+        let report = retry_due_deliveries(&state, "hash", |_, _| async {
+            Err::<(), _>("must not send".to_string())
+        })
+        .await;
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.dropped, 1);
+        assert_eq!(report.failed + report.delivered, 0);
+    }
+
+    #[tokio::test]
+    async fn test_nostr_only_heir_gets_no_email() {
+        use nostring_core::clock::MockClock;
+
+        let mut heir = crate::db::HeirRow {
+            npub: Some("npub1alice".into()),
+            email: Some("alice@example.com".into()),
+            ..test_heir_row("abcd1234", "Alice")
+        };
+        assert_eq!(heir_delivery_channels(&heir, true), vec!["nostr", "email"]);
+        assert_eq!(heir_delivery_channels(&heir, false), vec!["nostr"]);
+
+        heir.channels = Some(vec!["nostr".into()]);
+        assert_eq!(heir_delivery_channels(&heir, true), vec!["nostr"]);
+
+        // An email retry queued before the heir opted out is dropped
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"))
+            .with_clock(MockClock::new(1_700_000_000));
+        {
+            let conn = state.db.lock().unwrap();
+            crate::db::heir_upsert(&conn, &heir).unwrap();
+        }
+        state.log_delivery_attempt(
+            "abcd1234",
+            "email",
+            false,
+            Some("smtp timeout"),
+            Some("hash"),
+            1,
+            Some(1_700_000_000),
+        );
+        let report = retry_due_deliveries(&state, "hash", |_, _| async {
+            Err::<(), _>("must not send".to_string())
+        })
+        .await;
+        assert_eq!(report.dropped, 1);
+        assert_eq!(report.failed + report.delivered, 0);
+    }
+
+    #[test]
+    fn test_validate_heir_channels() {
+        let channels = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            validate_heir_channels(&channels(&["Email", "nostr", "email"])),
+            Ok(channels(&["email", "nostr"]))
+        );
+        assert!(validate_heir_channels(&[]).is_err());
+        assert!(validate_heir_channels(&channels(&["sms"])).is_err());
+    }
+
+    #[test]
+    fn test_heir_channels_need_contact() {
+        let channels = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let heir = crate::db::HeirRow {
+            npub: Some("npub1alice".into()),
+            ..test_heir_row("abcd1234", "Alice")
+        };
+
+        assert!(check_heir_channel_contacts(&heir, &channels(&["nostr"])).is_ok());
+        let err = check_heir_channel_contacts(&heir, &channels(&["nostr", "email"])).unwrap_err();
+        assert!(err.contains("no email contact"), "{}", err);
+    }
+
+    #[test]
+    fn test_xpub_network_match() {
+        use bitcoin::Network;
+
+        let xpub = test_xpub(NetworkKind::Main, 7);
+        let tpub = test_xpub(NetworkKind::Test, 7);
+        assert!(xpub.to_string().starts_with("xpub"));
+        assert!(tpub.to_string().starts_with("tpub"));
+
+        assert!(check_xpub_network(&xpub, Network::Bitcoin).is_ok());
+        assert!(check_xpub_network(&tpub, Network::Testnet).is_ok());
+        assert!(check_xpub_network(&tpub, Network::Signet).is_ok());
+        assert!(check_xpub_network(&tpub, Network::Regtest).is_ok());
+    }
+
+    #[test]
+    fn test_xpub_network_mismatch() {
+        use bitcoin::Network;
+
+        let xpub = test_xpub(NetworkKind::Main, 7);
+        let tpub = test_xpub(NetworkKind::Test, 7);
+
+        // Mainnet key on test networks
+        let err = check_xpub_network(&xpub, Network::Testnet).unwrap_err();
+        assert!(err.contains("mainnet"), "{}", err);
+        assert!(check_xpub_network(&xpub, Network::Signet).is_err());
+        assert!(check_xpub_network(&xpub, Network::Regtest).is_err());
+
+        // Test-network key on a mainnet wallet
+        let err = check_xpub_network(&tpub, Network::Bitcoin).unwrap_err();
+        assert!(err.contains("tpub"), "{}", err);
+        assert!(err.contains("bitcoin"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_coldcard_generic_export() {
+        let xpub84 = test_xpub(NetworkKind::Main, 5);
+        let export = serde_json::json!({
+            "chain": "BTC",
+            "xfp": "0F056943",
+            "account": 0,
+            "xpub": test_xpub(NetworkKind::Main, 6).to_string(),
+            "bip44": {
+                "name": "p2pkh",
+                "deriv": "m/44'/0'/0'",
+                "xpub": test_xpub(NetworkKind::Main, 7).to_string(),
+            },
+            "bip84": {
+                "name": "p2wpkh",
+                "xfp": "0F056943",
+                "deriv": "m/84'/0'/0'",
+                "xpub": xpub84.to_string(),
+                "_pub": "zpub-not-parsed",
+            },
+        })
+        .to_string();
+
+        let parsed = parse_hardware_export(&export).unwrap();
+        assert_eq!(parsed.fingerprint.to_string(), "0f056943");
+        assert_eq!(
+            parsed.derivation_path,
+            DerivationPath::from_str("m/84'/0'/0'").unwrap()
+        );
+        assert_eq!(parsed.xpub, xpub84);
+        assert_eq!(
+            parsed.key_expression(),
+            format!("[0f056943/84'/0'/0']{}", xpub84)
+        );
+
+        // Falls back to the top-level xfp and the next available section
+        let legacy_only = serde_json::json!({
+            "xfp": "0F056943",
+            "bip44": { "deriv": "m/44'/0'/0'", "xpub": test_xpub(NetworkKind::Main, 7).to_string() },
+        })
+        .to_string();
+        let parsed = parse_hardware_export(&legacy_only).unwrap();
+        assert_eq!(
+            parsed.derivation_path,
+            DerivationPath::from_str("m/44'/0'/0'").unwrap()
+        );
+
+        assert!(parse_hardware_export(r#"{"xfp": "0F056943"}"#).is_err());
+        assert!(parse_hardware_export("{not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_descriptor_export() {
+        let xpub = test_xpub(NetworkKind::Main, 8);
+
+        let desc = format!("wpkh([d34db33f/84'/0'/0']{}/<0;1>/*)", xpub);
+        let parsed = parse_hardware_export(&desc).unwrap();
+        assert_eq!(parsed.fingerprint.to_string(), "d34db33f");
+        assert_eq!(
+            parsed.derivation_path,
+            DerivationPath::from_str("m/84'/0'/0'").unwrap()
+        );
+        assert_eq!(parsed.xpub, xpub);
+
+        let key = format!("[d34db33f/48'/0'/0'/2']{}", xpub);
+        let parsed = parse_hardware_export(&key).unwrap();
+        assert_eq!(
+            parsed.derivation_path,
+            DerivationPath::from_str("m/48'/0'/0'/2'").unwrap()
+        );
+
+        // No origin to recover the derivation from
+        assert!(parse_hardware_export(&format!("wpkh({}/0/*)", xpub)).is_err());
+    }
+
+    #[test]
+    fn test_import_heirs_from_descriptor() {
+        let desc = format!(
+            "wsh(multi(2,[aaaaaaa1/84'/0'/0']{}/0/*,[aaaaaaa2/84'/0'/0']{}/0/*,[aaaaaaa3/84'/0'/0']{}/0/*))",
+            test_xpub(NetworkKind::Main, 1),
+            test_xpub(NetworkKind::Main, 2),
+            test_xpub(NetworkKind::Main, 3)
+        );
+
+        let rows = parse_heir_import(&desc, "descriptor", &mainnet_account()).unwrap();
+        assert_eq!(rows.len(), 3);
+        for (i, (row, label, parsed)) in rows.iter().enumerate() {
+            assert_eq!(*row, i + 1);
+            assert_eq!(label, &format!("Heir {}", i + 1));
+            let parsed = parsed.as_ref().unwrap();
+            assert_eq!(parsed.heir.xpub, test_xpub(NetworkKind::Main, i as u8 + 1));
+            assert_eq!(
+                parsed.heir.fingerprint.to_string(),
+                format!("aaaaaaa{}", i + 1)
+            );
+        }
+
+        assert!(parse_heir_import("wsh(garbage)", "descriptor", &mainnet_account()).is_err());
+        assert!(parse_heir_import(&desc, "xml", &mainnet_account()).is_err());
+    }
+
+    /// Default account path on mainnet
+    fn mainnet_account() -> DerivationPath {
+        crate::state::default_account_path(
+            bitcoin::Network::Bitcoin,
+            nostring_core::keys::CoinType::Standard,
+        )
+    }
+
+    #[test]
+    fn test_heir_custom_taproot_path() {
+        let xpub = test_xpub(NetworkKind::Main, 4).to_string();
+        let taproot = DerivationPath::from_str("m/86'/0'/0'").unwrap();
+
+        let heir =
+            parse_heir_key("Taproot", &xpub, Some("m/86'/0'/0'"), &mainnet_account()).unwrap();
+        assert_eq!(heir.derivation_path, taproot);
+
+        // Default is the network's BIP-84 account path
+        let heir = parse_heir_key("Segwit", &xpub, None, &mainnet_account()).unwrap();
+        assert_eq!(
+            heir.derivation_path,
+            DerivationPath::from_str("m/84'/0'/0'").unwrap()
+        );
+
+        // Descriptor keys keep their origin path
+        let desc_key = format!("[aaaaaaa4/86'/0'/0']{}", xpub);
+        let heir = parse_heir_key("Origin", &desc_key, None, &mainnet_account()).unwrap();
+        assert_eq!(heir.derivation_path, taproot);
+        assert!(
+            parse_heir_key("Origin", &desc_key, Some("m/86'/0'/0'"), &mainnet_account()).is_ok()
+        );
+        assert!(
+            parse_heir_key("Origin", &desc_key, Some("m/84'/0'/0'"), &mainnet_account()).is_err()
+        );
+
+        // The stored path ends up in the inheritance descriptor
+        let owner = test_xpub(NetworkKind::Main, 5).to_string();
+        let heir =
+            parse_heir_key("Taproot", &xpub, Some("m/86'/0'/0'"), &mainnet_account()).unwrap();
+        let (descriptor, _) = crate::state::derive_inheritance_descriptor(
+            &owner,
+            &[heir],
+            26280,
+            bitcoin::Network::Bitcoin,
+        )
+        .unwrap();
+        assert!(descriptor.contains("/86'/0'/0']"), "{}", descriptor);
+    }
+
+    #[test]
+    fn test_heir_malformed_derivation_path() {
+        let xpub = test_xpub(NetworkKind::Main, 4).to_string();
+        for bad in ["m/86'/zero'/0'", "86''/0", "m//1"] {
+            let err = parse_heir_key("Bad", &xpub, Some(bad), &mainnet_account()).unwrap_err();
+            assert!(err.contains("Invalid derivation path"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_import_heirs_csv_partial_failure() {
+        let csv = format!(
+            "label,xpub,email,npub,timelock_months\n\
+             Alice,{},alice@example.com,,6\n\
+             Bob,not-an-xpub,bob@example.com,,12\n\
+             \n\
+             Carol,{},,,\n",
+            test_xpub(NetworkKind::Main, 4),
+            test_xpub(NetworkKind::Main, 5)
+        );
+
+        let rows = parse_heir_import(&csv, "csv", &mainnet_account()).unwrap();
+        assert_eq!(rows.len(), 3, "header and blank lines are skipped");
+
+        let (row, label, alice) = &rows[0];
+        assert_eq!((*row, label.as_str()), (2, "Alice"));
+        let alice = alice.as_ref().unwrap();
+        assert_eq!(alice.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(alice.timelock_months, Some(6));
+
+        let (row, label, bob) = &rows[1];
+        assert_eq!((*row, label.as_str()), (3, "Bob"));
+        assert!(bob.as_ref().err().unwrap().contains("Invalid xpub"));
+
+        let (row, _, carol) = &rows[2];
+        assert_eq!(*row, 5);
+        let carol = carol.as_ref().unwrap();
+        assert!(carol.email.is_none() && carol.npub.is_none());
+        assert!(carol.timelock_months.is_none());
+    }
+
+    #[test]
+    fn test_import_heirs_csv_field_validation() {
+        let xpub = test_xpub(NetworkKind::Main, 6);
+        let bad = [
+            format!("Dave,{},not-an-email,,", xpub),
+            format!("Dave,{},,npub1garbage,", xpub),
+            format!("Dave,{},,,0", xpub),
+            format!(",{},,,", xpub),
+            "Dave".to_string(),
+        ];
+        for line in &bad {
+            let rows = parse_heir_import(line, "csv", &mainnet_account()).unwrap();
+            assert!(rows[0].2.is_err(), "accepted: {}", line);
+        }
+    }
+
+    fn sample_backup() -> DescriptorBackupData {
+        DescriptorBackupData {
+            descriptor:
+                "wsh(pk(02e6642fd69bd211f93f7f1f36ca51a26a5290eb2dd1b0d8279a87bb0d480c8443))".into(),
+            network: "bitcoin".into(),
+            timelock_blocks: 26280,
+            address: None,
+            address_index: None,
+            heirs: vec![DescriptorBackupHeir {
+                label: "Spouse".into(),
+                xpub: "xpub-placeholder".into(),
+                timelock_months: 6.0,
+            }],
+            nsec_owner_npub: None,
+            locked_shares: Some(vec!["share-a".into(), "share-b".into()]),
+            signature: None,
+            signer_npub: None,
+            stale_warning: None,
+        }
+    }
+
+    #[test]
+    fn test_descriptor_backup_signature_roundtrip() {
+        let secret = nostr_sdk::prelude::Keys::generate()
+            .secret_key()
+            .to_secret_hex();
+        let mut backup = sample_backup();
+        sign_descriptor_backup(&mut backup, &secret).unwrap();
+
+        // Survives a JSON round trip with different key order / whitespace
+        let json = serde_json::to_string_pretty(&backup).unwrap();
+        let parsed: DescriptorBackupData = serde_json::from_str(&json).unwrap();
+        assert!(verify_descriptor_backup_data(&parsed).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_descriptor_backup_rejected() {
+        let secret = nostr_sdk::prelude::Keys::generate()
+            .secret_key()
+            .to_secret_hex();
+        let mut backup = sample_backup();
+        sign_descriptor_backup(&mut backup, &secret).unwrap();
+
+        let mut tampered = backup.clone();
+        tampered.descriptor =
+            "wsh(pk(03a1633cafcc01ebfb6d78e39f687a1f0995c62fc95f51ead10a02ee0be551b5dc))".into();
+        assert!(verify_descriptor_backup_data(&tampered).is_err());
+
+        let mut unsigned = backup;
+        unsigned.signature = None;
+        assert!(verify_descriptor_backup_data(&unsigned).is_err());
+    }
+
+    #[test]
+    fn test_heir_delivery_preview_contents() {
+        let secret = nostr_sdk::prelude::Keys::generate()
+            .secret_key()
+            .to_secret_hex();
+        let mut backup = sample_backup();
+        backup.address = Some("bc1qexampleinheritanceaddress0000000000000".into());
+        let descriptor = backup.descriptor.clone();
+
+        let json = sign_and_serialize_backup(backup, &secret).unwrap();
+        let message = nostring_notify::templates::generate_heir_delivery_message("Spouse", &json);
+
+        assert!(message.body.contains("Dear Spouse"));
+        assert!(message.body.contains(&descriptor));
+        assert!(message
+            .body
+            .contains("bc1qexampleinheritanceaddress0000000000000"));
+        assert!(message.body.contains("\"signature\""));
+
+        // The embedded backup still verifies
+        let start = message.body.find('{').unwrap();
+        let end = message.body.rfind('}').unwrap();
+        let parsed: DescriptorBackupData =
+            serde_json::from_str(&message.body[start..=end]).unwrap();
+        assert!(verify_descriptor_backup_data(&parsed).is_ok());
+    }
+
+    #[test]
+    fn test_backup_content_hash_is_canonical() {
+        let secret = nostr_sdk::prelude::Keys::generate()
+            .secret_key()
+            .to_secret_hex();
+        let pretty = sign_and_serialize_backup(sample_backup(), &secret).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        let compact = serde_json::to_string(&value).unwrap();
+
+        // Formatting doesn't change the hash, content does
+        assert_eq!(
+            backup_content_hash(&pretty).unwrap(),
+            backup_content_hash(&compact).unwrap()
+        );
+        let mut other = sample_backup();
+        other.timelock_blocks += 144;
+        let other = sign_and_serialize_backup(other, &secret).unwrap();
+        assert_ne!(
+            backup_content_hash(&pretty).unwrap(),
+            backup_content_hash(&other).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resume_publishes_only_missing_half() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let conn = crate::db::open_db(file.path()).unwrap();
+
+        let npubs = vec!["npub1alice".to_string(), "npub1bob".to_string()];
+        let relays = vec![
+            "wss://relay.damus.io".to_string(),
+            "wss://nos.lol".to_string(),
+        ];
+        let shares = 3;
+
+        // First run reached Alice on both relays, Bob on neither
+        for relay in &relays {
+            for i in 0..shares {
+                crate::db::relay_publication_insert(
+                    &conn,
+                    "split1",
+                    "fp_alice",
+                    "npub1alice",
+                    relay,
+                    Some(&format!("event_{}", i)),
+                    i,
+                    shares,
+                    1000,
+                    true,
+                    None,
+                )
+                .unwrap();
+            }
+        }
+
+        let completed =
+            crate::db::relay_publication_completed(&conn, "split1", shares as usize).unwrap();
+        assert_eq!(completed.len(), 2);
+        let pending = pending_relay_publications(&npubs, &relays, &completed);
+        assert_eq!(pending, vec![(1, relays.clone())]);
+
+        // A pair missing one share is still pending
+        for i in 0..shares - 1 {
+            crate::db::relay_publication_insert(
+                &conn,
+                "split1",
+                "fp_bob",
+                "npub1bob",
+                &relays[0],
+                Some(&format!("event_b{}", i)),
+                i,
+                shares,
+                1001,
+                true,
+                None,
+            )
+            .unwrap();
+        }
+        let completed =
+            crate::db::relay_publication_completed(&conn, "split1", shares as usize).unwrap();
+        assert_eq!(
+            pending_relay_publications(&npubs, &relays, &completed),
+            vec![(1, relays.clone())]
+        );
+
+        // Other splits and forced republish start from scratch
+        let fresh = crate::db::relay_publication_completed(&conn, "split2", 3).unwrap();
+        assert_eq!(
+            pending_relay_publications(&npubs, &relays, &fresh),
+            vec![(0, relays.clone()), (1, relays.clone())]
+        );
+    }
+
+    #[test]
+    fn test_custom_split_invariant_holds() {
+        use nostring_shamir::codex32::{combine_shares, generate_shares};
+
+        let secret = [0x42u8; 32];
+        // (threshold, total, heirs)
+        for (k, total, heirs) in [(2, 3, 1), (3, 4, 2), (5, 6, 4), (5, 9, 4), (9, 31, 8)] {
+            validate_split_params(k, total, heirs).unwrap();
+
+            let config = Codex32Config::new(k, "test", total).unwrap();
+            let shares = generate_shares(&secret, 32, &config).unwrap();
+            let (pre_distributed, locked) = shares.split_at(heirs);
+
+            // Every heir colluding stays below the threshold
+            assert!(pre_distributed.len() < k as usize);
+            assert!(combine_shares(pre_distributed)
+                .map(|s| s != secret)
+                .unwrap_or(true));
+
+            // After the backup is released, enough heirs can recover
+            let required = (k as usize).saturating_sub(locked.len()).max(1);
+            assert!(required <= heirs);
+            let mut pool = pre_distributed[..required].to_vec();
+            pool.extend_from_slice(locked);
+            assert_eq!(combine_shares(&pool).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn test_insecure_custom_split_rejected() {
+        // Threshold equal to or below the pre-distributed count
+        assert!(validate_split_params(2, 5, 2)
+            .unwrap_err()
+            .contains("Insecure"));
+        assert!(validate_split_params(3, 7, 4).is_err());
+        // Codex32 limits
+        assert!(validate_split_params(1, 3, 0).is_err());
+        assert!(validate_split_params(10, 20, 3).is_err());
+        assert!(validate_split_params(5, 32, 3).is_err());
+        // Nothing left to lock
+        assert!(validate_split_params(4, 4, 4).is_err());
+        assert!(validate_split_params(5, 4, 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_service_key_publishes_migration() {
+        use nostr_sdk::prelude::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        *state.unlocked.lock().unwrap() = true;
+        let old_keys = Keys::generate();
+        let old_npub = old_keys.public_key().to_bech32().unwrap();
+        state.set_service_key(&old_keys.secret_key().to_secret_hex(), &old_npub);
+
+        // No relay accepts the event: the old key stays in place
+        let failed =
+            rotate_service_key_with(&state, |_| async { Err("offline".to_string()) }).await;
+        assert!(failed.unwrap_err().contains("offline"));
+        assert_eq!(
+            state.service_npub.lock().unwrap().as_deref(),
+            Some(old_npub.as_str())
+        );
+        assert!(state.retired_service_keys().is_empty());
+
+        let published = std::cell::RefCell::new(None);
+        let rotation = rotate_service_key_with(&state, |event| {
+            let id = event.id.to_hex();
+            *published.borrow_mut() = Some(event);
+            async move { Ok(id) }
+        })
+        .await
+        .unwrap();
+
+        // Signed by the old key, announcing the new one
+        let event = published.into_inner().unwrap();
+        assert_eq!(event.pubkey, old_keys.public_key());
+        let announced = nostring_notify::nostr_relay::parse_key_migration(&event).unwrap();
+        assert_eq!(announced.to_bech32().unwrap(), rotation.new_npub);
+        assert_eq!(rotation.old_npub, old_npub);
+        assert_eq!(rotation.migration_event_id, event.id.to_hex());
+
+        // The new key is stored; the old one is kept as retired
+        assert_eq!(
+            state.service_npub.lock().unwrap().as_deref(),
+            Some(rotation.new_npub.as_str())
+        );
+        let new_keys = Keys::parse(state.service_secret().unwrap().as_str()).unwrap();
+        assert_eq!(new_keys.public_key(), announced);
+        let retired = state.retired_service_keys();
+        assert_eq!(retired.len(), 1);
+        assert_eq!(retired[0].npub, old_npub);
+        assert_eq!(retired[0].replaced_by, rotation.new_npub);
+    }
+
+    #[tokio::test]
+    async fn test_split_kept_when_relay_publish_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        *state.unlocked.lock().unwrap() = true;
+        for (label, seed) in [("Alice", 1), ("Bob", 2)] {
+            let xpub = test_xpub(NetworkKind::Main, seed);
+            state.heir_registry.lock().unwrap().add(HeirKey::new(
+                label,
+                xpub.fingerprint(),
+                xpub,
+                None,
+            ));
+        }
+        let nsec = nostr_sdk::Keys::generate().secret_key().to_secret_hex();
+        let stored_shares = || {
+            let conn = state.db.lock().unwrap();
+            crate::db::config_get(&conn, "nsec_locked_shares").unwrap()
+        };
+
+        // Every relay unreachable: the split still stands
+        let split = split_nsec_with_params(&state, &nsec, None);
+        let result =
+            publish_after_split(split, || async { Err("no healthy relays".to_string()) }).await;
+        assert!(result.success);
+        let data = result.data.unwrap();
+        assert_eq!(data.locked_shares.len(), 3);
+        assert!(matches!(
+            data.relay_publish,
+            Some(SplitRelayPublish::Failed { ref error }) if error == "no healthy relays"
+        ));
+        assert_eq!(
+            stored_shares(),
+            Some(serde_json::to_string(&data.locked_shares).unwrap())
+        );
+
+        // One heir's relays rejected the shares: partial, retryable
+        let heir = |label: &str, error: Option<&str>| RelayHeirStatus {
+            label: label.into(),
+            npub: format!("npub1{}", label.to_lowercase()),
+            shares_published: if error.is_none() { 3 } else { 0 },
+            event_ids: Vec::new(),
+            error: error.map(String::from),
+        };
+        let status = RelayPublishStatus {
+            shares_published: 3,
+            heirs_targeted: 2,
+            split_id: "split1".into(),
+            heir_results: vec![heir("Alice", None), heir("Bob", Some("rate limited"))],
+            skipped: 0,
+        };
+        let split = split_nsec_with_params(&state, &nsec, None);
+        let result = publish_after_split(split, || async move { Ok(status) }).await;
+        let data = result.data.unwrap();
+        assert!(data.was_resplit);
+        let outcome = data.relay_publish.unwrap();
+        assert!(outcome.needs_retry());
+        assert!(matches!(outcome, SplitRelayPublish::Partial(ref s) if s.heir_results.len() == 2));
+        assert_eq!(
+            stored_shares(),
+            Some(serde_json::to_string(&data.locked_shares).unwrap())
+        );
+
+        // A failed split never publishes
+        *state.unlocked.lock().unwrap() = false;
+        let split = split_nsec_with_params(&state, &nsec, None);
+        let published = std::cell::Cell::new(false);
+        let result = publish_after_split(split, || {
+            published.set(true);
+            async { Err(String::new()) }
+        })
+        .await;
+        assert!(!result.success);
+        assert!(!published.get());
+    }
+
+    #[tokio::test]
+    async fn test_recommend_psbt_chain_length() {
+        // 6-month lock, default 30-day threshold
+        let one_year = recommend_psbt_chain_length(365, 26_280, None)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(one_year.checkins_needed, 2);
+        assert_eq!(one_year.recommended, 2);
+        assert!(!one_year.exceeds_max);
+
+        // 30-day lock broadcast a day early, for ten years: more than one chain
+        let decade = recommend_psbt_chain_length(3_650, 4_320, Some(144))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert!(decade.checkins_needed > MAX_PRESIGNED_CHAIN);
+        assert_eq!(decade.recommended, MAX_PRESIGNED_CHAIN);
+        assert!(decade.exceeds_max);
+
+        // The default threshold is longer than a 1-day lock
+        assert!(
+            !recommend_psbt_chain_length(365, 144, None)
+                .await
+                .unwrap()
+                .success
+        );
+        assert!(
+            !recommend_psbt_chain_length(0, 26_280, None)
+                .await
+                .unwrap()
+                .success
+        );
+    }
 }
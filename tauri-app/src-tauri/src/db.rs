@@ -51,6 +51,27 @@ pub fn open_db(path: &Path) -> SqlResult<Connection> {
     // v0.4 migrations — per-heir timelock
     migrate_v04_timelock(&conn)?;
 
+    // v0.5 migrations — content hash on delivery log
+    migrate_v05_delivery_hash(&conn)?;
+
+    // v0.6 migrations — signed check-in receipts
+    migrate_v06_checkin_receipts(&conn)?;
+
+    // v0.7 migrations — retry tracking for failed heir deliveries
+    migrate_v07_delivery_retry(&conn)?;
+
+    // v0.8 migrations — last-known chain state for offline status
+    migrate_v08_chain_cache(&conn)?;
+
+    // v0.9 migrations — per-heir delivery channel preferences
+    migrate_v09_heir_channels(&conn)?;
+
+    // v0.10 migrations — hash-chained audit log of sensitive actions
+    migrate_v10_audit_log(&conn)?;
+
+    // v0.11 migrations — which heir made a detected claim
+    migrate_v11_spend_event_heir(&conn)?;
+
     Ok(conn)
 }
 
@@ -144,6 +165,108 @@ fn migrate_v04_timelock(conn: &Connection) -> SqlResult<()> {
     Ok(())
 }
 
+/// v0.5 migration: SHA-256 of the delivered backup, for content de-duplication.
+fn migrate_v05_delivery_hash(conn: &Connection) -> SqlResult<()> {
+    let has_hash = conn
+        .prepare("SELECT content_hash FROM delivery_log LIMIT 0")
+        .is_ok();
+    if !has_hash {
+        conn.execute_batch("ALTER TABLE delivery_log ADD COLUMN content_hash TEXT;")?;
+    }
+    Ok(())
+}
+
+/// v0.6 migration: signed check-in receipts, one per broadcast txid.
+fn migrate_v06_checkin_receipts(conn: &Connection) -> SqlResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS checkin_receipts (
+            txid        TEXT PRIMARY KEY,
+            receipt     TEXT NOT NULL,
+            created_at  INTEGER NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+/// v0.7 migration: attempt number and next retry time on the delivery log.
+fn migrate_v07_delivery_retry(conn: &Connection) -> SqlResult<()> {
+    let has_retry = conn
+        .prepare("SELECT next_retry_at FROM delivery_log LIMIT 0")
+        .is_ok();
+    if !has_retry {
+        conn.execute_batch(
+            "ALTER TABLE delivery_log ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1;
+             ALTER TABLE delivery_log ADD COLUMN next_retry_at INTEGER;",
+        )?;
+    }
+    Ok(())
+}
+
+/// v0.8 migration: single-row cache of the last successful chain fetch,
+/// tagged with the hash of the descriptor it was fetched for.
+fn migrate_v08_chain_cache(conn: &Connection) -> SqlResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS chain_cache (
+            id               INTEGER PRIMARY KEY CHECK (id = 1),
+            block_height     INTEGER NOT NULL,
+            balance_sats     INTEGER,
+            utxos            TEXT NOT NULL,
+            fetched_at       INTEGER NOT NULL,
+            descriptor_hash  TEXT NOT NULL DEFAULT ''
+        );",
+    )?;
+    // Caches written before the tag never match a descriptor, so they're
+    // dropped on first read
+    let has_hash = conn
+        .prepare("SELECT descriptor_hash FROM chain_cache LIMIT 0")
+        .is_ok();
+    if !has_hash {
+        conn.execute_batch(
+            "ALTER TABLE chain_cache ADD COLUMN descriptor_hash TEXT NOT NULL DEFAULT '';",
+        )?;
+    }
+    Ok(())
+}
+
+/// v0.9 migration: JSON array of the delivery channels each heir accepts.
+fn migrate_v09_heir_channels(conn: &Connection) -> SqlResult<()> {
+    let has_channels = conn.prepare("SELECT channels FROM heirs LIMIT 0").is_ok();
+    if !has_channels {
+        conn.execute_batch("ALTER TABLE heirs ADD COLUMN channels TEXT;")?;
+    }
+    Ok(())
+}
+
+/// v0.10 migration: append-only audit log, each row hashed over the previous.
+fn migrate_v10_audit_log(conn: &Connection) -> SqlResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp  INTEGER NOT NULL,
+            action     TEXT NOT NULL,
+            detail     TEXT NOT NULL,
+            prev_hash  TEXT NOT NULL,
+            hash       TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+/// v0.11 migration: heir identified on a spend event, or the JSON array of
+/// candidates when the witness doesn't single one out.
+fn migrate_v11_spend_event_heir(conn: &Connection) -> SqlResult<()> {
+    let has_heir = conn
+        .prepare("SELECT heir_fingerprint FROM spend_events LIMIT 0")
+        .is_ok();
+    if !has_heir {
+        conn.execute_batch(
+            "ALTER TABLE spend_events ADD COLUMN heir_fingerprint TEXT;
+             ALTER TABLE spend_events ADD COLUMN heir_candidates TEXT;",
+        )?;
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Config helpers (key-value)
 // ============================================================================
@@ -192,20 +315,46 @@ pub struct HeirRow {
     pub email: Option<String>,
     /// Per-heir timelock in months (optional, v0.4)
     pub timelock_months: Option<u32>,
+    /// Delivery channels the heir accepts; `None` means every configured
+    /// channel (optional, v0.9)
+    pub channels: Option<Vec<String>>,
+}
+
+impl HeirRow {
+    /// Whether contact info for `channel` is on file.
+    pub fn has_contact(&self, channel: &str) -> bool {
+        match channel {
+            "nostr" => self.npub.is_some(),
+            "email" => self.email.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Whether the heir accepts deliveries over `channel`.
+    pub fn accepts_channel(&self, channel: &str) -> bool {
+        match &self.channels {
+            Some(channels) => channels.iter().any(|c| c == channel),
+            None => true,
+        }
+    }
 }
 
 /// Insert or replace an heir.
+///
+/// A `None` channel preference keeps whatever is stored; use
+/// [`heir_set_channels`] to reset it.
 pub fn heir_upsert(conn: &Connection, heir: &HeirRow) -> SqlResult<()> {
     conn.execute(
-        "INSERT INTO heirs (fingerprint, label, xpub, derivation_path, npub, email, timelock_months)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "INSERT INTO heirs (fingerprint, label, xpub, derivation_path, npub, email, timelock_months, channels)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
          ON CONFLICT(fingerprint) DO UPDATE SET
             label = excluded.label,
             xpub = excluded.xpub,
             derivation_path = excluded.derivation_path,
             npub = excluded.npub,
             email = excluded.email,
-            timelock_months = excluded.timelock_months",
+            timelock_months = excluded.timelock_months,
+            channels = COALESCE(excluded.channels, heirs.channels)",
         params![
             heir.fingerprint,
             heir.label,
@@ -214,11 +363,34 @@ pub fn heir_upsert(conn: &Connection, heir: &HeirRow) -> SqlResult<()> {
             heir.npub,
             heir.email,
             heir.timelock_months,
+            channels_to_json(heir.channels.as_deref()),
         ],
     )?;
     Ok(())
 }
 
+/// Set (or with `None`, reset to "all channels") an heir's delivery channels.
+pub fn heir_set_channels(
+    conn: &Connection,
+    fingerprint: &str,
+    channels: Option<&[String]>,
+) -> SqlResult<bool> {
+    let affected = conn.execute(
+        "UPDATE heirs SET channels = ?2 WHERE fingerprint = ?1",
+        params![fingerprint, channels_to_json(channels)],
+    )?;
+    Ok(affected > 0)
+}
+
+fn channels_to_json(channels: Option<&[String]>) -> Option<String> {
+    channels.map(|c| serde_json::to_string(c).expect("string list serializes"))
+}
+
+/// Parse a stored channel list; an unreadable value falls back to all channels.
+fn channels_from_json(json: Option<String>) -> Option<Vec<String>> {
+    json.and_then(|j| serde_json::from_str(&j).ok())
+}
+
 /// Update only the contact fields (npub/email) for an existing heir.
 pub fn heir_update_contact(
     conn: &Connection,
@@ -236,7 +408,8 @@ pub fn heir_update_contact(
 /// List all heirs.
 pub fn heir_list(conn: &Connection) -> SqlResult<Vec<HeirRow>> {
     let mut stmt = conn.prepare(
-        "SELECT fingerprint, label, xpub, derivation_path, npub, email, timelock_months FROM heirs",
+        "SELECT fingerprint, label, xpub, derivation_path, npub, email, timelock_months, channels
+         FROM heirs",
     )?;
     let rows = stmt.query_map([], |row| {
         Ok(HeirRow {
@@ -247,6 +420,7 @@ pub fn heir_list(conn: &Connection) -> SqlResult<Vec<HeirRow>> {
             npub: row.get(4)?,
             email: row.get(5)?,
             timelock_months: row.get::<_, Option<u32>>(6)?,
+            channels: channels_from_json(row.get(7)?),
         })
     })?;
     rows.collect()
@@ -264,7 +438,7 @@ pub fn heir_remove(conn: &Connection, fingerprint: &str) -> SqlResult<bool> {
 /// Get a single heir by fingerprint.
 pub fn heir_get(conn: &Connection, fingerprint: &str) -> SqlResult<Option<HeirRow>> {
     let mut stmt = conn.prepare(
-        "SELECT fingerprint, label, xpub, derivation_path, npub, email, timelock_months
+        "SELECT fingerprint, label, xpub, derivation_path, npub, email, timelock_months, channels
          FROM heirs WHERE fingerprint = ?1",
     )?;
     let mut rows = stmt.query(params![fingerprint])?;
@@ -277,6 +451,7 @@ pub fn heir_get(conn: &Connection, fingerprint: &str) -> SqlResult<Option<HeirRo
             npub: row.get(4)?,
             email: row.get(5)?,
             timelock_months: row.get::<_, Option<u32>>(6)?,
+            channels: channels_from_json(row.get(7)?),
         })),
         None => Ok(None),
     }
@@ -298,6 +473,10 @@ pub struct SpendEventRow {
     pub method: String,
     pub policy_id: Option<String>,
     pub outpoint: Option<String>,
+    /// Fingerprint of the heir who claimed, when identified (v0.11)
+    pub heir_fingerprint: Option<String>,
+    /// Heirs who may have claimed, when several share the path (v0.11)
+    pub heir_candidates: Vec<String>,
 }
 
 /// Insert a spend event.
@@ -311,34 +490,57 @@ pub fn spend_event_insert(
     method: &str,
     policy_id: Option<&str>,
     outpoint: Option<&str>,
+    heir_fingerprint: Option<&str>,
+    heir_candidates: &[String],
 ) -> SqlResult<()> {
+    let heir_candidates = (!heir_candidates.is_empty())
+        .then(|| serde_json::to_string(heir_candidates).expect("string list serializes"));
     conn.execute(
-        "INSERT INTO spend_events (timestamp, txid, spend_type, confidence, method, policy_id, outpoint)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![timestamp, txid, spend_type, confidence, method, policy_id, outpoint],
+        "INSERT INTO spend_events (timestamp, txid, spend_type, confidence, method, policy_id, outpoint,
+                                   heir_fingerprint, heir_candidates)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            timestamp,
+            txid,
+            spend_type,
+            confidence,
+            method,
+            policy_id,
+            outpoint,
+            heir_fingerprint,
+            heir_candidates
+        ],
     )?;
     Ok(())
 }
 
+fn spend_event_from_row(row: &rusqlite::Row) -> SqlResult<SpendEventRow> {
+    let heir_candidates: Option<String> = row.get(9)?;
+    Ok(SpendEventRow {
+        id: row.get(0)?,
+        timestamp: row.get(1)?,
+        txid: row.get(2)?,
+        spend_type: row.get(3)?,
+        confidence: row.get(4)?,
+        method: row.get(5)?,
+        policy_id: row.get(6)?,
+        outpoint: row.get(7)?,
+        heir_fingerprint: row.get(8)?,
+        heir_candidates: heir_candidates
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+    })
+}
+
 /// List all spend events (most recent first).
 #[allow(dead_code)]
 pub fn spend_event_list(conn: &Connection) -> SqlResult<Vec<SpendEventRow>> {
     let mut stmt = conn.prepare(
-        "SELECT id, timestamp, txid, spend_type, confidence, method, policy_id, outpoint
+        "SELECT id, timestamp, txid, spend_type, confidence, method, policy_id, outpoint,
+                heir_fingerprint, heir_candidates
          FROM spend_events ORDER BY id DESC",
     )?;
-    let rows = stmt.query_map([], |row| {
-        Ok(SpendEventRow {
-            id: row.get(0)?,
-            timestamp: row.get(1)?,
-            txid: row.get(2)?,
-            spend_type: row.get(3)?,
-            confidence: row.get(4)?,
-            method: row.get(5)?,
-            policy_id: row.get(6)?,
-            outpoint: row.get(7)?,
-        })
-    })?;
+    let rows = stmt.query_map([], spend_event_from_row)?;
     rows.collect()
 }
 
@@ -349,30 +551,26 @@ pub fn spend_event_list_by_type(
     spend_type: &str,
 ) -> SqlResult<Vec<SpendEventRow>> {
     let mut stmt = conn.prepare(
-        "SELECT id, timestamp, txid, spend_type, confidence, method, policy_id, outpoint
+        "SELECT id, timestamp, txid, spend_type, confidence, method, policy_id, outpoint,
+                heir_fingerprint, heir_candidates
          FROM spend_events WHERE spend_type = ?1 ORDER BY id DESC",
     )?;
-    let rows = stmt.query_map(params![spend_type], |row| {
-        Ok(SpendEventRow {
-            id: row.get(0)?,
-            timestamp: row.get(1)?,
-            txid: row.get(2)?,
-            spend_type: row.get(3)?,
-            confidence: row.get(4)?,
-            method: row.get(5)?,
-            policy_id: row.get(6)?,
-            outpoint: row.get(7)?,
-        })
-    })?;
+    let rows = stmt.query_map(params![spend_type], spend_event_from_row)?;
     rows.collect()
 }
 
-/// Check if any heir claims have been detected.
-#[allow(dead_code)]
-pub fn has_heir_claims(conn: &Connection) -> SqlResult<bool> {
-    let mut stmt =
-        conn.prepare_cached("SELECT COUNT(*) FROM spend_events WHERE spend_type = 'heir_claim'")?;
-    let count: i64 = stmt.query_row([], |row| row.get(0))?;
+/// Minimum detection confidence for an heir claim to raise the alert
+pub const DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE: f64 = 0.7;
+
+/// Check if any heir claims with at least `min_confidence` have been detected.
+///
+/// Lower-confidence (timing-only) detections stay in `spend_events` but
+/// don't count here.
+pub fn has_heir_claims(conn: &Connection, min_confidence: f64) -> SqlResult<bool> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT COUNT(*) FROM spend_events WHERE spend_type = 'heir_claim' AND confidence >= ?1",
+    )?;
+    let count: i64 = stmt.query_row(params![min_confidence], |row| row.get(0))?;
     Ok(count > 0)
 }
 
@@ -381,6 +579,7 @@ pub fn has_heir_claims(conn: &Connection) -> SqlResult<bool> {
 // ============================================================================
 
 /// Record a descriptor delivery attempt to an heir.
+#[allow(dead_code)]
 pub fn delivery_log_insert(
     conn: &Connection,
     heir_fingerprint: &str,
@@ -388,35 +587,147 @@ pub fn delivery_log_insert(
     timestamp: u64,
     success: bool,
     error_msg: Option<&str>,
+) -> SqlResult<()> {
+    delivery_log_insert_with_hash(
+        conn,
+        heir_fingerprint,
+        channel,
+        timestamp,
+        success,
+        error_msg,
+        None,
+    )
+}
+
+/// Record a descriptor delivery attempt along with the SHA-256 (hex) of the
+/// delivered content.
+pub fn delivery_log_insert_with_hash(
+    conn: &Connection,
+    heir_fingerprint: &str,
+    channel: &str,
+    timestamp: u64,
+    success: bool,
+    error_msg: Option<&str>,
+    content_hash: Option<&str>,
+) -> SqlResult<()> {
+    delivery_log_insert_attempt(
+        conn,
+        heir_fingerprint,
+        channel,
+        timestamp,
+        success,
+        error_msg,
+        content_hash,
+        1,
+        None,
+    )
+}
+
+/// Record a delivery attempt with its attempt number and, for failures that
+/// should be retried, when to retry.
+///
+/// Any retry still pending for the same heir + channel is cancelled: the
+/// newest attempt is the only one that can be retried.
+#[allow(clippy::too_many_arguments)]
+pub fn delivery_log_insert_attempt(
+    conn: &Connection,
+    heir_fingerprint: &str,
+    channel: &str,
+    timestamp: u64,
+    success: bool,
+    error_msg: Option<&str>,
+    content_hash: Option<&str>,
+    attempt: u32,
+    next_retry_at: Option<u64>,
 ) -> SqlResult<()> {
     conn.execute(
-        "INSERT INTO delivery_log (heir_fingerprint, channel, timestamp, success, error_msg)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
+        "UPDATE delivery_log SET next_retry_at = NULL
+         WHERE heir_fingerprint = ?1 AND channel = ?2 AND next_retry_at IS NOT NULL",
+        params![heir_fingerprint, channel],
+    )?;
+    conn.execute(
+        "INSERT INTO delivery_log
+         (heir_fingerprint, channel, timestamp, success, error_msg, content_hash, attempt, next_retry_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         params![
             heir_fingerprint,
             channel,
             timestamp,
             success as i32,
-            error_msg
+            error_msg,
+            content_hash,
+            attempt,
+            next_retry_at
         ],
     )?;
     Ok(())
 }
 
+/// A failed delivery waiting to be retried.
+#[derive(Debug, Clone)]
+pub struct DeliveryRetryRow {
+    pub id: i64,
+    pub heir_fingerprint: String,
+    pub channel: String,
+    /// Attempt number of the failed delivery (the retry is `attempt + 1`)
+    pub attempt: u32,
+    pub content_hash: Option<String>,
+}
+
+/// Failed deliveries whose retry is due at `now` (oldest first).
+pub fn delivery_retry_due(conn: &Connection, now: u64) -> SqlResult<Vec<DeliveryRetryRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, heir_fingerprint, channel, attempt, content_hash
+         FROM delivery_log
+         WHERE success = 0 AND next_retry_at IS NOT NULL AND next_retry_at <= ?1
+         ORDER BY next_retry_at ASC, id ASC",
+    )?;
+    let rows = stmt.query_map(params![now], |row| {
+        Ok(DeliveryRetryRow {
+            id: row.get(0)?,
+            heir_fingerprint: row.get(1)?,
+            channel: row.get(2)?,
+            attempt: row.get(3)?,
+            content_hash: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Cancel the pending retry on a delivery log row.
+pub fn delivery_retry_clear(conn: &Connection, id: i64) -> SqlResult<()> {
+    conn.execute(
+        "UPDATE delivery_log SET next_retry_at = NULL WHERE id = ?1",
+        params![id],
+    )?;
+    Ok(())
+}
+
 /// Get the last successful delivery timestamp for a given heir + channel.
+#[allow(dead_code)]
 pub fn delivery_last_success(
     conn: &Connection,
     heir_fingerprint: &str,
     channel: &str,
 ) -> SqlResult<Option<u64>> {
+    Ok(delivery_last_success_entry(conn, heir_fingerprint, channel)?.map(|(ts, _)| ts))
+}
+
+/// Get the last successful delivery for a given heir + channel as
+/// `(timestamp, content_hash)`. The hash is `None` for rows logged before v0.5.
+pub fn delivery_last_success_entry(
+    conn: &Connection,
+    heir_fingerprint: &str,
+    channel: &str,
+) -> SqlResult<Option<(u64, Option<String>)>> {
     let mut stmt = conn.prepare_cached(
-        "SELECT timestamp FROM delivery_log
+        "SELECT timestamp, content_hash FROM delivery_log
          WHERE heir_fingerprint = ?1 AND channel = ?2 AND success = 1
          ORDER BY id DESC LIMIT 1",
     )?;
     let mut rows = stmt.query(params![heir_fingerprint, channel])?;
     match rows.next()? {
-        Some(row) => Ok(Some(row.get(0)?)),
+        Some(row) => Ok(Some((row.get(0)?, row.get(1)?))),
         None => Ok(None),
     }
 }
@@ -539,6 +850,38 @@ pub fn presigned_checkin_add(
     Ok(conn.last_insert_rowid())
 }
 
+/// One link of a pre-signed chain to insert.
+#[derive(Debug, Clone)]
+pub struct NewPresignedCheckin {
+    pub psbt_base64: String,
+    pub sequence_index: i64,
+    pub spending_txid: Option<String>,
+    pub spending_vout: Option<i64>,
+}
+
+/// Insert a whole pre-signed chain in one transaction.
+///
+/// Either every link is stored or, if any insert fails, none are.
+pub fn presigned_checkin_add_chain(
+    conn: &Connection,
+    links: &[NewPresignedCheckin],
+    created_at: u64,
+) -> SqlResult<usize> {
+    let tx = conn.unchecked_transaction()?;
+    for link in links {
+        presigned_checkin_add(
+            &tx,
+            &link.psbt_base64,
+            link.sequence_index,
+            link.spending_txid.as_deref(),
+            link.spending_vout,
+            created_at,
+        )?;
+    }
+    tx.commit()?;
+    Ok(links.len())
+}
+
 /// List all pre-signed check-ins (active = not broadcast and not invalidated).
 #[allow(dead_code)]
 pub fn presigned_checkin_list_active(conn: &Connection) -> SqlResult<Vec<PresignedCheckinRow>> {
@@ -845,6 +1188,329 @@ pub fn relay_publication_success_count(conn: &Connection, split_id: &str) -> Sql
     stmt.query_row(params![split_id], |row| row.get(0))
 }
 
+/// `(heir_npub, relay_url)` pairs that already hold every share of a split.
+///
+/// A pair counts as complete once `share_total` distinct share indices were
+/// published successfully to it.
+pub fn relay_publication_completed(
+    conn: &Connection,
+    split_id: &str,
+    share_total: usize,
+) -> SqlResult<std::collections::HashSet<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT heir_npub, relay_url FROM relay_publications
+         WHERE split_id = ?1 AND success = 1
+         GROUP BY heir_npub, relay_url
+         HAVING COUNT(DISTINCT share_index) >= ?2",
+    )?;
+    let rows = stmt.query_map(params![split_id, share_total as i64], |row| {
+        Ok((row.get(0)?, row.get(1)?))
+    })?;
+    rows.collect()
+}
+
+// ============================================================================
+// Check-in Receipts (v0.6)
+// ============================================================================
+
+/// Store a signed receipt (JSON) for a check-in txid, replacing any earlier one.
+pub fn checkin_receipt_insert(
+    conn: &Connection,
+    txid: &str,
+    receipt_json: &str,
+    created_at: u64,
+) -> SqlResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO checkin_receipts (txid, receipt, created_at)
+         VALUES (?1, ?2, ?3)",
+        params![txid, receipt_json, created_at],
+    )?;
+    Ok(())
+}
+
+/// Get the receipt JSON for a check-in txid.
+pub fn checkin_receipt_get(conn: &Connection, txid: &str) -> SqlResult<Option<String>> {
+    let mut stmt = conn.prepare_cached("SELECT receipt FROM checkin_receipts WHERE txid = ?1")?;
+    let mut rows = stmt.query(params![txid])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+// ============================================================================
+// Chain cache (v0.8 — last-known chain state for offline status)
+// ============================================================================
+
+/// An unspent output at the inheritance address.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedUtxo {
+    /// `txid:vout`
+    pub outpoint: String,
+    pub value_sats: u64,
+    /// Confirmation height (0 if unconfirmed)
+    pub height: u32,
+}
+
+/// Chain state as of one successful Electrum fetch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainSnapshot {
+    pub block_height: u64,
+    /// `None` if the balance couldn't be fetched
+    pub balance_sats: Option<u64>,
+    pub utxos: Vec<CachedUtxo>,
+    /// Unix timestamp of the fetch
+    pub fetched_at: u64,
+}
+
+/// Hex SHA-256 of `descriptor`, the key the chain cache is stored under.
+fn descriptor_hash(descriptor: &str) -> String {
+    use bitcoin::hashes::{sha256, Hash};
+    sha256::Hash::hash(descriptor.as_bytes()).to_string()
+}
+
+/// Replace the cached chain snapshot with one fetched for `descriptor`.
+pub fn chain_cache_put(
+    conn: &Connection,
+    descriptor: &str,
+    snapshot: &ChainSnapshot,
+) -> SqlResult<()> {
+    let utxos = serde_json::to_string(&snapshot.utxos)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO chain_cache
+             (id, block_height, balance_sats, utxos, fetched_at, descriptor_hash)
+         VALUES (1, ?1, ?2, ?3, ?4, ?5)",
+        params![
+            snapshot.block_height,
+            snapshot.balance_sats,
+            utxos,
+            snapshot.fetched_at,
+            descriptor_hash(descriptor)
+        ],
+    )?;
+    Ok(())
+}
+
+/// The last chain snapshot cached for `descriptor`, if any.
+///
+/// A snapshot cached for another descriptor describes another address, so
+/// it is deleted rather than returned.
+pub fn chain_cache_get(conn: &Connection, descriptor: &str) -> SqlResult<Option<ChainSnapshot>> {
+    let hash = descriptor_hash(descriptor);
+    conn.execute(
+        "DELETE FROM chain_cache WHERE descriptor_hash != ?1",
+        params![hash],
+    )?;
+    let mut stmt = conn.prepare_cached(
+        "SELECT block_height, balance_sats, utxos, fetched_at FROM chain_cache WHERE id = 1",
+    )?;
+    let mut rows = stmt.query([])?;
+    match rows.next()? {
+        Some(row) => {
+            let utxos: String = row.get(2)?;
+            Ok(Some(ChainSnapshot {
+                block_height: row.get(0)?,
+                balance_sats: row.get(1)?,
+                utxos: serde_json::from_str(&utxos).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        2,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })?,
+                fetched_at: row.get(3)?,
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+// ============================================================================
+// Audit log (v0.10 — hash-chained record of sensitive actions)
+// ============================================================================
+
+/// A row of the audit log. See `crate::audit` for how `hash` is computed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogRow {
+    pub id: i64,
+    pub timestamp: u64,
+    pub action: String,
+    pub detail: String,
+    /// `hash` of the previous row (all zeros for the first)
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// Append a row. The caller computes `hash` and must hold the connection
+/// between reading [`audit_log_last_hash`] and inserting.
+pub fn audit_log_insert(
+    conn: &Connection,
+    timestamp: u64,
+    action: &str,
+    detail: &str,
+    prev_hash: &str,
+    hash: &str,
+) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO audit_log (timestamp, action, detail, prev_hash, hash)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![timestamp, action, detail, prev_hash, hash],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Hash of the newest row, if the log has any.
+pub fn audit_log_last_hash(conn: &Connection) -> SqlResult<Option<String>> {
+    let mut stmt = conn.prepare_cached("SELECT hash FROM audit_log ORDER BY id DESC LIMIT 1")?;
+    let mut rows = stmt.query([])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Every row, oldest first.
+pub fn audit_log_list(conn: &Connection) -> SqlResult<Vec<AuditLogRow>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, timestamp, action, detail, prev_hash, hash FROM audit_log ORDER BY id ASC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(AuditLogRow {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            action: row.get(2)?,
+            detail: row.get(3)?,
+            prev_hash: row.get(4)?,
+            hash: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ============================================================================
+// Inheritance timeline (merged view over the event tables)
+// ============================================================================
+
+/// One entry in the merged inheritance timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub timestamp: u64,
+    /// Source of the event: `checkin`, `spend`, `delivery` or `relay_publication`.
+    pub kind: String,
+    pub summary: String,
+}
+
+/// Check-ins from `checkin_log` as timeline events.
+pub fn timeline_checkins(conn: &Connection) -> SqlResult<Vec<TimelineEvent>> {
+    let mut stmt = conn.prepare("SELECT timestamp, txid, spend_type FROM checkin_log")?;
+    let rows = stmt.query_map([], |row| {
+        let txid: String = row.get(1)?;
+        let spend_type: String = row.get(2)?;
+        Ok(TimelineEvent {
+            timestamp: row.get(0)?,
+            kind: "checkin".into(),
+            summary: format!("Check-in ({}) {}", spend_type, txid),
+        })
+    })?;
+    rows.collect()
+}
+
+/// Detected spends from `spend_events` as timeline events.
+pub fn timeline_spend_events(conn: &Connection) -> SqlResult<Vec<TimelineEvent>> {
+    let mut stmt =
+        conn.prepare("SELECT timestamp, txid, spend_type, confidence FROM spend_events")?;
+    let rows = stmt.query_map([], |row| {
+        let txid: String = row.get(1)?;
+        let spend_type: String = row.get(2)?;
+        let confidence: f64 = row.get(3)?;
+        Ok(TimelineEvent {
+            timestamp: row.get(0)?,
+            kind: "spend".into(),
+            summary: format!(
+                "Spend detected: {} {} ({:.0}% confidence)",
+                spend_type,
+                txid,
+                confidence * 100.0
+            ),
+        })
+    })?;
+    rows.collect()
+}
+
+/// Descriptor deliveries from `delivery_log` as timeline events.
+pub fn timeline_deliveries(conn: &Connection) -> SqlResult<Vec<TimelineEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, heir_fingerprint, channel, success, error_msg FROM delivery_log",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let fingerprint: String = row.get(1)?;
+        let channel: String = row.get(2)?;
+        let success = row.get::<_, i32>(3)? != 0;
+        let error_msg: Option<String> = row.get(4)?;
+        let summary = if success {
+            format!("Backup delivered to heir {} via {}", fingerprint, channel)
+        } else {
+            format!(
+                "Backup delivery to heir {} via {} failed: {}",
+                fingerprint,
+                channel,
+                error_msg.unwrap_or_default()
+            )
+        };
+        Ok(TimelineEvent {
+            timestamp: row.get(0)?,
+            kind: "delivery".into(),
+            summary,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Locked-share relay publications from `relay_publications` as timeline events.
+pub fn timeline_relay_publications(conn: &Connection) -> SqlResult<Vec<TimelineEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT published_at, heir_fingerprint, relay_url, share_index, share_total, success
+         FROM relay_publications",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let fingerprint: String = row.get(1)?;
+        let relay_url: String = row.get(2)?;
+        let share_index: i32 = row.get(3)?;
+        let share_total: i32 = row.get(4)?;
+        let success = row.get::<_, i32>(5)? != 0;
+        Ok(TimelineEvent {
+            timestamp: row.get(0)?,
+            kind: "relay_publication".into(),
+            summary: format!(
+                "Share {}/{} for heir {} {} {}",
+                share_index,
+                share_total,
+                fingerprint,
+                if success {
+                    "published to"
+                } else {
+                    "failed to publish to"
+                },
+                relay_url
+            ),
+        })
+    })?;
+    rows.collect()
+}
+
+/// All inheritance events from every source table, oldest first.
+///
+/// Ties keep source order (check-ins, spends, deliveries, relay publications).
+pub fn inheritance_timeline(conn: &Connection) -> SqlResult<Vec<TimelineEvent>> {
+    let mut events = timeline_checkins(conn)?;
+    events.extend(timeline_spend_events(conn)?);
+    events.extend(timeline_deliveries(conn)?);
+    events.extend(timeline_relay_publications(conn)?);
+    events.sort_by_key(|e| e.timestamp);
+    Ok(events)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -852,6 +1518,7 @@ pub fn relay_publication_success_count(conn: &Connection, split_id: &str) -> Sql
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::test_heir_row;
     use tempfile::NamedTempFile;
 
     fn temp_db() -> (Connection, NamedTempFile) {
@@ -895,6 +1562,7 @@ mod tests {
             npub: None,
             email: None,
             timelock_months: None,
+            channels: None,
         };
 
         // Insert
@@ -922,6 +1590,7 @@ mod tests {
             npub: Some("npub1test".into()),
             email: Some("wife@example.com".into()),
             timelock_months: Some(12),
+            channels: None,
         };
         heir_upsert(&conn, &updated).unwrap();
         let list = heir_list(&conn).unwrap();
@@ -948,6 +1617,7 @@ mod tests {
             npub: None,
             email: None,
             timelock_months: None,
+            channels: None,
         };
         heir_upsert(&conn, &heir).unwrap();
 
@@ -1031,6 +1701,104 @@ mod tests {
         assert_eq!(all[0].timestamp, 3000);
     }
 
+    #[test]
+    fn test_delivery_log_content_hash() {
+        let (conn, _f) = temp_db();
+
+        // Legacy insert has no hash
+        delivery_log_insert(&conn, "a1b2c3d4", "nostr", 1000, true, None).unwrap();
+        assert_eq!(
+            delivery_last_success_entry(&conn, "a1b2c3d4", "nostr").unwrap(),
+            Some((1000, None))
+        );
+
+        delivery_log_insert_with_hash(&conn, "a1b2c3d4", "nostr", 2000, true, None, Some("aa"))
+            .unwrap();
+        assert_eq!(
+            delivery_last_success_entry(&conn, "a1b2c3d4", "nostr").unwrap(),
+            Some((2000, Some("aa".to_string())))
+        );
+
+        // A failed attempt with new content doesn't replace the last success
+        delivery_log_insert_with_hash(
+            &conn,
+            "a1b2c3d4",
+            "nostr",
+            3000,
+            false,
+            Some("relay timeout"),
+            Some("bb"),
+        )
+        .unwrap();
+        assert_eq!(
+            delivery_last_success_entry(&conn, "a1b2c3d4", "nostr").unwrap(),
+            Some((2000, Some("aa".to_string())))
+        );
+        assert_eq!(
+            delivery_last_success(&conn, "a1b2c3d4", "nostr").unwrap(),
+            Some(2000)
+        );
+    }
+
+    #[test]
+    fn test_delivery_retry_scheduling() {
+        let (conn, _f) = temp_db();
+
+        delivery_log_insert_attempt(
+            &conn,
+            "fp1",
+            "nostr",
+            1000,
+            false,
+            Some("relay down"),
+            Some("h1"),
+            1,
+            Some(1300),
+        )
+        .unwrap();
+        // Failures without a retry time are never picked up
+        delivery_log_insert_attempt(
+            &conn,
+            "fp2",
+            "email",
+            1000,
+            false,
+            Some("smtp"),
+            Some("h1"),
+            5,
+            None,
+        )
+        .unwrap();
+
+        assert!(delivery_retry_due(&conn, 1299).unwrap().is_empty());
+        let due = delivery_retry_due(&conn, 1300).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].heir_fingerprint, "fp1");
+        assert_eq!(due[0].attempt, 1);
+        assert_eq!(due[0].content_hash.as_deref(), Some("h1"));
+
+        // A newer attempt on the same heir + channel supersedes the retry
+        delivery_log_insert_attempt(&conn, "fp1", "nostr", 1300, true, None, Some("h1"), 2, None)
+            .unwrap();
+        assert!(delivery_retry_due(&conn, 10_000).unwrap().is_empty());
+
+        delivery_log_insert_attempt(
+            &conn,
+            "fp1",
+            "nostr",
+            2000,
+            false,
+            Some("relay down"),
+            Some("h2"),
+            1,
+            Some(2300),
+        )
+        .unwrap();
+        let due = delivery_retry_due(&conn, 2300).unwrap();
+        delivery_retry_clear(&conn, due[0].id).unwrap();
+        assert!(delivery_retry_due(&conn, 2300).unwrap().is_empty());
+    }
+
     #[test]
     fn test_delivery_log_across_connections() {
         let file = NamedTempFile::new().expect("create temp file");
@@ -1049,6 +1817,7 @@ mod tests {
                     npub: Some("npub1test".into()),
                     email: None,
                     timelock_months: None,
+                    channels: None,
                 },
             )
             .unwrap();
@@ -1101,7 +1870,7 @@ mod tests {
         // No events initially
         let events = spend_event_list(&conn).unwrap();
         assert!(events.is_empty());
-        assert!(!has_heir_claims(&conn).unwrap());
+        assert!(!has_heir_claims(&conn, DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE).unwrap());
 
         // Insert owner checkin
         spend_event_insert(
@@ -1113,6 +1882,8 @@ mod tests {
             "witness_analysis",
             Some("policy1"),
             Some("abc:0"),
+            None,
+            &[],
         )
         .unwrap();
 
@@ -1120,7 +1891,7 @@ mod tests {
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].spend_type, "owner_checkin");
         assert_eq!(events[0].confidence, 0.95);
-        assert!(!has_heir_claims(&conn).unwrap());
+        assert!(!has_heir_claims(&conn, DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE).unwrap());
 
         // Insert heir claim
         spend_event_insert(
@@ -1132,24 +1903,110 @@ mod tests {
             "witness_analysis",
             Some("policy1"),
             Some("def:0"),
+            Some("aabbccdd"),
+            &[],
         )
         .unwrap();
 
-        assert!(has_heir_claims(&conn).unwrap());
+        assert!(has_heir_claims(&conn, DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE).unwrap());
 
         let events = spend_event_list(&conn).unwrap();
         assert_eq!(events.len(), 2);
+        assert_eq!(events[0].heir_fingerprint.as_deref(), Some("aabbccdd"));
+        assert!(events[0].heir_candidates.is_empty());
+        assert_eq!(events[1].heir_fingerprint, None);
 
         // Filter by type
         let heir_events = spend_event_list_by_type(&conn, "heir_claim").unwrap();
         assert_eq!(heir_events.len(), 1);
         assert_eq!(heir_events[0].txid, "txid_heir");
+        assert_eq!(heir_events[0].heir_fingerprint.as_deref(), Some("aabbccdd"));
 
         let owner_events = spend_event_list_by_type(&conn, "owner_checkin").unwrap();
         assert_eq!(owner_events.len(), 1);
         assert_eq!(owner_events[0].txid, "txid_owner");
     }
 
+    #[test]
+    fn test_heir_channels() {
+        let (conn, _f) = temp_db();
+
+        let mut heir = HeirRow {
+            npub: Some("npub1alice".into()),
+            email: Some("alice@example.com".into()),
+            ..test_heir_row("a1b2c3d4", "Alice")
+        };
+        heir_upsert(&conn, &heir).unwrap();
+        let found = heir_get(&conn, "a1b2c3d4").unwrap().unwrap();
+        assert!(found.channels.is_none());
+        assert!(found.accepts_channel("nostr") && found.accepts_channel("email"));
+
+        let nostr_only = vec!["nostr".to_string()];
+        assert!(heir_set_channels(&conn, "a1b2c3d4", Some(&nostr_only)).unwrap());
+        let found = heir_get(&conn, "a1b2c3d4").unwrap().unwrap();
+        assert_eq!(found.channels, Some(nostr_only.clone()));
+        assert!(!found.accepts_channel("email"));
+
+        // Re-saving the heir without a preference keeps the stored one
+        heir.label = "Alice B".into();
+        heir_upsert(&conn, &heir).unwrap();
+        let found = &heir_list(&conn).unwrap()[0];
+        assert_eq!(found.channels, Some(nostr_only));
+
+        assert!(heir_set_channels(&conn, "a1b2c3d4", None).unwrap());
+        assert!(heir_get(&conn, "a1b2c3d4")
+            .unwrap()
+            .unwrap()
+            .channels
+            .is_none());
+        assert!(!heir_set_channels(&conn, "nonexistent", None).unwrap());
+    }
+
+    #[test]
+    fn test_heir_claim_confidence_threshold() {
+        let (conn, _f) = temp_db();
+
+        // Timing-only guess: recorded, but no alert at the default threshold
+        spend_event_insert(
+            &conn,
+            1000,
+            "txid_guess",
+            "heir_claim",
+            0.5,
+            "timing",
+            Some("policy1"),
+            Some("abc:0"),
+            None,
+            &[],
+        )
+        .unwrap();
+        assert!(!has_heir_claims(&conn, DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE).unwrap());
+        assert!(has_heir_claims(&conn, 0.5).unwrap());
+        assert_eq!(spend_event_list(&conn).unwrap().len(), 1);
+
+        // Witness-confirmed claim trips it, even when two heirs share the path
+        let candidates = vec!["aaaa0001".to_string(), "aaaa0002".to_string()];
+        spend_event_insert(
+            &conn,
+            2000,
+            "txid_heir",
+            "heir_claim",
+            0.9,
+            "witness_analysis",
+            Some("policy1"),
+            Some("def:0"),
+            None,
+            &candidates,
+        )
+        .unwrap();
+        assert!(has_heir_claims(&conn, DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE).unwrap());
+        assert!(!has_heir_claims(&conn, 0.95).unwrap());
+        assert_eq!(
+            spend_event_list(&conn).unwrap()[0].heir_candidates,
+            candidates
+        );
+    }
+
     #[test]
     fn test_persistence_across_connections() {
         let file = NamedTempFile::new().expect("create temp file");
@@ -1172,6 +2029,7 @@ mod tests {
                     npub: Some("npub1child".into()),
                     email: Some("child@example.com".into()),
                     timelock_months: Some(18),
+                    channels: None,
                 },
             )
             .unwrap();
@@ -1308,6 +2166,7 @@ mod tests {
                     npub: None,
                     email: Some(format!("heir{}@example.com", i)),
                     timelock_months: Some(6 * (i as u32 + 1)),
+                    channels: None,
                 },
             )
             .unwrap();
@@ -1717,6 +2576,7 @@ mod tests {
             npub: None,
             email: None,
             timelock_months: Some(12),
+            channels: None,
         };
         heir_upsert(&conn, &heir).unwrap();
 
@@ -1732,6 +2592,7 @@ mod tests {
         // Update timelock via upsert
         let updated = HeirRow {
             timelock_months: Some(24),
+            channels: None,
             ..heir.clone()
         };
         heir_upsert(&conn, &updated).unwrap();
@@ -1747,6 +2608,7 @@ mod tests {
             npub: None,
             email: None,
             timelock_months: None,
+            channels: None,
         };
         heir_upsert(&conn, &heir_no_tl).unwrap();
         let found = heir_get(&conn, "n0t1m3lk").unwrap().unwrap();
@@ -1771,6 +2633,7 @@ mod tests {
                     npub: None,
                     email: None,
                     timelock_months: Some(18),
+                    channels: None,
                 },
             )
             .unwrap();
@@ -1784,4 +2647,128 @@ mod tests {
             assert_eq!(found.label, "PersistHeir");
         }
     }
+
+    #[test]
+    fn test_chain_cache_roundtrip() {
+        let (conn, _f) = temp_db();
+        let descriptor = "wsh(pk(A))";
+        assert!(chain_cache_get(&conn, descriptor).unwrap().is_none());
+
+        let mut snapshot = ChainSnapshot {
+            block_height: 880_000,
+            balance_sats: Some(150_000),
+            utxos: vec![CachedUtxo {
+                outpoint: format!("{}:0", "ab".repeat(32)),
+                value_sats: 150_000,
+                height: 879_990,
+            }],
+            fetched_at: 1_700_000_000,
+        };
+        chain_cache_put(&conn, descriptor, &snapshot).unwrap();
+        assert_eq!(
+            chain_cache_get(&conn, descriptor).unwrap(),
+            Some(snapshot.clone())
+        );
+
+        // Only the latest snapshot is kept
+        snapshot.block_height = 880_001;
+        snapshot.balance_sats = None;
+        snapshot.utxos.clear();
+        chain_cache_put(&conn, descriptor, &snapshot).unwrap();
+        assert_eq!(chain_cache_get(&conn, descriptor).unwrap(), Some(snapshot));
+    }
+
+    #[test]
+    fn test_chain_cache_dropped_when_descriptor_changes() {
+        let (conn, _f) = temp_db();
+        let snapshot = ChainSnapshot {
+            block_height: 880_000,
+            balance_sats: Some(150_000),
+            utxos: Vec::new(),
+            fetched_at: 1_700_000_000,
+        };
+        chain_cache_put(&conn, "wsh(pk(A))", &snapshot).unwrap();
+
+        assert!(chain_cache_get(&conn, "wsh(pk(B))").unwrap().is_none());
+        // Gone for good, not just hidden
+        assert!(chain_cache_get(&conn, "wsh(pk(A))").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_untagged_chain_cache_is_dropped() {
+        let (conn, _f) = temp_db();
+        conn.execute(
+            "INSERT INTO chain_cache (id, block_height, balance_sats, utxos, fetched_at)
+             VALUES (1, 880000, 150000, '[]', 1700000000)",
+            [],
+        )
+        .unwrap();
+        assert!(chain_cache_get(&conn, "wsh(pk(A))").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_inheritance_timeline_ordering() {
+        let (conn, _f) = temp_db();
+
+        relay_publication_insert(
+            &conn,
+            "split-1",
+            "fp1",
+            "npub1heir",
+            "wss://relay.example",
+            Some("evt"),
+            1,
+            2,
+            1_700_000_400,
+            true,
+            None,
+        )
+        .unwrap();
+        delivery_log_insert(&conn, "fp1", "email", 1_700_000_100, true, None).unwrap();
+        checkin_log_insert(&conn, 1_700_000_300, "txid_checkin").unwrap();
+        spend_event_insert(
+            &conn,
+            1_700_000_200,
+            "txid_spend",
+            "owner_checkin",
+            0.95,
+            "witness_analysis",
+            None,
+            None,
+            None,
+            &[],
+        )
+        .unwrap();
+
+        let timeline = inheritance_timeline(&conn).unwrap();
+        let kinds: Vec<&str> = timeline.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            vec!["delivery", "spend", "checkin", "relay_publication"]
+        );
+        assert!(timeline
+            .windows(2)
+            .all(|w| w[0].timestamp <= w[1].timestamp));
+        assert!(timeline[2].summary.contains("txid_checkin"));
+        assert!(timeline[3].summary.contains("wss://relay.example"));
+    }
+
+    #[test]
+    fn test_checkin_receipt_roundtrip() {
+        let (conn, _f) = temp_db();
+
+        assert_eq!(checkin_receipt_get(&conn, "abc").unwrap(), None);
+        checkin_receipt_insert(&conn, "abc", "{\"v\":1}", 1_700_000_000).unwrap();
+        assert_eq!(
+            checkin_receipt_get(&conn, "abc").unwrap().as_deref(),
+            Some("{\"v\":1}")
+        );
+
+        // Re-issuing replaces the receipt
+        checkin_receipt_insert(&conn, "abc", "{\"v\":2}", 1_700_000_100).unwrap();
+        assert_eq!(
+            checkin_receipt_get(&conn, "abc").unwrap().as_deref(),
+            Some("{\"v\":2}")
+        );
+    }
 }
@@ -0,0 +1,175 @@
+//! Check-in fee-rate strategy
+//!
+//! Owners choose how quickly check-ins should confirm instead of guessing a
+//! sat/vB rate. `economy`, `normal` and `priority` ask the Electrum server
+//! for a 6-, 3- and 1-block estimate; `fixed:<sat/vB>` skips estimation.
+//! The default lives in the `checkin_fee_strategy` config row.
+//!
+//! A check-in stuck in the mempool while the timelock runs out hands the
+//! funds to the heirs, so close to expiry every check-in is escalated to
+//! `priority` whatever the configured default.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Config key holding the default strategy
+pub const FEE_STRATEGY_KEY: &str = "checkin_fee_strategy";
+
+/// How to pick a check-in's fee rate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeStrategy {
+    /// Confirm within ~6 blocks
+    Economy,
+    /// Confirm within ~3 blocks
+    #[default]
+    Normal,
+    /// Confirm in the next block
+    Priority,
+    /// A fixed rate in sat/vB
+    Fixed(u64),
+}
+
+impl FeeStrategy {
+    /// Confirmation target to estimate for; `None` for a fixed rate
+    pub fn target_blocks(self) -> Option<usize> {
+        match self {
+            FeeStrategy::Economy => Some(6),
+            FeeStrategy::Normal => Some(3),
+            FeeStrategy::Priority => Some(1),
+            FeeStrategy::Fixed(_) => None,
+        }
+    }
+
+    /// The strategy to use with `blocks_remaining` until the timelock
+    /// expires: `Priority` once fewer than `threshold_blocks` remain.
+    pub fn near_expiry(self, blocks_remaining: Option<i64>, threshold_blocks: i64) -> Self {
+        match blocks_remaining {
+            Some(remaining) if remaining < threshold_blocks => FeeStrategy::Priority,
+            _ => self,
+        }
+    }
+
+    /// Resolve to a whole sat/vB rate (at least 1), calling `estimate` with
+    /// the confirmation target for every strategy but `Fixed`.
+    pub fn resolve<E>(self, estimate: impl FnOnce(usize) -> Result<f64, E>) -> Result<u64, E> {
+        if let FeeStrategy::Fixed(rate) = self {
+            return Ok(rate.max(1));
+        }
+        let target = self.target_blocks().unwrap_or(1);
+        Ok((estimate(target)?.ceil() as u64).max(1))
+    }
+}
+
+impl fmt::Display for FeeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeStrategy::Economy => f.write_str("economy"),
+            FeeStrategy::Normal => f.write_str("normal"),
+            FeeStrategy::Priority => f.write_str("priority"),
+            FeeStrategy::Fixed(rate) => write!(f, "fixed:{}", rate),
+        }
+    }
+}
+
+impl FromStr for FeeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "economy" => Ok(FeeStrategy::Economy),
+            "normal" => Ok(FeeStrategy::Normal),
+            "priority" => Ok(FeeStrategy::Priority),
+            other => match other.strip_prefix("fixed:").map(|r| r.trim().parse::<u64>()) {
+                Some(Ok(rate)) if rate > 0 => Ok(FeeStrategy::Fixed(rate)),
+                Some(_) => Err(format!(
+                    "Invalid fixed fee rate in '{}' (expected a whole sat/vB above 0)",
+                    other
+                )),
+                None => Err(format!(
+                    "Unknown fee strategy '{}' (expected economy, normal, priority or fixed:<sat/vB>)",
+                    other
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_targets() {
+        assert_eq!(FeeStrategy::Economy.target_blocks(), Some(6));
+        assert_eq!(FeeStrategy::Normal.target_blocks(), Some(3));
+        assert_eq!(FeeStrategy::Priority.target_blocks(), Some(1));
+        assert_eq!(FeeStrategy::Fixed(25).target_blocks(), None);
+        assert_eq!(FeeStrategy::default(), FeeStrategy::Normal);
+    }
+
+    #[test]
+    fn test_resolve_uses_target_estimate() {
+        // A fee market where faster confirmation costs more
+        let market = |target: usize| -> Result<f64, String> {
+            Ok(match target {
+                1 => 40.2,
+                3 => 12.0,
+                6 => 4.5,
+                _ => return Err(format!("unexpected target {}", target)),
+            })
+        };
+        assert_eq!(FeeStrategy::Priority.resolve(market), Ok(41));
+        assert_eq!(FeeStrategy::Normal.resolve(market), Ok(12));
+        assert_eq!(FeeStrategy::Economy.resolve(market), Ok(5));
+
+        // Fixed rates never estimate
+        let unused = |_: usize| -> Result<f64, String> { panic!("estimated a fixed rate") };
+        assert_eq!(FeeStrategy::Fixed(7).resolve(unused), Ok(7));
+
+        // Estimation errors propagate; rates never drop below 1 sat/vB
+        assert!(FeeStrategy::Normal
+            .resolve(|_| Err::<f64, _>("offline"))
+            .is_err());
+        assert_eq!(FeeStrategy::Economy.resolve(|_| Ok::<_, ()>(0.2)), Ok(1));
+    }
+
+    #[test]
+    fn test_escalates_near_expiry() {
+        let threshold = 4_320;
+        for strategy in [
+            FeeStrategy::Economy,
+            FeeStrategy::Normal,
+            FeeStrategy::Fixed(3),
+        ] {
+            assert_eq!(
+                strategy.near_expiry(Some(threshold - 1), threshold),
+                FeeStrategy::Priority
+            );
+            assert_eq!(
+                strategy.near_expiry(Some(-10), threshold),
+                FeeStrategy::Priority
+            );
+            assert_eq!(strategy.near_expiry(Some(threshold), threshold), strategy);
+            assert_eq!(strategy.near_expiry(None, threshold), strategy);
+        }
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        for strategy in [
+            FeeStrategy::Economy,
+            FeeStrategy::Normal,
+            FeeStrategy::Priority,
+            FeeStrategy::Fixed(15),
+        ] {
+            assert_eq!(strategy.to_string().parse::<FeeStrategy>(), Ok(strategy));
+        }
+        assert_eq!(" Priority ".parse(), Ok(FeeStrategy::Priority));
+        assert!("fixed:0".parse::<FeeStrategy>().is_err());
+        assert!("fixed:fast".parse::<FeeStrategy>().is_err());
+        assert!("turbo".parse::<FeeStrategy>().is_err());
+    }
+}
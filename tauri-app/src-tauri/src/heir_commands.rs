@@ -11,14 +11,15 @@
 //! 4. build_heir_claim — paste destination address, get unsigned PSBT
 //! 5. broadcast_heir_claim — submit signed PSBT, broadcast to network
 
+use crate::audit::AuditAction;
 use crate::db;
 use crate::state::{AppState, CcdState};
 use bitcoin::address::NetworkChecked;
 use bitcoin::{Address, Amount, Network};
 use nostring_ccd::register_cosigner_with_chain_code;
 use nostring_ccd::types::ChainCode;
+use nostring_inherit::checkin::TimelockStatus;
 use nostring_inherit::heir::HeirKey;
-use nostring_inherit::policy::Timelock;
 use nostring_inherit::taproot::{
     build_heir_claim_psbt, create_inheritable_vault, estimate_heir_claim_vbytes,
 };
@@ -26,7 +27,7 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use tauri::State;
 
-use crate::ccd_commands::CcdResult;
+use crate::ccd_commands::{chain_point, CcdResult};
 
 // ============================================================================
 // Descriptor backup format (shared with heir app via nostring-inherit)
@@ -48,6 +49,8 @@ pub struct ClaimStatus {
     pub days_remaining: f64,
     /// Vault balance in satoshis
     pub vault_balance_sat: u64,
+    /// Vault balance in the user's display unit
+    pub vault_balance_display: String,
     /// Number of UTXOs in the vault
     pub utxo_count: usize,
     /// Heir's role in the quorum
@@ -121,12 +124,9 @@ pub async fn import_vault_backup(
     }
 
     // Parse network
-    let network = match backup.network.as_str() {
-        "bitcoin" | "mainnet" => Network::Bitcoin,
-        "testnet" | "testnet3" => Network::Testnet,
-        "signet" => Network::Signet,
-        "regtest" => Network::Regtest,
-        other => return Ok(CcdResult::err(format!("Unknown network: {}", other))),
+    let network = match parse_backup_network(&backup.network) {
+        Ok(n) => n,
+        Err(e) => return Ok(CcdResult::err(e)),
     };
 
     // Parse owner pubkey
@@ -179,9 +179,7 @@ pub async fn import_vault_backup(
             };
             let fp = xpub.fingerprint();
             let derivation = bitcoin::bip32::DerivationPath::from_str(&entry.derivation_path)
-                .unwrap_or_else(|_| {
-                    bitcoin::bip32::DerivationPath::from_str("m/84'/0'/0'").unwrap()
-                });
+                .unwrap_or_else(|_| state.default_account_path());
             let mut heir = HeirKey::new(&entry.label, fp, xpub, Some(derivation));
             heir.npub = entry.npub.clone();
             keys.push(heir);
@@ -199,7 +197,7 @@ pub async fn import_vault_backup(
         None => return Ok(CcdResult::err("Failed to convert heir keys")),
     };
 
-    let timelock = match Timelock::from_blocks(backup.timelock_blocks) {
+    let timelock = match backup.timelock() {
         Ok(t) => t,
         Err(e) => return Ok(CcdResult::err(format!("Invalid timelock: {}", e))),
     };
@@ -259,15 +257,51 @@ pub async fn import_vault_backup(
     state.set_network(network);
 
     log::info!(
-        "Heir vault imported: {} ({} heirs, timelock {} blocks)",
+        "Heir vault imported: {} ({} heirs, timelock {})",
         reconstructed_addr,
         heir_keys.len(),
-        backup.timelock_blocks
+        timelock
     );
 
     Ok(CcdResult::ok(reconstructed_addr))
 }
 
+/// Network name as written in a backup.
+fn parse_backup_network(network: &str) -> Result<Network, String> {
+    match network {
+        "bitcoin" | "mainnet" => Ok(Network::Bitcoin),
+        "testnet" | "testnet3" => Ok(Network::Testnet),
+        "signet" => Ok(Network::Signet),
+        "regtest" => Ok(Network::Regtest),
+        other => Err(format!("Unknown network: {}", other)),
+    }
+}
+
+/// Check that a backup's address really belongs to its descriptor.
+///
+/// Derives the backup's `address_index` (0 when absent) from the
+/// descriptor locally, so the heir doesn't have to trust the address the
+/// backup came with. A mismatch means the descriptor or the address was
+/// altered.
+#[tauri::command]
+pub async fn verify_inheritance_address(
+    descriptor: String,
+    address: String,
+    address_index: Option<u32>,
+    network: String,
+) -> Result<CcdResult<bool>, ()> {
+    let network = match parse_backup_network(&network) {
+        Ok(n) => n,
+        Err(e) => return Ok(CcdResult::err(e)),
+    };
+
+    let index = address_index.unwrap_or(0);
+    match nostring_inherit::verify_address_at(&descriptor, &address, index, network) {
+        Ok(matches) => Ok(CcdResult::ok(matches)),
+        Err(e) => Ok(CcdResult::err(format!("Cannot verify address: {}", e))),
+    }
+}
+
 /// Verify the heir's identity against the imported vault.
 ///
 /// The heir provides their xpub and/or npub. The app checks if either matches
@@ -382,10 +416,7 @@ pub async fn check_claim_eligibility(
         }
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => return Ok(CcdResult::err(format!("Electrum connection failed: {}", e))),
     };
@@ -415,11 +446,20 @@ pub async fn check_claim_eligibility(
         ));
     }
 
-    let timelock_blocks = vault.timelock.blocks() as u64;
-    let expiry = oldest_height as u64 + timelock_blocks;
-    let remaining = expiry as i64 - current_height as i64;
-    let eligible = remaining <= 0;
-    let days = remaining as f64 * 10.0 / 1440.0;
+    let (oldest, current) = match (
+        chain_point(&client, oldest_height, &vault.timelock),
+        chain_point(&client, current_height, &vault.timelock),
+    ) {
+        (Ok(oldest), Ok(current)) => (oldest, current),
+        (Err(e), _) | (_, Err(e)) => {
+            return Ok(CcdResult::err(format!("Failed to get block time: {}", e)))
+        }
+    };
+
+    let timelock_status = TimelockStatus::for_timelock(&vault.timelock, current, oldest);
+    let remaining = timelock_status.blocks_remaining as i64;
+    let eligible = timelock_status.expired;
+    let days = timelock_status.seconds_remaining as f64 / 86_400.0;
 
     // Load backup for quorum info
     let quorum = {
@@ -452,6 +492,7 @@ pub async fn check_claim_eligibility(
         blocks_remaining: remaining,
         days_remaining: days,
         vault_balance_sat: total_balance,
+        vault_balance_display: crate::units::format_amount(total_balance, state.display_unit()),
         utxo_count: utxos.len(),
         quorum_info: quorum,
     }))
@@ -492,9 +533,7 @@ pub async fn build_heir_claim(
         Err(e) => return Ok(CcdResult::err(format!("Invalid Bitcoin address: {}", e))),
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
+    let client = match state.electrum_client() {
         Ok(c) => c,
         Err(e) => return Ok(CcdResult::err(format!("Electrum connection failed: {}", e))),
     };
@@ -597,29 +636,23 @@ pub async fn broadcast_heir_claim(
         }
     };
 
-    let electrum_url = state.electrum_url.lock().unwrap().clone();
-    let network = *state.network.lock().unwrap();
-
-    let client = match nostring_electrum::ElectrumClient::new(&electrum_url, network) {
-        Ok(c) => c,
-        Err(e) => return Ok(CcdResult::err(format!("Electrum connection failed: {}", e))),
-    };
-
-    let txid = match client.broadcast(&tx) {
+    // Broadcast over Electrum and HTTP; one acceptance is enough
+    let report = crate::broadcast::broadcast_tx(&state, &tx).await;
+    let txid = match report.result() {
         Ok(id) => id,
-        Err(e) => {
-            let msg = format!("{}", e);
+        Err(msg) => {
             if msg.contains("non-BIP68-final") || msg.contains("non-final") {
                 return Ok(CcdResult::err(
                     "Transaction rejected: timelock has not expired yet. \
                      The vault's check-in period has not elapsed.",
                 ));
             }
-            return Ok(CcdResult::err(format!("Broadcast failed: {}", e)));
+            return Ok(CcdResult::err(format!("Broadcast failed: {}", msg)));
         }
     };
 
     log::info!("Heir claim broadcast: {}", txid);
+    state.audit(AuditAction::Broadcast, &format!("heir claim {}", txid));
 
     // Log the claim
     {
@@ -777,6 +810,7 @@ pub async fn export_vault_backup(state: State<'_, AppState>) -> Result<CcdResult
         chain_code,
         address_index,
         timelock_blocks: vault.timelock.blocks(),
+        timelock: Some(u32::from(vault.timelock)),
         threshold: {
             // Read threshold from DB (set during vault creation), default to n-of-n
             let conn = state.db.lock().unwrap();
@@ -999,6 +1033,7 @@ fn build_vault_backup(
         chain_code: hex::encode(cosigner.chain_code.0),
         address_index: vault.address_index,
         timelock_blocks: vault.timelock.blocks(),
+        timelock: Some(u32::from(vault.timelock)),
         threshold: heir_entries.len().max(1),
         heirs: heir_entries,
         vault_address: vault.address.to_string(),
@@ -1032,6 +1067,7 @@ mod tests {
             chain_code: "ab".repeat(32),
             address_index: 0,
             timelock_blocks: 26280,
+            timelock: None,
             threshold: 1,
             heirs: vec![HeirBackupEntry {
                 label: "Alice".into(),
@@ -1048,6 +1084,7 @@ mod tests {
                 script_hex: "20abcd1234".into(),
                 control_block_hex: "c0deadbeef".into(),
                 timelock_blocks: 26280,
+                timelock: None,
                 leaf_version: 0xc0,
             }],
             created_at: Some("1739318400".into()),
@@ -4,11 +4,21 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod audit;
+mod broadcast;
+mod cancel;
 mod ccd_commands;
 mod commands;
 mod db;
+mod fees;
 mod heir_commands;
+mod manifest_commands;
+mod receipt_commands;
+mod self_test_commands;
 mod state;
+#[cfg(test)]
+mod test_utils;
+mod units;
 
 use state::AppState;
 use std::fs;
@@ -51,20 +61,29 @@ fn main() {
             commands::check_password_strength,
             commands::import_seed,
             commands::import_watch_only,
+            commands::import_hardware_export,
             commands::has_seed,
             commands::is_unlocked,
             commands::is_watch_only,
             commands::unlock_seed,
             commands::lock_wallet,
+            commands::export_seed_qr,
+            commands::import_seed_qr,
             // Policy status
             commands::get_policy_status,
+            commands::explain_descriptor,
             commands::refresh_policy_status,
+            commands::get_expiry_estimate,
+            cancel::cancel_operation,
             // Check-in
             commands::initiate_checkin,
+            commands::estimate_consolidation,
+            commands::estimate_heir_claim_fee,
             commands::complete_checkin,
             commands::broadcast_signed_psbt,
             // Heir management
             commands::add_heir,
+            commands::import_heirs,
             commands::list_heirs,
             commands::remove_heir,
             commands::get_heir,
@@ -72,11 +91,16 @@ fn main() {
             // Heir contact info (v0.2 - descriptor delivery)
             commands::set_heir_contact,
             commands::get_heir_contact,
+            commands::set_heir_channels,
             // Shamir shares
             commands::generate_codex32_shares,
             commands::combine_codex32_shares,
+            commands::generate_seed_shares,
+            commands::combine_seed_shares,
+            commands::check_recovery_progress,
             // nsec inheritance (Shamir split + recovery)
             commands::split_nsec,
+            commands::split_nsec_custom,
             commands::get_nsec_inheritance_status,
             commands::get_locked_shares,
             commands::recover_nsec,
@@ -84,33 +108,63 @@ fn main() {
             // Service key (notifications)
             commands::generate_service_key,
             commands::get_service_npub,
+            commands::rotate_service_key,
+            commands::get_retired_service_keys,
             // Notification management
             commands::configure_notifications,
             commands::get_notification_settings,
             commands::send_test_notification,
             commands::check_and_notify,
+            commands::retry_failed_deliveries,
             // Descriptor backup
             commands::get_descriptor_backup,
+            commands::backup_is_stale,
+            commands::verify_descriptor_backup,
+            commands::preview_heir_delivery,
             // Spend type detection
             commands::detect_spend_type,
             commands::get_spend_events,
+            commands::get_inheritance_timeline,
             commands::check_heir_claims,
+            commands::get_heir_claim_min_confidence,
+            commands::set_heir_claim_min_confidence,
             // Pre-signed check-in stack (v0.3 auto check-in)
             commands::add_presigned_checkin,
+            commands::import_presigned_chain,
             commands::get_presigned_checkin_status,
             commands::auto_broadcast_checkin,
             commands::invalidate_presigned_checkins,
             commands::delete_presigned_checkin,
             commands::generate_checkin_psbt_chain,
+            commands::recommend_psbt_chain_length,
             // Relay storage (v0.3.1 — locked share relay backup)
             commands::publish_locked_shares_to_relays,
+            commands::check_relay_health,
+            self_test_commands::inheritance_self_test,
+            manifest_commands::get_inheritance_manifest,
+            manifest_commands::load_inheritance_manifest,
+            receipt_commands::get_checkin_receipt,
+            receipt_commands::verify_checkin_receipt,
+            audit::verify_audit_log,
+            audit::export_audit_log,
             commands::fetch_locked_shares_from_relays,
             commands::get_relay_publication_status,
             // Settings
             commands::get_network,
             commands::set_network,
+            commands::set_custom_signet,
             commands::get_electrum_url,
             commands::set_electrum_url,
+            commands::get_http_broadcast_url,
+            commands::set_http_broadcast_url,
+            commands::get_display_unit,
+            commands::set_display_unit,
+            commands::get_coin_type,
+            commands::set_coin_type,
+            commands::get_fee_strategy,
+            commands::set_fee_strategy,
+            commands::get_checkin_rotation,
+            commands::set_checkin_rotation,
             // CCD (Chain Code Delegation)
             ccd_commands::get_ccd_load_error,
             ccd_commands::register_cosigner,
@@ -124,6 +178,7 @@ fn main() {
             // Heir claim
             heir_commands::import_vault_backup,
             heir_commands::verify_heir_identity,
+            heir_commands::verify_inheritance_address,
             heir_commands::check_claim_eligibility,
             heir_commands::build_heir_claim,
             heir_commands::broadcast_heir_claim,
@@ -0,0 +1,280 @@
+//! Portable `.nostring` inheritance manifest.
+//!
+//! One file holding everything an heir's recovery tool needs: the
+//! descriptor backup, locked nsec shares, and where those shares were
+//! published on Nostr. The file format, signing and encryption live in
+//! [`nostring_core::manifest`], shared with `nostring-recover`.
+
+use crate::commands::{
+    assemble_descriptor_backup, sign_digest_with_service_key, verify_digest_signature,
+    CommandResult, DescriptorBackupHeir,
+};
+use crate::state::AppState;
+use nostring_core::manifest::{ManifestFile, MANIFEST_VERSION};
+use nostring_notify::nostr_relay;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Everything needed to recover an inheritance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InheritanceManifest {
+    pub version: u32,
+    pub created_at: u64,
+    pub descriptor: String,
+    pub network: String,
+    pub timelock_blocks: u16,
+    pub address: Option<String>,
+    /// Receive index `address` is derived at, when not 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_index: Option<u32>,
+    pub heirs: Vec<DescriptorBackupHeir>,
+    pub nsec_owner_npub: Option<String>,
+    pub locked_shares: Option<Vec<String>>,
+    /// Relays locked shares are published to
+    pub relays: Vec<String>,
+    /// Service key npub. In standard mode share events on relays are
+    /// authored by it; gift-wrapped events are signed by throwaway keys and
+    /// only name it inside the seal, so heirs must search by their own npub
+    pub service_npub: Option<String>,
+    /// How the shares were published; `None` in manifests written before
+    /// this field existed, which were always standard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy_mode: Option<nostr_relay::PrivacyMode>,
+    /// `split_id` tag of the published share events
+    pub split_id: Option<String>,
+    /// Hex Schnorr signature by the service key over the canonical JSON of
+    /// every other field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// npub of the service key that produced `signature`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_npub: Option<String>,
+}
+
+/// SHA-256 of the manifest's canonical JSON, excluding the signature fields.
+fn manifest_digest(manifest: &InheritanceManifest) -> Result<[u8; 32], String> {
+    nostring_core::manifest::signed_digest(manifest).map_err(|e| e.to_string())
+}
+
+/// Sign `manifest` and serialize it to file bytes, encrypting when a
+/// password is given.
+fn write_manifest_file(
+    mut manifest: InheritanceManifest,
+    service_secret: &str,
+    password: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    let (signature, npub) =
+        sign_digest_with_service_key(manifest_digest(&manifest)?, service_secret)?;
+    manifest.signature = Some(signature);
+    manifest.signer_npub = Some(npub);
+
+    ManifestFile::new(&manifest, password)
+        .and_then(|file| file.to_vec())
+        .map_err(|e| e.to_string())
+}
+
+/// Parse file bytes, decrypting if needed, and verify the signature.
+fn read_manifest_file(bytes: &[u8], password: Option<&str>) -> Result<InheritanceManifest, String> {
+    let value = ManifestFile::from_slice(bytes)
+        .and_then(|file| file.open(password))
+        .map_err(|e| e.to_string())?;
+    let manifest: InheritanceManifest =
+        serde_json::from_value(value).map_err(|e| format!("Invalid manifest: {}", e))?;
+
+    let (Some(sig_hex), Some(npub)) = (&manifest.signature, &manifest.signer_npub) else {
+        return Err("Manifest is not signed".into());
+    };
+    verify_digest_signature(manifest_digest(&manifest)?, sig_hex, npub)?;
+    Ok(manifest)
+}
+
+/// Export the inheritance setup as a `.nostring` manifest file.
+///
+/// With `password`, the manifest is encrypted; heirs need the password to
+/// open it. Requires the service key, which signs the contents.
+#[tauri::command]
+pub async fn get_inheritance_manifest(
+    password: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Vec<u8>>, ()> {
+    let Some(backup) = assemble_descriptor_backup(&state) else {
+        return Ok(CommandResult::err(
+            "No inheritance policy configured. Add heirs first.",
+        ));
+    };
+    let Some(service_secret) = state.service_secret() else {
+        return Ok(CommandResult::err(
+            "No service key generated. Go to Settings → Notifications to set up.",
+        ));
+    };
+
+    let split_id = {
+        let conn = state.db.lock().unwrap();
+        crate::db::config_get(&conn, "last_relay_split_id")
+            .ok()
+            .flatten()
+    };
+    let manifest = InheritanceManifest {
+        version: MANIFEST_VERSION,
+        created_at: state.clock.now_secs(),
+        descriptor: backup.descriptor,
+        network: backup.network,
+        timelock_blocks: backup.timelock_blocks,
+        address: backup.address,
+        address_index: backup.address_index,
+        heirs: backup.heirs,
+        nsec_owner_npub: backup.nsec_owner_npub,
+        locked_shares: backup.locked_shares,
+        relays: nostr_relay::DEFAULT_RELAYS
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        service_npub: state.service_npub.lock().unwrap().clone(),
+        privacy_mode: Some(state.relay_privacy_mode()),
+        split_id,
+        signature: None,
+        signer_npub: None,
+    };
+
+    let password = password.filter(|p| !p.is_empty());
+    match write_manifest_file(manifest, service_secret.as_str(), password.as_deref()) {
+        Ok(bytes) => Ok(CommandResult::ok(bytes)),
+        Err(e) => Ok(CommandResult::err(format!(
+            "Failed to build manifest: {}",
+            e
+        ))),
+    }
+}
+
+/// Open a `.nostring` manifest (heir recovery tool).
+///
+/// Decrypts with `password` if the file is encrypted and rejects it if the
+/// signature doesn't match. Compare `signer_npub` with the npub the owner
+/// shared out of band.
+#[tauri::command]
+pub async fn load_inheritance_manifest(
+    bytes: Vec<u8>,
+    password: Option<String>,
+) -> CommandResult<InheritanceManifest> {
+    match read_manifest_file(&bytes, password.as_deref()) {
+        Ok(manifest) => CommandResult::ok(manifest),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_sdk::prelude::Keys;
+
+    fn sample_manifest() -> InheritanceManifest {
+        InheritanceManifest {
+            version: MANIFEST_VERSION,
+            created_at: 1_700_000_000,
+            descriptor: "wsh(or_d(pk(owner),and_v(v:pkh(heir),older(26280))))".into(),
+            network: "testnet".into(),
+            timelock_blocks: 26280,
+            address: Some("tb1qexample".into()),
+            address_index: None,
+            heirs: vec![DescriptorBackupHeir {
+                label: "Alice".into(),
+                xpub: "tpubheir".into(),
+                timelock_months: 6.0,
+            }],
+            nsec_owner_npub: None,
+            locked_shares: Some(vec!["ms12nsecaxxxx".into()]),
+            relays: vec!["wss://nos.lol".into()],
+            service_npub: None,
+            privacy_mode: Some(nostr_relay::PrivacyMode::GiftWrap),
+            split_id: Some("abc123".into()),
+            signature: None,
+            signer_npub: None,
+        }
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let keys = Keys::generate();
+        let bytes =
+            write_manifest_file(sample_manifest(), &keys.secret_key().to_secret_hex(), None)
+                .unwrap();
+
+        let loaded = read_manifest_file(&bytes, None).unwrap();
+        assert!(loaded.signature.is_some());
+        assert_eq!(
+            InheritanceManifest {
+                signature: None,
+                signer_npub: None,
+                ..loaded
+            },
+            sample_manifest()
+        );
+    }
+
+    #[test]
+    fn test_manifest_without_privacy_mode_still_verifies() {
+        // Manifests signed before `privacy_mode` existed must still load
+        let keys = Keys::generate();
+        let old = InheritanceManifest {
+            privacy_mode: None,
+            ..sample_manifest()
+        };
+        let bytes = write_manifest_file(old, &keys.secret_key().to_secret_hex(), None).unwrap();
+
+        let file: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(file["manifest"].get("privacy_mode").is_none());
+        let loaded = read_manifest_file(&bytes, None).unwrap();
+        assert_eq!(loaded.privacy_mode, None);
+    }
+
+    #[test]
+    fn test_encrypted_manifest_roundtrip() {
+        let keys = Keys::generate();
+        let bytes = write_manifest_file(
+            sample_manifest(),
+            &keys.secret_key().to_secret_hex(),
+            Some("hunter2"),
+        )
+        .unwrap();
+
+        // Nothing readable without the password
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert!(!text.contains("wsh("));
+        assert!(read_manifest_file(&bytes, None).is_err());
+        assert!(read_manifest_file(&bytes, Some("wrong")).is_err());
+
+        let loaded = read_manifest_file(&bytes, Some("hunter2")).unwrap();
+        assert_eq!(loaded.split_id.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_tampered_manifest_rejected() {
+        let keys = Keys::generate();
+        let bytes =
+            write_manifest_file(sample_manifest(), &keys.secret_key().to_secret_hex(), None)
+                .unwrap();
+
+        let mut file: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        file["manifest"]["relays"] = serde_json::json!(["wss://evil.example"]);
+        let tampered = serde_json::to_vec(&file).unwrap();
+        assert!(read_manifest_file(&tampered, None).is_err());
+
+        // Unsigned manifests are rejected too
+        file["manifest"]["signature"] = serde_json::Value::Null;
+        let unsigned = serde_json::to_vec(&file).unwrap();
+        assert!(read_manifest_file(&unsigned, None).is_err());
+    }
+
+    #[test]
+    fn test_newer_version_rejected() {
+        let keys = Keys::generate();
+        let bytes =
+            write_manifest_file(sample_manifest(), &keys.secret_key().to_secret_hex(), None)
+                .unwrap();
+
+        let mut file: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        file["version"] = serde_json::json!(MANIFEST_VERSION + 1);
+        let err = read_manifest_file(&serde_json::to_vec(&file).unwrap(), None).unwrap_err();
+        assert!(err.contains("newer"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Signed check-in receipts.
+//!
+//! After a manual check-in is broadcast, the service key signs a short
+//! statement — txid, time, chain height and a hash of the policy
+//! descriptor — so the owner can later prove they checked in by a given
+//! date (e.g. for estate documentation). Receipts are stored in the
+//! `checkin_receipts` table and verify offline; the on-chain check is
+//! optional.
+
+use crate::commands::{sign_digest_with_service_key, verify_digest_signature, CommandResult};
+use crate::state::{descriptor_hash, AppState};
+use nostring_core::canonical::to_canonical_json;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Current receipt format version
+pub const RECEIPT_VERSION: u32 = 1;
+
+/// A signed statement that a check-in transaction was broadcast
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckinReceipt {
+    pub version: u32,
+    /// Check-in transaction id
+    pub txid: String,
+    /// Unix time of the broadcast
+    pub timestamp: u64,
+    /// Chain tip height when the check-in was broadcast
+    pub block_height: u32,
+    /// SHA-256 of the inheritance policy descriptor
+    pub descriptor_hash: String,
+    pub network: String,
+    /// Hex Schnorr signature by the service key over the canonical JSON of
+    /// every other field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// npub of the service key that produced `signature`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_npub: Option<String>,
+}
+
+/// Outcome of [`verify_checkin_receipt`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiptVerification {
+    /// npub the receipt was signed by
+    pub signer_npub: String,
+    /// Whether the txid is confirmed on-chain (`None` if not checked)
+    pub confirmed: Option<bool>,
+    /// Block the check-in confirmed in, when confirmed
+    pub confirmation_height: Option<u32>,
+}
+
+/// SHA-256 of the receipt's canonical JSON, excluding the signature fields.
+fn receipt_digest(receipt: &CheckinReceipt) -> Result<[u8; 32], String> {
+    use bitcoin::hashes::{sha256, Hash};
+
+    let mut value = serde_json::to_value(receipt).map_err(|e| e.to_string())?;
+    if let Some(map) = value.as_object_mut() {
+        map.remove("signature");
+        map.remove("signer_npub");
+    }
+    Ok(sha256::Hash::hash(&to_canonical_json(&value)?).to_byte_array())
+}
+
+/// Build and sign a receipt with the service key (hex secret).
+fn sign_checkin_receipt(
+    txid: &str,
+    timestamp: u64,
+    block_height: u32,
+    descriptor: &str,
+    network: &str,
+    service_secret: &str,
+) -> Result<CheckinReceipt, String> {
+    let mut receipt = CheckinReceipt {
+        version: RECEIPT_VERSION,
+        txid: txid.to_string(),
+        timestamp,
+        block_height,
+        descriptor_hash: descriptor_hash(descriptor),
+        network: network.to_string(),
+        signature: None,
+        signer_npub: None,
+    };
+    let (signature, npub) =
+        sign_digest_with_service_key(receipt_digest(&receipt)?, service_secret)?;
+    receipt.signature = Some(signature);
+    receipt.signer_npub = Some(npub);
+    Ok(receipt)
+}
+
+/// Check a receipt's signature against the service keys we trust.
+///
+/// The embedded `signer_npub` is not trusted: anyone can sign a receipt
+/// with their own key and name it there. Returns the npub that verified.
+fn verify_receipt_signature(
+    receipt: &CheckinReceipt,
+    trusted_npubs: &[String],
+) -> Result<String, String> {
+    let Some(sig_hex) = &receipt.signature else {
+        return Err("Receipt is not signed".into());
+    };
+    let digest = receipt_digest(receipt)?;
+    trusted_npubs
+        .iter()
+        .find(|npub| verify_digest_signature(digest, sig_hex, npub).is_ok())
+        .cloned()
+        .ok_or_else(|| "Receipt is not signed by this vault's service key".to_string())
+}
+
+/// Sign and store a receipt for a check-in that was just broadcast.
+///
+/// Best effort: without a service key or inheritance policy no receipt is
+/// issued, and failures are logged rather than failing the check-in.
+pub(crate) fn issue_checkin_receipt(state: &AppState, txid: &str, block_height: u32) {
+    let Some(service_secret) = state.service_secret() else {
+        log::info!("No service key; skipping check-in receipt for {}", txid);
+        return;
+    };
+    let Some(config) = state.inheritance_config.lock().unwrap().clone() else {
+        return;
+    };
+
+    let now = state.clock.now_secs();
+    let receipt = match sign_checkin_receipt(
+        txid,
+        now,
+        block_height,
+        &config.descriptor,
+        &config.network,
+        service_secret.as_str(),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            log::warn!("Failed to sign check-in receipt for {}: {}", txid, e);
+            return;
+        }
+    };
+
+    let json = serde_json::to_string(&receipt).unwrap_or_default();
+    let conn = state.db.lock().unwrap();
+    if let Err(e) = crate::db::checkin_receipt_insert(&conn, txid, &json, now) {
+        log::warn!("Failed to store check-in receipt for {}: {}", txid, e);
+    }
+}
+
+/// Get the signed receipt for a check-in, if one was issued.
+#[tauri::command]
+pub async fn get_checkin_receipt(
+    txid: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<Option<CheckinReceipt>>, ()> {
+    let stored = {
+        let conn = state.db.lock().unwrap();
+        crate::db::checkin_receipt_get(&conn, &txid)
+    };
+    match stored {
+        Ok(Some(json)) => match serde_json::from_str(&json) {
+            Ok(receipt) => Ok(CommandResult::ok(Some(receipt))),
+            Err(e) => Ok(CommandResult::err(format!("Corrupt receipt: {}", e))),
+        },
+        Ok(None) => Ok(CommandResult::ok(None)),
+        Err(e) => Ok(CommandResult::err(format!("Database error: {}", e))),
+    }
+}
+
+/// Verify a check-in receipt.
+///
+/// Always checks the signature (offline) against the current service key
+/// and any retired by rotation. With `check_chain`, also asks Electrum
+/// whether the txid is confirmed.
+#[tauri::command]
+pub async fn verify_checkin_receipt(
+    receipt: CheckinReceipt,
+    check_chain: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<CommandResult<ReceiptVerification>, ()> {
+    let trusted_npubs: Vec<String> = state
+        .service_npub
+        .lock()
+        .unwrap()
+        .clone()
+        .into_iter()
+        .chain(state.retired_service_keys().into_iter().map(|k| k.npub))
+        .collect();
+    let signer_npub = match verify_receipt_signature(&receipt, &trusted_npubs) {
+        Ok(npub) => npub,
+        Err(e) => return Ok(CommandResult::err(e)),
+    };
+
+    let (confirmed, confirmation_height) = if check_chain.unwrap_or(false) {
+        let txid: bitcoin::Txid = match receipt.txid.parse() {
+            Ok(t) => t,
+            Err(e) => return Ok(CommandResult::err(format!("Invalid txid: {}", e))),
+        };
+        let height = state
+            .electrum_client()
+            .map_err(|e| format!("Failed to connect to Electrum: {}", e))
+            .and_then(|client| {
+                client
+                    .get_confirmation_height(&txid)
+                    .map_err(|e| format!("Failed to look up txid: {}", e))
+            });
+        match height {
+            Ok(height) => (Some(height.is_some()), height),
+            Err(e) => return Ok(CommandResult::err(e)),
+        }
+    } else {
+        (None, None)
+    };
+
+    Ok(CommandResult::ok(ReceiptVerification {
+        signer_npub,
+        confirmed,
+        confirmation_height,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_sdk::prelude::{Keys, ToBech32};
+
+    const TXID: &str = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+
+    fn signed(keys: &Keys) -> CheckinReceipt {
+        sign_checkin_receipt(
+            TXID,
+            1_700_000_000,
+            820_000,
+            "wsh(or_d(pk(owner),and_v(v:pkh(heir),older(26280))))",
+            "bitcoin",
+            &keys.secret_key().to_secret_hex(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_receipt_generation() {
+        let keys = Keys::generate();
+        let receipt = signed(&keys);
+
+        assert_eq!(receipt.version, RECEIPT_VERSION);
+        assert_eq!(receipt.txid, TXID);
+        assert_eq!(receipt.block_height, 820_000);
+        assert_eq!(receipt.descriptor_hash.len(), 64);
+        assert_eq!(
+            receipt.signer_npub.as_deref(),
+            Some(keys.public_key().to_bech32().unwrap().as_str())
+        );
+    }
+
+    #[test]
+    fn test_receipt_signature_verification() {
+        let keys = Keys::generate();
+        let npub = keys.public_key().to_bech32().unwrap();
+        let trusted = vec![npub.clone()];
+        let receipt = signed(&keys);
+
+        // Survives a JSON roundtrip
+        let json = serde_json::to_string(&receipt).unwrap();
+        let parsed: CheckinReceipt = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_receipt_signature(&parsed, &trusted).unwrap(), npub);
+
+        // Backdating is detected
+        let backdated = CheckinReceipt {
+            timestamp: receipt.timestamp - 86_400,
+            ..receipt.clone()
+        };
+        assert!(verify_receipt_signature(&backdated, &trusted).is_err());
+
+        // So is an unsigned receipt
+        let unsigned = CheckinReceipt {
+            signature: None,
+            ..receipt
+        };
+        assert!(verify_receipt_signature(&unsigned, &trusted).is_err());
+    }
+
+    #[test]
+    fn test_receipt_from_other_key_rejected() {
+        let ours = Keys::generate();
+        let ours_npub = ours.public_key().to_bech32().unwrap();
+
+        // A forger signs with their own key and names it as the signer
+        let forged = signed(&Keys::generate());
+        assert!(verify_receipt_signature(&forged, std::slice::from_ref(&ours_npub)).is_err());
+
+        // Claiming our npub without our signature doesn't help either
+        let relabelled = CheckinReceipt {
+            signer_npub: Some(ours_npub.clone()),
+            ..forged
+        };
+        assert!(verify_receipt_signature(&relabelled, std::slice::from_ref(&ours_npub)).is_err());
+
+        // A receipt from a key since rotated away still verifies
+        let retired = Keys::generate();
+        let retired_npub = retired.public_key().to_bech32().unwrap();
+        let trusted = vec![ours_npub, retired_npub.clone()];
+        assert_eq!(
+            verify_receipt_signature(&signed(&retired), &trusted).unwrap(),
+            retired_npub
+        );
+    }
+}
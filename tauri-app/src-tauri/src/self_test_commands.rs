@@ -0,0 +1,461 @@
+//! Recovery-readiness self-test.
+//!
+//! A dry run of everything inheritance depends on, so the owner finds a
+//! broken piece while they can still fix it. Network lookups (Electrum
+//! balance, relay reachability, published shares) are gathered first;
+//! [`build_report`] then turns them into pass/warn/fail checks without
+//! touching the network, which keeps the verdicts testable.
+
+use crate::commands::CommandResult;
+use crate::state::AppState;
+use bitcoin::{Address, Network};
+use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::Descriptor;
+use nostring_notify::nostr_relay;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Verdict of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckOutcome {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the self-test report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub outcome: CheckOutcome,
+    pub detail: String,
+}
+
+/// Full self-test result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+    /// No check failed (warnings allowed)
+    pub ready: bool,
+}
+
+/// An heir's contact channels
+#[derive(Debug, Clone)]
+struct HeirContact {
+    label: String,
+    npub: Option<String>,
+    email: Option<String>,
+}
+
+/// Everything the report is built from
+#[derive(Debug, Clone)]
+struct SelfTestInputs {
+    descriptor: Option<String>,
+    network: Network,
+    /// Receive index the funds sit on (0 until a rotating check-in)
+    address_index: u32,
+    /// Balance at the inheritance addresses; `None` if there was no address
+    balance: Option<Result<u64, String>>,
+    heirs: Vec<HeirContact>,
+    locked_shares: usize,
+    /// Share events found on relays per heir label; `None` if not checked
+    relay_shares: Option<Result<Vec<(String, usize)>, String>>,
+    /// Shares were gift-wrapped, so relays can't be counted without heir keys
+    shares_giftwrapped: bool,
+    /// Relays reachable with the service key; `None` without a service key
+    reachable_relays: Option<usize>,
+}
+
+/// Receive address at `index` of `descriptor`
+fn derive_address(descriptor: &str, index: u32, network: Network) -> Result<Address, String> {
+    let descriptor: Descriptor<DescriptorPublicKey> = descriptor
+        .parse()
+        .map_err(|e| format!("Descriptor does not parse: {}", e))?;
+    let (_, script) = crate::state::checkin_scripts(&descriptor, index)
+        .pop()
+        .filter(|(i, _)| *i == index)
+        .ok_or_else(|| format!("Cannot derive index {}", index))?;
+    Address::from_script(&script, network)
+        .map_err(|e| format!("No address for this descriptor: {}", e))
+}
+
+fn check(name: &str, outcome: CheckOutcome, detail: impl Into<String>) -> SelfTestCheck {
+    SelfTestCheck {
+        name: name.to_string(),
+        outcome,
+        detail: detail.into(),
+    }
+}
+
+fn build_report(inputs: &SelfTestInputs) -> SelfTestReport {
+    use CheckOutcome::*;
+    let mut checks = Vec::new();
+
+    // Descriptor and address
+    match &inputs.descriptor {
+        None => {
+            checks.push(check(
+                "descriptor",
+                Fail,
+                "No inheritance descriptor. Add heirs first.",
+            ));
+            checks.push(check("address", Fail, "No descriptor to derive from"));
+        }
+        Some(desc) => match desc.parse::<Descriptor<DescriptorPublicKey>>() {
+            Err(e) => {
+                checks.push(check(
+                    "descriptor",
+                    Fail,
+                    format!("Descriptor does not parse: {}", e),
+                ));
+                checks.push(check("address", Fail, "No valid descriptor"));
+            }
+            Ok(_) => {
+                checks.push(check("descriptor", Pass, "Descriptor compiles"));
+                checks.push(
+                    match derive_address(desc, inputs.address_index, inputs.network) {
+                        Ok(addr) => check("address", Pass, addr.to_string()),
+                        Err(e) => check("address", Fail, e),
+                    },
+                );
+            }
+        },
+    }
+
+    // Funds
+    checks.push(match &inputs.balance {
+        None => check("funds", Fail, "No inheritance address to check"),
+        Some(Err(e)) => check("funds", Warn, format!("Could not check balance: {}", e)),
+        Some(Ok(0)) => check("funds", Warn, "Inheritance address holds no funds"),
+        Some(Ok(sats)) => check("funds", Pass, format!("{} sat at the address", sats)),
+    });
+
+    // Heir contacts
+    let unreachable: Vec<&str> = inputs
+        .heirs
+        .iter()
+        .filter(|h| h.npub.is_none() && h.email.is_none())
+        .map(|h| h.label.as_str())
+        .collect();
+    checks.push(if inputs.heirs.is_empty() {
+        check("heir_contacts", Fail, "No heirs configured")
+    } else if unreachable.is_empty() {
+        check(
+            "heir_contacts",
+            Pass,
+            format!("All {} heirs have a contact channel", inputs.heirs.len()),
+        )
+    } else {
+        check(
+            "heir_contacts",
+            Fail,
+            format!("No npub or email for: {}", unreachable.join(", ")),
+        )
+    });
+
+    // Locked shares and their relay copies
+    if inputs.locked_shares == 0 {
+        checks.push(check(
+            "locked_shares",
+            Warn,
+            "No locked shares — nsec inheritance is not set up",
+        ));
+    } else {
+        checks.push(check(
+            "locked_shares",
+            Pass,
+            format!("{} locked shares stored", inputs.locked_shares),
+        ));
+        checks.push(match &inputs.relay_shares {
+            _ if inputs.shares_giftwrapped => check(
+                "relay_shares",
+                Warn,
+                "Shares are gift-wrapped; only heirs can confirm they are on relays",
+            ),
+            None => check(
+                "relay_shares",
+                Warn,
+                "Shares were never published to relays",
+            ),
+            Some(Err(e)) => check("relay_shares", Fail, format!("Relay lookup failed: {}", e)),
+            Some(Ok(found)) => {
+                let short: Vec<String> = found
+                    .iter()
+                    .filter(|(_, n)| *n < inputs.locked_shares)
+                    .map(|(label, n)| format!("{} ({}/{})", label, n, inputs.locked_shares))
+                    .collect();
+                if found.is_empty() {
+                    check(
+                        "relay_shares",
+                        Warn,
+                        "No heir has an npub to receive shares",
+                    )
+                } else if short.is_empty() {
+                    check(
+                        "relay_shares",
+                        Pass,
+                        "Every heir's shares are retrievable from relays",
+                    )
+                } else {
+                    check(
+                        "relay_shares",
+                        Fail,
+                        format!("Missing shares on relays for: {}", short.join(", ")),
+                    )
+                }
+            }
+        });
+    }
+
+    // Service key reachability
+    checks.push(match inputs.reachable_relays {
+        None => check("service_key", Fail, "No service key generated"),
+        Some(0) => check("service_key", Fail, "No relay reachable"),
+        Some(n) => check("service_key", Pass, format!("{} relays reachable", n)),
+    });
+
+    let ready = checks.iter().all(|c| c.outcome != Fail);
+    SelfTestReport { checks, ready }
+}
+
+/// Dry-run every piece inheritance depends on and report per-check results.
+#[tauri::command]
+pub async fn inheritance_self_test(
+    state: State<'_, AppState>,
+) -> Result<CommandResult<SelfTestReport>, ()> {
+    let network = *state.network.lock().unwrap();
+    let descriptor = state.ensure_inheritance_config().map(|c| c.descriptor);
+    let has_service_key = state.service_key.lock().unwrap().is_some();
+    let service_npub = state.service_npub.lock().unwrap().clone();
+
+    let (heirs, locked_shares, split_id) = {
+        let conn = state.db.lock().unwrap();
+        let heirs: Vec<HeirContact> = crate::db::heir_list(&conn)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|h| HeirContact {
+                label: h.label,
+                npub: h.npub.filter(|n| !n.is_empty()),
+                email: h.email.filter(|e| !e.is_empty()),
+            })
+            .collect();
+        let locked_shares = crate::db::config_get(&conn, "nsec_locked_shares")
+            .ok()
+            .flatten()
+            .and_then(|j| serde_json::from_str::<Vec<String>>(&j).ok())
+            .map_or(0, |s| s.len());
+        let split_id = crate::db::config_get(&conn, "last_relay_split_id")
+            .ok()
+            .flatten();
+        (heirs, locked_shares, split_id)
+    };
+
+    // Funds a rotating check-in left on earlier addresses count too
+    let address_index = state.checkin_address_index();
+    let balance = descriptor
+        .as_deref()
+        .and_then(|d| d.parse::<Descriptor<DescriptorPublicKey>>().ok())
+        .map(|d| crate::state::checkin_scripts(&d, address_index))
+        .filter(|scripts| !scripts.is_empty())
+        .map(|scripts| -> Result<u64, String> {
+            let client = state.electrum_client().map_err(|e| e.to_string())?;
+            let mut total = 0;
+            for (_, script) in scripts {
+                let utxos = client
+                    .get_utxos_for_script(&script)
+                    .map_err(|e| e.to_string())?;
+                total += utxos.iter().map(|u| u.value.to_sat()).sum::<u64>();
+            }
+            Ok(total)
+        });
+
+    let reachable_relays = if has_service_key {
+        let relays: Vec<String> = nostr_relay::DEFAULT_RELAYS
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let health = nostr_relay::probe_relays(&relays).await;
+        Some(health.iter().filter(|h| h.reachable).count())
+    } else {
+        None
+    };
+
+    let shares_giftwrapped =
+        split_id.is_some() && state.relay_privacy_mode() == nostr_relay::PrivacyMode::GiftWrap;
+    let relay_shares = match (split_id, service_npub) {
+        (Some(split_id), Some(npub)) if locked_shares > 0 && !shares_giftwrapped => Some(
+            nostr_relay::count_published_shares(&npub, &split_id, None)
+                .await
+                .map(|counts| {
+                    heirs
+                        .iter()
+                        .filter_map(|h| {
+                            let pk = nostr_sdk::prelude::PublicKey::parse(h.npub.as_ref()?).ok()?;
+                            Some((h.label.clone(), counts.get(&pk).copied().unwrap_or(0)))
+                        })
+                        .collect()
+                })
+                .map_err(|e| e.to_string()),
+        ),
+        _ => None,
+    };
+
+    let report = build_report(&SelfTestInputs {
+        descriptor,
+        network,
+        address_index,
+        balance,
+        heirs,
+        locked_shares,
+        relay_shares,
+        shares_giftwrapped,
+        reachable_relays,
+    });
+    Ok(CommandResult::ok(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_xpub;
+    use bitcoin::NetworkKind;
+
+    fn heir(label: &str, npub: Option<&str>, email: Option<&str>) -> HeirContact {
+        HeirContact {
+            label: label.into(),
+            npub: npub.map(Into::into),
+            email: email.map(Into::into),
+        }
+    }
+
+    fn configured() -> SelfTestInputs {
+        let descriptor = format!(
+            "wsh(or_d(pk({}/0/*),and_v(v:pk({}/0/*),older(26280))))",
+            test_xpub(NetworkKind::Test, 1),
+            test_xpub(NetworkKind::Test, 2)
+        );
+        SelfTestInputs {
+            descriptor: Some(descriptor),
+            network: Network::Testnet,
+            address_index: 0,
+            balance: Some(Ok(150_000)),
+            heirs: vec![
+                heir("Alice", Some("npub1alice"), None),
+                heir("Bob", None, Some("bob@example.com")),
+            ],
+            locked_shares: 2,
+            relay_shares: Some(Ok(vec![("Alice".into(), 2)])),
+            shares_giftwrapped: false,
+            reachable_relays: Some(3),
+        }
+    }
+
+    fn outcome(report: &SelfTestReport, name: &str) -> CheckOutcome {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| panic!("no check {}", name))
+            .outcome
+    }
+
+    #[test]
+    fn test_fully_configured_passes() {
+        let report = build_report(&configured());
+        assert!(report.ready);
+        assert!(
+            report
+                .checks
+                .iter()
+                .all(|c| c.outcome == CheckOutcome::Pass),
+            "{:?}",
+            report.checks
+        );
+        let address = report.checks.iter().find(|c| c.name == "address").unwrap();
+        assert!(address.detail.starts_with("tb1q"));
+
+        // After a rotating check-in the current address is reported
+        let rotated = build_report(&SelfTestInputs {
+            address_index: 1,
+            ..configured()
+        });
+        let rotated = rotated.checks.iter().find(|c| c.name == "address").unwrap();
+        assert!(rotated.detail.starts_with("tb1q"));
+        assert_ne!(rotated.detail, address.detail);
+    }
+
+    #[test]
+    fn test_missing_descriptor_fails() {
+        let mut inputs = configured();
+        inputs.descriptor = None;
+        inputs.balance = None;
+        let report = build_report(&inputs);
+        assert!(!report.ready);
+        assert_eq!(outcome(&report, "descriptor"), CheckOutcome::Fail);
+        assert_eq!(outcome(&report, "address"), CheckOutcome::Fail);
+        assert_eq!(outcome(&report, "funds"), CheckOutcome::Fail);
+
+        inputs.descriptor = Some("wsh(garbage)".into());
+        assert_eq!(
+            outcome(&build_report(&inputs), "descriptor"),
+            CheckOutcome::Fail
+        );
+    }
+
+    #[test]
+    fn test_partial_failures() {
+        // Unfunded address and no locked shares only warn
+        let mut inputs = configured();
+        inputs.balance = Some(Ok(0));
+        inputs.locked_shares = 0;
+        let report = build_report(&inputs);
+        assert!(report.ready);
+        assert_eq!(outcome(&report, "funds"), CheckOutcome::Warn);
+        assert_eq!(outcome(&report, "locked_shares"), CheckOutcome::Warn);
+        assert!(report.checks.iter().all(|c| c.name != "relay_shares"));
+
+        // An heir nobody can reach fails
+        let mut inputs = configured();
+        inputs.heirs.push(heir("Carol", None, None));
+        let report = build_report(&inputs);
+        assert!(!report.ready);
+        let contacts = report
+            .checks
+            .iter()
+            .find(|c| c.name == "heir_contacts")
+            .unwrap();
+        assert_eq!(contacts.outcome, CheckOutcome::Fail);
+        assert!(contacts.detail.contains("Carol"));
+
+        // Shares missing on relays fail; never published only warns
+        let mut inputs = configured();
+        inputs.relay_shares = Some(Ok(vec![("Alice".into(), 1)]));
+        assert_eq!(
+            outcome(&build_report(&inputs), "relay_shares"),
+            CheckOutcome::Fail
+        );
+        inputs.relay_shares = None;
+        assert_eq!(
+            outcome(&build_report(&inputs), "relay_shares"),
+            CheckOutcome::Warn
+        );
+
+        // No service key, or no relay reachable
+        let mut inputs = configured();
+        inputs.reachable_relays = None;
+        assert_eq!(
+            outcome(&build_report(&inputs), "service_key"),
+            CheckOutcome::Fail
+        );
+        inputs.reachable_relays = Some(0);
+        assert!(!build_report(&inputs).ready);
+
+        // Electrum down: can't confirm funds, but not a failure
+        let mut inputs = configured();
+        inputs.balance = Some(Err("connection refused".into()));
+        let report = build_report(&inputs);
+        assert!(report.ready);
+        assert_eq!(outcome(&report, "funds"), CheckOutcome::Warn);
+    }
+}
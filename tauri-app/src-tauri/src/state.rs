@@ -3,19 +3,31 @@
 //! In-memory cache backed by SQLite persistence.
 //! All mutations write through to the database.
 
+use crate::audit::{AuditAction, AuditKey};
+use crate::cancel::OperationRegistry;
 use crate::db::{self, HeirRow};
+use crate::fees::{FeeStrategy, FEE_STRATEGY_KEY};
+use crate::units::{DisplayUnit, DISPLAY_UNIT_KEY};
 use bitcoin::bip32::{DerivationPath, Xpub};
-use bitcoin::Network;
+use bitcoin::{Network, ScriptBuf, Transaction};
 use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::Descriptor;
 use nostring_ccd::types::DelegatedKey;
+use nostring_core::clock::{Clock, SystemClock};
+use nostring_core::keys::{bip84_path, CoinType};
+use nostring_electrum::{CustomSignet, ElectrumClient, ServerPool};
 use nostring_inherit::heir::{HeirKey, HeirRegistry};
-use nostring_inherit::policy::{PathInfo, Timelock};
+use nostring_inherit::policy::{
+    check_standardness, InheritancePolicy, PathInfo, PolicyError, Timelock,
+};
 use nostring_inherit::taproot::{create_inheritable_vault, InheritableVault};
+use nostring_notify::nostr_relay::PrivacyMode;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroizing;
 
 /// Policy status for display
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +38,66 @@ pub struct PolicyStatus {
     pub days_remaining: f64,
     pub urgency: String,
     pub last_checkin: Option<u64>,
+    /// Confirmed + unconfirmed balance at the inheritance address
+    #[serde(default)]
+    pub balance_sats: Option<u64>,
+    /// `balance_sats` in the user's display unit
+    #[serde(default)]
+    pub balance_display: Option<String>,
+    /// Chain data, or just the balance, is the cached last-known state
+    /// (Electrum was unreachable or the balance lookup failed)
+    #[serde(default)]
+    pub stale: bool,
+    /// Unix timestamp of the chain fetch this status is based on
+    #[serde(default)]
+    pub last_updated: Option<u64>,
+}
+
+impl PolicyStatus {
+    /// Compute the status from a chain snapshot. `timelock_blocks` is `None`
+    /// when no inheritance policy is configured yet.
+    pub fn from_snapshot(
+        snapshot: &db::ChainSnapshot,
+        timelock_blocks: Option<u16>,
+        last_checkin: Option<u64>,
+        unit: DisplayUnit,
+        stale: bool,
+    ) -> Self {
+        let current_block = snapshot.block_height;
+        let (expiry_block, blocks_remaining, days_remaining, urgency) = match timelock_blocks {
+            Some(timelock) => {
+                let expiry = current_block + timelock as u64;
+                let remaining = expiry.saturating_sub(current_block) as i64;
+                let days = remaining as f64 * 10.0 / 60.0 / 24.0;
+
+                let urgency = if remaining > 4320 {
+                    "ok"
+                } else if remaining > 1008 {
+                    "warning"
+                } else {
+                    "critical"
+                };
+
+                (expiry, remaining, days, urgency.to_string())
+            }
+            None => (current_block + 26280, 26280, 182.5, "ok".to_string()),
+        };
+
+        Self {
+            current_block,
+            expiry_block,
+            blocks_remaining,
+            days_remaining,
+            urgency,
+            last_checkin,
+            balance_sats: snapshot.balance_sats,
+            balance_display: snapshot
+                .balance_sats
+                .map(|sats| crate::units::format_amount(sats, unit)),
+            stale,
+            last_updated: Some(snapshot.fetched_at),
+        }
+    }
 }
 
 /// Inheritance configuration
@@ -36,6 +108,17 @@ pub struct InheritanceConfig {
     pub network: String,
 }
 
+/// A service key replaced by a rotation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetiredServiceKey {
+    pub npub: String,
+    /// The key that replaced it
+    pub replaced_by: String,
+    pub retired_at: u64,
+    /// Migration event signed by this key announcing `replaced_by`
+    pub migration_event_id: String,
+}
+
 /// CCD (Chain Code Delegation) state.
 ///
 /// Encapsulates all collaborative custody state: co-signer registration,
@@ -208,8 +291,8 @@ impl CcdState {
             )
         })?;
 
-        let owner_xpub =
-            Xpub::from_str(owner_xpub_str).map_err(|e| format!("Owner xpub is invalid: {}", e))?;
+        let owner_xpub = owner_account_xpub(owner_xpub_str)
+            .map_err(|e| format!("Owner xpub is invalid: {}", e))?;
         let owner_pubkey = owner_xpub.public_key;
 
         let heirs = registry.list();
@@ -273,6 +356,123 @@ impl CcdState {
     }
 }
 
+/// Config key marking the stored inheritance descriptor as derived from
+/// `owner_xpub` + heirs rather than set explicitly (so it goes stale when
+/// the heirs change)
+const DERIVED_DESCRIPTOR_KEY: &str = "inheritance_descriptor_derived";
+
+/// Build the inheritance descriptor for a watch-only wallet.
+///
+/// The owner key can spend at any time; the heirs (all of them, matching
+/// the vault policy) can spend after `timelock_blocks`. Returns the
+/// descriptor string and its index-0 address.
+pub fn derive_inheritance_descriptor(
+    owner_xpub: &str,
+    heirs: &[HeirKey],
+    timelock_blocks: u16,
+    network: Network,
+) -> Result<(String, bitcoin::Address), String> {
+    let owner_str = if owner_xpub.contains('*') {
+        owner_xpub.to_string()
+    } else {
+        format!("{}/0/*", owner_xpub)
+    };
+    let owner = DescriptorPublicKey::from_str(&owner_str)
+        .map_err(|e| format!("Owner xpub is invalid: {}", e))?;
+
+    let heir_keys: Vec<DescriptorPublicKey> = heirs
+        .iter()
+        .map(|h| {
+            let path = h.derivation_path.to_string();
+            let path = path.trim_start_matches('m').trim_start_matches('/');
+            let origin = if path.is_empty() {
+                h.fingerprint.to_string()
+            } else {
+                format!("{}/{}", h.fingerprint, path)
+            };
+            DescriptorPublicKey::from_str(&format!("[{}]{}/0/*", origin, h.xpub))
+                .map_err(|e| format!("Heir '{}' key is invalid: {}", h.label, e))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let heir_path = match heir_keys.len() {
+        0 => return Err("No heirs configured".into()),
+        1 => PathInfo::Single(heir_keys.into_iter().next().unwrap()),
+        n => PathInfo::multi(n, heir_keys).map_err(|e| e.to_string())?,
+    };
+    let timelock = Timelock::from_blocks(timelock_blocks).map_err(|e| e.to_string())?;
+
+    let policy = InheritancePolicy::cascade(owner, vec![(timelock, heir_path)])
+        .map_err(|e| e.to_string())?;
+    let descriptor = policy.to_wsh_descriptor().map_err(|e| e.to_string())?;
+    let address = descriptor
+        .at_derivation_index(0)
+        .map_err(|e| e.to_string())?
+        .address(network)
+        .map_err(|e| e.to_string())?;
+
+    Ok((descriptor.to_string(), address))
+}
+
+/// Account xpub of the stored owner key, bare (`xpub…`) or with the origin
+/// a hardware wallet import stores (`[fingerprint/path]xpub…`).
+pub(crate) fn owner_account_xpub(key: &str) -> Result<Xpub, String> {
+    match DescriptorPublicKey::from_str(key.trim()).map_err(|e| e.to_string())? {
+        DescriptorPublicKey::XPub(xkey) => Ok(xkey.xkey),
+        _ => Err("not an extended public key".into()),
+    }
+}
+
+/// Receive scripts of `descriptor` at indexes `0..=through`: every address
+/// a rotating check-in may have left funds on. Multipath descriptors use
+/// their receive branch.
+pub(crate) fn checkin_scripts(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    through: u32,
+) -> Vec<(u32, ScriptBuf)> {
+    let Some(receive) = descriptor
+        .clone()
+        .into_single_descriptors()
+        .ok()
+        .and_then(|paths| paths.into_iter().next())
+    else {
+        return Vec::new();
+    };
+    (0..=through)
+        .filter_map(|index| {
+            let script = receive.at_derivation_index(index).ok()?.script_pubkey();
+            Some((index, script))
+        })
+        .collect()
+}
+
+/// Index the funds moved to if `tx` pays the descriptor's receive address
+/// after `current` (a rotating check-in), otherwise `None`.
+fn rotated_checkin_index(descriptor: &str, current: u32, tx: &Transaction) -> Option<u32> {
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor).ok()?;
+    let next = current.checked_add(1)?;
+    let (_, script) = checkin_scripts(&descriptor, next).pop()?;
+    tx.output
+        .iter()
+        .any(|out| out.script_pubkey == script)
+        .then_some(next)
+}
+
+/// Relay-policy warnings for an inheritance descriptor, empty when every
+/// spending path is standard (see [`check_standardness`]).
+pub fn standardness_warnings(descriptor: &str) -> Vec<String> {
+    let checked = Descriptor::<DescriptorPublicKey>::from_str(descriptor)
+        .map_err(PolicyError::from)
+        .and_then(|d| check_standardness(&d));
+    match checked {
+        Ok(report) => report.warnings,
+        Err(e) => vec![format!(
+            "Cannot check the descriptor against relay limits: {}",
+            e
+        )],
+    }
+}
+
 /// Application state (thread-safe, SQLite-backed)
 pub struct AppState {
     // --- Persistent (backed by SQLite) ---
@@ -295,6 +495,9 @@ pub struct AppState {
     pub service_npub: Mutex<Option<String>>,
     /// Electrum server URL
     pub electrum_url: Mutex<String>,
+    /// Servers connections rotate through: every default server for the
+    /// network, or just `electrum_url` when the user picked their own
+    pub electrum_pool: Mutex<Arc<ServerPool>>,
     /// Bitcoin network
     pub network: Mutex<Network>,
 
@@ -306,12 +509,21 @@ pub struct AppState {
     pub unlocked: Mutex<bool>,
     /// Cached policy status (recomputed from blockchain)
     pub policy_status: Mutex<Option<PolicyStatus>>,
+    /// Time source for delivery/check-in logs and cooldowns
+    pub clock: Arc<dyn Clock>,
+    /// Cancellation tokens of running network commands
+    pub operations: OperationRegistry,
+    /// Relay lists discovered for service npubs during share fetches
+    pub relay_discovery: nostring_notify::nostr_relay::RelayDiscoveryCache,
+    /// Key of the audit log's hash chain, derived from the app secret
+    pub audit_key: AuditKey,
 }
 
 impl AppState {
     /// Create state from a database path, loading any persisted data.
     pub fn from_db_path(db_path: PathBuf) -> Self {
         let conn = db::open_db(&db_path).expect("Failed to open database");
+        let audit_key = AuditKey::load_or_create(&db_path).expect("Failed to load app secret");
 
         // Load persisted values
         let owner_xpub = db::config_get(&conn, "owner_xpub").ok().flatten();
@@ -334,10 +546,17 @@ impl AppState {
             "regtest" => Network::Regtest,
             _ => Network::Bitcoin,
         };
+        let coin_type = coin_type_from_config(
+            db::config_get(&conn, COIN_TYPE_KEY)
+                .ok()
+                .flatten()
+                .as_deref(),
+        );
         let electrum_url = db::config_get(&conn, "electrum_url")
             .ok()
             .flatten()
             .unwrap_or_else(|| nostring_electrum::default_server(network).to_string());
+        let electrum_pool = electrum_pool_for(&electrum_url, network);
         let service_key = db::config_get(&conn, "service_key").ok().flatten();
         let service_npub = db::config_get(&conn, "service_npub").ok().flatten();
 
@@ -369,23 +588,47 @@ impl AppState {
                 if let Ok(xpub) = Xpub::from_str(&row.xpub) {
                     let fp = xpub.fingerprint();
                     let derivation_path = DerivationPath::from_str(&row.derivation_path)
-                        .unwrap_or_else(|_| DerivationPath::from_str("m/84'/0'/0'").unwrap());
+                        .unwrap_or_else(|_| default_account_path(network, coin_type));
                     let heir = HeirKey::new(&row.label, fp, xpub, Some(derivation_path));
                     registry.add(heir);
                 }
             }
         }
 
-        // Load last check-in time
+        // Last-known status until the first refresh: the cached chain
+        // snapshot if there is one, else just the last check-in time
         let last_checkin = db::checkin_last(&conn).ok().flatten();
-        let policy_status = last_checkin.map(|ts| PolicyStatus {
-            current_block: 0,
-            expiry_block: 0,
-            blocks_remaining: 0,
-            days_remaining: 0.0,
-            urgency: "unknown".to_string(),
-            last_checkin: Some(ts),
-        });
+        let cache_descriptor = inheritance_config
+            .as_ref()
+            .map_or("", |c| c.descriptor.as_str());
+        let policy_status = match db::chain_cache_get(&conn, cache_descriptor).ok().flatten() {
+            Some(snapshot) => {
+                let unit = db::config_get(&conn, DISPLAY_UNIT_KEY)
+                    .ok()
+                    .flatten()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default();
+                Some(PolicyStatus::from_snapshot(
+                    &snapshot,
+                    inheritance_config.as_ref().map(|c| c.timelock_blocks),
+                    last_checkin,
+                    unit,
+                    true,
+                ))
+            }
+            None => last_checkin.map(|ts| PolicyStatus {
+                current_block: 0,
+                expiry_block: 0,
+                blocks_remaining: 0,
+                days_remaining: 0.0,
+                urgency: "unknown".to_string(),
+                last_checkin: Some(ts),
+                balance_sats: None,
+                balance_display: None,
+                stale: true,
+                last_updated: None,
+            }),
+        };
 
         // Load CCD state (cosigner + vault reconstruction)
         let ccd = CcdState::from_db(&conn, owner_xpub.as_deref(), &registry, network);
@@ -403,12 +646,23 @@ impl AppState {
             service_key: Mutex::new(service_key),
             service_npub: Mutex::new(service_npub),
             electrum_url: Mutex::new(electrum_url),
+            electrum_pool: Mutex::new(Arc::new(electrum_pool)),
             network: Mutex::new(network),
             ccd: Mutex::new(ccd),
             unlocked: Mutex::new(unlocked),
             policy_status: Mutex::new(policy_status),
+            clock: Arc::new(SystemClock),
+            operations: OperationRegistry::default(),
+            relay_discovery: Default::default(),
+            audit_key,
         }
     }
+
+    /// Use `clock` instead of the system clock (for tests).
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
 }
 
 // ============================================================================
@@ -439,8 +693,11 @@ impl AppState {
             npub: None,
             email: None,
             timelock_months,
+            channels: None,
         };
         let _ = db::heir_upsert(&conn, &row);
+        drop(conn);
+        self.invalidate_derived_inheritance_config();
     }
 
     /// Update heir contact info (npub/email) in the database.
@@ -454,67 +711,106 @@ impl AppState {
         db::heir_update_contact(&conn, fingerprint, npub, email).unwrap_or(false)
     }
 
-    /// Log a descriptor delivery attempt.
+    /// Log a descriptor delivery attempt, recording the content hash so
+    /// unchanged backups aren't re-sent.
     pub fn log_delivery(
         &self,
         heir_fingerprint: &str,
         channel: &str,
         success: bool,
         error_msg: Option<&str>,
+        content_hash: Option<&str>,
+    ) {
+        self.log_delivery_attempt(
+            heir_fingerprint,
+            channel,
+            success,
+            error_msg,
+            content_hash,
+            1,
+            None,
+        );
+    }
+
+    /// Log a numbered delivery attempt; failures with `next_retry_at` set
+    /// are picked up by the retry queue once that time passes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn log_delivery_attempt(
+        &self,
+        heir_fingerprint: &str,
+        channel: &str,
+        success: bool,
+        error_msg: Option<&str>,
+        content_hash: Option<&str>,
+        attempt: u32,
+        next_retry_at: Option<u64>,
     ) {
         let conn = self.db.lock().unwrap();
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        let _ = db::delivery_log_insert(
+        let timestamp = self.clock.now_secs();
+        let _ = db::delivery_log_insert_attempt(
             &conn,
             heir_fingerprint,
             channel,
             timestamp,
             success,
             error_msg,
+            content_hash,
+            attempt,
+            next_retry_at,
         );
     }
 
-    /// Check if we already delivered to this heir on this channel recently
-    /// (within the cooldown period). Returns true if delivery is allowed.
-    pub fn can_deliver_to_heir(
+    /// Last successful delivery to this heir on this channel, as
+    /// `(timestamp, content_hash)`.
+    pub fn last_heir_delivery(
         &self,
         heir_fingerprint: &str,
         channel: &str,
-        cooldown_secs: u64,
-    ) -> bool {
+    ) -> Option<(u64, Option<String>)> {
         let conn = self.db.lock().unwrap();
-        let last = db::delivery_last_success(&conn, heir_fingerprint, channel)
+        db::delivery_last_success_entry(&conn, heir_fingerprint, channel)
             .ok()
-            .flatten();
-        match last {
-            None => true, // Never delivered
-            Some(ts) => {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                now.saturating_sub(ts) >= cooldown_secs
-            }
-        }
+            .flatten()
     }
 
     /// Remove an heir from the database.
     pub fn remove_heir_db(&self, fingerprint: &str) {
         let conn = self.db.lock().unwrap();
         let _ = db::heir_remove(&conn, fingerprint);
+        drop(conn);
+        self.invalidate_derived_inheritance_config();
     }
 
-    /// Log a successful check-in.
-    pub fn log_checkin(&self, txid: &str) {
+    /// Append a sensitive action to the hash-chained audit log.
+    ///
+    /// `detail` must never contain secret values.
+    pub fn audit(&self, action: AuditAction, detail: &str) {
         let conn = self.db.lock().unwrap();
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        let _ = db::checkin_log_insert(&conn, timestamp, txid);
+        let timestamp = self.clock.now_secs();
+        if let Err(e) = crate::audit::append(&conn, &self.audit_key, timestamp, action, detail) {
+            log::warn!("Failed to write audit log ({}): {}", action.as_str(), e);
+        }
+    }
+
+    /// Log a successful check-in.
+    ///
+    /// A rotating check-in (one paying the next receive address) also
+    /// advances the check-in index, whichever path broadcast it.
+    pub fn log_checkin(&self, tx: &Transaction) {
+        {
+            let conn = self.db.lock().unwrap();
+            let timestamp = self.clock.now_secs();
+            let _ = db::checkin_log_insert(&conn, timestamp, &tx.compute_txid().to_string());
+        }
+
+        let current = self.checkin_address_index();
+        if let Some(next) = self
+            .ensure_inheritance_config()
+            .and_then(|c| rotated_checkin_index(&c.descriptor, current, tx))
+        {
+            log::info!("Check-in rotated funds to receive index {}", next);
+            self.set_checkin_address_index(next);
+        }
     }
 
     /// Set owner xpub and persist.
@@ -545,6 +841,24 @@ impl AppState {
         self.persist_config("encrypted_seed", &hex_str);
     }
 
+    /// Store the wallet's BIP-39 entropy (encrypted like the seed), or
+    /// forget it for seeds imported without their words.
+    pub fn set_encrypted_entropy(&self, bytes: Option<&[u8]>) {
+        match bytes {
+            Some(bytes) => self.persist_config(ENCRYPTED_ENTROPY_KEY, &hex::encode(bytes)),
+            None => self.delete_config(ENCRYPTED_ENTROPY_KEY),
+        }
+    }
+
+    /// The encrypted BIP-39 entropy, if the seed was imported from words.
+    pub fn encrypted_entropy(&self) -> Option<Vec<u8>> {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, ENCRYPTED_ENTROPY_KEY)
+            .ok()
+            .flatten()
+            .and_then(|hex_str| hex::decode(hex_str).ok())
+    }
+
     /// Set Bitcoin network and persist.
     pub fn set_network(&self, network: Network) {
         let network_str = match network {
@@ -563,13 +877,200 @@ impl AppState {
 
     /// Set electrum URL and persist.
     pub fn set_electrum_url(&self, url: &str) {
+        let network = *self.network.lock().unwrap();
         {
             let mut lock = self.electrum_url.lock().unwrap();
             *lock = url.to_string();
         }
+        *self.electrum_pool.lock().unwrap() = Arc::new(electrum_pool_for(url, network));
         self.persist_config("electrum_url", url);
     }
 
+    /// Connect to Electrum, rotating through the configured servers.
+    pub fn electrum_client(&self) -> Result<ElectrumClient, nostring_electrum::Error> {
+        let network = *self.network.lock().unwrap();
+        let genesis = self.expected_genesis();
+        let pool = Arc::clone(&self.electrum_pool.lock().unwrap());
+        pool.connect_expecting(network, genesis)
+    }
+
+    /// Like [`electrum_client`](Self::electrum_client), but connects when
+    /// called, without borrowing the state (e.g. on the blocking pool).
+    pub fn electrum_connector(
+        &self,
+    ) -> impl FnOnce() -> Result<ElectrumClient, nostring_electrum::Error> + Send + 'static {
+        let network = *self.network.lock().unwrap();
+        let genesis = self.expected_genesis();
+        let pool = Arc::clone(&self.electrum_pool.lock().unwrap());
+        move || pool.connect_expecting(network, genesis)
+    }
+
+    /// The configured custom signet, if any (only meaningful on signet).
+    pub fn custom_signet(&self) -> Option<CustomSignet> {
+        let conn = self.db.lock().unwrap();
+        let challenge = db::config_get(&conn, SIGNET_CHALLENGE_KEY).ok().flatten()?;
+        let genesis = db::config_get(&conn, SIGNET_GENESIS_KEY).ok().flatten();
+        CustomSignet::from_hex(&challenge, genesis.as_deref())
+            .map_err(|e| log::warn!("Ignoring stored custom signet: {}", e))
+            .ok()
+    }
+
+    /// Genesis hash Electrum servers must report, when it isn't the
+    /// network's standard one (a custom signet).
+    pub fn expected_genesis(&self) -> Option<bitcoin::BlockHash> {
+        if *self.network.lock().unwrap() != Network::Signet {
+            return None;
+        }
+        self.custom_signet().map(|signet| signet.genesis_hash())
+    }
+
+    /// Switch to a custom signet and persist its parameters.
+    pub fn set_custom_signet(&self, signet: &CustomSignet) {
+        self.persist_config(SIGNET_CHALLENGE_KEY, &signet.challenge.to_hex_string());
+        match signet.genesis {
+            Some(genesis) => self.persist_config(SIGNET_GENESIS_KEY, &genesis.to_string()),
+            None => self.delete_config(SIGNET_GENESIS_KEY),
+        }
+        self.set_network(Network::Signet);
+    }
+
+    /// Forget any custom signet (back to the default signet parameters).
+    pub fn clear_custom_signet(&self) {
+        self.delete_config(SIGNET_CHALLENGE_KEY);
+        self.delete_config(SIGNET_GENESIS_KEY);
+    }
+
+    /// HTTP endpoint transactions are also broadcast to, if any.
+    ///
+    /// Off unless the user sets one; an empty value disables it too.
+    pub fn http_broadcast_url(&self) -> Option<String> {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, HTTP_BROADCAST_URL_KEY)
+            .ok()
+            .flatten()
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Preferred unit for displaying amounts (defaults to sats).
+    pub fn display_unit(&self) -> DisplayUnit {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, DISPLAY_UNIT_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Set the display unit and persist.
+    pub fn set_display_unit(&self, unit: DisplayUnit) {
+        self.persist_config(DISPLAY_UNIT_KEY, unit.as_str());
+    }
+
+    /// Coin type for test-network keys (standard when never set).
+    pub fn coin_type(&self) -> CoinType {
+        let conn = self.db.lock().unwrap();
+        coin_type_from_config(
+            db::config_get(&conn, COIN_TYPE_KEY)
+                .ok()
+                .flatten()
+                .as_deref(),
+        )
+    }
+
+    /// Set the coin type and persist.
+    pub fn set_coin_type(&self, coin_type: CoinType) {
+        self.persist_config(COIN_TYPE_KEY, coin_type_name(coin_type));
+    }
+
+    /// BIP-84 account path for keys that don't carry their own, on the
+    /// current network and coin type.
+    pub fn default_account_path(&self) -> DerivationPath {
+        default_account_path(*self.network.lock().unwrap(), self.coin_type())
+    }
+
+    /// Default check-in fee strategy (normal when never set).
+    pub fn fee_strategy(&self) -> FeeStrategy {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, FEE_STRATEGY_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Set the default check-in fee strategy and persist.
+    pub fn set_fee_strategy(&self, strategy: FeeStrategy) {
+        self.persist_config(FEE_STRATEGY_KEY, &strategy.to_string());
+    }
+
+    /// How the last relay split was published (standard when never set).
+    pub fn relay_privacy_mode(&self) -> PrivacyMode {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, RELAY_PRIVACY_MODE_KEY)
+            .ok()
+            .flatten()
+            .map(|v| match v.as_str() {
+                "giftwrap" => PrivacyMode::GiftWrap,
+                _ => PrivacyMode::Standard,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Record how the last relay split was published.
+    pub fn set_relay_privacy_mode(&self, mode: PrivacyMode) {
+        let value = match mode {
+            PrivacyMode::Standard => "standard",
+            PrivacyMode::GiftWrap => "giftwrap",
+        };
+        self.persist_config(RELAY_PRIVACY_MODE_KEY, value);
+    }
+
+    /// Whether check-ins rotate to the descriptor's next receive address.
+    pub fn checkin_rotation(&self) -> bool {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, CHECKIN_ROTATION_KEY)
+            .ok()
+            .flatten()
+            .is_some_and(|v| v == "true")
+    }
+
+    /// Turn check-in address rotation on or off and persist.
+    pub fn set_checkin_rotation(&self, enabled: bool) {
+        self.persist_config(CHECKIN_ROTATION_KEY, if enabled { "true" } else { "false" });
+    }
+
+    /// Receive index of the inheritance address the funds sit on (0 until
+    /// a rotating check-in moves them).
+    pub fn checkin_address_index(&self) -> u32 {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, CHECKIN_INDEX_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Record that the funds moved to receive index `index`.
+    pub fn set_checkin_address_index(&self, index: u32) {
+        self.persist_config(CHECKIN_INDEX_KEY, &index.to_string());
+    }
+
+    /// Confidence an heir claim needs to raise the alert.
+    pub fn heir_claim_min_confidence(&self) -> f64 {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, HEIR_CLAIM_MIN_CONFIDENCE_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(db::DEFAULT_HEIR_CLAIM_MIN_CONFIDENCE)
+    }
+
+    /// Copy of the service key secret (hex), zeroized when dropped.
+    pub fn service_secret(&self) -> Option<Zeroizing<String>> {
+        self.service_key.lock().unwrap().clone().map(Zeroizing::new)
+    }
+
     /// Set service key and persist.
     pub fn set_service_key(&self, secret_hex: &str, npub: &str) {
         {
@@ -584,9 +1085,37 @@ impl AppState {
         self.persist_config("service_npub", npub);
     }
 
+    /// Service keys replaced by `rotate_service_key`, oldest first.
+    pub fn retired_service_keys(&self) -> Vec<RetiredServiceKey> {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, RETIRED_SERVICE_KEYS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Record a replaced service key so references to it still resolve.
+    pub fn retire_service_key(&self, retired: RetiredServiceKey) {
+        let mut keys = self.retired_service_keys();
+        keys.push(retired);
+        if let Ok(json) = serde_json::to_string(&keys) {
+            self.persist_config(RETIRED_SERVICE_KEYS_KEY, &json);
+        }
+    }
+
     /// Set inheritance config and persist.
-    #[allow(dead_code)]
     pub fn set_inheritance_config(&self, config: InheritanceConfig) {
+        let changed = self
+            .inheritance_config
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|old| old.descriptor != config.descriptor);
+        if changed {
+            // Addresses of the old descriptor mean nothing for the new one
+            self.delete_config(CHECKIN_INDEX_KEY);
+        }
         self.persist_config("inheritance_descriptor", &config.descriptor);
         self.persist_config("inheritance_timelock", &config.timelock_blocks.to_string());
         self.persist_config("inheritance_network", &config.network);
@@ -595,4 +1124,478 @@ impl AppState {
             *lock = Some(config);
         }
     }
+
+    /// Remember which descriptor the latest backup was generated from.
+    pub fn record_backup_descriptor(&self, descriptor: &str) {
+        self.persist_config(BACKUP_DESCRIPTOR_HASH_KEY, &descriptor_hash(descriptor));
+    }
+
+    /// Whether the descriptor changed since the last backup was generated.
+    ///
+    /// Adding or removing an heir (or changing the timelock) yields a new
+    /// descriptor, and backups already handed to heirs no longer describe
+    /// where the funds are.
+    pub fn backup_is_stale(&self) -> bool {
+        let stored = {
+            let conn = self.db.lock().unwrap();
+            db::config_get(&conn, BACKUP_DESCRIPTOR_HASH_KEY)
+                .ok()
+                .flatten()
+        };
+        let current = self.ensure_inheritance_config().map(|c| c.descriptor);
+        backup_stale(stored.as_deref(), current.as_deref())
+    }
+
+    /// Current inheritance config, deriving one for watch-only wallets.
+    ///
+    /// A watch-only user who imported only an xpub has no stored
+    /// descriptor; once heirs exist it is built from `owner_xpub` + heirs +
+    /// timelock and cached until the heirs or the timelock change.
+    pub fn ensure_inheritance_config(&self) -> Option<InheritanceConfig> {
+        let cached = self.inheritance_config.lock().unwrap().clone();
+        if let Some(config) = cached {
+            if config.timelock_blocks == self.inheritance_timelock_blocks() {
+                return Some(config);
+            }
+            self.invalidate_derived_inheritance_config();
+            if let Some(config) = self.inheritance_config.lock().unwrap().clone() {
+                return Some(config);
+            }
+        }
+
+        let owner_xpub = self.owner_xpub.lock().unwrap().clone()?;
+        let heirs = self.heir_registry.lock().unwrap().list().to_vec();
+        if heirs.is_empty() {
+            return None;
+        }
+        let network = *self.network.lock().unwrap();
+        let timelock_blocks = self.inheritance_timelock_blocks();
+
+        match derive_inheritance_descriptor(&owner_xpub, &heirs, timelock_blocks, network) {
+            Ok((descriptor, address)) => {
+                log::info!("Derived watch-only inheritance address {}", address);
+                for warning in standardness_warnings(&descriptor) {
+                    log::warn!("Inheritance descriptor: {}", warning);
+                }
+                let config = InheritanceConfig {
+                    descriptor,
+                    timelock_blocks,
+                    network: network.to_string(),
+                };
+                self.set_inheritance_config(config.clone());
+                self.persist_config(DERIVED_DESCRIPTOR_KEY, "true");
+                Some(config)
+            }
+            Err(e) => {
+                log::warn!("Cannot derive inheritance descriptor: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Heir timelock a derived descriptor uses (default ~6 months)
+    fn inheritance_timelock_blocks(&self) -> u16 {
+        let conn = self.db.lock().unwrap();
+        db::config_get(&conn, "inheritance_timelock")
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse::<u16>().ok())
+            .unwrap_or(26280)
+    }
+
+    /// Relay-policy warnings for the descriptor the current heirs derive to.
+    ///
+    /// Only watch-only wallets build their descriptor from the heir list, so
+    /// there is nothing to check without an `owner_xpub` or heirs. A policy
+    /// that can't be built at all is reported as a warning too.
+    pub fn derived_policy_warnings(&self) -> Vec<String> {
+        let Some(owner_xpub) = self.owner_xpub.lock().unwrap().clone() else {
+            return Vec::new();
+        };
+        let heirs = self.heir_registry.lock().unwrap().list().to_vec();
+        if heirs.is_empty() {
+            return Vec::new();
+        }
+        let network = *self.network.lock().unwrap();
+
+        match derive_inheritance_descriptor(
+            &owner_xpub,
+            &heirs,
+            self.inheritance_timelock_blocks(),
+            network,
+        ) {
+            Ok((descriptor, _)) => standardness_warnings(&descriptor),
+            Err(e) => vec![format!("Cannot build the inheritance policy: {}", e)],
+        }
+    }
+
+    /// Drop a derived (not user-supplied) descriptor so the next
+    /// [`ensure_inheritance_config`](Self::ensure_inheritance_config)
+    /// rebuilds it from the current heirs and timelock.
+    fn invalidate_derived_inheritance_config(&self) {
+        let derived = {
+            let conn = self.db.lock().unwrap();
+            db::config_get(&conn, DERIVED_DESCRIPTOR_KEY)
+                .ok()
+                .flatten()
+                .is_some()
+        };
+        if derived {
+            self.delete_config("inheritance_descriptor");
+            self.delete_config(DERIVED_DESCRIPTOR_KEY);
+            *self.inheritance_config.lock().unwrap() = None;
+        }
+    }
+}
+
+/// Server pool for `url`: all defaults for `network` (starting at `url`)
+/// if it is one of them, otherwise only the user's own server.
+fn electrum_pool_for(url: &str, network: Network) -> ServerPool {
+    let defaults = nostring_electrum::default_servers(network);
+    match defaults.iter().position(|s| *s == url) {
+        Some(i) => ServerPool::new(
+            defaults[i..]
+                .iter()
+                .chain(&defaults[..i])
+                .map(|s| s.to_string())
+                .collect(),
+        ),
+        None => ServerPool::new(vec![url.to_string()]),
+    }
+}
+
+/// Config key holding the hex challenge script of a custom signet
+const SIGNET_CHALLENGE_KEY: &str = "signet_challenge";
+
+/// Config key holding a custom signet's genesis block hash, if non-default
+const SIGNET_GENESIS_KEY: &str = "signet_genesis";
+
+/// Config key: "legacy_mainnet" when test-network keys were set up under
+/// coin type 0' (the pre-BIP-44 default) rather than 1'
+pub const COIN_TYPE_KEY: &str = "coin_type";
+
+/// How a coin type is stored under [`COIN_TYPE_KEY`]
+pub fn coin_type_name(coin_type: CoinType) -> &'static str {
+    match coin_type {
+        CoinType::Standard => "standard",
+        CoinType::LegacyMainnet => "legacy_mainnet",
+    }
+}
+
+/// Parse a stored [`COIN_TYPE_KEY`] value; anything else is standard.
+fn coin_type_from_config(value: Option<&str>) -> CoinType {
+    match value {
+        Some("legacy_mainnet") => CoinType::LegacyMainnet,
+        _ => CoinType::Standard,
+    }
+}
+
+/// BIP-84 account path assumed for keys that don't carry their own.
+pub fn default_account_path(network: Network, coin_type: CoinType) -> DerivationPath {
+    bip84_path(network, coin_type).expect("BIP-84 paths are valid")
+}
+
+/// Config key holding the HTTP broadcast endpoint (empty = disabled)
+pub const HTTP_BROADCAST_URL_KEY: &str = "http_broadcast_url";
+
+/// Config key holding the heir-claim alert threshold
+pub const HEIR_CLAIM_MIN_CONFIDENCE_KEY: &str = "heir_claim_min_confidence";
+
+/// Config key holding the password-encrypted BIP-39 entropy
+const ENCRYPTED_ENTROPY_KEY: &str = "encrypted_entropy";
+
+/// Config key: "true" when check-ins rotate to the next receive address
+const CHECKIN_ROTATION_KEY: &str = "checkin_rotation";
+
+/// Config key holding the receive index the inheritance funds sit on
+const CHECKIN_INDEX_KEY: &str = "checkin_address_index";
+
+/// Config key holding the JSON list of retired service keys
+const RETIRED_SERVICE_KEYS_KEY: &str = "retired_service_keys";
+
+/// Config key recording whether the last relay split was gift-wrapped
+const RELAY_PRIVACY_MODE_KEY: &str = "relay_privacy_mode";
+
+/// Config key holding the hash of the descriptor in the latest backup
+const BACKUP_DESCRIPTOR_HASH_KEY: &str = "last_backup_descriptor_hash";
+
+/// SHA-256 of a descriptor string, hex-encoded.
+pub fn descriptor_hash(descriptor: &str) -> String {
+    use bitcoin::hashes::{sha256, Hash};
+    sha256::Hash::hash(descriptor.as_bytes()).to_string()
+}
+
+/// A backup is stale when one was made and the descriptor has since changed.
+///
+/// No backup yet, or no descriptor at all, is not reported as stale.
+fn backup_stale(stored_hash: Option<&str>, current_descriptor: Option<&str>) -> bool {
+    match (stored_hash, current_descriptor) {
+        (Some(hash), Some(descriptor)) => hash != descriptor_hash(descriptor),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_xpub;
+    use bitcoin::NetworkKind;
+
+    fn heir(kind: NetworkKind, seed: u8) -> HeirKey {
+        let key = test_xpub(kind, seed);
+        let path = DerivationPath::from_str("m/84'/1'/0'").unwrap();
+        HeirKey::new(format!("Heir {}", seed), key.fingerprint(), key, Some(path))
+    }
+
+    #[test]
+    fn test_derive_watch_only_address() {
+        let owner = test_xpub(NetworkKind::Test, 1).to_string();
+        let heirs = vec![heir(NetworkKind::Test, 2), heir(NetworkKind::Test, 3)];
+
+        let (descriptor, address) =
+            derive_inheritance_descriptor(&owner, &heirs, 26280, Network::Testnet).unwrap();
+
+        assert!(descriptor.starts_with("wsh("));
+        assert!(descriptor.contains("older(26280)"));
+        let address = address.to_string();
+        assert!(!address.is_empty());
+        assert!(
+            address.starts_with("tb1q"),
+            "not a testnet P2WSH: {}",
+            address
+        );
+
+        // Deterministic, so the cached copy stays valid until heirs change
+        let (again, _) =
+            derive_inheritance_descriptor(&owner, &heirs, 26280, Network::Testnet).unwrap();
+        assert_eq!(descriptor, again);
+    }
+
+    #[test]
+    fn test_derived_descriptor_follows_timelock() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        state.set_network(Network::Testnet);
+        *state.owner_xpub.lock().unwrap() = Some(test_xpub(NetworkKind::Test, 1).to_string());
+        state
+            .heir_registry
+            .lock()
+            .unwrap()
+            .add(heir(NetworkKind::Test, 2));
+
+        let config = state.ensure_inheritance_config().unwrap();
+        assert!(config.descriptor.contains("older(26280)"));
+
+        // A new timelock rebuilds the cached descriptor
+        state.persist_config("inheritance_timelock", "4320");
+        let config = state.ensure_inheritance_config().unwrap();
+        assert_eq!(config.timelock_blocks, 4320);
+        assert!(config.descriptor.contains("older(4320)"));
+    }
+
+    #[test]
+    fn test_derive_watch_only_mainnet_single_heir() {
+        let owner = format!("[d34db33f/84'/0'/0']{}", test_xpub(NetworkKind::Main, 4));
+        let heirs = vec![heir(NetworkKind::Main, 5)];
+
+        let (descriptor, address) =
+            derive_inheritance_descriptor(&owner, &heirs, 4320, Network::Bitcoin).unwrap();
+        assert!(address.to_string().starts_with("bc1q"));
+        assert!(standardness_warnings(&descriptor).is_empty());
+        assert!(!standardness_warnings("wsh(garbage)").is_empty());
+
+        assert!(derive_inheritance_descriptor(&owner, &[], 4320, Network::Bitcoin).is_err());
+        assert!(
+            derive_inheritance_descriptor("zpub-garbage", &heirs, 4320, Network::Bitcoin).is_err()
+        );
+    }
+
+    #[test]
+    fn test_owner_account_xpub_accepts_origin() {
+        let key = test_xpub(NetworkKind::Test, 1);
+        let origin = format!("[{}/84'/1'/0']{}", key.fingerprint(), key);
+        assert_eq!(owner_account_xpub(&key.to_string()).unwrap(), key);
+        assert_eq!(owner_account_xpub(&origin).unwrap(), key);
+        assert!(owner_account_xpub("[deadbeef/84'/1'/0']").is_err());
+        assert!(owner_account_xpub(
+            "02e6642fd69bd211f93f7f1f36ca51a26a5290eb2dd1b0d8279a87bb0d480c8443"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_electrum_pool_for() {
+        let defaults = nostring_electrum::default_servers(Network::Bitcoin);
+
+        // A default URL seeds the pool with every default, that one first
+        let pool = electrum_pool_for(defaults[1], Network::Bitcoin);
+        assert_eq!(pool.servers().len(), defaults.len());
+        assert_eq!(pool.servers()[0], defaults[1]);
+
+        // A custom server is used alone
+        let pool = electrum_pool_for("ssl://my-node.local:50002", Network::Bitcoin);
+        assert_eq!(pool.servers(), ["ssl://my-node.local:50002".to_string()]);
+    }
+
+    #[test]
+    fn test_changing_heirs_makes_backup_stale() {
+        let owner = test_xpub(NetworkKind::Test, 1).to_string();
+        let mut heirs = vec![heir(NetworkKind::Test, 2)];
+
+        let (backed_up, _) =
+            derive_inheritance_descriptor(&owner, &heirs, 26280, Network::Testnet).unwrap();
+        let stored = descriptor_hash(&backed_up);
+        assert!(!backup_stale(Some(&stored), Some(&backed_up)));
+
+        heirs.push(heir(NetworkKind::Test, 3));
+        let (current, _) =
+            derive_inheritance_descriptor(&owner, &heirs, 26280, Network::Testnet).unwrap();
+        assert!(backup_stale(Some(&stored), Some(&current)));
+
+        // A fresh backup clears the flag
+        let stored = descriptor_hash(&current);
+        assert!(!backup_stale(Some(&stored), Some(&current)));
+
+        // Never backed up: nothing to be stale
+        assert!(!backup_stale(None, Some(&current)));
+    }
+
+    /// A transaction paying `descriptor`'s receive address at `index`
+    fn paying(descriptor: &str, index: u32) -> Transaction {
+        let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor).unwrap();
+        let (_, script) = checkin_scripts(&descriptor, index).pop().unwrap();
+        Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(50_000),
+                script_pubkey: script,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_rotated_checkin_index() {
+        let owner = test_xpub(NetworkKind::Test, 1).to_string();
+        let (descriptor, _) = derive_inheritance_descriptor(
+            &owner,
+            &[heir(NetworkKind::Test, 2)],
+            144,
+            Network::Signet,
+        )
+        .unwrap();
+        let paying = |index| paying(&descriptor, index);
+
+        // Each rotating check-in moves the funds one index further
+        assert_eq!(rotated_checkin_index(&descriptor, 0, &paying(1)), Some(1));
+        assert_eq!(rotated_checkin_index(&descriptor, 1, &paying(2)), Some(2));
+
+        // Same-address check-ins leave the index alone
+        assert_eq!(rotated_checkin_index(&descriptor, 0, &paying(0)), None);
+        assert_eq!(rotated_checkin_index(&descriptor, 3, &paying(3)), None);
+        assert_eq!(rotated_checkin_index("wsh(garbage)", 0, &paying(1)), None);
+    }
+
+    #[test]
+    fn test_log_checkin_advances_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        let owner = test_xpub(NetworkKind::Test, 1).to_string();
+        let (descriptor, _) = derive_inheritance_descriptor(
+            &owner,
+            &[heir(NetworkKind::Test, 2)],
+            144,
+            Network::Testnet,
+        )
+        .unwrap();
+        state.set_inheritance_config(InheritanceConfig {
+            descriptor: descriptor.clone(),
+            timelock_blocks: 144,
+            network: "testnet".into(),
+        });
+
+        // Every broadcast path logs through here, so each rotation counts
+        state.log_checkin(&paying(&descriptor, 1));
+        assert_eq!(state.checkin_address_index(), 1);
+        state.log_checkin(&paying(&descriptor, 1));
+        assert_eq!(state.checkin_address_index(), 1);
+        state.log_checkin(&paying(&descriptor, 2));
+        assert_eq!(state.checkin_address_index(), 2);
+
+        let conn = state.db.lock().unwrap();
+        assert!(db::checkin_last(&conn).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_http_broadcast_is_opt_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        assert_eq!(state.http_broadcast_url(), None);
+
+        state.persist_config(HTTP_BROADCAST_URL_KEY, " https://mempool.space/api/tx ");
+        assert_eq!(
+            state.http_broadcast_url().as_deref(),
+            Some("https://mempool.space/api/tx")
+        );
+        state.persist_config(HTTP_BROADCAST_URL_KEY, "");
+        assert_eq!(state.http_broadcast_url(), None);
+    }
+
+    #[test]
+    fn test_default_account_path_follows_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        let path = |p: &str| DerivationPath::from_str(p).unwrap();
+
+        // Mainnet is unchanged
+        assert_eq!(state.default_account_path(), path("m/84'/0'/0'"));
+
+        state.set_network(Network::Testnet);
+        assert_eq!(state.coin_type(), CoinType::Standard);
+        assert_eq!(state.default_account_path(), path("m/84'/1'/0'"));
+
+        // Wallets set up under the old path keep it
+        state.set_coin_type(CoinType::LegacyMainnet);
+        assert_eq!(state.default_account_path(), path("m/84'/0'/0'"));
+
+        // The choice survives a restart
+        drop(state);
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        assert_eq!(state.coin_type(), CoinType::LegacyMainnet);
+        assert_eq!(state.default_account_path(), path("m/84'/0'/0'"));
+    }
+
+    #[test]
+    fn test_checkin_index_resets_with_descriptor() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::from_db_path(dir.path().join("test.db"));
+        assert!(!state.checkin_rotation());
+        assert_eq!(state.checkin_address_index(), 0);
+
+        let owner = test_xpub(NetworkKind::Test, 1).to_string();
+        let config = |heirs: &[HeirKey]| InheritanceConfig {
+            descriptor: derive_inheritance_descriptor(&owner, heirs, 144, Network::Testnet)
+                .unwrap()
+                .0,
+            timelock_blocks: 144,
+            network: "testnet".into(),
+        };
+        let mut heirs = vec![heir(NetworkKind::Test, 2)];
+        state.set_inheritance_config(config(&heirs));
+        state.set_checkin_rotation(true);
+        state.set_checkin_address_index(3);
+        assert!(state.checkin_rotation());
+        assert_eq!(state.checkin_address_index(), 3);
+
+        // Saving the same descriptor keeps the index
+        state.set_inheritance_config(config(&heirs));
+        assert_eq!(state.checkin_address_index(), 3);
+
+        // A new descriptor starts over at index 0; the mode stays on
+        heirs.push(heir(NetworkKind::Test, 3));
+        state.set_inheritance_config(config(&heirs));
+        assert_eq!(state.checkin_address_index(), 0);
+        assert!(state.checkin_rotation());
+    }
 }
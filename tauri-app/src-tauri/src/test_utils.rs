@@ -0,0 +1,28 @@
+//! Shared test fixtures for the app's command and state tests.
+
+use bitcoin::bip32::{Xpriv, Xpub};
+use bitcoin::NetworkKind;
+
+use crate::db::HeirRow;
+
+/// Deterministic account xpub for `kind`; different seeds give different keys.
+pub(crate) fn test_xpub(kind: NetworkKind, seed: u8) -> Xpub {
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+    let master = Xpriv::new_master(kind, &[seed; 32]).unwrap();
+    Xpub::from_priv(&secp, &master)
+}
+
+/// Heir row with a mainnet key and no contacts, timelock or channel
+/// preferences. Tests fill in what they need with struct update syntax.
+pub(crate) fn test_heir_row(fingerprint: &str, label: &str) -> HeirRow {
+    HeirRow {
+        fingerprint: fingerprint.into(),
+        label: label.into(),
+        xpub: test_xpub(NetworkKind::Main, 1).to_string(),
+        derivation_path: "m/84'/0'/0'".into(),
+        npub: None,
+        email: None,
+        timelock_months: None,
+        channels: None,
+    }
+}
@@ -0,0 +1,141 @@
+//! Amount display units
+//!
+//! Everything below the UI works in satoshis. The user's preferred display
+//! unit (`display_unit` config row) only affects the formatted strings that
+//! commands return next to the raw values.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Config key holding the preferred unit
+pub const DISPLAY_UNIT_KEY: &str = "display_unit";
+
+const MSAT_PER_SAT: u64 = 1_000;
+const SAT_PER_BTC: u64 = 100_000_000;
+
+/// Unit amounts are shown in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayUnit {
+    #[default]
+    Sat,
+    Btc,
+    Msat,
+}
+
+impl DisplayUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisplayUnit::Sat => "sat",
+            DisplayUnit::Btc => "btc",
+            DisplayUnit::Msat => "msat",
+        }
+    }
+}
+
+impl fmt::Display for DisplayUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DisplayUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sat" | "sats" => Ok(DisplayUnit::Sat),
+            "btc" => Ok(DisplayUnit::Btc),
+            "msat" | "msats" => Ok(DisplayUnit::Msat),
+            other => Err(format!(
+                "Unknown display unit '{}' (expected sat, btc or msat)",
+                other
+            )),
+        }
+    }
+}
+
+/// Format a satoshi amount in `unit`.
+pub fn format_amount(sats: u64, unit: DisplayUnit) -> String {
+    format_amount_msat(sats.saturating_mul(MSAT_PER_SAT), unit)
+}
+
+/// Format a millisatoshi amount in `unit`.
+///
+/// BTC is rounded to the nearest satoshi (8 decimals) and sats keep up to
+/// three decimals for sub-satoshi values; trailing zeros are trimmed.
+pub fn format_amount_msat(msat: u64, unit: DisplayUnit) -> String {
+    match unit {
+        DisplayUnit::Msat => format!("{} msat", msat),
+        DisplayUnit::Sat => format!(
+            "{} sat",
+            decimal(msat / MSAT_PER_SAT, msat % MSAT_PER_SAT, 3)
+        ),
+        DisplayUnit::Btc => {
+            let sats = msat / MSAT_PER_SAT + u64::from(msat % MSAT_PER_SAT >= MSAT_PER_SAT / 2);
+            format!("{} BTC", decimal(sats / SAT_PER_BTC, sats % SAT_PER_BTC, 8))
+        }
+    }
+}
+
+/// `whole.frac` with `frac` zero-padded to `digits` and trailing zeros trimmed
+fn decimal(whole: u64, frac: u64, digits: usize) -> String {
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac = format!("{:0width$}", frac, width = digits);
+    format!("{}.{}", whole, frac.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sat() {
+        assert_eq!(format_amount(0, DisplayUnit::Sat), "0 sat");
+        assert_eq!(format_amount(1, DisplayUnit::Sat), "1 sat");
+        assert_eq!(
+            format_amount(150_000_000, DisplayUnit::Sat),
+            "150000000 sat"
+        );
+        assert_eq!(format_amount_msat(1_500, DisplayUnit::Sat), "1.5 sat");
+        assert_eq!(format_amount_msat(1, DisplayUnit::Sat), "0.001 sat");
+    }
+
+    #[test]
+    fn test_format_btc() {
+        assert_eq!(format_amount(0, DisplayUnit::Btc), "0 BTC");
+        assert_eq!(format_amount(1, DisplayUnit::Btc), "0.00000001 BTC");
+        assert_eq!(format_amount(100_000_000, DisplayUnit::Btc), "1 BTC");
+        assert_eq!(format_amount(150_000_000, DisplayUnit::Btc), "1.5 BTC");
+        assert_eq!(
+            format_amount(2_100_000_000_000_000, DisplayUnit::Btc),
+            "21000000 BTC"
+        );
+        // Sub-satoshi values round to the nearest sat
+        assert_eq!(format_amount_msat(499, DisplayUnit::Btc), "0 BTC");
+        assert_eq!(format_amount_msat(500, DisplayUnit::Btc), "0.00000001 BTC");
+        assert_eq!(
+            format_amount_msat(99_999_999_999, DisplayUnit::Btc),
+            "1 BTC"
+        );
+    }
+
+    #[test]
+    fn test_format_msat() {
+        assert_eq!(format_amount(0, DisplayUnit::Msat), "0 msat");
+        assert_eq!(format_amount(21, DisplayUnit::Msat), "21000 msat");
+        assert_eq!(format_amount_msat(1, DisplayUnit::Msat), "1 msat");
+    }
+
+    #[test]
+    fn test_parse_unit() {
+        assert_eq!("sat".parse::<DisplayUnit>().unwrap(), DisplayUnit::Sat);
+        assert_eq!(" BTC ".parse::<DisplayUnit>().unwrap(), DisplayUnit::Btc);
+        assert_eq!("msats".parse::<DisplayUnit>().unwrap(), DisplayUnit::Msat);
+        assert!("bits".parse::<DisplayUnit>().is_err());
+        assert_eq!(DisplayUnit::default(), DisplayUnit::Sat);
+    }
+}
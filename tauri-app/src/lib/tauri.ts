@@ -50,8 +50,9 @@ export async function hasSeed(): Promise<boolean> {
   return invoke('has_seed');
 }
 
-export async function createSeed(wordCount?: number): Promise<CcdResult<string>> {
-  return invoke('create_seed', { word_count: wordCount ?? 24 });
+/** `language` is a BIP-39 wordlist name, e.g. 'spanish'; English if omitted. */
+export async function createSeed(wordCount?: number, language?: string): Promise<CcdResult<string>> {
+  return invoke('create_seed', { word_count: wordCount ?? 24, language });
 }
 
 export async function importSeed(mnemonic: string, password: string): Promise<CcdResult<boolean>> {
@@ -62,6 +63,10 @@ export async function importWatchOnly(xpub: string, password: string): Promise<C
   return invoke('import_watch_only', { xpub, password });
 }
 
+export async function importHardwareExport(jsonOrDescriptor: string, password: string): Promise<CcdResult<string>> {
+  return invoke('import_hardware_export', { jsonOrDescriptor, password });
+}
+
 export async function isUnlocked(): Promise<boolean> {
   return invoke('is_unlocked');
 }
@@ -108,13 +113,67 @@ export async function setElectrumUrl(url: string): Promise<void> {
   return invoke('set_electrum_url', { url });
 }
 
-export async function refreshPolicyStatus(): Promise<CcdResult<any>> {
-  return invoke('refresh_policy_status');
+export async function getHttpBroadcastUrl(): Promise<string | null> {
+  return invoke('get_http_broadcast_url');
+}
+
+/** Empty string disables HTTP broadcast; null restores the network default. */
+export async function setHttpBroadcastUrl(url: string | null): Promise<void> {
+  return invoke('set_http_broadcast_url', { url });
 }
 
+export interface SpendingPathExplanation {
+  role: 'Owner' | 'Heir';
+  threshold: number;
+  key_fingerprints: string[];
+  timelock_blocks: number | null;
+  timelock: number | null;
+  timelock_days: number | null;
+  timelock_months: number | null;
+  description: string;
+}
 
-export async function validateXpub(xpub: string): Promise<CcdResult<boolean>> {
-  return invoke('validate_xpub', { xpub });
+export interface PolicyExplanation {
+  kind: 'Wsh' | 'Tr';
+  paths: SpendingPathExplanation[];
+  summary: string;
+}
+
+export async function explainDescriptor(descriptor: string): Promise<CcdResult<PolicyExplanation>> {
+  return invoke('explain_descriptor', { descriptor });
+}
+
+export async function refreshPolicyStatus(opId?: string): Promise<CcdResult<any>> {
+  return invoke('refresh_policy_status', { opId });
+}
+
+/** Cancel a running command started with `opId`. False if it already finished. */
+export async function cancelOperation(opId: string): Promise<CcdResult<boolean>> {
+  return invoke('cancel_operation', { opId });
+}
+
+export interface ExpiryEstimate {
+  current_block: number;
+  expiry_block: number;
+  blocks_remaining: number;
+  naive_expiry: number;
+  calibrated_expiry: number;
+  avg_block_secs: number;
+}
+
+export async function getExpiryEstimate(): Promise<CcdResult<ExpiryEstimate>> {
+  return invoke('get_expiry_estimate');
+}
+
+
+export async function validateXpub(
+  xpub: string,
+  allowNetworkMismatch?: boolean
+): Promise<CcdResult<boolean>> {
+  return invoke('validate_xpub', {
+    xpub,
+    allow_network_mismatch: allowNetworkMismatch ?? null,
+  });
 }
 
 // ─── CCD Commands ───────────────────────────────────────────────────────────
@@ -195,22 +254,42 @@ export interface HeirInfo {
   npub: string | null;
   email: string | null;
   timelock_months: number | null;
+  /** Relay-policy warnings for the policy with this heir (addHeir only) */
+  policy_warnings?: string[];
 }
 
 export async function addHeir(
   label: string,
   xpubOrDescriptor: string,
   timelockMonths?: number,
-  npub?: string
+  npub?: string,
+  allowNetworkMismatch?: boolean,
+  derivationPath?: string
 ): Promise<CcdResult<HeirInfo>> {
   return invoke('add_heir', {
     label,
     xpub_or_descriptor: xpubOrDescriptor,
     timelock_months: timelockMonths ?? null,
     npub: npub || null,
+    allow_network_mismatch: allowNetworkMismatch ?? null,
+    derivation_path: derivationPath || null,
   });
 }
 
+export interface HeirImportResult {
+  row: number;
+  label: string;
+  heir: HeirInfo | null;
+  error: string | null;
+}
+
+export async function importHeirs(
+  data: string,
+  format: 'descriptor' | 'csv'
+): Promise<CcdResult<HeirImportResult[]>> {
+  return invoke('import_heirs', { data, format });
+}
+
 export async function listHeirs(): Promise<HeirInfo[]> {
   return invoke('list_heirs');
 }
@@ -688,7 +688,10 @@ async fn test_email_notification_mailhog() {
         smtp_password: "test".to_string(),
         from_address: "nostring@nostring.dev".to_string(),
         to_address: "rensovereign@proton.me".to_string(),
-        plaintext: true,
+        cc: Vec::new(),
+        bcc: Vec::new(),
+        tls_mode: nostring_notify::TlsMode::None,
+        plaintext_only: false,
     };
 
     // Generate a warning-level notification
@@ -713,6 +716,7 @@ async fn test_email_notification_mailhog() {
         ],
         email: Some(email_config.clone()),
         nostr: None,
+        ..NotifyConfig::default()
     };
 
     let service = NotificationService::new(config);
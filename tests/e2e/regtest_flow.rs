@@ -0,0 +1,260 @@
+//! Full On-Chain Inheritance Flow on Regtest
+//!
+//! Runs the whole lifecycle against a local bitcoind + electrs (see
+//! `nostring_e2e::regtest`): fund the inheritance address, check in via
+//! `CheckinTxBuilder`, mine past the timelock, claim as the heir, and check
+//! the watch service classifies every spend.
+//!
+//! Keys are fixed seeds, so every run builds the same policy.
+//!
+//! Run with (needs `bitcoind` and `electrs`, see the harness docs):
+//!   cargo test -p nostring-e2e --features regtest --test regtest_flow -- --nocapture
+
+use bitcoin::{
+    bip32::{DerivationPath, Xpriv, Xpub},
+    hashes::Hash,
+    psbt::Psbt,
+    secp256k1::{All, Message, Secp256k1},
+    sighash::{EcdsaSighashType, SighashCache},
+    Address, Amount, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use miniscript::descriptor::DescriptorPublicKey;
+use miniscript::Descriptor;
+use nostring_e2e::regtest::RegtestEnv;
+use nostring_inherit::checkin::{CheckinTxBuilder, InheritanceUtxo};
+use nostring_inherit::finalize_psbt;
+use nostring_inherit::policy::{InheritancePolicy, Timelock};
+use nostring_watch::{SpendType, WatchConfig, WatchEvent, WatchService};
+use std::str::FromStr;
+
+/// Heir timelock; small so the test mines past it quickly
+const TIMELOCK_BLOCKS: u16 = 10;
+const FUNDING_SATS: u64 = 100_000;
+const FEE_RATE: u64 = 2;
+const HEIR_CLAIM_FEE_SATS: u64 = 1_000;
+
+/// BIP-84 account key (m/84'/1'/0') for a fixed seed
+struct AccountKey {
+    xpriv: Xpriv,
+    descriptor_key: DescriptorPublicKey,
+}
+
+impl AccountKey {
+    fn from_seed(secp: &Secp256k1<All>, seed: [u8; 32]) -> Self {
+        let master = Xpriv::new_master(Network::Regtest, &seed).unwrap();
+        let path = DerivationPath::from_str("m/84'/1'/0'").unwrap();
+        let xpriv = master.derive_priv(secp, &path).unwrap();
+        let descriptor_key = DescriptorPublicKey::from_str(&format!(
+            "[{}/84'/1'/0']{}/<0;1>/*",
+            master.fingerprint(secp),
+            Xpub::from_priv(secp, &xpriv)
+        ))
+        .unwrap();
+        Self {
+            xpriv,
+            descriptor_key,
+        }
+    }
+
+    /// Private key of the first receive address (`/0/0`)
+    fn receive_key(&self, secp: &Secp256k1<All>) -> bitcoin::secp256k1::SecretKey {
+        let path = DerivationPath::from_str("m/0/0").unwrap();
+        self.xpriv.derive_priv(secp, &path).unwrap().private_key
+    }
+}
+
+/// (script_pubkey, witness script) of the receive address at `index`
+fn derive_scripts(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    index: u32,
+) -> (ScriptBuf, ScriptBuf) {
+    let secp = Secp256k1::verification_only();
+    let derived = descriptor.clone().into_single_descriptors().unwrap()[0]
+        .derived_descriptor(&secp, index)
+        .unwrap();
+    (derived.script_pubkey(), derived.explicit_script().unwrap())
+}
+
+/// Sign input 0 of `psbt` with `key` and finalize it.
+///
+/// The witness comes from miniscript's satisfier (via `finalize_psbt`),
+/// which picks the branch the signature and the input's sequence allow.
+fn sign_and_finalize(
+    secp: &Secp256k1<All>,
+    mut psbt: Psbt,
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    witness_script: &ScriptBuf,
+    key: &bitcoin::secp256k1::SecretKey,
+) -> Transaction {
+    let value = psbt.inputs[0].witness_utxo.as_ref().unwrap().value;
+    let sighash = SighashCache::new(&psbt.unsigned_tx)
+        .p2wsh_signature_hash(0, witness_script, value, EcdsaSighashType::All)
+        .unwrap();
+    let msg = Message::from_digest(sighash.to_byte_array());
+    let signature = bitcoin::ecdsa::Signature::sighash_all(secp.sign_ecdsa(&msg, key));
+    psbt.inputs[0]
+        .partial_sigs
+        .insert(bitcoin::PublicKey::new(key.public_key(secp)), signature);
+    finalize_psbt(psbt, descriptor).expect("finalize")
+}
+
+/// Spend types of every `UtxoSpent` in `events`
+fn spend_types(events: &[WatchEvent]) -> Vec<SpendType> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            WatchEvent::UtxoSpent { spend_type, .. } => Some(*spend_type),
+            _ => None,
+        })
+        .collect()
+}
+
+fn appeared(events: &[WatchEvent]) -> Vec<OutPoint> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            WatchEvent::UtxoAppeared { outpoint, .. } => Some(*outpoint),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_regtest_checkin_and_heir_claim() {
+    let env = RegtestEnv::start();
+    let client = env.electrum_client();
+    let secp = Secp256k1::new();
+
+    // Policy: owner any time, heir after TIMELOCK_BLOCKS
+    let owner = AccountKey::from_seed(&secp, [1u8; 32]);
+    let heir = AccountKey::from_seed(&secp, [2u8; 32]);
+    let timelock = Timelock::from_blocks(TIMELOCK_BLOCKS).unwrap();
+    let descriptor = InheritancePolicy::simple(
+        owner.descriptor_key.clone(),
+        heir.descriptor_key.clone(),
+        timelock,
+    )
+    .unwrap()
+    .to_wsh_descriptor()
+    .unwrap();
+    let (inheritance_spk, witness_script) = derive_scripts(&descriptor, 0);
+    let inheritance_address = Address::from_script(&inheritance_spk, Network::Regtest).unwrap();
+
+    let state_dir = tempfile::tempdir().unwrap();
+    let mut watch = WatchService::new(
+        env.electrum_client(),
+        WatchConfig {
+            state_path: state_dir.path().join("watch.json"),
+            min_poll_interval_secs: 0,
+            ..WatchConfig::default()
+        },
+    )
+    .unwrap();
+    watch
+        .add_policy(
+            "inheritance",
+            descriptor.to_string(),
+            TIMELOCK_BLOCKS as u32,
+        )
+        .unwrap();
+
+    // ------------------------------------------------------------------
+    // Fund the inheritance address
+    // ------------------------------------------------------------------
+    let funding_txid = env.fund(&inheritance_address, Amount::from_sat(FUNDING_SATS));
+    env.mine(1);
+
+    let funding = client
+        .get_utxos_for_script(&inheritance_spk)
+        .unwrap()
+        .into_iter()
+        .find(|u| u.outpoint.txid == funding_txid)
+        .expect("funding output indexed");
+    assert!(funding.height > 0);
+
+    let events = watch.poll().unwrap();
+    assert_eq!(appeared(&events), vec![funding.outpoint]);
+
+    // ------------------------------------------------------------------
+    // Owner check-in: spend back to the same address, resetting the timer
+    // ------------------------------------------------------------------
+    let builder = CheckinTxBuilder::new(
+        InheritanceUtxo::new(
+            funding.outpoint,
+            funding.value,
+            funding.height,
+            inheritance_spk.clone(),
+        ),
+        descriptor.clone(),
+        FEE_RATE,
+        0,
+    )
+    .with_network(Network::Regtest);
+    let checkin_tx = sign_and_finalize(
+        &secp,
+        builder.build_psbt().unwrap(),
+        &descriptor,
+        &witness_script,
+        &owner.receive_key(&secp),
+    );
+
+    let checkin_txid = client.broadcast(&checkin_tx).expect("check-in broadcast");
+    assert!(env.in_mempool(&checkin_txid));
+    env.mine(1);
+
+    let events = watch.poll().unwrap();
+    assert_eq!(spend_types(&events), vec![SpendType::OwnerCheckin]);
+    let checkin_outpoint = OutPoint::new(checkin_txid, checkin_tx.output.len() as u32 - 1);
+    assert_eq!(appeared(&events), vec![checkin_outpoint]);
+
+    // ------------------------------------------------------------------
+    // Heir claim: rejected until the timelock matures, then accepted
+    // ------------------------------------------------------------------
+    let checkin_value = checkin_tx.output[checkin_outpoint.vout as usize].value;
+    let heir_key = heir.receive_key(&secp);
+    let heir_address = Address::p2wpkh(
+        &bitcoin::CompressedPublicKey(heir_key.public_key(&secp)),
+        Network::Regtest,
+    );
+    let claim_tx = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: checkin_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::from_height(TIMELOCK_BLOCKS),
+            witness: Witness::default(),
+        }],
+        output: vec![TxOut {
+            value: checkin_value - Amount::from_sat(HEIR_CLAIM_FEE_SATS),
+            script_pubkey: heir_address.script_pubkey(),
+        }],
+    };
+    let mut claim_psbt = Psbt::from_unsigned_tx(claim_tx).unwrap();
+    claim_psbt.inputs[0].witness_utxo = Some(TxOut {
+        value: checkin_value,
+        script_pubkey: inheritance_spk.clone(),
+    });
+    // Only the heir signed, so the satisfier takes the timelocked branch
+    let claim_tx = sign_and_finalize(&secp, claim_psbt, &descriptor, &witness_script, &heir_key);
+
+    let early = client.broadcast(&claim_tx);
+    assert!(
+        early.is_err(),
+        "heir claim must be rejected before the timelock matures"
+    );
+
+    // The check-in has 1 confirmation; the claim may enter the block that
+    // gives it TIMELOCK_BLOCKS
+    env.mine(TIMELOCK_BLOCKS as u64 - 1);
+    let claim_txid = client.broadcast(&claim_tx).expect("heir claim broadcast");
+    env.mine(1);
+
+    let events = watch.poll().unwrap();
+    assert_eq!(spend_types(&events), vec![SpendType::HeirClaim]);
+    assert!(client
+        .get_utxos_for_script(&heir_address.script_pubkey())
+        .unwrap()
+        .iter()
+        .any(|u| u.outpoint.txid == claim_txid));
+}
@@ -8,7 +8,7 @@
 //! 5. Codex32 fuzz testing with random inputs
 
 use nostring_core::crypto::{decrypt_seed, encrypt_seed, EncryptedSeed};
-use nostring_core::seed::{derive_seed, generate_mnemonic, parse_mnemonic, WordCount};
+use nostring_core::seed::{derive_seed, generate_mnemonic, parse_mnemonic, Language, WordCount};
 use nostring_shamir::codex32::{
     combine_shares, generate_shares as codex32_generate, parse_share, Codex32Config,
 };
@@ -382,7 +382,7 @@ fn test_codex32_generate_and_verify_roundtrip_random_seeds() {
         rng.fill(&mut seed[..]);
 
         let config = Codex32Config::new(2, "test", 3).unwrap();
-        let shares = codex32_generate(&seed, &config).unwrap();
+        let shares = codex32_generate(&seed, 16, &config).unwrap();
 
         // Verify any 2 shares reconstruct correctly
         let recovered = combine_shares(&shares[0..2]).unwrap();
@@ -480,7 +480,7 @@ fn test_all_valid_word_counts() {
         WordCount::Words21,
         WordCount::Words24,
     ] {
-        let mnemonic = generate_mnemonic(wc).unwrap();
+        let mnemonic = generate_mnemonic(wc, Language::English).unwrap();
         assert_eq!(mnemonic.word_count(), wc as usize);
     }
 }
@@ -1 +1,5 @@
 // Placeholder — tests are in ../e2e_integration.rs
+
+/// Local bitcoind + electrs harness for the `regtest_flow` test
+#[cfg(feature = "regtest")]
+pub mod regtest;
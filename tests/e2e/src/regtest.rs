@@ -0,0 +1,244 @@
+//! Local regtest network for end-to-end tests.
+//!
+//! Starts a throwaway `bitcoind` and `electrs` on free localhost ports, each
+//! with its own temporary data directory, and kills both on drop. Nothing
+//! touches a real network, so runs are deterministic.
+//!
+//! Requires both binaries: on `PATH` as `bitcoind` and `electrs`, or at the
+//! paths in `BITCOIND_EXE` / `ELECTRS_EXE`. Tested with Bitcoin Core 25+ and
+//! electrs 0.10 (romanz/electrs).
+
+use bitcoin::{Address, Amount, Network, Txid};
+use nostring_electrum::ElectrumClient;
+use serde_json::{json, Value};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How long to wait for a daemon to come up or catch up
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Blocks mined up front: coinbase maturity, and out of initial block
+/// download (electrs won't index before that)
+const INITIAL_BLOCKS: u64 = 101;
+
+/// A running bitcoind + electrs pair on regtest.
+pub struct RegtestEnv {
+    bitcoind: Child,
+    electrs: Child,
+    rpc: Rpc,
+    electrum_url: String,
+    /// Wallet address block rewards are mined to
+    mining_address: Address,
+    _datadir: tempfile::TempDir,
+}
+
+/// bitcoind JSON-RPC, authenticated with its cookie file.
+struct Rpc {
+    url: String,
+    cookie_path: PathBuf,
+    http: reqwest::blocking::Client,
+}
+
+impl Rpc {
+    fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let cookie = std::fs::read_to_string(&self.cookie_path).map_err(|e| e.to_string())?;
+        let (user, password) = cookie.trim().split_once(':').ok_or("malformed cookie")?;
+        let response: Value = self
+            .http
+            .post(&self.url)
+            .basic_auth(user, Some(password))
+            .json(&json!({"jsonrpc": "1.0", "id": "e2e", "method": method, "params": params}))
+            .send()
+            .and_then(|r| r.json())
+            .map_err(|e| e.to_string())?;
+        match response.get("error") {
+            Some(error) if !error.is_null() => Err(error.to_string()),
+            _ => Ok(response["result"].clone()),
+        }
+    }
+
+    fn expect(&self, method: &str, params: Value) -> Value {
+        self.call(method, params)
+            .unwrap_or_else(|e| panic!("bitcoind {} failed: {}", method, e))
+    }
+}
+
+impl RegtestEnv {
+    /// Start both daemons, fund the bitcoind wallet and wait until electrs
+    /// has indexed the chain.
+    pub fn start() -> Self {
+        let datadir = tempfile::tempdir().expect("temp dir");
+        let bitcoind_dir = datadir.path().join("bitcoind");
+        let electrs_dir = datadir.path().join("electrs");
+        std::fs::create_dir_all(&bitcoind_dir).unwrap();
+        std::fs::create_dir_all(&electrs_dir).unwrap();
+
+        let rpc_port = free_port();
+        let p2p_port = free_port();
+        let electrum_port = free_port();
+
+        let bitcoind = Command::new(exe("BITCOIND_EXE", "bitcoind"))
+            .arg("-regtest")
+            .arg(format!("-datadir={}", bitcoind_dir.display()))
+            .arg(format!("-rpcport={}", rpc_port))
+            .arg(format!("-port={}", p2p_port))
+            .args(["-bind=127.0.0.1", "-listen=1", "-server=1"])
+            .args(["-fallbackfee=0.0002", "-printtoconsole=0"])
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("failed to start bitcoind (set BITCOIND_EXE?)");
+
+        let rpc = Rpc {
+            url: format!("http://127.0.0.1:{}", rpc_port),
+            cookie_path: bitcoind_dir.join("regtest").join(".cookie"),
+            http: reqwest::blocking::Client::new(),
+        };
+        wait_until("bitcoind RPC", || {
+            rpc.call("getblockchaininfo", json!([])).is_ok()
+        });
+        rpc.expect("createwallet", json!(["harness"]));
+        let mining_address = parse_address(&rpc.expect("getnewaddress", json!([])));
+        rpc.expect(
+            "generatetoaddress",
+            json!([INITIAL_BLOCKS, mining_address.to_string()]),
+        );
+
+        let electrs = Command::new(exe("ELECTRS_EXE", "electrs"))
+            .args(["--network", "regtest"])
+            .arg("--daemon-dir")
+            .arg(&bitcoind_dir)
+            .args(["--daemon-rpc-addr", &format!("127.0.0.1:{}", rpc_port)])
+            .args(["--daemon-p2p-addr", &format!("127.0.0.1:{}", p2p_port)])
+            .args([
+                "--electrum-rpc-addr",
+                &format!("127.0.0.1:{}", electrum_port),
+            ])
+            .arg("--db-dir")
+            .arg(&electrs_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start electrs (set ELECTRS_EXE?)");
+
+        let env = Self {
+            bitcoind,
+            electrs,
+            rpc,
+            electrum_url: format!("tcp://127.0.0.1:{}", electrum_port),
+            mining_address,
+            _datadir: datadir,
+        };
+        wait_until("electrs to listen", || {
+            TcpStream::connect(("127.0.0.1", electrum_port)).is_ok()
+        });
+        env.wait_for_sync();
+        env
+    }
+
+    /// Electrum URL of the local electrs.
+    pub fn electrum_url(&self) -> &str {
+        &self.electrum_url
+    }
+
+    /// A fresh client connected to the local electrs.
+    pub fn electrum_client(&self) -> ElectrumClient {
+        ElectrumClient::new(&self.electrum_url, Network::Regtest).expect("connect to electrs")
+    }
+
+    /// Call a bitcoind RPC method, panicking on error.
+    pub fn rpc(&self, method: &str, params: Value) -> Value {
+        self.rpc.expect(method, params)
+    }
+
+    /// Current bitcoind block height.
+    pub fn height(&self) -> u32 {
+        self.rpc("getblockcount", json!([])).as_u64().unwrap() as u32
+    }
+
+    /// A new address in the bitcoind wallet.
+    pub fn new_address(&self) -> Address {
+        parse_address(&self.rpc("getnewaddress", json!([])))
+    }
+
+    /// Mine `blocks` blocks and wait for electrs to index them.
+    pub fn mine(&self, blocks: u64) {
+        self.rpc(
+            "generatetoaddress",
+            json!([blocks, self.mining_address.to_string()]),
+        );
+        self.wait_for_sync();
+    }
+
+    /// Send `amount` from the bitcoind wallet to `address` (unconfirmed).
+    pub fn fund(&self, address: &Address, amount: Amount) -> Txid {
+        let txid = self.rpc(
+            "sendtoaddress",
+            json!([address.to_string(), amount.to_btc()]),
+        );
+        Txid::from_str(txid.as_str().unwrap()).unwrap()
+    }
+
+    /// Whether bitcoind's mempool holds `txid`.
+    pub fn in_mempool(&self, txid: &Txid) -> bool {
+        self.rpc("getrawmempool", json!([]))
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t.as_str() == Some(&txid.to_string()))
+    }
+
+    /// Wait until electrs reports bitcoind's tip.
+    fn wait_for_sync(&self) {
+        let target = self.height();
+        wait_until("electrs to sync", || {
+            ElectrumClient::new(&self.electrum_url, Network::Regtest)
+                .and_then(|c| c.get_height())
+                .is_ok_and(|h| h >= target)
+        });
+    }
+}
+
+impl Drop for RegtestEnv {
+    fn drop(&mut self) {
+        let _ = self.electrs.kill();
+        let _ = self.electrs.wait();
+        // A clean stop; kill only if bitcoind won't take the request
+        if self.rpc.call("stop", json!([])).is_err() {
+            let _ = self.bitcoind.kill();
+        }
+        let _ = self.bitcoind.wait();
+    }
+}
+
+fn parse_address(value: &Value) -> Address {
+    Address::from_str(value.as_str().expect("address string"))
+        .unwrap()
+        .require_network(Network::Regtest)
+        .unwrap()
+}
+
+fn exe(var: &str, default: &str) -> String {
+    std::env::var(var).unwrap_or_else(|_| default.to_string())
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .map(|a| a.port())
+        .expect("free port")
+}
+
+fn wait_until(what: &str, mut ready: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !ready() {
+        assert!(
+            start.elapsed() < STARTUP_TIMEOUT,
+            "timed out waiting for {}",
+            what
+        );
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
@@ -303,7 +303,10 @@ fn mailhog_email_config() -> EmailConfig {
         smtp_password: "nostring".to_string(),
         from_address: "nostring-demo@nostring.dev".to_string(),
         to_address: "placeholder@nostring.dev".to_string(), // overridden per-heir
-        plaintext: true,
+        cc: Vec::new(),
+        bcc: Vec::new(),
+        tls_mode: nostring_notify::TlsMode::None,
+        plaintext_only: false,
     }
 }
 
@@ -345,6 +348,7 @@ Keep it safe — you'll need it to reconstruct the owner's Nostr identity.
 Stay sovereign,
 NoString Cascade Demo"#,
         ),
+        html_body: None,
         level: nostring_notify::NotificationLevel::Reminder,
     };
 
@@ -380,6 +384,7 @@ Your Shamir inheritance share will be delivered via Nostr DM shortly.
 Stay sovereign,
 NoString Cascade Demo"#,
         ),
+        html_body: None,
         level: nostring_notify::NotificationLevel::Critical,
     };
 
@@ -417,6 +422,7 @@ reconstruct the owner's Nostr identity (nsec).
 Stay sovereign,
 NoString Cascade Demo"#,
         ),
+        html_body: None,
         level: nostring_notify::NotificationLevel::Critical,
     };
 
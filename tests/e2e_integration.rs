@@ -272,7 +272,7 @@ fn test_revocation_full_crypto_flow() {
     let total = 2 * n_heirs + 1; // 5
 
     let config_v1 = Codex32Config::new(threshold, "rev0", total).expect("config");
-    let shares_v1 = generate_shares(&original_nsec_bytes, &config_v1).expect("shares");
+    let shares_v1 = generate_shares(&original_nsec_bytes, 32, &config_v1).expect("shares");
     assert_eq!(shares_v1.len(), 5);
 
     // Pre-distributed to heirs: shares[0], shares[1]
@@ -319,7 +319,7 @@ fn test_revocation_full_crypto_flow() {
     );
 
     let config_v2 = Codex32Config::new(threshold, "rev2", total).expect("config");
-    let shares_v2 = generate_shares(&new_nsec_bytes, &config_v2).expect("shares");
+    let shares_v2 = generate_shares(&new_nsec_bytes, 32, &config_v2).expect("shares");
 
     let pre_dist_v2: Vec<String> = shares_v2[..2].iter().map(|s| s.encoded.clone()).collect();
     let locked_v2: Vec<String> = shares_v2[2..].iter().map(|s| s.encoded.clone()).collect();
@@ -401,14 +401,14 @@ fn test_revocation_same_key_resplit() {
 
     // First split
     let config_v1 = Codex32Config::new(2, "sp0a", 3).expect("config");
-    let shares_v1 = generate_shares(&nsec_bytes, &config_v1).expect("shares");
+    let shares_v1 = generate_shares(&nsec_bytes, 32, &config_v1).expect("shares");
     let pre_dist_v1 = shares_v1[0].encoded.clone();
     let _locked_v1: Vec<String> = shares_v1[1..].iter().map(|s| s.encoded.clone()).collect();
     // locked_v1 is intentionally unused — simulates revocation (cleared from DB)
 
     // Revoke and re-split SAME key with different polynomial
     let config_v2 = Codex32Config::new(2, "sp0c", 3).expect("config");
-    let shares_v2 = generate_shares(&nsec_bytes, &config_v2).expect("shares");
+    let shares_v2 = generate_shares(&nsec_bytes, 32, &config_v2).expect("shares");
     let pre_dist_v2 = shares_v2[0].encoded.clone();
     let locked_v2: Vec<String> = shares_v2[1..].iter().map(|s| s.encoded.clone()).collect();
 
@@ -468,7 +468,7 @@ fn test_full_heir_delivery_flow() {
 
     // === Step 2: Create Shamir split (1 heir → 2-of-3) ===
     let config = Codex32Config::new(2, "dlvr", 3).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
     let pre_distributed_share = shares[0].encoded.clone(); // given to heir
     let locked_shares: Vec<String> = shares[1..].iter().map(|s| s.encoded.clone()).collect();
@@ -605,7 +605,7 @@ fn test_delivery_flow_multiple_heirs() {
     let total = 2 * n_heirs + 1; // 7
 
     let config = Codex32Config::new(threshold, "mhrs", total).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
     let heir_shares: Vec<String> = shares[..n_heirs as usize]
         .iter()
@@ -695,7 +695,7 @@ fn test_descriptor_backup_roundtrip() {
     let total = 2 * n_heirs + 1; // 5
 
     let config = Codex32Config::new(threshold, "rcvy", total).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
     assert_eq!(shares.len(), 5);
 
     // Pre-distributed: shares[0], shares[1] (one per heir)
@@ -813,7 +813,7 @@ fn test_locked_shares_json_roundtrip() {
 
     // 1 heir: 2-of-3
     let config = Codex32Config::new(2, "jsnr", 3).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
     let pre_dist = shares[0].encoded.clone();
     let locked: Vec<String> = shares[1..].iter().map(|s| s.encoded.clone()).collect();
@@ -863,7 +863,7 @@ fn test_heir_recovery_command_flow() {
 
     // 3 heirs: 4-of-7
     let config = Codex32Config::new(4, "rcvr", 7).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
     // Simulate: heir 2 has their share + receives locked shares from descriptor backup
     let heir_share = shares[2].encoded.clone(); // heir #3's pre-distributed share
@@ -911,7 +911,7 @@ fn test_heir_recovery_insufficient_shares() {
 
     // 2 heirs: 3-of-5
     let config = Codex32Config::new(3, "fa9l", 5).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
     // Only 2 shares (below threshold of 3)
     let two_shares: Vec<_> = shares[0..2]
@@ -938,7 +938,7 @@ fn test_heir_recovery_single_share_rejected() {
     let nsec_bytes = keys.secret_key().as_secret_bytes().to_vec();
 
     let config = Codex32Config::new(2, "sng0", 3).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
     let single = vec![parse_share(&shares[0].encoded).expect("parse")];
     let result = combine_shares(&single);
@@ -971,7 +971,7 @@ fn test_heir_recovery_all_heir_counts() {
             safe_chars[(n_heirs as usize + 2) % 6]
         );
         let config = Codex32Config::new(threshold, &id, total).expect("config");
-        let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+        let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
 
         // Verify: all heirs colluding can't recover
         if n_heirs > 1 {
@@ -1035,9 +1035,12 @@ fn test_heir_recovery_all_heir_counts() {
 
 #[test]
 fn test_seed_generation_and_derivation() {
-    use nostring_core::seed::{derive_seed, generate_mnemonic, parse_mnemonic, WordCount};
+    use nostring_core::seed::{
+        derive_seed, generate_mnemonic, parse_mnemonic, Language, WordCount,
+    };
 
-    let mnemonic = generate_mnemonic(WordCount::Words24).expect("mnemonic generation");
+    let mnemonic =
+        generate_mnemonic(WordCount::Words24, Language::English).expect("mnemonic generation");
     let mnemonic_str = mnemonic.to_string();
     let words: Vec<&str> = mnemonic_str.split_whitespace().collect();
     assert_eq!(words.len(), 24, "Expected 24-word mnemonic");
@@ -1052,9 +1055,11 @@ fn test_seed_generation_and_derivation() {
 #[test]
 fn test_seed_encryption_roundtrip() {
     use nostring_core::crypto::{decrypt_seed, encrypt_seed};
-    use nostring_core::seed::{derive_seed, generate_mnemonic, parse_mnemonic, WordCount};
+    use nostring_core::seed::{
+        derive_seed, generate_mnemonic, parse_mnemonic, Language, WordCount,
+    };
 
-    let mnemonic = generate_mnemonic(WordCount::Words12).expect("mnemonic");
+    let mnemonic = generate_mnemonic(WordCount::Words12, Language::English).expect("mnemonic");
     let parsed = parse_mnemonic(&mnemonic.to_string()).expect("parse");
     let seed = derive_seed(&parsed, "");
 
@@ -1135,6 +1140,76 @@ fn test_inheritance_policy_creation() {
     println!("✓ Miniscript policy creation works");
 }
 
+#[test]
+fn test_taproot_policy_address_and_checkin_psbt() {
+    use bitcoin::psbt::Psbt;
+    use bitcoin::{Address, Amount, OutPoint, Txid};
+    use miniscript::Descriptor;
+    use nostring_inherit::checkin::{CheckinTxBuilder, InheritanceUtxo};
+    use nostring_inherit::policy::{DescriptorKind, InheritancePolicy, Timelock};
+
+    let owner_desc = "[73c5da0a/86'/0'/0']xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/<0;1>/*";
+    let heir_desc = "[b2e5c4d1/86'/0'/0']xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB/<0;1>/*";
+    let owner_key: DescriptorPublicKey = owner_desc.parse().expect("parse owner key");
+    let heir_key: DescriptorPublicKey = heir_desc.parse().expect("parse heir key");
+
+    let policy = InheritancePolicy::simple(owner_key, heir_key, Timelock::six_months())
+        .expect("policy creation");
+    let descriptor = policy.to_tr_descriptor().expect("compile tr descriptor");
+    assert_eq!(DescriptorKind::of(&descriptor).unwrap(), DescriptorKind::Tr);
+
+    // Derive the first receive address
+    let receive: Descriptor<DescriptorPublicKey> = descriptor
+        .clone()
+        .into_single_descriptors()
+        .expect("split multipath")
+        .remove(0);
+    let derived = receive.at_derivation_index(0).expect("derive index 0");
+    let address: Address = derived.address(Network::Bitcoin).expect("address");
+    assert!(
+        address.to_string().starts_with("bc1p"),
+        "Taproot address expected: {}",
+        address
+    );
+
+    // Build a check-in PSBT against a UTXO at that address and round-trip it
+    let utxo = InheritanceUtxo::new(
+        OutPoint {
+            txid: Txid::from_str(
+                "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        Amount::from_sat(250_000),
+        850_000,
+        address.script_pubkey(),
+    );
+    let builder = CheckinTxBuilder::new(utxo, descriptor, 5, 0);
+    let psbt_b64 = builder.build_psbt_base64().expect("build PSBT");
+    let psbt = Psbt::from_str(&psbt_b64).expect("parse PSBT");
+
+    assert_eq!(psbt.unsigned_tx.input.len(), 1);
+    assert_eq!(
+        psbt.unsigned_tx.output[0].script_pubkey,
+        address.script_pubkey(),
+        "Check-in must recreate the same Taproot output"
+    );
+    let input = &psbt.inputs[0];
+    assert_eq!(
+        input.witness_utxo.as_ref().map(|o| o.value),
+        Some(Amount::from_sat(250_000))
+    );
+    assert!(input.tap_internal_key.is_some());
+    assert!(input.tap_merkle_root.is_some());
+    assert!(input.witness_script.is_none());
+
+    println!(
+        "✓ Taproot inheritance address {} and check-in PSBT",
+        address
+    );
+}
+
 // ============================================================================
 // 4. Shamir Secret Sharing — with REAL Nostr keys
 // ============================================================================
@@ -1153,7 +1228,7 @@ fn test_codex32_roundtrip_real_key() {
 
     // Split the real secret key
     let config = Codex32Config::new(2, "test", 3).expect("config");
-    let shares = generate_shares(&secret_bytes, &config).expect("generate");
+    let shares = generate_shares(&secret_bytes, 32, &config).expect("generate");
     assert_eq!(shares.len(), 3, "Should generate 3 shares");
 
     // Combine any 2 shares
@@ -1201,7 +1276,7 @@ fn test_shamir_nsec_inheritance_formula_real_key() {
 
     // Use lowercase bech32 chars for Codex32 identifier
     let config = Codex32Config::new(threshold, "nsec", total_shares).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("generate");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("generate");
 
     assert_eq!(shares.len(), total_shares as usize);
 
@@ -1384,7 +1459,9 @@ fn test_testnet_electrum_connectivity() {
 fn test_full_inheritance_flow_offline() {
     use nostr_sdk::prelude::*;
     use nostring_core::crypto::{decrypt_seed, encrypt_seed};
-    use nostring_core::seed::{derive_seed, generate_mnemonic, parse_mnemonic, WordCount};
+    use nostring_core::seed::{
+        derive_seed, generate_mnemonic, parse_mnemonic, Language, WordCount,
+    };
     use nostring_inherit::heir::{HeirKey, HeirRegistry};
     use nostring_inherit::policy::{InheritancePolicy, Timelock};
     use nostring_shamir::codex32::{combine_shares, generate_shares, Codex32Config};
@@ -1392,7 +1469,7 @@ fn test_full_inheritance_flow_offline() {
     println!("\n=== Full Inheritance Flow (Offline Simulation) ===\n");
 
     // Step 1: Owner generates seed
-    let mnemonic = generate_mnemonic(WordCount::Words24).expect("mnemonic");
+    let mnemonic = generate_mnemonic(WordCount::Words24, Language::English).expect("mnemonic");
     let parsed = parse_mnemonic(&mnemonic.to_string()).expect("parse");
     let seed = derive_seed(&parsed, "");
     println!("1. ✓ Owner generated 24-word mnemonic");
@@ -1446,7 +1523,7 @@ fn test_full_inheritance_flow_offline() {
     let total = 2 * n_heirs + 1; // 3
 
     let config = Codex32Config::new(threshold, "her0", total).expect("config");
-    let shares = generate_shares(&nsec_bytes, &config).expect("shares");
+    let shares = generate_shares(&nsec_bytes, 32, &config).expect("shares");
     assert_eq!(shares.len(), 3);
     println!(
         "6. ✓ nsec Shamir-split: {}-of-{} (pre-dist: {}, locked: {})",